@@ -0,0 +1,41 @@
+use std::io::Result;
+use std::path::PathBuf;
+
+fn main() -> Result<()> {
+    let proto_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("proto");
+
+    let proto_files = &[proto_root.join("asyncapiv2.proto")];
+
+    let include_dirs = &[&proto_root];
+
+    // The OpenAPI v3 types that the model shares are used from gnostic-openapiv3.
+    // Serde support is opt-in through the `serde` feature.
+    let mut config = prost_build::Config::new();
+    config
+        .extern_path(".openapi.v3", "::gnostic_openapiv3::openapi_v3")
+        .type_attribute(
+            ".",
+            "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]",
+        );
+
+    // The shared types listed implement Eq and Hash by hand in gnostic-openapiv3.
+    let implemented = &[
+        ".openapi.v3.Any",
+        ".openapi.v3.DefaultType",
+        ".openapi.v3.Schema",
+        ".openapi.v3.SpecificationExtension",
+    ];
+    gnostic_build::derive_eq_and_hash(&mut config, proto_files, include_dirs, implemented)?;
+    config.compile_protos(proto_files, include_dirs)?;
+
+    for proto in proto_files {
+        println!("cargo:rerun-if-changed={}", proto.display());
+    }
+
+    Ok(())
+}
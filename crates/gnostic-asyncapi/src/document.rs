@@ -0,0 +1,110 @@
+//! AsyncAPI document parsing.
+
+use gnostic_compiler::{cached_document, CompilerError, Context, ErrorGroup, ParseOptions, Parsed, read_info_from_bytes_with_limits, read_bytes_for_file, resolve_for_parse};
+use prost::Message;
+use std::sync::Arc;
+use serde_yaml::Value as Yaml;
+
+use crate::asyncapi_v2::Document;
+use crate::parser::Parser;
+
+/// Parses an AsyncAPI document from YAML/JSON bytes.
+pub fn parse_document(bytes: &[u8]) -> Result<Document, ErrorGroup> {
+    parse_document_with_options(bytes, &ParseOptions::default()).map(Parsed::into_value)
+}
+
+/// Parses an AsyncAPI document with the given options.
+///
+/// Warnings reported in lenient mode are returned alongside the document; if the
+/// parse fails they are included in the returned ErrorGroup.
+pub fn parse_document_with_options(
+    bytes: &[u8],
+    options: &ParseOptions,
+) -> Result<Parsed<Document>, ErrorGroup> {
+    parse_document_at("", bytes, options)
+}
+
+/// Parses a document read from `base`, which locates relative external
+/// references when the options resolve references.
+fn parse_document_at(base: &str, bytes: &[u8], options: &ParseOptions) -> Result<Parsed<Document>, ErrorGroup> {
+    let yaml = read_info_from_bytes_with_limits("", bytes, &options.limits())
+        .map_err(|e| ErrorGroup::new(vec![e]))?;
+
+    let node = if let Yaml::Sequence(ref content) = yaml {
+        if content.len() == 1 {
+            &content[0]
+        } else {
+            &yaml
+        }
+    } else {
+        &yaml
+    };
+
+    let context = Arc::new(Context::root_with_options("$", options.clone()));
+    let resolved = resolve_for_parse(node, base, &context);
+    let node: &Yaml = &resolved;
+    context.finish(Parser::parse_document(node, &context))
+}
+
+/// Parses an AsyncAPI document from a file path or URL.
+///
+/// When the document cache is enabled, a file whose content has already been
+/// parsed returns the cached document.
+pub fn parse_document_from_file(path: &str) -> Result<Document, ErrorGroup> {
+    let bytes = read_bytes_for_file(path)
+        .map_err(|e| ErrorGroup::new(vec![e]))?;
+    cached_document(&bytes, || parse_document_at(path, &bytes, &ParseOptions::default()).map(Parsed::into_value))
+}
+
+/// Parses a document from a file path or URL with the given options.
+/// Relative external references are resolved against the path.
+pub fn parse_document_from_file_with_options(
+    path: &str,
+    options: &ParseOptions,
+) -> Result<Parsed<Document>, ErrorGroup> {
+    let bytes = read_bytes_for_file(path)
+        .map_err(|e| ErrorGroup::new(vec![e]))?;
+    parse_document_at(path, &bytes, options)
+}
+
+impl Document {
+    /// Encodes the document as a binary protocol buffer.
+    pub fn to_proto_bytes(&self) -> Vec<u8> {
+        self.encode_to_vec()
+    }
+
+    /// Decodes a document from a binary protocol buffer.
+    pub fn from_proto_bytes(bytes: &[u8]) -> Result<Document, ErrorGroup> {
+        Document::decode(bytes).map_err(|e| ErrorGroup::from(CompilerError::from(e)))
+    }
+
+    /// Encodes the document as CBOR.
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor_bytes(&self) -> Result<Vec<u8>, ErrorGroup> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes)
+            .map(|_| bytes)
+            .map_err(|e| ErrorGroup::from(CompilerError::Encoding(format!("CBOR: {}", e))))
+    }
+
+    /// Decodes a document from CBOR.
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor_bytes(bytes: &[u8]) -> Result<Document, ErrorGroup> {
+        ciborium::from_reader(bytes)
+            .map_err(|e| ErrorGroup::from(CompilerError::Encoding(format!("CBOR: {}", e))))
+    }
+
+    /// Encodes the document as MessagePack, with fields keyed by name.
+    #[cfg(feature = "msgpack")]
+    pub fn to_msgpack_bytes(&self) -> Result<Vec<u8>, ErrorGroup> {
+        rmp_serde::to_vec_named(self)
+            .map_err(|e| ErrorGroup::from(CompilerError::Encoding(format!("MessagePack: {}", e))))
+    }
+
+    /// Decodes a document from MessagePack.
+    #[cfg(feature = "msgpack")]
+    pub fn from_msgpack_bytes(bytes: &[u8]) -> Result<Document, ErrorGroup> {
+        rmp_serde::from_slice(bytes)
+            .map_err(|e| ErrorGroup::from(CompilerError::Encoding(format!("MessagePack: {}", e))))
+    }
+}
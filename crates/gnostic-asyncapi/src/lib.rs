@@ -0,0 +1,17 @@
+//! AsyncAPI support for gnostic-models.
+//!
+//! This crate provides Protocol Buffer models and parsing for AsyncAPI 2.x
+//! specifications. Info, tags, schemas and references use the OpenAPI v3
+//! models and parser from gnostic-openapiv3.
+
+pub mod parser;
+pub mod document;
+
+/// Generated Protocol Buffer code for AsyncAPI 2.x.
+#[allow(clippy::large_enum_variant)]
+pub mod asyncapi_v2 {
+    include!(concat!(env!("OUT_DIR"), "/asyncapi.v2.rs"));
+}
+
+pub use document::*;
+pub use asyncapi_v2::Document;
@@ -0,0 +1,396 @@
+//! AsyncAPI 2.x YAML to Protocol Buffer parser.
+
+use gnostic_compiler::{Context, CompilerError, ErrorCode, ErrorGroup};
+use gnostic_compiler::{map_value_for_key, string_for_scalar_node, string_array_for_sequence_node, is_mapping, iter_map};
+use gnostic_openapiv3::openapi_v3::{
+    NamedAny, NamedServerVariable, NamedStringArray, SecurityRequirement, ServerVariable, ServerVariables,
+    StringArray,
+};
+use gnostic_openapiv3::parser::Parser as OpenApiParser;
+use serde_yaml::Value as Yaml;
+use std::sync::Arc;
+
+use crate::asyncapi_v2::*;
+
+/// Parser for converting YAML nodes to AsyncAPI Protocol Buffer types.
+///
+/// Info, tags, external docs, schemas and references are parsed by the
+/// OpenAPI v3 parser.
+pub struct Parser;
+
+impl Parser {
+    /// Parses a Document from a YAML node.
+    pub fn parse_document(node: &Yaml, context: &Arc<Context>) -> Result<Document, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut doc = Document::default();
+
+        if !is_mapping(node) {
+            errors.push(CompilerError::new_with_code(context, ErrorCode::InvalidType, format!("expected mapping, got {:?}", node)));
+            return Err(ErrorGroup::new(errors));
+        }
+
+        doc.asyncapi = string_field(node, "asyncapi");
+        doc.id = string_field(node, "id");
+        doc.default_content_type = string_field(node, "defaultContentType");
+        doc.info = parse_field(node, "info", context, &mut errors, OpenApiParser::parse_info);
+        doc.servers = parse_field(node, "servers", context, &mut errors, Self::parse_servers);
+        doc.channels = parse_field(node, "channels", context, &mut errors, Self::parse_channels);
+        doc.components = parse_field(node, "components", context, &mut errors, Self::parse_components);
+        doc.tags = parse_sequence(node, "tags", context, &mut errors, OpenApiParser::parse_tag);
+        doc.external_docs = parse_field(node, "externalDocs", context, &mut errors, OpenApiParser::parse_external_docs);
+        doc.specification_extension = Self::parse_specification_extensions(node, context);
+
+        if errors.is_empty() {
+            Ok(doc)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses Servers from a YAML node.
+    pub fn parse_servers(node: &Yaml, context: &Arc<Context>) -> Result<Servers, ErrorGroup> {
+        let mut errors = Vec::new();
+        let servers = parse_map(node, context, &mut errors, Self::parse_server)
+            .into_iter()
+            .map(|(name, value)| NamedServer { name, value: Some(value) })
+            .collect();
+        finish(Servers { additional_properties: servers }, errors)
+    }
+
+    /// Parses Server from a YAML node.
+    pub fn parse_server(node: &Yaml, context: &Arc<Context>) -> Result<Server, ErrorGroup> {
+        let mut errors = Vec::new();
+        let server = Server {
+            url: string_field(node, "url"),
+            protocol: string_field(node, "protocol"),
+            protocol_version: string_field(node, "protocolVersion"),
+            description: string_field(node, "description"),
+            variables: parse_field(node, "variables", context, &mut errors, Self::parse_server_variables),
+            security: parse_sequence(node, "security", context, &mut errors, Self::parse_security_requirement),
+            bindings: parse_field(node, "bindings", context, &mut errors, OpenApiParser::parse_any),
+            specification_extension: Self::parse_specification_extensions(node, context),
+        };
+        finish(server, errors)
+    }
+
+    /// Parses ServerVariables from a YAML node.
+    pub fn parse_server_variables(node: &Yaml, context: &Arc<Context>) -> Result<ServerVariables, ErrorGroup> {
+        let mut variables = ServerVariables::default();
+        iter_map(node, |name, value| {
+            let variable = ServerVariable {
+                r#enum: map_value_for_key(value, "enum").map(string_array_for_sequence_node).unwrap_or_default(),
+                default: string_field(value, "default"),
+                description: string_field(value, "description"),
+                specification_extension: Self::parse_specification_extensions(value, context),
+            };
+            variables.additional_properties.push(NamedServerVariable {
+                name: name.to_string(),
+                value: Some(variable),
+            });
+        });
+        Ok(variables)
+    }
+
+    /// Parses a SecurityRequirement from a YAML node.
+    pub fn parse_security_requirement(node: &Yaml, _context: &Arc<Context>) -> Result<SecurityRequirement, ErrorGroup> {
+        let mut requirement = SecurityRequirement::default();
+        iter_map(node, |name, scopes| {
+            requirement.additional_properties.push(NamedStringArray {
+                name: name.to_string(),
+                value: Some(StringArray {
+                    value: string_array_for_sequence_node(scopes),
+                }),
+            });
+        });
+        Ok(requirement)
+    }
+
+    /// Parses Channels from a YAML node.
+    pub fn parse_channels(node: &Yaml, context: &Arc<Context>) -> Result<Channels, ErrorGroup> {
+        let mut errors = Vec::new();
+        let channels = parse_map(node, context, &mut errors, Self::parse_channel_item)
+            .into_iter()
+            .map(|(name, value)| NamedChannelItem { name, value: Some(value) })
+            .collect();
+        finish(Channels { additional_properties: channels }, errors)
+    }
+
+    /// Parses ChannelItem from a YAML node.
+    pub fn parse_channel_item(node: &Yaml, context: &Arc<Context>) -> Result<ChannelItem, ErrorGroup> {
+        let mut errors = Vec::new();
+        let channel = ChannelItem {
+            r#ref: string_field(node, "$ref"),
+            description: string_field(node, "description"),
+            servers: map_value_for_key(node, "servers").map(string_array_for_sequence_node).unwrap_or_default(),
+            subscribe: parse_field(node, "subscribe", context, &mut errors, Self::parse_operation),
+            publish: parse_field(node, "publish", context, &mut errors, Self::parse_operation),
+            parameters: parse_field(node, "parameters", context, &mut errors, Self::parse_parameters),
+            bindings: parse_field(node, "bindings", context, &mut errors, OpenApiParser::parse_any),
+            specification_extension: Self::parse_specification_extensions(node, context),
+        };
+        finish(channel, errors)
+    }
+
+    /// Parses Operation from a YAML node.
+    pub fn parse_operation(node: &Yaml, context: &Arc<Context>) -> Result<Operation, ErrorGroup> {
+        let mut errors = Vec::new();
+        let operation = Operation {
+            operation_id: string_field(node, "operationId"),
+            summary: string_field(node, "summary"),
+            description: string_field(node, "description"),
+            security: parse_sequence(node, "security", context, &mut errors, Self::parse_security_requirement),
+            tags: parse_sequence(node, "tags", context, &mut errors, OpenApiParser::parse_tag),
+            external_docs: parse_field(node, "externalDocs", context, &mut errors, OpenApiParser::parse_external_docs),
+            bindings: parse_field(node, "bindings", context, &mut errors, OpenApiParser::parse_any),
+            traits: parse_sequence(node, "traits", context, &mut errors, OpenApiParser::parse_any),
+            message: parse_field(node, "message", context, &mut errors, Self::parse_operation_message),
+            specification_extension: Self::parse_specification_extensions(node, context),
+        };
+        finish(operation, errors)
+    }
+
+    /// Parses the message of an operation, which may list alternatives in oneOf.
+    pub fn parse_operation_message(node: &Yaml, context: &Arc<Context>) -> Result<OperationMessage, ErrorGroup> {
+        let mut errors = Vec::new();
+        let oneof = if map_value_for_key(node, "oneOf").is_some() {
+            let messages = parse_sequence(node, "oneOf", context, &mut errors, Self::parse_message_or_reference);
+            operation_message::Oneof::OneOf(MessagesOrReferences {
+                message_or_reference: messages,
+            })
+        } else {
+            operation_message::Oneof::Message(Self::parse_message_or_reference(node, context)?)
+        };
+        finish(OperationMessage { oneof: Some(oneof) }, errors)
+    }
+
+    /// Parses MessageOrReference from a YAML node.
+    pub fn parse_message_or_reference(node: &Yaml, context: &Arc<Context>) -> Result<MessageOrReference, ErrorGroup> {
+        if let Some(reference) = OpenApiParser::parse_reference(node, context) {
+            return Ok(MessageOrReference {
+                oneof: Some(message_or_reference::Oneof::Reference(reference)),
+            });
+        }
+
+        Self::parse_message(node, context).map(|m| MessageOrReference {
+            oneof: Some(message_or_reference::Oneof::Message(m)),
+        })
+    }
+
+    /// Parses Message from a YAML node.
+    pub fn parse_message(node: &Yaml, context: &Arc<Context>) -> Result<Message, ErrorGroup> {
+        let mut errors = Vec::new();
+        let message = Message {
+            message_id: string_field(node, "messageId"),
+            headers: parse_field(node, "headers", context, &mut errors, OpenApiParser::parse_schema_or_reference),
+            payload: parse_field(node, "payload", context, &mut errors, OpenApiParser::parse_schema_or_reference),
+            correlation_id: parse_field(
+                node,
+                "correlationId",
+                context,
+                &mut errors,
+                Self::parse_correlation_id_or_reference,
+            ),
+            schema_format: string_field(node, "schemaFormat"),
+            content_type: string_field(node, "contentType"),
+            name: string_field(node, "name"),
+            title: string_field(node, "title"),
+            summary: string_field(node, "summary"),
+            description: string_field(node, "description"),
+            tags: parse_sequence(node, "tags", context, &mut errors, OpenApiParser::parse_tag),
+            external_docs: parse_field(node, "externalDocs", context, &mut errors, OpenApiParser::parse_external_docs),
+            bindings: parse_field(node, "bindings", context, &mut errors, OpenApiParser::parse_any),
+            examples: parse_sequence(node, "examples", context, &mut errors, OpenApiParser::parse_any),
+            traits: parse_sequence(node, "traits", context, &mut errors, OpenApiParser::parse_any),
+            specification_extension: Self::parse_specification_extensions(node, context),
+        };
+        finish(message, errors)
+    }
+
+    /// Parses CorrelationIdOrReference from a YAML node.
+    pub fn parse_correlation_id_or_reference(
+        node: &Yaml,
+        context: &Arc<Context>,
+    ) -> Result<CorrelationIdOrReference, ErrorGroup> {
+        let oneof = match OpenApiParser::parse_reference(node, context) {
+            Some(reference) => correlation_id_or_reference::Oneof::Reference(reference),
+            None => correlation_id_or_reference::Oneof::CorrelationId(CorrelationId {
+                description: string_field(node, "description"),
+                location: string_field(node, "location"),
+                specification_extension: Self::parse_specification_extensions(node, context),
+            }),
+        };
+        Ok(CorrelationIdOrReference { oneof: Some(oneof) })
+    }
+
+    /// Parses Parameters from a YAML node.
+    pub fn parse_parameters(node: &Yaml, context: &Arc<Context>) -> Result<Parameters, ErrorGroup> {
+        let mut errors = Vec::new();
+        let parameters = parse_map(node, context, &mut errors, Self::parse_parameter_or_reference)
+            .into_iter()
+            .map(|(name, value)| NamedParameterOrReference { name, value: Some(value) })
+            .collect();
+        finish(Parameters { additional_properties: parameters }, errors)
+    }
+
+    /// Parses ParameterOrReference from a YAML node.
+    pub fn parse_parameter_or_reference(
+        node: &Yaml,
+        context: &Arc<Context>,
+    ) -> Result<ParameterOrReference, ErrorGroup> {
+        if let Some(reference) = OpenApiParser::parse_reference(node, context) {
+            return Ok(ParameterOrReference {
+                oneof: Some(parameter_or_reference::Oneof::Reference(reference)),
+            });
+        }
+
+        Self::parse_parameter(node, context).map(|p| ParameterOrReference {
+            oneof: Some(parameter_or_reference::Oneof::Parameter(p)),
+        })
+    }
+
+    /// Parses Parameter from a YAML node.
+    pub fn parse_parameter(node: &Yaml, context: &Arc<Context>) -> Result<Parameter, ErrorGroup> {
+        let mut errors = Vec::new();
+        let parameter = Parameter {
+            description: string_field(node, "description"),
+            schema: parse_field(node, "schema", context, &mut errors, OpenApiParser::parse_schema_or_reference),
+            location: string_field(node, "location"),
+            specification_extension: Self::parse_specification_extensions(node, context),
+        };
+        finish(parameter, errors)
+    }
+
+    /// Parses Components from a YAML node.
+    pub fn parse_components(node: &Yaml, context: &Arc<Context>) -> Result<Components, ErrorGroup> {
+        let mut errors = Vec::new();
+        let messages = parse_field(node, "messages", context, &mut errors, |node, context| {
+            let mut errors = Vec::new();
+            let messages = parse_map(node, context, &mut errors, Self::parse_message_or_reference)
+                .into_iter()
+                .map(|(name, value)| NamedMessageOrReference { name, value: Some(value) })
+                .collect();
+            finish(MessagesOrReferencesMap { additional_properties: messages }, errors)
+        });
+        let security_schemes = map_value_for_key(node, "securitySchemes")
+            .map(|schemes| {
+                let context = Arc::new(context.child("securitySchemes"));
+                named_anys(parse_map(schemes, &context, &mut errors, OpenApiParser::parse_any))
+            })
+            .unwrap_or_default();
+        let components = Components {
+            schemas: parse_field(node, "schemas", context, &mut errors, OpenApiParser::parse_schemas_or_references),
+            servers: parse_field(node, "servers", context, &mut errors, Self::parse_servers),
+            channels: parse_field(node, "channels", context, &mut errors, Self::parse_channels),
+            messages,
+            parameters: parse_field(node, "parameters", context, &mut errors, Self::parse_parameters),
+            security_schemes,
+            specification_extension: Self::parse_specification_extensions(node, context),
+        };
+        finish(components, errors)
+    }
+
+    /// Parses the specification extensions ("x-" keys) of a YAML node.
+    pub fn parse_specification_extensions(node: &Yaml, context: &Arc<Context>) -> Vec<NamedAny> {
+        let mut extensions = Vec::new();
+        iter_map(node, |name, value| {
+            if let (true, Ok(any)) = (name.starts_with("x-"), OpenApiParser::parse_any(value, context)) {
+                extensions.push(NamedAny {
+                    name: name.to_string(),
+                    value: Some(any),
+                });
+            }
+        });
+        extensions
+    }
+}
+
+/// Returns the string value of `key` in `node`, or an empty string.
+fn string_field(node: &Yaml, key: &str) -> String {
+    map_value_for_key(node, key).and_then(string_for_scalar_node).unwrap_or_default()
+}
+
+/// Parses the value of `key` in `node`, if present, collecting its errors.
+fn parse_field<T>(
+    node: &Yaml,
+    key: &str,
+    context: &Arc<Context>,
+    errors: &mut Vec<CompilerError>,
+    parse: impl FnOnce(&Yaml, &Arc<Context>) -> Result<T, ErrorGroup>,
+) -> Option<T> {
+    let value = map_value_for_key(node, key)?;
+    if context.should_stop(errors) {
+        return None;
+    }
+    let child_ctx = Arc::new(context.child(key));
+    parse(value, &child_ctx).map_err(|e| errors.extend(e.errors)).ok()
+}
+
+/// Parses the items of the sequence at `key` in `node`, collecting their errors.
+fn parse_sequence<T>(
+    node: &Yaml,
+    key: &str,
+    context: &Arc<Context>,
+    errors: &mut Vec<CompilerError>,
+    parse: impl Fn(&Yaml, &Arc<Context>) -> Result<T, ErrorGroup>,
+) -> Vec<T> {
+    let mut values = Vec::new();
+    match map_value_for_key(node, key) {
+        Some(Yaml::Sequence(items)) => {
+            for (i, item) in items.iter().enumerate() {
+                if context.should_stop(errors) {
+                    break;
+                }
+                let child_ctx = Arc::new(context.child(format!("{}[{}]", key, i)));
+                match parse(item, &child_ctx) {
+                    Ok(value) => values.push(value),
+                    Err(e) => errors.extend(e.errors),
+                }
+            }
+        }
+        Some(_) => {
+            let message = format!("{} must be an array", key);
+            if let Err(e) = Arc::new(context.child(key)).report_recoverable(ErrorCode::InvalidType, message) {
+                errors.push(e);
+            }
+        }
+        None => {}
+    }
+    values
+}
+
+/// Parses the values of a mapping, collecting their errors.
+fn parse_map<T>(
+    node: &Yaml,
+    context: &Arc<Context>,
+    errors: &mut Vec<CompilerError>,
+    parse: impl Fn(&Yaml, &Arc<Context>) -> Result<T, ErrorGroup>,
+) -> Vec<(String, T)> {
+    let mut values = Vec::new();
+    if !is_mapping(node) {
+        errors.push(CompilerError::new_with_code(context, ErrorCode::InvalidType, "expected mapping"));
+        return values;
+    }
+    iter_map(node, |name, value| {
+        if context.should_stop(errors) {
+            return;
+        }
+        let child_ctx = Arc::new(context.child(name));
+        match parse(value, &child_ctx) {
+            Ok(value) => values.push((name.to_string(), value)),
+            Err(e) => errors.extend(e.errors),
+        }
+    });
+    values
+}
+
+fn named_anys(values: Vec<(String, gnostic_openapiv3::openapi_v3::Any)>) -> Vec<NamedAny> {
+    values.into_iter().map(|(name, value)| NamedAny { name, value: Some(value) }).collect()
+}
+
+fn finish<T>(value: T, errors: Vec<CompilerError>) -> Result<T, ErrorGroup> {
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(ErrorGroup::new(errors))
+    }
+}
@@ -0,0 +1,111 @@
+//! Integration tests for parsing AsyncAPI documents.
+
+use gnostic_asyncapi::asyncapi_v2::*;
+use gnostic_asyncapi::document::{parse_document, parse_document_with_options};
+use gnostic_compiler::ParseOptions;
+use gnostic_openapiv3::openapi_v3::schema_or_reference;
+use std::fs;
+
+const TESTDATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../testdata");
+
+/// Load an AsyncAPI file
+fn load_asyncapi_file(filename: &str) -> Vec<u8> {
+    let path = format!("{}/{}", TESTDATA_DIR, filename);
+    fs::read(&path)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e))
+}
+
+#[test]
+fn test_asyncapi_parse_streetlights() {
+    let doc = parse_document(&load_asyncapi_file("streetlights-asyncapi.yaml"))
+        .expect("Failed to parse streetlights-asyncapi.yaml");
+
+    assert_eq!(doc.asyncapi, "2.6.0");
+    assert_eq!(doc.id, "urn:example:streetlights");
+    assert_eq!(doc.default_content_type, "application/json");
+    let info = doc.info.as_ref().unwrap();
+    assert_eq!(info.title, "Streetlights API");
+    assert_eq!(info.license.as_ref().unwrap().name, "Apache 2.0");
+
+    let servers = &doc.servers.as_ref().unwrap().additional_properties;
+    assert_eq!(servers.len(), 1);
+    let production = servers[0].value.as_ref().unwrap();
+    assert_eq!(servers[0].name, "production");
+    assert_eq!((production.protocol.as_str(), production.protocol_version.as_str()), ("mqtt", "3.1.1"));
+    let variables = &production.variables.as_ref().unwrap().additional_properties;
+    assert_eq!(variables[0].value.as_ref().unwrap().r#enum, ["eu", "us"]);
+    assert_eq!(variables[1].value.as_ref().unwrap().default, "1883");
+    assert_eq!(production.security[0].additional_properties[0].name, "apiKey");
+
+    let channels = &doc.channels.as_ref().unwrap().additional_properties;
+    assert_eq!(channels.len(), 2);
+    let measured = channels[0].value.as_ref().unwrap();
+    let subscribe = measured.subscribe.as_ref().unwrap();
+    assert_eq!(subscribe.operation_id, "receiveLightMeasurement");
+    assert_eq!(subscribe.traits.len(), 1);
+    match subscribe.message.as_ref().unwrap().oneof.as_ref().unwrap() {
+        operation_message::Oneof::Message(MessageOrReference {
+            oneof: Some(message_or_reference::Oneof::Reference(reference)),
+        }) => assert_eq!(reference.r#ref, "#/components/messages/lightMeasured"),
+        other => panic!("unexpected message {:?}", other),
+    }
+    let parameters = &measured.parameters.as_ref().unwrap().additional_properties;
+    assert_eq!(parameters[0].name, "streetlightId");
+
+    let turn = channels[1].value.as_ref().unwrap();
+    assert_eq!(turn.specification_extension[0].name, "x-internal");
+    let publish = turn.publish.as_ref().unwrap();
+    assert_eq!(publish.tags[0].name, "actions");
+    let Some(operation_message::Oneof::OneOf(messages)) = &publish.message.as_ref().unwrap().oneof else {
+        panic!("expected oneOf messages");
+    };
+    assert_eq!(messages.message_or_reference.len(), 2);
+    let Some(message_or_reference::Oneof::Message(turn_off)) = &messages.message_or_reference[1].oneof else {
+        panic!("expected an inline message");
+    };
+    assert_eq!(turn_off.name, "turnOff");
+    assert!(matches!(
+        turn_off.payload.as_ref().unwrap().oneof,
+        Some(schema_or_reference::Oneof::Schema(ref schema)) if schema.r#type == "object"
+    ));
+
+    let components = doc.components.as_ref().unwrap();
+    let messages = &components.messages.as_ref().unwrap().additional_properties;
+    assert_eq!(messages.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), ["lightMeasured", "turnOn"]);
+    let Some(message_or_reference::Oneof::Message(light_measured)) = &messages[0].value.as_ref().unwrap().oneof else {
+        panic!("expected an inline message");
+    };
+    assert_eq!(light_measured.content_type, "application/json");
+    let correlation_id = &light_measured.correlation_id.as_ref().unwrap().oneof;
+    let Some(correlation_id_or_reference::Oneof::CorrelationId(id)) = correlation_id else {
+        panic!("expected an inline correlation ID");
+    };
+    assert_eq!(id.location, "$message.header#/correlationId");
+    let schemas = &components.schemas.as_ref().unwrap().additional_properties;
+    assert_eq!(schemas[0].name, "lightMeasuredPayload");
+    assert_eq!(components.security_schemes[0].name, "apiKey");
+    let parameter = &components.parameters.as_ref().unwrap().additional_properties[0];
+    assert!(matches!(
+        parameter.value.as_ref().unwrap().oneof,
+        Some(parameter_or_reference::Oneof::Parameter(ref p)) if p.description == "The ID of the streetlight."
+    ));
+}
+
+#[test]
+fn test_asyncapi_proto_bytes_round_trip() {
+    let doc = parse_document(&load_asyncapi_file("streetlights-asyncapi.yaml")).expect("Failed to parse document");
+    let decoded = Document::from_proto_bytes(&doc.to_proto_bytes()).expect("Failed to decode document");
+    assert_eq!(decoded, doc);
+}
+
+#[test]
+fn test_asyncapi_invalid_types() {
+    let err = parse_document(b"asyncapi: 2.6.0\ntags: actions\nchannels: []\n").unwrap_err();
+    let messages: Vec<String> = err.errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(messages, ["$.channels expected mapping", "$.tags tags must be an array"]);
+
+    let parsed = parse_document_with_options(b"asyncapi: 2.6.0\ntags: actions\n", &ParseOptions::lenient())
+        .expect("Lenient parse should succeed");
+    assert_eq!(parsed.warnings.len(), 1);
+    assert!(parse_document(b"- one\n- two\n").is_err());
+}
@@ -0,0 +1,145 @@
+//! Build script support for gnostic-models.
+//!
+//! The model crates generate their types from protocol buffer definitions
+//! with prost-build, which derives only `PartialEq` for messages. This crate
+//! finds the messages that can also derive `Eq` and `Hash`, so that models
+//! can be kept in sets and used as keys of maps.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+
+use prost::Message;
+use prost_build::Config;
+use prost_types::field_descriptor_proto::Type;
+use prost_types::{DescriptorProto, FileDescriptorSet};
+
+/// Configures `config` to derive `Eq` and `Hash` on the messages of `protos`
+/// that can derive them, and on the oneofs of those messages.
+///
+/// Messages with floating-point fields cannot derive them, and neither can
+/// google.protobuf.Any. The messages named in `implemented` implement `Eq`
+/// and `Hash` by hand instead, such as a model's own Any or a schema hashing
+/// its bounds by their bits, so they are not derived but the messages
+/// containing them may derive them.
+pub fn derive_eq_and_hash(
+    config: &mut Config,
+    protos: &[impl AsRef<Path>],
+    includes: &[impl AsRef<Path>],
+    implemented: &[&str],
+) -> Result<()> {
+    for message in eq_and_hash_messages(&file_descriptor_set(protos, includes)?, implemented) {
+        if !implemented.contains(&message.as_str()) {
+            // The attributes of a message also apply to the oneofs in it.
+            config.type_attribute(message, "#[derive(Eq, Hash)]");
+        }
+    }
+    Ok(())
+}
+
+/// Returns the full names, such as ".openapi.v3.Info", of the messages that
+/// can derive `Eq` and `Hash`: those without floating-point fields, directly
+/// or in the messages they contain.
+///
+/// The well-known types of prost-types derive neither, so messages that
+/// contain them, as well as maps and nested messages, are left out too,
+/// unless they are among the `implemented` messages.
+pub fn eq_and_hash_messages(set: &FileDescriptorSet, implemented: &[&str]) -> Vec<String> {
+    let messages: Vec<(String, &DescriptorProto)> = set
+        .file
+        .iter()
+        .filter(|file| file.package() != "google.protobuf")
+        .flat_map(|file| {
+            let package = if file.package().is_empty() { String::new() } else { format!(".{}", file.package()) };
+            file.message_type.iter().map(move |message| (format!("{}.{}", package, message.name()), message))
+        })
+        .collect();
+
+    let mut eligible: HashSet<&str> = messages.iter().map(|(name, _)| name.as_str()).collect();
+    loop {
+        let ineligible: Vec<&str> = messages
+            .iter()
+            .filter(|(name, message)| {
+                eligible.contains(name.as_str())
+                    && !implemented.contains(&name.as_str())
+                    && message.field.iter().any(|field| match field.r#type() {
+                        Type::Double | Type::Float => true,
+                        Type::Message | Type::Group => !eligible.contains(field.type_name()),
+                        _ => false,
+                    })
+            })
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if ineligible.is_empty() {
+            break;
+        }
+        for name in ineligible {
+            eligible.remove(name);
+        }
+    }
+    messages.iter().map(|(name, _)| name).filter(|name| eligible.contains(name.as_str())).cloned().collect()
+}
+
+/// Compiles `protos` into a scratch directory to obtain their descriptors.
+fn file_descriptor_set(protos: &[impl AsRef<Path>], includes: &[impl AsRef<Path>]) -> Result<FileDescriptorSet> {
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| Error::other("OUT_DIR is not set"))?;
+    let out_dir = PathBuf::from(out_dir).join("descriptors");
+    fs::create_dir_all(&out_dir)?;
+    let path = out_dir.join("file_descriptor_set.bin");
+    Config::new().out_dir(&out_dir).file_descriptor_set_path(&path).compile_protos(protos, includes)?;
+    let bytes = fs::read(&path)?;
+    FileDescriptorSet::decode(bytes.as_slice()).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::{FieldDescriptorProto, FileDescriptorProto};
+
+    fn message(name: &str, fields: &[(Type, &str)]) -> DescriptorProto {
+        let field = fields
+            .iter()
+            .map(|(field_type, type_name)| FieldDescriptorProto {
+                r#type: Some(*field_type as i32),
+                type_name: Some(type_name.to_string()),
+                ..FieldDescriptorProto::default()
+            })
+            .collect();
+        DescriptorProto { name: Some(name.to_string()), field, ..DescriptorProto::default() }
+    }
+
+    fn file(package: &str, message_type: Vec<DescriptorProto>) -> FileDescriptorProto {
+        FileDescriptorProto { package: Some(package.to_string()), message_type, ..FileDescriptorProto::default() }
+    }
+
+    #[test]
+    fn test_eq_and_hash_messages() {
+        let set = FileDescriptorSet {
+            file: vec![
+                file("google.protobuf", vec![message("Any", &[(Type::String, ""), (Type::Bytes, "")])]),
+                file(
+                    "openapi.v3",
+                    vec![
+                        message("Info", &[(Type::String, ""), (Type::Message, ".openapi.v3.Contact")]),
+                        message("Contact", &[(Type::String, ""), (Type::Bool, ""), (Type::Enum, ".openapi.v3.E")]),
+                        message("Schema", &[(Type::Double, ""), (Type::Message, ".openapi.v3.Info")]),
+                        message("Document", &[(Type::Message, ".openapi.v3.Info"), (Type::Message, ".openapi.v3.Item")]),
+                        message("Item", &[(Type::Message, ".openapi.v3.Schema")]),
+                        message("Extension", &[(Type::Message, ".google.protobuf.Any")]),
+                        message("Node", &[(Type::Message, ".openapi.v3.Node"), (Type::Int64, "")]),
+                        message("Any", &[(Type::Message, ".google.protobuf.Any")]),
+                        message("Example", &[(Type::Message, ".openapi.v3.Any")]),
+                    ],
+                ),
+            ],
+        };
+        let messages = eq_and_hash_messages(&set, &[]);
+        assert_eq!(messages, [".openapi.v3.Info", ".openapi.v3.Contact", ".openapi.v3.Node"]);
+        assert_eq!(
+            eq_and_hash_messages(&set, &[".openapi.v3.Any"]),
+            [".openapi.v3.Info", ".openapi.v3.Contact", ".openapi.v3.Node", ".openapi.v3.Any", ".openapi.v3.Example"]
+        );
+    }
+}
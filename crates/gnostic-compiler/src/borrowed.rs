@@ -0,0 +1,211 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Borrowed parsing of JSON documents.
+//!
+//! The loader copies every string of a document into its node tree, and
+//! parsers copy them again into the models they build. A [`BorrowedNode`]
+//! tree instead borrows the strings of JSON source that need no unescaping,
+//! so that each string is copied once, into the model.
+
+use serde::de::{Deserialize, Deserializer, Error as _, MapAccess, SeqAccess, Visitor};
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::error::{CompilerError, Result};
+use crate::limits::limits;
+use crate::node::Node;
+
+/// A node of a JSON document whose strings borrow from its source.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BorrowedNode<'a> {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(Cow<'a, str>),
+    Sequence(Vec<BorrowedNode<'a>>),
+    Mapping(Vec<(Cow<'a, str>, BorrowedNode<'a>)>),
+}
+
+/// Parses JSON source into a tree of borrowed nodes, applying the same
+/// limits as [`read_info_from_bytes`](crate::read_info_from_bytes).
+pub fn read_borrowed_json(source: &str) -> Result<BorrowedNode<'_>> {
+    let limits = limits();
+    limits.check_input_size(source.len())?;
+    let node: BorrowedNode =
+        serde_json::from_str(source).map_err(CompilerError::from)?;
+
+    let mut stack = vec![(&node, 0usize)];
+    let mut count = 0usize;
+    while let Some((node, depth)) = stack.pop() {
+        count += 1;
+        limits.check_visited(count, depth)?;
+        match node {
+            BorrowedNode::Mapping(entries) => {
+                // Keys count as nodes, as they do in YAML trees
+                count += entries.len();
+                stack.extend(entries.iter().map(|(_, value)| (value, depth + 1)));
+            }
+            BorrowedNode::Sequence(items) => stack.extend(items.iter().map(|item| (item, depth + 1))),
+            _ => {}
+        }
+    }
+    Ok(node)
+}
+
+impl BorrowedNode<'_> {
+    /// Returns the value of a string node.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BorrowedNode::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl Node for BorrowedNode<'_> {
+    fn is_mapping(&self) -> bool {
+        matches!(self, BorrowedNode::Mapping(_))
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (&str, &Self)> {
+        let entries = match self {
+            BorrowedNode::Mapping(entries) => &entries[..],
+            _ => &[],
+        };
+        entries.iter().map(|(key, value)| (&**key, value))
+    }
+
+    fn items(&self) -> &[Self] {
+        match self {
+            BorrowedNode::Sequence(items) => items,
+            _ => &[],
+        }
+    }
+
+    fn string_value(&self) -> Option<String> {
+        match self {
+            BorrowedNode::String(s) => Some(s.to_string()),
+            BorrowedNode::Number(n) => match n.as_i64() {
+                Some(i) => Some(i.to_string()),
+                None => n.as_f64().map(|f| f.to_string()),
+            },
+            BorrowedNode::Bool(b) => Some(b.to_string()),
+            BorrowedNode::Null => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    fn bool_value(&self) -> Option<bool> {
+        match self {
+            BorrowedNode::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for BorrowedNode<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(NodeVisitor(PhantomData))
+    }
+}
+
+/// Builds nodes, borrowing strings where the deserializer allows.
+struct NodeVisitor<'a>(PhantomData<&'a ()>);
+
+impl<'de: 'a, 'a> Visitor<'de> for NodeVisitor<'a> {
+    type Value = BorrowedNode<'a>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> std::result::Result<Self::Value, E> {
+        Ok(BorrowedNode::Null)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> std::result::Result<Self::Value, E> {
+        Ok(BorrowedNode::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E> {
+        Ok(BorrowedNode::Number(v.into()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E> {
+        Ok(BorrowedNode::Number(v.into()))
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> std::result::Result<Self::Value, E> {
+        serde_json::Number::from_f64(v)
+            .map(BorrowedNode::Number)
+            .ok_or_else(|| E::custom("non-finite number"))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> std::result::Result<Self::Value, E> {
+        Ok(BorrowedNode::String(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> std::result::Result<Self::Value, E> {
+        Ok(BorrowedNode::String(Cow::Owned(v.to_string())))
+    }
+
+    fn visit_string<E>(self, v: String) -> std::result::Result<Self::Value, E> {
+        Ok(BorrowedNode::String(Cow::Owned(v)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(BorrowedNode::Sequence(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(key) = map.next_key::<BorrowedNode<'a>>()? {
+            let BorrowedNode::String(key) = key else {
+                return Err(A::Error::custom("mapping keys must be strings"));
+            };
+            entries.push((key, map.next_value()?));
+        }
+        Ok(BorrowedNode::Mapping(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::read_info_from_json_bytes;
+
+    #[test]
+    fn test_read_borrowed_json() {
+        let source = r#"{"name": "pets", "escaped": "a\"b", "count": 3, "ratio": 0.5, "tags": ["a", true, null]}"#;
+        let node = read_borrowed_json(source).unwrap();
+        assert!(matches!(node.get("name"), Some(BorrowedNode::String(Cow::Borrowed("pets")))));
+        assert!(matches!(node.get("escaped"), Some(BorrowedNode::String(Cow::Owned(_)))));
+        assert_eq!(node.get("escaped").and_then(BorrowedNode::as_str), Some("a\"b"));
+
+        let yaml = read_info_from_json_bytes(source.as_bytes()).unwrap();
+        for key in ["name", "escaped", "count", "ratio"] {
+            assert_eq!(node.get(key).unwrap().string_value(), yaml[key].string_value(), "{}", key);
+        }
+        assert_eq!(node.get("tags").unwrap().string_values(), yaml["tags"].string_values());
+        assert_eq!(node.entries().count(), yaml.entries().count());
+        assert!(read_borrowed_json("{\"name\": ").is_err());
+    }
+}
@@ -0,0 +1,269 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Changelogs between versions of a document.
+//!
+//! A [`Changelog`] groups the changes of a [`Changeset`] by the path or
+//! component they are in, and renders them as Markdown for release notes or
+//! as JSON for other tools, with the changes that break clients called out.
+
+use crate::diff::{Change, Changeset};
+use crate::helpers::unescape_json_pointer_segment;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::fmt::Write;
+
+/// The top-level sections of OpenAPI v2 documents that hold components.
+const V2_SECTIONS: &[&str] = &["definitions", "parameters", "responses", "securityDefinitions"];
+
+/// What the changes of a section of a changelog are in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Subject {
+    /// A path, such as "/pets".
+    Path,
+    /// A component, such as "schemas/Pet" or "definitions/Pet".
+    Component,
+    /// The rest of the document.
+    Document,
+}
+
+/// The changes to one path or component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangelogSection {
+    pub subject: Subject,
+    /// The path or component; empty for the rest of the document.
+    pub name: String,
+    pub changes: Vec<ChangelogEntry>,
+}
+
+/// A change, located within its section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangelogEntry {
+    /// The keys from the path or component to the changed node, joined by
+    /// "/", such as "get/parameters/0"; empty for the path or component itself.
+    pub location: String,
+    pub message: String,
+    pub breaking: bool,
+    pub change: Change,
+}
+
+/// The changes between two versions of a document, grouped by path and
+/// component.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Changelog {
+    /// The number of changes that break clients.
+    pub breaking: usize,
+    /// The sections of paths, then components, then the rest of the
+    /// document, each in the order of their first change.
+    pub sections: Vec<ChangelogSection>,
+}
+
+impl Changelog {
+    /// Groups the changes of a changeset.
+    pub fn new(changeset: &Changeset) -> Self {
+        let mut sections: Vec<ChangelogSection> = Vec::new();
+        for change in &changeset.changes {
+            let keys: Vec<String> = change.pointer.split('/').skip(1).map(unescape_json_pointer_segment).collect();
+            let (subject, name, rest) = match keys.as_slice() {
+                [paths, path, rest @ ..] if paths == "paths" => (Subject::Path, path.clone(), rest),
+                [components, section, name, rest @ ..] if components == "components" => {
+                    (Subject::Component, format!("{}/{}", section, name), rest)
+                }
+                [section, name, rest @ ..] if V2_SECTIONS.contains(&section.as_str()) => {
+                    (Subject::Component, format!("{}/{}", section, name), rest)
+                }
+                rest => (Subject::Document, String::new(), rest),
+            };
+            let entry = ChangelogEntry {
+                location: rest.join("/"),
+                message: change.message(),
+                breaking: change.is_breaking(),
+                change: change.clone(),
+            };
+            match sections.iter_mut().find(|section| section.subject == subject && section.name == name) {
+                Some(section) => section.changes.push(entry),
+                None => sections.push(ChangelogSection { subject, name, changes: vec![entry] }),
+            }
+        }
+        // Sorting is stable, so sections of a subject keep their order.
+        sections.sort_by_key(|section| section.subject);
+        Changelog { breaking: changeset.breaking().count(), sections }
+    }
+
+    /// Returns true if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Renders the changelog as Markdown, starting at second-level headings so
+    /// that it can be put under the heading of a release.
+    ///
+    /// A summary of the breaking changes comes first, and each breaking change
+    /// is marked in its section.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let total: usize = self.sections.iter().map(|section| section.changes.len()).sum();
+        if total == 0 {
+            return "No changes.\n".to_string();
+        }
+        let others = total - self.breaking;
+        let _ = writeln!(out, "{} and {}.", count(self.breaking, "breaking change"), count(others, "other change"));
+
+        if self.breaking > 0 {
+            out.push_str("\n## Breaking changes\n\n");
+            for section in &self.sections {
+                for entry in section.changes.iter().filter(|entry| entry.breaking) {
+                    let location = [section.name.as_str(), entry.location.as_str()];
+                    let location: Vec<&str> = location.into_iter().filter(|part| !part.is_empty()).collect();
+                    let _ = writeln!(out, "- {}", item(&location.join(" "), &entry.message));
+                }
+            }
+        }
+
+        let mut subject = None;
+        for section in &self.sections {
+            if subject != Some(section.subject) {
+                subject = Some(section.subject);
+                let heading = match section.subject {
+                    Subject::Path => "Paths",
+                    Subject::Component => "Components",
+                    Subject::Document => "Document",
+                };
+                let _ = write!(out, "\n## {}\n", heading);
+            }
+            if !section.name.is_empty() {
+                let _ = write!(out, "\n### `{}`\n", section.name);
+            }
+            out.push('\n');
+            for entry in &section.changes {
+                let marker = if entry.breaking { "**Breaking:** " } else { "" };
+                let _ = writeln!(out, "- {}{}", marker, item(&entry.location, &entry.message));
+            }
+        }
+        out
+    }
+
+    /// Returns the changelog as JSON.
+    pub fn to_json(&self) -> JsonValue {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+impl Changeset {
+    /// Returns the changes grouped by path and component.
+    pub fn changelog(&self) -> Changelog {
+        Changelog::new(self)
+    }
+}
+
+fn count(n: usize, noun: &str) -> String {
+    format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" })
+}
+
+fn item(location: &str, message: &str) -> String {
+    if location.is_empty() {
+        message.to_string()
+    } else {
+        format!("`{}`: {}", location, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::diff_documents;
+
+    fn changelog(old: &str, new: &str) -> Changelog {
+        let (old, new) = (serde_yaml::from_str(old).unwrap(), serde_yaml::from_str(new).unwrap());
+        diff_documents(&old, &new).changelog()
+    }
+
+    #[test]
+    fn test_changelog() {
+        let old = r##"
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, schema: {type: integer}}
+      responses: {"200": {description: Pets.}}
+  /stores: {}
+components:
+  schemas:
+    Pet: {properties: {id: {type: integer}}}
+"##;
+        let new = r##"
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, required: true, schema: {type: integer}}
+      responses: {"200": {description: Pets.}, default: {description: An error.}}
+components:
+  schemas:
+    Pet: {properties: {id: {type: integer}, name: {type: string}}}
+"##;
+        let changelog = changelog(old, new);
+        let names: Vec<&str> = changelog.sections.iter().map(|section| section.name.as_str()).collect();
+        assert_eq!(names, ["/stores", "/pets", "schemas/Pet"]);
+        assert_eq!(changelog.breaking, 2);
+        assert_eq!(
+            changelog.to_markdown(),
+            r#"2 breaking changes and 2 other changes.
+
+## Breaking changes
+
+- `/stores`: path removed
+- `/pets get/parameters/0/required`: required changed from false to true
+
+## Paths
+
+### `/stores`
+
+- **Breaking:** path removed
+
+### `/pets`
+
+- **Breaking:** `get/parameters/0/required`: required changed from false to true
+- `get/responses/default`: response added
+
+## Components
+
+### `schemas/Pet`
+
+- `properties/name`: property added
+"#
+        );
+
+        let json = changelog.to_json();
+        assert_eq!(json["breaking"], 2);
+        assert_eq!(json["sections"][2]["subject"], "component");
+        assert_eq!(json["sections"][2]["changes"][0]["location"], "properties/name");
+        assert_eq!(json["sections"][2]["changes"][0]["change"]["kind"], "property-added");
+    }
+
+    #[test]
+    fn test_changelog_v2_and_empty() {
+        let removed = changelog("swagger: '2.0'\ndefinitions: {Pet: {}}", "swagger: '2.0'");
+        assert_eq!(removed.sections[0].subject, Subject::Component);
+        assert_eq!(removed.sections[0].name, "definitions/Pet");
+
+        let unchanged = changelog("paths: {/a: {}}", "paths: {/a: {}}");
+        assert!(unchanged.is_empty());
+        assert_eq!(unchanged.to_markdown(), "No changes.\n");
+    }
+}
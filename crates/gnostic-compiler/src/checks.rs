@@ -0,0 +1,536 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Semantic checks shared by the OpenAPI validators.
+//!
+//! Structural validation checks each object on its own; these checks look
+//! at relations within a document that break tools consuming it, such as
+//! two operations sharing an operationId. They work on the YAML of OpenAPI
+//! v2 and v3 documents, whose paths have the same shape.
+
+use crate::reader::node_for_pointer;
+use crate::{CompilerError, Context, ErrorCode, Severity};
+use serde_yaml::Value as Yaml;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// ValidateOptions configures the semantic checks of the validators.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidateOptions {
+    /// Report operations that have no operationId.
+    pub require_operation_ids: bool,
+    /// The severity of fields that the declared version of the document
+    /// does not have, such as webhooks in OpenAPI 3.0.
+    pub version_features: Severity,
+}
+
+impl ValidateOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        ValidateOptions::default()
+    }
+
+    /// Returns the options with operations required to have an operationId.
+    pub fn with_required_operation_ids(mut self) -> Self {
+        self.require_operation_ids = true;
+        self
+    }
+
+    /// Returns the options with fields of other versions reported with `severity`.
+    pub fn with_version_feature_severity(mut self, severity: Severity) -> Self {
+        self.version_features = severity;
+        self
+    }
+}
+
+/// Returns the operations under the paths of a document with their contexts,
+/// in document order. `methods` are the keys of a path item that hold
+/// operations.
+pub fn operations<'a>(node: &'a Yaml, methods: &[&str]) -> Vec<(Arc<Context>, &'a Yaml)> {
+    let mut operations = Vec::new();
+    let Yaml::Mapping(paths) = &node["paths"] else {
+        return operations;
+    };
+    let root = Arc::new(Context::root("$"));
+    let context = Arc::new(root.child("paths"));
+    for (path, item) in paths {
+        let (Some(path), Yaml::Mapping(item)) = (path.as_str(), item) else {
+            continue;
+        };
+        let path_context = Arc::new(context.child(path));
+        for (method, operation) in item {
+            let Some(method) = method.as_str() else {
+                continue;
+            };
+            if methods.contains(&method) && operation.is_mapping() {
+                operations.push((Arc::new(path_context.child(method)), operation));
+            }
+        }
+    }
+    operations
+}
+
+/// Reports operationIds used by more than one operation and, if the options
+/// require them, operations without one.
+pub fn check_operation_ids(node: &Yaml, methods: &[&str], options: &ValidateOptions) -> Vec<CompilerError> {
+    let mut errors = Vec::new();
+    let mut seen: HashMap<&str, String> = HashMap::new();
+    for (context, operation) in operations(node, methods) {
+        match operation["operationId"].as_str() {
+            Some(id) => match seen.get(id) {
+                Some(first) => errors.push(CompilerError::new_with_code(
+                    &context.child("operationId"),
+                    ErrorCode::DuplicateName,
+                    format!("duplicates operationId {} of {}", id, first),
+                )),
+                None => {
+                    seen.insert(id, context.description());
+                }
+            },
+            None if options.require_operation_ids => errors.push(CompilerError::new_with_code(
+                &context,
+                ErrorCode::MissingRequiredField,
+                "is missing required property: operationId",
+            )),
+            None => {}
+        }
+    }
+    errors
+}
+
+/// Reports mismatches between the templates of paths and their path
+/// parameters, and paths that differ only in the names of their templates.
+///
+/// Every "{name}" in a path needs a parameter with `in: path` in each
+/// operation, either its own or one of the path item; path parameters must
+/// be required and appear in the path. Parameters given by references are
+/// followed within the document; operations with other references are not
+/// checked for missing parameters.
+pub fn check_path_parameters(node: &Yaml, methods: &[&str]) -> Vec<CompilerError> {
+    let mut errors = Vec::new();
+    let Yaml::Mapping(paths) = &node["paths"] else {
+        return errors;
+    };
+    let root = Arc::new(Context::root("$"));
+    let context = Arc::new(root.child("paths"));
+    let mut shapes: HashMap<String, &str> = HashMap::new();
+    for (path, item) in paths {
+        let Some(path) = path.as_str().filter(|path| path.starts_with('/')) else {
+            continue;
+        };
+        let path_context = Arc::new(context.child(path));
+        let names = template_names(path);
+
+        let shape = path_shape(path);
+        match shapes.get(&shape) {
+            Some(first) => errors.push(CompilerError::new_with_code(
+                &path_context,
+                ErrorCode::DuplicateName,
+                format!("is ambiguous with path {}", first),
+            )),
+            None => {
+                shapes.insert(shape, path);
+            }
+        }
+
+        let Yaml::Mapping(item) = item else {
+            continue;
+        };
+        let (shared, shared_complete) =
+            path_parameters(node, item.get("parameters"), &path_context, &names, &mut errors);
+        for (method, operation) in item {
+            let Some(method) = method.as_str().filter(|method| methods.contains(method)) else {
+                continue;
+            };
+            if !operation.is_mapping() {
+                continue;
+            }
+            let operation_context = Arc::new(path_context.child(method));
+            let (own, complete) =
+                path_parameters(node, operation.get("parameters"), &operation_context, &names, &mut errors);
+            if !(complete && shared_complete) {
+                continue;
+            }
+            for name in &names {
+                if !own.contains(name) && !shared.contains(name) {
+                    errors.push(CompilerError::new_with_code(
+                        &operation_context,
+                        ErrorCode::MissingRequiredField,
+                        format!("is missing path parameter: {}", name),
+                    ));
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Reports response keys that are not "default", an extension or a status
+/// code, and operations without responses.
+///
+/// Status codes are three digits; codes outside 100-599 are reported as
+/// warnings. If `ranges` is true, as in OpenAPI v3, a range such as "2XX"
+/// may be given for a class of codes.
+pub fn check_responses(node: &Yaml, methods: &[&str], ranges: bool) -> Vec<CompilerError> {
+    let mut errors = Vec::new();
+    for (context, operation) in operations(node, methods) {
+        let Some(Yaml::Mapping(responses)) = operation.get("responses") else {
+            continue;
+        };
+        let responses_context = Arc::new(context.child("responses"));
+        let mut count = 0;
+        for key in responses.keys() {
+            let key = match key {
+                Yaml::String(key) => key.clone(),
+                Yaml::Number(number) => number.to_string(),
+                _ => continue,
+            };
+            if key.starts_with("x-") {
+                continue;
+            }
+            count += 1;
+            if key == "default" {
+                continue;
+            }
+            let digits: Vec<char> = key.chars().collect();
+            let range = ranges && digits.len() == 3 && digits[1..].iter().all(|c| *c == 'X');
+            if digits.len() != 3 || !digits[0].is_ascii_digit() || !(range || key.chars().all(|c| c.is_ascii_digit())) {
+                errors.push(CompilerError::new_with_code(
+                    &responses_context,
+                    ErrorCode::InvalidValue,
+                    format!("has invalid status code: {}", key),
+                ));
+            } else if !('1'..='5').contains(&digits[0]) {
+                errors.push(
+                    CompilerError::warning(&responses_context, format!("has unknown status code: {}", key))
+                        .with_code(ErrorCode::InvalidValue),
+                );
+            }
+        }
+        if count == 0 {
+            errors.push(CompilerError::new_with_code(
+                &responses_context,
+                ErrorCode::MissingRequiredField,
+                "must contain at least one response",
+            ));
+        }
+    }
+    errors
+}
+
+/// Reports security requirements of the document and its operations that
+/// name schemes missing from the mapping at `schemes`, such as
+/// `&["components", "securitySchemes"]`, or scopes an oauth2 scheme does
+/// not define.
+pub fn check_security(node: &Yaml, methods: &[&str], schemes: &[&str]) -> Vec<CompilerError> {
+    let mut errors = Vec::new();
+    let mut definitions = node;
+    for key in schemes {
+        definitions = &definitions[*key];
+    }
+    let root = Arc::new(Context::root("$"));
+    let mut requirements = vec![(root.clone(), &node["security"])];
+    for (context, operation) in operations(node, methods) {
+        requirements.push((context, &operation["security"]));
+    }
+    for (context, security) in requirements {
+        let Yaml::Sequence(security) = security else {
+            continue;
+        };
+        let security_context = Arc::new(context.child("security"));
+        for (i, requirement) in security.iter().enumerate() {
+            let Yaml::Mapping(requirement) = requirement else {
+                continue;
+            };
+            let requirement_context = Arc::new(security_context.child(i.to_string()));
+            for (name, scopes) in requirement {
+                let Some(name) = name.as_str() else {
+                    continue;
+                };
+                let Some(scheme) = definitions.get(name) else {
+                    errors.push(CompilerError::new_with_code(
+                        &requirement_context,
+                        ErrorCode::UnresolvedReference,
+                        format!("uses undefined security scheme: {}", name),
+                    ));
+                    continue;
+                };
+                let Some(defined) = local_target(node, scheme).and_then(oauth2_scopes) else {
+                    continue;
+                };
+                for scope in scopes.as_sequence().into_iter().flatten().filter_map(Yaml::as_str) {
+                    if !defined.contains(scope) {
+                        errors.push(CompilerError::new_with_code(
+                            &requirement_context.child(name),
+                            ErrorCode::InvalidValue,
+                            format!("uses undefined scope {} of security scheme {}", scope, name),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Returns the scopes of an oauth2 security scheme, which are listed in its
+/// flows in OpenAPI v3 and on the scheme itself in OpenAPI v2.
+fn oauth2_scopes(scheme: &Yaml) -> Option<HashSet<&str>> {
+    if scheme["type"].as_str() != Some("oauth2") {
+        return None;
+    }
+    let flows = scheme["flows"].as_mapping().into_iter().flat_map(|flows| flows.values());
+    let scopes = std::iter::once(&scheme["scopes"])
+        .chain(flows.map(|flow| &flow["scopes"]))
+        .filter_map(Yaml::as_mapping)
+        .flat_map(|scopes| scopes.keys().filter_map(Yaml::as_str))
+        .collect();
+    Some(scopes)
+}
+
+/// Returns the names of the templates in a path or URL, such as "id" in
+/// "/pets/{id}".
+pub fn template_names(path: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        names.push(&rest[start + 1..start + end]);
+        rest = &rest[start + end + 1..];
+    }
+    names
+}
+
+/// Returns `path` with the names of its templates removed, so that
+/// "/pets/{id}" and "/pets/{petId}" have the same shape.
+fn path_shape(path: &str) -> String {
+    let mut shape = String::new();
+    let mut in_template = false;
+    for c in path.chars() {
+        match c {
+            '{' => in_template = true,
+            '}' => in_template = false,
+            _ if in_template => continue,
+            _ => {}
+        }
+        shape.push(c);
+    }
+    shape
+}
+
+/// Checks the path parameters of a parameter list against the templates of
+/// its path. Returns the names of the path parameters and whether every
+/// parameter of the list could be read.
+fn path_parameters<'a>(
+    root: &'a Yaml,
+    parameters: Option<&'a Yaml>,
+    context: &Arc<Context>,
+    names: &[&str],
+    errors: &mut Vec<CompilerError>,
+) -> (HashSet<&'a str>, bool) {
+    let mut found = HashSet::new();
+    let mut complete = true;
+    let Some(Yaml::Sequence(parameters)) = parameters else {
+        return (found, complete);
+    };
+    let parameters_context = Arc::new(context.child("parameters"));
+    for (i, parameter) in parameters.iter().enumerate() {
+        let Some(parameter) = local_target(root, parameter) else {
+            complete = false;
+            continue;
+        };
+        if parameter["in"].as_str() != Some("path") {
+            continue;
+        }
+        let Some(name) = parameter["name"].as_str() else {
+            continue;
+        };
+        let parameter_context = parameters_context.child(i.to_string());
+        if !names.contains(&name) {
+            errors.push(CompilerError::new_with_code(
+                &parameter_context,
+                ErrorCode::InvalidValue,
+                format!("declares path parameter {} that is not in the path", name),
+            ));
+        }
+        if parameter["required"].as_bool() != Some(true) {
+            errors.push(CompilerError::new_with_code(
+                &parameter_context,
+                ErrorCode::InvalidValue,
+                format!("path parameter {} must be required", name),
+            ));
+        }
+        found.insert(name);
+    }
+    (found, complete)
+}
+
+/// Returns the node that `node` refers to, following references within the
+/// document `root`, or `node` itself if it is not a reference. Returns None
+/// for external, unresolved and circular references.
+pub fn local_target<'a>(root: &'a Yaml, node: &'a Yaml) -> Option<&'a Yaml> {
+    let mut current = node;
+    let mut visited = HashSet::new();
+    while let Some(reference) = current["$ref"].as_str() {
+        let pointer = reference.strip_prefix('#')?;
+        if !visited.insert(pointer) {
+            return None;
+        }
+        current = node_for_pointer(root, pointer)?;
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_ids() {
+        let node: Yaml = serde_yaml::from_str(
+            "paths:\n  /pets:\n    get:\n      operationId: listPets\n    post:\n      operationId: listPets\n    parameters: []\n  /pets/{id}:\n    get:\n      responses: {}\n    delete:\n      operationId: listPets\n",
+        )
+        .unwrap();
+        let methods = ["get", "post", "delete"];
+        let messages = |options| -> Vec<String> {
+            check_operation_ids(&node, &methods, &options).iter().map(|e| e.to_string()).collect()
+        };
+        assert_eq!(
+            messages(ValidateOptions::new()),
+            [
+                "$.paths./pets.post.operationId duplicates operationId listPets of $.paths./pets.get",
+                "$.paths./pets/{id}.delete.operationId duplicates operationId listPets of $.paths./pets.get",
+            ]
+        );
+        let errors = check_operation_ids(&node, &methods, &ValidateOptions::new().with_required_operation_ids());
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[1].to_string(), "$.paths./pets/{id}.get is missing required property: operationId");
+        assert_eq!(errors[1].code(), ErrorCode::MissingRequiredField);
+    }
+
+    #[test]
+    fn test_path_parameters() {
+        let node: Yaml = serde_yaml::from_str(
+            r#"
+paths:
+  /pets/{id}:
+    parameters:
+    - $ref: '#/parameters/id'
+    get: {}
+    delete:
+      parameters:
+      - name: force
+        in: path
+  /pets/{petId}:
+    get:
+      parameters:
+      - name: petId
+        in: query
+  /owners/{ownerId}/pets:
+    get:
+      parameters:
+      - $ref: 'common.yaml#/ownerId'
+parameters:
+  id:
+    name: id
+    in: path
+    required: true
+"#,
+        )
+        .unwrap();
+        let messages: Vec<String> = check_path_parameters(&node, &["get", "delete"])
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "$.paths./pets/{id}.delete.parameters.0 declares path parameter force that is not in the path",
+                "$.paths./pets/{id}.delete.parameters.0 path parameter force must be required",
+                "$.paths./pets/{petId} is ambiguous with path /pets/{id}",
+                "$.paths./pets/{petId}.get is missing path parameter: petId",
+            ]
+        );
+        assert_eq!(template_names("/a/{b}/c/{d}.json"), ["b", "d"]);
+        assert_eq!(path_shape("/a/{b}/c/{d}.json"), "/a/{}/c/{}.json");
+    }
+
+    #[test]
+    fn test_responses() {
+        let node: Yaml = serde_yaml::from_str(
+            "paths:\n  /pets:\n    get:\n      responses:\n        200: {}\n        '2XX': {}\n        '2xx': {}\n        '20': {}\n        '600': {}\n        default: {}\n    post:\n      responses:\n        x-note: none\n",
+        )
+        .unwrap();
+        let messages = |ranges| -> Vec<String> {
+            check_responses(&node, &["get", "post"], ranges).iter().map(|e| e.to_string()).collect()
+        };
+        assert_eq!(
+            messages(true),
+            [
+                "$.paths./pets.get.responses has invalid status code: 2xx",
+                "$.paths./pets.get.responses has invalid status code: 20",
+                "warning: $.paths./pets.get.responses has unknown status code: 600",
+                "$.paths./pets.post.responses must contain at least one response",
+            ]
+        );
+        assert_eq!(messages(false)[0], "$.paths./pets.get.responses has invalid status code: 2XX");
+    }
+
+    #[test]
+    fn test_security() {
+        let node: Yaml = serde_yaml::from_str(
+            r#"
+security:
+- api_key: []
+- basic: []
+paths:
+  /pets:
+    get:
+      security:
+      - oauth: [read, write]
+        api_key: []
+components:
+  securitySchemes:
+    api_key:
+      type: apiKey
+      name: key
+      in: header
+    oauth:
+      $ref: '#/schemes/oauth'
+schemes:
+  oauth:
+    type: oauth2
+    flows:
+      implicit:
+        authorizationUrl: https://example.com/auth
+        scopes:
+          read: read pets
+"#,
+        )
+        .unwrap();
+        let messages: Vec<String> = check_security(&node, &["get"], &["components", "securitySchemes"])
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "$.security.1 uses undefined security scheme: basic",
+                "$.paths./pets.get.security.0.oauth uses undefined scope write of security scheme oauth",
+            ]
+        );
+    }
+}
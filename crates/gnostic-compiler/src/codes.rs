@@ -0,0 +1,153 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Stable error codes for compiler diagnostics.
+//!
+//! Codes are never renumbered or reused, so they can be referenced from
+//! documentation and used to filter or suppress diagnostics.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Declares the ErrorCode enum along with its code strings and names.
+macro_rules! error_codes {
+    ($($(#[$doc:meta])* $variant:ident = $code:literal,)*) => {
+        /// A stable identifier for a kind of compiler diagnostic.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        #[non_exhaustive]
+        pub enum ErrorCode {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl ErrorCode {
+            /// All known error codes.
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)*];
+
+            /// Returns the code string, e.g. "GNO0001".
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                }
+            }
+
+            /// Returns the name of the code, e.g. "MissingRequiredField".
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => stringify!($variant),)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// A diagnostic that has not been assigned a specific code.
+    #[default]
+    Unspecified = "GNO0000",
+    /// A required field is missing.
+    MissingRequiredField = "GNO0001",
+    /// A node has the wrong YAML type (e.g. a scalar where a mapping is expected).
+    InvalidType = "GNO0002",
+    /// A value is outside the set of allowed values.
+    InvalidValue = "GNO0003",
+    /// A string value does not match its expected format.
+    InvalidFormat = "GNO0004",
+    /// A mapping contains the same key more than once.
+    DuplicateKey = "GNO0005",
+    /// Two named entities share the same name.
+    DuplicateName = "GNO0006",
+    /// A $ref value is malformed.
+    InvalidReference = "GNO0007",
+    /// A $ref value does not point to anything.
+    UnresolvedReference = "GNO0008",
+    /// A chain of $ref values refers back to itself.
+    CircularReference = "GNO0009",
+    /// The specification version is not supported.
+    UnsupportedVersion = "GNO0010",
+    /// A feature is recognized but not supported.
+    UnsupportedFeature = "GNO0011",
+    /// A mapping contains a key that is not allowed.
+    UnknownKey = "GNO0012",
+    /// A file could not be read.
+    Io = "GNO0100",
+    /// The input is not valid YAML or JSON.
+    Syntax = "GNO0101",
+    /// A remote document could not be fetched.
+    Http = "GNO0102",
+    /// A configured resource limit was exceeded.
+    LimitExceeded = "GNO0103",
+    /// A binary protocol buffer message could not be decoded.
+    Protobuf = "GNO0104",
+    /// A model could not be encoded or decoded in a serde binary format.
+    Encoding = "GNO0105",
+    /// Fetching a remote document is not allowed by the fetch policy.
+    FetchDenied = "GNO0106",
+    /// A JSON Patch operation could not be applied.
+    PatchFailed = "GNO0107",
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = String;
+
+    /// Parses either a code string ("GNO0012") or a name ("UnknownKey").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ErrorCode::ALL
+            .iter()
+            .find(|code| code.as_str().eq_ignore_ascii_case(s) || code.name() == s)
+            .copied()
+            .ok_or_else(|| format!("unknown error code: {}", s))
+    }
+}
+
+impl serde::Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_strings() {
+        assert_eq!(ErrorCode::MissingRequiredField.as_str(), "GNO0001");
+        assert_eq!(ErrorCode::UnknownKey.as_str(), "GNO0012");
+        assert_eq!(ErrorCode::UnknownKey.name(), "UnknownKey");
+        assert_eq!(ErrorCode::default(), ErrorCode::Unspecified);
+        assert_eq!(ErrorCode::LimitExceeded.to_string(), "GNO0103");
+    }
+
+    #[test]
+    fn test_error_code_from_str() {
+        assert_eq!("GNO0012".parse::<ErrorCode>(), Ok(ErrorCode::UnknownKey));
+        assert_eq!("gno0001".parse::<ErrorCode>(), Ok(ErrorCode::MissingRequiredField));
+        assert_eq!("InvalidType".parse::<ErrorCode>(), Ok(ErrorCode::InvalidType));
+        assert!("GNO9999".parse::<ErrorCode>().is_err());
+    }
+
+    #[test]
+    fn test_error_codes_are_unique() {
+        let mut codes: Vec<&str> = ErrorCode::ALL.iter().map(|c| c.as_str()).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), ErrorCode::ALL.len());
+    }
+}
@@ -0,0 +1,357 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Preservation of YAML comments.
+//!
+//! serde_yaml discards comments, so they are collected from the source text
+//! separately and keyed by the path of the node they belong to: full-line
+//! comments lead the node that follows them and end-of-line comments trail the
+//! node on that line. The emitter replays them when [`Comments`] are set in
+//! [`EmitOptions`](crate::EmitOptions).
+//!
+//! Only block-style YAML is scanned. Comments inside flow collections that
+//! span several lines are not collected.
+
+use std::collections::HashMap;
+
+/// Comments attached to a single node.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeComments {
+    /// Full-line comments preceding the node, including the leading "#".
+    pub leading: Vec<String>,
+    /// The comment at the end of the node's line, including the leading "#".
+    pub trailing: Option<String>,
+}
+
+/// The comments of a YAML document, keyed by node path.
+///
+/// A path holds one segment per level: the key for mapping entries and the
+/// index for sequence entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Comments {
+    nodes: HashMap<Vec<String>, NodeComments>,
+    footer: Vec<String>,
+}
+
+impl Comments {
+    /// Collects the comments in a YAML source text.
+    pub fn from_source(source: &str) -> Self {
+        Scanner::default().scan(source)
+    }
+
+    /// Returns the comments attached to the node at `path`.
+    pub fn get<S: AsRef<str>>(&self, path: &[S]) -> Option<&NodeComments> {
+        let path: Vec<String> = path.iter().map(|s| s.as_ref().to_string()).collect();
+        self.nodes.get(&path)
+    }
+
+    /// Returns the comments that follow the last node of the document.
+    pub fn footer(&self) -> &[String] {
+        &self.footer
+    }
+
+    /// Returns true if no comments were collected.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.footer.is_empty()
+    }
+}
+
+/// An open node in the block structure of the source.
+struct Frame {
+    indent: usize,
+    segment: String,
+    /// A mapping key whose value starts on a following line.
+    open_key: bool,
+}
+
+#[derive(Default)]
+struct Scanner {
+    stack: Vec<Frame>,
+    counters: HashMap<Vec<String>, usize>,
+    pending: Vec<String>,
+    comments: Comments,
+}
+
+impl Scanner {
+    fn scan(mut self, source: &str) -> Comments {
+        // Lines with an indentation greater than this belong to a block scalar.
+        let mut block_scalar: Option<usize> = None;
+        // Nesting depth of a flow collection continued on following lines.
+        let mut flow_depth = 0i32;
+
+        for line in source.lines() {
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+            if let Some(parent) = block_scalar {
+                if trimmed.is_empty() || indent > parent {
+                    continue;
+                }
+                block_scalar = None;
+            }
+            if flow_depth > 0 {
+                flow_depth += bracket_depth(split_comment(trimmed).0);
+                continue;
+            }
+            if trimmed.is_empty() || trimmed == "---" || trimmed == "..." || trimmed.starts_with('%') {
+                continue;
+            }
+            if trimmed.starts_with('#') {
+                self.pending.push(trimmed.trim_end().to_string());
+                continue;
+            }
+            if self.stack.is_empty() && indent == 0 && trimmed.starts_with(['{', '[']) {
+                // A flow-style (JSON) document has no block structure to follow.
+                break;
+            }
+
+            let (content, trailing) = split_comment(trimmed);
+            let mut rest = content.trim_end();
+            let mut column = indent;
+            let mut attached = false;
+
+            while rest == "-" || rest.starts_with("- ") {
+                self.pop_while(|frame| {
+                    frame.indent > column || (frame.indent == column && !frame.open_key)
+                });
+                let parent = self.path();
+                let counter = self.counters.entry(parent).or_insert(0);
+                let index = *counter;
+                *counter += 1;
+                self.stack.push(Frame {
+                    indent: column,
+                    segment: index.to_string(),
+                    open_key: false,
+                });
+                attached = true;
+                let after = &rest[1..];
+                let value = after.trim_start();
+                column += 1 + after.len() - value.len();
+                rest = value;
+            }
+
+            let value = match split_key(rest) {
+                Some((key, value)) => {
+                    self.pop_while(|frame| frame.indent >= column);
+                    self.stack.push(Frame {
+                        indent: column,
+                        segment: key,
+                        open_key: value.is_empty(),
+                    });
+                    attached = true;
+                    value
+                }
+                None => rest,
+            };
+            if value.starts_with(['|', '>']) {
+                block_scalar = Some(self.stack.last().map_or(0, |frame| frame.indent));
+            } else if value.starts_with(['[', '{']) {
+                flow_depth = bracket_depth(value);
+            }
+
+            // Lines without a key or item continue a multi-line plain scalar,
+            // so their comments stay pending.
+            if attached && (!self.pending.is_empty() || trailing.is_some()) {
+                let path = self.path();
+                let node = self.comments.nodes.entry(path).or_default();
+                node.leading.append(&mut self.pending);
+                if let Some(trailing) = trailing {
+                    node.trailing = Some(trailing.to_string());
+                }
+            }
+        }
+        self.comments.footer = self.pending;
+        self.comments
+    }
+
+    fn pop_while(&mut self, condition: impl Fn(&Frame) -> bool) {
+        while self.stack.last().is_some_and(&condition) {
+            let frame = self.stack.pop();
+            // A key that is reopened later starts its sequences from zero.
+            if let Some(frame) = frame {
+                let mut path = self.path();
+                path.push(frame.segment);
+                self.counters.remove(&path);
+            }
+        }
+    }
+
+    fn path(&self) -> Vec<String> {
+        self.stack.iter().map(|frame| frame.segment.clone()).collect()
+    }
+}
+
+/// Splits a line into its content and end-of-line comment.
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some('"') if c == '\\' && previous != '\\' => {}
+            Some(q) if c == q && !(q == '"' && previous == '\\') => quote = None,
+            Some(_) => {}
+            None if (c == '"' || c == '\'') && (previous == ' ' || i == 0 || "[{,:".contains(previous)) => {
+                quote = Some(c)
+            }
+            None if c == '#' && previous.is_whitespace() => {
+                return (&line[..i], Some(line[i..].trim_end()));
+            }
+            None => {}
+        }
+        previous = if quote == Some('"') && previous == '\\' && c == '\\' { ' ' } else { c };
+    }
+    (line, None)
+}
+
+/// Splits "key: value" into the key text and the value, if the line is a
+/// mapping entry.
+fn split_key(content: &str) -> Option<(String, &str)> {
+    if content.starts_with(['[', '{', '?']) {
+        return None;
+    }
+    let (key, after) = if content.starts_with(['"', '\'']) {
+        let quote = content.chars().next()?;
+        let mut end = None;
+        let mut escaped = false;
+        for (i, c) in content.char_indices().skip(1) {
+            if quote == '"' && c == '\\' && !escaped {
+                escaped = true;
+                continue;
+            }
+            if c == quote && !escaped {
+                if quote == '\'' && content[i + 1..].starts_with('\'') {
+                    escaped = true;
+                    continue;
+                }
+                end = Some(i);
+                break;
+            }
+            escaped = false;
+        }
+        let end = end?;
+        let key: String = serde_yaml::from_str(&content[..=end]).ok()?;
+        (key, content[end + 1..].trim_start())
+    } else {
+        let colon = content
+            .match_indices(':')
+            .map(|(i, _)| i)
+            .find(|&i| content[i + 1..].is_empty() || content[i + 1..].starts_with(' '))?;
+        (content[..colon].trim_end().to_string(), &content[colon..])
+    };
+    let value = after.strip_prefix(':')?;
+    if !(value.is_empty() || value.starts_with(' ')) {
+        return None;
+    }
+    Some((key, value.trim()))
+}
+
+/// Returns the change in flow collection nesting over a line.
+fn bracket_depth(text: &str) -> i32 {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_source() {
+        let source = "\
+# API description
+openapi: 3.0.0 # version
+info:
+  # the title
+  title: Pets
+paths:
+  /pets:
+    get:
+      tags:
+      - pets # primary
+      # secondary
+      - animals
+      description: |
+        # not a comment
+        text
+      responses:
+        '200': # ok
+          description: ok
+# end
+";
+        let comments = Comments::from_source(source);
+        let openapi = comments.get(&["openapi"]).unwrap();
+        assert_eq!(openapi.leading, vec!["# API description"]);
+        assert_eq!(openapi.trailing.as_deref(), Some("# version"));
+        assert_eq!(comments.get(&["info", "title"]).unwrap().leading, vec!["# the title"]);
+
+        let get = ["paths", "/pets", "get"];
+        let tag = |i: &str| comments.get(&[get[0], get[1], get[2], "tags", i]).cloned();
+        assert_eq!(tag("0").unwrap().trailing.as_deref(), Some("# primary"));
+        assert_eq!(tag("1").unwrap().leading, vec!["# secondary"]);
+        assert!(comments.get(&[get[0], get[1], get[2], "description"]).is_none());
+        let ok = comments.get(&[get[0], get[1], get[2], "responses", "200"]).unwrap();
+        assert_eq!(ok.trailing.as_deref(), Some("# ok"));
+        assert_eq!(comments.footer(), ["# end"]);
+    }
+
+    #[test]
+    fn test_sequence_of_mappings() {
+        let source = "\
+tags:
+# first
+- name: a # named
+  description: x
+- name: b
+  # described
+  description: y
+servers: []
+";
+        let comments = Comments::from_source(source);
+        let first = comments.get(&["tags", "0", "name"]).unwrap();
+        assert_eq!(first.leading, vec!["# first"]);
+        assert_eq!(first.trailing.as_deref(), Some("# named"));
+        let second = comments.get(&["tags", "1", "description"]).unwrap();
+        assert_eq!(second.leading, vec!["# described"]);
+        assert!(Comments::from_source("{\"a\": 1}").is_empty());
+    }
+
+    #[test]
+    fn test_split_comment() {
+        assert_eq!(split_comment("a: b # c"), ("a: b ", Some("# c")));
+        assert_eq!(split_comment("a: 'b # c'"), ("a: 'b # c'", None));
+        assert_eq!(split_comment("a: \"b \\\" # c\" # d"), ("a: \"b \\\" # c\" ", Some("# d")));
+        assert_eq!(split_comment("url: http://x/#anchor"), ("url: http://x/#anchor", None));
+        assert_eq!(split_comment("a: it's # c"), ("a: it's ", Some("# c")));
+    }
+
+    #[test]
+    fn test_split_key() {
+        assert_eq!(split_key("title: Pets"), Some(("title".to_string(), "Pets")));
+        assert_eq!(split_key("'200':"), Some(("200".to_string(), "")));
+        assert_eq!(split_key("\"a: b\": c"), Some(("a: b".to_string(), "c")));
+        assert_eq!(split_key("url: http://x"), Some(("url".to_string(), "http://x")));
+        assert_eq!(split_key("http://x"), None);
+        assert_eq!(split_key("{a: b}"), None);
+    }
+}
@@ -14,7 +14,12 @@
 
 //! Context management for document traversal.
 
+use crate::codes::ErrorCode;
+use crate::error::{CompilerError, ErrorGroup, Parsed, Result, Severity};
 use crate::extensions::ExtensionHandler;
+use crate::intern::Name;
+use crate::options::ParseOptions;
+use parking_lot::Mutex;
 use std::sync::Arc;
 use serde_yaml::Value as Yaml;
 
@@ -24,70 +29,97 @@ pub struct Context {
     /// Parent context in the traversal hierarchy.
     pub parent: Option<Arc<Context>>,
     /// Name of the current element being processed.
-    pub name: String,
+    pub name: Name,
     /// Line number in the source document (if available).
     pub line: Option<usize>,
     /// Column number in the source document (if available).
     pub column: Option<usize>,
     /// Extension handlers for processing vendor extensions.
     pub extension_handlers: Option<Arc<Vec<ExtensionHandler>>>,
+    /// Number of ancestors of this context (zero for a root context).
+    pub depth: usize,
+    /// Options for the parse, shared by all contexts in the traversal.
+    pub options: Arc<ParseOptions>,
+    /// Non-fatal diagnostics collected during the traversal.
+    pub diagnostics: Arc<Mutex<Vec<CompilerError>>>,
 }
 
 impl Context {
     /// Creates a new Context with extension handlers.
     pub fn new_with_extensions(
-        name: impl Into<String>,
+        name: impl Into<Name>,
         line: Option<usize>,
         column: Option<usize>,
         parent: Option<Arc<Context>>,
         extension_handlers: Option<Arc<Vec<ExtensionHandler>>>,
     ) -> Self {
+        let depth = parent.as_ref().map_or(0, |p| p.depth + 1);
+        let (options, diagnostics) = inherited_state(parent.as_deref());
         Context {
             parent,
             name: name.into(),
             line,
             column,
             extension_handlers,
+            depth,
+            options,
+            diagnostics,
         }
     }
 
-    /// Creates a new Context, inheriting extension handlers from the parent.
+    /// Creates a new Context, inheriting extension handlers and options from the parent.
     pub fn new(
-        name: impl Into<String>,
+        name: impl Into<Name>,
         line: Option<usize>,
         column: Option<usize>,
         parent: Option<Arc<Context>>,
     ) -> Self {
         let extension_handlers = parent.as_ref().and_then(|p| p.extension_handlers.clone());
+        let depth = parent.as_ref().map_or(0, |p| p.depth + 1);
+        let (options, diagnostics) = inherited_state(parent.as_deref());
         Context {
             parent,
             name: name.into(),
             line,
             column,
             extension_handlers,
+            depth,
+            options,
+            diagnostics,
         }
     }
 
     /// Creates a new root Context.
-    pub fn root(name: impl Into<String>) -> Self {
+    pub fn root(name: impl Into<Name>) -> Self {
+        Context::root_with_options(name, ParseOptions::default())
+    }
+
+    /// Creates a new root Context with the given parse options, which also
+    /// supply its extension handlers.
+    pub fn root_with_options(name: impl Into<Name>, options: ParseOptions) -> Self {
+        let handlers = &options.extension_handlers;
+        let extension_handlers = (!handlers.is_empty()).then(|| Arc::new(handlers.clone()));
         Context {
             parent: None,
             name: name.into(),
             line: None,
             column: None,
-            extension_handlers: None,
+            extension_handlers,
+            depth: 0,
+            options: Arc::new(options),
+            diagnostics: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
     /// Creates a child Context with the given name.
-    pub fn child(self: &Arc<Self>, name: impl Into<String>) -> Self {
+    pub fn child(self: &Arc<Self>, name: impl Into<Name>) -> Self {
         Context::new(name, None, None, Some(Arc::clone(self)))
     }
 
     /// Creates a child Context with position information.
     pub fn child_with_position(
         self: &Arc<Self>,
-        name: impl Into<String>,
+        name: impl Into<Name>,
         line: usize,
         column: usize,
     ) -> Self {
@@ -98,8 +130,72 @@ impl Context {
     pub fn description(&self) -> String {
         match &self.parent {
             Some(parent) => format!("{}.{}", parent.description(), self.name),
-            None => self.name.clone(),
+            None => self.name.to_string(),
+        }
+    }
+
+    /// Reports a recoverable problem at this context.
+    ///
+    /// In lenient mode the problem is recorded as a warning and Ok is returned so
+    /// that parsing can continue; otherwise the problem is returned as an error.
+    pub fn report_recoverable(&self, code: ErrorCode, message: impl Into<String>) -> Result<()> {
+        let error = CompilerError::new_with_code(self, code, message);
+        if self.options.lenient {
+            self.diagnostics.lock().push(error.with_severity(Severity::Warning));
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Returns true if a parser should stop collecting errors.
+    ///
+    /// This is the case in fail-fast mode once `errors` contains an error, or
+    /// once `errors` exceeds the configured maximum; the extra entry lets
+    /// [`finish`](Self::finish) report that more were found. Warnings alone
+    /// never stop a fail-fast parse.
+    pub fn should_stop(&self, errors: &[CompilerError]) -> bool {
+        if let Some(max) = self.options.max_errors {
+            if errors.len() > max {
+                return true;
+            }
         }
+        self.options.fail_fast && errors.iter().any(CompilerError::is_error)
+    }
+
+    /// Completes a parse started at this (root) context.
+    ///
+    /// Collected diagnostics are attached to the result: as warnings on success,
+    /// or appended to the error group on failure. Both are capped at the
+    /// configured maximum number of errors.
+    pub fn finish<T>(
+        &self,
+        result: std::result::Result<T, ErrorGroup>,
+    ) -> std::result::Result<Parsed<T>, ErrorGroup> {
+        let diagnostics = self.take_diagnostics();
+        let cap = |mut group: ErrorGroup| {
+            if let Some(max) = self.options.max_errors {
+                group.truncate(max);
+            }
+            group
+        };
+        match result {
+            Ok(value) => Ok(Parsed {
+                value,
+                warnings: cap(ErrorGroup::new(diagnostics)),
+                raw_info: None,
+                comments: None,
+            }),
+            Err(mut group) => {
+                group.errors.extend(diagnostics);
+                Err(cap(group))
+            }
+        }
+    }
+
+    /// Removes and returns the diagnostics collected so far.
+    pub fn take_diagnostics(&self) -> Vec<CompilerError> {
+        std::mem::take(&mut *self.diagnostics.lock())
     }
 
     /// Returns the location description with line and column if available.
@@ -113,6 +209,20 @@ impl Context {
     }
 }
 
+/// Returns the options and diagnostics sink a new context shares with its parent.
+#[allow(clippy::type_complexity)]
+fn inherited_state(
+    parent: Option<&Context>,
+) -> (Arc<ParseOptions>, Arc<Mutex<Vec<CompilerError>>>) {
+    match parent {
+        Some(p) => (Arc::clone(&p.options), Arc::clone(&p.diagnostics)),
+        None => (
+            Arc::new(ParseOptions::default()),
+            Arc::new(Mutex::new(Vec::new())),
+        ),
+    }
+}
+
 /// Extracts line and column from a serde_yaml node if available.
 /// Note: serde_yaml doesn't directly provide line/column info in the same way as Go's yaml.v3,
 /// so this function is a placeholder for future enhancement.
@@ -136,6 +246,68 @@ mod tests {
 
         let grandchild = child.child("grandchild");
         assert_eq!(grandchild.description(), "root.child.grandchild");
+        assert_eq!(grandchild.depth, 2);
+    }
+
+    #[test]
+    fn test_report_recoverable() {
+        let strict = Arc::new(Context::root("$"));
+        let child = strict.child("tags");
+        assert!(child.report_recoverable(ErrorCode::InvalidType, "tags must be an array").is_err());
+        assert!(strict.take_diagnostics().is_empty());
+
+        let lenient = Arc::new(Context::root_with_options("$", ParseOptions::lenient()));
+        let child = lenient.child("tags");
+        assert!(child.options.lenient);
+        assert!(child.report_recoverable(ErrorCode::InvalidType, "tags must be an array").is_ok());
+        let diagnostics = lenient.take_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity(), Severity::Warning);
+        assert_eq!(diagnostics[0].to_string(), "warning: $.tags tags must be an array");
+        assert!(lenient.take_diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_should_stop() {
+        let ctx = Context::new("test", None, None, None);
+        let warning = vec![CompilerError::warning(&ctx, "unusual")];
+        let error = vec![CompilerError::new(&ctx, "invalid")];
+        assert!(!ctx.should_stop(&error));
+
+        let fail_fast = Context::root_with_options("$", ParseOptions::fail_fast());
+        assert!(!fail_fast.should_stop(&[]));
+        assert!(!fail_fast.should_stop(&warning));
+        assert!(fail_fast.should_stop(&error));
+    }
+
+    #[test]
+    fn test_max_errors() {
+        let options = ParseOptions::default().with_max_errors(2);
+        let ctx = Context::root_with_options("$", options);
+        let errors: Vec<CompilerError> =
+            (0..5).map(|i| CompilerError::new(&ctx, format!("error {}", i))).collect();
+        assert!(!ctx.should_stop(&errors[..2]));
+        assert!(ctx.should_stop(&errors[..3]));
+
+        let group = ctx.finish::<()>(Err(ErrorGroup::new(errors))).unwrap_err();
+        assert_eq!(group.len(), 3);
+        assert_eq!(group.errors[2].to_string(), "$ and 3 more errors");
+    }
+
+    #[test]
+    fn test_extension_handlers_from_options() {
+        assert!(Context::root("$").extension_handlers.is_none());
+        let options = ParseOptions::default().with_extension_handler(ExtensionHandler::new("gnostic-x-sample"));
+        let root = Arc::new(Context::root_with_options("$", options));
+        let child = root.child("info");
+        assert_eq!(child.extension_handlers.unwrap()[0].name, "gnostic-x-sample");
+
+        let options = ParseOptions::default()
+            .with_extension_handler(ExtensionHandler::new("gnostic-x-google").with_prefix("x-google-"))
+            .with_extension_handler(ExtensionHandler::new("gnostic-x-sample"))
+            .with_extension_handler_enabled("gnostic-x-google", false);
+        assert!(!options.extension_handlers[0].enabled);
+        assert!(options.extension_handlers[1].enabled);
     }
 
     #[test]
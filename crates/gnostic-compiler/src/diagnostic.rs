@@ -0,0 +1,227 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! miette integration for compiler errors (requires the `miette` feature).
+//!
+//! CompilerError and ErrorGroup implement [`miette::Diagnostic`] directly. To
+//! get labeled source spans, attach the document text with
+//! [`ErrorGroup::with_source_code`].
+
+use crate::error::{CompilerError, ErrorGroup, Severity};
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode, SourceSpan};
+use std::fmt;
+
+impl From<Severity> for miette::Severity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Error => miette::Severity::Error,
+            Severity::Warning => miette::Severity::Warning,
+            Severity::Info => miette::Severity::Advice,
+        }
+    }
+}
+
+impl Diagnostic for CompilerError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(self.code()))
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        Some(CompilerError::severity(self).into())
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        match self {
+            CompilerError::UnknownKey {
+                suggestion: Some(suggestion),
+                ..
+            } => Some(Box::new(format!("did you mean {}?", suggestion))),
+            _ => None,
+        }
+    }
+}
+
+impl Diagnostic for ErrorGroup {
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        Some(Box::new(self.errors.iter().map(|e| e as &dyn Diagnostic)))
+    }
+}
+
+impl ErrorGroup {
+    /// Attaches the source document so that located errors render with labeled spans.
+    pub fn with_source_code(
+        self,
+        name: impl AsRef<str>,
+        source: impl Into<String>,
+    ) -> SourcedErrorGroup {
+        let source = source.into();
+        let errors = self
+            .errors
+            .into_iter()
+            .map(|error| {
+                let span = error
+                    .location()
+                    .and_then(|(line, column)| span_for_position(&source, line, column));
+                SourcedError { error, span }
+            })
+            .collect();
+        SourcedErrorGroup {
+            errors,
+            source: NamedSource::new(name, source),
+        }
+    }
+}
+
+/// An ErrorGroup together with the document it was reported against.
+#[derive(Debug)]
+pub struct SourcedErrorGroup {
+    errors: Vec<SourcedError>,
+    source: NamedSource<String>,
+}
+
+impl SourcedErrorGroup {
+    /// Returns the errors with their source spans.
+    pub fn errors(&self) -> &[SourcedError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for SourcedErrorGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.errors.len();
+        write!(
+            f,
+            "{} {} in {}",
+            count,
+            if count == 1 { "problem" } else { "problems" },
+            self.source.name()
+        )
+    }
+}
+
+impl std::error::Error for SourcedErrorGroup {}
+
+impl Diagnostic for SourcedErrorGroup {
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.source)
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        Some(Box::new(self.errors.iter().map(|e| e as &dyn Diagnostic)))
+    }
+}
+
+/// A CompilerError with the span of source text it refers to.
+#[derive(Debug)]
+pub struct SourcedError {
+    /// The underlying error.
+    pub error: CompilerError,
+    /// The byte span of the offending token, if the error has a position in the source.
+    pub span: Option<SourceSpan>,
+}
+
+impl fmt::Display for SourcedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for SourcedError {}
+
+impl Diagnostic for SourcedError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Diagnostic::code(&self.error)
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        Diagnostic::severity(&self.error)
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Diagnostic::help(&self.error)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let span = self.span?;
+        let label = self.error.message().into_owned();
+        Some(Box::new(std::iter::once(LabeledSpan::new_primary_with_span(
+            Some(label),
+            span,
+        ))))
+    }
+}
+
+/// Returns the span of the token starting at a 1-based line and column.
+///
+/// The token extends to the next whitespace or YAML flow indicator.
+fn span_for_position(source: &str, line: usize, column: usize) -> Option<SourceSpan> {
+    let mut line_start = 0;
+    for _ in 1..line {
+        line_start += source[line_start..].find('\n')? + 1;
+    }
+    let text = source[line_start..].lines().next().unwrap_or("");
+    let start = text
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(text.len(), |(i, _)| i);
+    let len = text[start..]
+        .find(|c: char| c.is_whitespace() || matches!(c, ':' | ',' | '[' | ']' | '{' | '}'))
+        .unwrap_or(text.len() - start)
+        .max(1)
+        .min(text.len() - start);
+    Some(SourceSpan::new((line_start + start).into(), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Context;
+
+    #[test]
+    fn test_span_for_position() {
+        let source = "openapi: 3.0.0\ninfo:\n  titel: Pets\n";
+        let span = span_for_position(source, 3, 3).unwrap();
+        assert_eq!(&source[span.offset()..span.offset() + span.len()], "titel");
+
+        let span = span_for_position(source, 1, 10).unwrap();
+        assert_eq!(&source[span.offset()..span.offset() + span.len()], "3.0.0");
+
+        assert!(span_for_position(source, 10, 1).is_none());
+    }
+
+    #[test]
+    fn test_diagnostic_for_errors() {
+        let source = "openapi: 3.0.0\ninfo:\n  titel: Pets\n";
+        let ctx = Context::new("$.info", Some(3), Some(3), None);
+        let group = ErrorGroup::new(vec![
+            CompilerError::unknown_key(&ctx, "titel", &["title", "version"]),
+            CompilerError::io("not found"),
+        ]);
+
+        let first = &group.errors[0];
+        assert_eq!(Diagnostic::code(first).unwrap().to_string(), "GNO0012");
+        assert_eq!(Diagnostic::help(first).unwrap().to_string(), "did you mean title?");
+        assert_eq!(Diagnostic::severity(first), Some(miette::Severity::Error));
+        assert_eq!(group.related().unwrap().count(), 2);
+
+        let sourced = group.with_source_code("openapi.yaml", source);
+        assert_eq!(sourced.to_string(), "2 problems in openapi.yaml");
+        assert!(sourced.source_code().is_some());
+        let labels: Vec<LabeledSpan> = sourced.errors()[0].labels().unwrap().collect();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].offset(), source.find("titel").unwrap());
+        assert_eq!(labels[0].len(), 5);
+        assert!(sourced.errors()[1].labels().is_none());
+    }
+}
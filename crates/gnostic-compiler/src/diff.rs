@@ -0,0 +1,830 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Semantic differences between two versions of a document.
+//!
+//! [`diff_documents`] compares the YAML of two OpenAPI v2 or v3 documents
+//! structurally rather than as text: paths and operations are matched by
+//! their keys, parameters by their name and location, and schemas by their
+//! shape, so that reordering a document changes nothing. Each difference is
+//! a [`Change`] located by a JSON pointer, into the new document or, for
+//! removals, into the old one.
+//!
+//! Changes are classified by their effect on existing clients: those that
+//! break them have [`Severity::Error`], those that may break some, such as
+//! removing a parameter that clients still send, have [`Severity::Warning`],
+//! and compatible ones have [`Severity::Info`]. Whether a change to a schema
+//! breaks clients depends on the [`Direction`] its values travel in, so that
+//! narrowing an enum breaks requests but not responses.
+
+use crate::checks::local_target;
+use crate::helpers::{escape_json_pointer_segment, string_for_scalar_node, yaml_to_json};
+use crate::lint::METHODS;
+use crate::report::{Finding, ValidationReport};
+use crate::Severity;
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as Yaml;
+use std::fmt;
+
+/// The kind of a difference between two documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeKind {
+    PathAdded,
+    PathRemoved,
+    OperationAdded,
+    OperationRemoved,
+    ParameterAdded,
+    ParameterRemoved,
+    /// A parameter or request body became required or optional.
+    RequiredChanged,
+    RequestBodyAdded,
+    RequestBodyRemoved,
+    MediaTypeAdded,
+    MediaTypeRemoved,
+    ResponseAdded,
+    ResponseRemoved,
+    ComponentAdded,
+    ComponentRemoved,
+    /// A parameter, media type, response or schema gained a schema.
+    SchemaAdded,
+    /// A parameter, media type, response or schema lost its schema.
+    SchemaRemoved,
+    /// A schema refers to a different schema.
+    ReferenceChanged,
+    TypeChanged,
+    FormatChanged,
+    EnumValueAdded,
+    EnumValueRemoved,
+    PropertyAdded,
+    PropertyRemoved,
+    /// A property of an object schema became required.
+    RequiredPropertyAdded,
+    /// A property of an object schema became optional.
+    RequiredPropertyRemoved,
+}
+
+impl ChangeKind {
+    /// Returns the name of the kind, such as "path-added".
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::PathAdded => "path-added",
+            ChangeKind::PathRemoved => "path-removed",
+            ChangeKind::OperationAdded => "operation-added",
+            ChangeKind::OperationRemoved => "operation-removed",
+            ChangeKind::ParameterAdded => "parameter-added",
+            ChangeKind::ParameterRemoved => "parameter-removed",
+            ChangeKind::RequiredChanged => "required-changed",
+            ChangeKind::RequestBodyAdded => "request-body-added",
+            ChangeKind::RequestBodyRemoved => "request-body-removed",
+            ChangeKind::MediaTypeAdded => "media-type-added",
+            ChangeKind::MediaTypeRemoved => "media-type-removed",
+            ChangeKind::ResponseAdded => "response-added",
+            ChangeKind::ResponseRemoved => "response-removed",
+            ChangeKind::ComponentAdded => "component-added",
+            ChangeKind::ComponentRemoved => "component-removed",
+            ChangeKind::SchemaAdded => "schema-added",
+            ChangeKind::SchemaRemoved => "schema-removed",
+            ChangeKind::ReferenceChanged => "reference-changed",
+            ChangeKind::TypeChanged => "type-changed",
+            ChangeKind::FormatChanged => "format-changed",
+            ChangeKind::EnumValueAdded => "enum-value-added",
+            ChangeKind::EnumValueRemoved => "enum-value-removed",
+            ChangeKind::PropertyAdded => "property-added",
+            ChangeKind::PropertyRemoved => "property-removed",
+            ChangeKind::RequiredPropertyAdded => "required-property-added",
+            ChangeKind::RequiredPropertyRemoved => "required-property-removed",
+        }
+    }
+}
+
+impl ChangeKind {
+    /// Returns the effect of a change of this kind on existing clients, for
+    /// values sent in `direction`. Adding a required parameter or request
+    /// body, or making one required, breaks clients regardless.
+    pub fn severity(&self, direction: Direction) -> Severity {
+        use ChangeKind::*;
+        let (request, response) = match self {
+            PathRemoved | OperationRemoved | MediaTypeRemoved | ReferenceChanged | TypeChanged => {
+                (Severity::Error, Severity::Error)
+            }
+            ParameterRemoved | RequestBodyRemoved | ResponseRemoved | ComponentRemoved | SchemaRemoved
+            | FormatChanged => (Severity::Warning, Severity::Warning),
+            RequiredChanged => (Severity::Error, Severity::Info),
+            EnumValueRemoved => (Severity::Error, Severity::Info),
+            EnumValueAdded => (Severity::Info, Severity::Warning),
+            PropertyRemoved => (Severity::Warning, Severity::Error),
+            RequiredPropertyAdded => (Severity::Error, Severity::Info),
+            RequiredPropertyRemoved => (Severity::Info, Severity::Error),
+            PathAdded | OperationAdded | ParameterAdded | RequestBodyAdded | MediaTypeAdded | ResponseAdded
+            | ComponentAdded | SchemaAdded | PropertyAdded => (Severity::Info, Severity::Info),
+        };
+        match direction {
+            Direction::Request => request,
+            Direction::Response => response,
+            // Errors order first, so the lesser severity is the graver one.
+            Direction::Both => request.min(response),
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The direction that the values of a changed node travel in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Values that clients send, such as parameters and request bodies.
+    Request,
+    /// Values that clients receive, such as responses.
+    Response,
+    /// Values that travel either way, such as component schemas, and paths
+    /// and operations themselves.
+    Both,
+}
+
+/// A difference between two documents.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub direction: Direction,
+    /// The effect of the change on existing clients.
+    pub severity: Severity,
+    /// JSON pointer to the changed node in the new document or, if it was
+    /// removed, in the old one, such as "/paths/~1pets/get".
+    pub pointer: String,
+    /// The old value of a changed or removed value, such as a type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<JsonValue>,
+    /// The new value of a changed or added value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<JsonValue>,
+}
+
+impl Change {
+    /// Returns whether the change breaks existing clients.
+    pub fn is_breaking(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Describes the change, such as `type changed from "integer" to "string"`.
+    pub fn message(&self) -> String {
+        let description = self.kind.as_str().replace('-', " ");
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => format!("{} from {} to {}", description, old, new),
+            (Some(value), None) | (None, Some(value)) => format!("{} {}", description, value),
+            (None, None) => description,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} #{}", self.kind, self.pointer)?;
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, " {} -> {}", old, new),
+            (Some(value), None) | (None, Some(value)) => write!(f, " {}", value),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// The differences between two documents, in document order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct Changeset {
+    pub changes: Vec<Change>,
+}
+
+impl Changeset {
+    /// Returns true if the documents are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the changes of one kind.
+    pub fn of_kind(&self, kind: ChangeKind) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(move |change| change.kind == kind)
+    }
+
+    /// Returns the changes that break existing clients.
+    pub fn breaking(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.is_breaking())
+    }
+
+    /// Returns true if any change breaks existing clients.
+    pub fn has_breaking_changes(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+
+    /// Returns the changes as a report whose findings are identified by the
+    /// kinds of the changes, so that CI can fail on reports with errors.
+    pub fn to_report(&self) -> ValidationReport {
+        let findings = self.changes.iter().map(|change| Finding {
+            rule: change.kind.as_str().to_string(),
+            severity: change.severity,
+            pointer: change.pointer.clone(),
+            line: None,
+            column: None,
+            message: change.message(),
+            fix: None,
+        });
+        ValidationReport { findings: findings.collect() }
+    }
+
+    /// Returns the changes at or below the node at `pointer`.
+    pub fn under<'a>(&'a self, pointer: &'a str) -> impl Iterator<Item = &'a Change> {
+        self.changes.iter().filter(move |change| {
+            change.pointer.strip_prefix(pointer).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+impl fmt::Display for Changeset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the YAML of two OpenAPI v2 or v3 documents.
+pub fn diff_documents(old: &Yaml, new: &Yaml) -> Changeset {
+    let mut differ = Differ { old, new, direction: Direction::Both, changes: Vec::new() };
+    let (removed, added) = (ChangeKind::PathRemoved, ChangeKind::PathAdded);
+    differ.compare_map(&old["paths"], &new["paths"], "/paths", removed, added, Differ::compare_path_item);
+
+    let sections: &[(&str, Direction, Compare)] = if old["components"].is_mapping() || new["components"].is_mapping() {
+        &[
+            ("/components/schemas", Direction::Both, Differ::compare_schema),
+            ("/components/parameters", Direction::Request, Differ::compare_parameter),
+            ("/components/requestBodies", Direction::Request, Differ::compare_request_body),
+            ("/components/responses", Direction::Response, Differ::compare_response),
+            ("/components/headers", Direction::Both, Differ::compare_parameter),
+            ("/components/examples", Direction::Both, Differ::ignore),
+            ("/components/links", Direction::Both, Differ::ignore),
+            ("/components/callbacks", Direction::Both, Differ::ignore),
+            ("/components/securitySchemes", Direction::Both, Differ::ignore),
+        ]
+    } else {
+        &[
+            ("/definitions", Direction::Both, Differ::compare_schema),
+            ("/parameters", Direction::Request, Differ::compare_parameter),
+            ("/responses", Direction::Response, Differ::compare_response),
+            ("/securityDefinitions", Direction::Both, Differ::ignore),
+        ]
+    };
+    let (removed, added) = (ChangeKind::ComponentRemoved, ChangeKind::ComponentAdded);
+    for (pointer, direction, compare) in sections {
+        differ.direction = *direction;
+        differ.compare_map(node_at(old, pointer), node_at(new, pointer), pointer, removed, added, *compare);
+    }
+    Changeset { changes: differ.changes }
+}
+
+/// Compares two versions of a node located by a pointer.
+type Compare<'a> = fn(&mut Differ<'a>, &'a Yaml, &'a Yaml, &str);
+
+struct Differ<'a> {
+    old: &'a Yaml,
+    new: &'a Yaml,
+    /// The direction of the values of the nodes being compared.
+    direction: Direction,
+    changes: Vec<Change>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(&mut self, kind: ChangeKind, pointer: &str, old: Option<&Yaml>, new: Option<&Yaml>) {
+        self.record(kind, kind.severity(self.direction), pointer, old, new);
+    }
+
+    fn record(&mut self, kind: ChangeKind, severity: Severity, pointer: &str, old: Option<&Yaml>, new: Option<&Yaml>) {
+        self.changes.push(Change {
+            kind,
+            direction: self.direction,
+            severity,
+            pointer: pointer.to_string(),
+            old: old.map(yaml_to_json),
+            new: new.map(yaml_to_json),
+        });
+    }
+
+    /// Compares the entries of two mappings by key, reporting removed
+    /// entries, then added ones and the changes of common ones.
+    fn compare_map(
+        &mut self,
+        old: &'a Yaml,
+        new: &'a Yaml,
+        pointer: &str,
+        removed: ChangeKind,
+        added: ChangeKind,
+        compare: Compare<'a>,
+    ) {
+        let (old_entries, new_entries) = (entries(old), entries(new));
+        for (key, _) in &old_entries {
+            if !new_entries.iter().any(|(new_key, _)| new_key == key) {
+                self.push(removed, &child(pointer, key), None, None);
+            }
+        }
+        for (key, new_value) in &new_entries {
+            let pointer = child(pointer, key);
+            match old_entries.iter().find(|(old_key, _)| old_key == key) {
+                Some((_, old_value)) => compare(self, old_value, new_value, &pointer),
+                None => self.push(added, &pointer, None, None),
+            }
+        }
+    }
+
+    fn ignore(&mut self, _old: &'a Yaml, _new: &'a Yaml, _pointer: &str) {}
+
+    fn compare_path_item(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        self.direction = Direction::Request;
+        self.compare_parameters(&old["parameters"], &new["parameters"], &child(pointer, "parameters"));
+        self.direction = Direction::Both;
+        for method in METHODS {
+            let pointer = child(pointer, method);
+            match (old[*method].is_mapping(), new[*method].is_mapping()) {
+                (true, true) => self.compare_operation(&old[*method], &new[*method], &pointer),
+                (true, false) => self.push(ChangeKind::OperationRemoved, &pointer, None, None),
+                (false, true) => self.push(ChangeKind::OperationAdded, &pointer, None, None),
+                (false, false) => {}
+            }
+        }
+    }
+
+    fn compare_operation(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        self.direction = Direction::Request;
+        self.compare_parameters(&old["parameters"], &new["parameters"], &child(pointer, "parameters"));
+        let body_pointer = child(pointer, "requestBody");
+        match (old["requestBody"].is_mapping(), new["requestBody"].is_mapping()) {
+            (true, true) => self.compare_request_body(&old["requestBody"], &new["requestBody"], &body_pointer),
+            (true, false) => self.push(ChangeKind::RequestBodyRemoved, &body_pointer, None, None),
+            (false, true) => {
+                let required = local_target(self.new, &new["requestBody"]).is_some_and(is_required);
+                self.push_input(ChangeKind::RequestBodyAdded, &body_pointer, None, required);
+            }
+            (false, false) => {}
+        }
+        self.direction = Direction::Response;
+        self.compare_map(
+            &old["responses"],
+            &new["responses"],
+            &child(pointer, "responses"),
+            ChangeKind::ResponseRemoved,
+            ChangeKind::ResponseAdded,
+            Differ::compare_response,
+        );
+        self.direction = Direction::Both;
+    }
+
+    /// Records an added parameter or request body, which breaks clients if
+    /// it is required.
+    fn push_input(&mut self, kind: ChangeKind, pointer: &str, name: Option<&Yaml>, required: bool) {
+        let severity = if required { Severity::Error } else { kind.severity(self.direction) };
+        self.record(kind, severity, pointer, None, name);
+    }
+
+    /// Compares two lists of parameters, matching them by name and location.
+    fn compare_parameters(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        let identify = |root: &'a Yaml, parameters: &'a Yaml| -> Vec<(usize, (&'a Yaml, &'a Yaml), &'a Yaml)> {
+            let parameters = parameters.as_sequence().into_iter().flatten().enumerate();
+            parameters
+                .filter_map(|(i, parameter)| {
+                    let parameter = local_target(root, parameter)?;
+                    Some((i, (&parameter["name"], &parameter["in"]), parameter))
+                })
+                .collect()
+        };
+        let (old_parameters, new_parameters) = (identify(self.old, old), identify(self.new, new));
+        for (i, key, _) in &old_parameters {
+            if !new_parameters.iter().any(|(_, new_key, _)| new_key == key) {
+                self.push(ChangeKind::ParameterRemoved, &child(pointer, &i.to_string()), None, Some(key.0));
+            }
+        }
+        for (i, key, new_parameter) in &new_parameters {
+            let pointer = child(pointer, &i.to_string());
+            match old_parameters.iter().find(|(_, old_key, _)| old_key == key) {
+                Some((_, _, old_parameter)) => self.compare_parameter(old_parameter, new_parameter, &pointer),
+                None => {
+                    let required = is_required(new_parameter);
+                    self.push_input(ChangeKind::ParameterAdded, &pointer, Some(key.0), required);
+                }
+            }
+        }
+    }
+
+    /// Compares two parameters or headers, which are described by a schema
+    /// in OpenAPI v3 and by a type and format of their own in v2.
+    fn compare_parameter(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        self.compare_required(old, new, pointer);
+        if old["schema"].is_mapping() || new["schema"].is_mapping() {
+            self.compare_schema(&old["schema"], &new["schema"], &child(pointer, "schema"));
+        } else {
+            self.compare_schema(old, new, pointer);
+        }
+    }
+
+    fn compare_request_body(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        let (Some(old), Some(new)) = (local_target(self.old, old), local_target(self.new, new)) else {
+            return;
+        };
+        self.compare_required(old, new, pointer);
+        self.compare_content(old, new, pointer);
+    }
+
+    fn compare_response(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        let (Some(old), Some(new)) = (local_target(self.old, old), local_target(self.new, new)) else {
+            return;
+        };
+        if old["content"].is_mapping() || new["content"].is_mapping() {
+            self.compare_content(old, new, pointer);
+        } else {
+            self.compare_schema(&old["schema"], &new["schema"], &child(pointer, "schema"));
+        }
+    }
+
+    fn compare_content(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        self.compare_map(
+            &old["content"],
+            &new["content"],
+            &child(pointer, "content"),
+            ChangeKind::MediaTypeRemoved,
+            ChangeKind::MediaTypeAdded,
+            Differ::compare_media_type,
+        );
+    }
+
+    fn compare_media_type(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        self.compare_schema(&old["schema"], &new["schema"], &child(pointer, "schema"));
+    }
+
+    fn compare_required(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        let (was_required, required) = (is_required(old), is_required(new));
+        if was_required != required {
+            // Only making an input required breaks clients.
+            let severity = if required { ChangeKind::RequiredChanged.severity(self.direction) } else { Severity::Info };
+            let (old, new) = (Yaml::Bool(was_required), Yaml::Bool(required));
+            self.record(ChangeKind::RequiredChanged, severity, &child(pointer, "required"), Some(&old), Some(&new));
+        }
+    }
+
+    /// Compares the shapes of two schemas. References are compared as
+    /// written; the schemas they refer to are compared as components.
+    fn compare_schema(&mut self, old: &'a Yaml, new: &'a Yaml, pointer: &str) {
+        match (old.is_mapping(), new.is_mapping()) {
+            (true, true) => {}
+            (true, false) => return self.push(ChangeKind::SchemaRemoved, pointer, None, None),
+            (false, true) => return self.push(ChangeKind::SchemaAdded, pointer, None, None),
+            (false, false) => return,
+        }
+        if old["$ref"].is_string() || new["$ref"].is_string() {
+            if old["$ref"] != new["$ref"] {
+                let pointer = child(pointer, "$ref");
+                self.push(ChangeKind::ReferenceChanged, &pointer, Some(&old["$ref"]), Some(&new["$ref"]));
+            }
+            return;
+        }
+        for (key, kind) in [("type", ChangeKind::TypeChanged), ("format", ChangeKind::FormatChanged)] {
+            if old[key] != new[key] {
+                self.push(kind, &child(pointer, key), Some(&old[key]), Some(&new[key]));
+            }
+        }
+        self.compare_values(
+            &old["enum"],
+            &new["enum"],
+            &child(pointer, "enum"),
+            ChangeKind::EnumValueRemoved,
+            ChangeKind::EnumValueAdded,
+        );
+        self.compare_map(
+            &old["properties"],
+            &new["properties"],
+            &child(pointer, "properties"),
+            ChangeKind::PropertyRemoved,
+            ChangeKind::PropertyAdded,
+            Differ::compare_schema,
+        );
+        if old["required"].is_sequence() || new["required"].is_sequence() {
+            self.compare_values(
+                &old["required"],
+                &new["required"],
+                &child(pointer, "required"),
+                ChangeKind::RequiredPropertyRemoved,
+                ChangeKind::RequiredPropertyAdded,
+            );
+        }
+        self.compare_schema(&old["items"], &new["items"], &child(pointer, "items"));
+        let additional_properties = child(pointer, "additionalProperties");
+        self.compare_schema(&old["additionalProperties"], &new["additionalProperties"], &additional_properties);
+        self.compare_schema(&old["not"], &new["not"], &child(pointer, "not"));
+        for key in ["allOf", "anyOf", "oneOf"] {
+            let (old_members, new_members) = (members(&old[key]), members(&new[key]));
+            for i in 0..old_members.len().max(new_members.len()) {
+                let old_member = old_members.get(i).unwrap_or(&Yaml::Null);
+                let new_member = new_members.get(i).unwrap_or(&Yaml::Null);
+                self.compare_schema(old_member, new_member, &child(&child(pointer, key), &i.to_string()));
+            }
+        }
+    }
+
+    /// Compares two lists of values as sets, such as the values of enums.
+    fn compare_values(
+        &mut self,
+        old: &'a Yaml,
+        new: &'a Yaml,
+        pointer: &str,
+        removed: ChangeKind,
+        added: ChangeKind,
+    ) {
+        let (old_values, new_values) = (members(old), members(new));
+        for value in old_values.iter().filter(|value| !new_values.contains(value)) {
+            self.push(removed, pointer, Some(value), None);
+        }
+        for (i, value) in new_values.iter().enumerate().filter(|(_, value)| !old_values.contains(value)) {
+            self.push(added, &child(pointer, &i.to_string()), None, Some(value));
+        }
+    }
+}
+
+/// Returns whether a parameter, header or request body is required.
+fn is_required(node: &Yaml) -> bool {
+    node["required"].as_bool().unwrap_or_default()
+}
+
+fn members(node: &Yaml) -> &[Yaml] {
+    node.as_sequence().map_or(&[], Vec::as_slice)
+}
+
+/// Returns the entries of a mapping with their keys as strings, such as
+/// response codes written as numbers.
+fn entries(node: &Yaml) -> Vec<(String, &Yaml)> {
+    let Yaml::Mapping(map) = node else {
+        return Vec::new();
+    };
+    map.iter().filter_map(|(key, value)| Some((string_for_scalar_node(key)?, value))).collect()
+}
+
+fn child(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, escape_json_pointer_segment(key))
+}
+
+/// Returns the node at a pointer whose segments need no escaping.
+fn node_at<'a>(node: &'a Yaml, pointer: &str) -> &'a Yaml {
+    pointer.split('/').skip(1).fold(node, |node, key| &node[key])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> Vec<String> {
+        let (old, new) = (serde_yaml::from_str(old).unwrap(), serde_yaml::from_str(new).unwrap());
+        diff_documents(&old, &new).changes.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_diff_v3() {
+        let old = r##"
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, schema: {type: integer, format: int32}}
+        - {name: tag, in: query, schema: {type: string}}
+      responses:
+        200:
+          description: Pets.
+          content:
+            application/json: {schema: {type: array, items: {$ref: "#/components/schemas/Pet"}}}
+  /stores: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id]
+      properties:
+        id: {type: integer}
+        status: {type: string, enum: [available, sold]}
+    Legacy: {type: string}
+"##;
+        let new = r##"
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: tag, in: query, schema: {type: string}}
+        - {name: limit, in: query, required: true, schema: {type: integer, format: int64}}
+      responses:
+        "200":
+          description: Pets.
+          content:
+            application/json: {schema: {type: array, items: {$ref: "#/components/schemas/Pet"}}}
+        default: {description: An error.}
+    post:
+      requestBody:
+        content:
+          application/json: {schema: {$ref: "#/components/schemas/Pet"}}
+      responses: {}
+  /owners: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id: {type: string}
+        name: {type: string}
+        status: {type: string, enum: [available, pending]}
+"##;
+        assert_eq!(
+            diff(old, new),
+            [
+                "path-removed #/paths/~1stores",
+                "required-changed #/paths/~1pets/get/parameters/1/required false -> true",
+                "format-changed #/paths/~1pets/get/parameters/1/schema/format \"int32\" -> \"int64\"",
+                "response-added #/paths/~1pets/get/responses/default",
+                "operation-added #/paths/~1pets/post",
+                "path-added #/paths/~1owners",
+                "component-removed #/components/schemas/Legacy",
+                "type-changed #/components/schemas/Pet/properties/id/type \"integer\" -> \"string\"",
+                "property-added #/components/schemas/Pet/properties/name",
+                "enum-value-removed #/components/schemas/Pet/properties/status/enum \"sold\"",
+                "enum-value-added #/components/schemas/Pet/properties/status/enum/1 \"pending\"",
+                "required-property-added #/components/schemas/Pet/required/1 \"name\"",
+            ]
+        );
+        assert!(diff(new, new).is_empty());
+    }
+
+    #[test]
+    fn test_diff_v2() {
+        let old = r##"
+swagger: "2.0"
+paths:
+  /pets:
+    get:
+      parameters:
+        - $ref: "#/parameters/limit"
+        - {name: body, in: body, schema: {$ref: "#/definitions/Pet"}}
+      responses:
+        200: {description: Pets., schema: {type: array, items: {$ref: "#/definitions/Pet"}}}
+parameters:
+  limit: {name: limit, in: query, type: integer}
+definitions:
+  Pet: {type: object}
+"##;
+        let new = r##"
+swagger: "2.0"
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, type: string}
+        - {name: body, in: body, schema: {$ref: "#/definitions/Animal"}}
+        - {name: page, in: query, type: integer}
+      responses:
+        200: {description: Pets.}
+parameters:
+  limit: {name: limit, in: query, type: integer}
+definitions:
+  Pet: {type: object}
+"##;
+        assert_eq!(
+            diff(old, new),
+            [
+                "type-changed #/paths/~1pets/get/parameters/0/type \"integer\" -> \"string\"",
+                concat!(
+                    "reference-changed #/paths/~1pets/get/parameters/1/schema/$ref ",
+                    "\"#/definitions/Pet\" -> \"#/definitions/Animal\"",
+                ),
+                "parameter-added #/paths/~1pets/get/parameters/2 \"page\"",
+                "schema-removed #/paths/~1pets/get/responses/200/schema",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changeset() {
+        let old = serde_yaml::from_str("paths: {/a: {}, /b: {}}").unwrap();
+        let new = serde_yaml::from_str("paths: {/b: {get: {}}}").unwrap();
+        let changeset = diff_documents(&old, &new);
+        assert_eq!(changeset.of_kind(ChangeKind::PathRemoved).count(), 1);
+        let kinds: Vec<ChangeKind> = changeset.under("/paths/~1b").map(|change| change.kind).collect();
+        assert_eq!(kinds, [ChangeKind::OperationAdded]);
+        assert_eq!(changeset.under("/paths/~1").count(), 0);
+        assert_eq!(
+            serde_json::to_value(&changeset.changes[0]).unwrap(),
+            serde_json::json!({
+                "kind": "path-removed", "direction": "both", "severity": "error", "pointer": "/paths/~1a"
+            })
+        );
+        assert_eq!(changeset.to_string(), "path-removed #/paths/~1a\noperation-added #/paths/~1b/get");
+    }
+
+    #[test]
+    fn test_breaking_changes() {
+        let old = r##"
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: status, in: query, schema: {type: string, enum: [available, sold]}}
+      responses:
+        "200":
+          description: Pets.
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  kind: {type: string, enum: [cat, dog]}
+                  name: {type: string}
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema: {type: object, properties: {name: {type: string}}}
+      responses: {}
+"##;
+        let new = r##"
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: status, in: query, schema: {type: string, enum: [available]}}
+        - {name: page, in: query}
+        - {name: owner, in: query, required: true}
+      responses:
+        "200":
+          description: Pets.
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  kind: {type: string, enum: [cat]}
+    post:
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: {type: object, required: [name], properties: {name: {type: string}}}
+      responses: {}
+"##;
+        let (old, new) = (serde_yaml::from_str(old).unwrap(), serde_yaml::from_str(new).unwrap());
+        let changeset = diff_documents(&old, &new);
+        let severities: Vec<(ChangeKind, Severity)> =
+            changeset.changes.iter().map(|change| (change.kind, change.severity)).collect();
+        assert_eq!(
+            severities,
+            [
+                (ChangeKind::EnumValueRemoved, Severity::Error),
+                (ChangeKind::ParameterAdded, Severity::Info),
+                (ChangeKind::ParameterAdded, Severity::Error),
+                (ChangeKind::PropertyRemoved, Severity::Error),
+                (ChangeKind::EnumValueRemoved, Severity::Info),
+                (ChangeKind::RequiredChanged, Severity::Error),
+                (ChangeKind::RequiredPropertyAdded, Severity::Error),
+            ]
+        );
+        assert_eq!(changeset.changes[4].direction, Direction::Response);
+        assert!(changeset.has_breaking_changes());
+        assert_eq!(changeset.breaking().count(), 5);
+
+        let report = changeset.to_report();
+        assert!(report.has_errors());
+        assert_eq!(report.count(Severity::Info), 2);
+        assert_eq!(report.findings[0].rule, "enum-value-removed");
+        assert_eq!(report.findings[0].pointer, "/paths/~1pets/get/parameters/0/schema/enum");
+        assert_eq!(report.findings[0].message, "enum value removed \"sold\"");
+
+        // Making an input optional is compatible.
+        let reverse = diff_documents(&new, &old);
+        assert_eq!(reverse.of_kind(ChangeKind::RequiredChanged).next().unwrap().severity, Severity::Info);
+        assert!(!diff_documents(&old, &old).has_breaking_changes());
+        assert_eq!(ChangeKind::EnumValueAdded.severity(Direction::Both), Severity::Warning);
+        assert_eq!(ChangeKind::TypeChanged.severity(Direction::Request), Severity::Error);
+    }
+}
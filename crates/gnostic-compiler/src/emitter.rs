@@ -0,0 +1,592 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A YAML emitter with configurable output style.
+//!
+//! serde_yaml always writes the same style, so writers use this emitter to
+//! honor the indentation, quoting, sequence style and line width set in
+//! [`EmitOptions`]. Strings are quoted only when a plain scalar would be read
+//! back as something else (a number, boolean, null or date) or is not valid
+//! YAML; multi-line strings are written as literal block scalars.
+//!
+//! Comments set in [`EmitOptions::comments`] are written before and after the
+//! nodes whose paths they were collected at.
+
+use crate::comments::NodeComments;
+use crate::writer::EmitOptions;
+use serde_yaml::{Mapping, Value as Yaml};
+
+/// Quotes used for strings that cannot be written as plain scalars.
+///
+/// Strings with non-printable characters always use double quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuoteStyle {
+    /// 'single quotes', as written by serde_yaml.
+    #[default]
+    Single,
+    /// "double quotes".
+    Double,
+}
+
+/// Style used for sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SequenceStyle {
+    /// One "- item" line per entry.
+    #[default]
+    Block,
+    /// Sequences of scalars are written inline as "[a, b]" when they fit in
+    /// the line width; other sequences use block style.
+    Flow,
+}
+
+/// Writes a YAML node as text using the given options.
+pub fn emit_yaml(node: &Yaml, options: &EmitOptions) -> String {
+    let mut emitter = Emitter {
+        options,
+        indent: options.indent.max(1),
+        out: String::new(),
+        path: Vec::new(),
+    };
+    match node {
+        Yaml::Mapping(map) if !map.is_empty() => emitter.mapping(map, 0, false),
+        Yaml::Sequence(items) if !items.is_empty() => emitter.sequence(items, 0, false),
+        scalar => {
+            let text = emitter.scalar(scalar, 0, 0, false);
+            emitter.out.push_str(&text);
+            emitter.out.push('\n');
+        }
+    }
+    if let Some(comments) = &options.comments {
+        for comment in comments.footer() {
+            emitter.out.push_str(comment);
+            emitter.out.push('\n');
+        }
+    }
+    emitter.out
+}
+
+struct Emitter<'a> {
+    options: &'a EmitOptions,
+    indent: usize,
+    out: String,
+    /// Path of the node being written, as used by [`Comments`](crate::Comments).
+    path: Vec<String>,
+}
+
+impl<'a> Emitter<'a> {
+    /// Writes a mapping whose keys are at column `indent`. If `inline` is set
+    /// the cursor is already positioned for the first key.
+    fn mapping(&mut self, map: &Mapping, indent: usize, inline: bool) {
+        for (i, (key, value)) in map.iter().enumerate() {
+            self.path.push(path_segment(key));
+            if i > 0 || !inline {
+                self.leading_comments(indent);
+                self.pad(indent);
+            }
+            let key = self.key(key);
+            self.out.push_str(&key);
+            self.out.push(':');
+            self.value_after_key(value, indent, indent + key.len() + 1);
+            self.path.pop();
+        }
+    }
+
+    /// Writes a sequence whose dashes are at column `indent`.
+    fn sequence(&mut self, items: &[Yaml], indent: usize, inline: bool) {
+        for (i, item) in items.iter().enumerate() {
+            self.path.push(i.to_string());
+            if i > 0 || !inline {
+                self.leading_comments(indent);
+                // The first key of a mapping entry shares the dash's line.
+                if let Yaml::Mapping(map) = item {
+                    if let Some((key, _)) = map.iter().next() {
+                        self.path.push(path_segment(key));
+                        self.leading_comments(indent);
+                        self.path.pop();
+                    }
+                }
+                self.pad(indent);
+            }
+            self.out.push('-');
+            self.item_after_dash(item, indent);
+            self.path.pop();
+        }
+    }
+
+    /// Writes the value of a mapping entry; the cursor is after the colon.
+    fn value_after_key(&mut self, value: &Yaml, indent: usize, column: usize) {
+        match value {
+            Yaml::Mapping(map) if !map.is_empty() => {
+                self.trailing_comment();
+                self.out.push('\n');
+                self.mapping(map, indent + self.indent, false);
+            }
+            Yaml::Sequence(items) if !items.is_empty() => {
+                if let Some(flow) = self.flow_sequence(items, column + 1) {
+                    self.out.push(' ');
+                    self.out.push_str(&flow);
+                    self.trailing_comment();
+                    self.out.push('\n');
+                } else {
+                    self.trailing_comment();
+                    self.out.push('\n');
+                    let indent = if self.options.indent_sequences {
+                        indent + self.indent
+                    } else {
+                        indent
+                    };
+                    self.sequence(items, indent, false);
+                }
+            }
+            Yaml::Tagged(tagged) => {
+                let tag = tagged.tag.to_string();
+                self.out.push(' ');
+                self.out.push_str(&tag);
+                self.value_after_key(&tagged.value, indent, column + tag.len() + 1);
+            }
+            scalar => {
+                let text = self.scalar(scalar, indent, column + 1, false);
+                self.out.push(' ');
+                self.scalar_line(&text);
+            }
+        }
+    }
+
+    /// Writes a sequence entry; the cursor is after the dash.
+    fn item_after_dash(&mut self, item: &Yaml, indent: usize) {
+        match item {
+            Yaml::Mapping(map) if !map.is_empty() => {
+                self.out.push(' ');
+                self.mapping(map, indent + 2, true);
+            }
+            Yaml::Sequence(items) if !items.is_empty() => {
+                if let Some(flow) = self.flow_sequence(items, indent + 2) {
+                    self.out.push(' ');
+                    self.out.push_str(&flow);
+                    self.trailing_comment();
+                    self.out.push('\n');
+                } else {
+                    self.out.push(' ');
+                    self.sequence(items, indent + 2, true);
+                }
+            }
+            Yaml::Tagged(tagged) => {
+                let tag = tagged.tag.to_string();
+                self.out.push(' ');
+                self.out.push_str(&tag);
+                self.value_after_key(&tagged.value, indent, indent + tag.len() + 2);
+            }
+            scalar => {
+                let text = self.scalar(scalar, indent, indent + 2, false);
+                self.out.push(' ');
+                self.scalar_line(&text);
+            }
+        }
+    }
+
+    /// Returns the flow form of a sequence if flow style applies and it fits.
+    fn flow_sequence(&self, items: &[Yaml], column: usize) -> Option<String> {
+        if self.options.sequence_style != SequenceStyle::Flow {
+            return None;
+        }
+        let mut parts = Vec::with_capacity(items.len());
+        for item in items {
+            match item {
+                Yaml::Mapping(map) if map.is_empty() => parts.push("{}".to_string()),
+                Yaml::Sequence(items) if items.is_empty() => parts.push("[]".to_string()),
+                Yaml::Null | Yaml::Bool(_) | Yaml::Number(_) | Yaml::String(_) => {
+                    parts.push(self.scalar(item, 0, usize::MAX, true))
+                }
+                _ => return None,
+            }
+        }
+        let flow = format!("[{}]", parts.join(", "));
+        match self.options.line_width {
+            Some(width) if column + flow.len() > width => None,
+            _ => Some(flow),
+        }
+    }
+
+    /// Returns the text of a mapping key.
+    fn key(&self, key: &Yaml) -> String {
+        match key {
+            Yaml::String(s) if is_plain_safe(s, false) => s.clone(),
+            Yaml::String(s) if s.contains('\n') || !is_printable(s) => double_quoted(s),
+            Yaml::String(s) => self.quoted(s),
+            Yaml::Mapping(_) | Yaml::Sequence(_) | Yaml::Tagged(_) => {
+                crate::helpers::yaml_to_json(key).to_string()
+            }
+            scalar => self.scalar(scalar, 0, 0, true),
+        }
+    }
+
+    /// Returns the text of a scalar written at `column` in a node whose
+    /// parent is indented by `indent`.
+    fn scalar(&self, node: &Yaml, indent: usize, column: usize, flow: bool) -> String {
+        match node {
+            Yaml::Null => "null".to_string(),
+            Yaml::Bool(b) => b.to_string(),
+            Yaml::Number(n) => n.to_string(),
+            Yaml::String(s) => self.string(s, indent, column, flow),
+            Yaml::Mapping(_) => "{}".to_string(),
+            Yaml::Sequence(_) => "[]".to_string(),
+            Yaml::Tagged(tagged) => format!(
+                "{} {}",
+                tagged.tag,
+                self.scalar(&tagged.value, indent, column, flow)
+            ),
+        }
+    }
+
+    fn string(&self, s: &str, indent: usize, column: usize, flow: bool) -> String {
+        if !flow && s.contains('\n') && is_literal_safe(s) {
+            return self.literal(s, indent + self.indent);
+        }
+        let text = if is_plain_safe(s, flow) {
+            s.to_string()
+        } else if s.contains('\n') || !is_printable(s) {
+            double_quoted(s)
+        } else {
+            self.quoted(s)
+        };
+        match self.options.line_width {
+            Some(width) if !flow && column.saturating_add(text.len()) > width && s.contains(' ') => {
+                self.folded_double_quoted(s, indent + self.indent, column, width)
+            }
+            _ => text,
+        }
+    }
+
+    fn quoted(&self, s: &str) -> String {
+        match self.options.quote_style {
+            QuoteStyle::Single => format!("'{}'", s.replace('\'', "''")),
+            QuoteStyle::Double => double_quoted(s),
+        }
+    }
+
+    /// Returns a literal block scalar with content lines at `indent`.
+    fn literal(&self, s: &str, indent: usize) -> String {
+        let body = s.trim_end_matches('\n');
+        let trailing = s.len() - body.len();
+        let mut text = String::from(match trailing {
+            0 => "|-",
+            1 => "|",
+            _ => "|+",
+        });
+        for line in body.split('\n') {
+            text.push('\n');
+            if !line.is_empty() {
+                text.push_str(&" ".repeat(indent));
+                text.push_str(line);
+            }
+        }
+        for _ in 1..trailing {
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Returns a double-quoted scalar broken at spaces so that lines fit in
+    /// `width`; continuation lines start at `indent`.
+    fn folded_double_quoted(&self, s: &str, indent: usize, column: usize, width: usize) -> String {
+        let mut text = String::from("\"");
+        let mut line_length = column + 1;
+        let words: Vec<&str> = s.split_inclusive(' ').collect();
+        for (i, word) in words.iter().enumerate() {
+            let escaped = escape(word);
+            if i > 0 && line_length + escaped.len() + 1 > width {
+                // An escaped line break: the break and the next line's
+                // indentation are not part of the value.
+                text.push_str("\\\n");
+                text.push_str(&" ".repeat(indent));
+                line_length = indent;
+            }
+            text.push_str(&escaped);
+            line_length += escaped.len();
+        }
+        text.push('"');
+        text
+    }
+
+    /// Writes the text of a scalar and ends its line, adding the trailing
+    /// comment of the current node after the first line of block scalars.
+    fn scalar_line(&mut self, text: &str) {
+        match text.split_once('\n') {
+            None => {
+                self.out.push_str(text);
+                self.trailing_comment();
+            }
+            Some((header, body)) if header.starts_with('|') => {
+                self.out.push_str(header);
+                self.trailing_comment();
+                self.out.push('\n');
+                self.out.push_str(body);
+            }
+            // Comments cannot follow a line of a quoted scalar.
+            Some(_) => self.out.push_str(text),
+        }
+        self.out.push('\n');
+    }
+
+    fn node_comments(&self) -> Option<&'a NodeComments> {
+        self.options.comments.as_ref()?.get(&self.path)
+    }
+
+    /// Writes the leading comments of the current node at `indent`.
+    fn leading_comments(&mut self, indent: usize) {
+        if let Some(node) = self.node_comments() {
+            for comment in &node.leading {
+                self.pad(indent);
+                self.out.push_str(comment);
+                self.out.push('\n');
+            }
+        }
+    }
+
+    /// Writes the trailing comment of the current node at the cursor.
+    fn trailing_comment(&mut self) {
+        if let Some(comment) = self.node_comments().and_then(|node| node.trailing.as_ref()) {
+            self.out.push(' ');
+            self.out.push_str(comment);
+        }
+    }
+
+    fn pad(&mut self, indent: usize) {
+        self.out.extend(std::iter::repeat_n(' ', indent));
+    }
+}
+
+/// Returns the path segment of a mapping key.
+fn path_segment(key: &Yaml) -> String {
+    match key {
+        Yaml::String(s) => s.clone(),
+        Yaml::Bool(b) => b.to_string(),
+        Yaml::Number(n) => n.to_string(),
+        other => crate::helpers::yaml_to_json(other).to_string(),
+    }
+}
+
+/// Returns a double-quoted scalar.
+fn double_quoted(s: &str) -> String {
+    format!("\"{}\"", escape(s))
+}
+
+/// Escapes a string for use inside double quotes.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            c if !is_printable_char(c) => {
+                let code = c as u32;
+                if code <= 0xff {
+                    escaped.push_str(&format!("\\x{:02X}", code));
+                } else {
+                    escaped.push_str(&format!("\\u{:04X}", code));
+                }
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn is_printable_char(c: char) -> bool {
+    !(c.is_control() || matches!(c, '\u{2028}' | '\u{2029}' | '\u{feff}'))
+}
+
+fn is_printable(s: &str) -> bool {
+    s.chars().all(is_printable_char)
+}
+
+/// Returns true if a string can be written as a literal block scalar.
+fn is_literal_safe(s: &str) -> bool {
+    !s.starts_with([' ', '\n'])
+        && s.chars().all(|c| c == '\n' || c == '\t' || is_printable_char(c))
+        && !s.split('\n').any(|line| line.ends_with([' ', '\t']))
+}
+
+/// Returns true if a string reads back as the same string when written plain.
+fn is_plain_safe(s: &str, flow: bool) -> bool {
+    let Some(first) = s.chars().next() else {
+        return false;
+    };
+    if s.starts_with(char::is_whitespace)
+        || s.ends_with(char::is_whitespace)
+        || !is_printable(s)
+        || s.contains(": ")
+        || s.contains(" #")
+        || s.ends_with(':')
+        || (flow && s.contains([',', '[', ']', '{', '}']))
+    {
+        return false;
+    }
+    if "?:,[]{}#&*!|>'\"%@`".contains(first) || (first == '-' && (s.len() == 1 || s.starts_with("- "))) {
+        return false;
+    }
+    if s == "---" || s == "..." || looks_like_yaml_1_1_scalar(s) {
+        return false;
+    }
+    matches!(serde_yaml::from_str::<Yaml>(s), Ok(Yaml::String(ref parsed)) if parsed == s)
+}
+
+/// Returns true for plain scalars that YAML 1.1 parsers read as booleans,
+/// nulls, numbers or timestamps even though YAML 1.2 reads them as strings.
+fn looks_like_yaml_1_1_scalar(s: &str) -> bool {
+    const WORDS: &[&str] = &[
+        "y", "Y", "yes", "Yes", "YES", "n", "N", "no", "No", "NO", "on", "On", "ON", "off", "Off",
+        "OFF", "true", "True", "TRUE", "false", "False", "FALSE", "null", "Null", "NULL", "~",
+    ];
+    if WORDS.contains(&s) {
+        return true;
+    }
+    let digits = s.trim_start_matches(['+', '-']);
+    let starts_numeric = digits.starts_with(|c: char| c.is_ascii_digit())
+        || (digits.starts_with('.') && digits[1..].starts_with(|c: char| c.is_ascii_digit()));
+    // Numbers with separators ("1_000", "1:30") and dates ("2024-01-31").
+    starts_numeric
+        && digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '_' | ':' | '.' | '-' | 'e' | 'E' | '+'))
+        && (digits.contains(['_', ':']) || is_date(digits))
+}
+
+fn is_date(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    bytes.len() >= 10
+        && bytes[..4].iter().all(u8::is_ascii_digit)
+        && bytes[4] == b'-'
+        && bytes[5..7].iter().all(u8::is_ascii_digit)
+        && bytes[7] == b'-'
+        && bytes[8..10].iter().all(u8::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(text: &str) -> Yaml {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    /// Emits a node and checks that it reads back unchanged.
+    fn emit(node: &Yaml, options: &EmitOptions) -> String {
+        let text = emit_yaml(node, options);
+        assert_eq!(&yaml(&text), node, "emitted:\n{}", text);
+        text
+    }
+
+    #[test]
+    fn test_default_style() {
+        let node = yaml(
+            "openapi: 3.0.0\nresponses:\n  '200':\n    description: ok\ntags:\n- name: pets\n  x: 1\n- b\nempty: {}\n",
+        );
+        assert_eq!(
+            emit(&node, &EmitOptions::default()),
+            "openapi: 3.0.0\nresponses:\n  '200':\n    description: ok\ntags:\n- name: pets\n  x: 1\n- b\nempty: {}\n"
+        );
+    }
+
+    #[test]
+    fn test_quoting() {
+        let mut map = Mapping::new();
+        for key in ["200", "true", "yes", "null", "1.0", "2024-01-31", "1:30", "a: b", "- x", "", "#c", "'s", "it's"] {
+            map.insert(key.into(), key.into());
+        }
+        map.insert(Yaml::Number(404.into()), Yaml::Bool(false));
+        let node = Yaml::Mapping(map);
+        let single = emit(&node, &EmitOptions::default());
+        assert!(single.starts_with("'200': '200'\n'true': 'true'\n'yes': 'yes'\n"));
+        assert!(single.contains("'''s': '''s'\nit's: it's\n"));
+        assert!(single.ends_with("404: false\n"));
+
+        let double = emit(&node, &EmitOptions::default().with_quote_style(QuoteStyle::Double));
+        assert!(double.starts_with("\"200\": \"200\"\n"));
+        assert!(double.contains("\"'s\": \"'s\"\nit's: it's\n"));
+    }
+
+    #[test]
+    fn test_indent_and_sequences() {
+        let node = yaml("a:\n  b:\n  - 1\n  - x\n  c:\n  - k: v\n");
+        let options = EmitOptions::default().with_indent(4).with_indented_sequences(true);
+        assert_eq!(emit(&node, &options), "a:\n    b:\n        - 1\n        - x\n    c:\n        - k: v\n");
+
+        let flow = EmitOptions::default().with_sequence_style(SequenceStyle::Flow);
+        assert_eq!(emit(&node, &flow), "a:\n  b: [1, x]\n  c:\n  - k: v\n");
+
+        // Flow sequences that do not fit fall back to block style
+        let narrow = flow.with_line_width(11);
+        assert_eq!(emit(&node, &narrow), "a:\n  b: [1, x]\n  c:\n  - k: v\n");
+        let narrower = narrow.with_line_width(10);
+        assert_eq!(emit(&node, &narrower), "a:\n  b:\n  - 1\n  - x\n  c:\n  - k: v\n");
+    }
+
+    #[test]
+    fn test_multiline_and_special_strings() {
+        let mut map = Mapping::new();
+        map.insert("description".into(), "line one\nline two\n".into());
+        map.insert("strip".into(), "a\n\nb".into());
+        map.insert("keep".into(), "a\n\n".into());
+        map.insert("spaced".into(), " leading\nx".into());
+        map.insert("control".into(), "bell\u{7}".into());
+        let node = Yaml::Mapping(map);
+        let text = emit(&node, &EmitOptions::default());
+        assert!(text.starts_with("description: |\n  line one\n  line two\nstrip: |-\n  a\n\n  b\n"));
+        assert!(text.contains("spaced: \" leading\\nx\"\n"));
+        assert!(text.contains("control: \"bell\\x07\"\n"));
+
+        let seq = Yaml::Sequence(vec!["multi\nline".into()]);
+        assert_eq!(emit(&seq, &EmitOptions::default()), "- |-\n  multi\n  line\n");
+    }
+
+    #[test]
+    fn test_comments() {
+        let source = "\
+# header
+a: 1 # one
+b:
+  # nested
+  c: |- # block
+    x
+    y
+items: # list
+# first
+- k: v
+  # second key
+  l: w
+- plain # scalar
+# footer
+";
+        let options = EmitOptions::default().with_comments(crate::Comments::from_source(source));
+        assert_eq!(emit(&yaml(source), &options), source);
+    }
+
+    #[test]
+    fn test_line_width() {
+        let mut map = Mapping::new();
+        map.insert(
+            "description".into(),
+            "a long description that does not fit on one line".into(),
+        );
+        let node = Yaml::Mapping(map);
+        let text = emit(&node, &EmitOptions::default().with_line_width(30));
+        assert!(text.lines().all(|line| line.len() <= 30), "{}", text);
+        assert!(text.starts_with("description: \"a long \\\n"));
+    }
+}
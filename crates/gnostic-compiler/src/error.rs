@@ -14,41 +14,156 @@
 
 //! Error types for the compiler.
 
+use crate::codes::ErrorCode;
+use crate::comments::Comments;
 use crate::context::Context;
+use crate::helpers::{closest_match, invalid_keys_in_map};
+use crate::raw_info::RawInfo;
+use regex::Regex;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::{json, Value as JsonValue};
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use thiserror::Error;
 
+/// SARIF schema location written into generated logs.
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Severity of a compiler diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// A problem that prevents the document from being used.
+    #[default]
+    Error,
+    /// A problem that was recovered from.
+    Warning,
+    /// An informational note.
+    Info,
+}
+
+impl Severity {
+    /// Returns the prefix used when displaying a diagnostic of this severity.
+    fn prefix(&self) -> &'static str {
+        match self {
+            Severity::Error => "",
+            Severity::Warning => "warning: ",
+            Severity::Info => "info: ",
+        }
+    }
+
+    /// Returns the SARIF result level for this severity.
+    fn sarif_level(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "note",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
 /// CompilerError represents compiler errors and their location in the document.
+///
+/// Errors that wrap an underlying error, such as an IO or YAML error, return
+/// it from [`source`](std::error::Error::source); it is shared so that errors
+/// stay cheap to clone, so downcast it as `Arc<std::io::Error>` and so on.
+/// Errors are compared and hashed by what they report, leaving the underlying
+/// errors out.
 #[derive(Error, Debug, Clone)]
 pub enum CompilerError {
     /// Error with location information (line and column).
-    #[error("[{line},{column}] {path} {message}")]
+    #[error("{}[{line},{column}] {path} {message}", .severity.prefix())]
     Located {
         line: usize,
         column: usize,
         path: String,
         message: String,
+        severity: Severity,
+        code: ErrorCode,
     },
 
     /// Error without location information.
-    #[error("{path} {message}")]
-    Unlocated { path: String, message: String },
+    #[error("{}{path} {message}", .severity.prefix())]
+    Unlocated {
+        path: String,
+        message: String,
+        severity: Severity,
+        code: ErrorCode,
+    },
+
+    /// A mapping key that is not allowed where it appears.
+    #[error(
+        "{}{}{path} has invalid property: {key}{}",
+        .severity.prefix(),
+        position_prefix(.line, .column),
+        suggestion_suffix(.suggestion)
+    )]
+    UnknownKey {
+        key: String,
+        path: String,
+        line: Option<usize>,
+        column: Option<usize>,
+        /// The closest allowed key, if one is similar enough to suggest.
+        suggestion: Option<String>,
+        severity: Severity,
+    },
 
     /// Simple error message without context.
     #[error("{0}")]
     Simple(String),
 
     /// IO error.
-    #[error("IO error: {0}")]
-    Io(String),
+    #[error("IO error: {message}")]
+    Io {
+        message: String,
+        #[source]
+        source: Option<Arc<std::io::Error>>,
+    },
 
     /// YAML parsing error.
-    #[error("YAML error: {0}")]
-    Yaml(String),
+    #[error("YAML error: {message}")]
+    Yaml {
+        message: String,
+        #[source]
+        source: Option<Arc<serde_yaml::Error>>,
+    },
+
+    /// JSON parsing error.
+    #[error("Invalid JSON: {message}")]
+    Json {
+        message: String,
+        #[source]
+        source: Option<Arc<serde_json::Error>>,
+    },
 
     /// HTTP error.
     #[error("HTTP error: {0}")]
     Http(String),
+
+    /// A configured resource limit was exceeded.
+    #[error("Limit exceeded: {0}")]
+    Limit(String),
+
+    /// Protocol buffer encoding or decoding error.
+    #[error("Protobuf error: {0}")]
+    Protobuf(String),
+
+    /// Encoding or decoding error for a serde binary format (CBOR, MessagePack).
+    #[error("Encoding error: {0}")]
+    Encoding(String),
 }
 
 impl CompilerError {
@@ -61,14 +176,241 @@ impl CompilerError {
                 column,
                 path: context.description(),
                 message,
+                severity: Severity::Error,
+                code: ErrorCode::Unspecified,
             },
             _ => CompilerError::Unlocated {
                 path: context.description(),
                 message,
+                severity: Severity::Error,
+                code: ErrorCode::Unspecified,
             },
         }
     }
 
+    /// Creates an IO error that wraps no underlying error.
+    pub fn io(message: impl Into<String>) -> Self {
+        CompilerError::Io { message: message.into(), source: None }
+    }
+
+    /// Creates a YAML error that wraps no underlying error.
+    pub fn yaml(message: impl Into<String>) -> Self {
+        CompilerError::Yaml { message: message.into(), source: None }
+    }
+
+    /// Creates a JSON error that wraps no underlying error.
+    pub fn json(message: impl Into<String>) -> Self {
+        CompilerError::Json { message: message.into(), source: None }
+    }
+
+    /// Creates an unknown-key error, suggesting the closest of `allowed_keys`.
+    pub fn unknown_key(context: &Context, key: impl Into<String>, allowed_keys: &[&str]) -> Self {
+        let key = key.into();
+        let suggestion = closest_match(&key, allowed_keys).map(str::to_string);
+        CompilerError::UnknownKey {
+            key,
+            path: context.description(),
+            line: context.line,
+            column: context.column,
+            suggestion,
+            severity: Severity::Error,
+        }
+    }
+
+    /// Returns an unknown-key error for each key of `node` that is neither in
+    /// `allowed_keys` nor matched by one of `allowed_patterns`.
+    pub fn unknown_keys_in_map(
+        context: &Context,
+        node: &serde_yaml::Value,
+        allowed_keys: &[&str],
+        allowed_patterns: &[&Regex],
+    ) -> Vec<Self> {
+        invalid_keys_in_map(node, allowed_keys, allowed_patterns)
+            .into_iter()
+            .map(|key| Self::unknown_key(context, key, allowed_keys))
+            .collect()
+    }
+
+    /// Creates a new warning from a context and message.
+    pub fn warning(context: &Context, message: impl Into<String>) -> Self {
+        Self::new(context, message).with_severity(Severity::Warning)
+    }
+
+    /// Creates a new error with the given code from a context and message.
+    pub fn new_with_code(
+        context: &Context,
+        code: ErrorCode,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(context, message).with_code(code)
+    }
+
+    /// Returns the severity of the error.
+    ///
+    /// Only errors created from a context carry a severity; all others are errors.
+    pub fn severity(&self) -> Severity {
+        match self {
+            CompilerError::Located { severity, .. }
+            | CompilerError::Unlocated { severity, .. }
+            | CompilerError::UnknownKey { severity, .. } => *severity,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Returns the error with its severity changed, if the variant carries one.
+    pub fn with_severity(mut self, new_severity: Severity) -> Self {
+        if let CompilerError::Located { severity, .. }
+        | CompilerError::Unlocated { severity, .. }
+        | CompilerError::UnknownKey { severity, .. } = &mut self
+        {
+            *severity = new_severity;
+        }
+        self
+    }
+
+    /// Returns the stable code identifying the kind of error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CompilerError::Located { code, .. } | CompilerError::Unlocated { code, .. } => *code,
+            CompilerError::UnknownKey { .. } => ErrorCode::UnknownKey,
+            CompilerError::Simple(_) => ErrorCode::Unspecified,
+            CompilerError::Io { .. } => ErrorCode::Io,
+            CompilerError::Yaml { .. } | CompilerError::Json { .. } => ErrorCode::Syntax,
+            CompilerError::Http(_) => ErrorCode::Http,
+            CompilerError::Limit(_) => ErrorCode::LimitExceeded,
+            CompilerError::Protobuf(_) => ErrorCode::Protobuf,
+            CompilerError::Encoding(_) => ErrorCode::Encoding,
+        }
+    }
+
+    /// Returns the error with its code changed, if the variant carries one.
+    pub fn with_code(mut self, new_code: ErrorCode) -> Self {
+        if let CompilerError::Located { code, .. } | CompilerError::Unlocated { code, .. } =
+            &mut self
+        {
+            *code = new_code;
+        }
+        self
+    }
+
+    /// Returns true if this diagnostic has error severity.
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+
+    /// Returns a short name for the kind of error.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CompilerError::Located { .. } | CompilerError::Unlocated { .. } => "document",
+            CompilerError::UnknownKey { .. } => "unknown_key",
+            CompilerError::Simple(_) => "simple",
+            CompilerError::Io { .. } => "io",
+            CompilerError::Yaml { .. } => "yaml",
+            CompilerError::Json { .. } => "json",
+            CompilerError::Http(_) => "http",
+            CompilerError::Limit(_) => "limit",
+            CompilerError::Protobuf(_) => "protobuf",
+            CompilerError::Encoding(_) => "encoding",
+        }
+    }
+
+    /// Returns the document path the error refers to, if known.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            CompilerError::Located { path, .. }
+            | CompilerError::Unlocated { path, .. }
+            | CompilerError::UnknownKey { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns the line and column of the error, if known.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        match self {
+            CompilerError::Located { line, column, .. } => Some((*line, *column)),
+            CompilerError::UnknownKey {
+                line: Some(line),
+                column: Some(column),
+                ..
+            } => Some((*line, *column)),
+            _ => None,
+        }
+    }
+
+    /// Returns the error message without location or severity decoration.
+    pub fn message(&self) -> Cow<'_, str> {
+        match self {
+            CompilerError::Located { message, .. } | CompilerError::Unlocated { message, .. } => {
+                Cow::Borrowed(message)
+            }
+            CompilerError::UnknownKey { key, suggestion, .. } => Cow::Owned(format!(
+                "has invalid property: {}{}",
+                key,
+                suggestion_suffix(suggestion)
+            )),
+            CompilerError::Simple(message)
+            | CompilerError::Io { message, .. }
+            | CompilerError::Yaml { message, .. }
+            | CompilerError::Json { message, .. }
+            | CompilerError::Http(message)
+            | CompilerError::Limit(message)
+            | CompilerError::Protobuf(message)
+            | CompilerError::Encoding(message) => Cow::Borrowed(message),
+        }
+    }
+
+    /// Returns the error followed by the offending source line and a caret
+    /// marking its column, when position information is available.
+    pub fn display_with_source(&self, source: &str) -> String {
+        let mut out = self.to_string();
+        if let Some((line, column)) = self.location() {
+            if let Some(text) = line.checked_sub(1).and_then(|i| source.lines().nth(i)) {
+                let number = line.to_string();
+                let gutter = " ".repeat(number.len());
+                // Tabs are kept so the caret lines up with the source in a terminal
+                let padding: String = text
+                    .chars()
+                    .take(column.saturating_sub(1))
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                out.push_str(&format!("\n{} | {}\n{} | {}^", number, text, gutter, padding));
+            }
+        }
+        out
+    }
+
+    /// Returns a SARIF result object describing this error.
+    fn to_sarif_result(&self, artifact_uri: Option<&str>) -> JsonValue {
+        let mut location = serde_json::Map::new();
+        if let Some(path) = self.path() {
+            location.insert(
+                "logicalLocations".to_string(),
+                json!([{ "fullyQualifiedName": path }]),
+            );
+        }
+        if let Some(uri) = artifact_uri {
+            let mut physical = serde_json::Map::new();
+            physical.insert("artifactLocation".to_string(), json!({ "uri": uri }));
+            if let Some((line, column)) = self.location() {
+                physical.insert(
+                    "region".to_string(),
+                    json!({ "startLine": line, "startColumn": column }),
+                );
+            }
+            location.insert("physicalLocation".to_string(), JsonValue::Object(physical));
+        }
+
+        let mut result = json!({
+            "ruleId": self.code().as_str(),
+            "level": self.severity().sarif_level(),
+            "message": { "text": self.message() },
+        });
+        if !location.is_empty() {
+            result["locations"] = json!([location]);
+        }
+        result
+    }
+
     /// Creates a new error from an optional context and message.
     pub fn new_opt(context: Option<&Context>, message: impl Into<String>) -> Self {
         match context {
@@ -78,8 +420,102 @@ impl CompilerError {
     }
 }
 
+impl Serialize for CompilerError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", &self.code())?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("severity", &self.severity())?;
+        if let Some(path) = self.path() {
+            map.serialize_entry("path", path)?;
+        }
+        if let Some((line, column)) = self.location() {
+            map.serialize_entry("line", &line)?;
+            map.serialize_entry("column", &column)?;
+        }
+        if let CompilerError::UnknownKey {
+            key, suggestion, ..
+        } = self
+        {
+            map.serialize_entry("key", key)?;
+            if let Some(suggestion) = suggestion {
+                map.serialize_entry("suggestion", suggestion)?;
+            }
+        }
+        map.serialize_entry("message", &self.message())?;
+        map.end()
+    }
+}
+
+/// The parts of an error that equality and hashing look at: the variant and
+/// everything the error reports, but not the underlying error it wraps.
+type ErrorIdentity<'a> = (
+    std::mem::Discriminant<CompilerError>,
+    Option<&'a str>,
+    (Option<usize>, Option<usize>),
+    Cow<'a, str>,
+    Severity,
+    ErrorCode,
+);
+
+impl CompilerError {
+    /// Returns the identity used to compare and hash the error.
+    fn identity(&self) -> ErrorIdentity<'_> {
+        let position = match self {
+            CompilerError::Located { line, column, .. } => (Some(*line), Some(*column)),
+            CompilerError::UnknownKey { line, column, .. } => (*line, *column),
+            _ => (None, None),
+        };
+        (
+            std::mem::discriminant(self),
+            self.path(),
+            position,
+            self.message(),
+            self.severity(),
+            self.code(),
+        )
+    }
+}
+
+impl PartialEq for CompilerError {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for CompilerError {}
+
+impl Hash for CompilerError {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identity().hash(state);
+    }
+}
+
+/// Formats an optional position as the "[line,column] " prefix used in error messages.
+fn position_prefix(line: &Option<usize>, column: &Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!("[{},{}] ", line, column),
+        _ => String::new(),
+    }
+}
+
+/// Formats an optional key suggestion for appending to an error message.
+fn suggestion_suffix(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(" (did you mean {}?)", suggestion),
+        None => String::new(),
+    }
+}
+
+/// Compares document paths segment by segment, ordering numeric segments
+/// (array indices) by value so that `items.2` comes before `items.10`.
+fn compare_paths(a: &str, b: &str) -> std::cmp::Ordering {
+    let segment = |s| (str::parse::<usize>(s).ok(), s);
+    a.split('.').map(segment).cmp(b.split('.').map(segment))
+}
+
 /// ErrorGroup is a container for groups of errors.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct ErrorGroup {
     pub errors: Vec<CompilerError>,
 }
@@ -119,16 +555,181 @@ impl ErrorGroup {
         self.errors.extend(other.errors);
     }
 
-    /// Converts the group to a Result, returning Ok(()) if empty.
+    /// Removes repeated identical errors, keeping the first occurrence of each.
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+        self.errors.retain(|e| seen.insert(e.clone()));
+    }
+
+    /// Sorts the errors into document order.
+    ///
+    /// Errors with a position come first, ordered by line and column. Errors
+    /// without one, which includes everything the parsers report, follow in
+    /// order of their document path, comparing array indices numerically.
+    /// Errors with neither keep their original order at the end.
+    pub fn sort_by_location(&mut self) {
+        self.errors.sort_by(|a, b| {
+            let rank = |e: &CompilerError| match (e.location(), e.path()) {
+                (Some(_), _) => 0,
+                (None, Some(_)) => 1,
+                (None, None) => 2,
+            };
+            rank(a)
+                .cmp(&rank(b))
+                .then_with(|| a.location().cmp(&b.location()))
+                .then_with(|| match (a.location(), a.path(), b.path()) {
+                    (None, Some(a), Some(b)) => compare_paths(a, b),
+                    _ => std::cmp::Ordering::Equal,
+                })
+        });
+    }
+
+    /// Shortens the group to at most `max` entries.
+    ///
+    /// If entries are removed, a summary entry "and N more errors" is appended.
+    /// The summary takes the most severe severity of the entries it replaces,
+    /// so capped warnings are still reported as warnings.
+    pub fn truncate(&mut self, max: usize) {
+        if self.errors.len() > max {
+            let dropped = self.errors.split_off(max);
+            let remaining = dropped.len();
+            let severity = dropped.iter().map(CompilerError::severity).min().unwrap_or_default();
+            let noun = match severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Info => "note",
+            };
+            self.errors.push(CompilerError::Unlocated {
+                path: "$".to_string(),
+                message: format!("and {} more {}{}", remaining, noun, if remaining == 1 { "" } else { "s" }),
+                severity,
+                code: ErrorCode::Unspecified,
+            });
+        }
+    }
+
+    /// Returns true if the group contains at least one diagnostic with error severity.
+    pub fn has_errors(&self) -> bool {
+        self.errors.iter().any(CompilerError::is_error)
+    }
+
+    /// Returns the number of diagnostics with the given severity.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.errors.iter().filter(|e| e.severity() == severity).count()
+    }
+
+    /// Returns the diagnostics that are not errors.
+    pub fn warnings(&self) -> impl Iterator<Item = &CompilerError> {
+        self.errors.iter().filter(|e| !e.is_error())
+    }
+
+    /// Returns the diagnostics with the given code.
+    pub fn with_code(&self, code: ErrorCode) -> impl Iterator<Item = &CompilerError> {
+        self.errors.iter().filter(move |e| e.code() == code)
+    }
+
+    /// Removes all diagnostics whose code is in `codes`.
+    pub fn suppress(&mut self, codes: &[ErrorCode]) {
+        self.errors.retain(|e| !codes.contains(&e.code()));
+    }
+
+    /// Returns the errors with source snippets rendered from `source`.
+    ///
+    /// See [`CompilerError::display_with_source`].
+    pub fn display_with_source(&self, source: &str) -> String {
+        self.errors
+            .iter()
+            .map(|e| e.display_with_source(source))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the errors as a pretty-printed JSON document.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Returns the errors as a SARIF 2.1.0 log.
+    ///
+    /// Results carry the document path as a logical location. Use
+    /// [`ErrorGroup::to_sarif_for`] to also attach the source file.
+    pub fn to_sarif(&self) -> String {
+        serde_json::to_string_pretty(&self.sarif_log(None)).unwrap_or_default()
+    }
+
+    /// Returns the errors as a SARIF 2.1.0 log whose results point into `artifact_uri`.
+    pub fn to_sarif_for(&self, artifact_uri: &str) -> String {
+        serde_json::to_string_pretty(&self.sarif_log(Some(artifact_uri))).unwrap_or_default()
+    }
+
+    /// Builds the SARIF log object for the group, with a rule for each
+    /// error code that occurs, so that results can be filtered by code.
+    fn sarif_log(&self, artifact_uri: Option<&str>) -> JsonValue {
+        let mut codes: Vec<ErrorCode> = Vec::new();
+        let results: Vec<JsonValue> = self
+            .errors
+            .iter()
+            .map(|e| {
+                let code = e.code();
+                let index = codes.iter().position(|c| *c == code).unwrap_or_else(|| {
+                    codes.push(code);
+                    codes.len() - 1
+                });
+                let mut result = e.to_sarif_result(artifact_uri);
+                result["ruleIndex"] = json!(index);
+                result
+            })
+            .collect();
+        let rules: Vec<JsonValue> =
+            codes.iter().map(|code| json!({ "id": code.as_str(), "name": code.name() })).collect();
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "gnostic",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }],
+        })
+    }
+
+    /// Converts the group to a Result, returning Ok(()) if it contains no errors.
+    ///
+    /// Warnings and informational diagnostics do not cause an Err.
     pub fn into_result(self) -> std::result::Result<(), Self> {
-        if self.errors.is_empty() {
-            Ok(())
-        } else {
+        if self.has_errors() {
             Err(self)
+        } else {
+            Ok(())
         }
     }
 }
 
+/// Parsed holds a successfully parsed value along with any non-fatal diagnostics.
+#[derive(Debug, Clone)]
+pub struct Parsed<T> {
+    /// The parsed value.
+    pub value: T,
+    /// Warnings and informational diagnostics reported during the parse.
+    pub warnings: ErrorGroup,
+    /// The source of the document, if raw info preservation was requested.
+    pub raw_info: Option<RawInfo>,
+    /// The comments of the source, if comment preservation was requested.
+    pub comments: Option<Comments>,
+}
+
+impl<T> Parsed<T> {
+    /// Returns the parsed value, discarding any diagnostics.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
 impl fmt::Display for ErrorGroup {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (i, err) in self.errors.iter().enumerate() {
@@ -141,11 +742,17 @@ impl fmt::Display for ErrorGroup {
     }
 }
 
-impl std::error::Error for ErrorGroup {}
-
-impl Default for ErrorGroup {
-    fn default() -> Self {
-        ErrorGroup { errors: Vec::new() }
+impl std::error::Error for ErrorGroup {
+    /// Returns the underlying error wrapped by the first error in the group, so
+    /// that the chain reaches the original IO, JSON or YAML error. The entries
+    /// themselves are not chained, since Display already prints every one; they
+    /// are exposed to miette reporters through `related()`.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.errors
+            .iter()
+            .find(|e| e.is_error())
+            .or_else(|| self.errors.first())
+            .and_then(|e| e.source())
     }
 }
 
@@ -159,13 +766,25 @@ impl From<CompilerError> for ErrorGroup {
 
 impl From<std::io::Error> for CompilerError {
     fn from(err: std::io::Error) -> Self {
-        CompilerError::Io(err.to_string())
+        CompilerError::Io { message: err.to_string(), source: Some(Arc::new(err)) }
+    }
+}
+
+impl From<prost::DecodeError> for CompilerError {
+    fn from(err: prost::DecodeError) -> Self {
+        CompilerError::Protobuf(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CompilerError {
+    fn from(err: serde_json::Error) -> Self {
+        CompilerError::Json { message: err.to_string(), source: Some(Arc::new(err)) }
     }
 }
 
 impl From<serde_yaml::Error> for CompilerError {
     fn from(err: serde_yaml::Error) -> Self {
-        CompilerError::Yaml(err.to_string())
+        CompilerError::Yaml { message: err.to_string(), source: Some(Arc::new(err)) }
     }
 }
 
@@ -204,6 +823,307 @@ mod tests {
         assert!(!group.is_empty());
     }
 
+    #[test]
+    fn test_error_severity() {
+        let ctx = Context::new("test.field", Some(10), Some(5), None);
+        let err = CompilerError::new(&ctx, "invalid value");
+        assert_eq!(err.severity(), Severity::Error);
+        assert!(err.is_error());
+
+        let warning = CompilerError::warning(&ctx, "unusual value");
+        assert_eq!(warning.severity(), Severity::Warning);
+        assert_eq!(warning.to_string(), "warning: [10,5] test.field unusual value");
+
+        let simple = CompilerError::Simple("test".to_string()).with_severity(Severity::Info);
+        assert_eq!(simple.severity(), Severity::Error);
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        let ctx = Context::new("$.info", Some(2), Some(3), None);
+        let err = CompilerError::unknown_key(&ctx, "titel", &["title", "version", "description"]);
+        assert_eq!(err.code(), ErrorCode::UnknownKey);
+        assert_eq!(err.to_string(), "[2,3] $.info has invalid property: titel (did you mean title?)");
+        assert_eq!(err.location(), Some((2, 3)));
+
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["key"], "titel");
+        assert_eq!(value["suggestion"], "title");
+
+        let node: serde_yaml::Value =
+            serde_yaml::from_str("title: t\nx-logo: l\nfoo: bar\n").unwrap();
+        let ctx = Context::new("$.info", None, None, None);
+        let pattern = Regex::new("^x-").unwrap();
+        let errors =
+            CompilerError::unknown_keys_in_map(&ctx, &node, &["title", "version"], &[&pattern]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "$.info has invalid property: foo");
+        assert_eq!(
+            errors[0].clone().with_severity(Severity::Warning).to_string(),
+            "warning: $.info has invalid property: foo"
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let ctx = Context::new("test.field", Some(10), Some(5), None);
+        let err = CompilerError::new(&ctx, "invalid value");
+        assert_eq!(err.code(), ErrorCode::Unspecified);
+
+        let err = CompilerError::new_with_code(&ctx, ErrorCode::UnknownKey, "unknown key");
+        assert_eq!(err.code(), ErrorCode::UnknownKey);
+        assert_eq!(err.to_string(), "[10,5] test.field unknown key");
+
+        assert_eq!(CompilerError::io("x").code(), ErrorCode::Io);
+        assert_eq!(CompilerError::Limit("x".to_string()).code(), ErrorCode::LimitExceeded);
+
+        let mut group = ErrorGroup::new(vec![
+            err,
+            CompilerError::new_with_code(&ctx, ErrorCode::InvalidType, "not a mapping"),
+        ]);
+        assert_eq!(group.with_code(ErrorCode::UnknownKey).count(), 1);
+        group.suppress(&[ErrorCode::UnknownKey]);
+        assert_eq!(group.len(), 1);
+        assert_eq!(group.errors[0].code(), ErrorCode::InvalidType);
+    }
+
+    #[test]
+    fn test_error_group_mixed_severities() {
+        let ctx = Context::root("root");
+        let mut group = ErrorGroup::default();
+        group.push(CompilerError::warning(&ctx, "warning 1"));
+        group.push(CompilerError::new(&ctx, "note").with_severity(Severity::Info));
+        assert!(!group.has_errors());
+        assert_eq!(group.warnings().count(), 2);
+        assert_eq!(group.count(Severity::Info), 1);
+        assert!(group.clone().into_result().is_ok());
+
+        group.push(CompilerError::new(&ctx, "error 1"));
+        assert!(group.has_errors());
+        assert!(group.into_result().is_err());
+    }
+
+    #[test]
+    fn test_display_with_source() {
+        let source = "openapi: 3.0.0\ninfo:\n  title: 42\n";
+        let ctx = Context::new("$.info.title", Some(3), Some(10), None);
+        let group = ErrorGroup::new(vec![
+            CompilerError::new(&ctx, "title must be a string"),
+            CompilerError::io("not found"),
+        ]);
+        let expected = [
+            "[3,10] $.info.title title must be a string",
+            "3 |   title: 42",
+            "  |          ^",
+            "IO error: not found",
+        ];
+        assert_eq!(group.display_with_source(source), expected.join("\n"));
+
+        // Positions outside the source are displayed without a snippet
+        let ctx = Context::new("$.x", Some(40), Some(1), None);
+        let err = CompilerError::new(&ctx, "bad");
+        assert_eq!(err.display_with_source(source), "[40,1] $.x bad");
+    }
+
+    #[test]
+    fn test_error_to_json() {
+        let ctx = Context::new("$.info.title", Some(3), Some(7), None);
+        let mut group = ErrorGroup::default();
+        group.push(CompilerError::new(&ctx, "title must be a string"));
+        group.push(CompilerError::io("not found"));
+
+        let value: serde_json::Value = serde_json::from_str(&group.to_json()).unwrap();
+        let errors = value["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0]["code"], "GNO0000");
+        assert_eq!(errors[0]["kind"], "document");
+        assert_eq!(errors[0]["severity"], "error");
+        assert_eq!(errors[0]["path"], "$.info.title");
+        assert_eq!(errors[0]["line"], 3);
+        assert_eq!(errors[0]["column"], 7);
+        assert_eq!(errors[0]["message"], "title must be a string");
+        assert_eq!(errors[1]["code"], "GNO0100");
+        assert_eq!(errors[1]["kind"], "io");
+        assert!(errors[1].get("path").is_none());
+    }
+
+    #[test]
+    fn test_error_to_sarif() {
+        let ctx = Context::new("$.info.title", Some(3), Some(7), None);
+        let group = ErrorGroup::new(vec![CompilerError::warning(&ctx, "title is empty")]);
+
+        let value: serde_json::Value = serde_json::from_str(&group.to_sarif()).unwrap();
+        assert_eq!(value["version"], "2.1.0");
+        let result = &value["runs"][0]["results"][0];
+        assert_eq!(result["level"], "warning");
+        assert_eq!(result["message"]["text"], "title is empty");
+        assert_eq!(
+            result["locations"][0]["logicalLocations"][0]["fullyQualifiedName"],
+            "$.info.title"
+        );
+        assert!(result["locations"][0].get("physicalLocation").is_none());
+        assert_eq!(result["ruleId"], "GNO0000");
+        assert_eq!(result["ruleIndex"], 0);
+        let rules = &value["runs"][0]["tool"]["driver"]["rules"];
+        assert_eq!(rules, &serde_json::json!([{"id": "GNO0000", "name": "Unspecified"}]));
+
+        let value: serde_json::Value =
+            serde_json::from_str(&group.to_sarif_for("openapi.yaml")).unwrap();
+        let physical = &value["runs"][0]["results"][0]["locations"][0]["physicalLocation"];
+        assert_eq!(physical["artifactLocation"]["uri"], "openapi.yaml");
+        assert_eq!(physical["region"]["startLine"], 3);
+        assert_eq!(physical["region"]["startColumn"], 7);
+
+        let group = ErrorGroup::new(vec![
+            CompilerError::new_with_code(&ctx, ErrorCode::MissingRequiredField, "missing"),
+            CompilerError::io("unreadable"),
+            CompilerError::new_with_code(&ctx, ErrorCode::MissingRequiredField, "missing"),
+        ]);
+        let value: serde_json::Value = serde_json::from_str(&group.to_sarif()).unwrap();
+        let results = &value["runs"][0]["results"];
+        let ids: Vec<_> = (0..3).map(|i| results[i]["ruleId"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["GNO0001", "GNO0100", "GNO0001"]);
+        assert_eq!(results[2]["ruleIndex"], 0);
+        let rules = value["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1]["name"], "Io");
+    }
+
+    #[test]
+    fn test_error_group_truncate() {
+        let mut group = ErrorGroup::new(
+            (0..4).map(|i| CompilerError::Simple(format!("error {}", i))).collect(),
+        );
+        group.truncate(4);
+        assert_eq!(group.len(), 4);
+        group.truncate(3);
+        assert_eq!(group.len(), 4);
+        assert_eq!(group.errors[3].to_string(), "$ and 1 more error");
+
+        let warning = |message: &str| CompilerError::Unlocated {
+            path: "$".to_string(),
+            message: message.to_string(),
+            severity: Severity::Warning,
+            code: ErrorCode::Unspecified,
+        };
+        let mut group = ErrorGroup::new((0..3).map(|i| warning(&format!("w{}", i))).collect());
+        group.truncate(1);
+        assert_eq!(group.len(), 2);
+        assert_eq!(group.errors[1].severity(), Severity::Warning);
+        assert_eq!(group.errors[1].to_string(), "warning: $ and 2 more warnings");
+        assert!(!group.has_errors());
+    }
+
+    #[test]
+    fn test_error_group_dedup_and_sort() {
+        let at = |line, column| Context::new("$.x", Some(line), Some(column), None);
+        let mut group = ErrorGroup::new(vec![
+            CompilerError::new(&at(9, 1), "could not resolve #/a"),
+            CompilerError::Simple("unlocated".to_string()),
+            CompilerError::new(&at(2, 5), "bad"),
+            CompilerError::new(&at(9, 1), "could not resolve #/a"),
+            CompilerError::new(&at(2, 3), "bad"),
+            CompilerError::warning(&at(9, 1), "could not resolve #/a"),
+        ]);
+
+        group.dedup();
+        assert_eq!(group.len(), 5);
+
+        group.sort_by_location();
+        let locations: Vec<_> = group.errors.iter().map(CompilerError::location).collect();
+        assert_eq!(
+            locations,
+            vec![Some((2, 3)), Some((2, 5)), Some((9, 1)), Some((9, 1)), None]
+        );
+        assert!(group.errors[2].is_error());
+        assert!(!group.errors[3].is_error());
+    }
+
+    #[test]
+    fn test_error_group_sort_by_path() {
+        let at = |path: &str| Context::root(path);
+        let mut group = ErrorGroup::new(vec![
+            CompilerError::Simple("unlocated".to_string()),
+            CompilerError::new(&at("$.tags"), "bad"),
+            CompilerError::new(&at("$.items.10"), "bad"),
+            CompilerError::new(&Context::new("$.z", Some(4), Some(1), None), "bad"),
+            CompilerError::new(&at("$.items.2"), "bad"),
+            CompilerError::new(&at("$.items.2.name"), "bad"),
+        ]);
+
+        group.sort_by_location();
+        let paths: Vec<_> = group.errors.iter().map(CompilerError::path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                Some("$.z"),
+                Some("$.items.2"),
+                Some("$.items.2.name"),
+                Some("$.items.10"),
+                Some("$.tags"),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_group_source() {
+        use std::error::Error as _;
+
+        let ctx = Context::root("$");
+        let group = ErrorGroup::new(vec![
+            CompilerError::warning(&ctx, "unusual"),
+            CompilerError::new(&ctx, "invalid"),
+        ]);
+        assert!(group.source().is_none());
+
+        // ErrorGroup converts into anyhow::Error and can be recovered from it
+        let err = anyhow::Error::from(group);
+        assert_eq!(err.chain().count(), 1);
+        assert_eq!(err.downcast_ref::<ErrorGroup>().unwrap().len(), 2);
+
+        // A group chains to the error wrapped by its first entry
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        let group = ErrorGroup::new(vec![CompilerError::from(io), CompilerError::new(&ctx, "invalid")]);
+        let err = anyhow::Error::from(group);
+        assert_eq!(err.chain().count(), 2);
+        let root = err.root_cause().downcast_ref::<Arc<std::io::Error>>().unwrap();
+        assert_eq!(root.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_compiler_error_source() {
+        use std::error::Error as _;
+
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let err = CompilerError::from(io);
+        assert_eq!(err.to_string(), "IO error: denied");
+        let source = err.source().unwrap().downcast_ref::<Arc<std::io::Error>>().unwrap();
+        assert_eq!(source.kind(), std::io::ErrorKind::PermissionDenied);
+
+        let json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let (line, column) = (json.line(), json.column());
+        let err = CompilerError::from(json);
+        let source = err.source().unwrap().downcast_ref::<Arc<serde_json::Error>>().unwrap();
+        assert_eq!((source.line(), source.column()), (line, column));
+
+        let yaml = serde_yaml::from_str::<serde_yaml::Value>("a: [").unwrap_err();
+        let message = yaml.to_string();
+        let err = CompilerError::from(yaml);
+        let source = err.source().unwrap().downcast_ref::<Arc<serde_yaml::Error>>().unwrap();
+        assert_eq!(source.to_string(), message);
+
+        // Errors built from a message alone have no source
+        assert!(CompilerError::io("x").source().is_none());
+
+        // Equality and hashing ignore the wrapped error
+        let again = std::io::Error::new(std::io::ErrorKind::NotFound, "denied");
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(CompilerError::from(denied), CompilerError::from(again));
+        assert_eq!(CompilerError::io("denied"), CompilerError::from(std::io::Error::other("denied")));
+        assert_ne!(CompilerError::io("x"), CompilerError::json("x"));
+    }
+
     #[test]
     fn test_error_group_from_errors() {
         let empty: Vec<CompilerError> = vec![];
@@ -14,27 +14,68 @@
 
 //! Extension handler support for vendor extensions.
 
+use crate::codes::ErrorCode;
 use crate::context::Context;
 use crate::error::{CompilerError, Result};
 use serde_yaml::Value as Yaml;
+#[cfg(feature = "extension-handlers")]
 use std::io::Write;
+#[cfg(feature = "extension-handlers")]
 use std::process::{Command, Stdio};
 use std::sync::Arc;
 
 /// ExtensionHandler describes a binary that is called by the compiler to handle specification extensions.
-#[derive(Debug, Clone)]
+///
+/// A handler may be routed the extensions whose names start with given
+/// prefixes. The handlers routed an extension are tried in turn until one
+/// claims it: those with the longest matching prefix first, then the
+/// handlers without prefixes, each group in the order they were added.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExtensionHandler {
     /// Name of the extension handler binary.
     pub name: String,
+    /// Prefixes of the extension names routed to the handler, such as
+    /// "x-google-"; a handler without prefixes is routed every extension.
+    pub prefixes: Vec<String>,
+    /// Whether the handler is called; disabled handlers are skipped.
+    pub enabled: bool,
 }
 
 impl ExtensionHandler {
     /// Creates a new ExtensionHandler.
     pub fn new(name: impl Into<String>) -> Self {
-        ExtensionHandler { name: name.into() }
+        ExtensionHandler { name: name.into(), prefixes: Vec::new(), enabled: true }
+    }
+
+    /// Returns the handler with a prefix of the extension names routed to it.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    /// Returns the handler enabled or disabled.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Returns the length of the longest prefix that routes an extension to
+    /// the handler, which is 0 for handlers without prefixes, or None if the
+    /// handler is disabled or not routed the extension.
+    pub fn route(&self, extension_name: &str) -> Option<usize> {
+        if !self.enabled {
+            return None;
+        }
+        if self.prefixes.is_empty() {
+            return Some(0);
+        }
+        self.prefixes.iter().filter(|prefix| extension_name.starts_with(prefix.as_str())).map(String::len).max()
     }
 
     /// Handles an extension by calling the external binary.
+    ///
+    /// Without the `extension-handlers` feature, handlers with a name fail
+    /// with an error instead of starting a process.
     pub fn handle(&self, node: &Yaml, extension_name: &str) -> Result<Option<Vec<u8>>> {
         if self.name.is_empty() {
             return Ok(None);
@@ -42,7 +83,10 @@ impl ExtensionHandler {
 
         // Serialize the YAML node
         let yaml_str = serde_yaml::to_string(node)
-            .map_err(|e| CompilerError::Yaml(format!("Failed to serialize YAML: {}", e)))?;
+            .map_err(|e| CompilerError::Yaml {
+                message: format!("Failed to serialize YAML: {}", e),
+                source: Some(Arc::new(e)),
+            })?;
 
         // Build request (simplified - in real implementation this would use protobuf)
         // For now, we'll pass YAML directly and expect YAML back
@@ -56,26 +100,35 @@ impl ExtensionHandler {
                 .join("\n")
         );
 
+        self.run(&request)
+    }
+
+    /// Runs the handler binary with a request on its standard input.
+    #[cfg(feature = "extension-handlers")]
+    fn run(&self, request: &str) -> Result<Option<Vec<u8>>> {
         // Call the external handler
         let mut child = Command::new(&self.name)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
-            .map_err(|e| {
-                CompilerError::Io(format!("Failed to spawn extension handler {}: {}", self.name, e))
+            .map_err(|e| CompilerError::Io {
+                message: format!("Failed to spawn extension handler {}: {}", self.name, e),
+                source: Some(Arc::new(e)),
             })?;
 
         // Write request to stdin
         if let Some(mut stdin) = child.stdin.take() {
-            stdin.write_all(request.as_bytes()).map_err(|e| {
-                CompilerError::Io(format!("Failed to write to extension handler: {}", e))
+            stdin.write_all(request.as_bytes()).map_err(|e| CompilerError::Io {
+                message: format!("Failed to write to extension handler: {}", e),
+                source: Some(Arc::new(e)),
             })?;
         }
 
         // Wait for output
-        let output = child.wait_with_output().map_err(|e| {
-            CompilerError::Io(format!("Failed to get extension handler output: {}", e))
+        let output = child.wait_with_output().map_err(|e| CompilerError::Io {
+            message: format!("Failed to get extension handler output: {}", e),
+            source: Some(Arc::new(e)),
         })?;
 
         if !output.status.success() {
@@ -92,6 +145,15 @@ impl ExtensionHandler {
 
         Ok(Some(output.stdout))
     }
+
+    /// Reports that running handlers needs the `extension-handlers` feature.
+    #[cfg(not(feature = "extension-handlers"))]
+    fn run(&self, _request: &str) -> Result<Option<Vec<u8>>> {
+        Err(CompilerError::Simple(format!(
+            "Cannot run extension handler {}: gnostic-compiler was built without the `extension-handlers` feature",
+            self.name
+        )))
+    }
 }
 
 /// Calls extension handlers for a given extension.
@@ -105,7 +167,7 @@ pub fn call_extension(
         None => return Ok((false, None)),
     };
 
-    for handler in handlers.iter() {
+    for handler in routed_handlers(handlers, extension_name) {
         match handler.handle(node, extension_name)? {
             Some(response) => return Ok((true, Some(response))),
             None => continue,
@@ -115,6 +177,37 @@ pub fn call_extension(
     Ok((false, None))
 }
 
+/// Returns the handlers routed an extension, in the order they are tried.
+fn routed_handlers<'a>(handlers: &'a [ExtensionHandler], extension_name: &str) -> Vec<&'a ExtensionHandler> {
+    let mut routed: Vec<(usize, &ExtensionHandler)> =
+        handlers.iter().filter_map(|handler| Some((handler.route(extension_name)?, handler))).collect();
+    // The sort is stable, so handlers with equal prefixes keep their order
+    routed.sort_by_key(|(length, _)| std::cmp::Reverse(*length));
+    routed.into_iter().map(|(_, handler)| handler).collect()
+}
+
+/// Calls the extension handlers for an extension, decoding the response of
+/// the handler that claims it as a protobuf message.
+///
+/// Returns None when no handler claims the extension, in which case parsers
+/// keep the YAML of its value.
+pub fn handle_extension<M: prost::Message + Default>(
+    context: &Context,
+    node: &Yaml,
+    extension_name: &str,
+) -> Result<Option<M>> {
+    let (true, Some(response)) = call_extension(context, node, extension_name)? else {
+        return Ok(None);
+    };
+    M::decode(response.as_slice()).map(Some).map_err(|e| {
+        CompilerError::new_with_code(
+            context,
+            ErrorCode::Protobuf,
+            format!("has an invalid response from its extension handler: {}", e),
+        )
+    })
+}
+
 /// Creates extension handlers from a context.
 pub fn get_extension_handlers(context: &Context) -> Option<Arc<Vec<ExtensionHandler>>> {
     context.extension_handlers.clone()
@@ -130,6 +223,27 @@ mod tests {
         assert_eq!(handler.name, "test-handler");
     }
 
+    #[test]
+    fn test_routed_handlers() {
+        let handlers = [
+            ExtensionHandler::new("fallback"),
+            ExtensionHandler::new("google").with_prefix("x-google-"),
+            ExtensionHandler::new("internal").with_prefix("x-internal-").with_prefix("x-private-"),
+            ExtensionHandler::new("backend").with_prefix("x-google-backend"),
+            ExtensionHandler::new("disabled").with_prefix("x-google-").with_enabled(false),
+            ExtensionHandler::new("second-fallback"),
+        ];
+        let names = |extension_name: &str| -> Vec<&str> {
+            routed_handlers(&handlers, extension_name).iter().map(|handler| handler.name.as_str()).collect()
+        };
+        assert_eq!(names("x-google-backend"), ["backend", "google", "fallback", "second-fallback"]);
+        assert_eq!(names("x-google-endpoints"), ["google", "fallback", "second-fallback"]);
+        assert_eq!(names("x-private-notes"), ["internal", "fallback", "second-fallback"]);
+        assert_eq!(names("x-other"), ["fallback", "second-fallback"]);
+        assert_eq!(handlers[2].route("x-internal-id"), Some(11));
+        assert_eq!(handlers[4].route("x-google-backend"), None);
+    }
+
     #[test]
     fn test_extension_handler_empty_name() {
         let handler = ExtensionHandler::new("");
@@ -0,0 +1,212 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Filtering of the operations of a document.
+//!
+//! The filters take a subset of a document, such as the public operations of
+//! an internal API, from the YAML of an OpenAPI v2 or v3 document. Path items
+//! left without operations are removed; [`remove_unused_components`] then
+//! removes the components that only the removed operations used.
+
+use crate::graph::ReferenceGraph;
+use crate::lint::METHODS;
+use crate::reader::pointer_segments;
+use regex::Regex;
+use serde_yaml::{Mapping, Value as Yaml};
+
+/// Returns the document with only the operations tagged with one of `tags`,
+/// and only the tag definitions of `tags`.
+pub fn filter_by_tags<S: AsRef<str>>(node: &Yaml, tags: &[S]) -> Yaml {
+    let selected = |tag: &Yaml| tag.as_str().is_some_and(|tag| tags.iter().any(|t| t.as_ref() == tag));
+    let mut filtered = node.clone();
+    if let Some(Yaml::Mapping(paths)) = filtered.get_mut("paths") {
+        for item in paths.values_mut() {
+            if let Yaml::Mapping(item) = item {
+                item.retain(|key, operation| {
+                    let is_operation = key.as_str().is_some_and(|key| METHODS.contains(&key));
+                    !is_operation || operation["tags"].as_sequence().is_some_and(|t| t.iter().any(selected))
+                });
+            }
+        }
+    }
+    if let Some(Yaml::Sequence(definitions)) = filtered.get_mut("tags") {
+        definitions.retain(|definition| selected(&definition["name"]));
+    }
+    remove_empty_path_items(node, filtered)
+}
+
+/// Returns the document without the path items whose paths match `pattern`.
+pub fn remove_paths_matching(node: &Yaml, pattern: &Regex) -> Yaml {
+    let mut filtered = node.clone();
+    if let Some(Yaml::Mapping(paths)) = filtered.get_mut("paths") {
+        paths.retain(|path, _| !path.as_str().is_some_and(|path| pattern.is_match(path)));
+    }
+    filtered
+}
+
+/// Returns the document without the objects marked with an extension whose
+/// name starts with `prefix`, such as operations, properties and parameters
+/// with "x-internal: true", and without the remaining extensions with that
+/// prefix.
+///
+/// Removed properties are also removed from the "required" lists of their
+/// schemas.
+pub fn strip_extension_prefix(node: &Yaml, prefix: &str) -> Yaml {
+    let stripped = strip(node, prefix);
+    remove_empty_path_items(node, stripped)
+}
+
+/// Returns the document without the components that cannot be reached from
+/// the rest of it, where components are the entries of the mappings at
+/// `sections`, such as `&[&["components", "schemas"]]`.
+pub fn remove_unused_components(node: &Yaml, sections: &[&[&str]]) -> Yaml {
+    let graph = ReferenceGraph::new(node, sections);
+    let mut pruned = node.clone();
+    for component in graph.unused() {
+        let mut segments = pointer_segments(component);
+        let Some(name) = segments.pop() else {
+            continue;
+        };
+        let section = segments.iter().try_fold(&mut pruned, |node, key| node.get_mut(key.as_str()));
+        if let Some(Yaml::Mapping(entries)) = section {
+            entries.shift_remove(name.as_str());
+        }
+    }
+    pruned
+}
+
+/// Returns whether an object is marked with a true extension starting with `prefix`.
+fn is_marked(node: &Yaml, prefix: &str) -> bool {
+    node.as_mapping().is_some_and(|map| {
+        map.iter().any(|(key, value)| key.as_str().is_some_and(|key| key.starts_with(prefix)) && value == &true)
+    })
+}
+
+fn strip(node: &Yaml, prefix: &str) -> Yaml {
+    match node {
+        Yaml::Mapping(map) => {
+            let mut stripped = Mapping::new();
+            for (key, value) in map {
+                if key.as_str().is_some_and(|key| key.starts_with(prefix)) || is_marked(value, prefix) {
+                    continue;
+                }
+                stripped.insert(key.clone(), strip(value, prefix));
+            }
+            if let (Some(Yaml::Mapping(properties)), Some(Yaml::Sequence(required))) =
+                (stripped.get("properties"), stripped.get("required"))
+            {
+                let original = map.get("properties");
+                let removed = |name: &Yaml| {
+                    original.is_some_and(|original| original.get(name).is_some()) && !properties.contains_key(name)
+                };
+                let required: Vec<Yaml> = required.iter().filter(|name| !removed(name)).cloned().collect();
+                stripped.insert(Yaml::String("required".to_string()), Yaml::Sequence(required));
+            }
+            Yaml::Mapping(stripped)
+        }
+        Yaml::Sequence(items) => Yaml::Sequence(
+            items.iter().filter(|item| !is_marked(item, prefix)).map(|item| strip(item, prefix)).collect(),
+        ),
+        _ => node.clone(),
+    }
+}
+
+/// Removes the path items of `filtered` that have lost all the operations
+/// they had in `node`.
+fn remove_empty_path_items(node: &Yaml, mut filtered: Yaml) -> Yaml {
+    let has_operations = |item: &Yaml| METHODS.iter().any(|method| item.get(*method).is_some());
+    if let Some(Yaml::Mapping(paths)) = filtered.get_mut("paths") {
+        paths.retain(|path, item| has_operations(item) || !node["paths"].get(path).is_some_and(has_operations));
+    }
+    filtered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r##"
+openapi: 3.0.3
+tags: [{name: public}, {name: admin}]
+paths:
+  /pets:
+    get:
+      tags: [public]
+      responses:
+        "200": {content: {application/json: {schema: {$ref: "#/components/schemas/Pets"}}}}
+    post:
+      tags: [admin]
+      x-internal: true
+      requestBody: {content: {application/json: {schema: {$ref: "#/components/schemas/NewPet"}}}}
+  /admin/users:
+    parameters:
+      - {name: limit, in: query}
+    get:
+      tags: [admin]
+      responses:
+        "200": {content: {application/json: {schema: {$ref: "#/components/schemas/User"}}}}
+components:
+  schemas:
+    Pets: {type: array, items: {$ref: "#/components/schemas/Pet"}}
+    Pet:
+      required: [id, secret]
+      x-internal-owner: pets-team
+      properties:
+        id: {type: integer}
+        secret: {type: string, x-internal: true}
+    NewPet: {properties: {name: {type: string}}}
+    User: {properties: {pet: {$ref: "#/components/schemas/Pet"}}}
+"##;
+
+    const SECTIONS: &[&[&str]] = &[&["components", "schemas"]];
+
+    fn node() -> Yaml {
+        serde_yaml::from_str(DOCUMENT).unwrap()
+    }
+
+    fn keys(node: &Yaml) -> Vec<&str> {
+        node.as_mapping().unwrap().keys().filter_map(Yaml::as_str).collect()
+    }
+
+    #[test]
+    fn test_filter_by_tags() {
+        let filtered = filter_by_tags(&node(), &["public"]);
+        assert_eq!(keys(&filtered["paths"]), ["/pets"]);
+        assert_eq!(keys(&filtered["paths"]["/pets"]), ["get"]);
+        assert_eq!(filtered["tags"].as_sequence().unwrap().len(), 1);
+
+        let pruned = remove_unused_components(&filtered, SECTIONS);
+        assert_eq!(keys(&pruned["components"]["schemas"]), ["Pets", "Pet"]);
+        assert_eq!(remove_unused_components(&node(), SECTIONS), node());
+    }
+
+    #[test]
+    fn test_remove_paths_matching() {
+        let filtered = remove_paths_matching(&node(), &Regex::new("^/admin/").unwrap());
+        assert_eq!(keys(&filtered["paths"]), ["/pets"]);
+        let pruned = remove_unused_components(&filtered, SECTIONS);
+        assert_eq!(keys(&pruned["components"]["schemas"]), ["Pets", "Pet", "NewPet"]);
+    }
+
+    #[test]
+    fn test_strip_extension_prefix() {
+        let stripped = strip_extension_prefix(&node(), "x-internal");
+        assert_eq!(keys(&stripped["paths"]["/pets"]), ["get"]);
+        let pet = &stripped["components"]["schemas"]["Pet"];
+        assert_eq!(keys(pet), ["required", "properties"]);
+        assert_eq!(keys(&pet["properties"]), ["id"]);
+        assert_eq!(pet["required"], serde_yaml::from_str::<Yaml>("[id]").unwrap());
+        assert_eq!(remove_unused_components(&stripped, SECTIONS)["components"]["schemas"].get("NewPet"), None);
+    }
+}
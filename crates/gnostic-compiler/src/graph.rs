@@ -0,0 +1,247 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The graph of $ref edges in a document.
+//!
+//! Components are the reusable definitions of a document, such as
+//! "#/components/schemas/Pet" in OpenAPI v3 or "#/definitions/Pet" in
+//! Swagger 2.0. The graph records every reference and the components it
+//! connects, so that callers can find what uses a component, which
+//! components nothing uses, and an order in which to generate them.
+
+use crate::reader::{pointer_for_segments, pointer_segments};
+use serde_yaml::Value as Yaml;
+use std::collections::HashSet;
+
+/// A $ref in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceEdge {
+    /// JSON pointer to the node containing the $ref, such as
+    /// "#/paths/~1pets/get/responses/200".
+    pub location: String,
+    /// The component containing the $ref, if any.
+    pub from: Option<String>,
+    /// The reference as written.
+    pub reference: String,
+    /// The component the reference points to or into; None for external
+    /// references and references to other parts of the document.
+    pub to: Option<String>,
+}
+
+/// The components of a document and the references between them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReferenceGraph {
+    components: Vec<String>,
+    edges: Vec<ReferenceEdge>,
+}
+
+impl ReferenceGraph {
+    /// Builds the graph of a document whose components are the entries of
+    /// the mappings at `sections`, such as `&[&["components", "schemas"]]`.
+    pub fn new(node: &Yaml, sections: &[&[&str]]) -> Self {
+        let mut components = Vec::new();
+        for section in sections {
+            let mut current = node;
+            for key in *section {
+                current = &current[*key];
+            }
+            if let Yaml::Mapping(map) = current {
+                for name in map.keys().filter_map(Yaml::as_str) {
+                    let mut path: Vec<&str> = section.to_vec();
+                    path.push(name);
+                    components.push(format!("#{}", pointer_for_segments(&path)));
+                }
+            }
+        }
+        let mut graph = ReferenceGraph {
+            components,
+            edges: Vec::new(),
+        };
+        graph.collect(node, &mut Vec::new());
+        graph
+    }
+
+    /// Returns the components, in the order they are declared.
+    pub fn components(&self) -> &[String] {
+        &self.components
+    }
+
+    /// Returns every reference in the document, in document order.
+    pub fn edges(&self) -> &[ReferenceEdge] {
+        &self.edges
+    }
+
+    /// Returns the references that point to or into `component`.
+    pub fn referrers(&self, component: &str) -> Vec<&ReferenceEdge> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.to.as_deref() == Some(component))
+            .collect()
+    }
+
+    /// Returns the components that `component` refers to directly.
+    pub fn dependencies(&self, component: &str) -> Vec<&str> {
+        let mut dependencies = Vec::new();
+        for edge in &self.edges {
+            if edge.from.as_deref() != Some(component) {
+                continue;
+            }
+            if let Some(to) = edge.to.as_deref() {
+                if !dependencies.contains(&to) {
+                    dependencies.push(to);
+                }
+            }
+        }
+        dependencies
+    }
+
+    /// Returns the components that cannot be reached from the rest of the
+    /// document, such as its paths.
+    ///
+    /// A component used only by other unused components is itself unused,
+    /// so all of them can be removed together.
+    pub fn unused(&self) -> Vec<&str> {
+        let mut reachable = HashSet::new();
+        let mut pending: Vec<&str> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.from.is_none())
+            .filter_map(|edge| edge.to.as_deref())
+            .collect();
+        while let Some(component) = pending.pop() {
+            if reachable.insert(component) {
+                pending.extend(self.dependencies(component));
+            }
+        }
+        self.components
+            .iter()
+            .map(String::as_str)
+            .filter(|component| !reachable.contains(component))
+            .collect()
+    }
+
+    /// Returns the components ordered so that each follows the components
+    /// it depends on. Components in a cycle keep their declaration order.
+    pub fn dependency_order(&self) -> Vec<&str> {
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        for component in &self.components {
+            self.visit(component, &mut visited, &mut order);
+        }
+        order
+    }
+
+    fn visit<'a>(&'a self, component: &'a str, visited: &mut HashSet<&'a str>, order: &mut Vec<&'a str>) {
+        if !visited.insert(component) {
+            return;
+        }
+        for dependency in self.dependencies(component) {
+            self.visit(dependency, visited, order);
+        }
+        order.push(component);
+    }
+
+    fn collect(&mut self, node: &Yaml, path: &mut Vec<String>) {
+        match node {
+            Yaml::Mapping(map) => {
+                if let Some(reference) = map.get("$ref").and_then(Yaml::as_str) {
+                    let location = format!("#{}", pointer_for_segments(path));
+                    self.edges.push(ReferenceEdge {
+                        from: self.component_containing(&location),
+                        // Compare the canonical form of internal references
+                        to: reference
+                            .strip_prefix('#')
+                            .map(|pointer| format!("#{}", pointer_for_segments(&pointer_segments(pointer))))
+                            .and_then(|pointer| self.component_containing(&pointer)),
+                        location,
+                        reference: reference.to_string(),
+                    });
+                }
+                for (key, value) in map {
+                    path.push(key.as_str().map(str::to_string).unwrap_or_default());
+                    self.collect(value, path);
+                    path.pop();
+                }
+            }
+            Yaml::Sequence(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    path.push(i.to_string());
+                    self.collect(item, path);
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the component that `pointer` names or points into.
+    fn component_containing(&self, pointer: &str) -> Option<String> {
+        self.components
+            .iter()
+            .find(|component| {
+                pointer
+                    .strip_prefix(component.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(text: &str) -> ReferenceGraph {
+        ReferenceGraph::new(&serde_yaml::from_str(text).unwrap(), &[&["defs"]])
+    }
+
+    #[test]
+    fn test_reference_graph() {
+        let graph = graph(
+            "paths:\n  /pets:\n    $ref: '#/defs/Pets'\ndefs:\n  Pet:\n    properties:\n      owner:\n        $ref: '#/defs/Owner'\n  Pets:\n    items:\n      $ref: '#/defs/Pet'\n  Owner:\n    type: string\n  Unused:\n    $ref: '#/defs/Orphan'\n  Orphan:\n    $ref: 'other.yaml#/Orphan'\n",
+        );
+        assert_eq!(graph.components(), ["#/defs/Pet", "#/defs/Pets", "#/defs/Owner", "#/defs/Unused", "#/defs/Orphan"]);
+        assert_eq!(graph.edges().len(), 5);
+        assert_eq!(graph.edges()[0].location, "#/paths/~1pets");
+        assert_eq!(graph.edges()[0].from, None);
+        assert_eq!(graph.edges()[4].to, None);
+
+        let referrers: Vec<_> = graph.referrers("#/defs/Pet").iter().map(|e| e.location.as_str()).collect();
+        assert_eq!(referrers, ["#/defs/Pets/items"]);
+        assert_eq!(graph.dependencies("#/defs/Pet"), ["#/defs/Owner"]);
+        assert_eq!(graph.unused(), ["#/defs/Unused", "#/defs/Orphan"]);
+        assert_eq!(
+            graph.dependency_order(),
+            ["#/defs/Owner", "#/defs/Pet", "#/defs/Pets", "#/defs/Orphan", "#/defs/Unused"]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let graph = graph(
+            "root:\n  $ref: '#/defs/A/properties/b'\ndefs:\n  A:\n    properties:\n      b:\n        $ref: '#/defs/B'\n  B:\n    properties:\n      a:\n        $ref: '#/defs/A'\n  AB:\n    type: string\n",
+        );
+        // A reference into a component counts as a reference to it
+        assert_eq!(graph.edges()[0].to.as_deref(), Some("#/defs/A"));
+        assert_eq!(graph.unused(), ["#/defs/AB"]);
+        assert_eq!(graph.dependency_order(), ["#/defs/B", "#/defs/A", "#/defs/AB"]);
+    }
+
+    #[test]
+    fn test_escaped_references() {
+        let graph = graph("a:\n  $ref: '#/defs/Pet~1Item'\nb:\n  $ref: '#/defs/Pet%7E1Item'\ndefs:\n  Pet/Item:\n    type: string\n");
+        assert_eq!(graph.components(), ["#/defs/Pet~1Item"]);
+        assert_eq!(graph.referrers("#/defs/Pet~1Item").len(), 2);
+    }
+}
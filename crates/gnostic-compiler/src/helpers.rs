@@ -13,10 +13,17 @@
 // limitations under the License.
 
 //! Helper functions for YAML node manipulation.
+//!
+//! YAML mappings are backed by an insertion-ordered map, so every helper that
+//! walks a mapping visits keys in the order they appear in the source document.
+//! Only `sorted_keys_for_map` reorders keys.
 
 use regex::Regex;
 use serde_yaml::Value as Yaml;
 
+use crate::node::Node;
+use crate::reader::pointer_segments;
+
 /// Checks if a YAML node is a mapping (map/object).
 pub fn is_mapping(node: &Yaml) -> bool {
     matches!(node, Yaml::Mapping(_))
@@ -27,10 +34,10 @@ pub fn is_sequence(node: &Yaml) -> bool {
     matches!(node, Yaml::Sequence(_))
 }
 
-/// Checks if a YAML node is a scalar value.
+/// Checks if a YAML node is a scalar value (including tagged scalars).
 pub fn is_scalar(node: &Yaml) -> bool {
     matches!(
-        node,
+        untag(node),
         Yaml::String(_)
             | Yaml::Number(_)
             | Yaml::Bool(_)
@@ -38,6 +45,26 @@ pub fn is_scalar(node: &Yaml) -> bool {
     )
 }
 
+/// Returns the value wrapped by any YAML tags on a node.
+///
+/// Standard tags such as `!!timestamp` and `!!binary` are resolved by the loader,
+/// but application-specific tags (`!include`, `!custom`) wrap the node they annotate.
+pub fn untag(node: &Yaml) -> &Yaml {
+    let mut node = node;
+    while let Yaml::Tagged(tagged) = node {
+        node = &tagged.value;
+    }
+    node
+}
+
+/// Returns the tag of a tagged node, without the leading `!`.
+pub fn tag_for_node(node: &Yaml) -> Option<String> {
+    match node {
+        Yaml::Tagged(tagged) => Some(tagged.tag.to_string().trim_start_matches('!').to_string()),
+        _ => None,
+    }
+}
+
 /// Unpacks a YAML node if it's a mapping, returning a reference to the mapping.
 pub fn unpack_map(node: &Yaml) -> Option<&serde_yaml::Mapping> {
     match node {
@@ -46,8 +73,8 @@ pub fn unpack_map(node: &Yaml) -> Option<&serde_yaml::Mapping> {
     }
 }
 
-/// Returns sorted keys from a YAML mapping node.
-pub fn sorted_keys_for_map(node: &Yaml) -> Vec<String> {
+/// Returns keys from a YAML mapping node in document order.
+pub fn keys_for_map(node: &Yaml) -> Vec<String> {
     let mut keys = Vec::new();
     if let Yaml::Mapping(map) = node {
         for key in map.keys() {
@@ -56,6 +83,12 @@ pub fn sorted_keys_for_map(node: &Yaml) -> Vec<String> {
             }
         }
     }
+    keys
+}
+
+/// Returns sorted keys from a YAML mapping node.
+pub fn sorted_keys_for_map(node: &Yaml) -> Vec<String> {
+    let mut keys = keys_for_map(node);
     keys.sort();
     keys
 }
@@ -63,21 +96,105 @@ pub fn sorted_keys_for_map(node: &Yaml) -> Vec<String> {
 /// Checks if a YAML mapping contains a specific key.
 pub fn map_has_key(node: &Yaml, key: &str) -> bool {
     if let Yaml::Mapping(map) = node {
-        map.contains_key(&Yaml::String(key.to_string()))
+        map.contains_key(key)
     } else {
         false
     }
 }
 
 /// Gets the value for a specific key from a YAML mapping.
+///
+/// The lookup borrows the key, so no node is allocated per call and only the
+/// key itself is hashed.
 pub fn map_value_for_key<'a>(node: &'a Yaml, key: &str) -> Option<&'a Yaml> {
     if let Yaml::Mapping(map) = node {
-        map.get(&Yaml::String(key.to_string()))
+        map.get(key)
     } else {
         None
     }
 }
 
+/// An index of the string keys of a mapping node.
+///
+/// Parsers look up every field of a model in each node, which for
+/// [`map_value_for_key`] means hashing each field name even when the node has
+/// only a few keys. The index is built once per node and sorted, so lookups
+/// compare keys without hashing them.
+#[derive(Debug)]
+pub struct KeyIndex<'a, N = Yaml> {
+    entries: Vec<(&'a str, &'a N)>,
+}
+
+impl<'a, N: Node> KeyIndex<'a, N> {
+    /// Indexes the string keys of a node, which is empty if it is not a mapping.
+    pub fn new(node: &'a N) -> Self {
+        let mut entries: Vec<_> = node.entries().collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        KeyIndex { entries }
+    }
+
+    /// Gets the value for a key, as [`map_value_for_key`] does.
+    pub fn get(&self, key: &str) -> Option<&'a N> {
+        self.entries
+            .binary_search_by_key(&key, |(key, _)| *key)
+            .ok()
+            .map(|i| self.entries[i].1)
+    }
+
+    /// Returns true if the node has the key.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the number of string keys.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the node has no string keys.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Gets the value at a nested path within a YAML node.
+///
+/// Paths starting with `/` or `#` are JSON Pointers (RFC 6901), so
+/// `#/paths/~1pets/get` addresses the `get` operation of `/pets`. Other paths are
+/// split on `/` when they contain one (`components/schemas/Pet`) and on `.`
+/// otherwise (`components.schemas.Pet`). Numeric segments index into sequences.
+pub fn yaml_value_at_path<'a>(node: &'a Yaml, path: &str) -> Option<&'a Yaml> {
+    let segments: Vec<String> = if path.starts_with('/') || path.starts_with('#') {
+        pointer_segments(path)
+    } else if path.is_empty() {
+        Vec::new()
+    } else if path.contains('/') {
+        path.split('/').map(unescape_json_pointer_segment).collect()
+    } else {
+        path.split('.').map(str::to_string).collect()
+    };
+
+    let mut current = node;
+    for segment in &segments {
+        current = match untag(current) {
+            Yaml::Mapping(map) => map.get(segment.as_str())?,
+            Yaml::Sequence(arr) => arr.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Unescapes a JSON Pointer segment (`~1` becomes `/`, then `~0` becomes `~`).
+pub fn unescape_json_pointer_segment(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// Escapes a string for use as a JSON Pointer segment.
+pub fn escape_json_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
 /// Gets a sequence node if the node is a sequence.
 pub fn sequence_node_for_node(node: &Yaml) -> Option<&Vec<Yaml>> {
     match node {
@@ -88,7 +205,7 @@ pub fn sequence_node_for_node(node: &Yaml) -> Option<&Vec<Yaml>> {
 
 /// Gets a boolean value from a scalar node.
 pub fn bool_for_scalar_node(node: &Yaml) -> Option<bool> {
-    match node {
+    match untag(node) {
         Yaml::Bool(b) => Some(*b),
         _ => None,
     }
@@ -96,7 +213,7 @@ pub fn bool_for_scalar_node(node: &Yaml) -> Option<bool> {
 
 /// Gets an integer value from a scalar node.
 pub fn int_for_scalar_node(node: &Yaml) -> Option<i64> {
-    match node {
+    match untag(node) {
         Yaml::Number(n) => n.as_i64(),
         _ => None,
     }
@@ -104,23 +221,23 @@ pub fn int_for_scalar_node(node: &Yaml) -> Option<i64> {
 
 /// Gets a float value from a scalar node.
 pub fn float_for_scalar_node(node: &Yaml) -> Option<f64> {
-    match node {
+    match untag(node) {
         Yaml::Number(n) => n.as_f64(),
         _ => None,
     }
 }
 
 /// Gets a string value from a scalar node.
+///
+/// Timestamps are returned in their original text form.
 pub fn string_for_scalar_node(node: &Yaml) -> Option<String> {
-    match node {
+    match untag(node) {
         Yaml::String(s) => Some(s.clone()),
         Yaml::Number(n) => {
             if let Some(i) = n.as_i64() {
                 Some(i.to_string())
-            } else if let Some(f) = n.as_f64() {
-                Some(f.to_string())
             } else {
-                None
+                n.as_f64().map(|f| f.to_string())
             }
         }
         Yaml::Bool(b) => Some(b.to_string()),
@@ -129,6 +246,42 @@ pub fn string_for_scalar_node(node: &Yaml) -> Option<String> {
     }
 }
 
+/// Gets the bytes of a base64-encoded scalar node, such as a `!!binary` value.
+///
+/// Whitespace (including the line breaks of block scalars) is ignored.
+pub fn bytes_for_scalar_node(node: &Yaml) -> Option<Vec<u8>> {
+    match untag(node) {
+        Yaml::String(s) => decode_base64(s),
+        _ => None,
+    }
+}
+
+/// Decodes standard base64, returning None for invalid input.
+fn decode_base64(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in s.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
 /// Converts a sequence node to an array of strings.
 pub fn string_array_for_sequence_node(node: &Yaml) -> Vec<String> {
     let mut strings = Vec::new();
@@ -189,12 +342,58 @@ pub fn invalid_keys_in_map(
     invalid
 }
 
+/// Returns the edit distance between two strings.
+///
+/// Insertions, deletions, substitutions and swaps of adjacent characters each
+/// count as one edit, so common typos like "titel" are one edit from "title".
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let width = b.len() + 1;
+    let mut d = vec![0; (a.len() + 1) * width];
+    for i in 0..=a.len() {
+        d[i * width] = i;
+    }
+    for (j, cell) in d.iter_mut().enumerate().take(width) {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (d[(i - 1) * width + j] + 1)
+                .min(d[i * width + j - 1] + 1)
+                .min(d[(i - 1) * width + j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(d[(i - 2) * width + j - 2] + 1);
+            }
+            d[i * width + j] = best;
+        }
+    }
+    d[a.len() * width + b.len()]
+}
+
+/// Returns the candidate closest to `value`, if it is close enough to be a likely typo.
+///
+/// A candidate qualifies when its edit distance is at most a third of the length
+/// of `value` (and at least one edit is always allowed).
+pub fn closest_match<'a>(value: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let threshold = (value.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(value, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
 /// Creates a new null YAML node.
 pub fn new_null_node() -> Yaml {
     Yaml::Null
 }
 
 /// Creates a new mapping (hash) YAML node.
+///
+/// The mapping preserves insertion order, so emitters write keys in the order they were added.
 pub fn new_mapping_node() -> Yaml {
     Yaml::Mapping(serde_yaml::Mapping::new())
 }
@@ -265,7 +464,7 @@ pub fn display(node: &Yaml) -> String {
         Yaml::Null => "null".to_string(),
         Yaml::Sequence(_) => "[array]".to_string(),
         Yaml::Mapping(_) => "{object}".to_string(),
-        _ => format!("{:?}", node),
+        Yaml::Tagged(tagged) => format!("{} {}", tagged.tag, display(&tagged.value)),
     }
 }
 
@@ -277,7 +476,77 @@ pub fn marshal(node: &Yaml) -> Vec<u8> {
     }
 }
 
-/// Iterates over key-value pairs in a YAML mapping.
+/// Converts a YAML node to a JSON value.
+///
+/// Mapping order is preserved. The conversion is lossless for nodes that have a
+/// JSON equivalent; otherwise non-string keys become their YAML text, tags are
+/// dropped in favor of the tagged value, and non-finite floats become null.
+pub fn yaml_to_json(node: &Yaml) -> serde_json::Value {
+    match node {
+        Yaml::Null => serde_json::Value::Null,
+        Yaml::Bool(b) => serde_json::Value::Bool(*b),
+        Yaml::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                serde_json::Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                serde_json::Value::from(u)
+            } else {
+                n.as_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map_or(serde_json::Value::Null, serde_json::Value::Number)
+            }
+        }
+        Yaml::String(s) => serde_json::Value::String(s.clone()),
+        Yaml::Sequence(arr) => serde_json::Value::Array(arr.iter().map(yaml_to_json).collect()),
+        Yaml::Mapping(map) => {
+            let mut object = serde_json::Map::with_capacity(map.len());
+            for (key, value) in map {
+                object.insert(json_key_for_node(key), yaml_to_json(value));
+            }
+            serde_json::Value::Object(object)
+        }
+        Yaml::Tagged(tagged) => yaml_to_json(&tagged.value),
+    }
+}
+
+/// Returns the JSON object key used for a YAML mapping key.
+fn json_key_for_node(key: &Yaml) -> String {
+    match untag(key) {
+        Yaml::String(s) => s.clone(),
+        scalar @ (Yaml::Number(_) | Yaml::Bool(_) | Yaml::Null) => {
+            string_for_scalar_node(scalar).unwrap_or_default()
+        }
+        complex => String::from_utf8_lossy(&marshal(complex)).trim_end().to_string(),
+    }
+}
+
+/// Converts a JSON value to a YAML node, preserving object key order.
+pub fn json_to_yaml(value: &serde_json::Value) -> Yaml {
+    match value {
+        serde_json::Value::Null => Yaml::Null,
+        serde_json::Value::Bool(b) => Yaml::Bool(*b),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Yaml::Number(i.into())
+            } else if let Some(u) = n.as_u64() {
+                Yaml::Number(u.into())
+            } else {
+                Yaml::Number(n.as_f64().unwrap_or(f64::NAN).into())
+            }
+        }
+        serde_json::Value::String(s) => Yaml::String(s.clone()),
+        serde_json::Value::Array(arr) => Yaml::Sequence(arr.iter().map(json_to_yaml).collect()),
+        serde_json::Value::Object(object) => {
+            let mut map = serde_yaml::Mapping::with_capacity(object.len());
+            for (key, value) in object {
+                map.insert(Yaml::String(key.clone()), json_to_yaml(value));
+            }
+            Yaml::Mapping(map)
+        }
+    }
+}
+
+/// Iterates over key-value pairs in a YAML mapping, in document order.
 pub fn iter_map<F>(node: &Yaml, mut f: F)
 where
     F: FnMut(&str, &Yaml),
@@ -340,6 +609,74 @@ mod tests {
         assert!(missing.is_none());
     }
 
+    #[test]
+    fn test_key_index() {
+        let yaml = parse_yaml("type: object\ntitle: Pet\n1: one\ndescription: A pet");
+        let index = KeyIndex::new(&yaml);
+        assert_eq!(index.len(), 3);
+        for key in ["type", "title", "description", "missing", "1"] {
+            assert_eq!(index.get(key), map_value_for_key(&yaml, key), "{}", key);
+        }
+        assert!(index.contains_key("title"));
+        assert!(KeyIndex::new(&parse_yaml("- type")).is_empty());
+    }
+
+    #[test]
+    fn test_yaml_value_at_path() {
+        let yaml = parse_yaml(
+            "components:\n  schemas:\n    Pet:\n      properties:\n        id:\n          type: integer\n\
+             paths:\n  /pets/{id}:\n    get:\n      tags: [pets, read]\n",
+        );
+        let expected = Some(&Yaml::String("integer".to_string()));
+        assert_eq!(yaml_value_at_path(&yaml, "components/schemas/Pet/properties/id/type"), expected);
+        assert_eq!(yaml_value_at_path(&yaml, "components.schemas.Pet.properties.id.type"), expected);
+        assert_eq!(yaml_value_at_path(&yaml, "/components/schemas/Pet/properties/id/type"), expected);
+        assert_eq!(yaml_value_at_path(&yaml, "#/components/schemas/Pet/properties/id/type"), expected);
+
+        let tag = yaml_value_at_path(&yaml, "#/paths/~1pets~1{id}/get/tags/1");
+        assert_eq!(tag, Some(&Yaml::String("read".to_string())));
+
+        assert_eq!(yaml_value_at_path(&yaml, "#"), Some(&yaml));
+        assert_eq!(yaml_value_at_path(&yaml, ""), Some(&yaml));
+        assert!(yaml_value_at_path(&yaml, "components/schemas/Missing").is_none());
+        assert!(yaml_value_at_path(&yaml, "#/paths/~1pets~1{id}/get/tags/9").is_none());
+    }
+
+    #[test]
+    fn test_json_pointer_escaping() {
+        assert_eq!(escape_json_pointer_segment("/pets/~x"), "~1pets~1~0x");
+        assert_eq!(unescape_json_pointer_segment("~01"), "~1");
+    }
+
+    #[test]
+    fn test_yaml_json_round_trip() {
+        let yaml = parse_yaml("z: [1, -2, 2.5, true, null]\na:\n  nested: text\nbig: 18446744073709551615");
+        let json = yaml_to_json(&yaml);
+        assert_eq!(
+            json.to_string(),
+            r#"{"z":[1,-2,2.5,true,null],"a":{"nested":"text"},"big":18446744073709551615}"#
+        );
+        assert_eq!(json_to_yaml(&json), yaml);
+    }
+
+    #[test]
+    fn test_yaml_to_json_lossy_cases() {
+        let yaml = parse_yaml("1: one\ntrue: yes\nvalue: !custom 5\ninf: .inf");
+        let json = yaml_to_json(&yaml);
+        assert_eq!(json["1"], serde_json::json!("one"));
+        assert_eq!(json["true"], serde_json::json!("yes"));
+        assert_eq!(json["value"], serde_json::json!(5));
+        assert_eq!(json["inf"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_map_has_key() {
+        let yaml = parse_yaml("name: test\n1: one");
+        assert!(map_has_key(&yaml, "name"));
+        assert!(!map_has_key(&yaml, "1"));
+        assert!(!map_has_key(&parse_yaml("- name"), "name"));
+    }
+
     #[test]
     fn test_string_for_scalar_node() {
         let yaml = parse_yaml("test");
@@ -349,6 +686,38 @@ mod tests {
         assert_eq!(string_for_scalar_node(&yaml), Some("123".to_string()));
     }
 
+    #[test]
+    fn test_string_for_tagged_scalar_node() {
+        let yaml = parse_yaml("created: !!timestamp 2024-01-01T10:00:00Z\nid: !custom abc");
+        let created = map_value_for_key(&yaml, "created").unwrap();
+        assert_eq!(string_for_scalar_node(created), Some("2024-01-01T10:00:00Z".to_string()));
+
+        let id = map_value_for_key(&yaml, "id").unwrap();
+        assert!(is_scalar(id));
+        assert_eq!(tag_for_node(id), Some("custom".to_string()));
+        assert_eq!(string_for_scalar_node(id), Some("abc".to_string()));
+        assert_eq!(display(id), "!custom abc (string)");
+    }
+
+    #[test]
+    fn test_string_for_date_scalar_node() {
+        let yaml = parse_yaml("example: 2024-01-01");
+        let example = map_value_for_key(&yaml, "example").unwrap();
+        assert_eq!(string_for_scalar_node(example), Some("2024-01-01".to_string()));
+    }
+
+    #[test]
+    fn test_bytes_for_scalar_node() {
+        let yaml = parse_yaml("inline: !!binary aGVsbG8=\nblock: !!binary |\n  aGVs\n  bG8gd29y\n  bGQ=\n");
+        let inline = map_value_for_key(&yaml, "inline").unwrap();
+        assert_eq!(bytes_for_scalar_node(inline), Some(b"hello".to_vec()));
+
+        let block = map_value_for_key(&yaml, "block").unwrap();
+        assert_eq!(bytes_for_scalar_node(block), Some(b"hello world".to_vec()));
+
+        assert_eq!(bytes_for_scalar_node(&parse_yaml("not base64!")), None);
+    }
+
     #[test]
     fn test_bool_for_scalar_node() {
         let yaml = parse_yaml("true");
@@ -365,6 +734,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_float_for_scalar_node() {
         let yaml = parse_yaml("3.14");
         let result = float_for_scalar_node(&yaml);
@@ -386,6 +756,32 @@ mod tests {
         assert_eq!(keys, vec!["a", "m", "z"]);
     }
 
+    #[test]
+    fn test_keys_for_map_preserves_order() {
+        let yaml = parse_yaml("z: 1\na: 2\nm: 3");
+        assert_eq!(keys_for_map(&yaml), vec!["z", "a", "m"]);
+    }
+
+    #[test]
+    fn test_iter_map_preserves_order() {
+        let yaml = parse_yaml("paths: 1\ninfo: 2\nopenapi: 3\ncomponents: 4");
+        let mut keys = Vec::new();
+        iter_map(&yaml, |key, _| keys.push(key.to_string()));
+        assert_eq!(keys, vec!["paths", "info", "openapi", "components"]);
+    }
+
+    #[test]
+    fn test_new_mapping_node_preserves_insertion_order() {
+        let mut node = new_mapping_node();
+        if let Yaml::Mapping(map) = &mut node {
+            map.insert(new_scalar_node_for_string("title"), new_scalar_node_for_string("t"));
+            map.insert(new_scalar_node_for_string("version"), new_scalar_node_for_string("v"));
+            map.insert(new_scalar_node_for_string("description"), new_scalar_node_for_string("d"));
+        }
+        let output = String::from_utf8(marshal(&node)).unwrap();
+        assert_eq!(output, "title: t\nversion: v\ndescription: d\n");
+    }
+
     #[test]
     fn test_missing_keys_in_map() {
         let yaml = parse_yaml("a: 1\nb: 2");
@@ -400,4 +796,22 @@ mod tests {
         let invalid = invalid_keys_in_map(&yaml, &["valid"], &[&pattern]);
         assert_eq!(invalid, vec!["invalid"]);
     }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("summary", "summary"), 0);
+        assert_eq!(edit_distance("operationID", "operationId"), 1);
+        assert_eq!(edit_distance("titel", "title"), 1);
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let keys = ["summary", "description", "operationId"];
+        assert_eq!(closest_match("sumary", &keys), Some("summary"));
+        assert_eq!(closest_match("descripton", &keys), Some("description"));
+        assert_eq!(closest_match("responses", &keys), None);
+        assert_eq!(closest_match("x", &[]), None);
+    }
 }
@@ -0,0 +1,203 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interning of common keys.
+//!
+//! Parsers create a [`Context`](crate::Context) for every node they visit,
+//! named by the key of the node. Most keys are a few dozen keywords such as
+//! "description" and "type", so contexts store them as [`Name`]s, which
+//! refer to a static copy of a common key instead of allocating a string.
+
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+use std::ops::Deref;
+
+/// The keys that are interned, in sorted order.
+const COMMON_KEYS: &[&str] = &[
+    "$ref",
+    "200",
+    "201",
+    "204",
+    "400",
+    "401",
+    "403",
+    "404",
+    "500",
+    "additionalProperties",
+    "allOf",
+    "anyOf",
+    "application/json",
+    "callbacks",
+    "components",
+    "content",
+    "default",
+    "definitions",
+    "delete",
+    "deprecated",
+    "description",
+    "discriminator",
+    "enum",
+    "example",
+    "examples",
+    "format",
+    "get",
+    "head",
+    "headers",
+    "id",
+    "in",
+    "info",
+    "items",
+    "links",
+    "location",
+    "maximum",
+    "methods",
+    "minimum",
+    "name",
+    "not",
+    "nullable",
+    "oneOf",
+    "operationId",
+    "options",
+    "parameters",
+    "patch",
+    "paths",
+    "pattern",
+    "post",
+    "properties",
+    "put",
+    "readOnly",
+    "request",
+    "requestBodies",
+    "requestBody",
+    "required",
+    "resources",
+    "response",
+    "responses",
+    "schema",
+    "schemas",
+    "security",
+    "securitySchemes",
+    "servers",
+    "summary",
+    "tags",
+    "title",
+    "trace",
+    "type",
+    "url",
+    "value",
+    "variables",
+    "version",
+];
+
+/// Returns the static copy of `key` if it is one of the common keys.
+pub fn intern(key: &str) -> Option<&'static str> {
+    COMMON_KEYS.binary_search(&key).ok().map(|i| COMMON_KEYS[i])
+}
+
+/// A name, such as the key of a node, that shares the storage of the common
+/// keys and owns any other string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Name(Cow<'static, str>);
+
+impl Name {
+    /// Returns the name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns true if the name refers to an interned key.
+    pub fn is_interned(&self) -> bool {
+        matches!(self.0, Cow::Borrowed(_))
+    }
+}
+
+impl From<&str> for Name {
+    fn from(name: &str) -> Self {
+        Name(intern(name).map_or_else(|| Cow::Owned(name.to_string()), Cow::Borrowed))
+    }
+}
+
+impl From<&String> for Name {
+    fn from(name: &String) -> Self {
+        Name::from(name.as_str())
+    }
+}
+
+impl From<String> for Name {
+    fn from(name: String) -> Self {
+        Name(intern(&name).map_or(Cow::Owned(name), Cow::Borrowed))
+    }
+}
+
+impl Deref for Name {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Name {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Name {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Name {
+    fn eq(&self, other: &str) -> bool {
+        *self.0 == *other
+    }
+}
+
+impl PartialEq<&str> for Name {
+    fn eq(&self, other: &&str) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_keys_sorted() {
+        assert!(COMMON_KEYS.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_intern() {
+        assert_eq!(intern("description"), Some("description"));
+        assert_eq!(intern("x-internal"), None);
+
+        let name = Name::from("type".to_string());
+        assert!(name.is_interned());
+        assert_eq!(name, "type");
+        let name = Name::from("Pet");
+        assert!(!name.is_interned());
+        assert_eq!(name.as_str(), "Pet");
+        assert_eq!(format!("{}.{}", Name::from("$"), name), "$.Pet");
+    }
+}
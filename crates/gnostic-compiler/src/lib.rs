@@ -18,14 +18,79 @@
 //! including YAML node manipulation, error handling, file reading with caching, and
 //! extension handler support.
 
+pub mod borrowed;
+pub mod changelog;
+pub mod checks;
+pub mod codes;
+pub mod comments;
 pub mod context;
+#[cfg(feature = "miette")]
+pub mod diagnostic;
+pub mod diff;
+pub mod emitter;
 pub mod error;
 pub mod extensions;
+pub mod filter;
+pub mod graph;
 pub mod helpers;
+pub mod intern;
+pub mod limits;
+pub mod lint;
+pub mod named;
+pub mod node;
+pub mod options;
+pub mod patch;
+pub mod policy;
+pub mod postman;
+pub mod raw_info;
 pub mod reader;
+pub mod report;
+pub mod resolver;
+pub mod split;
+pub mod vendor;
+pub mod vocabulary;
+pub mod watch;
+pub mod writer;
 
+pub use borrowed::{read_borrowed_json, BorrowedNode};
+pub use changelog::{Changelog, ChangelogEntry, ChangelogSection, Subject};
+pub use checks::{
+    check_operation_ids, check_path_parameters, check_responses, check_security, local_target, operations,
+    template_names, ValidateOptions,
+};
+pub use codes::ErrorCode;
+pub use comments::{Comments, NodeComments};
 pub use context::Context;
-pub use error::{CompilerError, ErrorGroup, Result};
-pub use extensions::ExtensionHandler;
+#[cfg(feature = "miette")]
+pub use diagnostic::{SourcedError, SourcedErrorGroup};
+pub use diff::{diff_documents, Change, ChangeKind, Changeset, Direction};
+pub use emitter::{emit_yaml, QuoteStyle, SequenceStyle};
+pub use error::{CompilerError, ErrorGroup, Parsed, Result, Severity};
+pub use extensions::{handle_extension, ExtensionHandler};
+pub use filter::{filter_by_tags, remove_paths_matching, remove_unused_components, strip_extension_prefix};
+pub use graph::{ReferenceEdge, ReferenceGraph};
 pub use helpers::*;
+pub use intern::{intern, Name};
+pub use limits::{check_depth, limits, set_limits, Limits};
+pub use lint::{LintFinding, LintReport, Linter, Rule};
+pub use named::{NamedEntry, NamedMap, NamedValue};
+pub use node::Node;
+pub use options::{ParseOptions, RefSiblings};
+pub use patch::{apply_json_patch, apply_merge_patch};
+pub use policy::FetchPolicy;
+pub use postman::{postman_collection, POSTMAN_SCHEMA_URL};
+pub use raw_info::RawInfo;
 pub use reader::*;
+pub use report::{Finding, ValidationReport};
+pub use resolver::{
+    bundle_references, flatten_references, resolve_for_parse, resolve_references, CycleStrategy, ResolutionReport,
+    ResolveOptions,
+};
+pub use split::{split_document, SplitLayout, SplitOptions};
+pub use vendor::{
+    find_vendor_extensions, AmazonApigatewayIntegration, AmazonApigatewayIntegrationResponse, CodegenSettings,
+    GoogleBackend, GoogleEndpoint, VendorExtension, VENDOR_EXTENSIONS,
+};
+pub use vocabulary::Vocabulary;
+pub use watch::{FileWatcher, DEFAULT_WATCH_INTERVAL};
+pub use writer::{document_to_yaml, EmitOptions, KeyOrder, ToYaml};
@@ -0,0 +1,207 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resource limits for loading and parsing untrusted documents.
+//!
+//! Each parse uses the limits of its [`ParseOptions`](crate::ParseOptions),
+//! or the default limits set with [`set_limits`] if the options have none.
+
+use crate::codes::ErrorCode;
+use crate::context::Context;
+use crate::error::{CompilerError, Result};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde_yaml::Value as Yaml;
+
+/// Limits applied by the loader and the recursive parse functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum nesting depth of mappings and sequences.
+    pub max_depth: usize,
+    /// Maximum number of nodes in a document.
+    pub max_nodes: usize,
+    /// Maximum size of an input document in bytes.
+    pub max_input_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_depth: 128,
+            max_nodes: 10_000_000,
+            max_input_size: 1 << 30,
+        }
+    }
+}
+
+/// Default limits used by the loader (thread-safe).
+static LIMITS: Lazy<RwLock<Limits>> = Lazy::new(|| RwLock::new(Limits::default()));
+
+/// Sets the default limits, used by parses whose options set none and by
+/// reads of external documents.
+pub fn set_limits(limits: Limits) {
+    *LIMITS.write() = limits;
+}
+
+/// Returns the default limits.
+pub fn limits() -> Limits {
+    *LIMITS.read()
+}
+
+impl Limits {
+    /// Checks that an input of the given size may be loaded.
+    pub fn check_input_size(&self, size: usize) -> Result<()> {
+        if size > self.max_input_size {
+            return Err(CompilerError::Limit(format!(
+                "input size {} bytes exceeds maximum of {} bytes",
+                size, self.max_input_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the nesting depth and node count of a loaded document.
+    ///
+    /// The walk uses an explicit stack so that it cannot itself overflow on deep input.
+    pub fn check_node(&self, node: &Yaml) -> Result<()> {
+        let mut stack = vec![(node, 0usize)];
+        let mut count = 0usize;
+        while let Some((node, depth)) = stack.pop() {
+            count += 1;
+            self.check_visited(count, depth)?;
+            match node {
+                Yaml::Mapping(map) => {
+                    for (key, value) in map {
+                        stack.push((key, depth + 1));
+                        stack.push((value, depth + 1));
+                    }
+                }
+                Yaml::Sequence(arr) => {
+                    for item in arr {
+                        stack.push((item, depth + 1));
+                    }
+                }
+                Yaml::Tagged(tagged) => stack.push((&tagged.value, depth)),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the count of nodes visited so far and the depth of the last one.
+    pub(crate) fn check_visited(&self, count: usize, depth: usize) -> Result<()> {
+        if count > self.max_nodes {
+            return Err(CompilerError::Limit(format!(
+                "document exceeds maximum of {} nodes",
+                self.max_nodes
+            )));
+        }
+        if depth > self.max_depth {
+            return Err(CompilerError::Limit(format!(
+                "document exceeds maximum nesting depth of {}",
+                self.max_depth
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks that a parse function has not recursed past the maximum depth.
+    pub fn check_depth(&self, context: &Context) -> Result<()> {
+        if context.depth > self.max_depth {
+            return Err(CompilerError::new_with_code(
+                context,
+                ErrorCode::LimitExceeded,
+                format!("exceeds maximum nesting depth of {}", self.max_depth),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Checks the depth of a parse context against the limits of its options.
+pub fn check_depth(context: &Context) -> Result<()> {
+    context.options.limits().check_depth(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn nested(depth: usize) -> Yaml {
+        let mut node = Yaml::Null;
+        for _ in 0..depth {
+            node = Yaml::Sequence(vec![node]);
+        }
+        node
+    }
+
+    #[test]
+    fn test_check_input_size() {
+        let limits = Limits {
+            max_input_size: 10,
+            ..Default::default()
+        };
+        assert!(limits.check_input_size(10).is_ok());
+        assert!(limits.check_input_size(11).is_err());
+    }
+
+    #[test]
+    fn test_check_node_depth() {
+        let limits = Limits {
+            max_depth: 5,
+            ..Default::default()
+        };
+        assert!(limits.check_node(&nested(5)).is_ok());
+        let err = limits.check_node(&nested(6)).unwrap_err();
+        assert!(err.to_string().contains("nesting depth of 5"));
+    }
+
+    #[test]
+    fn test_check_node_count() {
+        let limits = Limits {
+            max_nodes: 3,
+            ..Default::default()
+        };
+        let small: Yaml = serde_yaml::from_str("a: 1").unwrap();
+        assert!(limits.check_node(&small).is_ok());
+        let large: Yaml = serde_yaml::from_str("a: 1\nb: 2").unwrap();
+        assert!(limits.check_node(&large).is_err());
+    }
+
+    #[test]
+    fn test_check_depth() {
+        let limits = Limits {
+            max_depth: 2,
+            ..Default::default()
+        };
+        let root = Arc::new(Context::root("$"));
+        let child = Arc::new(root.child("a"));
+        let grandchild = Arc::new(child.child("b"));
+        assert!(limits.check_depth(&grandchild).is_ok());
+        let too_deep = grandchild.child("c");
+        let err = limits.check_depth(&too_deep).unwrap_err();
+        assert_eq!(err.to_string(), "$.a.b.c exceeds maximum nesting depth of 2");
+    }
+
+    #[test]
+    fn test_check_depth_uses_options() {
+        let options = crate::ParseOptions::default().with_limits(Limits { max_depth: 1, ..Default::default() });
+        let root = Arc::new(Context::root_with_options("$", options));
+        let child = Arc::new(root.child("a"));
+        assert!(check_depth(&child).is_ok());
+        assert!(check_depth(&child.child("b")).is_err());
+        assert!(check_depth(&Context::root("$")).is_ok());
+    }
+}
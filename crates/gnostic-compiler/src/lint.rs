@@ -0,0 +1,367 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rule-based linting of documents.
+//!
+//! A [`Linter`] runs a set of named [`Rule`]s over a document and collects
+//! their findings in a [`LintReport`]. Each rule reports with its own
+//! severity, which can be changed or turned off per rule by name. The
+//! built-in rules here work on the YAML of OpenAPI v2 and v3 documents; the
+//! version crates add rules that use their models.
+
+use crate::checks::operations;
+use crate::{CompilerError, Context, ErrorGroup, Severity};
+use serde_yaml::Value as Yaml;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+/// The keys of a path item that hold operations, in either version.
+pub(crate) const METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// A named check of documents of type `D`.
+pub trait Rule<D: ?Sized> {
+    /// Returns the name the rule is configured by, such as "operation-tags".
+    fn name(&self) -> &str;
+
+    /// Returns a one-line description of what the rule checks.
+    fn description(&self) -> &str;
+
+    /// Returns the findings of the rule for `document`, whose YAML is
+    /// `node`. Findings are located by contexts rooted at "$".
+    fn check(&self, document: &D, node: &Yaml) -> Vec<CompilerError>;
+}
+
+/// A finding of a lint rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    /// The name of the rule that reported the finding.
+    pub rule: String,
+    /// The finding, with its location and severity.
+    pub error: CompilerError,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]", self.error, self.rule)
+    }
+}
+
+/// The findings of every rule of a linter, in rule order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintReport {
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    /// Returns true if no rule reported anything.
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Returns true if any finding has error severity.
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|finding| finding.error.is_error())
+    }
+
+    /// Returns the findings of the rule named `rule`.
+    pub fn for_rule<'a>(&'a self, rule: &'a str) -> impl Iterator<Item = &'a LintFinding> {
+        self.findings.iter().filter(move |finding| finding.rule == rule)
+    }
+
+    /// Returns the findings as an error group, for output such as SARIF.
+    pub fn to_error_group(&self) -> ErrorGroup {
+        ErrorGroup::new(self.findings.iter().map(|finding| finding.error.clone()).collect())
+    }
+}
+
+impl fmt::Display for LintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, finding) in self.findings.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", finding)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs rules over documents of type `D`.
+pub struct Linter<D: ?Sized> {
+    rules: Vec<Box<dyn Rule<D>>>,
+    severities: HashMap<String, Option<Severity>>,
+}
+
+impl<D: ?Sized> Default for Linter<D> {
+    fn default() -> Self {
+        Linter {
+            rules: Vec::new(),
+            severities: HashMap::new(),
+        }
+    }
+}
+
+impl<D: ?Sized> Linter<D> {
+    /// Creates a linter without rules.
+    pub fn new() -> Self {
+        Linter::default()
+    }
+
+    /// Returns the linter with `rule` added after its other rules.
+    pub fn with_rule(mut self, rule: impl Rule<D> + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Returns the linter with the findings of the rule named `rule`
+    /// reported with `severity`.
+    pub fn with_severity(mut self, rule: impl Into<String>, severity: Severity) -> Self {
+        self.severities.insert(rule.into(), Some(severity));
+        self
+    }
+
+    /// Returns the linter with the rule named `rule` turned off.
+    pub fn without_rule(mut self, rule: impl Into<String>) -> Self {
+        self.severities.insert(rule.into(), None);
+        self
+    }
+
+    /// Returns the rules of the linter, including those turned off.
+    pub fn rules(&self) -> impl Iterator<Item = &dyn Rule<D>> {
+        self.rules.iter().map(|rule| rule.as_ref())
+    }
+
+    /// Runs the rules that are turned on over `document`, whose YAML is `node`.
+    pub fn lint(&self, document: &D, node: &Yaml) -> LintReport {
+        let mut report = LintReport::default();
+        for rule in &self.rules {
+            let severity = match self.severities.get(rule.name()) {
+                Some(None) => continue,
+                Some(Some(severity)) => Some(*severity),
+                None => None,
+            };
+            for error in rule.check(document, node) {
+                report.findings.push(LintFinding {
+                    rule: rule.name().to_string(),
+                    error: match severity {
+                        Some(severity) => error.with_severity(severity),
+                        None => error,
+                    },
+                });
+            }
+        }
+        report
+    }
+
+    /// Returns the linter with the built-in rules added.
+    pub fn with_builtin_rules(self) -> Self {
+        self.with_rule(InfoDescription)
+            .with_rule(OperationDescription)
+            .with_rule(OperationIdCamelCase)
+            .with_rule(OperationTags)
+            .with_rule(OperationTagDefined)
+    }
+}
+
+/// Warns when the info object has no description.
+pub struct InfoDescription;
+
+impl<D: ?Sized> Rule<D> for InfoDescription {
+    fn name(&self) -> &str {
+        "info-description"
+    }
+
+    fn description(&self) -> &str {
+        "The info object should have a description."
+    }
+
+    fn check(&self, _document: &D, node: &Yaml) -> Vec<CompilerError> {
+        if !node["info"].is_mapping() || has_text(&node["info"]["description"]) {
+            return Vec::new();
+        }
+        let root = Arc::new(Context::root("$"));
+        vec![CompilerError::warning(&root.child("info"), "should have a description")]
+    }
+}
+
+/// Warns about operations with neither a summary nor a description.
+pub struct OperationDescription;
+
+impl<D: ?Sized> Rule<D> for OperationDescription {
+    fn name(&self) -> &str {
+        "operation-description"
+    }
+
+    fn description(&self) -> &str {
+        "Operations should have a summary or a description."
+    }
+
+    fn check(&self, _document: &D, node: &Yaml) -> Vec<CompilerError> {
+        operations(node, METHODS)
+            .into_iter()
+            .filter(|(_, operation)| !has_text(&operation["summary"]) && !has_text(&operation["description"]))
+            .map(|(context, _)| CompilerError::warning(&context, "should have a summary or a description"))
+            .collect()
+    }
+}
+
+/// Warns about operationIds that are not lowerCamelCase.
+pub struct OperationIdCamelCase;
+
+impl<D: ?Sized> Rule<D> for OperationIdCamelCase {
+    fn name(&self) -> &str {
+        "operation-id-camel-case"
+    }
+
+    fn description(&self) -> &str {
+        "OperationIds should be lowerCamelCase, such as listPets."
+    }
+
+    fn check(&self, _document: &D, node: &Yaml) -> Vec<CompilerError> {
+        let mut errors = Vec::new();
+        for (context, operation) in operations(node, METHODS) {
+            let Some(id) = operation["operationId"].as_str() else {
+                continue;
+            };
+            let camel_case = id.starts_with(|c: char| c.is_ascii_lowercase())
+                && id.chars().all(|c| c.is_ascii_alphanumeric());
+            if !camel_case {
+                errors.push(CompilerError::warning(
+                    &context.child("operationId"),
+                    format!("operationId {} should be lowerCamelCase", id),
+                ));
+            }
+        }
+        errors
+    }
+}
+
+/// Warns about operations without tags.
+pub struct OperationTags;
+
+impl<D: ?Sized> Rule<D> for OperationTags {
+    fn name(&self) -> &str {
+        "operation-tags"
+    }
+
+    fn description(&self) -> &str {
+        "Operations should have at least one tag."
+    }
+
+    fn check(&self, _document: &D, node: &Yaml) -> Vec<CompilerError> {
+        operations(node, METHODS)
+            .into_iter()
+            .filter(|(_, operation)| operation["tags"].as_sequence().is_none_or(|tags| tags.is_empty()))
+            .map(|(context, _)| CompilerError::warning(&context, "should have at least one tag"))
+            .collect()
+    }
+}
+
+/// Warns about operation tags that are not declared in the tags of the document.
+pub struct OperationTagDefined;
+
+impl<D: ?Sized> Rule<D> for OperationTagDefined {
+    fn name(&self) -> &str {
+        "operation-tag-defined"
+    }
+
+    fn description(&self) -> &str {
+        "Operation tags should be declared in the tags of the document."
+    }
+
+    fn check(&self, _document: &D, node: &Yaml) -> Vec<CompilerError> {
+        let declared: HashSet<&str> = node["tags"]
+            .as_sequence()
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| tag["name"].as_str())
+            .collect();
+        let mut errors = Vec::new();
+        for (context, operation) in operations(node, METHODS) {
+            let Some(tags) = operation["tags"].as_sequence() else {
+                continue;
+            };
+            let tags_context = Arc::new(context.child("tags"));
+            for (i, tag) in tags.iter().enumerate() {
+                if let Some(tag) = tag.as_str().filter(|tag| !declared.contains(tag)) {
+                    errors.push(CompilerError::warning(
+                        &tags_context.child(i.to_string()),
+                        format!("uses undeclared tag {}", tag),
+                    ));
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Returns true if `node` is a non-empty string.
+fn has_text(node: &Yaml) -> bool {
+    node.as_str().is_some_and(|text| !text.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+info:
+  title: Pets
+tags:
+- name: pets
+paths:
+  /pets:
+    get:
+      operationId: list_pets
+      summary: List pets
+      tags: [pets, animals]
+    post:
+      operationId: createPet
+"#;
+
+    #[test]
+    fn test_builtin_rules() {
+        let node: Yaml = serde_yaml::from_str(SOURCE).unwrap();
+        let report = Linter::<()>::new().with_builtin_rules().lint(&(), &node);
+        assert_eq!(
+            report.to_string(),
+            [
+                "warning: $.info should have a description [info-description]",
+                "warning: $.paths./pets.post should have a summary or a description [operation-description]",
+                "warning: $.paths./pets.get.operationId operationId list_pets should be lowerCamelCase [operation-id-camel-case]",
+                "warning: $.paths./pets.post should have at least one tag [operation-tags]",
+                "warning: $.paths./pets.get.tags.1 uses undeclared tag animals [operation-tag-defined]",
+            ]
+            .join("\n")
+        );
+        assert!(!report.has_errors());
+        assert_eq!(report.for_rule("operation-tags").count(), 1);
+    }
+
+    #[test]
+    fn test_severities() {
+        let node: Yaml = serde_yaml::from_str(SOURCE).unwrap();
+        let linter = Linter::<()>::new()
+            .with_builtin_rules()
+            .with_severity("operation-tags", Severity::Error)
+            .without_rule("info-description")
+            .without_rule("operation-tag-defined");
+        assert_eq!(linter.rules().count(), 5);
+        let report = linter.lint(&(), &node);
+        assert_eq!(report.findings.len(), 3);
+        assert!(report.has_errors());
+        assert_eq!(report.to_error_group().count(Severity::Error), 1);
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Map views of named collections.
+//!
+//! The models store mappings, such as the schemas of components or the paths
+//! of a document, as vectors of named entries in the order of the source.
+//! [`NamedMap`] gives those collections the operations of a map, and
+//! [`NamedMap::as_map`] indexes them for repeated lookups.
+
+use crate::writer::Named;
+use indexmap::IndexMap;
+
+/// An entry of a named collection that can be created and modified.
+pub trait NamedEntry: Named + Sized {
+    /// Returns an entry mapping `name` to `value`.
+    fn new(name: String, value: Self::Value) -> Self;
+
+    /// Returns the mapped value for modification, if present.
+    fn value_mut(&mut self) -> Option<&mut Self::Value>;
+
+    /// Returns the mapped value, consuming the entry.
+    fn into_value(self) -> Option<Self::Value>;
+}
+
+/// The value type of the entries of a named collection.
+pub type NamedValue<M> = <<M as NamedMap>::Entry as Named>::Value;
+
+/// A collection of named entries with the operations of a map.
+///
+/// Names are expected to be unique; lookups find the first entry of a name.
+pub trait NamedMap {
+    /// The type of the entries.
+    type Entry: NamedEntry;
+
+    /// Returns the entries in order.
+    fn entries(&self) -> &[Self::Entry];
+
+    /// Returns the entries for modification.
+    fn entries_mut(&mut self) -> &mut Vec<Self::Entry>;
+
+    /// Returns the value named `name`.
+    fn get(&self, name: &str) -> Option<&NamedValue<Self>> {
+        self.entries().iter().find(|entry| entry.name() == name)?.value()
+    }
+
+    /// Returns the value named `name` for modification.
+    fn get_mut(&mut self, name: &str) -> Option<&mut NamedValue<Self>> {
+        self.entries_mut().iter_mut().find(|entry| entry.name() == name)?.value_mut()
+    }
+
+    /// Returns true if there is an entry named `name`.
+    fn contains_key(&self, name: &str) -> bool {
+        self.entries().iter().any(|entry| entry.name() == name)
+    }
+
+    /// Sets the value named `name`, returning the value it replaces. New
+    /// entries are added at the end; replaced ones keep their position.
+    fn insert(&mut self, name: impl Into<String>, value: NamedValue<Self>) -> Option<NamedValue<Self>> {
+        let name = name.into();
+        let entries = self.entries_mut();
+        match entries.iter().position(|entry| entry.name() == name) {
+            Some(i) => std::mem::replace(&mut entries[i], Self::Entry::new(name, value)).into_value(),
+            None => {
+                entries.push(Self::Entry::new(name, value));
+                None
+            }
+        }
+    }
+
+    /// Removes the entry named `name`, keeping the order of the others, and
+    /// returns its value.
+    fn remove(&mut self, name: &str) -> Option<NamedValue<Self>> {
+        let entries = self.entries_mut();
+        let i = entries.iter().position(|entry| entry.name() == name)?;
+        entries.remove(i).into_value()
+    }
+
+    /// Returns the names of the entries in order.
+    fn names(&self) -> Vec<&str> {
+        self.entries().iter().map(|entry| entry.name()).collect()
+    }
+
+    /// Returns the entries as a map from names to values, in order. Entries
+    /// without values and later entries of a name are left out.
+    fn as_map(&self) -> IndexMap<&str, &NamedValue<Self>> {
+        let mut map = IndexMap::new();
+        for entry in self.entries() {
+            if let Some(value) = entry.value() {
+                map.entry(entry.name()).or_insert(value);
+            }
+        }
+        map
+    }
+
+    /// Returns the entries as a map from names to values, consuming the
+    /// collection.
+    fn into_map(mut self) -> IndexMap<String, NamedValue<Self>>
+    where
+        Self: Sized,
+    {
+        let mut map = IndexMap::new();
+        for entry in std::mem::take(self.entries_mut()) {
+            let name = entry.name().to_string();
+            if let Some(value) = entry.into_value() {
+                map.entry(name).or_insert(value);
+            }
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Entry {
+        name: String,
+        value: Option<String>,
+    }
+
+    impl Named for Entry {
+        type Value = String;
+
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn value(&self) -> Option<&String> {
+            self.value.as_ref()
+        }
+    }
+
+    impl NamedEntry for Entry {
+        fn new(name: String, value: String) -> Self {
+            Entry { name, value: Some(value) }
+        }
+
+        fn value_mut(&mut self) -> Option<&mut String> {
+            self.value.as_mut()
+        }
+
+        fn into_value(self) -> Option<String> {
+            self.value
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct Paths {
+        path: Vec<Entry>,
+    }
+
+    impl NamedMap for Paths {
+        type Entry = Entry;
+
+        fn entries(&self) -> &[Entry] {
+            &self.path
+        }
+
+        fn entries_mut(&mut self) -> &mut Vec<Entry> {
+            &mut self.path
+        }
+    }
+
+    #[test]
+    fn test_named_map() {
+        let mut paths = Paths::default();
+        assert_eq!(paths.insert("/pets", "list".to_string()), None);
+        assert_eq!(paths.insert("/owners", "owners".to_string()), None);
+        assert_eq!(paths.insert("/pets", "pets".to_string()), Some("list".to_string()));
+        paths.path.push(Entry { name: "/stores".to_string(), value: None });
+        paths.path.push(Entry::new("/owners".to_string(), "duplicate".to_string()));
+        assert_eq!(paths.names(), ["/pets", "/owners", "/stores", "/owners"]);
+
+        assert_eq!(paths.get("/owners").map(String::as_str), Some("owners"));
+        assert!(paths.contains_key("/stores"));
+        assert_eq!(paths.get("/stores"), None);
+        paths.get_mut("/pets").unwrap().push('!');
+
+        let map = paths.as_map();
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), ["/pets", "/owners"]);
+        assert_eq!(map["/owners"], "owners");
+
+        assert_eq!(paths.remove("/pets"), Some("pets!".to_string()));
+        assert_eq!(paths.remove("/pets"), None);
+        let map = paths.into_map();
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [("/owners".to_string(), "owners".to_string())]);
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Read access to document nodes.
+//!
+//! Parsers read the nodes of a document through the [`Node`] trait, so that
+//! the same parser can read the owned [`serde_yaml::Value`] trees built by the
+//! loader and the [`BorrowedNode`](crate::BorrowedNode) trees that borrow
+//! their strings from JSON source.
+
+use serde_yaml::Value as Yaml;
+use std::fmt;
+
+use crate::helpers::{bool_for_scalar_node, string_for_scalar_node};
+
+/// A node of a document tree.
+pub trait Node: fmt::Debug + Sized {
+    /// Returns true if the node is a mapping.
+    fn is_mapping(&self) -> bool;
+
+    /// Returns the entries of a mapping that have string keys, in order.
+    /// Other nodes have no entries.
+    fn entries(&self) -> impl Iterator<Item = (&str, &Self)>;
+
+    /// Returns the value for a key of a mapping.
+    fn get(&self, key: &str) -> Option<&Self> {
+        self.entries().find(|(k, _)| *k == key).map(|(_, value)| value)
+    }
+
+    /// Returns the items of a sequence. Other nodes have no items.
+    fn items(&self) -> &[Self];
+
+    /// Returns the string form of a scalar, as [`string_for_scalar_node`] does.
+    fn string_value(&self) -> Option<String>;
+
+    /// Returns the value of a boolean scalar.
+    fn bool_value(&self) -> Option<bool>;
+
+    /// Returns the string forms of the scalars of a sequence.
+    fn string_values(&self) -> Vec<String> {
+        self.items().iter().filter_map(Node::string_value).collect()
+    }
+}
+
+impl Node for Yaml {
+    fn is_mapping(&self) -> bool {
+        matches!(self, Yaml::Mapping(_))
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (&str, &Self)> {
+        self.as_mapping()
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, value)| Some((key.as_str()?, value)))
+    }
+
+    fn get(&self, key: &str) -> Option<&Self> {
+        self.as_mapping()?.get(key)
+    }
+
+    fn items(&self) -> &[Self] {
+        match self {
+            Yaml::Sequence(items) => items,
+            _ => &[],
+        }
+    }
+
+    fn string_value(&self) -> Option<String> {
+        string_for_scalar_node(self)
+    }
+
+    fn bool_value(&self) -> Option<bool> {
+        bool_for_scalar_node(self)
+    }
+}
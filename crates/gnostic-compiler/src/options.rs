@@ -0,0 +1,185 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Options controlling how documents are parsed.
+
+use crate::extensions::ExtensionHandler;
+use crate::limits::Limits;
+use crate::policy::FetchPolicy;
+
+/// ParseOptions configures the document parsers.
+///
+/// Options are attached to the root Context and inherited by every child context.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Report recoverable problems as warnings instead of failing the parse.
+    pub lenient: bool,
+    /// Stop at the first error instead of collecting every error in the document.
+    pub fail_fast: bool,
+    /// Maximum number of diagnostics to collect before stopping; None collects all.
+    ///
+    /// When the limit is hit the reported group ends with an "and N more" summary.
+    pub max_errors: Option<usize>,
+    /// Keep the source YAML so that writers can restore fields the models do
+    /// not represent. The source is returned in [`Parsed::raw_info`](crate::Parsed).
+    pub preserve_raw_info: bool,
+    /// Collect the comments of the source so that writers can emit them
+    /// again. They are returned in [`Parsed::comments`](crate::Parsed).
+    pub preserve_comments: bool,
+    /// How keywords next to a $ref are parsed; None follows the version the
+    /// document declares.
+    pub ref_siblings: Option<RefSiblings>,
+    /// Replace the $refs of the source with their targets before parsing.
+    /// References that cannot be resolved are left in place and reported as
+    /// warnings.
+    pub resolve_references: bool,
+    /// Which documents resolving references may read. The default policy
+    /// reads any local file, so set a stricter one for untrusted sources.
+    pub fetch_policy: FetchPolicy,
+    /// Handlers for specification extensions, given to the root Context.
+    pub extension_handlers: Vec<ExtensionHandler>,
+    /// Limits on the input and on the depth of parsing; None uses the limits
+    /// set with [`set_limits`](crate::set_limits).
+    pub limits: Option<Limits>,
+}
+
+impl ParseOptions {
+    /// Creates the default (strict) options.
+    pub fn new() -> Self {
+        ParseOptions::default()
+    }
+
+    /// Creates options for lenient parsing.
+    pub fn lenient() -> Self {
+        ParseOptions {
+            lenient: true,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the options with the maximum number of collected diagnostics set.
+    pub fn with_max_errors(mut self, max_errors: usize) -> Self {
+        self.max_errors = Some(max_errors);
+        self
+    }
+
+    /// Returns the options with the limits of this parse set.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Returns the limits of this parse.
+    pub fn limits(&self) -> Limits {
+        self.limits.unwrap_or_else(crate::limits::limits)
+    }
+
+    /// Returns the options with raw info preservation enabled.
+    pub fn with_raw_info(mut self) -> Self {
+        self.preserve_raw_info = true;
+        self
+    }
+
+    /// Returns the options with comment preservation enabled.
+    pub fn with_comments(mut self) -> Self {
+        self.preserve_comments = true;
+        self
+    }
+
+    /// Returns the options with the treatment of $ref siblings fixed,
+    /// whatever version the document declares.
+    pub fn with_ref_siblings(mut self, ref_siblings: RefSiblings) -> Self {
+        self.ref_siblings = Some(ref_siblings);
+        self
+    }
+
+    /// Returns the options with references resolved before parsing.
+    pub fn with_resolved_references(mut self) -> Self {
+        self.resolve_references = true;
+        self
+    }
+
+    /// Returns the options with the documents that resolving references may
+    /// read limited by `fetch_policy`.
+    pub fn with_fetch_policy(mut self, fetch_policy: FetchPolicy) -> Self {
+        self.fetch_policy = fetch_policy;
+        self
+    }
+
+    /// Returns the options with an extension handler added.
+    pub fn with_extension_handler(mut self, handler: ExtensionHandler) -> Self {
+        self.extension_handlers.push(handler);
+        self
+    }
+
+    /// Returns the options with the extension handlers of a name enabled or
+    /// disabled.
+    pub fn with_extension_handler_enabled(mut self, name: &str, enabled: bool) -> Self {
+        for handler in self.extension_handlers.iter_mut().filter(|handler| handler.name == name) {
+            handler.enabled = enabled;
+        }
+        self
+    }
+
+    /// Creates options that stop parsing at the first error.
+    pub fn fail_fast() -> Self {
+        ParseOptions {
+            fail_fast: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// How keywords next to a $ref, such as "description", are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RefSiblings {
+    /// Ignore them, as OpenAPI 3.0 and Swagger 2.0 require.
+    #[default]
+    Ignore,
+    /// Keep "summary" and "description" and let them override those of the
+    /// target, as OpenAPI 3.1 allows.
+    Merge,
+}
+
+impl RefSiblings {
+    /// The fields a reference may override in [`Merge`](Self::Merge) mode.
+    pub const FIELDS: &'static [&'static str] = &["summary", "description"];
+
+    /// Returns the treatment required by an OpenAPI or Swagger version,
+    /// which is [`Merge`](Self::Merge) from 3.1 on.
+    pub fn for_version(version: &str) -> Self {
+        let mut parts = version.split('.').map(|part| part.parse::<u32>().unwrap_or(0));
+        let major = parts.next().unwrap_or(0);
+        let minor = parts.next().unwrap_or(0);
+        if (major, minor) >= (3, 1) {
+            RefSiblings::Merge
+        } else {
+            RefSiblings::Ignore
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ref_siblings_for_version() {
+        assert_eq!(RefSiblings::for_version("2.0"), RefSiblings::Ignore);
+        assert_eq!(RefSiblings::for_version("3.0.3"), RefSiblings::Ignore);
+        assert_eq!(RefSiblings::for_version("3.1.0"), RefSiblings::Merge);
+        assert_eq!(RefSiblings::for_version("3.2"), RefSiblings::Merge);
+        assert_eq!(RefSiblings::for_version(""), RefSiblings::Ignore);
+    }
+}
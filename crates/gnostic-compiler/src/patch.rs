@@ -0,0 +1,276 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Patching of documents.
+//!
+//! [`apply_json_patch`] applies an RFC 6902 JSON Patch, a list of operations
+//! on locations given by JSON pointers, and [`apply_merge_patch`] an RFC 7386
+//! JSON Merge Patch, a partial document merged into the target. Both work on
+//! YAML nodes, so format crates can parse the patched document again.
+
+use crate::codes::ErrorCode;
+use crate::error::{CompilerError, Severity};
+use crate::helpers::{string_for_scalar_node, unescape_json_pointer_segment};
+use serde_yaml::{Mapping, Value as Yaml};
+
+/// Applies a JSON Patch, a sequence of operations, to a node.
+///
+/// The operations are applied in order and the patch fails as a whole if any
+/// of them fails, as when a "test" operation does not match.
+pub fn apply_json_patch(node: &Yaml, patch: &Yaml) -> Result<Yaml, CompilerError> {
+    let Yaml::Sequence(operations) = patch else {
+        return Err(patch_error("patch", "is not a sequence of operations".to_string()));
+    };
+    let mut patched = node.clone();
+    for (i, operation) in operations.iter().enumerate() {
+        apply_operation(&mut patched, operation).map_err(|message| patch_error(&format!("patch[{}]", i), message))?;
+    }
+    Ok(patched)
+}
+
+/// Applies a JSON Merge Patch to a node: mappings are merged key by key, null
+/// values remove keys, and any other value replaces the target.
+pub fn apply_merge_patch(node: &Yaml, patch: &Yaml) -> Yaml {
+    let Yaml::Mapping(patch) = patch else {
+        return patch.clone();
+    };
+    let mut merged = match node {
+        Yaml::Mapping(map) => map.clone(),
+        _ => Mapping::new(),
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            merged.shift_remove(key);
+        } else {
+            let value = apply_merge_patch(merged.get(key).unwrap_or(&Yaml::Null), value);
+            merged.insert(key.clone(), value);
+        }
+    }
+    Yaml::Mapping(merged)
+}
+
+fn patch_error(path: &str, message: String) -> CompilerError {
+    CompilerError::Unlocated {
+        path: path.to_string(),
+        message,
+        severity: Severity::Error,
+        code: ErrorCode::PatchFailed,
+    }
+}
+
+fn apply_operation(root: &mut Yaml, operation: &Yaml) -> Result<(), String> {
+    let op = operation["op"].as_str().ok_or("has no \"op\"")?;
+    let (raw, path) = pointer(operation, "path")?;
+    let failed = |reason: &str| format!("{} {:?} failed: {}", op, raw, reason);
+    match op {
+        "add" => add(root, &path, value(operation)?.clone()).map_err(&failed),
+        "remove" => remove(root, &path).map(|_| ()).map_err(&failed),
+        "replace" => {
+            let target = get_mut(root, &path).ok_or_else(|| failed("the location does not exist"))?;
+            *target = value(operation)?.clone();
+            Ok(())
+        }
+        "move" => {
+            let (_, from) = pointer(operation, "from")?;
+            if path.len() > from.len() && path.starts_with(&from) {
+                return Err(failed("a value cannot be moved into itself"));
+            }
+            let moved = remove(root, &from).map_err(&failed)?;
+            add(root, &path, moved).map_err(&failed)
+        }
+        "copy" => {
+            let (_, from) = pointer(operation, "from")?;
+            let copied = get(root, &from).ok_or_else(|| failed("the \"from\" location does not exist"))?.clone();
+            add(root, &path, copied).map_err(&failed)
+        }
+        "test" => match get(root, &path) {
+            Some(target) if target == value(operation)? => Ok(()),
+            Some(_) => Err(failed("the value differs")),
+            None => Err(failed("the location does not exist")),
+        },
+        _ => Err(format!("has unknown op {:?}", op)),
+    }
+}
+
+fn value(operation: &Yaml) -> Result<&Yaml, String> {
+    operation.get("value").ok_or_else(|| "has no \"value\"".to_string())
+}
+
+/// Returns a JSON pointer member of an operation and its keys. Unlike the
+/// fragments of references, patch pointers are not percent-encoded.
+fn pointer<'a>(operation: &'a Yaml, member: &str) -> Result<(&'a str, Vec<String>), String> {
+    let pointer = operation[member].as_str().ok_or_else(|| format!("has no {:?}", member))?;
+    if pointer.is_empty() {
+        return Ok((pointer, Vec::new()));
+    }
+    let keys = pointer.strip_prefix('/').ok_or_else(|| format!("has invalid {} {:?}", member, pointer))?;
+    Ok((pointer, keys.split('/').map(unescape_json_pointer_segment).collect()))
+}
+
+/// Returns whether a mapping key is written as `key`, such as 200 as "200".
+fn is_key(node: &Yaml, key: &str) -> bool {
+    string_for_scalar_node(node).is_some_and(|node| node == key)
+}
+
+/// Returns the index of a sequence element, rejecting leading zeros.
+fn index(key: &str) -> Option<usize> {
+    key.parse().ok().filter(|index: &usize| index.to_string() == key)
+}
+
+fn get<'a>(node: &'a Yaml, path: &[String]) -> Option<&'a Yaml> {
+    path.iter().try_fold(node, |node, key| match node {
+        Yaml::Mapping(map) => map.iter().find(|(k, _)| is_key(k, key)).map(|(_, value)| value),
+        Yaml::Sequence(items) => items.get(index(key)?),
+        _ => None,
+    })
+}
+
+fn get_mut<'a>(node: &'a mut Yaml, path: &[String]) -> Option<&'a mut Yaml> {
+    path.iter().try_fold(node, |node, key| match node {
+        Yaml::Mapping(map) => map.iter_mut().find(|(k, _)| is_key(k, key)).map(|(_, value)| value),
+        Yaml::Sequence(items) => items.get_mut(index(key)?),
+        _ => None,
+    })
+}
+
+fn add(root: &mut Yaml, path: &[String], value: Yaml) -> Result<(), &'static str> {
+    let Some((key, parent)) = path.split_last() else {
+        *root = value;
+        return Ok(());
+    };
+    match get_mut(root, parent) {
+        Some(Yaml::Mapping(map)) => {
+            match map.iter_mut().find(|(k, _)| is_key(k, key)) {
+                Some((_, existing)) => *existing = value,
+                None => {
+                    map.insert(Yaml::String(key.clone()), value);
+                }
+            }
+            Ok(())
+        }
+        Some(Yaml::Sequence(items)) if key == "-" => {
+            items.push(value);
+            Ok(())
+        }
+        Some(Yaml::Sequence(items)) => match index(key) {
+            Some(index) if index <= items.len() => {
+                items.insert(index, value);
+                Ok(())
+            }
+            _ => Err("the index is out of bounds"),
+        },
+        Some(_) => Err("the parent is not a mapping or sequence"),
+        None => Err("the parent does not exist"),
+    }
+}
+
+fn remove(root: &mut Yaml, path: &[String]) -> Result<Yaml, &'static str> {
+    let (key, parent) = path.split_last().ok_or("the whole document cannot be removed")?;
+    let removed = match get_mut(root, parent) {
+        Some(Yaml::Mapping(map)) => {
+            let key = map.keys().find(|k| is_key(k, key)).cloned();
+            key.and_then(|key| map.shift_remove(&key))
+        }
+        Some(Yaml::Sequence(items)) => index(key).filter(|index| *index < items.len()).map(|index| items.remove(index)),
+        _ => None,
+    };
+    removed.ok_or("the location does not exist")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(yaml: &str) -> Yaml {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_apply_json_patch() {
+        let document = node(
+            r#"
+info: {title: Pets, version: 1.0.0}
+tags: [{name: pets}]
+paths:
+  /pets:
+    get:
+      responses: {200: {description: OK}}
+"#,
+        );
+        let patch = node(
+            r#"
+- {op: test, path: /info/title, value: Pets}
+- {op: replace, path: /info/version, value: 2.0.0}
+- {op: add, path: /tags/0, value: {name: admin}}
+- {op: add, path: /tags/-, value: {name: store}}
+- {op: copy, from: /paths/~1pets/get, path: /paths/~1pets/head}
+- {op: move, from: /paths/~1pets/get/responses/200, path: /paths/~1pets/get/responses/default}
+- {op: remove, path: /tags/1}
+"#,
+        );
+        let patched = apply_json_patch(&document, &patch).unwrap();
+        assert_eq!(
+            patched,
+            node(
+                r#"
+info: {title: Pets, version: 2.0.0}
+tags: [{name: admin}, {name: store}]
+paths:
+  /pets:
+    get:
+      responses: {default: {description: OK}}
+    head:
+      responses: {200: {description: OK}}
+"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_apply_json_patch_errors() {
+        let document = node("{tags: [a], info: {title: Pets}}");
+        let error = |patch: &str| apply_json_patch(&document, &node(patch)).unwrap_err().to_string();
+        assert_eq!(
+            error("[{op: test, path: /info/title, value: Pets}, {op: test, path: /info/title, value: Zoo}]"),
+            "patch[1] test \"/info/title\" failed: the value differs"
+        );
+        assert_eq!(
+            error("[{op: remove, path: /info/version}]"),
+            "patch[0] remove \"/info/version\" failed: the location does not exist"
+        );
+        assert_eq!(
+            error("[{op: add, path: /tags/01, value: b}]"),
+            "patch[0] add \"/tags/01\" failed: the index is out of bounds"
+        );
+        assert_eq!(
+            error("[{op: move, from: /info, path: /info/x}]"),
+            "patch[0] move \"/info/x\" failed: a value cannot be moved into itself"
+        );
+        assert_eq!(error("[{op: replace, path: info}]"), "patch[0] has invalid path \"info\"");
+        assert_eq!(error("{op: remove}"), "patch is not a sequence of operations");
+        let error = apply_json_patch(&document, &node("[{op: nope, path: ''}]")).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::PatchFailed);
+    }
+
+    #[test]
+    fn test_apply_merge_patch() {
+        let document = node("{info: {title: Pets, version: 1.0.0, x-internal: true}, tags: [a, b]}");
+        let patch = node("{info: {version: 2.0.0, x-internal: null}, tags: [c], servers: [{url: /}]}");
+        assert_eq!(
+            apply_merge_patch(&document, &patch),
+            node("{info: {title: Pets, version: 2.0.0}, tags: [c], servers: [{url: /}]}")
+        );
+        assert_eq!(apply_merge_patch(&document, &node("[]")), node("[]"));
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Control over which documents external references may load.
+//!
+//! A specification from an untrusted source could otherwise use references
+//! to make the resolver request arbitrary URLs, such as services on an
+//! internal network, or read arbitrary local files. No remote documents are
+//! fetched unless a [`FetchPolicy`] allows them, and local files can be
+//! limited to a set of directories.
+
+use std::path::{Component, Path, PathBuf};
+use url::Url;
+
+/// Which documents the resolver may read for external references.
+///
+/// The default policy allows local files and no remote documents. Hosts may
+/// be given exactly ("api.example.com") or as a wildcard for subdomains
+/// ("*.example.com"); denied hosts take precedence over allowed ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchPolicy {
+    schemes: Vec<String>,
+    allowed_hosts: Vec<String>,
+    denied_hosts: Vec<String>,
+    /// Directories local files must be in; None allows any local file.
+    local_roots: Option<Vec<PathBuf>>,
+}
+
+impl FetchPolicy {
+    /// Creates a policy that allows no remote documents.
+    pub fn new() -> Self {
+        FetchPolicy::default()
+    }
+
+    /// Returns the policy with fetching over `scheme`, such as "https",
+    /// allowed. Until a host is allowed, any host not denied may be fetched.
+    pub fn allow_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.schemes.push(scheme.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Returns the policy with fetching limited to `host` and the other
+    /// allowed hosts.
+    pub fn allow_host(mut self, host: impl Into<String>) -> Self {
+        self.allowed_hosts.push(host.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Returns the policy with fetching from `host` denied.
+    pub fn deny_host(mut self, host: impl Into<String>) -> Self {
+        self.denied_hosts.push(host.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Returns the policy with local files limited to those under `root` and
+    /// the other allowed roots. Paths are compared after resolving "." and
+    /// ".." but without following symbolic links.
+    pub fn allow_local_root(mut self, root: impl AsRef<Path>) -> Self {
+        self.local_roots.get_or_insert_with(Vec::new).push(normalize_path(root.as_ref()));
+        self
+    }
+
+    /// Returns the policy with reading local files denied.
+    pub fn deny_local(mut self) -> Self {
+        self.local_roots = Some(Vec::new());
+        self
+    }
+
+    /// Returns an error message if the policy does not allow reading
+    /// `filename`, which may be a local path or a URL.
+    pub fn check(&self, filename: &str) -> Result<(), String> {
+        let Ok(url) = Url::parse(filename) else {
+            return self.check_local(filename, Path::new(filename));
+        };
+        if url.scheme() == "file" {
+            let path = url
+                .to_file_path()
+                .map_err(|_| format!("reading {} is not allowed: invalid file URL", filename))?;
+            return self.check_local(filename, &path);
+        }
+        if !self.schemes.iter().any(|scheme| scheme == url.scheme()) {
+            return Err(format!("fetching {} is not allowed: scheme {} is not allowed", filename, url.scheme()));
+        }
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        if self.denied_hosts.iter().any(|pattern| host_matches(pattern, &host)) {
+            return Err(format!("fetching {} is not allowed: host {} is denied", filename, host));
+        }
+        if !self.allowed_hosts.is_empty() && !self.allowed_hosts.iter().any(|pattern| host_matches(pattern, &host)) {
+            return Err(format!("fetching {} is not allowed: host {} is not allowed", filename, host));
+        }
+        Ok(())
+    }
+
+    /// Returns an error message if the policy does not allow reading the local file at `path`.
+    fn check_local(&self, filename: &str, path: &Path) -> Result<(), String> {
+        let Some(roots) = &self.local_roots else {
+            return Ok(());
+        };
+        if roots.is_empty() {
+            return Err(format!("reading {} is not allowed: local files are denied", filename));
+        }
+        let path = normalize_path(path);
+        if !roots.iter().any(|root| path.starts_with(root)) {
+            return Err(format!("reading {} is not allowed: it is outside the allowed directories", filename));
+        }
+        Ok(())
+    }
+}
+
+/// Returns `path` made absolute with its "." and ".." components resolved.
+fn normalize_path(path: &Path) -> PathBuf {
+    let path = match std::env::current_dir() {
+        Ok(dir) if path.is_relative() => dir.join(path),
+        _ => path.to_path_buf(),
+    };
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Returns true if `host` is `pattern` or, for "*.example.com", a subdomain of it.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+        None => pattern == host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy() {
+        let policy = FetchPolicy::new();
+        assert!(policy.check("models/pet.yaml").is_ok());
+        assert!(policy.check("/specs/pet.yaml").is_ok());
+        assert!(policy.check("file:///specs/pet.yaml").is_ok());
+        assert_eq!(
+            policy.check("http://169.254.169.254/latest").unwrap_err(),
+            "fetching http://169.254.169.254/latest is not allowed: scheme http is not allowed"
+        );
+    }
+
+    #[test]
+    fn test_hosts() {
+        let policy = FetchPolicy::new()
+            .allow_scheme("https")
+            .allow_host("*.example.com")
+            .allow_host("specs.test")
+            .deny_host("internal.example.com");
+        assert!(policy.check("https://api.example.com/pet.yaml").is_ok());
+        assert!(policy.check("https://SPECS.test/pet.yaml").is_ok());
+        assert!(policy.check("http://specs.test/pet.yaml").is_err());
+        assert!(policy.check("https://example.com/pet.yaml").is_err());
+        assert!(policy.check("https://badexample.com/pet.yaml").is_err());
+        assert!(policy.check("https://internal.example.com/pet.yaml").is_err());
+
+        let open = FetchPolicy::new().allow_scheme("https").deny_host("localhost");
+        assert!(open.check("https://anywhere.test/pet.yaml").is_ok());
+        assert!(open.check("https://localhost/pet.yaml").is_err());
+    }
+
+    #[test]
+    fn test_local_roots() {
+        let policy = FetchPolicy::new().allow_local_root("/specs");
+        assert!(policy.check("/specs/pet.yaml").is_ok());
+        assert!(policy.check("file:///specs/models/pet.yaml").is_ok());
+        assert_eq!(
+            policy.check("/specs/../etc/passwd").unwrap_err(),
+            "reading /specs/../etc/passwd is not allowed: it is outside the allowed directories"
+        );
+        assert!(policy.check("file:///etc/passwd").is_err());
+        assert!(policy.check("/specsheet.yaml").is_err());
+
+        let denied = FetchPolicy::new().deny_local();
+        assert_eq!(
+            denied.check("models/pet.yaml").unwrap_err(),
+            "reading models/pet.yaml is not allowed: local files are denied"
+        );
+        assert!(denied.check("file:///specs/pet.yaml").is_err());
+    }
+}
@@ -0,0 +1,419 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export of documents to Postman collections.
+//!
+//! [`postman_collection`] converts the YAML of an OpenAPI v2 or v3 document
+//! to a Postman Collection v2.1. Operations become requests, grouped in a
+//! folder for their first tag, and the base URL of the document becomes
+//! the `baseUrl` collection variable, along with the variables of the first
+//! v3 server.
+
+use crate::checks::local_target;
+use crate::helpers::yaml_to_json;
+use crate::lint::METHODS;
+use serde_json::{json, Value as JsonValue};
+use serde_yaml::Value as Yaml;
+
+/// The schema of Postman Collection v2.1 documents.
+pub const POSTMAN_SCHEMA_URL: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Converts the YAML of an OpenAPI v2 or v3 document to a Postman collection.
+pub fn postman_collection(node: &Yaml) -> JsonValue {
+    let mut info = json!({
+        "name": node["info"]["title"].as_str().unwrap_or("API"),
+        "schema": POSTMAN_SCHEMA_URL,
+    });
+    if let Some(description) = node["info"]["description"].as_str() {
+        info["description"] = json!(description);
+    }
+
+    // Folders for the declared tags come first, in declaration order.
+    let mut folders: Vec<(String, Vec<JsonValue>)> = node["tags"]
+        .as_sequence()
+        .into_iter()
+        .flatten()
+        .filter_map(|tag| tag["name"].as_str())
+        .map(|name| (name.to_string(), Vec::new()))
+        .collect();
+    let mut untagged = Vec::new();
+    if let Yaml::Mapping(paths) = &node["paths"] {
+        for (path, item) in paths {
+            let Some(path) = path.as_str() else {
+                continue;
+            };
+            let item = local_target(node, item).unwrap_or(item);
+            for method in METHODS {
+                let operation = &item[*method];
+                if !operation.is_mapping() {
+                    continue;
+                }
+                let request = request_item(node, path, method, &item["parameters"], operation);
+                let Some(tag) = operation["tags"][0].as_str() else {
+                    untagged.push(request);
+                    continue;
+                };
+                match folders.iter_mut().find(|(name, _)| name == tag) {
+                    Some((_, requests)) => requests.push(request),
+                    None => folders.push((tag.to_string(), vec![request])),
+                }
+            }
+        }
+    }
+
+    let mut items: Vec<JsonValue> = folders
+        .into_iter()
+        .filter(|(_, requests)| !requests.is_empty())
+        .map(|(name, requests)| {
+            let mut folder = json!({"name": name, "item": requests});
+            let declaration = node["tags"].as_sequence().into_iter().flatten().find(|tag| tag["name"] == name.as_str());
+            if let Some(description) = declaration.and_then(|tag| tag["description"].as_str()) {
+                folder["description"] = json!(description);
+            }
+            folder
+        })
+        .collect();
+    items.extend(untagged);
+
+    let (base_url, mut variables) = base_url(node);
+    variables.insert(0, json!({"key": "baseUrl", "value": base_url}));
+    json!({"info": info, "item": items, "variable": variables})
+}
+
+/// Returns the base URL of a document and the variables it uses.
+fn base_url(node: &Yaml) -> (String, Vec<JsonValue>) {
+    if node["swagger"].is_string() {
+        let base_path = node["basePath"].as_str().unwrap_or("");
+        let url = match node["host"].as_str() {
+            Some(host) => {
+                let scheme = node["schemes"][0].as_str().unwrap_or("https");
+                format!("{}://{}{}", scheme, host, base_path)
+            }
+            None => base_path.to_string(),
+        };
+        return (url.trim_end_matches('/').to_string(), Vec::new());
+    }
+    let server = &node["servers"][0];
+    let url = server["url"].as_str().unwrap_or("").replace('{', "{{").replace('}', "}}");
+    let variables = server["variables"]
+        .as_mapping()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, variable)| {
+            let mut entry = json!({
+                "key": name.as_str()?,
+                "value": scalar_string(&variable["default"]).unwrap_or_default(),
+            });
+            if let Some(description) = variable["description"].as_str() {
+                entry["description"] = json!(description);
+            }
+            Some(entry)
+        })
+        .collect();
+    (url.trim_end_matches('/').to_string(), variables)
+}
+
+/// Returns the Postman item for an operation.
+fn request_item(node: &Yaml, path: &str, method: &str, path_parameters: &Yaml, operation: &Yaml) -> JsonValue {
+    let name = operation["summary"]
+        .as_str()
+        .or_else(|| operation["operationId"].as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
+
+    // Operation parameters override path item parameters with the same name and location.
+    let mut parameters: Vec<&Yaml> = Vec::new();
+    for parameter in [path_parameters, &operation["parameters"]].into_iter().filter_map(Yaml::as_sequence).flatten() {
+        let Some(parameter) = local_target(node, parameter) else {
+            continue;
+        };
+        parameters.retain(|p| p["name"] != parameter["name"] || p["in"] != parameter["in"]);
+        parameters.push(parameter);
+    }
+
+    let mut headers = Vec::new();
+    let mut query = Vec::new();
+    let mut variables = Vec::new();
+    let mut form = Vec::new();
+    let mut body = None;
+    for parameter in &parameters {
+        let Some(key) = parameter["name"].as_str() else {
+            continue;
+        };
+        let mut entry = json!({"key": key, "value": parameter_value(parameter)});
+        if let Some(description) = parameter["description"].as_str() {
+            entry["description"] = json!(description);
+        }
+        match parameter["in"].as_str() {
+            Some("header") => headers.push(entry),
+            Some("query") => {
+                if parameter["required"].as_bool() != Some(true) {
+                    entry["disabled"] = json!(true);
+                }
+                query.push(entry);
+            }
+            Some("path") => variables.push(entry),
+            Some("formData") => {
+                entry["type"] = json!(if parameter["type"] == "file" { "file" } else { "text" });
+                form.push(entry);
+            }
+            Some("body") => body = Some(raw_body(example(&parameter["schema"]).or_else(|| example(parameter)))),
+            _ => {}
+        }
+    }
+
+    // A v2 operation takes the first media type it consumes, and a v3 operation its first request content.
+    let consumes = operation["consumes"][0].as_str().or_else(|| node["consumes"][0].as_str());
+    let mut content_type = None;
+    if !form.is_empty() {
+        let multipart = consumes == Some("multipart/form-data") || form.iter().any(|entry| entry["type"] == "file");
+        content_type = Some(if multipart { "multipart/form-data" } else { "application/x-www-form-urlencoded" });
+        body = Some(form_body(multipart, form));
+    } else if body.is_some() {
+        content_type = Some(consumes.unwrap_or("application/json"));
+    } else if let Some(request_body) = local_target(node, &operation["requestBody"]) {
+        let content = request_body["content"].as_mapping();
+        if let Some((media_type, media)) = content.and_then(|content| content.iter().next()) {
+            content_type = media_type.as_str();
+            let schema = local_target(node, &media["schema"]).unwrap_or(&media["schema"]);
+            body = Some(match content_type {
+                Some("multipart/form-data") => form_body(true, schema_fields(schema, true)),
+                Some("application/x-www-form-urlencoded") => form_body(false, schema_fields(schema, false)),
+                _ => raw_body(example(media).or_else(|| example(schema))),
+            });
+        }
+    }
+    if let Some(content_type) = content_type {
+        headers.insert(0, json!({"key": "Content-Type", "value": content_type}));
+    }
+
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.replace('{', ":").replace('}', ""))
+        .collect();
+    let mut raw = format!("{{{{baseUrl}}}}/{}", segments.join("/"));
+    if !query.is_empty() {
+        let pairs: Vec<String> = query
+            .iter()
+            .map(|entry| format!("{}={}", entry["key"].as_str().unwrap_or(""), entry["value"].as_str().unwrap_or("")))
+            .collect();
+        raw = format!("{}?{}", raw, pairs.join("&"));
+    }
+    let mut url = json!({"raw": raw, "host": ["{{baseUrl}}"], "path": segments});
+    if !query.is_empty() {
+        url["query"] = json!(query);
+    }
+    if !variables.is_empty() {
+        url["variable"] = json!(variables);
+    }
+
+    let mut request = json!({"method": method.to_uppercase(), "header": headers, "url": url});
+    if let Some(description) = operation["description"].as_str() {
+        request["description"] = json!(description);
+    }
+    if let Some(body) = body {
+        request["body"] = body;
+    }
+    json!({"name": name, "request": request})
+}
+
+/// Returns the example value of a parameter as text.
+fn parameter_value(parameter: &Yaml) -> String {
+    [&parameter["example"], &parameter["x-example"], &parameter["schema"]["example"], &parameter["default"]]
+        .into_iter()
+        .chain([&parameter["schema"]["default"]])
+        .find_map(scalar_string)
+        .unwrap_or_default()
+}
+
+/// Returns the example of a v3 media type, schema or v2 body parameter.
+fn example(node: &Yaml) -> Option<JsonValue> {
+    let examples = node["examples"].as_mapping().and_then(|examples| examples.values().next());
+    [Some(&node["example"]), Some(&node["x-example"]), examples.map(|example| &example["value"])]
+        .into_iter()
+        .flatten()
+        .find(|value| !value.is_null())
+        .map(yaml_to_json)
+}
+
+fn raw_body(example: Option<JsonValue>) -> JsonValue {
+    let raw = match example {
+        Some(JsonValue::String(text)) => text,
+        Some(value) => serde_json::to_string_pretty(&value).unwrap_or_default(),
+        None => String::new(),
+    };
+    json!({"mode": "raw", "raw": raw, "options": {"raw": {"language": "json"}}})
+}
+
+fn form_body(multipart: bool, fields: Vec<JsonValue>) -> JsonValue {
+    if multipart {
+        json!({"mode": "formdata", "formdata": fields})
+    } else {
+        json!({"mode": "urlencoded", "urlencoded": fields})
+    }
+}
+
+/// Returns form fields for the properties of a v3 request body schema.
+fn schema_fields(schema: &Yaml, multipart: bool) -> Vec<JsonValue> {
+    schema["properties"]
+        .as_mapping()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, property)| {
+            let file = multipart && property["format"] == "binary";
+            let value = scalar_string(&property["example"]).or_else(|| scalar_string(&property["default"]));
+            Some(json!({
+                "key": name.as_str()?,
+                "value": value.unwrap_or_default(),
+                "type": if file { "file" } else { "text" },
+            }))
+        })
+        .collect()
+}
+
+/// Returns a scalar node as text.
+fn scalar_string(node: &Yaml) -> Option<String> {
+    match node {
+        Yaml::String(text) => Some(text.clone()),
+        Yaml::Number(number) => Some(number.to_string()),
+        Yaml::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(text: &str) -> Yaml {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_v3_collection() {
+        let node = yaml(
+            r##"
+openapi: 3.0.3
+info: {title: Petstore, description: Pets for sale}
+servers:
+  - url: https://{region}.example.com/v1/
+    variables:
+      region: {default: eu, description: Region}
+tags:
+  - {name: admin, description: Administration}
+  - {name: pets}
+paths:
+  /pets/{petId}:
+    parameters:
+      - {name: petId, in: path, required: true, schema: {type: integer, example: 7}}
+    get:
+      tags: [pets]
+      summary: Get a pet
+      parameters:
+        - {name: fields, in: query, schema: {type: string}}
+        - {name: X-Trace, in: header, example: abc}
+    put:
+      tags: [pets]
+      operationId: updatePet
+      requestBody:
+        $ref: "#/components/requestBodies/Pet"
+  /health:
+    get: {}
+components:
+  requestBodies:
+    Pet:
+      content:
+        application/json:
+          example: {name: Rex}
+"##,
+        );
+        let collection = postman_collection(&node);
+        assert_eq!(collection["info"]["name"], "Petstore");
+        assert_eq!(collection["info"]["schema"], POSTMAN_SCHEMA_URL);
+        assert_eq!(
+            collection["variable"],
+            json!([
+                {"key": "baseUrl", "value": "https://{{region}}.example.com/v1"},
+                {"key": "region", "value": "eu", "description": "Region"},
+            ])
+        );
+        let items = collection["item"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["name"], "pets");
+        assert_eq!(items[1]["name"], "GET /health");
+        assert_eq!(items[1]["request"]["url"]["raw"], "{{baseUrl}}/health");
+
+        let get = &items[0]["item"][0];
+        assert_eq!(get["name"], "Get a pet");
+        assert_eq!(
+            get["request"],
+            json!({
+                "method": "GET",
+                "header": [{"key": "X-Trace", "value": "abc"}],
+                "url": {
+                    "raw": "{{baseUrl}}/pets/:petId?fields=",
+                    "host": ["{{baseUrl}}"],
+                    "path": ["pets", ":petId"],
+                    "query": [{"key": "fields", "value": "", "disabled": true}],
+                    "variable": [{"key": "petId", "value": "7"}],
+                },
+            })
+        );
+        let put = &items[0]["item"][1];
+        assert_eq!(put["name"], "updatePet");
+        assert_eq!(put["request"]["header"][0], json!({"key": "Content-Type", "value": "application/json"}));
+        assert_eq!(put["request"]["body"]["raw"], "{\n  \"name\": \"Rex\"\n}");
+    }
+
+    #[test]
+    fn test_v2_collection() {
+        let node = yaml(
+            r##"
+swagger: "2.0"
+info: {title: Uploads}
+host: api.example.com
+basePath: /v2
+schemes: [http, https]
+paths:
+  /files:
+    post:
+      tags: [files]
+      consumes: [multipart/form-data]
+      parameters:
+        - {name: file, in: formData, type: file}
+        - {name: note, in: formData, type: string, default: none}
+  /notes:
+    post:
+      parameters:
+        - {name: body, in: body, schema: {type: object, example: {text: hi}}}
+"##,
+        );
+        let collection = postman_collection(&node);
+        assert_eq!(collection["variable"], json!([{"key": "baseUrl", "value": "http://api.example.com/v2"}]));
+        let upload = &collection["item"][0]["item"][0]["request"];
+        assert_eq!(upload["header"][0]["value"], "multipart/form-data");
+        assert_eq!(
+            upload["body"],
+            json!({"mode": "formdata", "formdata": [
+                {"key": "file", "value": "", "type": "file"},
+                {"key": "note", "value": "none", "type": "text"},
+            ]})
+        );
+        let note = &collection["item"][1];
+        assert_eq!(note["name"], "POST /notes");
+        assert_eq!(note["request"]["header"][0]["value"], "application/json");
+        assert_eq!(note["request"]["body"]["raw"], "{\n  \"text\": \"hi\"\n}");
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Preservation of the source YAML of parsed documents.
+//!
+//! The models only hold the fields defined by the specification, so unknown
+//! or unsupported fields are dropped when a document is parsed. When
+//! [`ParseOptions::preserve_raw_info`](crate::ParseOptions) is set, parsers
+//! return a [`RawInfo`] holding the source node, which the writers use to put
+//! those fields back and to keep the source formatting of unchanged subtrees.
+
+use serde_yaml::{Mapping, Value as Yaml};
+
+/// The source YAML of a parsed document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawInfo {
+    /// The node the document was parsed from.
+    source: Yaml,
+    /// The YAML emitted for the document right after parsing.
+    parsed: Yaml,
+}
+
+impl RawInfo {
+    /// Creates raw info from a source node and the YAML emitted for the model
+    /// parsed from it.
+    pub fn new(source: Yaml, parsed: Yaml) -> Self {
+        RawInfo { source, parsed }
+    }
+
+    /// Returns the node the document was parsed from.
+    pub fn source(&self) -> &Yaml {
+        &self.source
+    }
+
+    /// Restores source content into YAML emitted for a (possibly modified) model.
+    ///
+    /// Subtrees that are unchanged since parsing are replaced by their source
+    /// text, mapping keys the model does not represent are reinserted in their
+    /// source position, and everything the model changed is kept as emitted.
+    pub fn restore(&self, emitted: &mut Yaml) {
+        restore_node(emitted, &self.source, &self.parsed);
+    }
+}
+
+fn restore_node(current: &mut Yaml, source: &Yaml, parsed: &Yaml) {
+    if current == parsed {
+        *current = source.clone();
+        return;
+    }
+    match (current, source, parsed) {
+        (Yaml::Mapping(current), Yaml::Mapping(source), Yaml::Mapping(parsed)) => {
+            let mut remaining = std::mem::take(current);
+            let mut restored = Mapping::with_capacity(remaining.len());
+            for (key, source_value) in source {
+                match (remaining.remove(key), parsed.get(key)) {
+                    (Some(mut value), Some(parsed_value)) => {
+                        restore_node(&mut value, source_value, parsed_value);
+                        restored.insert(key.clone(), value);
+                    }
+                    (Some(value), None) => {
+                        restored.insert(key.clone(), value);
+                    }
+                    // Not represented by the model, so restore it from the source
+                    (None, None) => {
+                        restored.insert(key.clone(), source_value.clone());
+                    }
+                    // Removed from the model since parsing
+                    (None, Some(_)) => {}
+                }
+            }
+            restored.extend(remaining);
+            *current = restored;
+        }
+        (Yaml::Sequence(current), Yaml::Sequence(source), Yaml::Sequence(parsed)) => {
+            for ((value, source_value), parsed_value) in
+                current.iter_mut().zip(source).zip(parsed)
+            {
+                restore_node(value, source_value, parsed_value);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(text: &str) -> Yaml {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_restore_unchanged() {
+        let source = yaml("version: '1.0'\ntitle: Pets\nx-unknown: 1\n");
+        let parsed = yaml("title: Pets\nversion: '1.0'\n");
+        let raw = RawInfo::new(source.clone(), parsed.clone());
+        let mut emitted = parsed;
+        raw.restore(&mut emitted);
+        assert_eq!(serde_yaml::to_string(&emitted).unwrap(), serde_yaml::to_string(&source).unwrap());
+    }
+
+    #[test]
+    fn test_restore_modified() {
+        let source = yaml("info:\n  title: Pets\n  summary: old\n  version: '1.0'\nlegacy: true\n");
+        let parsed = yaml("info:\n  title: Pets\n  version: '1.0'\n");
+        let raw = RawInfo::new(source, parsed);
+
+        let mut emitted = yaml("info:\n  title: Animals\n  description: new\n");
+        raw.restore(&mut emitted);
+        assert_eq!(
+            serde_yaml::to_string(&emitted).unwrap(),
+            "info:\n  title: Animals\n  summary: old\n  description: new\nlegacy: true\n"
+        );
+    }
+
+    #[test]
+    fn test_restore_sequences() {
+        let source = yaml("tags:\n- name: a\n  x-order: 2\n- name: b\n");
+        let parsed = yaml("tags:\n- name: a\n- name: b\n");
+        let raw = RawInfo::new(source, parsed);
+
+        let mut emitted = yaml("tags:\n- name: a\n- name: c\n- name: d\n");
+        raw.restore(&mut emitted);
+        assert_eq!(
+            serde_yaml::to_string(&emitted).unwrap(),
+            "tags:\n- name: a\n  x-order: 2\n- name: c\n- name: d\n"
+        );
+    }
+}
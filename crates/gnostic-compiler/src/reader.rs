@@ -14,20 +14,35 @@
 
 //! File and HTTP reading with caching support.
 
-use crate::error::{CompilerError, Result};
+use crate::codes::ErrorCode;
+use crate::error::{CompilerError, Result, Severity};
+use crate::helpers::{escape_json_pointer_segment, unescape_json_pointer_segment};
+use crate::limits::{limits, Limits};
+use crate::policy::FetchPolicy;
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
 use serde_yaml::Value as Yaml;
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use url::Url;
 
 /// Global file cache (thread-safe).
 static FILE_CACHE: Lazy<RwLock<HashMap<String, Vec<u8>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
 
-/// Global parsed YAML cache (thread-safe).
-static INFO_CACHE: Lazy<RwLock<HashMap<String, Yaml>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+/// Global parsed YAML cache (thread-safe), shared with callers rather than
+/// cloned on every hit.
+static INFO_CACHE: Lazy<RwLock<HashMap<String, Arc<Yaml>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Global parsed document cache (thread-safe), keyed by the type of the
+/// document and the length and hash of its source.
+#[allow(clippy::type_complexity)]
+static DOCUMENT_CACHE: Lazy<RwLock<HashMap<(TypeId, usize, u64), Arc<dyn Any + Send + Sync>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
 
 /// File cache enabled flag.
 static FILE_CACHE_ENABLED: AtomicBool = AtomicBool::new(true);
@@ -35,6 +50,9 @@ static FILE_CACHE_ENABLED: AtomicBool = AtomicBool::new(true);
 /// Info cache enabled flag.
 static INFO_CACHE_ENABLED: AtomicBool = AtomicBool::new(true);
 
+/// Document cache enabled flag; documents are not cached by default.
+static DOCUMENT_CACHE_ENABLED: AtomicBool = AtomicBool::new(false);
+
 /// Verbose reader flag.
 static VERBOSE_READER: AtomicBool = AtomicBool::new(false);
 
@@ -58,6 +76,16 @@ pub fn disable_info_cache() {
     INFO_CACHE_ENABLED.store(false, Ordering::SeqCst);
 }
 
+/// Enables parsed document caching.
+pub fn enable_document_cache() {
+    DOCUMENT_CACHE_ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Disables parsed document caching.
+pub fn disable_document_cache() {
+    DOCUMENT_CACHE_ENABLED.store(false, Ordering::SeqCst);
+}
+
 /// Sets verbose reader mode.
 pub fn set_verbose_reader(verbose: bool) {
     VERBOSE_READER.store(verbose, Ordering::SeqCst);
@@ -87,18 +115,56 @@ pub fn clear_info_cache() {
     INFO_CACHE.write().clear();
 }
 
+/// Clears the document cache.
+pub fn clear_document_cache() {
+    DOCUMENT_CACHE.write().clear();
+}
+
 /// Clears all caches.
 pub fn clear_caches() {
     clear_file_cache();
     clear_info_cache();
+    clear_document_cache();
+}
+
+/// Returns the document parsed from `bytes`, calling `parse` only if the
+/// document cache is disabled or has no document of the same type parsed
+/// from the same content.
+///
+/// Documents are cached by content rather than by file name, so a changed
+/// file is parsed again. Only successful parses are cached, and `parse` must
+/// depend on nothing but the content.
+pub fn cached_document<T, E, F>(bytes: &[u8], parse: F) -> std::result::Result<T, E>
+where
+    T: Clone + Send + Sync + 'static,
+    F: FnOnce() -> std::result::Result<T, E>,
+{
+    if !DOCUMENT_CACHE_ENABLED.load(Ordering::SeqCst) {
+        return parse();
+    }
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    let key = (TypeId::of::<T>(), bytes.len(), hasher.finish());
+    if let Some(document) = DOCUMENT_CACHE.read().get(&key).and_then(|document| document.downcast_ref::<T>()) {
+        if VERBOSE_READER.load(Ordering::SeqCst) {
+            log::info!("Cache hit document of {} bytes", bytes.len());
+        }
+        return Ok(document.clone());
+    }
+    let document = parse()?;
+    DOCUMENT_CACHE.write().insert(key, Arc::new(document.clone()));
+    Ok(document)
 }
 
 /// Fetches a URL asynchronously (public API for use by other crates).
+#[cfg(feature = "http")]
 pub async fn fetch_url(url_str: &str) -> Result<Vec<u8>> {
     fetch_url_async(url_str).await
 }
 
 /// Fetches a file from a URL using hyper.
+///
+/// Without the `http` feature, fetching fails with an error.
 pub fn fetch_file(fileurl: &str) -> Result<Vec<u8>> {
     let cache_enabled = FILE_CACHE_ENABLED.load(Ordering::SeqCst);
     let verbose = VERBOSE_READER.load(Ordering::SeqCst);
@@ -116,25 +182,41 @@ pub fn fetch_file(fileurl: &str) -> Result<Vec<u8>> {
         }
     }
 
+    let bytes = fetch_blocking(fileurl)?;
+
+    // Store in cache
+    if cache_enabled {
+        FILE_CACHE.write().insert(fileurl.to_string(), bytes.clone());
+    }
+
+    Ok(bytes)
+}
+
+/// Fetches a URL on a runtime of its own.
+#[cfg(feature = "http")]
+fn fetch_blocking(fileurl: &str) -> Result<Vec<u8>> {
     // Use tokio runtime for async HTTP request
     let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .map_err(|e| CompilerError::Http(format!("Failed to create runtime: {}", e)))?;
 
-    let bytes = runtime.block_on(async {
+    runtime.block_on(async {
         fetch_url_async(fileurl).await
-    })?;
-
-    // Store in cache
-    if cache_enabled {
-        FILE_CACHE.write().insert(fileurl.to_string(), bytes.clone());
-    }
+    })
+}
 
-    Ok(bytes)
+/// Reports that fetching URLs needs the `http` feature.
+#[cfg(not(feature = "http"))]
+fn fetch_blocking(fileurl: &str) -> Result<Vec<u8>> {
+    Err(CompilerError::Http(format!(
+        "Cannot fetch {}: gnostic-compiler was built without the `http` feature",
+        fileurl
+    )))
 }
 
 /// Async function to fetch URL using hyper (HTTP only).
+#[cfg(feature = "http")]
 async fn fetch_http(url_str: &str, uri: http::Uri, host: String) -> Result<Vec<u8>> {
     use hyper::{Body, Client, Request};
     use hyper::client::HttpConnector;
@@ -167,6 +249,7 @@ async fn fetch_http(url_str: &str, uri: http::Uri, host: String) -> Result<Vec<u
 }
 
 /// Async function to fetch URL using hyper.
+#[cfg(feature = "http")]
 async fn fetch_url_async(url_str: &str) -> Result<Vec<u8>> {
     use http::Uri;
 
@@ -197,126 +280,353 @@ pub fn read_bytes_for_file(filename: &str) -> Result<Vec<u8>> {
         if url.scheme() == "http" || url.scheme() == "https" {
             return fetch_file(filename);
         }
+        if url.scheme() == "file" {
+            let path = url
+                .to_file_path()
+                .map_err(|_| CompilerError::io(format!("Failed to read {}: invalid file URL", filename)))?;
+            return read_local_file(filename, &path);
+        }
     }
 
-    // Local file
-    std::fs::read(filename).map_err(|e| CompilerError::Io(format!("Failed to read {}: {}", filename, e)))
+    read_local_file(filename, Path::new(filename))
+}
+
+/// Reads a local file, checking its size against the default limits before
+/// reading it.
+fn read_local_file(filename: &str, path: &Path) -> Result<Vec<u8>> {
+    let io_error = |e: std::io::Error| CompilerError::Io {
+        message: format!("Failed to read {}: {}", filename, e),
+        source: Some(Arc::new(e)),
+    };
+    let size = std::fs::metadata(path).map_err(io_error)?.len();
+    limits().check_input_size(usize::try_from(size).unwrap_or(usize::MAX))?;
+    std::fs::read(path).map_err(io_error)
+}
+
+/// Returns true if the file has a `.json` extension, so that it must be
+/// loaded with the JSON parser.
+fn has_json_extension(filename: &str) -> bool {
+    Path::new(filename)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Returns true if the first non-whitespace byte suggests the input is JSON.
+///
+/// This is only a hint: such inputs may also be YAML flow collections, so they
+/// fall back to the YAML parser when they are not valid JSON.
+fn looks_like_json(bytes: &[u8]) -> bool {
+    matches!(
+        bytes.iter().find(|b| !b.is_ascii_whitespace()),
+        Some(b'{') | Some(b'[')
+    )
+}
+
+/// Parses bytes as JSON, producing the same node representation as the YAML loader.
+///
+/// The JSON parser is considerably faster than the YAML parser for large documents
+/// and reads integers without a round trip through YAML scalar resolution.
+///
+/// Numbers have the precision of the YAML node type, as they do when read as
+/// YAML: integers that fit in an `i64` or `u64` are exact, while larger
+/// integers and decimals become the nearest `f64`.
+pub fn read_info_from_json_bytes(bytes: &[u8]) -> Result<Yaml> {
+    Ok(serde_json::from_slice(bytes)?)
 }
 
-/// Parses bytes as YAML.
+/// Parses bytes as YAML, using the JSON fast path for JSON inputs.
 pub fn read_info_from_bytes(filename: &str, bytes: &[u8]) -> Result<Yaml> {
+    read_shared_info_from_bytes(filename, bytes).map(Arc::unwrap_or_clone)
+}
+
+/// Parses bytes as YAML like [`read_info_from_bytes`], applying the given
+/// limits instead of the default ones.
+pub fn read_info_from_bytes_with_limits(filename: &str, bytes: &[u8], limits: &Limits) -> Result<Yaml> {
+    read_shared_info_from_bytes_with_limits(filename, bytes, limits).map(Arc::unwrap_or_clone)
+}
+
+/// Parses bytes as YAML like [`read_info_from_bytes`], returning a handle
+/// shared with the cache instead of a copy of the node.
+pub fn read_shared_info_from_bytes(filename: &str, bytes: &[u8]) -> Result<Arc<Yaml>> {
+    read_shared_info_from_bytes_with_limits(filename, bytes, &limits())
+}
+
+/// Parses bytes as YAML like [`read_shared_info_from_bytes`], applying the
+/// given limits.
+///
+/// The size of the input is checked before it is parsed. Cached trees were
+/// checked against the default limits when they were parsed, so they are
+/// checked again only for other limits.
+pub fn read_shared_info_from_bytes_with_limits(filename: &str, bytes: &[u8], limits: &Limits) -> Result<Arc<Yaml>> {
     let cache_enabled = INFO_CACHE_ENABLED.load(Ordering::SeqCst);
     let verbose = VERBOSE_READER.load(Ordering::SeqCst);
+    limits.check_input_size(bytes.len())?;
 
     // Check cache first
     if cache_enabled && !filename.is_empty() {
-        if let Some(info) = INFO_CACHE.read().get(filename) {
+        if let Some(info) = INFO_CACHE.read().get(filename).cloned() {
             if verbose {
                 log::info!("Cache hit info for file {}", filename);
             }
-            return Ok(info.clone());
+            if *limits != self::limits() {
+                limits.check_node(&info)?;
+            }
+            return Ok(info);
         }
         if verbose {
             log::info!("Reading info for file {}", filename);
         }
     }
 
-    // Parse YAML
-    let content = std::str::from_utf8(bytes)
-        .map_err(|e| CompilerError::Yaml(format!("Invalid UTF-8: {}", e)))?;
+    let yaml = Arc::new(parse_info(filename, bytes)?);
+    limits.check_node(&yaml)?;
 
-    let yaml: Yaml = serde_yaml::from_str(content)?;
-
-    // Store in cache
-    if cache_enabled && !filename.is_empty() {
-        INFO_CACHE.write().insert(filename.to_string(), yaml.clone());
+    // Store in cache, unless the tree was checked against other limits
+    if cache_enabled && !filename.is_empty() && *limits == self::limits() {
+        INFO_CACHE.write().insert(filename.to_string(), Arc::clone(&yaml));
     }
 
     Ok(yaml)
 }
 
+/// Parses bytes into a YAML node without consulting the cache.
+fn parse_info(filename: &str, bytes: &[u8]) -> Result<Yaml> {
+    // Files named as JSON must be JSON, so their syntax errors are reported as
+    // JSON errors. Other inputs that look like JSON may be YAML flow collections
+    // and are handed to the YAML parser when they fail to parse as JSON.
+    if has_json_extension(filename) {
+        return read_info_from_json_bytes(bytes);
+    }
+    if looks_like_json(bytes) {
+        if let Ok(yaml) = read_info_from_json_bytes(bytes) {
+            return Ok(yaml);
+        }
+    }
+
+    let content = std::str::from_utf8(bytes)
+        .map_err(|e| CompilerError::yaml(format!("Invalid UTF-8: {}", e)))?;
+
+    Ok(serde_yaml::from_str(content)?)
+}
+
 /// Reads a file and returns the parsed YAML.
 pub fn read_info_for_file(filename: &str) -> Result<Yaml> {
+    read_shared_info_for_file(filename).map(Arc::unwrap_or_clone)
+}
+
+/// Reads a file like [`read_info_for_file`], returning a handle shared with
+/// the cache. Cached files are not read again.
+pub fn read_shared_info_for_file(filename: &str) -> Result<Arc<Yaml>> {
+    if INFO_CACHE_ENABLED.load(Ordering::SeqCst) {
+        if let Some(info) = INFO_CACHE.read().get(filename) {
+            return Ok(Arc::clone(info));
+        }
+    }
     let bytes = read_bytes_for_file(filename)?;
-    read_info_from_bytes(filename, &bytes)
+    read_shared_info_from_bytes(filename, &bytes)
+}
+
+/// Returns the name of the file in the file part of a $ref, resolved against
+/// the file or URL containing the reference.
+///
+/// References in documents fetched over HTTP resolve against the document
+/// URL, so "./models/pet.yaml" in "https://example.com/api/openapi.yaml" is
+/// "https://example.com/api/models/pet.yaml".
+pub fn filename_for_ref(basefile: &str, file: &str) -> String {
+    // Check if it's a URL
+    if Url::parse(file).is_ok() {
+        return file.to_string();
+    }
+    if let Ok(base) = Url::parse(basefile) {
+        if let Ok(url) = base.join(file) {
+            return url.to_string();
+        }
+    }
+    // Local file - resolve relative to base
+    if Path::new(file).is_absolute() {
+        return normalize_path(file);
+    }
+    let basedir = Path::new(basefile)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if basedir.is_empty() {
+        normalize_path(file)
+    } else {
+        normalize_path(&format!("{}/{}", basedir, file))
+    }
+}
+
+/// Removes "." segments and resolves ".." segments of a local path without
+/// consulting the file system, so that every path to a file is spelled the same.
+fn normalize_path(path: &str) -> String {
+    let absolute = path.starts_with('/');
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            ".." if absolute => {}
+            segment => segments.push(segment),
+        }
+    }
+    let joined = segments.join("/");
+    if absolute {
+        format!("/{}", joined)
+    } else {
+        joined
+    }
+}
+
+/// Returns the node at a JSON pointer such as "/components/schemas/Pet".
+///
+/// An empty pointer refers to the node itself.
+pub fn node_for_pointer<'a>(node: &'a Yaml, pointer: &str) -> Option<&'a Yaml> {
+    let mut current = node;
+    for key in pointer_segments(pointer) {
+        current = match current {
+            Yaml::Mapping(map) => map.get(key.as_str())?,
+            Yaml::Sequence(items) => items.get(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Returns the keys of a JSON pointer taken from the fragment of a $ref.
+///
+/// A leading "#" is skipped. The fragment is percent-decoded and each key
+/// unescaped as in RFC 6901, so "#/paths/~1pets~1%7Bid%7D" has the keys
+/// "paths" and "/pets/{id}".
+pub fn pointer_segments(pointer: &str) -> Vec<String> {
+    let pointer = percent_decode(pointer.strip_prefix('#').unwrap_or(pointer));
+    let pointer = pointer.strip_prefix('/').unwrap_or(&pointer);
+    if pointer.is_empty() {
+        return Vec::new();
+    }
+    pointer
+        .split('/')
+        .map(unescape_json_pointer_segment)
+        .collect()
+}
+
+/// Returns the JSON pointer for a list of keys, escaping "~" and "/".
+pub fn pointer_for_segments<S: AsRef<str>>(segments: &[S]) -> String {
+    let mut pointer = String::new();
+    for segment in segments {
+        pointer.push('/');
+        pointer.push_str(&escape_json_pointer_segment(segment.as_ref()));
+    }
+    pointer
+}
+
+/// Decodes the %XX escapes of a URL fragment, leaving malformed ones as they are.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
 }
 
 /// Reads a file and returns the fragment needed to resolve a $ref.
+///
+/// If the fragment is itself a reference, the chain of references is followed
+/// to its end; chains that loop fail with a circular reference error. Files
+/// are read as the default [`FetchPolicy`] allows.
 pub fn read_info_for_ref(basefile: &str, reference: &str) -> Result<Yaml> {
-    let cache_enabled = INFO_CACHE_ENABLED.load(Ordering::SeqCst);
+    read_info_for_ref_with_policy(basefile, reference, &FetchPolicy::default())
+}
+
+/// Reads a file and returns the fragment needed to resolve a $ref, checking
+/// every file in the chain of references against `policy`.
+pub fn read_info_for_ref_with_policy(basefile: &str, reference: &str, policy: &FetchPolicy) -> Result<Yaml> {
+    // Cached targets were reached under the default policy, so other policies
+    // bypass the cache rather than trust it.
+    let cache_enabled = INFO_CACHE_ENABLED.load(Ordering::SeqCst) && *policy == FetchPolicy::default();
     let verbose = VERBOSE_READER.load(Ordering::SeqCst);
 
-    // Check cache first
+    // Split reference into file and path parts
+    let (file, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+    let mut filename = if file.is_empty() {
+        basefile.to_string()
+    } else {
+        filename_for_ref(basefile, file)
+    };
+    let mut pointer = pointer.to_string();
+
+    // Check cache first; the same reference in different files can point to
+    // different targets, so entries are keyed by the resolved file.
+    let cache_key = format!("{}#{}", filename, pointer);
     if cache_enabled {
-        if let Some(info) = INFO_CACHE.read().get(reference) {
+        if let Some(info) = INFO_CACHE.read().get(&cache_key) {
             if verbose {
                 log::info!("Cache hit for ref {}#{}", basefile, reference);
             }
-            return Ok(info.clone());
+            return Ok(Yaml::clone(info));
         }
         if verbose {
             log::info!("Reading info for ref {}#{}", basefile, reference);
         }
     }
 
-    // Split reference into file and path parts
-    let parts: Vec<&str> = reference.splitn(2, '#').collect();
-    let filename = if !parts[0].is_empty() {
-        // Check if it's a URL
-        if Url::parse(parts[0]).is_ok() {
-            parts[0].to_string()
-        } else {
-            // Local file - resolve relative to base
-            let basedir = Path::new(basefile)
-                .parent()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_default();
-            if basedir.is_empty() {
-                parts[0].to_string()
-            } else {
-                format!("{}/{}", basedir, parts[0])
-            }
+    // Follow targets that are themselves references until the chain ends,
+    // failing if it comes back to a target it has already visited.
+    let mut visited = HashSet::new();
+    let info = loop {
+        if !visited.insert(format!("{}#{}", filename, pointer)) {
+            return Err(CompilerError::Unlocated {
+                path: reference.to_string(),
+                message: format!("is a circular reference through {}#{}", filename, pointer),
+                severity: Severity::Error,
+                code: ErrorCode::CircularReference,
+            });
         }
-    } else {
-        basefile.to_string()
-    };
 
-    // Read and parse the file
-    let bytes = read_bytes_for_file(&filename)?;
-    let mut info = read_info_from_bytes(&filename, &bytes)?;
-
-    // Handle document node (serde_yaml returns single value, not array)
-    // Navigate to the referenced path
-    if parts.len() > 1 && !parts[1].is_empty() {
-        let path: Vec<&str> = parts[1].split('/').collect();
-        for (i, key) in path.iter().enumerate() {
-            if i > 0 && !key.is_empty() {
-                // Skip empty keys (from leading /)
-                if let Yaml::Mapping(ref map) = info {
-                    if let Some(value) = map.get(&Yaml::String((*key).to_string())) {
-                        info = value.clone();
-                    } else {
-                        if cache_enabled {
-                            INFO_CACHE.write().insert(reference.to_string(), Yaml::Null);
-                        }
-                        return Err(CompilerError::Simple(format!(
-                            "could not resolve {}",
-                            reference
-                        )));
-                    }
-                } else {
-                    return Err(CompilerError::Simple(format!(
-                        "could not resolve {}",
-                        reference
-                    )));
-                }
+        // Read and parse the file, then navigate to the referenced path
+        policy.check(&filename).map_err(|message| CompilerError::Unlocated {
+            path: reference.to_string(),
+            message,
+            severity: Severity::Error,
+            code: ErrorCode::FetchDenied,
+        })?;
+        let root = read_shared_info_for_file(&filename)?;
+        let Some(info) = node_for_pointer(&root, &pointer).cloned() else {
+            if cache_enabled {
+                INFO_CACHE.write().insert(cache_key, Arc::new(Yaml::Null));
             }
+            return Err(CompilerError::Simple(format!("could not resolve {}", reference)));
+        };
+        let Some(next) = info.get("$ref").and_then(Yaml::as_str) else {
+            break info;
+        };
+        let (file, next_pointer) = next.split_once('#').unwrap_or((next, ""));
+        if !file.is_empty() {
+            filename = filename_for_ref(&filename, file);
         }
-    }
+        pointer = next_pointer.to_string();
+    };
 
     // Store in cache
     if cache_enabled {
-        INFO_CACHE.write().insert(reference.to_string(), info.clone());
+        INFO_CACHE.write().insert(cache_key, Arc::new(info.clone()));
     }
 
     Ok(info)
@@ -347,6 +657,46 @@ mod tests {
         enable_info_cache();
     }
 
+    #[test]
+    fn test_shared_info_cache_hits() {
+        enable_info_cache();
+        let filename = "shared-cache-hits.yaml";
+        remove_from_info_cache(filename);
+
+        // Hits return the cached tree without parsing the bytes again
+        let first = read_shared_info_from_bytes(filename, b"type: string").unwrap();
+        let second = read_shared_info_from_bytes(filename, b"type: integer").unwrap();
+        let third = read_shared_info_from_bytes(filename, b"").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(Arc::ptr_eq(&first, &third));
+        assert_eq!(read_info_from_bytes(filename, b"type: integer").unwrap(), *first);
+
+        // Trees are not shared across files or once evicted
+        let other = read_shared_info_from_bytes("other-shared-cache-hits.yaml", b"type: string").unwrap();
+        assert!(!Arc::ptr_eq(&first, &other));
+        remove_from_info_cache(filename);
+        let reparsed = read_shared_info_from_bytes(filename, b"type: string").unwrap();
+        assert!(!Arc::ptr_eq(&first, &reparsed));
+        assert_eq!(reparsed, first);
+    }
+
+    #[test]
+    fn test_cached_document() {
+        let parse = |bytes: &[u8]| -> std::result::Result<String, ()> { Ok(String::from_utf8_lossy(bytes).into()) };
+        assert_eq!(cached_document(b"a", || parse(b"a")), Ok("a".to_string()));
+
+        enable_document_cache();
+        assert_eq!(cached_document(b"cached", || parse(b"cached")), Ok("cached".to_string()));
+        // Hits do not call the parser
+        assert_eq!(cached_document(b"cached", || parse(b"other")), Ok("cached".to_string()));
+        assert_eq!(cached_document(b"cached", || Ok::<_, ()>(vec![1u8])), Ok(vec![1u8]));
+        assert_eq!(cached_document(b"changed", || parse(b"changed")), Ok("changed".to_string()));
+        assert_eq!(cached_document(b"failed", || Err::<String, _>(())), Err(()));
+        assert_eq!(cached_document(b"failed", || parse(b"failed")), Ok("failed".to_string()));
+        disable_document_cache();
+        assert_eq!(cached_document(b"cached", || parse(b"other")), Ok("other".to_string()));
+    }
+
     #[test]
     fn test_read_info_from_bytes() {
         let yaml_content = b"name: test\nvalue: 123";
@@ -356,4 +706,129 @@ mod tests {
         let yaml = result.unwrap();
         assert!(matches!(yaml, Yaml::Mapping(_)));
     }
+
+    #[test]
+    fn test_json_fast_path_matches_yaml() {
+        let json = br#"{"b": [1, 2.5, "x", true, null], "a": {"c": "d"}, "n": 18446744073709551615}"#;
+        let from_json = read_info_from_json_bytes(json).unwrap();
+        let from_yaml: Yaml = serde_yaml::from_slice(json).unwrap();
+        assert_eq!(from_json, from_yaml);
+    }
+
+    #[test]
+    fn test_json_fast_path_number_precision() {
+        let json = br#"[18446744073709551615, -9223372036854775808, 18446744073709551616, 0.1, 1.00000000000000000001]"#;
+        let yaml = read_info_from_json_bytes(json).unwrap();
+        let numbers = yaml.as_sequence().unwrap();
+        assert_eq!(numbers[0].as_u64(), Some(u64::MAX));
+        assert_eq!(numbers[1].as_i64(), Some(i64::MIN));
+        assert_eq!((numbers[2].as_u64(), numbers[2].as_f64()), (None, Some(18446744073709551616.0)));
+        assert_eq!(numbers[3].as_f64(), Some(0.1));
+        assert_eq!(numbers[4].as_f64(), Some(1.0));
+        assert!(matches!(read_info_from_json_bytes(b"{"), Err(CompilerError::Json { .. })));
+    }
+
+    #[test]
+    fn test_json_fast_path_preserves_order() {
+        let yaml = read_info_from_bytes("", br#"{"z": 1, "a": 2, "m": 3}"#).unwrap();
+        assert_eq!(crate::keys_for_map(&yaml), vec!["z", "a", "m"]);
+    }
+
+    #[test]
+    fn test_json_input_detection() {
+        assert!(has_json_extension("spec.json"));
+        assert!(has_json_extension("SPEC.JSON"));
+        assert!(!has_json_extension("spec.yaml"));
+        assert!(looks_like_json(b"  \n{\"a\": 1}"));
+        assert!(looks_like_json(b"[1]"));
+        assert!(!looks_like_json(b"openapi: 3.0.0"));
+    }
+
+    #[test]
+    fn test_json_input_falls_back_to_yaml() {
+        // Valid YAML flow mapping that is not valid JSON
+        let yaml = read_info_from_bytes("", b"{a: 1, b: [x, y]}").unwrap();
+        assert!(matches!(yaml, Yaml::Mapping(_)));
+        let yaml = read_info_from_bytes("spec.yaml", b"{\"a\": 1,}").unwrap();
+        assert!(matches!(yaml, Yaml::Mapping(_)));
+    }
+
+    #[test]
+    fn test_json_extension_requires_json() {
+        // Files named as JSON report JSON syntax errors instead of being read as YAML
+        let error = read_info_from_bytes("x.json", b"{\"a\": 1,}").unwrap_err();
+        assert!(matches!(error, CompilerError::Json { .. }), "{}", error);
+        let error = read_info_from_bytes("x.json", b"openapi: 3.0.0").unwrap_err();
+        assert!(matches!(error, CompilerError::Json { .. }), "{}", error);
+    }
+
+    #[test]
+    fn test_read_info_for_ref_chains() {
+        let path = std::env::temp_dir().join(format!("gnostic-ref-chain-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            "a:\n  $ref: '#/b'\nb:\n  $ref: '#/c'\nc:\n  type: string\nx:\n  $ref: '#/y'\ny:\n  $ref: '#/x'\n",
+        )
+        .unwrap();
+        let basefile = path.to_string_lossy().to_string();
+
+        let info = read_info_for_ref(&basefile, "#/a").unwrap();
+        assert_eq!(info, serde_yaml::from_str::<Yaml>("type: string").unwrap());
+
+        let err = read_info_for_ref(&basefile, "#/x").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::CircularReference);
+        assert!(err.to_string().starts_with("#/x is a circular reference through "));
+
+        let err = read_info_for_ref_with_policy(&basefile, "#/a", &FetchPolicy::new().deny_local()).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::FetchDenied);
+        let err = read_info_for_ref(&basefile, "http://169.254.169.254/latest#/a").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::FetchDenied);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(not(feature = "http"))]
+    #[test]
+    fn test_fetch_without_http_feature() {
+        let err = read_bytes_for_file("http://example.com/no-http/openapi.yaml").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Http);
+        assert!(err.to_string().contains("built without the `http` feature"), "{}", err);
+
+        // A remote $ref that the policy allows fails the same way.
+        let policy = FetchPolicy::new().allow_scheme("https");
+        let err = read_info_for_ref_with_policy("openapi.yaml", "https://example.com/no-http/pet.yaml#/Pet", &policy)
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Http);
+    }
+
+    #[test]
+    fn test_filename_for_ref() {
+        assert_eq!(filename_for_ref("", "pet.yaml"), "pet.yaml");
+        assert_eq!(filename_for_ref("specs/openapi.yaml", "./models/pet.yaml"), "specs/models/pet.yaml");
+        assert_eq!(filename_for_ref("specs/models/pet.yaml", "../common.yaml"), "specs/common.yaml");
+        assert_eq!(filename_for_ref("/specs/openapi.yaml", "../../x.yaml"), "/x.yaml");
+        assert_eq!(filename_for_ref("specs/openapi.yaml", "/abs/pet.yaml"), "/abs/pet.yaml");
+        assert_eq!(filename_for_ref("../specs/openapi.yaml", "../pet.yaml"), "../pet.yaml");
+
+        let base = "https://example.com/api/openapi.yaml";
+        assert_eq!(filename_for_ref(base, "./models/pet.yaml"), "https://example.com/api/models/pet.yaml");
+        assert_eq!(filename_for_ref(base, "../common.yaml"), "https://example.com/common.yaml");
+        assert_eq!(filename_for_ref(base, "/root.yaml"), "https://example.com/root.yaml");
+        assert_eq!(filename_for_ref(base, "https://other.org/x.yaml"), "https://other.org/x.yaml");
+    }
+
+    #[test]
+    fn test_pointer_segments() {
+        assert!(pointer_segments("").is_empty());
+        assert!(pointer_segments("#").is_empty());
+        assert_eq!(pointer_segments("#/a~1b/c~0d/~01"), ["a/b", "c~d", "~1"]);
+        assert_eq!(pointer_segments("/definitions/Pet"), ["definitions", "Pet"]);
+        assert_eq!(pointer_segments("/paths/~1pets~1{id}"), ["paths", "/pets/{id}"]);
+        assert_eq!(pointer_segments("/paths/~1pets~1%7Bid%7D/get"), ["paths", "/pets/{id}", "get"]);
+        // "~01" is "~1", not "/"
+        assert_eq!(pointer_segments("/a~01b/c%20d/100%"), ["a~1b", "c d", "100%"]);
+        assert_eq!(pointer_for_segments(&["paths", "/pets/{id}", "a~b"]), "/paths/~1pets~1{id}/a~0b");
+
+        let node: Yaml = serde_yaml::from_str("paths:\n  /pets/{id}:\n    get: x\n").unwrap();
+        assert_eq!(node_for_pointer(&node, "/paths/~1pets~1%7Bid%7D/get"), Some(&Yaml::String("x".to_string())));
+    }
 }
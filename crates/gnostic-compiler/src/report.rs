@@ -0,0 +1,262 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A report of the findings of validators and linters.
+//!
+//! Validators report [`CompilerError`]s located by context paths such as
+//! "$.paths./pets.get", and lint rules add the name of the rule. A
+//! [`ValidationReport`] gives both the same shape, located by JSON pointers
+//! into the document, so that CI tools can consume them as JSON.
+
+use crate::lint::LintReport;
+use crate::reader::pointer_for_segments;
+use crate::{CompilerError, ErrorGroup, Severity};
+use serde_yaml::Value as Yaml;
+use std::fmt;
+
+/// A finding of a validator, semantic check or lint rule.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Finding {
+    /// The error code ("GNO0012") or the name of the lint rule.
+    pub rule: String,
+    pub severity: Severity,
+    /// JSON pointer to the node the finding is about, such as
+    /// "/paths/~1pets/get"; "" for the document.
+    pub pointer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub message: String,
+    /// A change that would resolve the finding, if one is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    /// Creates a finding for `error` reported by `rule`, locating it in `node`.
+    pub fn new(rule: impl Into<String>, error: &CompilerError, node: &Yaml) -> Self {
+        let (line, column) = error.location().unzip();
+        let fix = match error {
+            CompilerError::UnknownKey {
+                key,
+                suggestion: Some(suggestion),
+                ..
+            } => Some(format!("rename {} to {}", key, suggestion)),
+            _ => None,
+        };
+        Finding {
+            rule: rule.into(),
+            severity: error.severity(),
+            pointer: error.path().map(|path| pointer_for_path(node, path)).unwrap_or_default(),
+            line,
+            column,
+            message: error.message().into_owned(),
+            fix,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} #{} {}", self.severity, self.rule, self.pointer, self.message)
+    }
+}
+
+/// The findings of validators and linters for a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        ValidationReport::default()
+    }
+
+    /// Creates a report of the errors of a validator for the document `node`,
+    /// identified by their codes.
+    pub fn from_errors(errors: &ErrorGroup, node: &Yaml) -> Self {
+        let mut report = ValidationReport::new();
+        report.add_errors(errors, node);
+        report
+    }
+
+    /// Creates a report of the findings of a linter for the document `node`.
+    pub fn from_lint(lint: &LintReport, node: &Yaml) -> Self {
+        let mut report = ValidationReport::new();
+        report.add_lint(lint, node);
+        report
+    }
+
+    /// Adds the errors of a validator, identified by their codes.
+    pub fn add_errors(&mut self, errors: &ErrorGroup, node: &Yaml) {
+        for error in &errors.errors {
+            self.findings.push(Finding::new(error.code().as_str(), error, node));
+        }
+    }
+
+    /// Adds the findings of a linter, identified by their rules.
+    pub fn add_lint(&mut self, lint: &LintReport, node: &Yaml) {
+        for finding in &lint.findings {
+            self.findings.push(Finding::new(finding.rule.as_str(), &finding.error, node));
+        }
+    }
+
+    /// Returns true if the report has no findings.
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Returns true if any finding has error severity.
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|finding| finding.severity == Severity::Error)
+    }
+
+    /// Returns the number of findings with the given severity.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|finding| finding.severity == severity).count()
+    }
+
+    /// Returns the report as a pretty-printed JSON document.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, finding) in self.findings.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", finding)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the JSON pointer for a context path such as "$.paths./pets.get".
+///
+/// Keys may contain dots, so the path is matched against the keys of `node`,
+/// preferring the longest key that fits. Parts of the path that are not in
+/// the document are split at dots.
+fn pointer_for_path(node: &Yaml, path: &str) -> String {
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    rest = rest.strip_prefix('.').unwrap_or(rest);
+    let mut current = Some(node);
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        let fits = |key: &str| rest == key || rest.strip_prefix(key).is_some_and(|tail| tail.starts_with('.'));
+        let keys: Vec<String> = match current {
+            Some(Yaml::Mapping(map)) => map.keys().filter_map(key_string).collect(),
+            Some(Yaml::Sequence(items)) => (0..items.len()).map(|i| i.to_string()).collect(),
+            _ => Vec::new(),
+        };
+        let segment = keys
+            .into_iter()
+            .filter(|key| fits(key))
+            .max_by_key(String::len)
+            .unwrap_or_else(|| rest.split('.').next().unwrap_or_default().to_string());
+        current = current.and_then(|node| match node {
+            Yaml::Mapping(map) => map
+                .iter()
+                .find(|(key, _)| key_string(key).as_deref() == Some(segment.as_str()))
+                .map(|(_, value)| value),
+            Yaml::Sequence(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        });
+        rest = rest[segment.len()..].strip_prefix('.').unwrap_or_default();
+        segments.push(segment);
+    }
+    pointer_for_segments(&segments)
+}
+
+/// Returns a mapping key as a string; numeric keys such as response codes
+/// are written in their YAML form.
+fn key_string(key: &Yaml) -> Option<String> {
+    match key {
+        Yaml::String(key) => Some(key.clone()),
+        Yaml::Number(number) => Some(number.to_string()),
+        Yaml::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, ErrorCode, LintFinding};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_pointer_for_path() {
+        let node: Yaml = serde_yaml::from_str(
+            "paths:\n  /pets.json:\n    get:\n      responses:\n        200:\n          description: ok\ntags:\n- name: a/b\n",
+        )
+        .unwrap();
+        assert_eq!(pointer_for_path(&node, "$"), "");
+        assert_eq!(
+            pointer_for_path(&node, "$.paths./pets.json.get.responses.200"),
+            "/paths/~1pets.json/get/responses/200"
+        );
+        assert_eq!(pointer_for_path(&node, "$.tags.0.name"), "/tags/0/name");
+        assert_eq!(pointer_for_path(&node, "$.components.schemas.a.b"), "/components/schemas/a/b");
+    }
+
+    #[test]
+    fn test_validation_report() {
+        let node: Yaml = serde_yaml::from_str("info:\n  titel: Pets\npaths: {}\n").unwrap();
+        let root = Arc::new(Context::root("$"));
+        let info = root.child("info");
+        let errors = ErrorGroup::new(vec![
+            CompilerError::unknown_key(&info, "titel", &["title", "version"]),
+            CompilerError::new_with_code(&info, ErrorCode::MissingRequiredField, "is missing required property: title"),
+        ]);
+        let mut report = ValidationReport::from_errors(&errors, &node);
+        report.add_lint(
+            &LintReport {
+                findings: vec![LintFinding {
+                    rule: "info-description".to_string(),
+                    error: CompilerError::warning(&info, "should have a description"),
+                }],
+            },
+            &node,
+        );
+        assert_eq!(
+            report.to_string(),
+            [
+                "error GNO0012 #/info has invalid property: titel (did you mean title?)",
+                "error GNO0001 #/info is missing required property: title",
+                "warning info-description #/info should have a description",
+            ]
+            .join("\n")
+        );
+        assert_eq!(report.count(Severity::Error), 2);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(
+            json["findings"][0],
+            serde_json::json!({
+                "rule": "GNO0012",
+                "severity": "error",
+                "pointer": "/info",
+                "message": "has invalid property: titel (did you mean title?)",
+                "fix": "rename titel to title",
+            })
+        );
+        assert!(json["findings"][1].get("fix").is_none());
+    }
+}
@@ -0,0 +1,762 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resolution of $ref values in YAML nodes.
+//!
+//! References are resolved on the YAML form of a document so that every kind
+//! of referenceable object is handled the same way. Internal references
+//! ("#/components/schemas/Pet") are looked up in the document containing them
+//! and external ones ("pet.yaml#/Pet", "https://...") are read with the
+//! [reader](crate::reader), relative to the file that contains them.
+//!
+//! [`resolve_references`] replaces references by their targets, while
+//! [`bundle_references`] copies the targets of external references into the
+//! document so that it no longer depends on other files.
+
+use crate::codes::ErrorCode;
+use crate::context::Context;
+use crate::error::{CompilerError, Severity};
+use crate::options::{ParseOptions, RefSiblings};
+use crate::policy::FetchPolicy;
+use crate::reader::{
+    filename_for_ref, node_for_pointer, pointer_for_segments, pointer_segments, read_shared_info_for_file,
+};
+use serde_yaml::{Mapping, Value as Yaml};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The outcome of resolving the references in a document.
+#[derive(Debug, Clone, Default)]
+pub struct ResolutionReport {
+    /// References that were replaced by their targets, as written in the source.
+    pub resolved: Vec<String>,
+    /// References that could not be resolved; they are left in place.
+    pub errors: Vec<CompilerError>,
+    /// Warnings for circular references, which are left in place.
+    pub cycles: Vec<CompilerError>,
+}
+
+impl ResolutionReport {
+    /// Returns true if every reference was resolved or is circular.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// How [`flatten_references`] breaks circular references.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CycleStrategy {
+    /// Replace a circular reference by an empty mapping, which as a schema
+    /// accepts any value.
+    #[default]
+    Truncate,
+    /// Expand a circular reference this many more times before truncating.
+    Unroll(usize),
+}
+
+/// Options for resolving, flattening and bundling the references of a node.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolveOptions {
+    /// The file or URL the node was read from, used to locate relative
+    /// external references; "" for documents read from memory.
+    pub base: String,
+    /// How keywords next to a $ref are treated.
+    pub ref_siblings: RefSiblings,
+    /// Which remote documents external references may load.
+    pub fetch_policy: FetchPolicy,
+}
+
+impl ResolveOptions {
+    /// Creates options for a document read from memory, with no remote
+    /// documents allowed.
+    pub fn new() -> Self {
+        ResolveOptions::default()
+    }
+
+    /// Returns the options with the file or URL the node was read from.
+    pub fn with_base(mut self, base: impl Into<String>) -> Self {
+        self.base = base.into();
+        self
+    }
+
+    /// Returns the options with the treatment of $ref siblings set.
+    pub fn with_ref_siblings(mut self, ref_siblings: RefSiblings) -> Self {
+        self.ref_siblings = ref_siblings;
+        self
+    }
+
+    /// Returns the options with the policy for fetching remote documents set.
+    pub fn with_fetch_policy(mut self, fetch_policy: FetchPolicy) -> Self {
+        self.fetch_policy = fetch_policy;
+        self
+    }
+}
+
+/// Returns a copy of `node` with every $ref replaced by its target.
+///
+/// A reference to the node containing it, or to one of that node's
+/// ancestors, would expand forever. Such circular references are left in
+/// place and reported in [`ResolutionReport::cycles`].
+///
+/// With [`RefSiblings::Merge`] the "summary" and "description" next to a
+/// reference replace those of its target. References to remote documents the
+/// fetch policy does not allow are reported as errors.
+pub fn resolve_references(node: &Yaml, options: &ResolveOptions) -> (Yaml, ResolutionReport) {
+    let source = Arc::new(Source {
+        file: options.base.clone(),
+        root: Arc::new(node.clone()),
+    });
+    let context = Arc::new(Context::root("$"));
+    let mut resolver = ReferenceResolver {
+        frames: vec![Frame {
+            source,
+            location: Vec::new(),
+        }],
+        cycle_strategy: None,
+        options,
+        report: ResolutionReport::default(),
+    };
+    let resolved = resolver.resolve_node(node, &context);
+    (resolved, resolver.report)
+}
+
+/// Returns the root node of a document to parse at `context`, with its
+/// references resolved if [`ParseOptions::resolve_references`] is set.
+///
+/// `base` locates relative external references, which are read only as
+/// [`ParseOptions::fetch_policy`] allows. References that cannot be
+/// resolved, and circular ones, are left in place and recorded as warnings
+/// of the parse.
+pub fn resolve_for_parse<'a>(node: &'a Yaml, base: &str, context: &Context) -> Cow<'a, Yaml> {
+    let options: &ParseOptions = &context.options;
+    if !options.resolve_references {
+        return Cow::Borrowed(node);
+    }
+    let version = node.get("openapi").or_else(|| node.get("swagger")).and_then(Yaml::as_str).unwrap_or_default();
+    let ref_siblings = options.ref_siblings.unwrap_or_else(|| RefSiblings::for_version(version));
+    let resolve_options = ResolveOptions::new()
+        .with_base(base)
+        .with_ref_siblings(ref_siblings)
+        .with_fetch_policy(options.fetch_policy.clone());
+    let (resolved, report) = resolve_references(node, &resolve_options);
+    let warnings = report.errors.into_iter().chain(report.cycles).map(|e| e.with_severity(Severity::Warning));
+    context.diagnostics.lock().extend(warnings);
+    Cow::Owned(resolved)
+}
+
+/// Returns a copy of `node` with every $ref replaced by its target and no
+/// references left, breaking circular references with `strategy`.
+///
+/// Broken cycles are reported in [`ResolutionReport::cycles`].
+pub fn flatten_references(
+    node: &Yaml,
+    options: &ResolveOptions,
+    strategy: CycleStrategy,
+) -> (Yaml, ResolutionReport) {
+    let source = Arc::new(Source {
+        file: options.base.clone(),
+        root: Arc::new(node.clone()),
+    });
+    let context = Arc::new(Context::root("$"));
+    let mut resolver = ReferenceResolver {
+        frames: vec![Frame {
+            source,
+            location: Vec::new(),
+        }],
+        cycle_strategy: Some(strategy),
+        options,
+        report: ResolutionReport::default(),
+    };
+    let flattened = resolver.resolve_node(node, &context);
+    (flattened, resolver.report)
+}
+
+/// A document that references are resolved against.
+struct Source {
+    file: String,
+    root: Arc<Yaml>,
+}
+
+/// A document the resolver is inside, entered from the root or by following
+/// a reference, and the location of the node being resolved within it.
+struct Frame {
+    source: Arc<Source>,
+    location: Vec<String>,
+}
+
+struct ReferenceResolver<'a> {
+    frames: Vec<Frame>,
+    /// How circular references are broken; None leaves them in place.
+    cycle_strategy: Option<CycleStrategy>,
+    options: &'a ResolveOptions,
+    report: ResolutionReport,
+}
+
+impl ReferenceResolver<'_> {
+    fn resolve_node(&mut self, node: &Yaml, context: &Arc<Context>) -> Yaml {
+        match node {
+            Yaml::Mapping(map) => {
+                if let Some(reference) = map.get("$ref").and_then(Yaml::as_str) {
+                    return self.resolve_reference(node, reference, context);
+                }
+                let mut resolved = Mapping::with_capacity(map.len());
+                for (key, value) in map {
+                    let name = key.as_str().map(str::to_string).unwrap_or_default();
+                    let child = Arc::new(context.child(name.as_str()));
+                    let value = self.within(name, |resolver| resolver.resolve_node(value, &child));
+                    resolved.insert(key.clone(), value);
+                }
+                Yaml::Mapping(resolved)
+            }
+            Yaml::Sequence(items) => Yaml::Sequence(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| {
+                        let child = Arc::new(context.child(i.to_string()));
+                        self.within(i.to_string(), |resolver| resolver.resolve_node(item, &child))
+                    })
+                    .collect(),
+            ),
+            _ => node.clone(),
+        }
+    }
+
+    fn resolve_reference(&mut self, node: &Yaml, reference: &str, context: &Arc<Context>) -> Yaml {
+        let source = Arc::clone(&self.frames.last().expect("resolver has a frame").source);
+        let (target, frame) = match self.target(reference, &source) {
+            Ok(target) => target,
+            Err((code, message)) => {
+                self.report.errors.push(CompilerError::new_with_code(context, code, message));
+                return node.clone();
+            }
+        };
+        let expansions = self.active_count(&frame);
+        let unrolled = matches!(self.cycle_strategy, Some(CycleStrategy::Unroll(n)) if expansions <= n);
+        if expansions > 0 && !unrolled {
+            self.report.cycles.push(
+                CompilerError::new_with_code(
+                    context,
+                    ErrorCode::CircularReference,
+                    format!("circular reference {}", reference),
+                )
+                .with_severity(Severity::Warning),
+            );
+            return match self.cycle_strategy {
+                None => node.clone(),
+                Some(_) => Yaml::Mapping(Mapping::new()),
+            };
+        }
+        self.report.resolved.push(reference.to_string());
+        self.frames.push(frame);
+        let mut resolved = self.resolve_node(&target, context);
+        self.frames.pop();
+        if let (RefSiblings::Merge, Yaml::Mapping(map), Yaml::Mapping(target)) =
+            (self.options.ref_siblings, node, &mut resolved)
+        {
+            for field in RefSiblings::FIELDS {
+                if let Some(value) = map.get(*field) {
+                    target.insert(Yaml::String(field.to_string()), value.clone());
+                }
+            }
+        }
+        resolved
+    }
+
+    /// Runs `f` with the current location extended by `segment`.
+    fn within<T>(&mut self, segment: String, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.frames.last_mut().expect("resolver has a frame").location.push(segment);
+        let result = f(self);
+        self.frames.last_mut().expect("resolver has a frame").location.pop();
+        result
+    }
+
+    /// Returns the number of times a reference target is being resolved,
+    /// that is the number of frames inside it. Any number but zero means the
+    /// reference is circular.
+    fn active_count(&self, target: &Frame) -> usize {
+        self.frames
+            .iter()
+            .filter(|frame| {
+                frame.source.file == target.source.file
+                    && frame.location.starts_with(&target.location)
+            })
+            .count()
+    }
+
+    /// Returns the node a reference points to and a frame for its location.
+    fn target(&self, reference: &str, source: &Arc<Source>) -> Result<(Yaml, Frame), (ErrorCode, String)> {
+        let root = &self.frames[0].source;
+        let (target, target_source, pointer) = find_target(reference, source, root, &self.options.fetch_policy)?;
+        let frame = Frame {
+            source: target_source,
+            location: pointer_segments(pointer),
+        };
+        Ok((target, frame))
+    }
+}
+
+/// Returns the node a reference in `source` points to, the document
+/// containing it and the pointer to it within that document. References to
+/// the root document use the node being resolved rather than the file.
+fn find_target<'r>(
+    reference: &'r str,
+    source: &Arc<Source>,
+    root: &Arc<Source>,
+    policy: &FetchPolicy,
+) -> Result<(Yaml, Arc<Source>, &'r str), (ErrorCode, String)> {
+    let unresolved = |message: String| (ErrorCode::UnresolvedReference, message);
+    let (file, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+    let target_source = if file.is_empty() {
+        Arc::clone(source)
+    } else if !root.file.is_empty() && filename_for_ref(&source.file, file) == root.file {
+        Arc::clone(root)
+    } else {
+        let filename = filename_for_ref(&source.file, file);
+        policy
+            .check(&filename)
+            .map_err(|e| (ErrorCode::FetchDenied, format!("could not resolve {}: {}", reference, e)))?;
+        let root = read_shared_info_for_file(&filename)
+            .map_err(|e| unresolved(format!("could not resolve {}: {}", reference, e)))?;
+        Arc::new(Source { file: filename, root })
+    };
+    let target = node_for_pointer(&target_source.root, pointer)
+        .ok_or_else(|| unresolved(format!("could not resolve {}", reference)))?
+        .clone();
+    Ok((target, target_source, pointer))
+}
+
+/// Returns a copy of `node` in which every external reference points to a
+/// copy of its target added to the document itself.
+///
+/// `section_for` receives the location of a reference in the bundled document
+/// and returns the path of the mapping that its target is added to, such as
+/// `["components", "schemas"]`, or None to replace the reference by its
+/// target. Targets are named after the last key of their pointer, or the file
+/// name for whole files, with a numeric suffix if the name is already taken.
+/// References that define an entry of a section are replaced by their target.
+/// Internal references are kept as they are.
+pub fn bundle_references(
+    node: &Yaml,
+    options: &ResolveOptions,
+    section_for: impl Fn(&[String]) -> Option<Vec<String>>,
+) -> (Yaml, ResolutionReport) {
+    let root = Arc::new(Source {
+        file: options.base.clone(),
+        root: Arc::new(node.clone()),
+    });
+    let mut bundler = Bundler {
+        root: Arc::clone(&root),
+        policy: &options.fetch_policy,
+        section_for: &section_for,
+        bundled: HashMap::new(),
+        inlining: Vec::new(),
+        additions: Vec::new(),
+        report: ResolutionReport::default(),
+    };
+    let mut bundled = bundler.bundle_node(node, &root, &mut Vec::new());
+    for (section, name, target) in bundler.additions {
+        if let Some(map) = mapping_at_mut(&mut bundled, &section) {
+            map.insert(Yaml::String(name), target);
+        }
+    }
+    (bundled, bundler.report)
+}
+
+struct Bundler<'a> {
+    root: Arc<Source>,
+    policy: &'a FetchPolicy,
+    section_for: &'a dyn Fn(&[String]) -> Option<Vec<String>>,
+    /// Local references to the targets bundled so far, by file and pointer.
+    bundled: HashMap<String, String>,
+    /// Targets being inlined, by file and pointer.
+    inlining: Vec<String>,
+    /// Targets to add to the document, with their section and name.
+    additions: Vec<(Vec<String>, String, Yaml)>,
+    report: ResolutionReport,
+}
+
+impl Bundler<'_> {
+    fn bundle_node(&mut self, node: &Yaml, source: &Arc<Source>, location: &mut Vec<String>) -> Yaml {
+        match node {
+            Yaml::Mapping(map) => {
+                if let Some(reference) = map.get("$ref").and_then(Yaml::as_str) {
+                    return self.bundle_reference(node, reference, source, location);
+                }
+                let mut bundled = Mapping::with_capacity(map.len());
+                for (key, value) in map {
+                    location.push(key.as_str().map(str::to_string).unwrap_or_default());
+                    bundled.insert(key.clone(), self.bundle_node(value, source, location));
+                    location.pop();
+                }
+                Yaml::Mapping(bundled)
+            }
+            Yaml::Sequence(items) => Yaml::Sequence(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| {
+                        location.push(i.to_string());
+                        let bundled = self.bundle_node(item, source, location);
+                        location.pop();
+                        bundled
+                    })
+                    .collect(),
+            ),
+            _ => node.clone(),
+        }
+    }
+
+    fn bundle_reference(
+        &mut self,
+        node: &Yaml,
+        reference: &str,
+        source: &Arc<Source>,
+        location: &mut Vec<String>,
+    ) -> Yaml {
+        let (target, target_source, pointer) = match find_target(reference, source, &self.root, self.policy) {
+            Ok(target) => target,
+            Err((code, message)) => {
+                self.report.errors.push(CompilerError::new_with_code(&context_for(location), code, message));
+                return node.clone();
+            }
+        };
+        if target_source.file == self.root.file {
+            // References from bundled files back into the root become internal
+            return with_reference(node, &format!("#{}", pointer));
+        }
+
+        let key = format!("{}#{}", target_source.file, pointer);
+        let section = (self.section_for)(location);
+        if let Some(local) = section.as_ref().and_then(|_| self.bundled.get(&key)) {
+            return with_reference(node, local);
+        }
+        self.report.resolved.push(reference.to_string());
+        match section {
+            // A component defined as a reference is replaced by its target
+            Some(section) if location.len() == section.len() + 1 && location.starts_with(&section) => {
+                self.bundled.insert(key, format!("#{}", pointer_for_segments(location)));
+                self.bundle_node(&target, &target_source, location)
+            }
+            Some(section) => {
+                let name = self.unique_name(&section, &preferred_name(&target_source.file, pointer));
+                let mut target_location = section.clone();
+                target_location.push(name.clone());
+                let local = format!("#{}", pointer_for_segments(&target_location));
+                self.bundled.insert(key, local.clone());
+                let bundled = self.bundle_node(&target, &target_source, &mut target_location);
+                self.additions.push((section, name, bundled));
+                with_reference(node, &local)
+            }
+            None if self.inlining.contains(&key) => {
+                self.report.cycles.push(
+                    CompilerError::new_with_code(
+                        &context_for(location),
+                        ErrorCode::CircularReference,
+                        format!("circular reference {}", reference),
+                    )
+                    .with_severity(Severity::Warning),
+                );
+                node.clone()
+            }
+            None => {
+                self.inlining.push(key);
+                let bundled = self.bundle_node(&target, &target_source, location);
+                self.inlining.pop();
+                bundled
+            }
+        }
+    }
+
+    /// Returns `preferred`, with a numeric suffix if a node of that name
+    /// already exists in the section.
+    fn unique_name(&self, section: &[String], preferred: &str) -> String {
+        let section_pointer: String = section.iter().map(|key| format!("/{}", key)).collect();
+        let existing = node_for_pointer(&self.root.root, &section_pointer);
+        let taken = |name: &str| {
+            existing.and_then(|node| node.get(name)).is_some()
+                || self
+                    .additions
+                    .iter()
+                    .any(|(s, n, _)| s.as_slice() == section && n == name)
+                    // Names reserved by targets that are still being bundled
+                    || self.bundled.values().any(|local| *local == format!("#{}/{}", section_pointer, name))
+        };
+        let mut name = preferred.to_string();
+        let mut suffix = 1;
+        while taken(&name) {
+            suffix += 1;
+            name = format!("{}{}", preferred, suffix);
+        }
+        name
+    }
+}
+
+/// Returns the name for a bundled target: the last key of its pointer, or
+/// the file name without extension, limited to characters allowed in
+/// component names.
+fn preferred_name(file: &str, pointer: &str) -> String {
+    let name = match pointer_segments(pointer).pop() {
+        Some(key) => key,
+        None => Path::new(file)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "._-".contains(c) { c } else { '_' })
+        .collect();
+    if name.is_empty() {
+        "bundled".to_string()
+    } else {
+        name
+    }
+}
+
+/// Returns a copy of a reference node pointing to `reference`.
+fn with_reference(node: &Yaml, reference: &str) -> Yaml {
+    let mut node = node.clone();
+    if let Yaml::Mapping(map) = &mut node {
+        map.insert(Yaml::String("$ref".to_string()), Yaml::String(reference.to_string()));
+    }
+    node
+}
+
+/// Returns the mapping at `path`, creating missing mappings along the way.
+fn mapping_at_mut<'a>(node: &'a mut Yaml, path: &[String]) -> Option<&'a mut Mapping> {
+    let mut current = node;
+    for key in path {
+        let Yaml::Mapping(map) = current else {
+            return None;
+        };
+        current = map
+            .entry(Yaml::String(key.clone()))
+            .or_insert_with(|| Yaml::Mapping(Mapping::new()));
+    }
+    match current {
+        Yaml::Mapping(map) => Some(map),
+        _ => None,
+    }
+}
+
+/// Returns a context describing a location in a document.
+fn context_for(location: &[String]) -> Arc<Context> {
+    location
+        .iter()
+        .fold(Arc::new(Context::root("$")), |context, key| Arc::new(context.child(key.clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(text: &str) -> Yaml {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_internal_references() {
+        let node = yaml(
+            "a:\n  $ref: '#/defs/b'\nlist:\n- $ref: '#/defs/c'\ndefs:\n  b:\n    $ref: '#/defs/c'\n  c:\n    type: string\n",
+        );
+        let (resolved, report) = resolve_references(&node, &ResolveOptions::new());
+        assert!(report.is_complete());
+        assert_eq!(report.resolved, ["#/defs/b", "#/defs/c", "#/defs/c", "#/defs/c"]);
+        assert_eq!(resolved["a"], yaml("type: string"));
+        assert_eq!(resolved["list"][0], yaml("type: string"));
+        assert_eq!(resolved["defs"]["b"], yaml("type: string"));
+    }
+
+    #[test]
+    fn test_unresolved_reference() {
+        let node = yaml("a:\n  b:\n    $ref: '#/missing'\n");
+        let (resolved, report) = resolve_references(&node, &ResolveOptions::new());
+        assert_eq!(resolved, node);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].to_string(), "$.a.b could not resolve #/missing");
+        assert_eq!(report.errors[0].code(), ErrorCode::UnresolvedReference);
+    }
+
+    #[test]
+    fn test_circular_references() {
+        let node = yaml(
+            "defs:\n  Node:\n    properties:\n      child:\n        $ref: '#/defs/Node'\n  A:\n    $ref: '#/defs/B'\n  B:\n    $ref: '#/defs/A'\nroot:\n  $ref: '#/defs/Node'\nself:\n  $ref: '#/self'\n",
+        );
+        let (resolved, report) = resolve_references(&node, &ResolveOptions::new());
+        assert!(report.is_complete());
+        // Self-referential schemas keep the reference to themselves
+        assert_eq!(resolved["defs"]["Node"], node["defs"]["Node"]);
+        assert_eq!(resolved["root"], node["defs"]["Node"]);
+        // Mutually recursive references stop where the chain closes
+        assert_eq!(resolved["defs"]["A"], yaml("$ref: '#/defs/A'"));
+        assert_eq!(resolved["self"], node["self"]);
+
+        let paths: Vec<String> = report.cycles.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            paths,
+            [
+                "warning: $.defs.Node.properties.child circular reference #/defs/Node",
+                "warning: $.defs.A circular reference #/defs/A",
+                "warning: $.defs.B circular reference #/defs/B",
+                "warning: $.root.properties.child circular reference #/defs/Node",
+                "warning: $.self circular reference #/self",
+            ]
+        );
+        assert!(report.cycles.iter().all(|e| e.code() == ErrorCode::CircularReference));
+    }
+
+    #[test]
+    fn test_ref_siblings() {
+        let node = yaml(
+            "a:\n  $ref: '#/defs/b'\n  description: local\n  summary: short\n  type: number\ndefs:\n  b:\n    type: string\n    description: shared\n",
+        );
+        let (ignored, _) = resolve_references(&node, &ResolveOptions::new());
+        assert_eq!(ignored["a"], yaml("type: string\ndescription: shared\n"));
+        let (merged, _) = resolve_references(&node, &ResolveOptions::new().with_ref_siblings(RefSiblings::Merge));
+        assert_eq!(merged["a"], yaml("type: string\ndescription: local\nsummary: short\n"));
+    }
+
+    #[test]
+    fn test_fetch_policy() {
+        let node = yaml("a:\n  $ref: 'http://169.254.169.254/latest/meta-data#/x'\nb:\n  $ref: 'https://specs.test/pet.yaml#/Pet'\n");
+        let (resolved, report) = resolve_references(&node, &ResolveOptions::new());
+        assert_eq!(resolved, node);
+        assert_eq!(report.errors.len(), 2);
+        assert!(report.errors.iter().all(|e| e.code() == ErrorCode::FetchDenied));
+        assert_eq!(
+            report.errors[0].to_string(),
+            "$.a could not resolve http://169.254.169.254/latest/meta-data#/x: fetching http://169.254.169.254/latest/meta-data is not allowed: scheme http is not allowed"
+        );
+
+        // Relative references in remote documents are checked once resolved
+        let options = ResolveOptions::new()
+            .with_base("https://specs.test/api/openapi.yaml")
+            .with_fetch_policy(FetchPolicy::new().allow_scheme("https").allow_host("specs.test"));
+        let node = yaml("a:\n  $ref: '//169.254.169.254/pet.yaml#/Pet'\n");
+        let (_, report) = bundle_references(&node, &options, |_| None);
+        assert_eq!(report.errors[0].code(), ErrorCode::FetchDenied);
+
+        // Local files outside the allowed directories are not read
+        let options = ResolveOptions::new()
+            .with_base("/specs/openapi.yaml")
+            .with_fetch_policy(FetchPolicy::new().allow_local_root("/specs"));
+        let node = yaml("a:
+  $ref: '../etc/passwd#/x'
+");
+        let (_, report) = resolve_references(&node, &options);
+        assert_eq!(report.errors[0].code(), ErrorCode::FetchDenied);
+        assert!(report.errors[0].to_string().contains("outside the allowed directories"));
+    }
+
+    #[test]
+    fn test_resolve_for_parse_fetch_policy() {
+        let dir = std::env::temp_dir().join(format!("gnostic-parse-policy-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("pet.yaml"), "Pet:\n  type: object\n").unwrap();
+        let base = dir.join("openapi.yaml").to_string_lossy().to_string();
+        let node = yaml("a:\n  $ref: 'pet.yaml#/Pet'\n");
+
+        let options = ParseOptions::new().with_resolved_references();
+        let context = Context::root_with_options("$", options.clone());
+        assert_eq!(resolve_for_parse(&node, &base, &context)["a"], yaml("type: object"));
+
+        let context = Context::root_with_options("$", options.with_fetch_policy(FetchPolicy::new().deny_local()));
+        assert_eq!(*resolve_for_parse(&node, &base, &context), node);
+        let diagnostics = context.diagnostics.lock();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code(), ErrorCode::FetchDenied);
+        assert_eq!(diagnostics[0].severity(), Severity::Warning);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_flatten_references() {
+        let node = yaml(
+            "Node:\n  properties:\n    value:\n      $ref: '#/Value'\n    next:\n      $ref: '#/Node'\nValue:\n  type: string\n",
+        );
+        let (flattened, report) = flatten_references(&node, &ResolveOptions::new(), CycleStrategy::Truncate);
+        assert!(report.is_complete());
+        assert_eq!(report.cycles.len(), 1);
+        assert_eq!(
+            flattened["Node"],
+            yaml("properties:\n  value:\n    type: string\n  next: {}\n")
+        );
+
+        let (unrolled, report) = flatten_references(&node, &ResolveOptions::new(), CycleStrategy::Unroll(1));
+        assert_eq!(report.cycles.len(), 1);
+        assert_eq!(
+            unrolled["Node"]["properties"]["next"],
+            yaml("properties:\n  value:\n    type: string\n  next: {}\n")
+        );
+    }
+
+    #[test]
+    fn test_mutually_recursive_files() {
+        let dir = std::env::temp_dir().join(format!("gnostic-resolver-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.yaml"), "A:\n  properties:\n    b:\n      $ref: 'b.yaml#/B'\n").unwrap();
+        std::fs::write(dir.join("b.yaml"), "B:\n  properties:\n    a:\n      $ref: 'a.yaml#/A'\n").unwrap();
+
+        let node = yaml("root:\n  $ref: 'a.yaml#/A'\n");
+        let base = dir.join("openapi.yaml").to_string_lossy().to_string();
+        let (resolved, report) = resolve_references(&node, &ResolveOptions::new().with_base(base));
+        assert!(report.is_complete());
+        assert_eq!(report.resolved, ["a.yaml#/A", "b.yaml#/B"]);
+        assert_eq!(report.cycles.len(), 1);
+        assert_eq!(resolved["root"]["properties"]["b"]["properties"]["a"], yaml("$ref: 'a.yaml#/A'"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_bundle_references() {
+        let dir = std::env::temp_dir().join(format!("gnostic-bundle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("pet.yaml"),
+            "Pet:\n  properties:\n    owner:\n      $ref: '#/Owner'\n    error:\n      $ref: 'openapi.yaml#/defs/Error'\nOwner:\n  type: string\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("item.yaml"), "type: integer\n").unwrap();
+
+        let node = yaml(
+            "a:\n  $ref: 'pet.yaml#/Pet'\nb:\n  $ref: 'pet.yaml#/Pet'\nc:\n  $ref: 'item.yaml'\nd:\n  $ref: 'item.yaml'\n  description: inline\ndefs:\n  Owner:\n    type: object\n  Error:\n    type: string\n  Item:\n    $ref: 'item.yaml'\n",
+        );
+        let base = dir.join("openapi.yaml").to_string_lossy().to_string();
+        let section = |location: &[String]| match location.first().map(String::as_str) {
+            Some("d") => None,
+            _ => Some(vec!["defs".to_string()]),
+        };
+        let (bundled, report) = bundle_references(&node, &ResolveOptions::new().with_base(base), section);
+        assert!(report.is_complete());
+        assert_eq!(report.resolved, ["pet.yaml#/Pet", "#/Owner", "item.yaml", "item.yaml"]);
+
+        assert_eq!(bundled["a"], yaml("$ref: '#/defs/Pet'"));
+        assert_eq!(bundled["b"], yaml("$ref: '#/defs/Pet'"));
+        assert_eq!(bundled["c"], yaml("$ref: '#/defs/item'"));
+        assert_eq!(bundled["d"], yaml("type: integer"));
+        assert_eq!(
+            bundled["defs"]["Pet"],
+            yaml("properties:\n  owner:\n    $ref: '#/defs/Owner2'\n  error:\n    $ref: '#/defs/Error'\n")
+        );
+        // Names already in use get a numeric suffix
+        assert_eq!(bundled["defs"]["Owner"], yaml("type: object"));
+        assert_eq!(bundled["defs"]["Owner2"], yaml("type: string"));
+        // Targets bundled earlier are referred to rather than copied again
+        assert_eq!(bundled["defs"]["Item"], yaml("$ref: '#/defs/item'"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
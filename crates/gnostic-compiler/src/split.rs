@@ -0,0 +1,372 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Splitting of a document into several files.
+//!
+//! [`split_document`] is the inverse of bundling: the components of a
+//! document are moved to files of their own, one per component or one per
+//! section, and the references to them are rewritten as relative file
+//! references. The root file keeps its components sections, with each entry
+//! referring to the file that now holds it, so that bundling the files gives
+//! back the original document.
+
+use crate::emitter::emit_yaml;
+use crate::helpers::yaml_to_json;
+use crate::reader::{pointer_for_segments, pointer_segments};
+use crate::writer::EmitOptions;
+use serde_yaml::{Mapping, Value as Yaml};
+use std::collections::HashSet;
+
+/// How components are grouped into files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SplitLayout {
+    /// A file per component, such as "components/schemas/Pet.yaml".
+    #[default]
+    PerComponent,
+    /// A file per section, such as "components/schemas.yaml", holding the
+    /// components of the section by name.
+    PerSection,
+}
+
+/// Options for splitting a document into files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitOptions {
+    /// The path of the root file, such as "openapi.yaml". Files are written
+    /// as JSON if it ends with ".json" and as YAML otherwise.
+    pub root: String,
+    pub layout: SplitLayout,
+    /// Options for writing YAML files.
+    pub emit_options: EmitOptions,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        SplitOptions {
+            root: "openapi.yaml".to_string(),
+            layout: SplitLayout::default(),
+            emit_options: EmitOptions::default(),
+        }
+    }
+}
+
+impl SplitOptions {
+    /// Creates options that write a file per component next to "openapi.yaml".
+    pub fn new() -> Self {
+        SplitOptions::default()
+    }
+
+    /// Returns the options with the path of the root file.
+    pub fn with_root(mut self, root: impl Into<String>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Returns the options with the grouping of components into files.
+    pub fn with_layout(mut self, layout: SplitLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Returns the options with the options for writing YAML files.
+    pub fn with_emit_options(mut self, emit_options: EmitOptions) -> Self {
+        self.emit_options = emit_options;
+        self
+    }
+}
+
+/// Splits a document whose components are the entries of the mappings at
+/// `sections`, such as `&[&["components", "schemas"]]`, into files.
+///
+/// Returns the path and contents of each file, the root file first. Paths
+/// are relative to the directory of the root file and use "/" separators.
+/// Component names are made safe for file names, with a numeric suffix if
+/// two names map to the same file.
+pub fn split_document(node: &Yaml, sections: &[&[&str]], options: &SplitOptions) -> Vec<(String, Vec<u8>)> {
+    let json = options.root.ends_with(".json");
+    let extension = if json { "json" } else { "yaml" };
+    let root_dir = options.root.rsplit_once('/').map_or("", |(dir, _)| dir);
+
+    // Where each component moves to: its section, name, file and the pointer
+    // to it within the file.
+    let mut targets = Vec::new();
+    let mut taken = HashSet::new();
+    for section in sections {
+        let Some(Yaml::Mapping(entries)) = value_at(node, section) else {
+            continue;
+        };
+        let section_path = section.join("/");
+        for name in entries.keys().filter_map(Yaml::as_str) {
+            let (file, pointer) = match options.layout {
+                SplitLayout::PerComponent => {
+                    let stem = format!("{}/{}", section_path, file_name(name));
+                    let mut file = format!("{}.{}", stem, extension);
+                    for i in 2.. {
+                        if taken.insert(file.clone()) {
+                            break;
+                        }
+                        file = format!("{}_{}.{}", stem, i, extension);
+                    }
+                    (file, Vec::new())
+                }
+                SplitLayout::PerSection => (format!("{}.{}", section_path, extension), vec![name.to_string()]),
+            };
+            targets.push(Target { section, name: name.to_string(), file: join(root_dir, &file), pointer });
+        }
+    }
+
+    let splitter = Splitter { targets: &targets, root: &options.root };
+    let mut root = splitter.rewrite(node, &options.root);
+    let mut files: Vec<(String, Yaml)> = Vec::new();
+    for target in &targets {
+        let Some(value) = value_at(node, target.section).and_then(|entries| entries.get(target.name.as_str())) else {
+            continue;
+        };
+        let value = splitter.rewrite(value, &target.file);
+        match options.layout {
+            SplitLayout::PerComponent => files.push((target.file.clone(), value)),
+            SplitLayout::PerSection => {
+                if files.last().is_none_or(|(file, _)| *file != target.file) {
+                    files.push((target.file.clone(), Yaml::Mapping(Mapping::new())));
+                }
+                if let Some((_, Yaml::Mapping(entries))) = files.last_mut() {
+                    entries.insert(Yaml::String(target.name.clone()), value);
+                }
+            }
+        }
+
+        // The root keeps the entry, as a reference to the file.
+        if let Some(Yaml::Mapping(entries)) = value_at_mut(&mut root, target.section) {
+            let reference = splitter.reference(&options.root, target, &[]);
+            entries.insert(Yaml::String(target.name.clone()), reference_node(reference));
+        }
+    }
+
+    let write = |node: &Yaml| -> Vec<u8> {
+        if json {
+            serde_json::to_vec_pretty(&yaml_to_json(node)).unwrap_or_default()
+        } else {
+            emit_yaml(node, &options.emit_options).into_bytes()
+        }
+    };
+    let mut output = vec![(options.root.clone(), write(&root))];
+    output.extend(files.iter().map(|(file, node)| (file.clone(), write(node))));
+    output
+}
+
+/// The file a component moves to.
+struct Target<'a> {
+    section: &'a [&'a str],
+    name: String,
+    file: String,
+    /// The keys of the component within its file.
+    pointer: Vec<String>,
+}
+
+struct Splitter<'a> {
+    targets: &'a [Target<'a>],
+    root: &'a str,
+}
+
+impl Splitter<'_> {
+    /// Returns a copy of a node moved to `file`, with its references
+    /// rewritten to hold from there.
+    fn rewrite(&self, node: &Yaml, file: &str) -> Yaml {
+        match node {
+            Yaml::Mapping(map) => {
+                let mut rewritten = Mapping::new();
+                for (key, value) in map {
+                    let value = match (key.as_str(), value.as_str()) {
+                        (Some("$ref"), Some(reference)) => Yaml::String(self.rewrite_reference(reference, file)),
+                        _ => self.rewrite(value, file),
+                    };
+                    rewritten.insert(key.clone(), value);
+                }
+                Yaml::Mapping(rewritten)
+            }
+            Yaml::Sequence(items) => Yaml::Sequence(items.iter().map(|item| self.rewrite(item, file)).collect()),
+            _ => node.clone(),
+        }
+    }
+
+    fn rewrite_reference(&self, reference: &str, file: &str) -> String {
+        let Some(fragment) = reference.strip_prefix('#') else {
+            // Relative references to other files now hold from the directory of `file`.
+            if reference.contains("://") || reference.starts_with('/') {
+                return reference.to_string();
+            }
+            return relative_path(file, &join(parent(self.root), reference));
+        };
+        let segments = pointer_segments(fragment);
+        for target in self.targets {
+            let section = target.section;
+            let inside = segments.len() > section.len()
+                && segments.iter().zip(section.iter()).all(|(segment, key)| segment == key)
+                && segments[section.len()] == target.name;
+            if inside {
+                return self.reference(file, target, &segments[section.len() + 1..]);
+            }
+        }
+        if file == self.root {
+            return reference.to_string();
+        }
+        format!("{}#{}", relative_path(file, self.root), pointer_for_segments(&segments))
+    }
+
+    /// Returns a reference from `file` to a node within a moved component.
+    fn reference(&self, file: &str, target: &Target, rest: &[String]) -> String {
+        let mut segments = target.pointer.clone();
+        segments.extend_from_slice(rest);
+        let pointer = if segments.is_empty() { String::new() } else { format!("#{}", pointer_for_segments(&segments)) };
+        if file == target.file {
+            return if pointer.is_empty() { "#".to_string() } else { pointer };
+        }
+        relative_path(file, &target.file) + &pointer
+    }
+}
+
+fn reference_node(reference: String) -> Yaml {
+    let mut map = Mapping::new();
+    map.insert(Yaml::String("$ref".to_string()), Yaml::String(reference));
+    Yaml::Mapping(map)
+}
+
+fn value_at<'a>(node: &'a Yaml, keys: &[&str]) -> Option<&'a Yaml> {
+    keys.iter().try_fold(node, |node, key| node.get(*key))
+}
+
+fn value_at_mut<'a>(node: &'a mut Yaml, keys: &[&str]) -> Option<&'a mut Yaml> {
+    keys.iter().try_fold(node, |node, key| node.get_mut(*key))
+}
+
+/// Returns a component name with the characters that are unsafe in file
+/// names replaced.
+fn file_name(name: &str) -> String {
+    let name: String =
+        name.chars().map(|c| if c.is_ascii_alphanumeric() || "._-".contains(c) { c } else { '_' }).collect();
+    if name.is_empty() || name.starts_with('.') {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+fn parent(file: &str) -> &str {
+    file.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+fn join(dir: &str, path: &str) -> String {
+    if dir.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", dir, path)
+    }
+}
+
+/// Returns the path of `to` relative to the directory of `from`; both are
+/// relative to the same directory.
+fn relative_path(from: &str, to: &str) -> String {
+    let from_dirs: Vec<&str> = parent(from).split('/').filter(|s| !s.is_empty()).collect();
+    let to_parts: Vec<&str> = to.split('/').filter(|s| !s.is_empty()).collect();
+    let common = from_dirs.iter().zip(&to_parts).take_while(|(a, b)| a == b).count();
+    let mut parts = vec![".."; from_dirs.len() - common];
+    parts.extend_from_slice(&to_parts[common..]);
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r##"
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      parameters:
+        - $ref: "#/components/parameters/limit"
+      responses:
+        "200":
+          description: Pets.
+          content:
+            application/json:
+              schema: {$ref: "#/components/schemas/Pets"}
+components:
+  schemas:
+    Pet:
+      properties:
+        owner: {$ref: "common.yaml#/Owner"}
+        parent: {$ref: "#/components/schemas/Pet"}
+    Pets: {type: array, items: {$ref: "#/components/schemas/Pet/properties/parent"}}
+  parameters:
+    limit: {name: limit, in: query}
+"##;
+
+    const SECTIONS: &[&[&str]] = &[&["components", "schemas"], &["components", "parameters"]];
+
+    fn split(options: &SplitOptions) -> Vec<(String, Yaml)> {
+        let node: Yaml = serde_yaml::from_str(DOCUMENT).unwrap();
+        let files = split_document(&node, SECTIONS, options);
+        files.into_iter().map(|(path, bytes)| (path, serde_yaml::from_slice(&bytes).unwrap())).collect()
+    }
+
+    #[test]
+    fn test_split_per_component() {
+        let files = split(&SplitOptions::new().with_root("api/openapi.yaml"));
+        let paths: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "api/openapi.yaml",
+                "api/components/schemas/Pet.yaml",
+                "api/components/schemas/Pets.yaml",
+                "api/components/parameters/limit.yaml",
+            ]
+        );
+        let root = &files[0].1;
+        let get = &root["paths"]["/pets"]["get"];
+        assert_eq!(get["parameters"][0]["$ref"], "components/parameters/limit.yaml");
+        let schema = &get["responses"]["200"]["content"]["application/json"]["schema"];
+        assert_eq!(schema["$ref"], "components/schemas/Pets.yaml");
+        assert_eq!(root["components"]["schemas"]["Pet"]["$ref"], "components/schemas/Pet.yaml");
+
+        let pet = &files[1].1;
+        assert_eq!(pet["properties"]["owner"]["$ref"], "../../common.yaml#/Owner");
+        assert_eq!(pet["properties"]["parent"]["$ref"], "#");
+        assert_eq!(files[2].1["items"]["$ref"], "Pet.yaml#/properties/parent");
+        assert_eq!(files[3].1["name"], "limit");
+    }
+
+    #[test]
+    fn test_split_per_section() {
+        let options = SplitOptions::new().with_root("openapi.json").with_layout(SplitLayout::PerSection);
+        let files = split(&options);
+        let paths: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, ["openapi.json", "components/schemas.json", "components/parameters.json"]);
+        let root = &files[0].1;
+        assert_eq!(root["components"]["schemas"]["Pets"]["$ref"], "components/schemas.json#/Pets");
+        let schemas = &files[1].1;
+        assert_eq!(schemas["Pet"]["properties"]["parent"]["$ref"], "#/Pet");
+        assert_eq!(schemas["Pets"]["items"]["$ref"], "#/Pet/properties/parent");
+        assert_eq!(schemas["Pet"]["properties"]["owner"]["$ref"], "../common.yaml#/Owner");
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(relative_path("a/b/c.yaml", "a/d.yaml"), "../d.yaml");
+        assert_eq!(relative_path("c.yaml", "a/d.yaml"), "a/d.yaml");
+        assert_eq!(relative_path("a/c.yaml", "a/d.yaml"), "d.yaml");
+        assert_eq!(file_name("Pet/Owner"), "Pet_Owner");
+        assert_eq!(file_name(".."), "_..");
+    }
+}
@@ -0,0 +1,340 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Typed models of common vendor extensions.
+//!
+//! Specification extensions are otherwise kept as opaque YAML. A few are
+//! used widely enough that tools want them as structured data: those of
+//! Google Cloud Endpoints and API Gateway, of Amazon API Gateway and of
+//! code generators. [`VendorExtension::parse`] reads one of them from its
+//! node, and [`find_vendor_extensions`] finds all of them in a document.
+
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value as Yaml;
+
+use crate::codes::ErrorCode;
+use crate::context::Context;
+use crate::error::{CompilerError, ErrorGroup, Result};
+use crate::reader::pointer_for_segments;
+use crate::writer::{EmitOptions, ToYaml};
+
+/// The names of the extensions that have typed models.
+pub const VENDOR_EXTENSIONS: &[&str] = &[
+    "x-amazon-apigateway-integration",
+    "x-codegen-settings",
+    "x-google-backend",
+    "x-google-endpoints",
+];
+
+/// A vendor extension with a typed model.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VendorExtension {
+    /// `x-google-endpoints`: the DNS names of a Cloud Endpoints service.
+    GoogleEndpoints(Vec<GoogleEndpoint>),
+    /// `x-google-backend`: the backend that API Gateway routes requests to.
+    GoogleBackend(GoogleBackend),
+    /// `x-amazon-apigateway-integration`: the backend of an Amazon API
+    /// Gateway method.
+    AmazonApigatewayIntegration(Box<AmazonApigatewayIntegration>),
+    /// `x-codegen-settings`: settings for code generators.
+    CodegenSettings(CodegenSettings),
+}
+
+impl VendorExtension {
+    /// Parses the node of an extension, returning None if the extension has
+    /// no typed model.
+    pub fn parse(name: &str, node: &Yaml, context: &Context) -> Option<Result<Self>> {
+        let extension = match name {
+            "x-google-endpoints" => from_node(node, context).map(VendorExtension::GoogleEndpoints),
+            "x-google-backend" => from_node(node, context).map(VendorExtension::GoogleBackend),
+            "x-amazon-apigateway-integration" => from_node(node, context)
+                .map(|integration| VendorExtension::AmazonApigatewayIntegration(Box::new(integration))),
+            "x-codegen-settings" => from_node(node, context).map(VendorExtension::CodegenSettings),
+            _ => return None,
+        };
+        Some(extension)
+    }
+
+    /// Parses the YAML of an extension, such as the `yaml` of the `Any`
+    /// values of parsed documents.
+    pub fn parse_str(name: &str, yaml: &str) -> Option<Result<Self>> {
+        if !VENDOR_EXTENSIONS.contains(&name) {
+            return None;
+        }
+        let node = match serde_yaml::from_str(yaml) {
+            Ok(node) => node,
+            Err(e) => {
+                return Some(Err(CompilerError::Yaml {
+                    message: format!("Invalid YAML for {}: {}", name, e),
+                    source: Some(Arc::new(e)),
+                }))
+            }
+        };
+        Self::parse(name, &node, &Context::root(name))
+    }
+
+    /// Returns the name of the extension.
+    pub fn name(&self) -> &'static str {
+        match self {
+            VendorExtension::GoogleEndpoints(_) => "x-google-endpoints",
+            VendorExtension::GoogleBackend(_) => "x-google-backend",
+            VendorExtension::AmazonApigatewayIntegration(_) => "x-amazon-apigateway-integration",
+            VendorExtension::CodegenSettings(_) => "x-codegen-settings",
+        }
+    }
+}
+
+impl ToYaml for VendorExtension {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        let node = match self {
+            VendorExtension::GoogleEndpoints(endpoints) => serde_yaml::to_value(endpoints),
+            VendorExtension::GoogleBackend(backend) => serde_yaml::to_value(backend),
+            VendorExtension::AmazonApigatewayIntegration(integration) => serde_yaml::to_value(integration),
+            VendorExtension::CodegenSettings(settings) => serde_yaml::to_value(settings),
+        };
+        node.unwrap_or_default()
+    }
+}
+
+/// Deserializes a model from a node, reporting errors at the context.
+fn from_node<T: DeserializeOwned>(node: &Yaml, context: &Context) -> Result<T> {
+    serde_yaml::from_value(node.clone())
+        .map_err(|e| CompilerError::new_with_code(context, ErrorCode::InvalidValue, format!("is invalid: {}", e)))
+}
+
+/// A DNS name of a Cloud Endpoints service.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleEndpoint {
+    pub name: String,
+    /// The IP address that the name resolves to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Allow CORS preflight requests through to the backend.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_cors: bool,
+}
+
+/// The backend of an API Gateway or Cloud Endpoints API or operation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GoogleBackend {
+    /// The URL of the backend.
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt_audience: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_auth: Option<bool>,
+    /// CONSTANT_ADDRESS or APPEND_PATH_TO_ADDRESS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_translation: Option<String>,
+    /// The timeout of requests, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+}
+
+/// The integration of an Amazon API Gateway method with its backend.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmazonApigatewayIntegration {
+    /// aws, aws_proxy, http, http_proxy or mock.
+    #[serde(rename = "type")]
+    pub integration_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passthrough_behavior: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_handling: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_in_millis: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_format_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_namespace: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cache_key_parameters: Vec<String>,
+    /// Integration request parameters by the method request parameters or
+    /// values they are mapped from.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub request_parameters: IndexMap<String, String>,
+    /// Mapping templates by content type.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub request_templates: IndexMap<String, String>,
+    /// Responses by the pattern of backend status codes or errors that
+    /// selects them.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub responses: IndexMap<String, AmazonApigatewayIntegrationResponse>,
+}
+
+/// A response of an Amazon API Gateway integration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmazonApigatewayIntegrationResponse {
+    pub status_code: String,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub response_parameters: IndexMap<String, String>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub response_templates: IndexMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_handling: Option<String>,
+}
+
+/// Settings for code generators.
+///
+/// Each generator reads settings of its own, so they are kept by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CodegenSettings {
+    pub settings: IndexMap<String, Yaml>,
+}
+
+impl CodegenSettings {
+    /// Returns a setting, if it is set.
+    pub fn get(&self, name: &str) -> Option<&Yaml> {
+        self.settings.get(name)
+    }
+
+    /// Returns a setting that is a string.
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(Yaml::as_str)
+    }
+
+    /// Returns a setting that is a boolean.
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        self.get(name).and_then(Yaml::as_bool)
+    }
+}
+
+/// Finds and parses the vendor extensions that have typed models anywhere
+/// in a document, returning each with the JSON pointer of its node.
+///
+/// Extensions whose nodes do not match their models are reported with the
+/// path of the node.
+pub fn find_vendor_extensions(node: &Yaml) -> std::result::Result<Vec<(String, VendorExtension)>, ErrorGroup> {
+    let mut found = Vec::new();
+    let mut errors = Vec::new();
+    let mut segments = Vec::new();
+    find(node, &Arc::new(Context::root("$")), &mut segments, &mut found, &mut errors);
+    if errors.is_empty() {
+        Ok(found)
+    } else {
+        Err(ErrorGroup::new(errors))
+    }
+}
+
+fn find(
+    node: &Yaml,
+    context: &Arc<Context>,
+    segments: &mut Vec<String>,
+    found: &mut Vec<(String, VendorExtension)>,
+    errors: &mut Vec<CompilerError>,
+) {
+    let children: Vec<(String, &Yaml)> = match node {
+        Yaml::Mapping(map) => map.iter().filter_map(|(key, value)| Some((key.as_str()?.to_string(), value))).collect(),
+        Yaml::Sequence(items) => items.iter().enumerate().map(|(i, item)| (i.to_string(), item)).collect(),
+        _ => return,
+    };
+    for (name, value) in children {
+        let child = Arc::new(context.child(name.as_str()));
+        let extension = if node.is_mapping() { VendorExtension::parse(&name, value, &child) } else { None };
+        segments.push(name);
+        match extension {
+            Some(Ok(extension)) => found.push((pointer_for_segments(segments), extension)),
+            Some(Err(e)) => errors.push(e),
+            None => find(value, &child, segments, found, errors),
+        }
+        segments.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendor_extensions() {
+        let source = r#"
+x-google-endpoints:
+- name: pets.endpoints.example.cloud.goog
+  allowCors: true
+x-google-backend:
+  address: https://pets.example.com
+  path_translation: APPEND_PATH_TO_ADDRESS
+  deadline: 30.0
+paths:
+  /pets:
+    get:
+      x-amazon-apigateway-integration:
+        type: http_proxy
+        httpMethod: GET
+        uri: https://pets.example.com/pets
+        timeoutInMillis: 2900
+        requestParameters:
+          integration.request.header.x-id: method.request.header.x-id
+        responses:
+          default: {statusCode: "200"}
+      x-codegen-settings: {clientName: PetsClient, async: true}
+      x-other: {type: 1}
+"#;
+        let node: Yaml = serde_yaml::from_str(source).unwrap();
+        let found = find_vendor_extensions(&node).unwrap();
+        let pointers: Vec<&str> = found.iter().map(|(pointer, _)| pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            [
+                "/x-google-endpoints",
+                "/x-google-backend",
+                "/paths/~1pets/get/x-amazon-apigateway-integration",
+                "/paths/~1pets/get/x-codegen-settings",
+            ]
+        );
+        let VendorExtension::GoogleEndpoints(endpoints) = &found[0].1 else { panic!("{:?}", found[0]) };
+        assert!(endpoints[0].allow_cors && endpoints[0].target.is_none());
+        let VendorExtension::GoogleBackend(backend) = &found[1].1 else { panic!("{:?}", found[1]) };
+        assert_eq!(backend.deadline, Some(30.0));
+        let VendorExtension::AmazonApigatewayIntegration(integration) = &found[2].1 else { panic!("{:?}", found[2]) };
+        assert_eq!(integration.integration_type, "http_proxy");
+        assert_eq!(integration.timeout_in_millis, Some(2900));
+        assert_eq!(integration.responses["default"].status_code, "200");
+        let VendorExtension::CodegenSettings(settings) = &found[3].1 else { panic!("{:?}", found[3]) };
+        assert_eq!(settings.get_str("clientName"), Some("PetsClient"));
+        assert_eq!(settings.get_bool("async"), Some(true));
+
+        // Models write back the YAML they were read from
+        let integration = &node["paths"]["/pets"]["get"]["x-amazon-apigateway-integration"];
+        assert_eq!(found[2].1.to_yaml(), *integration);
+        let yaml = serde_yaml::to_string(integration).unwrap();
+        assert_eq!(VendorExtension::parse_str("x-amazon-apigateway-integration", &yaml), Some(Ok(found[2].1.clone())));
+        assert!(VendorExtension::parse_str("x-other", "{}").is_none());
+
+        let node: Yaml = serde_yaml::from_str("paths: {/pets: {x-google-backend: {deadline: 1}}}").unwrap();
+        let error = find_vendor_extensions(&node).unwrap_err().to_string();
+        assert!(error.contains("$.paths./pets.x-google-backend is invalid: missing field `address`"), "{}", error);
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The vocabulary of an API.
+//!
+//! A [`Vocabulary`] counts the words an API is written in: the names of its
+//! schemas, of their properties, of its parameters and the IDs of its
+//! operations. Vocabularies are computed from the YAML of OpenAPI v2 or v3
+//! documents and combined with [`Vocabulary::union`],
+//! [`Vocabulary::intersection`] and [`Vocabulary::difference`], for example
+//! to find the words a set of APIs share.
+
+use crate::lint::METHODS;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value as Yaml;
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+
+/// The number of times each word of an API is used, by kind of word.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vocabulary {
+    /// Names of the schemas in "#/components/schemas" or "#/definitions".
+    pub schemas: BTreeMap<String, usize>,
+    /// Names of the properties of those schemas, including nested ones.
+    pub properties: BTreeMap<String, usize>,
+    /// IDs of the operations.
+    pub operations: BTreeMap<String, usize>,
+    /// Names of the parameters of path items, operations and components.
+    pub parameters: BTreeMap<String, usize>,
+}
+
+impl Vocabulary {
+    /// Computes the vocabulary of the YAML of an OpenAPI v2 or v3 document.
+    pub fn new(node: &Yaml) -> Self {
+        let mut vocabulary = Vocabulary::default();
+        let components = &node["components"];
+        let schemas = if components.is_mapping() { &components["schemas"] } else { &node["definitions"] };
+        if let Yaml::Mapping(schemas) = schemas {
+            for (name, schema) in schemas {
+                if let Some(name) = name.as_str() {
+                    add(&mut vocabulary.schemas, name);
+                }
+                vocabulary.add_properties(schema);
+            }
+        }
+
+        let parameters = if components.is_mapping() { &components["parameters"] } else { &node["parameters"] };
+        if let Yaml::Mapping(parameters) = parameters {
+            for parameter in parameters.values() {
+                vocabulary.add_parameter(parameter);
+            }
+        }
+        if let Yaml::Mapping(paths) = &node["paths"] {
+            for item in paths.values() {
+                vocabulary.add_parameters(&item["parameters"]);
+                for method in METHODS {
+                    let operation = &item[*method];
+                    if let Some(id) = operation["operationId"].as_str() {
+                        add(&mut vocabulary.operations, id);
+                    }
+                    vocabulary.add_parameters(&operation["parameters"]);
+                }
+            }
+        }
+        vocabulary
+    }
+
+    /// Computes the combined vocabulary of several documents, the union of
+    /// their vocabularies.
+    pub fn from_documents<I>(nodes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Yaml>,
+    {
+        nodes
+            .into_iter()
+            .fold(Vocabulary::default(), |vocabulary, node| vocabulary.union(&Vocabulary::new(node.borrow())))
+    }
+
+    /// Returns whether the vocabulary has no words.
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+            && self.properties.is_empty()
+            && self.operations.is_empty()
+            && self.parameters.is_empty()
+    }
+
+    /// Returns the words of both vocabularies, counted as often as they are
+    /// used in either.
+    pub fn union(&self, other: &Vocabulary) -> Vocabulary {
+        self.combine(other, |words, other| {
+            let mut union = words.clone();
+            for (word, count) in other {
+                *union.entry(word.clone()).or_default() += count;
+            }
+            union
+        })
+    }
+
+    /// Returns the words that both vocabularies use, counted as often as the
+    /// vocabulary that uses them less.
+    pub fn intersection(&self, other: &Vocabulary) -> Vocabulary {
+        self.combine(other, |words, other| {
+            words
+                .iter()
+                .filter_map(|(word, count)| Some((word.clone(), *count.min(other.get(word)?))))
+                .collect()
+        })
+    }
+
+    /// Returns the words of this vocabulary that the other does not use.
+    pub fn difference(&self, other: &Vocabulary) -> Vocabulary {
+        self.combine(other, |words, other| {
+            words
+                .iter()
+                .filter(|(word, _)| !other.contains_key(*word))
+                .map(|(word, count)| (word.clone(), *count))
+                .collect()
+        })
+    }
+
+    fn combine<F>(&self, other: &Vocabulary, f: F) -> Vocabulary
+    where
+        F: Fn(&BTreeMap<String, usize>, &BTreeMap<String, usize>) -> BTreeMap<String, usize>,
+    {
+        Vocabulary {
+            schemas: f(&self.schemas, &other.schemas),
+            properties: f(&self.properties, &other.properties),
+            operations: f(&self.operations, &other.operations),
+            parameters: f(&self.parameters, &other.parameters),
+        }
+    }
+
+    fn add_properties(&mut self, schema: &Yaml) {
+        if !schema.is_mapping() {
+            return;
+        }
+        if let Yaml::Mapping(properties) = &schema["properties"] {
+            for (name, property) in properties {
+                if let Some(name) = name.as_str() {
+                    add(&mut self.properties, name);
+                }
+                self.add_properties(property);
+            }
+        }
+        self.add_properties(&schema["items"]);
+        self.add_properties(&schema["additionalProperties"]);
+        self.add_properties(&schema["not"]);
+        for key in ["allOf", "anyOf", "oneOf"] {
+            for member in schema[key].as_sequence().into_iter().flatten() {
+                self.add_properties(member);
+            }
+        }
+    }
+
+    fn add_parameters(&mut self, parameters: &Yaml) {
+        for parameter in parameters.as_sequence().into_iter().flatten() {
+            self.add_parameter(parameter);
+        }
+    }
+
+    /// Counts the name of a parameter. References are not counted, as the
+    /// parameters they refer to are counted where they are defined.
+    fn add_parameter(&mut self, parameter: &Yaml) {
+        if let Some(name) = parameter["name"].as_str() {
+            add(&mut self.parameters, name);
+        }
+    }
+}
+
+fn add(words: &mut BTreeMap<String, usize>, word: &str) {
+    *words.entry(word.to_string()).or_default() += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(yaml: &str) -> Yaml {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn words(words: &[(&str, usize)]) -> BTreeMap<String, usize> {
+        words.iter().map(|(word, count)| (word.to_string(), *count)).collect()
+    }
+
+    #[test]
+    fn test_vocabulary() {
+        let v3 = Vocabulary::new(&node(
+            r##"
+openapi: 3.0.3
+paths:
+  /pets/{petId}:
+    parameters:
+      - {name: petId, in: path}
+    get:
+      operationId: getPet
+      parameters:
+        - {$ref: "#/components/parameters/limit"}
+components:
+  schemas:
+    Pet:
+      properties:
+        id: {type: integer}
+        tags: {type: array, items: {properties: {name: {type: string}}}}
+    Tag:
+      allOf:
+        - properties: {name: {type: string}}
+  parameters:
+    limit: {name: limit, in: query}
+"##,
+        ));
+        assert_eq!(v3.schemas, words(&[("Pet", 1), ("Tag", 1)]));
+        assert_eq!(v3.properties, words(&[("id", 1), ("name", 2), ("tags", 1)]));
+        assert_eq!(v3.operations, words(&[("getPet", 1)]));
+        assert_eq!(v3.parameters, words(&[("limit", 1), ("petId", 1)]));
+
+        let v2 = Vocabulary::new(&node(
+            r#"
+swagger: "2.0"
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - {name: limit, in: query}
+definitions:
+  Pet:
+    properties:
+      id: {type: integer}
+"#,
+        ));
+        assert_eq!(v2.schemas, words(&[("Pet", 1)]));
+        assert_eq!(v2.operations, words(&[("listPets", 1)]));
+        assert_eq!(v2.parameters, words(&[("limit", 1)]));
+    }
+
+    #[test]
+    fn test_operations() {
+        let first = node(
+            r#"
+definitions: {Pet: {properties: {id: {}, name: {}}}}
+parameters: {limit: {name: limit}, page: {name: page}}
+"#,
+        );
+        let second = node(
+            r#"
+definitions: {Pet: {properties: {id: {}}}, Owner: {properties: {id: {}}}}
+parameters: {limit: {name: limit}}
+"#,
+        );
+        let (a, b) = (Vocabulary::new(&first), Vocabulary::new(&second));
+
+        let union = a.union(&b);
+        assert_eq!(union.schemas, words(&[("Owner", 1), ("Pet", 2)]));
+        assert_eq!(union.properties, words(&[("id", 3), ("name", 1)]));
+        assert_eq!(union, Vocabulary::from_documents([&first, &second]));
+
+        let intersection = a.intersection(&b);
+        assert_eq!(intersection.schemas, words(&[("Pet", 1)]));
+        assert_eq!(intersection.properties, words(&[("id", 1)]));
+        assert_eq!(intersection.parameters, words(&[("limit", 1)]));
+
+        let difference = a.difference(&b);
+        assert_eq!(difference.schemas, words(&[]));
+        assert_eq!(difference.properties, words(&[("name", 1)]));
+        assert_eq!(difference.parameters, words(&[("page", 1)]));
+        assert!(a.difference(&a).is_empty());
+        assert!(Vocabulary::default().is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Watching files for changes.
+//!
+//! A [`FileWatcher`] polls the modification time and size of a file on a
+//! background thread. Polling needs no platform support, and documents are
+//! small enough that reading one again after a change is cheap.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crate::error::{CompilerError, Result};
+use crate::reader::{remove_from_file_cache, remove_from_info_cache};
+
+/// The default interval between polls of a watched file.
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Watches a file, calling back with its contents when they change.
+///
+/// The callback runs on the watcher's thread, first with the contents at
+/// the time the watcher starts. Watching stops when the watcher is dropped.
+#[derive(Debug)]
+pub struct FileWatcher {
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl FileWatcher {
+    /// Starts watching a file, polling it at the given interval.
+    ///
+    /// Before each callback after the first, the file is removed from the
+    /// reader caches, so that reading it again sees the change; other cached
+    /// files, such as the targets of its references, are kept.
+    pub fn new<F>(path: impl Into<PathBuf>, interval: Duration, mut callback: F) -> Self
+    where
+        F: FnMut(Result<Vec<u8>>) + Send + 'static,
+    {
+        let path = path.into();
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let path = path.clone();
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                // The modification time and size of the file, if it exists
+                let mut last: Option<Option<(Option<SystemTime>, u64)>> = None;
+                let mut contents: Option<Vec<u8>> = None;
+                while !stop.load(Ordering::SeqCst) {
+                    let stamp = std::fs::metadata(&path).ok().map(|m| (m.modified().ok(), m.len()));
+                    if last != Some(stamp) {
+                        last = Some(stamp);
+                        let name = path.to_string_lossy();
+                        remove_from_file_cache(&name);
+                        remove_from_info_cache(&name);
+                        match std::fs::read(&path) {
+                            // Touching a file without changing it is not a change
+                            Ok(bytes) if contents.as_ref() == Some(&bytes) => {}
+                            Ok(bytes) => {
+                                contents = Some(bytes.clone());
+                                callback(Ok(bytes));
+                            }
+                            Err(e) => {
+                                contents = None;
+                                callback(Err(CompilerError::Io {
+                                    message: format!("Failed to read {}: {}", name, e),
+                                    source: Some(Arc::new(e)),
+                                }));
+                            }
+                        }
+                    }
+                    thread::sleep(interval);
+                }
+            })
+        };
+        FileWatcher { path, stop, handle: Some(handle) }
+    }
+
+    /// Returns the path of the watched file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// Stops watching, waiting for a callback in progress to return.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            // A panic in the callback has already been reported
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_file_watcher() {
+        let path = std::env::temp_dir().join(format!("gnostic-watch-{}.yaml", std::process::id()));
+        std::fs::write(&path, "openapi: 3.0.0\n").unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let watcher = FileWatcher::new(&path, Duration::from_millis(5), move |result| {
+            let _ = sender.send(result.map_err(|e| e.to_string()));
+        });
+        let wait = || receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(wait(), Ok(b"openapi: 3.0.0\n".to_vec()));
+
+        std::fs::write(&path, "openapi: 3.1.0\ninfo: {}\n").unwrap();
+        assert_eq!(wait(), Ok(b"openapi: 3.1.0\ninfo: {}\n".to_vec()));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(wait().unwrap_err().contains("Failed to read"));
+        assert_eq!(watcher.path(), path);
+        watcher.stop();
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright 2017 Google LLC. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for writing parsed models back out as YAML.
+//!
+//! Model types implement [`ToYaml`] to build a YAML node tree. Like the Go
+//! implementation, fields holding their default value (empty strings, false,
+//! zero, empty lists and absent messages) are omitted from the output.
+
+use crate::emitter::{QuoteStyle, SequenceStyle};
+use crate::comments::Comments;
+use crate::raw_info::RawInfo;
+use serde_yaml::{Mapping, Value as Yaml};
+
+/// Order in which the entries of named collections are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyOrder {
+    /// Entries are written in the order they were parsed (or inserted).
+    #[default]
+    AsAuthored,
+    /// Entries are sorted by name so that output is stable across runs.
+    Canonical,
+}
+
+/// EmitOptions configures how models are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmitOptions {
+    /// Ordering of named collections such as paths, components and properties.
+    ///
+    /// Fixed fields are always written in specification order.
+    pub key_order: KeyOrder,
+    /// Source of the document being written, used to restore fields that the
+    /// models do not represent.
+    pub raw_info: Option<RawInfo>,
+    /// Comments of the source document, written next to the nodes they were
+    /// attached to.
+    pub comments: Option<Comments>,
+    /// Number of spaces per indentation level (default 2).
+    pub indent: usize,
+    /// Quotes used for strings that cannot be written plain, such as keys that
+    /// look like numbers or booleans.
+    pub quote_style: QuoteStyle,
+    /// Block or flow style for sequences.
+    pub sequence_style: SequenceStyle,
+    /// Indent block sequences under their key instead of aligning the dashes
+    /// with it.
+    pub indent_sequences: bool,
+    /// Preferred maximum line length; long strings are folded and flow
+    /// sequences that do not fit use block style. None never folds.
+    pub line_width: Option<usize>,
+}
+
+impl Default for EmitOptions {
+    fn default() -> Self {
+        EmitOptions {
+            key_order: KeyOrder::default(),
+            raw_info: None,
+            comments: None,
+            indent: 2,
+            quote_style: QuoteStyle::default(),
+            sequence_style: SequenceStyle::default(),
+            indent_sequences: false,
+            line_width: None,
+        }
+    }
+}
+
+impl EmitOptions {
+    /// Creates the default (as-authored) options.
+    pub fn new() -> Self {
+        EmitOptions::default()
+    }
+
+    /// Creates options for canonical output with sorted named collections.
+    pub fn canonical() -> Self {
+        EmitOptions {
+            key_order: KeyOrder::Canonical,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the options with the indentation width set.
+    pub fn with_indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Returns the options with the quote style set.
+    pub fn with_quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    /// Returns the options with the sequence style set.
+    pub fn with_sequence_style(mut self, sequence_style: SequenceStyle) -> Self {
+        self.sequence_style = sequence_style;
+        self
+    }
+
+    /// Returns the options with indentation of block sequences set.
+    pub fn with_indented_sequences(mut self, indent_sequences: bool) -> Self {
+        self.indent_sequences = indent_sequences;
+        self
+    }
+
+    /// Returns the options with the preferred line width set.
+    pub fn with_line_width(mut self, line_width: usize) -> Self {
+        self.line_width = Some(line_width);
+        self
+    }
+
+    /// Returns the options with raw info to restore into the output.
+    pub fn with_raw_info(mut self, raw_info: RawInfo) -> Self {
+        self.raw_info = Some(raw_info);
+        self
+    }
+
+    /// Returns the options with source comments to write into the output.
+    pub fn with_comments(mut self, comments: Comments) -> Self {
+        self.comments = Some(comments);
+        self
+    }
+}
+
+/// Converts a model into a YAML node.
+pub trait ToYaml {
+    /// Returns the YAML representation of the value using the default options.
+    fn to_yaml(&self) -> Yaml {
+        self.to_yaml_with(&EmitOptions::default())
+    }
+
+    /// Returns the YAML representation of the value.
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml;
+}
+
+impl ToYaml for String {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        Yaml::String(self.clone())
+    }
+}
+
+impl<T: ToYaml + ?Sized> ToYaml for Box<T> {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        (**self).to_yaml_with(options)
+    }
+}
+
+/// Returns the YAML for a document, restoring its raw info if any.
+pub fn document_to_yaml<T: ToYaml>(doc: &T, options: &EmitOptions) -> Yaml {
+    let mut node = doc.to_yaml_with(options);
+    if let Some(raw_info) = &options.raw_info {
+        raw_info.restore(&mut node);
+    }
+    node
+}
+
+/// An entry of a named collection: a mapping key with its value.
+pub trait Named {
+    /// The type of the mapped value.
+    type Value: ToYaml;
+
+    /// Returns the mapping key.
+    fn name(&self) -> &str;
+
+    /// Returns the mapped value, if present.
+    fn value(&self) -> Option<&Self::Value>;
+}
+
+/// Creates a number node, using an integer when the value has no fractional part.
+pub fn new_scalar_node_for_number(f: f64) -> Yaml {
+    if f.fract() == 0.0 && f.abs() < i64::MAX as f64 {
+        Yaml::Number((f as i64).into())
+    } else {
+        Yaml::Number(f.into())
+    }
+}
+
+/// Returns the node stored as YAML text in an Any value, or null if it does not parse.
+pub fn yaml_for_any_source(source: &str) -> Yaml {
+    if source.is_empty() {
+        return Yaml::Null;
+    }
+    serde_yaml::from_str(source).unwrap_or(Yaml::Null)
+}
+
+/// Adds a string entry to a mapping unless the value is empty.
+pub fn insert_string(map: &mut Mapping, key: &str, value: &str) {
+    if !value.is_empty() {
+        map.insert(key.into(), value.into());
+    }
+}
+
+/// Adds a boolean entry to a mapping if the value is true.
+pub fn insert_bool(map: &mut Mapping, key: &str, value: bool) {
+    if value {
+        map.insert(key.into(), Yaml::Bool(true));
+    }
+}
+
+/// Adds a number entry to a mapping unless the value is zero.
+pub fn insert_float(map: &mut Mapping, key: &str, value: f64) {
+    if value != 0.0 {
+        map.insert(key.into(), new_scalar_node_for_number(value));
+    }
+}
+
+/// Adds an integer entry to a mapping unless the value is zero.
+pub fn insert_int(map: &mut Mapping, key: &str, value: i64) {
+    if value != 0 {
+        map.insert(key.into(), Yaml::Number(value.into()));
+    }
+}
+
+/// Adds a sequence of strings to a mapping unless it is empty.
+pub fn insert_strings(map: &mut Mapping, key: &str, values: &[String]) {
+    if !values.is_empty() {
+        map.insert(
+            key.into(),
+            Yaml::Sequence(values.iter().map(|s| s.as_str().into()).collect()),
+        );
+    }
+}
+
+/// Adds a message to a mapping if it is present.
+pub fn insert_message<T: ToYaml + ?Sized>(
+    map: &mut Mapping,
+    key: &str,
+    value: Option<&T>,
+    options: &EmitOptions,
+) {
+    if let Some(value) = value {
+        map.insert(key.into(), value.to_yaml_with(options));
+    }
+}
+
+/// Adds a sequence of messages to a mapping unless it is empty.
+pub fn insert_messages<T: ToYaml>(
+    map: &mut Mapping,
+    key: &str,
+    values: &[T],
+    options: &EmitOptions,
+) {
+    if !values.is_empty() {
+        map.insert(key.into(), sequence_node(values, options));
+    }
+}
+
+/// Adds the entries of a named collection to a mapping.
+///
+/// Entries are sorted by name when canonical ordering is requested.
+pub fn insert_named<N: Named>(map: &mut Mapping, entries: &[N], options: &EmitOptions) {
+    let mut entries: Vec<&N> = entries.iter().collect();
+    if options.key_order == KeyOrder::Canonical {
+        entries.sort_by(|a, b| a.name().cmp(b.name()));
+    }
+    for entry in entries {
+        insert_message(map, entry.name(), entry.value(), options);
+    }
+}
+
+/// Returns a sequence node for a list of messages.
+pub fn sequence_node<T: ToYaml>(values: &[T], options: &EmitOptions) -> Yaml {
+    Yaml::Sequence(
+        values
+            .iter()
+            .map(|value| value.to_yaml_with(options))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Point {
+        x: i64,
+        label: String,
+    }
+
+    impl ToYaml for Point {
+        fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+            let mut map = Mapping::new();
+            insert_int(&mut map, "x", self.x);
+            insert_string(&mut map, "label", &self.label);
+            Yaml::Mapping(map)
+        }
+    }
+
+    #[test]
+    fn test_new_scalar_node_for_number() {
+        assert_eq!(serde_yaml::to_string(&new_scalar_node_for_number(100.0)).unwrap(), "100\n");
+        assert_eq!(serde_yaml::to_string(&new_scalar_node_for_number(2.5)).unwrap(), "2.5\n");
+    }
+
+    #[test]
+    fn test_yaml_for_any_source() {
+        assert_eq!(yaml_for_any_source(""), Yaml::Null);
+        assert_eq!(yaml_for_any_source("42\n"), Yaml::Number(42.into()));
+        assert_eq!(yaml_for_any_source("[unclosed"), Yaml::Null);
+    }
+
+    #[test]
+    fn test_insert_helpers_skip_defaults() {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "empty", "");
+        insert_bool(&mut map, "no", false);
+        insert_float(&mut map, "zero", 0.0);
+        insert_int(&mut map, "zero_int", 0);
+        insert_strings(&mut map, "none", &[]);
+        insert_message::<Point>(&mut map, "absent", None, &EmitOptions::default());
+        insert_messages::<Point>(&mut map, "nothing", &[], &EmitOptions::default());
+        assert!(map.is_empty());
+
+        let point = Point {
+            x: 3,
+            label: "a".to_string(),
+        };
+        insert_bool(&mut map, "yes", true);
+        insert_message(&mut map, "point", Some(&point), &EmitOptions::default());
+        insert_messages(&mut map, "points", &[point], &EmitOptions::default());
+        insert_strings(&mut map, "tags", &["t".to_string()]);
+        let text = serde_yaml::to_string(&Yaml::Mapping(map)).unwrap();
+        assert_eq!(
+            text,
+            "yes: true\npoint:\n  x: 3\n  label: a\npoints:\n- x: 3\n  label: a\ntags:\n- t\n"
+        );
+    }
+
+    struct Entry(&'static str, String);
+
+    impl Named for Entry {
+        type Value = String;
+
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn value(&self) -> Option<&String> {
+            Some(&self.1)
+        }
+    }
+
+    #[test]
+    fn test_insert_named_key_order() {
+        let entries = [
+            Entry("/pets", "b".to_string()),
+            Entry("/owners", "a".to_string()),
+        ];
+        let keys = |options: &EmitOptions| {
+            let mut map = Mapping::new();
+            insert_named(&mut map, &entries, options);
+            map.keys().map(|k| k.as_str().unwrap().to_string()).collect::<Vec<_>>()
+        };
+        assert_eq!(keys(&EmitOptions::default()), vec!["/pets", "/owners"]);
+        assert_eq!(keys(&EmitOptions::canonical()), vec!["/owners", "/pets"]);
+    }
+}
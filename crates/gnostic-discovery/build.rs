@@ -11,10 +11,21 @@ fn main() -> Result<()> {
 
     let proto_files = &[proto_root.join("discovery.proto")];
 
-    let include_dirs = &[proto_root.clone()];
+    let include_dirs = &[&proto_root];
 
-    prost_build::Config::new()
-        .compile_protos(proto_files, include_dirs)?;
+    // Serde support is opt-in through the `serde` feature. Embedded
+    // google.protobuf.Any values have no serde impls; their YAML text is kept.
+    let mut config = prost_build::Config::new();
+    config
+        .type_attribute(
+            ".",
+            "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]",
+        )
+        .field_attribute(".discovery.v1.Any.value", "#[cfg_attr(feature = \"serde\", serde(skip))]");
+
+    // Any implements Eq and Hash by hand in the crate root.
+    gnostic_build::derive_eq_and_hash(&mut config, proto_files, include_dirs, &[".discovery.v1.Any"])?;
+    config.compile_protos(proto_files, include_dirs)?;
 
     for proto in proto_files {
         println!("cargo:rerun-if-changed={}", proto.display());
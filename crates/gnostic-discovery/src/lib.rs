@@ -5,11 +5,24 @@
 pub mod parser;
 pub mod document;
 pub mod list;
+pub mod stream;
 
 /// Generated Protocol Buffer code for Discovery format.
+#[allow(clippy::large_enum_variant)]
 pub mod discovery {
     include!(concat!(env!("OUT_DIR"), "/discovery.v1.rs"));
+
+    impl Eq for Any {}
+
+    impl std::hash::Hash for Any {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.value.as_ref().map(|value| (&value.type_url, &value.value)).hash(state);
+            self.yaml.hash(state);
+        }
+    }
 }
 
 pub use document::*;
 pub use list::*;
+pub use stream::parse_document_from_reader;
+pub use discovery::Document;
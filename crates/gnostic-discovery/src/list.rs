@@ -45,6 +45,7 @@ pub struct Api {
 
 impl ApiList {
     /// Fetches the list of APIs from the Discovery Service asynchronously.
+    #[cfg(feature = "http")]
     pub async fn fetch_async() -> Result<Self, String> {
         use gnostic_compiler::fetch_url;
 
@@ -58,6 +59,7 @@ impl ApiList {
 
     /// Fetches the list of APIs from the Discovery Service (blocking).
     /// Note: This requires a tokio runtime to be available.
+    #[cfg(feature = "http")]
     pub fn fetch() -> Result<Self, String> {
         // Create a new runtime for blocking call
         let rt = tokio::runtime::Runtime::new()
@@ -1,120 +1,497 @@
 //! Google API Discovery format parser.
 
-use gnostic_compiler::{Context, CompilerError, ErrorGroup};
-use gnostic_compiler::{map_value_for_key, string_for_scalar_node, is_mapping};
+use gnostic_compiler::{Context, CompilerError, ErrorCode, ErrorGroup, check_depth};
+use gnostic_compiler::{KeyIndex, Node};
 use std::sync::Arc;
-use serde_yaml::Value as Yaml;
 
 use crate::discovery::*;
 
 /// Parser for converting YAML/JSON nodes to Discovery Protocol Buffer types.
+///
+/// The parse functions read any [`Node`], so they build the same models from
+/// YAML trees and from borrowed JSON trees.
 pub struct Parser;
 
 impl Parser {
     /// Parses a Document from a YAML node.
-    pub fn parse_document(node: &Yaml, context: &Arc<Context>) -> Result<Document, ErrorGroup> {
+    pub fn parse_document<N: Node>(node: &N, context: &Arc<Context>) -> Result<Document, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut doc = Document::default();
 
-        if !is_mapping(node) {
-            errors.push(CompilerError::new(context, format!("expected mapping, got {:?}", node)));
+        if !node.is_mapping() {
+            errors.push(CompilerError::new_with_code(context, ErrorCode::InvalidType, format!("expected mapping, got {:?}", node)));
             return Err(ErrorGroup::new(errors));
         }
 
-        if let Some(v) = map_value_for_key(node, "kind") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("kind") {
+            if let Some(s) = v.string_value() {
                 doc.kind = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "discoveryVersion") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("discoveryVersion") {
+            if let Some(s) = v.string_value() {
                 doc.discovery_version = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "id") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("id") {
+            if let Some(s) = v.string_value() {
                 doc.id = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "name") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("name") {
+            if let Some(s) = v.string_value() {
                 doc.name = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "version") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("version") {
+            if let Some(s) = v.string_value() {
                 doc.version = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "revision") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("revision") {
+            if let Some(s) = v.string_value() {
                 doc.revision = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "title") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("title") {
+            if let Some(s) = v.string_value() {
                 doc.title = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "description") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("description") {
+            if let Some(s) = v.string_value() {
                 doc.description = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "documentationLink") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("documentationLink") {
+            if let Some(s) = v.string_value() {
                 doc.documentation_link = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "protocol") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("protocol") {
+            if let Some(s) = v.string_value() {
                 doc.protocol = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "baseUrl") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("baseUrl") {
+            if let Some(s) = v.string_value() {
                 doc.base_url = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "basePath") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("basePath") {
+            if let Some(s) = v.string_value() {
                 doc.base_path = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "rootUrl") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("rootUrl") {
+            if let Some(s) = v.string_value() {
                 doc.root_url = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "servicePath") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("servicePath") {
+            if let Some(s) = v.string_value() {
                 doc.service_path = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "batchPath") {
-            if let Some(s) = string_for_scalar_node(v) {
+        if let Some(v) = index.get("batchPath") {
+            if let Some(s) = v.string_value() {
                 doc.batch_path = s;
             }
         }
 
+        if let Some(v) = index.get("parameters") {
+            match Self::parse_parameters(v, &Arc::new(context.child("parameters"))) {
+                Ok(parameters) => doc.parameters = Some(parameters),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("schemas") {
+            match Self::parse_schemas(v, &Arc::new(context.child("schemas"))) {
+                Ok(schemas) => doc.schemas = Some(schemas),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("methods") {
+            match Self::parse_methods(v, &Arc::new(context.child("methods"))) {
+                Ok(methods) => doc.methods = Some(methods),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("resources") {
+            match Self::parse_resources(v, &Arc::new(context.child("resources"))) {
+                Ok(resources) => doc.resources = Some(resources),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
         if errors.is_empty() {
             Ok(doc)
         } else {
             Err(ErrorGroup::new(errors))
         }
     }
+
+    /// Parses Schemas from a YAML node.
+    pub fn parse_schemas<N: Node>(node: &N, context: &Arc<Context>) -> Result<Schemas, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut schemas = Schemas::default();
+        for (name, value) in node.entries() {
+            match Self::parse_schema(value, &Arc::new(context.child(name))) {
+                Ok(schema) => schemas.additional_properties.push(NamedSchema {
+                    name: name.to_string(),
+                    value: Some(schema),
+                }),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+        finish(schemas, errors)
+    }
+
+    /// Parses Schema from a YAML node.
+    pub fn parse_schema<N: Node>(node: &N, context: &Arc<Context>) -> Result<Schema, ErrorGroup> {
+        check_depth(context)?;
+        expect_mapping(node, context)?;
+        let index = KeyIndex::new(node);
+
+        let mut errors = Vec::new();
+        let mut schema = Schema {
+            id: string_value(&index, "id"),
+            r#type: string_value(&index, "type"),
+            description: string_value(&index, "description"),
+            default: string_value(&index, "default"),
+            required: bool_value(&index, "required"),
+            format: string_value(&index, "format"),
+            pattern: string_value(&index, "pattern"),
+            minimum: string_value(&index, "minimum"),
+            maximum: string_value(&index, "maximum"),
+            r#enum: string_array_value(&index, "enum"),
+            enum_descriptions: string_array_value(&index, "enumDescriptions"),
+            repeated: bool_value(&index, "repeated"),
+            location: string_value(&index, "location"),
+            r#ref: string_value(&index, "$ref"),
+            read_only: bool_value(&index, "readOnly"),
+            ..Default::default()
+        };
+
+        if let Some(v) = index.get("properties") {
+            match Self::parse_schemas(v, &Arc::new(context.child("properties"))) {
+                Ok(properties) => schema.properties = Some(properties),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("additionalProperties") {
+            match Self::parse_schema(v, &Arc::new(context.child("additionalProperties"))) {
+                Ok(values) => schema.additional_properties = Some(Box::new(values)),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("items") {
+            match Self::parse_schema(v, &Arc::new(context.child("items"))) {
+                Ok(items) => schema.items = Some(Box::new(items)),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        finish(schema, errors)
+    }
+
+    /// Parses Parameters from a YAML node.
+    pub fn parse_parameters<N: Node>(node: &N, context: &Arc<Context>) -> Result<Parameters, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut parameters = Parameters::default();
+        for (name, value) in node.entries() {
+            match Self::parse_parameter(value, &Arc::new(context.child(name))) {
+                Ok(parameter) => parameters.additional_properties.push(NamedParameter {
+                    name: name.to_string(),
+                    value: Some(parameter),
+                }),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+        finish(parameters, errors)
+    }
+
+    /// Parses Parameter from a YAML node.
+    pub fn parse_parameter<N: Node>(node: &N, context: &Arc<Context>) -> Result<Parameter, ErrorGroup> {
+        expect_mapping(node, context)?;
+        let index = KeyIndex::new(node);
+
+        let mut errors = Vec::new();
+        let mut parameter = Parameter {
+            id: string_value(&index, "id"),
+            r#type: string_value(&index, "type"),
+            r#ref: string_value(&index, "$ref"),
+            description: string_value(&index, "description"),
+            default: string_value(&index, "default"),
+            required: bool_value(&index, "required"),
+            format: string_value(&index, "format"),
+            pattern: string_value(&index, "pattern"),
+            minimum: string_value(&index, "minimum"),
+            maximum: string_value(&index, "maximum"),
+            r#enum: string_array_value(&index, "enum"),
+            enum_descriptions: string_array_value(&index, "enumDescriptions"),
+            repeated: bool_value(&index, "repeated"),
+            location: string_value(&index, "location"),
+            ..Default::default()
+        };
+
+        if let Some(v) = index.get("properties") {
+            match Self::parse_schemas(v, &Arc::new(context.child("properties"))) {
+                Ok(properties) => parameter.properties = Some(properties),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("additionalProperties") {
+            match Self::parse_schema(v, &Arc::new(context.child("additionalProperties"))) {
+                Ok(values) => parameter.additional_properties = Some(values),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("items") {
+            match Self::parse_schema(v, &Arc::new(context.child("items"))) {
+                Ok(items) => parameter.items = Some(items),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        finish(parameter, errors)
+    }
+
+    /// Parses Methods from a YAML node.
+    pub fn parse_methods<N: Node>(node: &N, context: &Arc<Context>) -> Result<Methods, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut methods = Methods::default();
+        for (name, value) in node.entries() {
+            match Self::parse_method(value, &Arc::new(context.child(name))) {
+                Ok(method) => methods.additional_properties.push(NamedMethod {
+                    name: name.to_string(),
+                    value: Some(method),
+                }),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+        finish(methods, errors)
+    }
+
+    /// Parses Method from a YAML node.
+    pub fn parse_method<N: Node>(node: &N, context: &Arc<Context>) -> Result<Method, ErrorGroup> {
+        expect_mapping(node, context)?;
+        let index = KeyIndex::new(node);
+
+        let mut errors = Vec::new();
+        let mut method = Method {
+            id: string_value(&index, "id"),
+            path: string_value(&index, "path"),
+            http_method: string_value(&index, "httpMethod"),
+            description: string_value(&index, "description"),
+            parameter_order: string_array_value(&index, "parameterOrder"),
+            scopes: string_array_value(&index, "scopes"),
+            supports_media_download: bool_value(&index, "supportsMediaDownload"),
+            supports_media_upload: bool_value(&index, "supportsMediaUpload"),
+            use_media_download_service: bool_value(&index, "useMediaDownloadService"),
+            supports_subscription: bool_value(&index, "supportsSubscription"),
+            flat_path: string_value(&index, "flatPath"),
+            etag_required: bool_value(&index, "etagRequired"),
+            streaming_type: string_value(&index, "streamingType"),
+            ..Default::default()
+        };
+
+        if let Some(v) = index.get("parameters") {
+            match Self::parse_parameters(v, &Arc::new(context.child("parameters"))) {
+                Ok(parameters) => method.parameters = Some(parameters),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("request") {
+            let request = KeyIndex::new(v);
+            method.request = Some(Request {
+                r#ref: string_value(&request, "$ref"),
+                parameter_name: string_value(&request, "parameterName"),
+            });
+        }
+
+        if let Some(v) = index.get("response") {
+            method.response = Some(Response { r#ref: string_value(&KeyIndex::new(v), "$ref") });
+        }
+
+        finish(method, errors)
+    }
+
+    /// Parses Resources from a YAML node.
+    pub fn parse_resources<N: Node>(node: &N, context: &Arc<Context>) -> Result<Resources, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut resources = Resources::default();
+        for (name, value) in node.entries() {
+            match Self::parse_resource(value, &Arc::new(context.child(name))) {
+                Ok(resource) => resources.additional_properties.push(NamedResource {
+                    name: name.to_string(),
+                    value: Some(resource),
+                }),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+        finish(resources, errors)
+    }
+
+    /// Parses Resource from a YAML node.
+    pub fn parse_resource<N: Node>(node: &N, context: &Arc<Context>) -> Result<Resource, ErrorGroup> {
+        check_depth(context)?;
+        expect_mapping(node, context)?;
+
+        let mut errors = Vec::new();
+        let mut resource = Resource::default();
+
+        if let Some(v) = node.get("methods") {
+            match Self::parse_methods(v, &Arc::new(context.child("methods"))) {
+                Ok(methods) => resource.methods = Some(methods),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = node.get("resources") {
+            match Self::parse_resources(v, &Arc::new(context.child("resources"))) {
+                Ok(resources) => resource.resources = Some(resources),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        finish(resource, errors)
+    }
+}
+
+fn expect_mapping<N: Node>(node: &N, context: &Arc<Context>) -> Result<(), ErrorGroup> {
+    if node.is_mapping() {
+        Ok(())
+    } else {
+        let message = format!("expected mapping, got {:?}", node);
+        Err(ErrorGroup::new(vec![CompilerError::new_with_code(context, ErrorCode::InvalidType, message)]))
+    }
+}
+
+fn string_value<N: Node>(index: &KeyIndex<N>, key: &str) -> String {
+    index.get(key).and_then(Node::string_value).unwrap_or_default()
+}
+
+fn bool_value<N: Node>(index: &KeyIndex<N>, key: &str) -> bool {
+    index.get(key).and_then(Node::bool_value).unwrap_or_default()
+}
+
+fn string_array_value<N: Node>(index: &KeyIndex<N>, key: &str) -> Vec<String> {
+    index.get(key).map(Node::string_values).unwrap_or_default()
+}
+
+fn finish<T>(value: T, errors: Vec<CompilerError>) -> Result<T, ErrorGroup> {
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(ErrorGroup::new(errors))
+    }
 }
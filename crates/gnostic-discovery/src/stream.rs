@@ -0,0 +1,199 @@
+//! Streaming parsing of large JSON Discovery documents.
+//!
+//! [`parse_document`](crate::parse_document) reads the whole source into a
+//! node tree before building the model, which for corpora of hundreds of
+//! megabytes holds the source twice over. [`parse_document_from_reader`]
+//! reads JSON incrementally instead: the entries of the `parameters`,
+//! `schemas`, `methods` and `resources` maps are read and parsed one at a
+//! time, so that only the node of a single entry is held alongside the
+//! document being built.
+
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use gnostic_compiler::{CompilerError, Context, ErrorGroup, ParseOptions, Parsed};
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde_yaml::{Mapping, Value as Yaml};
+
+use crate::discovery::*;
+use crate::parser::Parser;
+
+/// The top-level maps whose entries are parsed as they are read.
+const STREAMED_SECTIONS: &[&str] = &["parameters", "schemas", "methods", "resources"];
+
+/// Parses a Discovery document from a reader of JSON, without building a
+/// node tree of the whole document.
+///
+/// The result is the same as that of
+/// [`parse_document_with_options`](crate::parse_document_with_options) for
+/// the same JSON. Readers are read in small pieces, so files are best wrapped
+/// in a [`BufReader`](std::io::BufReader).
+pub fn parse_document_from_reader(reader: impl Read, options: &ParseOptions) -> Result<Parsed<Document>, ErrorGroup> {
+    let context = Arc::new(Context::root_with_options("$", options.clone()));
+    let mut sections = Sections { context: Arc::clone(&context), document: Document::default(), errors: Vec::new() };
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let rest = deserializer
+        .deserialize_map(DocumentVisitor { sections: &mut sections })
+        .and_then(|rest| deserializer.end().map(|()| rest))
+        .map_err(|e| ErrorGroup::new(vec![CompilerError::from(e)]))?;
+
+    let result = Parser::parse_document(&Yaml::Mapping(rest), &context).and_then(|mut document| {
+        if !sections.errors.is_empty() {
+            return Err(ErrorGroup::new(sections.errors));
+        }
+        document.parameters = sections.document.parameters;
+        document.schemas = sections.document.schemas;
+        document.methods = sections.document.methods;
+        document.resources = sections.document.resources;
+        Ok(document)
+    });
+    context.finish(result)
+}
+
+/// The streamed sections of a document, parsed so far.
+struct Sections {
+    context: Arc<Context>,
+    document: Document,
+    errors: Vec<CompilerError>,
+}
+
+impl Sections {
+    /// Starts a section, which is present even if it has no entries.
+    fn open(&mut self, section: &str) {
+        let document = &mut self.document;
+        match section {
+            "parameters" => {
+                document.parameters.get_or_insert_default();
+            }
+            "schemas" => {
+                document.schemas.get_or_insert_default();
+            }
+            "methods" => {
+                document.methods.get_or_insert_default();
+            }
+            _ => {
+                document.resources.get_or_insert_default();
+            }
+        }
+    }
+
+    /// Parses an entry of a section and adds it to the document.
+    fn add(&mut self, section: &str, name: String, node: &Yaml) {
+        if self.context.should_stop(&self.errors) {
+            return;
+        }
+        let context = Arc::new(Arc::new(self.context.child(section)).child(name.as_str()));
+        let document = &mut self.document;
+        let result = match section {
+            "parameters" => Parser::parse_parameter(node, &context).map(|value| {
+                let parameters = document.parameters.get_or_insert_with(Parameters::default);
+                parameters.additional_properties.push(NamedParameter { name, value: Some(value) });
+            }),
+            "schemas" => Parser::parse_schema(node, &context).map(|value| {
+                let schemas = document.schemas.get_or_insert_with(Schemas::default);
+                schemas.additional_properties.push(NamedSchema { name, value: Some(value) });
+            }),
+            "methods" => Parser::parse_method(node, &context).map(|value| {
+                let methods = document.methods.get_or_insert_with(Methods::default);
+                methods.additional_properties.push(NamedMethod { name, value: Some(value) });
+            }),
+            _ => Parser::parse_resource(node, &context).map(|value| {
+                let resources = document.resources.get_or_insert_with(Resources::default);
+                resources.additional_properties.push(NamedResource { name, value: Some(value) });
+            }),
+        };
+        if let Err(e) = result {
+            self.errors.extend(e.errors);
+        }
+    }
+}
+
+/// Reads the top-level members of a document, streaming the sections and
+/// returning the other members.
+struct DocumentVisitor<'a> {
+    sections: &'a mut Sections,
+}
+
+impl<'de> Visitor<'de> for DocumentVisitor<'_> {
+    type Value = Mapping;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a Discovery document")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Mapping, A::Error> {
+        let mut rest = Mapping::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if STREAMED_SECTIONS.contains(&key.as_str()) {
+                map.next_value_seed(SectionVisitor { section: &key, sections: &mut *self.sections })?;
+            } else {
+                let value = map.next_value::<Yaml>()?;
+                rest.insert(Yaml::String(key), value);
+            }
+        }
+        Ok(rest)
+    }
+}
+
+/// Reads the entries of a section one at a time.
+struct SectionVisitor<'a> {
+    section: &'a str,
+    sections: &'a mut Sections,
+}
+
+impl<'de> DeserializeSeed<'de> for SectionVisitor<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SectionVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a map of {}", self.section)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        self.sections.open(self.section);
+        while let Some((name, node)) = map.next_entry::<String, Yaml>()? {
+            self.sections.add(self.section, name, &node);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_document_with_options;
+
+    #[test]
+    fn test_parse_document_from_reader() {
+        let source = br#"{
+            "kind": "discovery#restDescription",
+            "discoveryVersion": "v1",
+            "name": "pets",
+            "schemas": {
+                "Pet": {"id": "Pet", "type": "object", "properties": {"name": {"type": "string"}}},
+                "Pets": {"id": "Pets", "type": "array", "items": {"$ref": "Pet"}}
+            },
+            "parameters": {},
+            "resources": {"pets": {"methods": {"list": {"id": "pets.list", "httpMethod": "GET"}}}}
+        }"#;
+        let options = ParseOptions::default();
+        let streamed = parse_document_from_reader(&source[..], &options).unwrap();
+        assert_eq!(streamed.value, parse_document_with_options(source, &options).unwrap().value);
+        assert_eq!(streamed.value.schemas.unwrap().additional_properties.len(), 2);
+        assert!(streamed.value.parameters.is_some());
+
+        let error = parse_document_from_reader(&br#"{"schemas": {"Pet": "#[..], &options).unwrap_err();
+        assert!(matches!(error.errors[..], [CompilerError::Json { .. }]), "{}", error);
+        assert!(error.to_string().contains("Invalid JSON"), "{}", error);
+        let error = parse_document_from_reader(&br#"{"schemas": {"Pet": 1}}"#[..], &options).unwrap_err();
+        assert!(error.to_string().contains("$.schemas.Pet"), "{}", error);
+    }
+}
@@ -49,3 +49,93 @@ fn test_discovery_basic_fields_present() {
     assert!(!doc.protocol.is_empty(), "protocol should not be empty");
     assert!(!doc.base_url.is_empty(), "base_url should not be empty");
 }
+
+#[test]
+fn test_discovery_proto_bytes_round_trip() {
+    let bytes = load_discovery_file("books-discovery.json");
+    let doc = parse_document(&bytes).expect("Failed to parse books-discovery.json");
+
+    let encoded = doc.to_proto_bytes();
+    let decoded = gnostic_discovery::Document::from_proto_bytes(&encoded)
+        .expect("Failed to decode document");
+    assert_eq!(doc, decoded);
+
+    let err = gnostic_discovery::Document::from_proto_bytes(&[0xff, 0xff, 0xff])
+        .expect_err("garbage should not decode");
+    assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::Protobuf);
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_discovery_cbor_round_trip() {
+    let bytes = load_discovery_file("books-discovery.json");
+    let doc = parse_document(&bytes).expect("Failed to parse books-discovery.json");
+
+    let encoded = doc.to_cbor_bytes().expect("Failed to encode document");
+    let decoded = gnostic_discovery::Document::from_cbor_bytes(&encoded)
+        .expect("Failed to decode document");
+    assert_eq!(doc, decoded);
+
+    let err = gnostic_discovery::Document::from_cbor_bytes(&[0xff, 0xff, 0xff])
+        .expect_err("garbage should not decode");
+    assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::Encoding);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_discovery_msgpack_round_trip() {
+    let bytes = load_discovery_file("books-discovery.json");
+    let doc = parse_document(&bytes).expect("Failed to parse books-discovery.json");
+
+    let encoded = doc.to_msgpack_bytes().expect("Failed to encode document");
+    let decoded = gnostic_discovery::Document::from_msgpack_bytes(&encoded)
+        .expect("Failed to decode document");
+    assert_eq!(doc, decoded);
+
+    let err = gnostic_discovery::Document::from_msgpack_bytes(&[0xc1])
+        .expect_err("garbage should not decode");
+    assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::Encoding);
+}
+
+#[test]
+fn test_discovery_parse_from_reader() {
+    for filename in ["books-discovery.json", "urlshortener-discovery.json"] {
+        let path = format!("{}/{}", TESTDATA_DIR, filename);
+        let file = std::io::BufReader::new(fs::File::open(&path).unwrap());
+        let options = gnostic_compiler::ParseOptions::default();
+        let streamed = gnostic_discovery::parse_document_from_reader(file, &options)
+            .unwrap_or_else(|e| panic!("Failed to stream {}: {}", filename, e));
+        let doc = parse_document(&load_discovery_file(filename)).unwrap();
+        assert_eq!(streamed.value, doc, "{}", filename);
+    }
+}
+
+#[test]
+fn test_discovery_parse_borrowed() {
+    for filename in ["books-discovery.json", "urlshortener-discovery.json"] {
+        let bytes = load_discovery_file(filename);
+        let json = std::str::from_utf8(&bytes).unwrap();
+        let options = gnostic_compiler::ParseOptions::default();
+        let borrowed = gnostic_discovery::parse_document_borrowed(json, &options)
+            .unwrap_or_else(|e| panic!("Failed to parse {}: {}", filename, e));
+        assert_eq!(borrowed.value, parse_document(&bytes).unwrap(), "{}", filename);
+    }
+
+    let options = gnostic_compiler::ParseOptions::default();
+    let error = gnostic_discovery::parse_document_borrowed(r#"{"schemas": {"Pet": 1}}"#, &options).unwrap_err();
+    assert!(error.to_string().contains("$.schemas.Pet"), "{}", error);
+}
+
+#[test]
+fn test_discovery_fail_fast() {
+    let source = br#"{"schemas": {"Pet": {"properties": {"id": 1, "name": 2}}}}"#;
+
+    let err = parse_document(source).expect_err("parse should fail");
+    assert_eq!(err.errors.len(), 2);
+
+    let options = gnostic_compiler::ParseOptions::fail_fast();
+    let err = gnostic_discovery::document::parse_document_with_options(source, &options)
+        .expect_err("fail-fast parse should fail");
+    assert_eq!(err.errors.len(), 1);
+    assert!(err.errors[0].to_string().contains("$.schemas.Pet.properties.id"), "{}", err);
+}
@@ -11,10 +11,11 @@ fn main() -> Result<()> {
 
     let proto_files = &[proto_root.join("extension.proto")];
 
-    let include_dirs = &[proto_root.clone()];
+    let include_dirs = &[&proto_root];
 
-    prost_build::Config::new()
-        .compile_protos(proto_files, include_dirs)?;
+    let mut config = prost_build::Config::new();
+    gnostic_build::derive_eq_and_hash(&mut config, proto_files, include_dirs, &[])?;
+    config.compile_protos(proto_files, include_dirs)?;
 
     for proto in proto_files {
         println!("cargo:rerun-if-changed={}", proto.display());
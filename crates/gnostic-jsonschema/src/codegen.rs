@@ -0,0 +1,612 @@
+//! Generation of Rust types from schemas.
+//!
+//! [`generate_rust`] renders a schema and its definitions as Rust structs,
+//! enums and type aliases that serde reads and writes as the JSON the schema
+//! describes:
+//!
+//! - objects with properties become structs, with optional properties as
+//!   `Option` fields and additionalProperties as a flattened map;
+//! - objects without properties become `HashMap`s;
+//! - string enums become enums of unit variants;
+//! - oneOf and anyOf become untagged enums with a variant for each branch;
+//! - allOf branches are merged, and the remaining references to other types
+//!   become flattened fields;
+//! - references to definitions ("#/definitions/Pet", "#/$defs/Pet") use the
+//!   type generated for the definition, and other references become
+//!   `serde_json::Value`.
+//!
+//! Names are converted to Rust conventions, and fields whose types contain
+//! the struct directly or through other structs are boxed.
+
+use crate::merge::merge_all_of;
+use crate::models::{Schema, SchemaOrBoolean, SchemaOrSchemaArray, StringOrStringArray};
+use gnostic_compiler::escape_json_pointer_segment;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Identifiers that must be written as raw identifiers.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum",
+    "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod",
+    "move", "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Identifiers that cannot be raw identifiers.
+const RESERVED: &[&str] = &["crate", "self", "super", "Self"];
+
+/// Returns Rust definitions for `schema`, named `name`, and its definitions.
+pub fn generate_rust(schema: &Schema, name: &str) -> String {
+    let schema = merge_all_of(schema);
+    let mut generator = Generator {
+        references: HashMap::new(),
+        taken: HashSet::new(),
+        definitions: Vec::new(),
+    };
+    let root_name = generator.unique_type_name(name);
+    generator.references.insert("#".to_string(), root_name.clone());
+    let mut named: Vec<(&str, &String, &SchemaOrBoolean)> = Vec::new();
+    for (section, definitions) in [("definitions", &schema.definitions), ("$defs", &schema.defs)] {
+        named.extend(definitions.iter().flatten().map(|(key, definition)| (section, key, definition)));
+    }
+    let mut definition_names = Vec::new();
+    for (section, key, _) in &named {
+        let type_name = generator.unique_type_name(key);
+        let reference = format!("#/{}/{}", section, escape_json_pointer_segment(key));
+        generator.references.insert(reference, type_name.clone());
+        definition_names.push(type_name);
+    }
+    generator.define(&root_name, &schema);
+    for ((_, _, definition), type_name) in named.into_iter().zip(definition_names) {
+        generator.define(&type_name, &definition.to_schema());
+    }
+    let mut definitions = generator.definitions;
+    box_cycles(&mut definitions);
+    render(&definitions)
+}
+
+/// The Rust type of a value.
+#[derive(Debug, Clone, PartialEq)]
+enum RustType {
+    Primitive(&'static str),
+    Named(String),
+    Option(Box<RustType>),
+    Vec(Box<RustType>),
+    Map(Box<RustType>),
+    Tuple(Vec<RustType>),
+    Boxed(Box<RustType>),
+}
+
+impl fmt::Display for RustType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RustType::Primitive(name) => write!(f, "{}", name),
+            RustType::Named(name) => write!(f, "{}", name),
+            RustType::Option(inner) => write!(f, "Option<{}>", inner),
+            RustType::Vec(inner) => write!(f, "Vec<{}>", inner),
+            RustType::Map(inner) => write!(f, "HashMap<String, {}>", inner),
+            RustType::Tuple(items) => {
+                let items: Vec<String> = items.iter().map(RustType::to_string).collect();
+                write!(f, "({})", items.join(", "))
+            }
+            RustType::Boxed(inner) => write!(f, "Box<{}>", inner),
+        }
+    }
+}
+
+impl RustType {
+    fn uses_map(&self) -> bool {
+        match self {
+            RustType::Map(_) => true,
+            RustType::Option(inner) | RustType::Vec(inner) | RustType::Boxed(inner) => inner.uses_map(),
+            RustType::Tuple(items) => items.iter().any(RustType::uses_map),
+            RustType::Primitive(_) | RustType::Named(_) => false,
+        }
+    }
+
+    /// Lists the named types that a value of this type contains without
+    /// indirection, which would make a recursive type infinitely large.
+    fn direct_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            RustType::Named(name) => names.push(name),
+            RustType::Option(inner) => inner.direct_names(names),
+            RustType::Tuple(items) => items.iter().for_each(|item| item.direct_names(names)),
+            RustType::Primitive(_) | RustType::Vec(_) | RustType::Map(_) | RustType::Boxed(_) => {}
+        }
+    }
+
+    /// Boxes the named types contained without indirection for which `boxed` is true.
+    fn box_direct(&mut self, boxed: &dyn Fn(&str) -> bool) {
+        match self {
+            RustType::Named(name) if boxed(name) => *self = RustType::Boxed(Box::new(self.clone())),
+            RustType::Option(inner) => inner.box_direct(boxed),
+            RustType::Tuple(items) => items.iter_mut().for_each(|item| item.box_direct(boxed)),
+            _ => {}
+        }
+    }
+}
+
+struct Definition {
+    name: String,
+    doc: Option<String>,
+    kind: Kind,
+}
+
+enum Kind {
+    Struct(Vec<Field>),
+    /// An enum of strings, as variant names and values.
+    StringEnum(Vec<(String, String)>),
+    /// An untagged enum, as variant names and types.
+    Untagged(Vec<(String, RustType)>),
+    Alias(RustType),
+}
+
+struct Field {
+    name: String,
+    /// The name of the property, if it differs from the field name.
+    rename: Option<String>,
+    doc: Option<String>,
+    rust_type: RustType,
+    optional: bool,
+    flatten: bool,
+}
+
+struct Generator {
+    /// Type names by the references that point to their schemas.
+    references: HashMap<String, String>,
+    taken: HashSet<String>,
+    definitions: Vec<Definition>,
+}
+
+impl Generator {
+    fn unique_type_name(&mut self, name: &str) -> String {
+        unique(&mut self.taken, upper_camel_case(name, "Type"))
+    }
+
+    /// Adds a definition of `schema` named `name`.
+    fn define(&mut self, name: &str, schema: &Schema) {
+        let doc = schema.description.clone().or_else(|| schema.title.clone());
+        let kind = if let Some(values) = string_enum(schema) {
+            let mut taken = HashSet::new();
+            let variants = values
+                .into_iter()
+                .map(|value| (unique(&mut taken, upper_camel_case(&value, "Value")), value))
+                .collect();
+            Kind::StringEnum(variants)
+        } else if let Some(branches) = schema.one_of.as_ref().or(schema.any_of.as_ref()) {
+            let mut taken = HashSet::new();
+            let mut variants = Vec::new();
+            for branch in branches {
+                let branch = branch.to_schema();
+                let variant = unique(&mut taken, self.variant_name(&branch));
+                let rust_type = self.rust_type(&branch, &format!("{}{}", name, variant));
+                variants.push((variant, rust_type));
+            }
+            Kind::Untagged(variants)
+        } else if is_struct(schema) {
+            Kind::Struct(self.fields(name, schema))
+        } else {
+            Kind::Alias(self.rust_type(schema, &format!("{}Value", name)))
+        };
+        self.definitions.push(Definition {
+            name: name.to_string(),
+            doc,
+            kind,
+        });
+    }
+
+    fn fields(&mut self, name: &str, schema: &Schema) -> Vec<Field> {
+        let required = schema.required.as_deref().unwrap_or_default();
+        let mut taken = HashSet::new();
+        let mut fields = Vec::new();
+        for branch in schema.all_of.iter().flatten().filter_map(SchemaOrBoolean::as_schema) {
+            if let Some(RustType::Named(type_name)) = branch.reference.as_ref().map(|_| self.rust_type(branch, "")) {
+                fields.push(Field {
+                    name: unique(&mut taken, snake_case(&type_name)),
+                    rename: None,
+                    doc: None,
+                    rust_type: RustType::Named(type_name),
+                    optional: false,
+                    flatten: true,
+                });
+            }
+        }
+        for (property, property_schema) in schema.properties.iter().flatten() {
+            let property_schema = property_schema.to_schema();
+            let field_name = unique(&mut taken, snake_case(property));
+            let rust_type = self.rust_type(&property_schema, &format!("{}{}", name, upper_camel_case(property, "")));
+            fields.push(Field {
+                rename: (field_name.trim_start_matches("r#") != property).then(|| property.clone()),
+                name: field_name,
+                doc: property_schema.description.clone().or_else(|| property_schema.title.clone()),
+                optional: !required.contains(property),
+                rust_type,
+                flatten: false,
+            });
+        }
+        if let Some(SchemaOrBoolean::Schema(additional)) = &schema.additional_properties {
+            let rust_type = self.rust_type(additional, &format!("{}Value", name));
+            fields.push(Field {
+                name: unique(&mut taken, "additional_properties".to_string()),
+                rename: None,
+                doc: None,
+                rust_type: RustType::Map(Box::new(rust_type)),
+                optional: false,
+                flatten: true,
+            });
+        }
+        fields
+    }
+
+    /// Returns the type of values of `schema`, defining a type named after
+    /// `hint` if the schema needs one of its own.
+    fn rust_type(&mut self, schema: &Schema, hint: &str) -> RustType {
+        if let Some(reference) = &schema.reference {
+            return match self.references.get(reference) {
+                Some(name) => RustType::Named(name.clone()),
+                None => RustType::Primitive("serde_json::Value"),
+            };
+        }
+        if string_enum(schema).is_some() || schema.one_of.is_some() || schema.any_of.is_some() || is_struct(schema) {
+            let name = self.unique_type_name(hint);
+            self.define(&name, schema);
+            return RustType::Named(name);
+        }
+        let mut types: Vec<&str> = match &schema.type_value {
+            Some(StringOrStringArray::String(name)) => vec![name.as_str()],
+            Some(StringOrStringArray::Array(names)) => names.iter().map(String::as_str).collect(),
+            None => Vec::new(),
+        };
+        let nullable = types.len() > 1 && types.contains(&"null");
+        if nullable {
+            types.retain(|name| *name != "null");
+        }
+        let rust_type = match types.as_slice() {
+            ["string"] => RustType::Primitive("String"),
+            ["integer"] => RustType::Primitive("i64"),
+            ["number"] => RustType::Primitive("f64"),
+            ["boolean"] => RustType::Primitive("bool"),
+            ["null"] => RustType::Primitive("()"),
+            ["array"] => match schema.items.as_deref() {
+                Some(SchemaOrSchemaArray::Schema(item)) => {
+                    RustType::Vec(Box::new(self.rust_type(&item.to_schema(), &format!("{}Item", hint))))
+                }
+                Some(SchemaOrSchemaArray::Array(items)) => RustType::Tuple(
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| self.rust_type(&item.to_schema(), &format!("{}Item{}", hint, i + 1)))
+                        .collect(),
+                ),
+                None => RustType::Vec(Box::new(RustType::Primitive("serde_json::Value"))),
+            },
+            ["object"] => match &schema.additional_properties {
+                Some(SchemaOrBoolean::Schema(additional)) => {
+                    RustType::Map(Box::new(self.rust_type(additional, &format!("{}Value", hint))))
+                }
+                _ => RustType::Map(Box::new(RustType::Primitive("serde_json::Value"))),
+            },
+            _ => RustType::Primitive("serde_json::Value"),
+        };
+        if nullable {
+            RustType::Option(Box::new(rust_type))
+        } else {
+            rust_type
+        }
+    }
+
+    /// Returns the name of the variant for a branch of oneOf or anyOf.
+    fn variant_name(&self, branch: &Schema) -> String {
+        if let Some(name) = branch.reference.as_ref().and_then(|reference| self.references.get(reference)) {
+            return name.clone();
+        }
+        let name = match &branch.type_value {
+            Some(StringOrStringArray::String(name)) => name.as_str(),
+            _ if branch.properties.is_some() => "object",
+            _ => "variant",
+        };
+        upper_camel_case(name, "")
+    }
+}
+
+/// Returns the values of a schema that is an enum of strings.
+fn string_enum(schema: &Schema) -> Option<Vec<String>> {
+    let values = schema.enumeration.as_ref()?;
+    let strings: Vec<String> = values.iter().filter_map(|value| value.as_str().map(str::to_string)).collect();
+    (!strings.is_empty() && strings.len() == values.len()).then_some(strings)
+}
+
+/// Returns true for schemas that are represented by structs.
+fn is_struct(schema: &Schema) -> bool {
+    let is_reference = |branch: &SchemaOrBoolean| branch.as_schema().is_some_and(|branch| branch.reference.is_some());
+    schema.properties.is_some() || schema.all_of.iter().flatten().any(is_reference)
+}
+
+/// Boxes the fields and variants of each definition whose types contain the
+/// definition itself without indirection.
+fn box_cycles(definitions: &mut [Definition]) {
+    let mut contained: HashMap<String, Vec<String>> = HashMap::new();
+    for definition in definitions.iter() {
+        let mut names = Vec::new();
+        for rust_type in types_of(&definition.kind) {
+            rust_type.direct_names(&mut names);
+        }
+        let names = names.into_iter().map(str::to_string).collect();
+        contained.insert(definition.name.clone(), names);
+    }
+    let reaches = |from: &str, to: &str| {
+        let mut stack = vec![from];
+        let mut seen = HashSet::new();
+        while let Some(name) = stack.pop() {
+            if name == to {
+                return true;
+            }
+            if seen.insert(name) {
+                stack.extend(contained.get(name).into_iter().flatten().map(String::as_str));
+            }
+        }
+        false
+    };
+    for definition in definitions.iter_mut() {
+        let owner = definition.name.clone();
+        let boxed = |name: &str| reaches(name, &owner);
+        match &mut definition.kind {
+            Kind::Struct(fields) => fields.iter_mut().for_each(|field| field.rust_type.box_direct(&boxed)),
+            Kind::Untagged(variants) => variants.iter_mut().for_each(|(_, rust_type)| rust_type.box_direct(&boxed)),
+            Kind::StringEnum(_) | Kind::Alias(_) => {}
+        }
+    }
+}
+
+fn types_of(kind: &Kind) -> Vec<&RustType> {
+    match kind {
+        Kind::Struct(fields) => fields.iter().map(|field| &field.rust_type).collect(),
+        Kind::Untagged(variants) => variants.iter().map(|(_, rust_type)| rust_type).collect(),
+        Kind::Alias(rust_type) => vec![rust_type],
+        Kind::StringEnum(_) => Vec::new(),
+    }
+}
+
+fn render(definitions: &[Definition]) -> String {
+    let mut result = String::from("use serde::{Deserialize, Serialize};\n");
+    if definitions.iter().any(|definition| types_of(&definition.kind).into_iter().any(RustType::uses_map)) {
+        result.push_str("use std::collections::HashMap;\n");
+    }
+    for definition in definitions {
+        result.push('\n');
+        push_doc(&mut result, "", definition.doc.as_deref());
+        let name = &definition.name;
+        if !matches!(definition.kind, Kind::Alias(_)) {
+            result.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+        }
+        match &definition.kind {
+            Kind::Struct(fields) => {
+                result.push_str(&format!("pub struct {} {{\n", name));
+                for field in fields {
+                    push_doc(&mut result, "    ", field.doc.as_deref());
+                    let mut attributes = Vec::new();
+                    if let Some(rename) = &field.rename {
+                        attributes.push(format!("rename = {:?}", rename));
+                    }
+                    if field.flatten {
+                        attributes.push("flatten".to_string());
+                    }
+                    let rust_type = match &field.rust_type {
+                        rust_type @ RustType::Option(_) => rust_type.clone(),
+                        rust_type if field.optional => RustType::Option(Box::new(rust_type.clone())),
+                        rust_type => rust_type.clone(),
+                    };
+                    if field.optional {
+                        attributes.push("default, skip_serializing_if = \"Option::is_none\"".to_string());
+                    }
+                    if !attributes.is_empty() {
+                        result.push_str(&format!("    #[serde({})]\n", attributes.join(", ")));
+                    }
+                    result.push_str(&format!("    pub {}: {},\n", field.name, rust_type));
+                }
+                result.push_str("}\n");
+            }
+            Kind::StringEnum(variants) => {
+                result.push_str(&format!("pub enum {} {{\n", name));
+                for (variant, value) in variants {
+                    result.push_str(&format!("    #[serde(rename = {:?})]\n    {},\n", value, variant));
+                }
+                result.push_str("}\n");
+            }
+            Kind::Untagged(variants) => {
+                result.push_str(&format!("#[serde(untagged)]\npub enum {} {{\n", name));
+                for (variant, rust_type) in variants {
+                    result.push_str(&format!("    {}({}),\n", variant, rust_type));
+                }
+                result.push_str("}\n");
+            }
+            Kind::Alias(rust_type) => result.push_str(&format!("pub type {} = {};\n", name, rust_type)),
+        }
+    }
+    result
+}
+
+fn push_doc(result: &mut String, indent: &str, doc: Option<&str>) {
+    for line in doc.into_iter().flat_map(str::lines) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            result.push_str(&format!("{}///\n", indent));
+        } else {
+            result.push_str(&format!("{}/// {}\n", indent, line));
+        }
+    }
+}
+
+/// Returns `name`, or `name` followed by the first number from 2 that makes it unused.
+fn unique(taken: &mut HashSet<String>, name: String) -> String {
+    let mut unique = name.clone();
+    let mut suffix = 2;
+    while !taken.insert(unique.clone()) {
+        unique = format!("{}{}", name, suffix);
+        suffix += 1;
+    }
+    unique
+}
+
+/// Splits a name into words at non-alphanumeric characters and case changes,
+/// so that "petName", "pet_name" and "PetName" give the same words.
+fn words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = name.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let previous = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_uppercase()
+            && previous.is_some_and(|previous| {
+                previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+            });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Converts a name to UpperCamelCase, adding `prefix` before names that do
+/// not start with a letter.
+fn upper_camel_case(name: &str, prefix: &str) -> String {
+    let mut result: String = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().into_iter().flat_map(char::to_uppercase);
+            first.chain(chars.flat_map(char::to_lowercase)).collect::<String>()
+        })
+        .collect();
+    if !result.starts_with(|c: char| c.is_alphabetic()) {
+        result.insert_str(0, if prefix.is_empty() { "Value" } else { prefix });
+    }
+    result
+}
+
+/// Converts a name to a snake_case field name, as a raw identifier if it is a keyword.
+fn snake_case(name: &str) -> String {
+    let mut result = words(name).join("_").to_lowercase();
+    if !result.starts_with(|c: char| c.is_alphabetic()) {
+        result.insert(0, '_');
+    }
+    if KEYWORDS.contains(&result.as_str()) {
+        format!("r#{}", result)
+    } else if RESERVED.contains(&result.as_str()) {
+        format!("{}_", result)
+    } else {
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn generate(value: serde_json::Value, name: &str) -> String {
+        let schema: Schema = serde_json::from_value(value).unwrap();
+        generate_rust(&schema, name)
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(upper_camel_case("pet-store_API", "Type"), "PetStoreApi");
+        assert_eq!(upper_camel_case("HTTPServer", "Type"), "HttpServer");
+        assert_eq!(upper_camel_case("2xx", "Type"), "Type2xx");
+        assert_eq!(snake_case("petName"), "pet_name");
+        assert_eq!(snake_case("$ref"), "r#ref");
+        assert_eq!(snake_case("type"), "r#type");
+        assert_eq!(snake_case("self"), "self_");
+        assert_eq!(snake_case("3d"), "_3d");
+    }
+
+    #[test]
+    fn test_generate_structs_and_enums() {
+        let code = generate(
+            json!({
+                "description": "A pet.",
+                "type": "object",
+                "required": ["id", "type"],
+                "properties": {
+                    "id": {"type": "integer"},
+                    "type": {"type": "string", "enum": ["cat", "dog"]},
+                    "petName": {"type": ["string", "null"], "description": "The name."},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "labels": {"type": "object", "additionalProperties": {"type": "string"}},
+                },
+            }),
+            "pet",
+        );
+        assert_eq!(
+            code,
+            r#"use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PetType {
+    #[serde(rename = "cat")]
+    Cat,
+    #[serde(rename = "dog")]
+    Dog,
+}
+
+/// A pet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pet {
+    pub id: i64,
+    pub r#type: PetType,
+    /// The name.
+    #[serde(rename = "petName", default, skip_serializing_if = "Option::is_none")]
+    pub pet_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_generate_references_and_combinators() {
+        let code = generate(
+            json!({
+                "oneOf": [{"$ref": "#/definitions/Cat"}, {"$ref": "#/$defs/dog"}, {"type": "string"}],
+                "definitions": {
+                    "Cat": {
+                        "allOf": [{"$ref": "#/$defs/dog"}, {"properties": {"lives": {"type": "integer"}}}],
+                    },
+                    "Node": {
+                        "properties": {"next": {"$ref": "#/definitions/Node"}, "children": {"items": {"$ref": "#"}}},
+                        "additionalProperties": {"type": "number"},
+                    },
+                },
+                "$defs": {"dog": {"type": "object"}},
+            }),
+            "Animal",
+        );
+        assert!(code.contains(
+            "#[serde(untagged)]\npub enum Animal {\n    Cat(Cat),\n    Dog(Dog),\n    String(String),\n}"
+        ));
+        assert!(code.contains("pub struct Cat {\n    #[serde(flatten)]\n    pub dog: Dog,\n"));
+        assert!(code.contains("    pub lives: Option<i64>,\n"));
+        assert!(code.contains("pub type Dog = HashMap<String, serde_json::Value>;\n"));
+        assert!(code.contains("    pub next: Option<Box<Node>>,\n"));
+        assert!(code.contains("    pub children: Option<serde_json::Value>,\n"));
+        assert!(code.contains("    #[serde(flatten)]\n    pub additional_properties: HashMap<String, f64>,\n"));
+    }
+}
@@ -19,6 +19,27 @@ impl StringOrStringArray {
     }
 }
 
+impl fmt::Display for NumberOrBoolean {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumberOrBoolean::Number(SchemaNumber::Integer(n)) => write!(f, "{}", n),
+            NumberOrBoolean::Number(SchemaNumber::Float(n)) => write!(f, "{}", n),
+            NumberOrBoolean::Boolean(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+impl SchemaOrBoolean {
+    /// Returns a string representation of the schema with the given
+    /// indentation; true and false are written as they are.
+    pub fn describe_schema(&self, indent: &str) -> String {
+        match self {
+            SchemaOrBoolean::Schema(schema) => schema.describe_schema(indent),
+            SchemaOrBoolean::Boolean(b) => format!("{}{}\n", indent, b),
+        }
+    }
+}
+
 impl fmt::Display for Schema {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.describe_schema(""))
@@ -87,6 +108,10 @@ impl Schema {
                 }
             }
         }
+        if let Some(ref contains) = self.contains {
+            result.push_str(&format!("{}contains:\n", indent));
+            result.push_str(&contains.describe_schema(&next_indent));
+        }
         if let Some(ref max_items) = self.max_items {
             result.push_str(&format!("{}maxItems: {}\n", indent, max_items));
         }
@@ -147,12 +172,19 @@ impl Schema {
                 }
             }
         }
+        if let Some(ref property_names) = self.property_names {
+            result.push_str(&format!("{}propertyNames:\n", indent));
+            result.push_str(&property_names.describe_schema(&next_indent));
+        }
         if let Some(ref enumeration) = self.enumeration {
             result.push_str(&format!("{}enumeration:\n", indent));
             for value in enumeration {
                 result.push_str(&format!("{}{}\n", next_indent, value));
             }
         }
+        if let Some(ref const_value) = self.const_value {
+            result.push_str(&format!("{}const: {}\n", indent, const_value));
+        }
         if let Some(ref type_value) = self.type_value {
             result.push_str(&format!("{}type: {}\n", indent, type_value.description()));
         }
@@ -181,6 +213,18 @@ impl Schema {
             result.push_str(&format!("{}not:\n", indent));
             result.push_str(&not.describe_schema(&next_indent));
         }
+        if let Some(ref if_schema) = self.if_schema {
+            result.push_str(&format!("{}if:\n", indent));
+            result.push_str(&if_schema.describe_schema(&next_indent));
+        }
+        if let Some(ref then_schema) = self.then_schema {
+            result.push_str(&format!("{}then:\n", indent));
+            result.push_str(&then_schema.describe_schema(&next_indent));
+        }
+        if let Some(ref else_schema) = self.else_schema {
+            result.push_str(&format!("{}else:\n", indent));
+            result.push_str(&else_schema.describe_schema(&next_indent));
+        }
         if let Some(ref definitions) = self.definitions {
             result.push_str(&format!("{}definitions:\n", indent));
             for (name, s) in definitions {
@@ -188,6 +232,13 @@ impl Schema {
                 result.push_str(&s.describe_schema(&double_indent));
             }
         }
+        if let Some(ref defs) = self.defs {
+            result.push_str(&format!("{}$defs:\n", indent));
+            for (name, s) in defs {
+                result.push_str(&format!("{}{}:\n", next_indent, name));
+                result.push_str(&s.describe_schema(&double_indent));
+            }
+        }
         if let Some(ref title) = self.title {
             result.push_str(&format!("{}title: {}\n", indent, title));
         }
@@ -198,9 +249,27 @@ impl Schema {
             result.push_str(&format!("{}default:\n", indent));
             result.push_str(&format!("{}  {}\n", indent, default));
         }
+        if let Some(ref examples) = self.examples {
+            result.push_str(&format!("{}examples:\n", indent));
+            for value in examples {
+                result.push_str(&format!("{}{}\n", next_indent, value));
+            }
+        }
         if let Some(ref format) = self.format {
             result.push_str(&format!("{}format: {}\n", indent, format));
         }
+        if let Some(ref content_encoding) = self.content_encoding {
+            result.push_str(&format!("{}contentEncoding: {}\n", indent, content_encoding));
+        }
+        if let Some(ref content_media_type) = self.content_media_type {
+            result.push_str(&format!("{}contentMediaType: {}\n", indent, content_media_type));
+        }
+        if let Some(ref read_only) = self.read_only {
+            result.push_str(&format!("{}readOnly: {}\n", indent, read_only));
+        }
+        if let Some(ref write_only) = self.write_only {
+            result.push_str(&format!("{}writeOnly: {}\n", indent, write_only));
+        }
         if let Some(ref reference) = self.reference {
             result.push_str(&format!("{}$ref: {}\n", indent, reference));
         }
@@ -238,13 +307,16 @@ mod tests {
 
     #[test]
     fn test_schema_with_properties() {
-        use std::collections::HashMap;
 
-        let mut properties = HashMap::new();
-        properties.insert("name".to_string(), Schema {
-            type_value: Some(StringOrStringArray::String("string".to_string())),
-            ..Default::default()
-        });
+        let mut properties = IndexMap::new();
+        properties.insert(
+            "name".to_string(),
+            Schema {
+                type_value: Some(StringOrStringArray::String("string".to_string())),
+                ..Default::default()
+            }
+            .into(),
+        );
 
         let schema = Schema {
             type_value: Some(StringOrStringArray::String("object".to_string())),
@@ -256,4 +328,26 @@ mod tests {
         assert!(output.contains("properties:"));
         assert!(output.contains("name:"));
     }
+
+    #[test]
+    fn test_schema_draft7_display() {
+        let schema: Schema = serde_json::from_str(
+            r#"{"const": "cat", "readOnly": true, "contentMediaType": "image/png", "if": {"type": "string"}}"#,
+        )
+        .unwrap();
+        let output = schema.to_string();
+        assert!(output.contains("const: \"cat\""));
+        assert!(output.contains("if:\n  type: string"));
+        assert!(output.contains("contentMediaType: image/png"));
+        assert!(output.contains("readOnly: true"));
+    }
+
+    #[test]
+    fn test_boolean_schema_display() {
+        let schema: Schema = serde_json::from_str(r#"{"items": true, "not": false, "exclusiveMaximum": 5}"#).unwrap();
+        let output = schema.to_string();
+        assert!(output.contains("items:\n    true\n"));
+        assert!(output.contains("not:\n  false\n"));
+        assert!(output.contains("exclusiveMaximum: 5\n"));
+    }
 }
@@ -1,11 +1,29 @@
 //! JSON Schema support library for gnostic-models.
 
 pub mod base;
+pub mod codegen;
 pub mod display;
+pub mod merge;
 pub mod models;
+pub mod normalize;
 pub mod operations;
 pub mod reader;
+pub mod resolver;
+pub mod sample;
+pub mod stats;
+pub mod validator;
+pub mod visitor;
 pub mod writer;
 
-pub use base::{base_schema, base_schema_bytes, base_schema_string};
+pub use base::{
+    base_schema, base_schema_bytes, base_schema_for_draft, base_schema_string, base_schema_string_for_draft,
+    vocabulary_schema_strings, Draft,
+};
+pub use codegen::generate_rust;
+pub use merge::merge_all_of;
 pub use models::*;
+pub use resolver::{bundle_schema, bundle_schema_with_policy, resolve_schema, resolve_schema_with_policy, Resolver};
+pub use sample::sample_instance;
+pub use stats::SchemaStats;
+pub use validator::{validate_instance, ValidationError};
+pub use visitor::{walk, walk_mut, SchemaVisitor, SchemaVisitorMut};
@@ -0,0 +1,481 @@
+//! Merging of allOf branches.
+//!
+//! Code generators usually want one schema per type rather than a schema and
+//! the branches of its allOf. [`merge_all_of`] combines the branches into
+//! the schema containing them, intersecting their constraints: the merged
+//! schema accepts the values that the schema and all of its branches accept.
+//! Constraints that cannot be combined into one keyword, such as two
+//! different patterns, are kept in a remaining allOf.
+
+use crate::models::{
+    IndexMap, NumberOrBoolean, Schema, SchemaNumber, SchemaOrBoolean, SchemaOrSchemaArray, SchemaOrStringArray,
+    StringOrStringArray,
+};
+use crate::operations::subschemas_mut;
+use regex::Regex;
+
+/// Returns `schema` with the allOf branches of it and every schema nested in
+/// it merged into the schemas containing them.
+///
+/// Branches that are references are kept in the allOf, since their targets
+/// are not known here; resolve them first with
+/// [`resolve_schema`](crate::resolver::resolve_schema) to merge them too.
+pub fn merge_all_of(schema: &Schema) -> Schema {
+    let mut merged = schema.clone();
+    flatten(&mut merged);
+    merged
+}
+
+fn flatten(schema: &mut Schema) {
+    for subschema in subschemas_mut(schema) {
+        flatten(subschema);
+    }
+    // In Draft 4 the keywords next to $ref are ignored.
+    if schema.reference.is_some() {
+        return;
+    }
+    let Some(branches) = schema.all_of.take() else {
+        return;
+    };
+    let base = std::mem::take(schema);
+    *schema = branches.into_iter().fold(base, |merged, branch| match branch {
+        SchemaOrBoolean::Schema(branch) => merge(merged, *branch),
+        branch => merge(merged, branch.to_schema().into_owned()),
+    });
+}
+
+/// Returns a schema that accepts the values that both `a` and `b` accept.
+pub(crate) fn merge(mut a: Schema, mut b: Schema) -> Schema {
+    if b.reference.is_some() {
+        a.all_of.get_or_insert_with(Vec::new).push(b.into());
+        return a;
+    }
+    // Properties depend on the pattern and additional properties of both.
+    let properties = merge_properties(&a, &b);
+    let mut conflicts = Schema::default();
+
+    // Annotations: the first schema that has one wins.
+    a.schema = a.schema.or(b.schema);
+    a.id = a.id.or(b.id);
+    a.title = a.title.or(b.title);
+    a.description = a.description.or(b.description);
+    a.default = a.default.or(b.default);
+    a.examples = a.examples.or(b.examples);
+    a.read_only = either(a.read_only, b.read_only);
+    a.write_only = either(a.write_only, b.write_only);
+    merge_named(&mut a.definitions, b.definitions);
+    merge_named(&mut a.defs, b.defs);
+
+    a.type_value = match (a.type_value, b.type_value) {
+        (Some(x), Some(y)) => Some(intersect_types(&type_names(&x), &type_names(&y))),
+        (x, y) => x.or(y),
+    };
+    a.enumeration = match (a.enumeration, b.enumeration) {
+        (Some(x), Some(y)) => Some(x.into_iter().filter(|value| y.contains(value)).collect()),
+        (x, y) => x.or(y),
+    };
+
+    // Numbers.
+    keep(&mut a.multiple_of, b.multiple_of, &mut conflicts.multiple_of);
+    merge_bound(
+        (&mut a.maximum, &mut a.exclusive_maximum),
+        (b.maximum, b.exclusive_maximum),
+        &mut conflicts.exclusive_maximum,
+        |x, y| x < y,
+    );
+    merge_bound(
+        (&mut a.minimum, &mut a.exclusive_minimum),
+        (b.minimum, b.exclusive_minimum),
+        &mut conflicts.exclusive_minimum,
+        |x, y| x > y,
+    );
+
+    // Strings.
+    a.max_length = tighter(a.max_length, b.max_length, i64::min);
+    a.min_length = tighter(a.min_length, b.min_length, i64::max);
+    keep(&mut a.pattern, b.pattern, &mut conflicts.pattern);
+    keep(&mut a.format, b.format, &mut conflicts.format);
+    keep(&mut a.content_encoding, b.content_encoding, &mut conflicts.content_encoding);
+    keep(&mut a.content_media_type, b.content_media_type, &mut conflicts.content_media_type);
+
+    // Arrays.
+    a.max_items = tighter(a.max_items, b.max_items, i64::min);
+    a.min_items = tighter(a.min_items, b.min_items, i64::max);
+    a.unique_items = either(a.unique_items, b.unique_items);
+    a.items = match (a.items, b.items) {
+        (Some(x), Some(y)) => match (*x, *y) {
+            (SchemaOrSchemaArray::Schema(x), SchemaOrSchemaArray::Schema(y)) => {
+                Some(Box::new(SchemaOrSchemaArray::Schema(merge_subschemas(x, y))))
+            }
+            (x, y) => {
+                if x != y {
+                    conflicts.items = Some(Box::new(y));
+                }
+                Some(Box::new(x))
+            }
+        },
+        (x, y) => x.or(y),
+    };
+    a.additional_items = merge_additional(a.additional_items, b.additional_items);
+    keep(&mut a.contains, b.contains, &mut conflicts.contains);
+
+    // Objects.
+    a.max_properties = tighter(a.max_properties, b.max_properties, i64::min);
+    a.min_properties = tighter(a.min_properties, b.min_properties, i64::max);
+    if let Some(required) = b.required {
+        let merged = a.required.get_or_insert_with(Vec::new);
+        for name in required {
+            if !merged.contains(&name) {
+                merged.push(name);
+            }
+        }
+    }
+    a.properties = properties;
+    if let Some(pattern_properties) = b.pattern_properties.take() {
+        let merged = a.pattern_properties.get_or_insert_with(IndexMap::new);
+        for (pattern, schema) in pattern_properties {
+            update(merged, pattern, |current| match current {
+                Some(current) => merge_subschemas(current, schema),
+                None => schema,
+            });
+        }
+    }
+    a.additional_properties = merge_additional(a.additional_properties, b.additional_properties);
+    if let Some(dependencies) = b.dependencies {
+        let merged = a.dependencies.get_or_insert_with(IndexMap::new);
+        for (name, dependency) in dependencies {
+            let conflict_name = name.clone();
+            update(merged, name, |current| match (current, dependency) {
+                (None, y) => y,
+                (Some(SchemaOrStringArray::StringArray(mut x)), SchemaOrStringArray::StringArray(y)) => {
+                    for name in y {
+                        if !x.contains(&name) {
+                            x.push(name);
+                        }
+                    }
+                    SchemaOrStringArray::StringArray(x)
+                }
+                (Some(SchemaOrStringArray::Schema(x)), SchemaOrStringArray::Schema(y)) => {
+                    SchemaOrStringArray::Schema(merge_subschemas(x, y))
+                }
+                (Some(x), y) => {
+                    conflicts.dependencies.get_or_insert_with(IndexMap::new).insert(conflict_name, y);
+                    x
+                }
+            });
+        }
+    }
+    keep(&mut a.property_names, b.property_names, &mut conflicts.property_names);
+
+    // Everything else.
+    keep(&mut a.const_value, b.const_value, &mut conflicts.const_value);
+    keep(&mut a.any_of, b.any_of, &mut conflicts.any_of);
+    keep(&mut a.one_of, b.one_of, &mut conflicts.one_of);
+    keep(&mut a.not, b.not, &mut conflicts.not);
+    let condition = (&b.if_schema, &b.then_schema, &b.else_schema);
+    if a.if_schema.is_none() {
+        a.if_schema = b.if_schema;
+        a.then_schema = b.then_schema;
+        a.else_schema = b.else_schema;
+    } else if b.if_schema.is_some() && (&a.if_schema, &a.then_schema, &a.else_schema) != condition {
+        conflicts.if_schema = b.if_schema;
+        conflicts.then_schema = b.then_schema;
+        conflicts.else_schema = b.else_schema;
+    }
+
+    let mut remaining: Vec<SchemaOrBoolean> = b.all_of.unwrap_or_default();
+    if conflicts != Schema::default() {
+        remaining.push(conflicts.into());
+    }
+    if !remaining.is_empty() {
+        a.all_of.get_or_insert_with(Vec::new).extend(remaining);
+    }
+    a
+}
+
+/// Returns a subschema that accepts the values that both `a` and `b` accept,
+/// where true accepts every value and false none.
+fn merge_subschemas(a: SchemaOrBoolean, b: SchemaOrBoolean) -> SchemaOrBoolean {
+    match (a, b) {
+        (SchemaOrBoolean::Schema(x), SchemaOrBoolean::Schema(y)) => merge(*x, *y).into(),
+        (SchemaOrBoolean::Boolean(false), _) | (_, SchemaOrBoolean::Boolean(false)) => SchemaOrBoolean::Boolean(false),
+        (SchemaOrBoolean::Boolean(true), other) | (other, SchemaOrBoolean::Boolean(true)) => other,
+    }
+}
+
+/// Merges a maximum or minimum and its exclusive keyword, which is a flag of
+/// the bound in Draft 4 and a bound of its own from Draft 6 on; `tighter`
+/// tells whether a bound allows fewer values than another. An exclusive
+/// bound next to an exclusive flag is a conflict, which is recorded in
+/// `conflict`.
+fn merge_bound(
+    (bound, exclusive): (&mut Option<SchemaNumber>, &mut Option<NumberOrBoolean>),
+    (other_bound, other_exclusive): (Option<SchemaNumber>, Option<NumberOrBoolean>),
+    conflict: &mut Option<NumberOrBoolean>,
+    tighter: fn(f64, f64) -> bool,
+) {
+    let (mut flag, mut exclusive_bound) = exclusive_parts(exclusive.take());
+    let (other_flag, other_exclusive_bound) = exclusive_parts(other_exclusive);
+    if let Some(other_bound) = other_bound {
+        let current = bound.as_ref().map(SchemaNumber::as_f64);
+        if current.is_none_or(|current| tighter(other_bound.as_f64(), current)) {
+            *bound = Some(other_bound);
+            flag = other_flag;
+        } else if current == Some(other_bound.as_f64()) {
+            flag = either(flag, other_flag);
+        }
+    }
+    if let Some(other) = other_exclusive_bound {
+        if exclusive_bound.as_ref().is_none_or(|current| tighter(other.as_f64(), current.as_f64())) {
+            exclusive_bound = Some(other);
+        }
+    }
+    *exclusive = match (flag, exclusive_bound) {
+        (Some(true), Some(exclusive_bound)) => {
+            *conflict = Some(NumberOrBoolean::Number(exclusive_bound));
+            Some(NumberOrBoolean::Boolean(true))
+        }
+        (_, Some(exclusive_bound)) => Some(NumberOrBoolean::Number(exclusive_bound)),
+        (flag, None) => flag.map(NumberOrBoolean::Boolean),
+    };
+}
+
+/// Splits an exclusive keyword into its Draft 4 flag and its Draft 6 bound.
+fn exclusive_parts(exclusive: Option<NumberOrBoolean>) -> (Option<bool>, Option<SchemaNumber>) {
+    match exclusive {
+        Some(NumberOrBoolean::Boolean(flag)) => (Some(flag), None),
+        Some(NumberOrBoolean::Number(bound)) => (None, Some(bound)),
+        None => (None, None),
+    }
+}
+
+/// Sets `merged` to `other` if it is unset; a different `other` is a
+/// conflict, which is recorded in `conflict`.
+fn keep<T: PartialEq>(merged: &mut Option<T>, other: Option<T>, conflict: &mut Option<T>) {
+    match (merged.as_ref(), other) {
+        (None, other) => *merged = other,
+        (Some(current), Some(other)) if *current != other => *conflict = Some(other),
+        _ => {}
+    }
+}
+
+fn tighter(a: Option<i64>, b: Option<i64>, pick: fn(i64, i64) -> i64) -> Option<i64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(pick(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Merges flags that constrain more when true, such as uniqueItems.
+fn either(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (a, b) => a.or(b),
+    }
+}
+
+fn merge_named(
+    merged: &mut Option<IndexMap<String, SchemaOrBoolean>>,
+    other: Option<IndexMap<String, SchemaOrBoolean>>,
+) {
+    for (name, schema) in other.into_iter().flatten() {
+        merged.get_or_insert_with(IndexMap::new).entry(name).or_insert(schema);
+    }
+}
+
+/// Replaces the value of `key` in `map` by `value` of its current value,
+/// keeping its position.
+fn update<V>(map: &mut IndexMap<String, V>, key: String, value: impl FnOnce(Option<V>) -> V) {
+    match map.shift_remove_full(&key) {
+        Some((index, _, current)) => {
+            map.shift_insert(index, key, value(Some(current)));
+        }
+        None => {
+            let value = value(None);
+            map.insert(key, value);
+        }
+    }
+}
+
+fn merge_additional(a: Option<SchemaOrBoolean>, b: Option<SchemaOrBoolean>) -> Option<SchemaOrBoolean> {
+    match (a, b) {
+        (Some(x), Some(y)) => Some(merge_subschemas(x, y)),
+        (x, y) => x.or(y),
+    }
+}
+
+/// Merges the properties of `a` and `b`, constraining each property by
+/// everything either schema requires of it.
+fn merge_properties(a: &Schema, b: &Schema) -> Option<IndexMap<String, SchemaOrBoolean>> {
+    if a.properties.is_none() && b.properties.is_none() {
+        return None;
+    }
+    let mut names: Vec<&String> = Vec::new();
+    for name in a.properties.iter().chain(&b.properties).flat_map(IndexMap::keys) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    let properties = names
+        .into_iter()
+        .filter_map(|name| {
+            let merged = match (property_constraint(a, name), property_constraint(b, name)) {
+                (Some(x), Some(y)) => merge_subschemas(x, y),
+                (x, y) => x.or(y)?,
+            };
+            Some((name.clone(), merged))
+        })
+        .collect();
+    Some(properties)
+}
+
+/// Returns what `schema` requires of the property `name`, which may come
+/// from its properties, its pattern properties or its additional
+/// properties; None if it accepts any value.
+fn property_constraint(schema: &Schema, name: &str) -> Option<SchemaOrBoolean> {
+    if let Some(property) = schema.properties.as_ref().and_then(|properties| properties.get(name)) {
+        return Some(property.clone());
+    }
+    let matching: Vec<SchemaOrBoolean> = schema
+        .pattern_properties
+        .iter()
+        .flatten()
+        .filter(|(pattern, _)| Regex::new(pattern).is_ok_and(|regex| regex.is_match(name)))
+        .map(|(_, property)| property.clone())
+        .collect();
+    if !matching.is_empty() {
+        return matching.into_iter().reduce(merge_subschemas);
+    }
+    schema
+        .additional_properties
+        .clone()
+        .filter(|additional| *additional != SchemaOrBoolean::Boolean(true))
+}
+
+fn type_names(types: &StringOrStringArray) -> Vec<String> {
+    match types {
+        StringOrStringArray::String(name) => vec![name.clone()],
+        StringOrStringArray::Array(names) => names.clone(),
+    }
+}
+
+/// Returns the types in both `a` and `b`, where integers are numbers.
+fn intersect_types(a: &[String], b: &[String]) -> StringOrStringArray {
+    let mut names: Vec<String> = Vec::new();
+    for name in a {
+        let common = if b.contains(name) {
+            Some(name.as_str())
+        } else if (name == "number" && b.iter().any(|b| b == "integer"))
+            || (name == "integer" && b.iter().any(|b| b == "number"))
+        {
+            Some("integer")
+        } else {
+            None
+        };
+        if let Some(common) = common.filter(|common| !names.iter().any(|name| name == common)) {
+            names.push(common.to_string());
+        }
+    }
+    if names.len() == 1 {
+        StringOrStringArray::String(names.remove(0))
+    } else {
+        StringOrStringArray::Array(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn merged(value: serde_json::Value) -> serde_json::Value {
+        let schema: Schema = serde_json::from_value(value).unwrap();
+        serde_json::to_value(merge_all_of(&schema)).unwrap()
+    }
+
+    #[test]
+    fn test_merge_constraints() {
+        assert_eq!(
+            merged(json!({
+                "title": "Count",
+                "type": ["number", "string"],
+                "allOf": [
+                    {"type": "integer", "maximum": 10, "minimum": 0, "exclusiveMinimum": true},
+                    {"maximum": 5, "minimum": 0, "enum": [1, 2, 3]},
+                    {"title": "Ignored", "enum": [2, 3, 4], "maximum": 5, "exclusiveMaximum": true},
+                ],
+            })),
+            json!({
+                "title": "Count",
+                "type": "integer",
+                "maximum": 5,
+                "exclusiveMaximum": true,
+                "minimum": 0,
+                "exclusiveMinimum": true,
+                "enum": [2, 3],
+            })
+        );
+        assert_eq!(
+            merged(json!({
+                "allOf": [{"maxLength": 5, "pattern": "^a"}, {"maxLength": 3, "minLength": 1, "pattern": "b$"}],
+            })),
+            json!({"maxLength": 3, "minLength": 1, "pattern": "^a", "allOf": [{"pattern": "b$"}]})
+        );
+    }
+
+    #[test]
+    fn test_merge_draft6_forms() {
+        assert_eq!(
+            merged(json!({
+                "allOf": [
+                    {"exclusiveMaximum": 10, "exclusiveMinimum": 0, "items": true},
+                    {"exclusiveMaximum": 5, "exclusiveMinimum": -1, "items": {"type": "string"}},
+                    true,
+                ],
+            })),
+            json!({"exclusiveMaximum": 5, "exclusiveMinimum": 0, "items": {"type": "string"}})
+        );
+        assert_eq!(
+            merged(json!({"allOf": [{"maximum": 5, "exclusiveMaximum": true}, {"exclusiveMaximum": 3}]})),
+            json!({"maximum": 5, "exclusiveMaximum": true, "allOf": [{"exclusiveMaximum": 3}]})
+        );
+        assert_eq!(merged(json!({"allOf": [{"items": false}, {"items": {}}]})), json!({"items": false}));
+    }
+
+    #[test]
+    fn test_merge_objects() {
+        assert_eq!(
+            merged(json!({
+                "allOf": [
+                    {"required": ["id"], "properties": {"id": {"type": "integer"}}, "additionalProperties": false},
+                    {
+                        "required": ["name", "id"],
+                        "properties": {"id": {"minimum": 1}, "name": {"type": "string"}},
+                        "additionalProperties": {"type": "string"},
+                    },
+                ],
+            })),
+            json!({
+                "required": ["id", "name"],
+                "properties": {"id": {"type": "integer", "minimum": 1}, "name": false},
+                "additionalProperties": false,
+            })
+        );
+        assert_eq!(
+            merged(json!({
+                "properties": {
+                    "pet": {"allOf": [{"properties": {"name": {"type": "string"}}}, {"properties": {"age": {}}}]},
+                },
+            })),
+            json!({"properties": {"pet": {"properties": {"age": {}, "name": {"type": "string"}}}}})
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_references() {
+        assert_eq!(
+            merged(json!({"allOf": [{"$ref": "#/definitions/Pet"}, {"required": ["id"]}]})),
+            json!({"required": ["id"], "allOf": [{"$ref": "#/definitions/Pet"}]})
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! JSON Schema data structures.
 
-use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+pub use indexmap::IndexMap;
 
 /// Represents a JSON Schema number (can be integer or float).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -11,7 +13,20 @@ pub enum SchemaNumber {
     Float(f64),
 }
 
+/// Represents either a number or a boolean, as in exclusiveMaximum and
+/// exclusiveMinimum: a flag that makes maximum or minimum exclusive in
+/// Draft 4, and the exclusive bound itself from Draft 6 on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum NumberOrBoolean {
+    Number(SchemaNumber),
+    Boolean(bool),
+}
+
 /// Represents either a schema or a boolean.
+///
+/// From Draft 6 on, every keyword that holds a schema also accepts true,
+/// which any value matches, and false, which no value matches.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum SchemaOrBoolean {
@@ -37,12 +52,13 @@ pub struct NamedSchema {
 /// Named schema or string array.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum NamedSchemaOrStringArray {
     Schema(NamedSchema),
     StringArray(Vec<String>),
 }
 
-/// JSON Schema structure (Draft 4 compatible).
+/// JSON Schema structure (Draft 4, with the keywords added by Drafts 6 and 7).
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Schema {
@@ -70,6 +86,18 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<serde_json::Value>,
 
+    /// Example values (Draft 6).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Vec<serde_json::Value>>,
+
+    /// Whether the value is read-only (Draft 7).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+
+    /// Whether the value is write-only (Draft 7).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_only: Option<bool>,
+
     /// Multiple of constraint for numbers.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub multiple_of: Option<SchemaNumber>,
@@ -78,17 +106,17 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub maximum: Option<SchemaNumber>,
 
-    /// Whether maximum is exclusive.
+    /// Whether maximum is exclusive (Draft 4), or the exclusive maximum (Draft 6).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub exclusive_maximum: Option<bool>,
+    pub exclusive_maximum: Option<NumberOrBoolean>,
 
     /// Minimum value for numbers.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub minimum: Option<SchemaNumber>,
 
-    /// Whether minimum is exclusive.
+    /// Whether minimum is exclusive (Draft 4), or the exclusive minimum (Draft 6).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub exclusive_minimum: Option<bool>,
+    pub exclusive_minimum: Option<NumberOrBoolean>,
 
     /// Maximum length for strings.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -102,6 +130,14 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pattern: Option<String>,
 
+    /// Encoding of string content, such as "base64" (Draft 7).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_encoding: Option<String>,
+
+    /// Media type of string content, such as "image/png" (Draft 7).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_media_type: Option<String>,
+
     /// Additional items schema for arrays.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub additional_items: Option<SchemaOrBoolean>,
@@ -122,6 +158,10 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unique_items: Option<bool>,
 
+    /// Schema that at least one array item must match (Draft 6).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<SchemaOrBoolean>,
+
     /// Maximum properties for objects.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_properties: Option<i64>,
@@ -138,26 +178,38 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub additional_properties: Option<SchemaOrBoolean>,
 
-    /// Property definitions.
+    /// Property definitions, in source order.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub definitions: Option<HashMap<String, Schema>>,
+    pub definitions: Option<IndexMap<String, SchemaOrBoolean>>,
+
+    /// Schema definitions under $defs, which replaces definitions in Draft 2019-09.
+    #[serde(rename = "$defs", skip_serializing_if = "Option::is_none")]
+    pub defs: Option<IndexMap<String, SchemaOrBoolean>>,
 
-    /// Properties schema.
+    /// Properties schema, in source order.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub properties: Option<HashMap<String, Schema>>,
+    pub properties: Option<IndexMap<String, SchemaOrBoolean>>,
 
-    /// Pattern properties schema.
+    /// Pattern properties schema, in source order.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub pattern_properties: Option<HashMap<String, Schema>>,
+    pub pattern_properties: Option<IndexMap<String, SchemaOrBoolean>>,
 
-    /// Dependencies.
+    /// Dependencies, in source order.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub dependencies: Option<HashMap<String, SchemaOrStringArray>>,
+    pub dependencies: Option<IndexMap<String, SchemaOrStringArray>>,
+
+    /// Schema that every property name must match (Draft 6).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property_names: Option<SchemaOrBoolean>,
 
     /// Enumeration of allowed values.
     #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
     pub enumeration: Option<Vec<serde_json::Value>>,
 
+    /// The only allowed value (Draft 6).
+    #[serde(rename = "const", skip_serializing_if = "Option::is_none")]
+    pub const_value: Option<serde_json::Value>,
+
     /// Type constraint.
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub type_value: Option<StringOrStringArray>,
@@ -168,37 +220,96 @@ pub struct Schema {
 
     /// All of these schemas must match.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub all_of: Option<Vec<Schema>>,
+    pub all_of: Option<Vec<SchemaOrBoolean>>,
 
     /// Any of these schemas must match.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub any_of: Option<Vec<Schema>>,
+    pub any_of: Option<Vec<SchemaOrBoolean>>,
 
     /// One of these schemas must match.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub one_of: Option<Vec<Schema>>,
+    pub one_of: Option<Vec<SchemaOrBoolean>>,
 
     /// Schema must not match.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub not: Option<Box<Schema>>,
+    pub not: Option<SchemaOrBoolean>,
+
+    /// Condition that selects between then and else (Draft 7).
+    #[serde(rename = "if", skip_serializing_if = "Option::is_none")]
+    pub if_schema: Option<SchemaOrBoolean>,
+
+    /// Schema that must match when if matches (Draft 7).
+    #[serde(rename = "then", skip_serializing_if = "Option::is_none")]
+    pub then_schema: Option<SchemaOrBoolean>,
+
+    /// Schema that must match when if does not match (Draft 7).
+    #[serde(rename = "else", skip_serializing_if = "Option::is_none")]
+    pub else_schema: Option<SchemaOrBoolean>,
 }
 
 /// Represents either a single schema or an array of schemas.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum SchemaOrSchemaArray {
-    Schema(Schema),
-    Array(Vec<Schema>),
+    Schema(SchemaOrBoolean),
+    Array(Vec<SchemaOrBoolean>),
 }
 
 /// Represents either a schema or an array of strings (for dependencies).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum SchemaOrStringArray {
-    Schema(Schema),
+    Schema(SchemaOrBoolean),
     StringArray(Vec<String>),
 }
 
+impl SchemaNumber {
+    /// Returns the number as a float.
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            SchemaNumber::Integer(value) => *value as f64,
+            SchemaNumber::Float(value) => *value,
+        }
+    }
+}
+
+impl SchemaOrBoolean {
+    /// Returns the schema, or None for true and false.
+    pub fn as_schema(&self) -> Option<&Schema> {
+        match self {
+            SchemaOrBoolean::Schema(schema) => Some(schema),
+            SchemaOrBoolean::Boolean(_) => None,
+        }
+    }
+
+    /// Returns the schema for modification, or None for true and false.
+    pub fn as_schema_mut(&mut self) -> Option<&mut Schema> {
+        match self {
+            SchemaOrBoolean::Schema(schema) => Some(schema),
+            SchemaOrBoolean::Boolean(_) => None,
+        }
+    }
+
+    /// Returns the schema, with true as the empty schema and false as
+    /// `{"not": {}}`, which match the same values.
+    pub fn to_schema(&self) -> Cow<'_, Schema> {
+        match self {
+            SchemaOrBoolean::Schema(schema) => Cow::Borrowed(schema),
+            SchemaOrBoolean::Boolean(true) => Cow::Owned(Schema::default()),
+            SchemaOrBoolean::Boolean(false) => Cow::Owned(Schema {
+                not: Some(SchemaOrBoolean::Schema(Box::default())),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl From<Schema> for SchemaOrBoolean {
+    fn from(schema: Schema) -> Self {
+        SchemaOrBoolean::Schema(Box::new(schema))
+    }
+}
+
 impl Schema {
     /// Creates a new empty schema.
     pub fn new() -> Self {
@@ -220,4 +331,92 @@ impl Schema {
             ..Default::default()
         }
     }
+
+    /// Returns the tighter of maximum and a numeric exclusiveMaximum, and
+    /// whether it is exclusive.
+    pub fn upper_bound(&self) -> Option<(f64, bool)> {
+        tightest_bound(&self.maximum, &self.exclusive_maximum, |x, y| x < y)
+    }
+
+    /// Returns the tighter of minimum and a numeric exclusiveMinimum, and
+    /// whether it is exclusive.
+    pub fn lower_bound(&self) -> Option<(f64, bool)> {
+        tightest_bound(&self.minimum, &self.exclusive_minimum, |x, y| x > y)
+    }
+}
+
+/// Returns the tighter of a bound and its exclusive keyword, where
+/// `tighter` tells whether a bound allows fewer values than another.
+fn tightest_bound(
+    bound: &Option<SchemaNumber>,
+    exclusive: &Option<NumberOrBoolean>,
+    tighter: fn(f64, f64) -> bool,
+) -> Option<(f64, bool)> {
+    let bound = bound.as_ref().map(SchemaNumber::as_f64);
+    match exclusive {
+        Some(NumberOrBoolean::Number(exclusive)) if bound.is_none_or(|bound| !tighter(bound, exclusive.as_f64())) => {
+            Some((exclusive.as_f64(), true))
+        }
+        Some(NumberOrBoolean::Boolean(exclusive)) => bound.map(|bound| (bound, *exclusive)),
+        _ => bound.map(|bound| (bound, false)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draft7_keywords_round_trip() {
+        let json = serde_json::json!({
+            "type": "object",
+            "if": {"properties": {"kind": {"const": "dog"}}},
+            "then": {"required": ["bark"]},
+            "else": {"required": ["meow"]},
+            "propertyNames": {"pattern": "^[a-z]+$"},
+            "properties": {
+                "id": {"type": "string", "readOnly": true},
+                "password": {"type": "string", "writeOnly": true},
+                "photo": {"type": "string", "contentEncoding": "base64", "contentMediaType": "image/png"},
+                "tags": {"type": "array", "contains": {"const": "pet"}},
+            },
+            "examples": [{"kind": "dog", "bark": true}],
+        });
+        let schema: Schema = serde_json::from_value(json.clone()).unwrap();
+        let condition = schema.if_schema.as_ref().unwrap().to_schema();
+        assert_eq!(condition.properties.as_ref().unwrap()["kind"].to_schema().const_value, Some("dog".into()));
+        assert_eq!(schema.properties.as_ref().unwrap()["id"].to_schema().read_only, Some(true));
+        assert_eq!(schema.examples.as_ref().map(Vec::len), Some(1));
+        assert_eq!(serde_json::to_value(&schema).unwrap(), json);
+    }
+
+    #[test]
+    fn test_draft6_forms_round_trip() {
+        let json = serde_json::json!({
+            "items": true,
+            "properties": {"id": {"exclusiveMinimum": 0, "maximum": 10, "exclusiveMaximum": 12.5}, "secret": false},
+            "allOf": [true, {"minimum": 1, "exclusiveMinimum": true}],
+        });
+        let schema: Schema = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(schema.items.as_deref(), Some(&SchemaOrSchemaArray::Schema(SchemaOrBoolean::Boolean(true))));
+        let id = schema.properties.as_ref().unwrap()["id"].to_schema();
+        assert_eq!(id.exclusive_minimum, Some(NumberOrBoolean::Number(SchemaNumber::Integer(0))));
+        assert_eq!(id.lower_bound(), Some((0.0, true)));
+        assert_eq!(id.upper_bound(), Some((10.0, false)));
+        let draft4 = schema.all_of.as_ref().unwrap()[1].to_schema();
+        assert_eq!(draft4.lower_bound(), Some((1.0, true)));
+        assert_eq!(serde_json::to_value(&schema).unwrap(), json);
+    }
+
+    #[test]
+    fn test_maps_keep_source_order() {
+        let yaml = "definitions:\n  Zebra: {}\n  Ant: {}\nproperties:\n  zeta: {}\n  alpha: {}\n  mid: {}\n";
+        let schema: Schema = serde_yaml::from_str(yaml).unwrap();
+        let names: Vec<&str> = schema.properties.as_ref().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(names, ["zeta", "alpha", "mid"]);
+        assert_eq!(serde_yaml::to_string(&schema).unwrap(), yaml);
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(json, r#"{"definitions":{"Zebra":{},"Ant":{}},"properties":{"zeta":{},"alpha":{},"mid":{}}}"#);
+        assert!(schema.to_string().find("zeta").unwrap() < schema.to_string().find("alpha").unwrap());
+    }
 }
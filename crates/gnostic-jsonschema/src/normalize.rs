@@ -0,0 +1,217 @@
+//! Normalization of schemas.
+//!
+//! Many schemas can be written in more than one way: `"type": ["string"]`
+//! and `"type": "string"` mean the same, as do a schema with `"minItems": 0`
+//! and one without it. Normalizing rewrites such forms into one canonical
+//! form so that equivalent schemas compare equal.
+
+use crate::models::{NumberOrBoolean, Schema, SchemaOrBoolean, SchemaOrSchemaArray, StringOrStringArray};
+use crate::operations::subschemas_mut;
+
+impl Schema {
+    /// Rewrites the schema and every schema nested in it into canonical form.
+    ///
+    /// - single-element type arrays become strings, and other type arrays
+    ///   are sorted without duplicates;
+    /// - keywords that do not constrain anything, such as `"minLength": 0`,
+    ///   `"additionalProperties": true` or `"items": {}`, are removed, as
+    ///   are Draft 4 exclusive flags without the bound they modify;
+    /// - enum values and required properties are sorted without duplicates;
+    /// - allOf branches that are only an allOf are replaced by their
+    ///   branches, duplicate and true branches are removed, and a schema that is only
+    ///   an allOf of one branch is replaced by that branch.
+    pub fn normalize(&mut self) {
+        for subschema in subschemas_mut(self) {
+            subschema.normalize();
+        }
+        self.normalize_type();
+        self.drop_no_op_keywords();
+        if let Some(values) = &mut self.enumeration {
+            values.sort_by_cached_key(|value| value.to_string());
+            values.dedup();
+        }
+        if let Some(required) = &mut self.required {
+            required.sort();
+            required.dedup();
+        }
+        self.collapse_all_of();
+    }
+
+    /// Returns a normalized copy of the schema.
+    pub fn normalized(&self) -> Schema {
+        let mut schema = self.clone();
+        schema.normalize();
+        schema
+    }
+
+    fn normalize_type(&mut self) {
+        if let Some(StringOrStringArray::Array(names)) = &mut self.type_value {
+            names.sort();
+            names.dedup();
+            if names.len() == 1 {
+                self.type_value = names.pop().map(StringOrStringArray::String);
+            }
+        }
+    }
+
+    fn drop_no_op_keywords(&mut self) {
+        for minimum in [&mut self.min_length, &mut self.min_items, &mut self.min_properties] {
+            if *minimum == Some(0) {
+                *minimum = None;
+            }
+        }
+        for flag in [&mut self.unique_items, &mut self.read_only, &mut self.write_only] {
+            if *flag == Some(false) {
+                *flag = None;
+            }
+        }
+        // Draft 4 exclusive flags only modify maximum and minimum.
+        if let Some(NumberOrBoolean::Boolean(exclusive)) = self.exclusive_maximum {
+            if !exclusive || self.maximum.is_none() {
+                self.exclusive_maximum = None;
+            }
+        }
+        if let Some(NumberOrBoolean::Boolean(exclusive)) = self.exclusive_minimum {
+            if !exclusive || self.minimum.is_none() {
+                self.exclusive_minimum = None;
+            }
+        }
+        for additional in [&mut self.additional_items, &mut self.additional_properties] {
+            if additional.as_ref().is_some_and(accepts_all) {
+                *additional = None;
+            }
+        }
+        if let Some(SchemaOrSchemaArray::Schema(items)) = self.items.as_deref() {
+            if accepts_all(items) {
+                self.items = None;
+            }
+        }
+        // additionalItems only applies next to a list of item schemas.
+        if !matches!(self.items.as_deref(), Some(SchemaOrSchemaArray::Array(_))) {
+            self.additional_items = None;
+        }
+        if self.required.as_ref().is_some_and(Vec::is_empty) {
+            self.required = None;
+        }
+        for map in [
+            &mut self.definitions,
+            &mut self.defs,
+            &mut self.properties,
+            &mut self.pattern_properties,
+        ] {
+            if map.as_ref().is_some_and(|map| map.is_empty()) {
+                *map = None;
+            }
+        }
+        if self.dependencies.as_ref().is_some_and(|dependencies| dependencies.is_empty()) {
+            self.dependencies = None;
+        }
+    }
+
+    fn collapse_all_of(&mut self) {
+        let Some(branches) = self.all_of.take() else {
+            return;
+        };
+        let mut collapsed: Vec<SchemaOrBoolean> = Vec::new();
+        for branch in branches {
+            let only_all_of =
+                branch.as_schema().filter(|schema| Schema { all_of: None, ..(*schema).clone() } == Schema::default());
+            let nested = match only_all_of.and_then(|schema| schema.all_of.clone()) {
+                Some(nested) => nested,
+                None => vec![branch],
+            };
+            for branch in nested {
+                if !accepts_all(&branch) && !collapsed.contains(&branch) {
+                    collapsed.push(branch);
+                }
+            }
+        }
+        match collapsed.len() {
+            0 => {}
+            1 if *self == Schema::default() => *self = collapsed.remove(0).to_schema().into_owned(),
+            _ => self.all_of = Some(collapsed),
+        }
+    }
+}
+
+/// Returns true for true and the empty schema, which accept every value.
+fn accepts_all(schema: &SchemaOrBoolean) -> bool {
+    match schema {
+        SchemaOrBoolean::Boolean(value) => *value,
+        SchemaOrBoolean::Schema(schema) => **schema == Schema::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn normalized(value: serde_json::Value) -> serde_json::Value {
+        let schema: Schema = serde_json::from_value(value).unwrap();
+        serde_json::to_value(schema.normalized()).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_keywords() {
+        assert_eq!(
+            normalized(json!({
+                "type": ["string"],
+                "minLength": 0,
+                "exclusiveMaximum": true,
+                "enum": ["b", "a", "b"],
+                "readOnly": false,
+            })),
+            json!({"type": "string", "enum": ["a", "b"]})
+        );
+        assert_eq!(
+            normalized(json!({
+                "type": ["object", "null", "object"],
+                "required": ["b", "a"],
+                "properties": {"a": {"type": ["integer"], "minimum": 0, "exclusiveMinimum": false}},
+                "additionalProperties": true,
+                "patternProperties": {},
+            })),
+            json!({
+                "type": ["null", "object"],
+                "required": ["a", "b"],
+                "properties": {"a": {"type": "integer", "minimum": 0}},
+            })
+        );
+        assert_eq!(
+            normalized(json!({"items": {}, "additionalItems": false, "uniqueItems": false, "minItems": 0})),
+            json!({})
+        );
+        assert_eq!(
+            normalized(json!({"items": [{}], "additionalItems": {}})),
+            json!({"items": [{}]})
+        );
+        assert_eq!(
+            normalized(json!({"items": true, "exclusiveMaximum": 5, "exclusiveMinimum": true, "not": false})),
+            json!({"exclusiveMaximum": 5, "not": false})
+        );
+    }
+
+    #[test]
+    fn test_normalize_all_of() {
+        assert_eq!(
+            normalized(json!({
+                "type": "object",
+                "allOf": [{"allOf": [{"required": ["a"]}, {"required": ["b"]}]}, {"required": ["a"]}, {"minItems": 0}],
+            })),
+            json!({"type": "object", "allOf": [{"required": ["a"]}, {"required": ["b"]}]})
+        );
+        assert_eq!(
+            normalized(json!({"allOf": [{"allOf": [{"type": ["string"]}]}]})),
+            json!({"type": "string"})
+        );
+        assert_eq!(
+            normalized(json!({"allOf": [true, {}, false, false]})),
+            json!({"not": {}})
+        );
+        let a: Schema = serde_json::from_value(json!({"type": ["string"], "enum": ["y", "x"]})).unwrap();
+        let b: Schema = serde_json::from_value(json!({"allOf": [{"enum": ["x", "y"], "type": "string"}]})).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a.normalized(), b.normalized());
+    }
+}
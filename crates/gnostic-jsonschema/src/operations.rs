@@ -1,6 +1,7 @@
 //! JSON Schema operations.
 
-use crate::models::Schema;
+use crate::models::{Schema, SchemaOrSchemaArray, SchemaOrStringArray};
+use gnostic_compiler::pointer_for_segments;
 
 /// Checks if a schema is empty (has no constraints).
 pub fn is_empty(schema: &Schema) -> bool {
@@ -29,3 +30,91 @@ pub fn type_name(schema: &Schema) -> Option<String> {
         _ => None,
     }
 }
+
+/// Lists the schemas directly nested in `$schema` with their JSON pointers
+/// relative to it, as shared references or, given `mut`, mutable ones.
+/// Boolean schemas nest nothing and are left out.
+macro_rules! nested_schemas {
+    ($schema:ident, $iter:ident, $as_schema:ident, $($mut:ident)?) => {{
+        let mut result = Vec::new();
+        for (keyword, map) in [
+            ("definitions", &$($mut)? $schema.definitions),
+            ("$defs", &$($mut)? $schema.defs),
+            ("properties", &$($mut)? $schema.properties),
+            ("patternProperties", &$($mut)? $schema.pattern_properties),
+        ] {
+            for (name, nested) in map.$iter().flat_map(|map| map.$iter()) {
+                result.push((pointer_for_segments(&[keyword, name.as_str()]), nested));
+            }
+        }
+        for (name, dependency) in $schema.dependencies.$iter().flat_map(|map| map.$iter()) {
+            if let SchemaOrStringArray::Schema(nested) = dependency {
+                result.push((pointer_for_segments(&["dependencies", name.as_str()]), nested));
+            }
+        }
+        for (keyword, additional) in [
+            ("additionalItems", &$($mut)? $schema.additional_items),
+            ("additionalProperties", &$($mut)? $schema.additional_properties),
+        ] {
+            if let Some(nested) = additional {
+                result.push((pointer_for_segments(&[keyword]), nested));
+            }
+        }
+        match &$($mut)? $schema.items {
+            Some(items) => match &$($mut)? **items {
+                SchemaOrSchemaArray::Schema(nested) => result.push(("/items".to_string(), nested)),
+                SchemaOrSchemaArray::Array(list) => {
+                    for (i, nested) in list.$iter().enumerate() {
+                        result.push((format!("/items/{}", i), nested));
+                    }
+                }
+            },
+            None => {}
+        }
+        for (keyword, list) in [
+            ("allOf", &$($mut)? $schema.all_of),
+            ("anyOf", &$($mut)? $schema.any_of),
+            ("oneOf", &$($mut)? $schema.one_of),
+        ] {
+            for (i, nested) in list.$iter().flatten().enumerate() {
+                result.push((format!("/{}/{}", keyword, i), nested));
+            }
+        }
+        for (keyword, nested) in [
+            ("not", &$($mut)? $schema.not),
+            ("contains", &$($mut)? $schema.contains),
+            ("propertyNames", &$($mut)? $schema.property_names),
+            ("if", &$($mut)? $schema.if_schema),
+            ("then", &$($mut)? $schema.then_schema),
+            ("else", &$($mut)? $schema.else_schema),
+        ] {
+            if let Some(nested) = nested {
+                result.push((pointer_for_segments(&[keyword]), nested));
+            }
+        }
+        result.into_iter().filter_map(|(pointer, nested)| Some((pointer, nested.$as_schema()?))).collect()
+    }};
+}
+
+/// Returns the schemas directly nested in a schema, in every keyword that
+/// holds schemas, with their JSON pointers relative to it such as
+/// "/properties/name" or "/allOf/0".
+pub fn subschemas_with_pointers(schema: &Schema) -> Vec<(String, &Schema)> {
+    nested_schemas!(schema, iter, as_schema,)
+}
+
+/// Returns the schemas directly nested in a schema with their JSON pointers,
+/// for modification.
+pub fn subschemas_with_pointers_mut(schema: &mut Schema) -> Vec<(String, &mut Schema)> {
+    nested_schemas!(schema, iter_mut, as_schema_mut, mut)
+}
+
+/// Returns the schemas directly nested in a schema, in every keyword that holds schemas.
+pub fn subschemas(schema: &Schema) -> Vec<&Schema> {
+    subschemas_with_pointers(schema).into_iter().map(|(_, nested)| nested).collect()
+}
+
+/// Returns the schemas directly nested in a schema, for modification.
+pub fn subschemas_mut(schema: &mut Schema) -> Vec<&mut Schema> {
+    subschemas_with_pointers_mut(schema).into_iter().map(|(_, nested)| nested).collect()
+}
@@ -1,6 +1,12 @@
 //! JSON Schema reader.
 
-use crate::models::Schema;
+use crate::models::{
+    IndexMap, NumberOrBoolean, Schema, SchemaNumber, SchemaOrBoolean, SchemaOrSchemaArray, SchemaOrStringArray,
+    StringOrStringArray,
+};
+use gnostic_compiler::{check_depth, untag, yaml_to_json, CompilerError, Context, ErrorCode, ErrorGroup};
+use serde_yaml::Value as Yaml;
+use std::sync::Arc;
 
 /// Parses a JSON Schema from a JSON string.
 pub fn read_schema_from_json(json: &str) -> Result<Schema, serde_json::Error> {
@@ -24,3 +30,282 @@ pub fn read_schema(bytes: &[u8]) -> Result<Schema, String> {
     // Fall back to YAML
     read_schema_from_yaml(content).map_err(|e| e.to_string())
 }
+
+/// Parses a JSON Schema from a YAML node, such as a schema embedded in a
+/// larger document, without serializing it again.
+pub fn read_schema_from_yaml_node(node: &Yaml) -> Result<Schema, ErrorGroup> {
+    read_schema_from_yaml_node_with_context(node, &Arc::new(Context::root("$")))
+}
+
+/// Parses a JSON Schema from a YAML node found at `context`, which gives the
+/// paths of the errors reported for the node. Unknown keys are ignored.
+pub fn read_schema_from_yaml_node_with_context(node: &Yaml, context: &Arc<Context>) -> Result<Schema, ErrorGroup> {
+    let mut reader = NodeReader { errors: Vec::new() };
+    let schema = reader.schema(node, context);
+    match ErrorGroup::from_errors(reader.errors) {
+        Some(errors) => Err(errors),
+        None => Ok(schema),
+    }
+}
+
+struct NodeReader {
+    errors: Vec<CompilerError>,
+}
+
+impl NodeReader {
+    fn invalid(&mut self, context: &Context, expected: &str) {
+        self.errors
+            .push(CompilerError::new_with_code(context, ErrorCode::InvalidType, format!("expected {}", expected)));
+    }
+
+    fn schema(&mut self, node: &Yaml, context: &Arc<Context>) -> Schema {
+        let mut schema = Schema::default();
+        if let Err(error) = check_depth(context) {
+            self.errors.push(error);
+            return schema;
+        }
+        let Yaml::Mapping(map) = untag(node) else {
+            self.invalid(context, "a schema");
+            return schema;
+        };
+        for (key, value) in map {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            let context = &Arc::new(context.child(key));
+            match key {
+                "$schema" => schema.schema = self.string(value, context),
+                "id" => schema.id = self.string(value, context),
+                "$ref" => schema.reference = self.string(value, context),
+                "title" => schema.title = self.string(value, context),
+                "description" => schema.description = self.string(value, context),
+                "default" => schema.default = Some(yaml_to_json(value)),
+                "examples" => schema.examples = self.sequence(value, context, |_, item, _| Some(yaml_to_json(item))),
+                "readOnly" => schema.read_only = self.boolean(value, context),
+                "writeOnly" => schema.write_only = self.boolean(value, context),
+                "multipleOf" => schema.multiple_of = self.number(value, context),
+                "maximum" => schema.maximum = self.number(value, context),
+                "exclusiveMaximum" => schema.exclusive_maximum = self.number_or_boolean(value, context),
+                "minimum" => schema.minimum = self.number(value, context),
+                "exclusiveMinimum" => schema.exclusive_minimum = self.number_or_boolean(value, context),
+                "maxLength" => schema.max_length = self.integer(value, context),
+                "minLength" => schema.min_length = self.integer(value, context),
+                "pattern" => schema.pattern = self.string(value, context),
+                "contentEncoding" => schema.content_encoding = self.string(value, context),
+                "contentMediaType" => schema.content_media_type = self.string(value, context),
+                "additionalItems" => schema.additional_items = self.schema_or_boolean(value, context),
+                "items" => {
+                    schema.items = match untag(value) {
+                        Yaml::Sequence(_) => self.schemas(value, context).map(SchemaOrSchemaArray::Array),
+                        _ => self.schema_or_boolean(value, context).map(SchemaOrSchemaArray::Schema),
+                    }
+                    .map(Box::new)
+                }
+                "maxItems" => schema.max_items = self.integer(value, context),
+                "minItems" => schema.min_items = self.integer(value, context),
+                "uniqueItems" => schema.unique_items = self.boolean(value, context),
+                "contains" => schema.contains = self.schema_or_boolean(value, context),
+                "maxProperties" => schema.max_properties = self.integer(value, context),
+                "minProperties" => schema.min_properties = self.integer(value, context),
+                "required" => schema.required = self.strings(value, context),
+                "additionalProperties" => schema.additional_properties = self.schema_or_boolean(value, context),
+                "definitions" => schema.definitions = self.schema_map(value, context),
+                "$defs" => schema.defs = self.schema_map(value, context),
+                "properties" => schema.properties = self.schema_map(value, context),
+                "patternProperties" => schema.pattern_properties = self.schema_map(value, context),
+                "dependencies" => {
+                    schema.dependencies = self.map(value, context, |reader, node, context| match untag(node) {
+                        Yaml::Sequence(_) => reader.strings(node, context).map(SchemaOrStringArray::StringArray),
+                        _ => reader.schema_or_boolean(node, context).map(SchemaOrStringArray::Schema),
+                    })
+                }
+                "propertyNames" => schema.property_names = self.schema_or_boolean(value, context),
+                "enum" => schema.enumeration = self.sequence(value, context, |_, item, _| Some(yaml_to_json(item))),
+                "const" => schema.const_value = Some(yaml_to_json(value)),
+                "type" => {
+                    schema.type_value = match untag(value) {
+                        Yaml::Sequence(_) => self.strings(value, context).map(StringOrStringArray::Array),
+                        _ => self.string(value, context).map(StringOrStringArray::String),
+                    }
+                }
+                "format" => schema.format = self.string(value, context),
+                "allOf" => schema.all_of = self.schemas(value, context),
+                "anyOf" => schema.any_of = self.schemas(value, context),
+                "oneOf" => schema.one_of = self.schemas(value, context),
+                "not" => schema.not = self.schema_or_boolean(value, context),
+                "if" => schema.if_schema = self.schema_or_boolean(value, context),
+                "then" => schema.then_schema = self.schema_or_boolean(value, context),
+                "else" => schema.else_schema = self.schema_or_boolean(value, context),
+                _ => {}
+            }
+        }
+        schema
+    }
+
+    fn string(&mut self, node: &Yaml, context: &Context) -> Option<String> {
+        let value = untag(node).as_str().map(str::to_string);
+        if value.is_none() {
+            self.invalid(context, "a string");
+        }
+        value
+    }
+
+    fn boolean(&mut self, node: &Yaml, context: &Context) -> Option<bool> {
+        let value = untag(node).as_bool();
+        if value.is_none() {
+            self.invalid(context, "a boolean");
+        }
+        value
+    }
+
+    fn integer(&mut self, node: &Yaml, context: &Context) -> Option<i64> {
+        let value = untag(node).as_i64();
+        if value.is_none() {
+            self.invalid(context, "an integer");
+        }
+        value
+    }
+
+    fn number(&mut self, node: &Yaml, context: &Context) -> Option<SchemaNumber> {
+        let node = untag(node);
+        let value = match node.as_i64() {
+            Some(integer) => Some(SchemaNumber::Integer(integer)),
+            None => node.as_f64().map(SchemaNumber::Float),
+        };
+        if value.is_none() {
+            self.invalid(context, "a number");
+        }
+        value
+    }
+
+    /// Reads a Draft 4 boolean flag or a Draft 6 numeric bound.
+    fn number_or_boolean(&mut self, node: &Yaml, context: &Context) -> Option<NumberOrBoolean> {
+        match untag(node) {
+            Yaml::Bool(value) => Some(NumberOrBoolean::Boolean(*value)),
+            _ => self.number(node, context).map(NumberOrBoolean::Number),
+        }
+    }
+
+    fn schema_or_boolean(&mut self, node: &Yaml, context: &Arc<Context>) -> Option<SchemaOrBoolean> {
+        match untag(node) {
+            Yaml::Bool(value) => Some(SchemaOrBoolean::Boolean(*value)),
+            _ => Some(SchemaOrBoolean::Schema(Box::new(self.schema(node, context)))),
+        }
+    }
+
+    fn strings(&mut self, node: &Yaml, context: &Arc<Context>) -> Option<Vec<String>> {
+        self.sequence(node, context, |reader, item, context| reader.string(item, context))
+    }
+
+    fn schemas(&mut self, node: &Yaml, context: &Arc<Context>) -> Option<Vec<SchemaOrBoolean>> {
+        self.sequence(node, context, |reader, item, context| reader.schema_or_boolean(item, context))
+    }
+
+    fn schema_map(&mut self, node: &Yaml, context: &Arc<Context>) -> Option<IndexMap<String, SchemaOrBoolean>> {
+        self.map(node, context, |reader, value, context| reader.schema_or_boolean(value, context))
+    }
+
+    /// Reads the items of a sequence, which are named `key[index]` in errors.
+    fn sequence<T>(
+        &mut self,
+        node: &Yaml,
+        context: &Arc<Context>,
+        mut read: impl FnMut(&mut Self, &Yaml, &Arc<Context>) -> Option<T>,
+    ) -> Option<Vec<T>> {
+        let Yaml::Sequence(items) = untag(node) else {
+            self.invalid(context, "a sequence");
+            return None;
+        };
+        let parent = context.parent.clone();
+        let items = items.iter().enumerate().filter_map(|(i, item)| {
+            let name = format!("{}[{}]", context.name, i);
+            let context = Arc::new(Context::new(name, None, None, parent.clone()));
+            read(self, item, &context)
+        });
+        Some(items.collect())
+    }
+
+    fn map<T>(
+        &mut self,
+        node: &Yaml,
+        context: &Arc<Context>,
+        mut read: impl FnMut(&mut Self, &Yaml, &Arc<Context>) -> Option<T>,
+    ) -> Option<IndexMap<String, T>> {
+        let Yaml::Mapping(map) = untag(node) else {
+            self.invalid(context, "a mapping");
+            return None;
+        };
+        let mut result = IndexMap::new();
+        for (key, value) in map {
+            let Some(key) = key.as_str() else {
+                self.invalid(context, "string keys");
+                continue;
+            };
+            if let Some(value) = read(self, value, &Arc::new(context.child(key))) {
+                result.insert(key.to_string(), value);
+            }
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_schema_from_yaml_node() {
+        let document: Yaml = serde_yaml::from_str(
+            r#"
+components:
+  schemas:
+    Pet:
+      $schema: http://json-schema.org/draft-07/schema#
+      type: [object, "null"]
+      required: [id]
+      properties:
+        id: {type: integer, minimum: 1, exclusiveMinimum: true, exclusiveMaximum: 100}
+        tags: {items: {type: string}, uniqueItems: true}
+        pair: {items: [{const: 1}, {enum: [a, 2.5]}]}
+      additionalProperties: false
+      patternProperties:
+        "^x-": {}
+        "^y-": false
+      dependencies:
+        id: [tags]
+        tags: {required: [id]}
+      allOf: [{if: {maxProperties: 2}, then: {not: {}}, else: {contains: {}}}, true]
+      default: {id: 1}
+      examples: [{id: 2}]
+      x-internal: true
+"#,
+        )
+        .unwrap();
+        let node = &document["components"]["schemas"]["Pet"];
+        let expected: Schema = serde_yaml::from_value(node.clone()).unwrap();
+        assert_eq!(read_schema_from_yaml_node(node).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_read_schema_from_yaml_node_errors() {
+        let node: Yaml = serde_yaml::from_str(
+            "properties:\n  id: {type: 3, minimum: low}\nallOf: [{required: [1]}, x]\nitems: 7\n",
+        )
+        .unwrap();
+        let context = Arc::new(Arc::new(Context::root("$")).child("definitions"));
+        let context = Arc::new(context.child("Pet"));
+        let errors = read_schema_from_yaml_node_with_context(&node, &context).unwrap_err();
+        let messages: Vec<String> = errors.errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "$.definitions.Pet.properties.id.type expected a string",
+                "$.definitions.Pet.properties.id.minimum expected a number",
+                "$.definitions.Pet.allOf[0].required[0] expected a string",
+                "$.definitions.Pet.allOf[1] expected a schema",
+                "$.definitions.Pet.items expected a schema",
+            ]
+        );
+        assert!(errors.errors.iter().all(|error| error.code() == ErrorCode::InvalidType));
+    }
+}
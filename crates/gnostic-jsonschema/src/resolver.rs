@@ -0,0 +1,391 @@
+//! Resolution and bundling of $ref values in schemas.
+//!
+//! References are looked up in the schema containing them
+//! ("#/definitions/Pet", "#/$defs/Pet") or in other files ("pet.json#/Pet"),
+//! which are read with the gnostic-compiler reader relative to the file that
+//! contains the reference. [`resolve_schema`] replaces references by their
+//! targets, while [`bundle_schema`] copies the targets of external references
+//! into the definitions of the schema so that it no longer depends on other
+//! files.
+//!
+//! Files are read as a [`FetchPolicy`] allows, which by default fetches no
+//! remote documents. References to the meta-schemas of the supported drafts
+//! and their vocabularies are answered from the copies in [`crate::base`].
+
+use crate::base::{base_schema_string_for_draft, vocabulary_schema_strings, Draft};
+use crate::models::{IndexMap, Schema};
+use crate::operations::subschemas_mut;
+use gnostic_compiler::{
+    escape_json_pointer_segment, filename_for_ref, node_for_pointer, pointer_segments, read_shared_info_for_file,
+    CompilerError, ErrorCode, FetchPolicy, Result, Severity,
+};
+use serde_yaml::Value as Yaml;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Follows references of a schema, reading the files they point into once.
+pub struct Resolver {
+    /// The file the schema was read from; "" for schemas read from memory.
+    base: String,
+    /// Documents by file name, including the schema itself under `base`.
+    documents: HashMap<String, Arc<Yaml>>,
+    /// Which files may be read.
+    policy: FetchPolicy,
+}
+
+impl Resolver {
+    /// Creates a resolver for `root`, which was read from the file or URL `base`.
+    pub fn new(root: &Schema, base: impl Into<String>) -> Result<Self> {
+        let base = base.into();
+        let root = serde_yaml::to_value(root).map_err(CompilerError::from)?;
+        Ok(Resolver {
+            documents: HashMap::from([(base.clone(), Arc::new(root))]),
+            base,
+            policy: FetchPolicy::default(),
+        })
+    }
+
+    /// Returns the resolver with the files it reads limited by `policy`.
+    pub fn with_fetch_policy(mut self, policy: FetchPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the file and JSON pointer of the target of `reference`, which
+    /// appears in `file`, reading the file if needed and the policy allows it.
+    pub fn locate(&mut self, file: &str, reference: &str) -> Result<(String, String)> {
+        let (target_file, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+        let target_file = if target_file.is_empty() {
+            file.to_string()
+        } else {
+            filename_for_ref(file, target_file)
+        };
+        if !self.documents.contains_key(&target_file) {
+            let document = match metaschema(&target_file) {
+                Some(json) => {
+                    let document = serde_yaml::from_str(json).map_err(CompilerError::from)?;
+                    Arc::new(document)
+                }
+                None => {
+                    self.policy.check(&target_file).map_err(|message| CompilerError::Unlocated {
+                        path: reference.to_string(),
+                        message,
+                        severity: Severity::Error,
+                        code: ErrorCode::FetchDenied,
+                    })?;
+                    read_shared_info_for_file(&target_file)?
+                }
+            };
+            self.documents.insert(target_file.clone(), document);
+        }
+        Ok((target_file, pointer.to_string()))
+    }
+
+    /// Returns the schema at `pointer` in `file`, without following its reference.
+    pub fn schema_at(&self, file: &str, pointer: &str) -> Result<Schema> {
+        let node = self
+            .documents
+            .get(file)
+            .and_then(|document| node_for_pointer(document, pointer))
+            .ok_or_else(|| unresolved(&format!("{}#{}", file, pointer)))?;
+        serde_yaml::from_value(node.clone()).map_err(CompilerError::from)
+    }
+
+    /// Follows `reference`, which appears in `file`, and the references of
+    /// its targets to a schema that is not a reference. Returns the file
+    /// and pointer of that schema along with the schema.
+    pub fn resolve(&mut self, file: &str, reference: &str) -> Result<(String, String, Schema)> {
+        let mut visited = HashSet::new();
+        let (mut file, mut pointer) = self.locate(file, reference)?;
+        loop {
+            if !visited.insert(format!("{}#{}", file, pointer)) {
+                return Err(circular(reference, &file, &pointer));
+            }
+            let schema = self.schema_at(&file, &pointer)?;
+            let Some(next) = &schema.reference else {
+                return Ok((file, pointer, schema));
+            };
+            (file, pointer) = self.locate(&file, next)?;
+        }
+    }
+}
+
+/// Returns a copy of `schema` with every $ref replaced by its target.
+///
+/// `base` is the file or URL the schema was read from, used to locate
+/// relative external references. Recursive schemas cannot be expanded and
+/// fail with a circular reference error.
+pub fn resolve_schema(schema: &Schema, base: &str) -> Result<Schema> {
+    resolve_schema_with_policy(schema, base, &FetchPolicy::default())
+}
+
+/// Returns a copy of `schema` with every $ref replaced by its target,
+/// reading only the files that `policy` allows.
+pub fn resolve_schema_with_policy(schema: &Schema, base: &str, policy: &FetchPolicy) -> Result<Schema> {
+    let mut resolver = Resolver::new(schema, base)?.with_fetch_policy(policy.clone());
+    let mut resolved = schema.clone();
+    inline(&mut resolver, &mut resolved, base, &mut Vec::new())?;
+    Ok(resolved)
+}
+
+fn inline(resolver: &mut Resolver, schema: &mut Schema, file: &str, expanding: &mut Vec<String>) -> Result<()> {
+    if let Some(reference) = schema.reference.clone() {
+        let (target_file, pointer, mut target) = resolver.resolve(file, &reference)?;
+        let key = format!("{}#{}", target_file, pointer);
+        if expanding.contains(&key) {
+            return Err(circular(&reference, &target_file, &pointer));
+        }
+        expanding.push(key);
+        inline(resolver, &mut target, &target_file, expanding)?;
+        expanding.pop();
+        *schema = target;
+        return Ok(());
+    }
+    for subschema in subschemas_mut(schema) {
+        inline(resolver, subschema, file, expanding)?;
+    }
+    Ok(())
+}
+
+/// Returns a copy of `schema` that does not depend on other files.
+///
+/// The target of each external reference is added to the definitions of the
+/// schema ($defs if the schema uses them) under the last key of its pointer,
+/// or the name of its file, and the reference is changed to point there.
+/// Internal references are left as they are.
+pub fn bundle_schema(schema: &Schema, base: &str) -> Result<Schema> {
+    bundle_schema_with_policy(schema, base, &FetchPolicy::default())
+}
+
+/// Returns a copy of `schema` that does not depend on other files, reading
+/// only the files that `policy` allows.
+pub fn bundle_schema_with_policy(schema: &Schema, base: &str, policy: &FetchPolicy) -> Result<Schema> {
+    let mut bundler = Bundler {
+        resolver: Resolver::new(schema, base)?.with_fetch_policy(policy.clone()),
+        section: if schema.defs.is_some() && schema.definitions.is_none() {
+            "$defs"
+        } else {
+            "definitions"
+        },
+        names: HashMap::new(),
+        taken: schema
+            .definitions
+            .iter()
+            .chain(&schema.defs)
+            .flat_map(|definitions| definitions.keys().cloned())
+            .collect(),
+        bundled: Vec::new(),
+    };
+    let mut bundled = schema.clone();
+    bundler.bundle(&mut bundled, base)?;
+    if !bundler.bundled.is_empty() {
+        let definitions = if bundler.section == "$defs" {
+            &mut bundled.defs
+        } else {
+            &mut bundled.definitions
+        };
+        let bundled = bundler.bundled.into_iter().map(|(name, schema)| (name, schema.into()));
+        definitions.get_or_insert_with(IndexMap::new).extend(bundled);
+    }
+    Ok(bundled)
+}
+
+struct Bundler {
+    resolver: Resolver,
+    /// The key of the root schema that bundled schemas are added to.
+    section: &'static str,
+    /// Names of bundled schemas by "file#pointer" of their targets.
+    names: HashMap<String, String>,
+    /// Names in use in the definitions of the root schema.
+    taken: HashSet<String>,
+    bundled: Vec<(String, Schema)>,
+}
+
+impl Bundler {
+    fn bundle(&mut self, schema: &mut Schema, file: &str) -> Result<()> {
+        if let Some(reference) = schema.reference.clone() {
+            let (target_file, pointer) = self.resolver.locate(file, &reference)?;
+            if target_file == self.resolver.base {
+                schema.reference = Some(format!("#{}", pointer));
+                return Ok(());
+            }
+            let key = format!("{}#{}", target_file, pointer);
+            let name = match self.names.get(&key) {
+                Some(name) => name.clone(),
+                None => {
+                    let name = self.unique_name(&target_file, &pointer);
+                    // Named before bundling so that cycles refer back to it.
+                    self.names.insert(key, name.clone());
+                    let mut target = self.resolver.schema_at(&target_file, &pointer)?;
+                    self.bundle(&mut target, &target_file)?;
+                    self.bundled.push((name.clone(), target));
+                    name
+                }
+            };
+            schema.reference = Some(format!("#/{}/{}", self.section, escape_json_pointer_segment(&name)));
+            return Ok(());
+        }
+        for subschema in subschemas_mut(schema) {
+            self.bundle(subschema, file)?;
+        }
+        Ok(())
+    }
+
+    /// Returns an unused name for the schema at `pointer` in `file`.
+    fn unique_name(&mut self, file: &str, pointer: &str) -> String {
+        let name = pointer_segments(pointer)
+            .pop()
+            .or_else(|| Path::new(file).file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "schema".to_string());
+        let mut unique = name.clone();
+        let mut suffix = 2;
+        while !self.taken.insert(unique.clone()) {
+            unique = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+        unique
+    }
+}
+
+/// Returns the meta-schema or vocabulary meta-schema whose URI is `uri`.
+fn metaschema(uri: &str) -> Option<&'static str> {
+    if let Some(draft) = Draft::from_uri(uri) {
+        return Some(base_schema_string_for_draft(draft));
+    }
+    let uri = uri.trim_end_matches('#');
+    Draft::ALL
+        .into_iter()
+        .flat_map(vocabulary_schema_strings)
+        .find(|(id, _)| id.trim_end_matches('#') == uri)
+        .map(|(_, json)| *json)
+}
+
+fn unresolved(reference: &str) -> CompilerError {
+    CompilerError::Unlocated {
+        path: reference.to_string(),
+        message: format!("could not resolve {}", reference),
+        severity: Severity::Error,
+        code: ErrorCode::UnresolvedReference,
+    }
+}
+
+fn circular(reference: &str, file: &str, pointer: &str) -> CompilerError {
+    CompilerError::Unlocated {
+        path: reference.to_string(),
+        message: format!("is a circular reference through {}#{}", file, pointer),
+        severity: Severity::Error,
+        code: ErrorCode::CircularReference,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(value: serde_json::Value) -> Schema {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn write(dir: &Path, name: &str, value: serde_json::Value) {
+        std::fs::write(dir.join(name), value.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_internal_references() {
+        let root = schema(json!({
+            "$defs": {"id": {"type": "integer"}, "key": {"$ref": "#/$defs/id"}},
+            "definitions": {"name": {"type": "string"}},
+            "properties": {"id": {"$ref": "#/$defs/key"}, "name": {"$ref": "#/definitions/name"}},
+        }));
+        let resolved = resolve_schema(&root, "").unwrap();
+        let properties = resolved.properties.unwrap();
+        assert_eq!(properties["id"], schema(json!({"type": "integer"})).into());
+        assert_eq!(properties["name"], schema(json!({"type": "string"})).into());
+
+        let missing = resolve_schema(&schema(json!({"items": {"$ref": "#/definitions/pet"}})), "").unwrap_err();
+        assert_eq!(missing.code(), ErrorCode::UnresolvedReference);
+    }
+
+    #[test]
+    fn test_resolve_circular_references() {
+        let root = schema(json!({
+            "definitions": {"node": {"properties": {"next": {"$ref": "#/definitions/node"}}}},
+            "$ref": "#/definitions/node",
+        }));
+        let error = resolve_schema(&root, "").unwrap_err();
+        assert_eq!(error.code(), ErrorCode::CircularReference);
+        let root = schema(json!({"definitions": {"a": {"$ref": "#/definitions/b"}, "b": {"$ref": "#/definitions/a"}}}));
+        let mut resolver = Resolver::new(&root, "").unwrap();
+        assert!(resolver.resolve("", "#/definitions/a").is_err());
+    }
+
+    #[test]
+    fn test_resolve_and_bundle_external_references() {
+        let dir = std::env::temp_dir().join(format!("gnostic-jsonschema-resolver-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("models")).unwrap();
+        write(
+            &dir,
+            "models/pet.json",
+            json!({
+                "definitions": {
+                    "Pet": {"properties": {"tag": {"$ref": "#/definitions/Tag"}, "owner": {"$ref": "../owner.json"}}},
+                    "Tag": {"type": "string"},
+                },
+            }),
+        );
+        let owner = json!({"properties": {"pets": {"items": {"$ref": "models/pet.json#/definitions/Pet"}}}});
+        write(&dir, "owner.json", owner);
+        let base = dir.join("schema.json").to_string_lossy().into_owned();
+        let root = schema(json!({
+            "definitions": {"Pet": {"type": "object"}},
+            "properties": {"pet": {"$ref": "models/pet.json#/definitions/Pet"}, "local": {"$ref": "#/definitions/Pet"}},
+        }));
+
+        let error = resolve_schema(&root, &base).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::CircularReference);
+
+        let bundled = bundle_schema(&root, &base).unwrap();
+        let properties = bundled.properties.as_ref().unwrap();
+        assert_eq!(properties["pet"].to_schema().reference.as_deref(), Some("#/definitions/Pet2"));
+        assert_eq!(properties["local"].to_schema().reference.as_deref(), Some("#/definitions/Pet"));
+        let definitions = bundled.definitions.as_ref().unwrap();
+        let mut names: Vec<&str> = definitions.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, ["Pet", "Pet2", "Tag", "owner"]);
+        let pet = definitions["Pet2"].as_schema().unwrap().properties.as_ref().unwrap();
+        assert_eq!(pet["tag"].to_schema().reference.as_deref(), Some("#/definitions/Tag"));
+        assert_eq!(pet["owner"].to_schema().reference.as_deref(), Some("#/definitions/owner"));
+        let owner = &definitions["owner"].as_schema().unwrap().properties.as_ref().unwrap()["pets"];
+        assert_eq!(
+            serde_json::to_value(owner).unwrap(),
+            json!({"items": {"$ref": "#/definitions/Pet2"}})
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_policy() {
+        let metaschema = "http://json-schema.org/draft-07/schema#/definitions/nonNegativeInteger";
+        let resolved = resolve_schema(&schema(json!({"$ref": metaschema})), "").unwrap();
+        assert_eq!(resolved, schema(json!({"type": "integer", "minimum": 0})));
+
+        let remote = schema(json!({"$ref": "https://example.com/pet.json#/Pet"}));
+        let error = resolve_schema(&remote, "").unwrap_err();
+        assert_eq!(error.code(), ErrorCode::FetchDenied);
+        assert!(error.to_string().contains("scheme https is not allowed"), "{}", error);
+
+        let dir = std::env::temp_dir().join(format!("gnostic-jsonschema-policy-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write(&dir, "pet.json", json!({"type": "object"}));
+        let base = dir.join("schema.json").to_string_lossy().into_owned();
+        let local = schema(json!({"items": {"$ref": "pet.json"}}));
+        let error = bundle_schema_with_policy(&local, &base, &FetchPolicy::new().deny_local()).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::FetchDenied);
+        let policy = FetchPolicy::new().allow_local_root(&dir);
+        let resolved = resolve_schema_with_policy(&local, &base, &policy).unwrap();
+        assert_eq!(resolved, schema(json!({"items": {"type": "object"}})));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
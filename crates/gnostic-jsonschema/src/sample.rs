@@ -0,0 +1,375 @@
+//! Generation of sample instances from schemas.
+//!
+//! [`sample_instance`] builds a value that a schema plausibly accepts, for
+//! mock data and documentation. Values given by the schema (const, examples,
+//! default and enum) are used as they are; other values are built from the
+//! type, format and bounds of the schema. Objects get their required
+//! properties followed by their other properties, and arrays get as few items
+//! as allowed, but at least one. allOf branches are merged, and the first
+//! branch of anyOf and oneOf is used. Patterns and uniqueItems are not
+//! followed. Local references are followed; a reference back to a schema
+//! that is being sampled gives null.
+
+use crate::merge::merge;
+use crate::models::{Schema, SchemaNumber, SchemaOrBoolean, SchemaOrSchemaArray, StringOrStringArray};
+use crate::validator::resolve;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+
+/// The deepest nesting of combinators and references that is followed.
+const MAX_DEPTH: usize = 32;
+
+/// Returns a sample value for `schema`.
+pub fn sample_instance(schema: &Schema) -> Value {
+    Sampler {
+        root: schema,
+        expanding: Vec::new(),
+    }
+    .sample(schema)
+}
+
+struct Sampler<'a> {
+    root: &'a Schema,
+    /// References whose targets are being sampled.
+    expanding: Vec<String>,
+}
+
+impl Sampler<'_> {
+    fn sample(&mut self, schema: &Schema) -> Value {
+        if let Some(reference) = &schema.reference {
+            let target = match resolve(self.root, reference) {
+                Some(target) if !self.expanding.contains(reference) => target,
+                _ => return Value::Null,
+            };
+            self.expanding.push(reference.clone());
+            let value = self.sample(&target);
+            self.expanding.pop();
+            return value;
+        }
+        if let Some(value) = given_value(schema) {
+            return value;
+        }
+        if schema.all_of.is_none() && schema.any_of.is_none() && schema.one_of.is_none() {
+            return self.sample_type(schema);
+        }
+        let combined = self.combine(schema, 0);
+        given_value(&combined).unwrap_or_else(|| self.sample_type(&combined))
+    }
+
+    /// Returns `schema` with its references followed and its allOf branches
+    /// and first anyOf and oneOf branches merged into it.
+    fn combine(&mut self, schema: &Schema, depth: usize) -> Schema {
+        let mut schema = Cow::Borrowed(schema);
+        let mut steps = 0;
+        while let Some(reference) = &schema.reference {
+            match resolve(self.root, reference) {
+                Some(target) if steps < MAX_DEPTH => schema = target,
+                _ => return Schema::default(),
+            }
+            steps += 1;
+        }
+        let mut combined = Schema {
+            all_of: None,
+            any_of: None,
+            one_of: None,
+            ..schema.as_ref().clone()
+        };
+        if depth < MAX_DEPTH {
+            let branches = schema.all_of.iter().flatten();
+            let first = schema.any_of.iter().chain(&schema.one_of).filter_map(|branches| branches.first());
+            for branch in branches.chain(first) {
+                combined = merge(combined, self.combine(&branch.to_schema(), depth + 1));
+            }
+        }
+        // Keywords that could not be merged are left out.
+        combined.all_of = None;
+        combined
+    }
+
+    fn sample_type(&mut self, schema: &Schema) -> Value {
+        let name = match &schema.type_value {
+            Some(StringOrStringArray::String(name)) => name.as_str(),
+            Some(StringOrStringArray::Array(names)) => names
+                .iter()
+                .find(|name| *name != "null")
+                .or(names.first())
+                .map_or("null", String::as_str),
+            None => implied_type(schema),
+        };
+        match name {
+            "object" => self.sample_object(schema),
+            "array" => self.sample_array(schema),
+            "string" => sample_string(schema),
+            "integer" => sample_number(schema, true),
+            "number" => sample_number(schema, false),
+            "boolean" => Value::Bool(true),
+            _ => Value::Null,
+        }
+    }
+
+    fn sample_array(&mut self, schema: &Schema) -> Value {
+        let min_items = schema.min_items.unwrap_or(0).max(0) as usize;
+        let max_items = schema.max_items.map(|max| max.max(0) as usize);
+        let mut items = match schema.items.as_deref() {
+            Some(SchemaOrSchemaArray::Array(schemas)) => {
+                let mut items: Vec<Value> = schemas.iter().map(|item| self.sample(&item.to_schema())).collect();
+                while items.len() < min_items {
+                    let item = match &schema.additional_items {
+                        Some(SchemaOrBoolean::Schema(item)) => self.sample(item),
+                        _ => Value::Null,
+                    };
+                    items.push(item);
+                }
+                items
+            }
+            Some(SchemaOrSchemaArray::Schema(item)) => {
+                let count = min_items.max(1).min(max_items.unwrap_or(usize::MAX));
+                (0..count).map(|_| self.sample(&item.to_schema())).collect()
+            }
+            None => vec![Value::Null; min_items],
+        };
+        if let Some(contains) = &schema.contains {
+            let item = self.sample(&contains.to_schema());
+            match items.first_mut() {
+                Some(first) => *first = item,
+                None => items.push(item),
+            }
+        }
+        items.truncate(max_items.unwrap_or(usize::MAX));
+        Value::Array(items)
+    }
+
+    fn sample_object(&mut self, schema: &Schema) -> Value {
+        let required = schema.required.as_deref().unwrap_or_default();
+        let optional = schema.properties.iter().flatten().filter(|(name, _)| !required.contains(name));
+        let additional = schema.additional_properties.as_ref().filter(|additional| additional.as_schema().is_some());
+        let mut members = Vec::new();
+        for name in required {
+            let property = schema.properties.as_ref().and_then(|properties| properties.get(name));
+            let value = property.or(additional).map_or(Value::Null, |property| self.sample(&property.to_schema()));
+            members.push((name.clone(), value));
+        }
+        for (name, property) in optional {
+            members.push((name.clone(), self.sample(&property.to_schema())));
+        }
+        let min_properties = schema.min_properties.unwrap_or(0).max(0) as usize;
+        if schema.additional_properties != Some(SchemaOrBoolean::Boolean(false)) {
+            while members.len() < min_properties {
+                let value = additional.map_or(Value::Null, |additional| self.sample(&additional.to_schema()));
+                members.push((format!("property{}", members.len() + 1), value));
+            }
+        }
+        let max_properties = schema.max_properties.map_or(usize::MAX, |max| max.max(0) as usize);
+        members.truncate(max_properties.max(required.len()));
+        Value::Object(members.into_iter().collect::<Map<_, _>>())
+    }
+}
+
+/// Returns the value that a schema gives for its instances, if any.
+fn given_value(schema: &Schema) -> Option<Value> {
+    schema
+        .const_value
+        .clone()
+        .or_else(|| schema.examples.iter().flatten().next().cloned())
+        .or_else(|| schema.default.clone())
+        .or_else(|| schema.enumeration.iter().flatten().next().cloned())
+}
+
+/// Returns the type implied by the keywords of a schema without a type.
+fn implied_type(schema: &Schema) -> &'static str {
+    if schema.properties.is_some()
+        || schema.required.is_some()
+        || schema.additional_properties.is_some()
+        || schema.pattern_properties.is_some()
+        || schema.min_properties.is_some()
+        || schema.max_properties.is_some()
+    {
+        "object"
+    } else if schema.items.is_some() || schema.min_items.is_some() || schema.contains.is_some() {
+        "array"
+    } else if schema.format.is_some()
+        || schema.pattern.is_some()
+        || schema.min_length.is_some()
+        || schema.max_length.is_some()
+    {
+        "string"
+    } else if schema.minimum.is_some() || schema.maximum.is_some() || schema.multiple_of.is_some() {
+        "number"
+    } else {
+        "null"
+    }
+}
+
+fn sample_string(schema: &Schema) -> Value {
+    let sample = match schema.format.as_deref() {
+        Some("date-time") => "2024-01-01T00:00:00Z",
+        Some("date") => "2024-01-01",
+        Some("time") => "00:00:00Z",
+        Some("email" | "idn-email") => "user@example.com",
+        Some("hostname" | "idn-hostname") => "example.com",
+        Some("ipv4") => "192.0.2.1",
+        Some("ipv6") => "2001:db8::1",
+        Some("uri" | "iri" | "url") => "https://example.com/",
+        Some("uri-reference" | "iri-reference") => "/example",
+        Some("uri-template") => "https://example.com/{id}",
+        Some("uuid") => "123e4567-e89b-12d3-a456-426614174000",
+        Some("json-pointer") => "/example",
+        Some("byte") => "ZXhhbXBsZQ==",
+        Some("password") => "********",
+        _ => "string",
+    };
+    let mut text: Vec<char> = sample.chars().collect();
+    let min_length = schema.min_length.unwrap_or(0).max(0) as usize;
+    if text.len() < min_length {
+        text.resize(min_length, 'x');
+    }
+    text.truncate(schema.max_length.map_or(usize::MAX, |max| max.max(0) as usize));
+    Value::String(text.into_iter().collect())
+}
+
+/// Returns the number closest to zero within the bounds of a schema, which
+/// is a multiple of its multipleOf.
+fn sample_number(schema: &Schema, integer: bool) -> Value {
+    let step = schema
+        .multiple_of
+        .as_ref()
+        .map(SchemaNumber::as_f64)
+        .filter(|step| *step > 0.0)
+        .or(integer.then_some(1.0));
+    let (minimum, exclusive_minimum) = schema.lower_bound().unzip();
+    let (maximum, exclusive_maximum) = schema.upper_bound().unzip();
+    let exclusive_minimum = exclusive_minimum == Some(true);
+    let exclusive_maximum = exclusive_maximum == Some(true);
+    let above = |value: f64| minimum.is_none_or(|min| value > min || (!exclusive_minimum && value == min));
+    let below = |value: f64| maximum.is_none_or(|max| value < max || (!exclusive_maximum && value == max));
+    let value = match (minimum, maximum) {
+        (Some(min), _) if !above(0.0) => match step {
+            Some(step) => {
+                let value = (min / step).ceil() * step;
+                if above(value) {
+                    value
+                } else {
+                    value + step
+                }
+            }
+            None if above(min) => min,
+            None => maximum.map_or(min + 1.0, |max| (min + max) / 2.0),
+        },
+        (_, Some(max)) if !below(0.0) => match step {
+            Some(step) => {
+                let value = (max / step).floor() * step;
+                if below(value) {
+                    value
+                } else {
+                    value - step
+                }
+            }
+            None if below(max) => max,
+            None => minimum.map_or(max - 1.0, |min| (min + max) / 2.0),
+        },
+        _ => 0.0,
+    };
+    if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+        Value::from(value as i64)
+    } else {
+        serde_json::Number::from_f64(value).map_or(Value::Null, Value::Number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::validate_instance;
+    use serde_json::json;
+
+    fn sample(value: Value) -> Value {
+        let schema: Schema = serde_json::from_value(value).unwrap();
+        sample_instance(&schema)
+    }
+
+    #[test]
+    fn test_sample_scalars() {
+        assert_eq!(sample(json!({"type": "string", "enum": ["cat", "dog"]})), json!("cat"));
+        assert_eq!(sample(json!({"const": 3, "default": 4})), json!(3));
+        assert_eq!(sample(json!({"type": "integer", "examples": [7], "default": 4})), json!(7));
+        assert_eq!(sample(json!({"type": "string", "format": "date-time"})), json!("2024-01-01T00:00:00Z"));
+        assert_eq!(sample(json!({"type": "string", "minLength": 8})), json!("stringxx"));
+        assert_eq!(sample(json!({"type": "string", "maxLength": 3})), json!("str"));
+        assert_eq!(sample(json!({"type": ["null", "boolean"]})), json!(true));
+        assert_eq!(sample(json!({"type": "integer", "minimum": 5, "exclusiveMinimum": true})), json!(6));
+        assert_eq!(sample(json!({"type": "integer", "maximum": -3, "multipleOf": 2})), json!(-4));
+        assert_eq!(sample(json!({"type": "number", "minimum": 0.5, "maximum": 1})), json!(0.5));
+        assert_eq!(
+            sample(json!({"type": "number", "minimum": 1, "maximum": 2, "exclusiveMinimum": true})),
+            json!(1.5)
+        );
+        assert_eq!(sample(json!({"type": "integer", "minimum": 2, "exclusiveMinimum": 4})), json!(5));
+        assert_eq!(sample(json!({"type": "number", "exclusiveMaximum": -1, "maximum": 0})), json!(-2));
+        assert_eq!(sample(json!({"minimum": -1})), json!(0));
+        assert_eq!(sample(json!({})), Value::Null);
+    }
+
+    #[test]
+    fn test_sample_objects_and_arrays() {
+        assert_eq!(
+            sample(json!({
+                "type": "object",
+                "required": ["name", "id"],
+                "properties": {
+                    "id": {"type": "integer", "minimum": 1},
+                    "tags": {"type": "array", "items": {"type": "string"}, "minItems": 2},
+                    "name": {"type": "string"},
+                },
+            })),
+            json!({"name": "string", "id": 1, "tags": ["string", "string"]})
+        );
+        assert_eq!(
+            sample(json!({"items": [{"type": "integer"}], "additionalItems": {"type": "boolean"}, "minItems": 2})),
+            json!([0, true])
+        );
+        assert_eq!(sample(json!({"type": "array", "maxItems": 0, "items": {}})), json!([]));
+        assert_eq!(sample(json!({"type": "array", "contains": {"const": "x"}})), json!(["x"]));
+        assert_eq!(sample(json!({"type": "array", "items": true, "minItems": 1})), json!([null]));
+        assert_eq!(
+            sample(json!({"minProperties": 2, "additionalProperties": {"type": "integer"}})),
+            json!({"property1": 0, "property2": 0})
+        );
+        assert_eq!(
+            sample(json!({"required": ["a"], "properties": {"a": {}, "b": {}}, "maxProperties": 1})),
+            json!({"a": null})
+        );
+    }
+
+    #[test]
+    fn test_sample_combinators_and_references() {
+        let schema: Schema = serde_json::from_value(json!({
+            "definitions": {
+                "Pet": {"type": "object", "required": ["name"], "properties": {"name": {"type": "string"}}},
+                "Node": {"type": "object", "properties": {"next": {"$ref": "#/definitions/Node"}}},
+            },
+            "type": "object",
+            "properties": {
+                "pet": {"allOf": [
+                    {"$ref": "#/definitions/Pet"},
+                    {"required": ["age"], "properties": {"age": {"type": "integer", "minimum": 1}}},
+                ]},
+                "choice": {"oneOf": [{"type": "string", "format": "email"}, {"type": "integer"}]},
+                "node": {"$ref": "#/definitions/Node"},
+                "missing": {"$ref": "other.json"},
+            },
+        }))
+        .unwrap();
+        let value = sample_instance(&schema);
+        assert_eq!(
+            value,
+            json!({
+                "choice": "user@example.com",
+                "missing": null,
+                "node": {"next": null},
+                "pet": {"name": "string", "age": 1},
+            })
+        );
+        let valid = json!({"pet": value["pet"], "choice": value["choice"]});
+        assert!(validate_instance(&schema, &valid).is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+//! Statistics about schemas.
+//!
+//! [`Schema::stats`] summarizes a schema and the schemas nested in it, for
+//! comparing schemas and watching them grow across a corpus.
+
+use crate::models::{Schema, StringOrStringArray};
+use crate::visitor::{walk, SchemaVisitor};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Counts and metrics over a schema and every schema nested in it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaStats {
+    /// Number of schemas, including the root schema.
+    pub schemas: usize,
+    /// Number of schemas declaring each type; a schema with several types
+    /// counts once for each.
+    pub types: BTreeMap<String, usize>,
+    /// Deepest nesting of a schema below the root schema, which is at depth 0.
+    pub max_depth: usize,
+    /// Number of $ref values.
+    pub references: usize,
+    /// Number of properties declared in properties.
+    pub properties: usize,
+    /// Number of schemas using allOf.
+    pub all_of: usize,
+    /// Number of schemas using anyOf.
+    pub any_of: usize,
+    /// Number of schemas using oneOf.
+    pub one_of: usize,
+    /// Number of schemas using not.
+    pub not: usize,
+    /// Number of schemas using if.
+    pub conditionals: usize,
+    /// An estimate of how hard the schema is to read and validate: one for
+    /// each schema and reference, plus the branches of anyOf and oneOf,
+    /// which are alternatives to consider, and one for each condition.
+    pub complexity: usize,
+}
+
+impl Schema {
+    /// Returns statistics about the schema and every schema nested in it.
+    pub fn stats(&self) -> SchemaStats {
+        let mut collector = StatsCollector {
+            stats: SchemaStats::default(),
+            depth: 0,
+        };
+        walk(self, &mut collector);
+        collector.stats
+    }
+}
+
+struct StatsCollector {
+    stats: SchemaStats,
+    depth: usize,
+}
+
+impl SchemaVisitor for StatsCollector {
+    fn visit_schema(&mut self, _pointer: &str, schema: &Schema) -> bool {
+        let stats = &mut self.stats;
+        stats.schemas += 1;
+        stats.max_depth = stats.max_depth.max(self.depth);
+        let types = match &schema.type_value {
+            Some(StringOrStringArray::String(name)) => std::slice::from_ref(name),
+            Some(StringOrStringArray::Array(names)) => names.as_slice(),
+            None => &[],
+        };
+        for name in types {
+            *stats.types.entry(name.clone()).or_default() += 1;
+        }
+        let references = usize::from(schema.reference.is_some());
+        stats.references += references;
+        stats.properties += schema.properties.as_ref().map_or(0, |properties| properties.len());
+        stats.all_of += usize::from(schema.all_of.is_some());
+        stats.any_of += usize::from(schema.any_of.is_some());
+        stats.one_of += usize::from(schema.one_of.is_some());
+        stats.not += usize::from(schema.not.is_some());
+        let conditionals = usize::from(schema.if_schema.is_some());
+        stats.conditionals += conditionals;
+        let alternatives = schema.any_of.iter().chain(&schema.one_of).map(Vec::len).sum::<usize>();
+        stats.complexity += 1 + references + alternatives + conditionals;
+        self.depth += 1;
+        true
+    }
+
+    fn leave_schema(&mut self, _pointer: &str, _schema: &Schema) {
+        self.depth -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_stats() {
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "definitions": {"id": {"type": ["integer", "string"]}},
+            "properties": {
+                "id": {"$ref": "#/definitions/id"},
+                "pet": {
+                    "oneOf": [{"type": "string"}, {"type": "object", "properties": {"name": {"type": "string"}}}],
+                    "not": {"type": "null"},
+                },
+                "tags": {"type": "array", "items": {"allOf": [{"if": {"type": "string"}, "then": {"minLength": 1}}]}},
+            },
+        }))
+        .unwrap();
+        let stats = schema.stats();
+        assert_eq!(stats.schemas, 13);
+        assert_eq!(
+            stats.types,
+            BTreeMap::from([
+                ("array".to_string(), 1),
+                ("integer".to_string(), 1),
+                ("null".to_string(), 1),
+                ("object".to_string(), 2),
+                ("string".to_string(), 4),
+            ])
+        );
+        assert_eq!(stats.max_depth, 4);
+        assert_eq!(stats.references, 1);
+        assert_eq!(stats.properties, 4);
+        assert_eq!((stats.all_of, stats.any_of, stats.one_of, stats.not, stats.conditionals), (1, 0, 1, 1, 1));
+        assert_eq!(stats.complexity, 13 + 1 + 2 + 1);
+        assert_eq!(Schema::default().stats().complexity, 1);
+        assert_eq!(serde_json::to_value(&stats).unwrap()["maxDepth"], 4);
+    }
+}
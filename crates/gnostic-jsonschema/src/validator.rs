@@ -0,0 +1,595 @@
+//! Validation of JSON instances against schemas.
+//!
+//! [`validate_instance`] checks a value, such as a request or response
+//! payload, against a schema and reports every keyword it does not satisfy,
+//! including the Draft 6 and 7 keywords of the model.
+//! Local references ("#/definitions/Pet") are resolved against the schema
+//! being validated with; other references are reported as unresolved.
+
+use crate::models::{
+    NumberOrBoolean, Schema, SchemaNumber, SchemaOrBoolean, SchemaOrSchemaArray, SchemaOrStringArray,
+    StringOrStringArray,
+};
+use gnostic_compiler::{escape_json_pointer_segment, pointer_segments};
+use regex::Regex;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// The deepest nesting of schemas that is followed, which stops reference cycles.
+const MAX_DEPTH: usize = 256;
+
+/// A value of an instance that does not satisfy a schema.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("#{instance_path} {message}")]
+pub struct ValidationError {
+    /// JSON pointer to the value in the instance, such as "/pets/0/name";
+    /// "" for the instance itself.
+    pub instance_path: String,
+    /// The keyword that is not satisfied, such as "minLength".
+    pub keyword: String,
+    pub message: String,
+}
+
+/// Validates `instance` against `schema`, returning the errors found.
+pub fn validate_instance(schema: &Schema, instance: &Value) -> Vec<ValidationError> {
+    let mut validator = InstanceValidator {
+        root: schema,
+        patterns: HashMap::new(),
+        errors: Vec::new(),
+    };
+    validator.validate(schema, instance, "", 0);
+    validator.errors
+}
+
+struct InstanceValidator<'a> {
+    root: &'a Schema,
+    /// Compiled patterns, or None for patterns that do not compile.
+    patterns: HashMap<String, Option<Regex>>,
+    errors: Vec<ValidationError>,
+}
+
+impl<'a> InstanceValidator<'a> {
+    fn error(&mut self, path: &str, keyword: &str, message: impl Into<String>) {
+        self.errors.push(ValidationError {
+            instance_path: path.to_string(),
+            keyword: keyword.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Returns true if `instance` satisfies `schema`, without reporting errors.
+    fn is_valid(&mut self, schema: &SchemaOrBoolean, instance: &Value, depth: usize) -> bool {
+        let saved = std::mem::take(&mut self.errors);
+        self.validate_subschema(schema, instance, "", depth);
+        let valid = self.errors.is_empty();
+        self.errors = saved;
+        valid
+    }
+
+    /// Validates against a subschema, which true accepts and false rejects.
+    fn validate_subschema(&mut self, schema: &SchemaOrBoolean, instance: &Value, path: &str, depth: usize) {
+        match schema {
+            SchemaOrBoolean::Schema(schema) => self.validate(schema, instance, path, depth),
+            SchemaOrBoolean::Boolean(true) => {}
+            SchemaOrBoolean::Boolean(false) => self.error(path, "false", "is not allowed"),
+        }
+    }
+
+    fn validate(&mut self, schema: &Schema, instance: &Value, path: &str, depth: usize) {
+        if depth > MAX_DEPTH {
+            self.error(path, "$ref", "nests schemas too deeply");
+            return;
+        }
+        // In Draft 4 the keywords next to $ref are ignored.
+        if let Some(reference) = &schema.reference {
+            match resolve(self.root, reference) {
+                Some(target) => self.validate(&target, instance, path, depth + 1),
+                None => self.error(path, "$ref", format!("uses unresolved reference {}", reference)),
+            }
+            return;
+        }
+        if let Some(types) = &schema.type_value {
+            let names: Vec<&str> = match types {
+                StringOrStringArray::String(name) => vec![name.as_str()],
+                StringOrStringArray::Array(names) => names.iter().map(String::as_str).collect(),
+            };
+            if !names.iter().any(|name| has_type(instance, name)) {
+                self.error(
+                    path,
+                    "type",
+                    format!("has type {}, expected {}", type_of(instance), names.join(" or ")),
+                );
+            }
+        }
+        if let Some(values) = &schema.enumeration {
+            if !values.iter().any(|value| equal(value, instance)) {
+                self.error(path, "enum", "is not one of the enum values");
+            }
+        }
+        if let Some(value) = schema.const_value.as_ref().filter(|value| !equal(value, instance)) {
+            self.error(path, "const", format!("must be {}", value));
+        }
+        match instance {
+            Value::Number(number) => {
+                if let Some(number) = number.as_f64() {
+                    self.validate_number(schema, number, path);
+                }
+            }
+            Value::String(text) => self.validate_string(schema, text, path),
+            Value::Array(items) => self.validate_array(schema, items, path, depth),
+            Value::Object(object) => self.validate_object(schema, object, path, depth),
+            _ => {}
+        }
+        self.validate_combinators(schema, instance, path, depth);
+    }
+
+    fn validate_number(&mut self, schema: &Schema, number: f64, path: &str) {
+        let divisor = schema.multiple_of.as_ref().map(SchemaNumber::as_f64);
+        if let Some(divisor) = divisor.filter(|divisor| *divisor > 0.0) {
+            let quotient = number / divisor;
+            if (quotient - quotient.round()).abs() > 1e-9 {
+                self.error(path, "multipleOf", format!("is not a multiple of {}", divisor));
+            }
+        }
+        if let Some(maximum) = schema.maximum.as_ref().map(SchemaNumber::as_f64) {
+            if schema.exclusive_maximum == Some(NumberOrBoolean::Boolean(true)) && number >= maximum {
+                self.error(path, "maximum", format!("must be less than {}", maximum));
+            } else if number > maximum {
+                self.error(path, "maximum", format!("must be at most {}", maximum));
+            }
+        }
+        if let Some(NumberOrBoolean::Number(maximum)) = &schema.exclusive_maximum {
+            if number >= maximum.as_f64() {
+                self.error(path, "exclusiveMaximum", format!("must be less than {}", maximum.as_f64()));
+            }
+        }
+        if let Some(minimum) = schema.minimum.as_ref().map(SchemaNumber::as_f64) {
+            if schema.exclusive_minimum == Some(NumberOrBoolean::Boolean(true)) && number <= minimum {
+                self.error(path, "minimum", format!("must be greater than {}", minimum));
+            } else if number < minimum {
+                self.error(path, "minimum", format!("must be at least {}", minimum));
+            }
+        }
+        if let Some(NumberOrBoolean::Number(minimum)) = &schema.exclusive_minimum {
+            if number <= minimum.as_f64() {
+                self.error(path, "exclusiveMinimum", format!("must be greater than {}", minimum.as_f64()));
+            }
+        }
+    }
+
+    fn validate_string(&mut self, schema: &Schema, text: &str, path: &str) {
+        let length = text.chars().count() as i64;
+        if let Some(max) = schema.max_length.filter(|max| length > *max) {
+            self.error(path, "maxLength", format!("must be at most {} characters long", max));
+        }
+        if let Some(min) = schema.min_length.filter(|min| length < *min) {
+            self.error(path, "minLength", format!("must be at least {} characters long", min));
+        }
+        if let Some(pattern) = &schema.pattern {
+            match self.matches(pattern, text) {
+                Some(true) => {}
+                Some(false) => self.error(path, "pattern", format!("does not match pattern {}", pattern)),
+                None => self.error(path, "pattern", format!("has invalid pattern {}", pattern)),
+            }
+        }
+    }
+
+    fn validate_array(&mut self, schema: &Schema, items: &[Value], path: &str, depth: usize) {
+        let count = items.len() as i64;
+        if let Some(max) = schema.max_items.filter(|max| count > *max) {
+            self.error(path, "maxItems", format!("must have at most {} items", max));
+        }
+        if let Some(min) = schema.min_items.filter(|min| count < *min) {
+            self.error(path, "minItems", format!("must have at least {} items", min));
+        }
+        if schema.unique_items == Some(true) {
+            let duplicate = (0..items.len()).any(|i| items[i + 1..].iter().any(|item| equal(item, &items[i])));
+            if duplicate {
+                self.error(path, "uniqueItems", "has duplicate items");
+            }
+        }
+        if let Some(contains) = &schema.contains {
+            if !items.iter().any(|item| self.is_valid(contains, item, depth + 1)) {
+                self.error(path, "contains", "does not contain an item that matches the schema of contains");
+            }
+        }
+        match schema.items.as_deref() {
+            Some(SchemaOrSchemaArray::Schema(item_schema)) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.validate_subschema(item_schema, item, &child(path, &i.to_string()), depth + 1);
+                }
+            }
+            Some(SchemaOrSchemaArray::Array(item_schemas)) => {
+                for (i, (item, item_schema)) in items.iter().zip(item_schemas).enumerate() {
+                    self.validate_subschema(item_schema, item, &child(path, &i.to_string()), depth + 1);
+                }
+                match &schema.additional_items {
+                    Some(SchemaOrBoolean::Boolean(false)) if items.len() > item_schemas.len() => {
+                        self.error(
+                            path,
+                            "additionalItems",
+                            format!("must have at most {} items", item_schemas.len()),
+                        );
+                    }
+                    Some(SchemaOrBoolean::Schema(additional)) => {
+                        for (i, item) in items.iter().enumerate().skip(item_schemas.len()) {
+                            self.validate(additional, item, &child(path, &i.to_string()), depth + 1);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn validate_object(
+        &mut self,
+        schema: &Schema,
+        object: &serde_json::Map<String, Value>,
+        path: &str,
+        depth: usize,
+    ) {
+        let count = object.len() as i64;
+        if let Some(max) = schema.max_properties.filter(|max| count > *max) {
+            self.error(path, "maxProperties", format!("must have at most {} properties", max));
+        }
+        if let Some(min) = schema.min_properties.filter(|min| count < *min) {
+            self.error(path, "minProperties", format!("must have at least {} properties", min));
+        }
+        for name in schema.required.iter().flatten() {
+            if !object.contains_key(name) {
+                self.error(path, "required", format!("is missing required property: {}", name));
+            }
+        }
+        for (key, value) in object {
+            let value_path = child(path, key);
+            if let Some(property_names) = &schema.property_names {
+                if !self.is_valid(property_names, &Value::String(key.clone()), depth + 1) {
+                    self.error(path, "propertyNames", format!("has invalid property name: {}", key));
+                }
+            }
+            let mut matched = false;
+            if let Some(property) = schema.properties.as_ref().and_then(|properties| properties.get(key)) {
+                matched = true;
+                self.validate_subschema(property, value, &value_path, depth + 1);
+            }
+            for (pattern, property) in schema.pattern_properties.iter().flatten() {
+                if self.matches(pattern, key) == Some(true) {
+                    matched = true;
+                    self.validate_subschema(property, value, &value_path, depth + 1);
+                }
+            }
+            if matched {
+                continue;
+            }
+            match &schema.additional_properties {
+                Some(SchemaOrBoolean::Boolean(false)) => {
+                    self.error(path, "additionalProperties", format!("has invalid property: {}", key));
+                }
+                Some(SchemaOrBoolean::Schema(additional)) => {
+                    self.validate(additional, value, &value_path, depth + 1);
+                }
+                _ => {}
+            }
+        }
+        for key in object.keys() {
+            let Some(dependency) = schema.dependencies.as_ref().and_then(|dependencies| dependencies.get(key)) else {
+                continue;
+            };
+            match dependency {
+                SchemaOrStringArray::StringArray(names) => {
+                    for name in names.iter().filter(|name| !object.contains_key(*name)) {
+                        self.error(
+                            path,
+                            "dependencies",
+                            format!("is missing property {}, which {} depends on", name, key),
+                        );
+                    }
+                }
+                SchemaOrStringArray::Schema(dependency) => {
+                    self.validate_subschema(dependency, &Value::Object(object.clone()), path, depth + 1);
+                }
+            }
+        }
+    }
+
+    fn validate_combinators(&mut self, schema: &Schema, instance: &Value, path: &str, depth: usize) {
+        for all in schema.all_of.iter().flatten() {
+            self.validate_subschema(all, instance, path, depth + 1);
+        }
+        if let Some(any_of) = &schema.any_of {
+            if !any_of.iter().any(|any| self.is_valid(any, instance, depth + 1)) {
+                self.error(path, "anyOf", "does not match any schema of anyOf");
+            }
+        }
+        if let Some(one_of) = &schema.one_of {
+            let matches = one_of.iter().filter(|one| self.is_valid(one, instance, depth + 1)).count();
+            if matches == 0 {
+                self.error(path, "oneOf", "does not match any schema of oneOf");
+            } else if matches > 1 {
+                self.error(path, "oneOf", format!("matches {} schemas of oneOf, expected one", matches));
+            }
+        }
+        if let Some(not) = &schema.not {
+            if self.is_valid(not, instance, depth + 1) {
+                self.error(path, "not", "must not match the schema of not");
+            }
+        }
+        if let Some(condition) = &schema.if_schema {
+            let branch = if self.is_valid(condition, instance, depth + 1) {
+                &schema.then_schema
+            } else {
+                &schema.else_schema
+            };
+            if let Some(branch) = branch {
+                self.validate_subschema(branch, instance, path, depth + 1);
+            }
+        }
+    }
+
+    /// Returns whether `pattern` matches `text`, or None if it does not compile.
+    fn matches(&mut self, pattern: &str, text: &str) -> Option<bool> {
+        self.patterns
+            .entry(pattern.to_string())
+            .or_insert_with(|| Regex::new(pattern).ok())
+            .as_ref()
+            .map(|regex| regex.is_match(text))
+    }
+}
+
+/// Resolves a local reference such as "#/definitions/Pet" against `root`;
+/// a reference to true or false gives the schema that matches the same values.
+pub(crate) fn resolve<'a>(root: &'a Schema, reference: &str) -> Option<Cow<'a, Schema>> {
+    if !reference.starts_with('#') {
+        return None;
+    }
+    let segments = pointer_segments(reference);
+    let mut segments = segments.iter().map(String::as_str);
+    let mut target: Option<&SchemaOrBoolean> = None;
+    while let Some(segment) = segments.next() {
+        let current = match target {
+            Some(target) => target.as_schema()?,
+            None => root,
+        };
+        target = Some(match segment {
+            "definitions" => current.definitions.as_ref()?.get(segments.next()?)?,
+            "$defs" => current.defs.as_ref()?.get(segments.next()?)?,
+            "properties" => current.properties.as_ref()?.get(segments.next()?)?,
+            "patternProperties" => current.pattern_properties.as_ref()?.get(segments.next()?)?,
+            "items" => match current.items.as_deref()? {
+                SchemaOrSchemaArray::Schema(schema) => schema,
+                SchemaOrSchemaArray::Array(schemas) => schemas.get(segments.next()?.parse::<usize>().ok()?)?,
+            },
+            "allOf" => current.all_of.as_ref()?.get(segments.next()?.parse::<usize>().ok()?)?,
+            "anyOf" => current.any_of.as_ref()?.get(segments.next()?.parse::<usize>().ok()?)?,
+            "oneOf" => current.one_of.as_ref()?.get(segments.next()?.parse::<usize>().ok()?)?,
+            "not" => current.not.as_ref()?,
+            "contains" => current.contains.as_ref()?,
+            "propertyNames" => current.property_names.as_ref()?,
+            "if" => current.if_schema.as_ref()?,
+            "then" => current.then_schema.as_ref()?,
+            "else" => current.else_schema.as_ref()?,
+            "additionalProperties" => current.additional_properties.as_ref()?,
+            "additionalItems" => current.additional_items.as_ref()?,
+            _ => return None,
+        });
+    }
+    Some(target.map_or(Cow::Borrowed(root), SchemaOrBoolean::to_schema))
+}
+
+/// Returns the JSON pointer of the member `segment` of the value at `path`.
+fn child(path: &str, segment: &str) -> String {
+    format!("{}/{}", path, escape_json_pointer_segment(segment))
+}
+
+/// Returns true if `instance` has the JSON Schema type `name`.
+fn has_type(instance: &Value, name: &str) -> bool {
+    match name {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => is_integer(instance),
+        _ => false,
+    }
+}
+
+/// Returns the JSON Schema type of `instance`, preferring "integer" to "number".
+fn type_of(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) if is_integer(instance) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_integer(instance: &Value) -> bool {
+    match instance {
+        Value::Number(number) => {
+            number.is_i64() || number.is_u64() || number.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+/// Compares JSON values as JSON Schema does, where 1 and 1.0 are equal.
+fn equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a == b || a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equal(a, b)),
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| equal(a, b)))
+        }
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn errors(schema: Value, instance: Value) -> Vec<String> {
+        let schema: Schema = serde_json::from_value(schema).unwrap();
+        validate_instance(&schema, &instance).iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_type_and_enum() {
+        assert!(errors(json!({"type": "integer"}), json!(3.0)).is_empty());
+        assert_eq!(errors(json!({"type": "integer"}), json!(3.5)), ["# has type number, expected integer"]);
+        assert!(errors(json!({"type": ["string", "null"]}), json!(null)).is_empty());
+        assert_eq!(
+            errors(json!({"type": "string", "enum": ["cat", "dog"]}), json!("cow")),
+            ["# is not one of the enum values"]
+        );
+        assert!(errors(json!({"enum": [1, [2]]}), json!([2.0])).is_empty());
+    }
+
+    #[test]
+    fn test_number_and_string_constraints() {
+        let schema = json!({"maximum": 10, "exclusiveMaximum": true, "minimum": 0, "multipleOf": 0.5});
+        assert!(errors(schema.clone(), json!(9.5)).is_empty());
+        assert_eq!(errors(schema.clone(), json!(10)), ["# must be less than 10"]);
+        assert_eq!(errors(schema, json!(-0.25)), ["# is not a multiple of 0.5", "# must be at least 0"]);
+
+        let schema = json!({"minLength": 2, "maxLength": 3, "pattern": "^[a-zé]+$"});
+        assert_eq!(errors(schema.clone(), json!("café")), ["# must be at most 3 characters long"]);
+        assert_eq!(errors(schema.clone(), json!("x")), ["# must be at least 2 characters long"]);
+        assert_eq!(errors(schema, json!("AB")), ["# does not match pattern ^[a-zé]+$"]);
+        assert_eq!(errors(json!({"pattern": "("}), json!("a")), ["# has invalid pattern ("]);
+    }
+
+    #[test]
+    fn test_draft6_exclusive_bounds() {
+        let schema = json!({"exclusiveMaximum": 10, "exclusiveMinimum": 0.5});
+        assert!(errors(schema.clone(), json!(9.5)).is_empty());
+        assert_eq!(errors(schema.clone(), json!(10)), ["# must be less than 10"]);
+        assert_eq!(errors(schema, json!(0.5)), ["# must be greater than 0.5"]);
+    }
+
+    #[test]
+    fn test_boolean_schemas() {
+        let schema = json!({"items": true, "properties": {"id": true, "secret": false}, "not": false});
+        assert!(errors(schema.clone(), json!([1, "a"])).is_empty());
+        assert!(errors(schema.clone(), json!({"id": 1})).is_empty());
+        assert_eq!(errors(schema.clone(), json!({"secret": 1})), ["#/secret is not allowed"]);
+        assert_eq!(
+            errors(json!({"anyOf": [false, {"type": "string"}]}), json!(1)),
+            ["# does not match any schema of anyOf"]
+        );
+        let schema = json!({"definitions": {"never": false}, "$ref": "#/definitions/never"});
+        assert_eq!(errors(schema, json!(1)), ["# must not match the schema of not"]);
+    }
+
+    #[test]
+    fn test_array_constraints() {
+        let schema = json!({"items": {"type": "integer"}, "maxItems": 2, "uniqueItems": true});
+        assert_eq!(
+            errors(schema, json!([1, 1, "a"])),
+            ["# must have at most 2 items", "# has duplicate items", "#/2 has type string, expected integer"]
+        );
+        let schema = json!({"items": [{"type": "string"}], "additionalItems": false});
+        assert_eq!(errors(schema, json!(["a", 1])), ["# must have at most 1 items"]);
+        let schema = json!({"items": [{"type": "string"}], "additionalItems": {"type": "integer"}});
+        assert_eq!(errors(schema, json!(["a", 1, true])), ["#/2 has type boolean, expected integer"]);
+    }
+
+    #[test]
+    fn test_object_constraints() {
+        let schema = json!({
+            "required": ["name"],
+            "properties": {"name": {"type": "string"}, "a/b": {"type": "string"}},
+            "patternProperties": {"^x-": {"type": "integer"}},
+            "additionalProperties": false,
+            "dependencies": {"x-a": ["x-b"], "a/b": {"required": ["name"]}},
+        });
+        assert!(errors(schema.clone(), json!({"name": "Rex", "x-a": 1, "x-b": 2})).is_empty());
+        assert_eq!(
+            errors(schema, json!({"a/b": 1, "x-a": "1", "age": 3})),
+            [
+                "# is missing required property: name",
+                "#/a~1b has type integer, expected string",
+                "#/x-a has type string, expected integer",
+                "# has invalid property: age",
+                "# is missing required property: name",
+                "# is missing property x-b, which x-a depends on",
+            ]
+        );
+        let schema = json!({"minProperties": 1, "additionalProperties": {"type": "boolean"}});
+        assert_eq!(errors(schema.clone(), json!({})), ["# must have at least 1 properties"]);
+        assert_eq!(errors(schema, json!({"a": 1})), ["#/a has type integer, expected boolean"]);
+    }
+
+    #[test]
+    fn test_combinators() {
+        let schema = json!({"oneOf": [{"type": "integer"}, {"type": "number"}]});
+        assert_eq!(errors(schema.clone(), json!(1)), ["# matches 2 schemas of oneOf, expected one"]);
+        assert!(errors(schema.clone(), json!(1.5)).is_empty());
+        assert_eq!(errors(schema, json!("1")), ["# does not match any schema of oneOf"]);
+        let schema = json!({"anyOf": [{"type": "string"}, {"minimum": 2}], "not": {"enum": [3]}});
+        assert!(errors(schema.clone(), json!(2)).is_empty());
+        assert_eq!(errors(schema, json!(3)), ["# must not match the schema of not"]);
+        let schema = json!({"allOf": [{"type": "string"}, {"maxLength": 1}]});
+        assert_eq!(errors(schema, json!("ab")), ["# must be at most 1 characters long"]);
+        assert_eq!(
+            errors(json!({"anyOf": [{"type": "string"}]}), json!(1)),
+            ["# does not match any schema of anyOf"]
+        );
+    }
+
+    #[test]
+    fn test_draft7_keywords() {
+        assert_eq!(errors(json!({"const": {"a": 1}}), json!({"a": 1.0})), Vec::<String>::new());
+        assert_eq!(errors(json!({"const": "cat"}), json!("dog")), ["# must be \"cat\""]);
+        assert_eq!(
+            errors(json!({"contains": {"type": "string"}}), json!([1, 2])),
+            ["# does not contain an item that matches the schema of contains"]
+        );
+        assert_eq!(
+            errors(json!({"propertyNames": {"pattern": "^[a-z]+$"}}), json!({"ok": 1, "Bad": 2})),
+            ["# has invalid property name: Bad"]
+        );
+        let schema = json!({
+            "if": {"properties": {"kind": {"const": "dog"}}},
+            "then": {"required": ["bark"]},
+            "else": {"required": ["meow"]},
+        });
+        assert!(errors(schema.clone(), json!({"kind": "dog", "bark": true})).is_empty());
+        assert_eq!(errors(schema.clone(), json!({"kind": "dog"})), ["# is missing required property: bark"]);
+        assert_eq!(errors(schema, json!({"kind": "cat"})), ["# is missing required property: meow"]);
+    }
+
+    #[test]
+    fn test_references() {
+        let schema = json!({
+            "definitions": {
+                "node": {
+                    "type": "object",
+                    "properties": {"children": {"type": "array", "items": {"$ref": "#/definitions/node"}}},
+                    "required": ["children"],
+                },
+            },
+            "$ref": "#/definitions/node",
+        });
+        assert!(errors(schema.clone(), json!({"children": [{"children": []}]})).is_empty());
+        assert_eq!(
+            errors(schema, json!({"children": [{}]})),
+            ["#/children/0 is missing required property: children"]
+        );
+        assert_eq!(
+            errors(json!({"$ref": "other.json#/definitions/pet"}), json!(1)),
+            ["# uses unresolved reference other.json#/definitions/pet"]
+        );
+        let schema = json!({"definitions": {"loop": {"$ref": "#/definitions/loop"}}, "$ref": "#/definitions/loop"});
+        assert_eq!(errors(schema, json!(1)), ["# nests schemas too deeply"]);
+    }
+}
@@ -0,0 +1,158 @@
+//! Traversal of schemas.
+//!
+//! [`walk`] and [`walk_mut`] visit a schema and every schema nested in it,
+//! in properties, items, combinators, definitions, dependencies and the
+//! other keywords that hold schemas, so that transforms such as renaming
+//! references or stripping descriptions need not recurse by hand. Schemas
+//! are visited before the schemas nested in them, each with its JSON
+//! pointer relative to the root schema ("" for the root).
+
+use crate::models::Schema;
+use crate::operations::{subschemas_with_pointers, subschemas_with_pointers_mut};
+
+/// Receives the schemas visited by [`walk`].
+pub trait SchemaVisitor {
+    /// Visits a schema; returning false skips the schemas nested in it.
+    fn visit_schema(&mut self, pointer: &str, schema: &Schema) -> bool;
+
+    /// Called after the schemas nested in a visited schema were walked.
+    fn leave_schema(&mut self, _pointer: &str, _schema: &Schema) {}
+}
+
+/// Receives the schemas visited by [`walk_mut`].
+pub trait SchemaVisitorMut {
+    /// Visits a schema, which may be modified before the schemas nested in
+    /// it are walked; returning false skips them.
+    fn visit_schema_mut(&mut self, pointer: &str, schema: &mut Schema) -> bool;
+}
+
+impl<F: FnMut(&str, &Schema) -> bool> SchemaVisitor for F {
+    fn visit_schema(&mut self, pointer: &str, schema: &Schema) -> bool {
+        self(pointer, schema)
+    }
+}
+
+impl<F: FnMut(&str, &mut Schema) -> bool> SchemaVisitorMut for F {
+    fn visit_schema_mut(&mut self, pointer: &str, schema: &mut Schema) -> bool {
+        self(pointer, schema)
+    }
+}
+
+/// Visits `schema` and every schema nested in it.
+pub fn walk(schema: &Schema, visitor: &mut impl SchemaVisitor) {
+    walk_at(String::new(), schema, visitor);
+}
+
+fn walk_at(pointer: String, schema: &Schema, visitor: &mut impl SchemaVisitor) {
+    if visitor.visit_schema(&pointer, schema) {
+        for (relative, nested) in subschemas_with_pointers(schema) {
+            walk_at(pointer.clone() + &relative, nested, visitor);
+        }
+    }
+    visitor.leave_schema(&pointer, schema);
+}
+
+/// Visits `schema` and every schema nested in it, allowing each to be modified.
+pub fn walk_mut(schema: &mut Schema, visitor: &mut impl SchemaVisitorMut) {
+    walk_mut_at(String::new(), schema, visitor);
+}
+
+fn walk_mut_at(pointer: String, schema: &mut Schema, visitor: &mut impl SchemaVisitorMut) {
+    if visitor.visit_schema_mut(&pointer, schema) {
+        for (relative, nested) in subschemas_with_pointers_mut(schema) {
+            walk_mut_at(pointer.clone() + &relative, nested, visitor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(value: serde_json::Value) -> Schema {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_walk_pointers() {
+        let root = schema(json!({
+            "definitions": {"b/c": {"type": "string"}, "a": {"items": [{}, {"not": {}}]}},
+            "properties": {"name": {"$ref": "#/definitions/a"}},
+            "dependencies": {"name": ["id"], "id": {"required": ["name"]}},
+            "allOf": [{"if": {}, "then": {}}],
+        }));
+        let mut pointers = Vec::new();
+        walk(&root, &mut |pointer: &str, _: &Schema| {
+            pointers.push(pointer.to_string());
+            true
+        });
+        assert_eq!(
+            pointers,
+            [
+                "",
+                "/definitions/b~1c",
+                "/definitions/a",
+                "/definitions/a/items/0",
+                "/definitions/a/items/1",
+                "/definitions/a/items/1/not",
+                "/properties/name",
+                "/dependencies/id",
+                "/allOf/0",
+                "/allOf/0/if",
+                "/allOf/0/then",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_skip_and_leave() {
+        struct Depth {
+            depth: usize,
+            deepest: usize,
+        }
+        impl SchemaVisitor for Depth {
+            fn visit_schema(&mut self, pointer: &str, _: &Schema) -> bool {
+                self.depth += 1;
+                self.deepest = self.deepest.max(self.depth);
+                !pointer.starts_with("/properties")
+            }
+
+            fn leave_schema(&mut self, _: &str, _: &Schema) {
+                self.depth -= 1;
+            }
+        }
+        let root = schema(json!({
+            "properties": {"a": {"items": {"items": {}}}},
+            "anyOf": [{"not": {}}],
+        }));
+        let mut visitor = Depth { depth: 0, deepest: 0 };
+        walk(&root, &mut visitor);
+        assert_eq!((visitor.depth, visitor.deepest), (0, 3));
+    }
+
+    #[test]
+    fn test_walk_mut_transforms() {
+        let mut root = schema(json!({
+            "description": "root",
+            "definitions": {"Pet": {"description": "a pet", "type": "object"}},
+            "properties": {"pets": {"items": {"$ref": "#/definitions/Pet", "description": "pets"}}},
+            "additionalProperties": {"$ref": "#/definitions/Pet"},
+        }));
+        walk_mut(&mut root, &mut |_: &str, schema: &mut Schema| {
+            schema.description = None;
+            if let Some(reference) = &mut schema.reference {
+                *reference = reference.replace("#/definitions/", "#/$defs/");
+            }
+            true
+        });
+        assert_eq!(
+            serde_json::to_value(&root).unwrap(),
+            json!({
+                "definitions": {"Pet": {"type": "object"}},
+                "properties": {"pets": {"items": {"$ref": "#/$defs/Pet"}}},
+                "additionalProperties": {"$ref": "#/$defs/Pet"},
+            })
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! Benchmarks parsing and serializing the test documents.
+//!
+//! The petstores are small hand-written documents; the Books API Discovery
+//! document is a large real one. Run with `cargo bench -p gnostic-models`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use gnostic_models::compiler::ParseOptions;
+use gnostic_models::{discovery, openapiv2, openapiv3};
+use prost::Message;
+use std::fs;
+
+const TESTDATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../testdata");
+
+fn read_file(filename: &str) -> Vec<u8> {
+    let path = format!("{}/{}", TESTDATA_DIR, filename);
+    fs::read(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e))
+}
+
+fn bench_openapiv3(c: &mut Criterion) {
+    let bytes = read_file("petstore-v3.yaml");
+    let document = openapiv3::parse_document(&bytes).unwrap();
+    let mut group = c.benchmark_group("openapiv3/petstore");
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function("parse", |b| b.iter(|| openapiv3::parse_document(black_box(&bytes)).unwrap()));
+    group.bench_function("serialize", |b| b.iter(|| openapiv3::yaml_value(black_box(&document))));
+    group.finish();
+}
+
+fn bench_openapiv2(c: &mut Criterion) {
+    let bytes = read_file("petstore-v2.json");
+    let document = openapiv2::parse_document(&bytes).unwrap();
+    let mut group = c.benchmark_group("openapiv2/petstore");
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function("parse", |b| b.iter(|| openapiv2::parse_document(black_box(&bytes)).unwrap()));
+    group.bench_function("serialize", |b| b.iter(|| openapiv2::yaml_value(black_box(&document))));
+    group.finish();
+}
+
+fn bench_discovery(c: &mut Criterion) {
+    let bytes = read_file("books-discovery.json");
+    let document = discovery::parse_document(&bytes).unwrap();
+    let mut group = c.benchmark_group("discovery/books");
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function("parse", |b| b.iter(|| discovery::parse_document(black_box(&bytes)).unwrap()));
+    let json = std::str::from_utf8(&bytes).unwrap();
+    let options = ParseOptions::default();
+    group.bench_function("parse_borrowed", |b| {
+        b.iter(|| discovery::parse_document_borrowed(black_box(json), &options).unwrap())
+    });
+    group.bench_function("serialize", |b| b.iter(|| black_box(&document).encode_to_vec()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_openapiv3, bench_openapiv2, bench_discovery);
+criterion_main!(benches);
@@ -0,0 +1,84 @@
+//! Parsing of documents of any supported format.
+
+use gnostic_compiler::{read_info_from_bytes, CompilerError, ErrorGroup};
+use serde_yaml::Value as Yaml;
+
+/// A document of one of the supported formats.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyDocument {
+    /// An OpenAPI v2 (Swagger) document.
+    OpenApiV2(gnostic_openapiv2::Document),
+    /// An OpenAPI v3 document.
+    OpenApiV3(gnostic_openapiv3::Document),
+    /// A Google API Discovery document.
+    Discovery(gnostic_discovery::Document),
+}
+
+impl AnyDocument {
+    /// Returns the version the document declares, such as "3.0.3" for
+    /// OpenAPI v3 or "v1" for Discovery.
+    pub fn version(&self) -> &str {
+        match self {
+            AnyDocument::OpenApiV2(doc) => &doc.swagger,
+            AnyDocument::OpenApiV3(doc) => &doc.openapi,
+            AnyDocument::Discovery(doc) => &doc.discovery_version,
+        }
+    }
+}
+
+impl From<gnostic_openapiv2::Document> for AnyDocument {
+    fn from(doc: gnostic_openapiv2::Document) -> Self {
+        AnyDocument::OpenApiV2(doc)
+    }
+}
+
+impl From<gnostic_openapiv3::Document> for AnyDocument {
+    fn from(doc: gnostic_openapiv3::Document) -> Self {
+        AnyDocument::OpenApiV3(doc)
+    }
+}
+
+impl From<gnostic_discovery::Document> for AnyDocument {
+    fn from(doc: gnostic_discovery::Document) -> Self {
+        AnyDocument::Discovery(doc)
+    }
+}
+
+/// Parses a document from YAML/JSON bytes, telling its format by its
+/// top-level `swagger`, `openapi` or `discoveryVersion` field.
+pub fn parse_any(bytes: &[u8]) -> Result<AnyDocument, ErrorGroup> {
+    let yaml = read_info_from_bytes("", bytes).map_err(|e| ErrorGroup::new(vec![e]))?;
+    let has = |key: &str| yaml.as_mapping().is_some_and(|map| map.contains_key(Yaml::from(key)));
+    if has("swagger") {
+        gnostic_openapiv2::parse_document(bytes).map(AnyDocument::OpenApiV2)
+    } else if has("openapi") {
+        gnostic_openapiv3::parse_document(bytes).map(AnyDocument::OpenApiV3)
+    } else if has("discoveryVersion") {
+        gnostic_discovery::parse_document(bytes).map(AnyDocument::Discovery)
+    } else {
+        let message = "unknown document format: expected a swagger, openapi or discoveryVersion field";
+        Err(ErrorGroup::new(vec![CompilerError::Simple(message.to_string())]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_any() {
+        let doc = parse_any(b"openapi: 3.0.3\ninfo: {title: Pets, version: 1.0.0}\npaths: {}\n").unwrap();
+        assert!(matches!(doc, AnyDocument::OpenApiV3(_)));
+        assert_eq!(doc.version(), "3.0.3");
+
+        let doc = parse_any(br#"{"swagger": "2.0", "info": {"title": "Pets", "version": "1.0.0"}, "paths": {}}"#);
+        assert_eq!(doc.unwrap().version(), "2.0");
+
+        let doc = parse_any(br#"{"kind": "discovery#restDescription", "discoveryVersion": "v1"}"#).unwrap();
+        assert!(matches!(doc, AnyDocument::Discovery(_)));
+
+        let error = parse_any(b"asyncapi: 2.6.0\n").unwrap_err();
+        assert!(error.to_string().contains("unknown document format"));
+        assert!(parse_any(b"- openapi\n").is_err());
+    }
+}
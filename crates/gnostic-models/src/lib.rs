@@ -0,0 +1,16 @@
+//! Facade over the gnostic-models crates.
+//!
+//! This crate re-exports the compiler, OpenAPI v2, OpenAPI v3, Discovery and
+//! JSON Schema crates under one name, gathers their common types in
+//! [`prelude`], and parses documents of any of the supported formats with
+//! [`parse_any`], which tells the formats apart by their version fields.
+
+mod any;
+pub mod prelude;
+
+pub use any::{parse_any, AnyDocument};
+pub use gnostic_compiler as compiler;
+pub use gnostic_discovery as discovery;
+pub use gnostic_jsonschema as jsonschema;
+pub use gnostic_openapiv2 as openapiv2;
+pub use gnostic_openapiv3 as openapiv3;
@@ -0,0 +1,12 @@
+//! The types most programs need, for a single glob import.
+//!
+//! The documents of the formats are all named `Document` in their crates, so
+//! they are re-exported here under the names of their formats.
+
+pub use crate::{parse_any, AnyDocument};
+pub use gnostic_compiler::{CompilerError, ErrorGroup, NamedMap, ParseOptions, Parsed, Severity, ToYaml};
+pub use gnostic_discovery::Document as DiscoveryDocument;
+pub use gnostic_jsonschema::Schema as JsonSchema;
+pub use gnostic_openapiv2::Document as OpenApiV2Document;
+pub use gnostic_openapiv3::openapi_v3::{Operation, Parameter, PathItem, Reference, Response, Schema};
+pub use gnostic_openapiv3::{Document as OpenApiV3Document, HttpMethod};
@@ -0,0 +1,30 @@
+//! Integration tests parsing the test documents of every format.
+
+use gnostic_models::prelude::*;
+use std::fs;
+
+const TESTDATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../testdata");
+
+fn parse_file(filename: &str) -> AnyDocument {
+    let path = format!("{}/{}", TESTDATA_DIR, filename);
+    let bytes = fs::read(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e));
+    parse_any(&bytes).unwrap_or_else(|e| panic!("Failed to parse {}: {}", path, e))
+}
+
+#[test]
+fn test_parse_any_testdata() {
+    let AnyDocument::OpenApiV3(v3) = parse_file("petstore-v3.yaml") else {
+        panic!("not an OpenAPI v3 document");
+    };
+    let _: &OpenApiV3Document = &v3;
+    assert!(v3.paths.as_ref().is_some_and(|paths| !paths.names().is_empty()));
+
+    let AnyDocument::OpenApiV2(v2) = parse_file("petstore-v2.json") else {
+        panic!("not an OpenAPI v2 document");
+    };
+    assert_eq!(v2.swagger, "2.0");
+
+    let discovery = parse_file("books-discovery.json");
+    assert!(matches!(discovery, AnyDocument::Discovery(_)));
+    assert_eq!(discovery.version(), "v1");
+}
@@ -1,6 +1,23 @@
 use std::io::Result;
 use std::path::PathBuf;
 
+/// Fields of rarely used or sparse messages, boxed to keep parents small.
+const BOXED_FIELDS: &[&str] = &[
+    ".openapi.v2.PathItem.get",
+    ".openapi.v2.PathItem.put",
+    ".openapi.v2.PathItem.post",
+    ".openapi.v2.PathItem.delete",
+    ".openapi.v2.PathItem.options",
+    ".openapi.v2.PathItem.head",
+    ".openapi.v2.PathItem.patch",
+    ".openapi.v2.Schema.xml",
+    ".openapi.v2.Schema.external_docs",
+    ".openapi.v2.Schema.example",
+    ".openapi.v2.Operation.external_docs",
+    ".openapi.v2.Tag.external_docs",
+    ".openapi.v2.Document.external_docs",
+];
+
 fn main() -> Result<()> {
     let proto_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .parent()
@@ -11,10 +28,37 @@ fn main() -> Result<()> {
 
     let proto_files = &[proto_root.join("openapiv2.proto")];
 
-    let include_dirs = &[proto_root.clone()];
+    let include_dirs = &[&proto_root];
+
+    // Serde support is opt-in through the `serde` feature. Embedded
+    // google.protobuf.Any values have no serde impls; their YAML text is kept.
+    let mut config = prost_build::Config::new();
+    config
+        .type_attribute(
+            ".",
+            "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]",
+        )
+        .field_attribute(".openapi.v2.Any.value", "#[cfg_attr(feature = \"serde\", serde(skip))]");
+
+    // Optional messages that most documents leave out are boxed, so that they
+    // cost a pointer rather than their full size in every parent.
+    for path in BOXED_FIELDS {
+        config.boxed(path);
+    }
 
-    prost_build::Config::new()
-        .compile_protos(proto_files, include_dirs)?;
+    // The messages listed implement Eq and Hash by hand in the crate root.
+    let implemented = &[
+        ".openapi.v2.Any",
+        ".openapi.v2.FormDataParameterSubSchema",
+        ".openapi.v2.Header",
+        ".openapi.v2.HeaderParameterSubSchema",
+        ".openapi.v2.PathParameterSubSchema",
+        ".openapi.v2.PrimitivesItems",
+        ".openapi.v2.QueryParameterSubSchema",
+        ".openapi.v2.Schema",
+    ];
+    gnostic_build::derive_eq_and_hash(&mut config, proto_files, include_dirs, implemented)?;
+    config.compile_protos(proto_files, include_dirs)?;
 
     for proto in proto_files {
         println!("cargo:rerun-if-changed={}", proto.display());
@@ -0,0 +1,347 @@
+//! Display functionality for OpenAPI v2 structures.
+
+use std::fmt;
+use crate::openapi_v2::*;
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe_document(""))
+    }
+}
+
+impl fmt::Display for PathItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe_path_item(""))
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe_operation(""))
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe_schema(""))
+    }
+}
+
+impl Document {
+    /// Returns a string representation of the document with the given indentation.
+    pub fn describe_document(&self, indent: &str) -> String {
+        let mut result = String::new();
+        let next_indent = format!("{}  ", indent);
+        let double_indent = format!("{}    ", indent);
+
+        result.push_str(&format!("{}swagger: {}\n", indent, self.swagger));
+        if let Some(ref info) = self.info {
+            result.push_str(&format!("{}info:\n", indent));
+            result.push_str(&format!("{}title: {}\n", next_indent, info.title));
+            result.push_str(&format!("{}version: {}\n", next_indent, info.version));
+        }
+        if !self.host.is_empty() {
+            result.push_str(&format!("{}host: {}\n", indent, self.host));
+        }
+        if !self.base_path.is_empty() {
+            result.push_str(&format!("{}basePath: {}\n", indent, self.base_path));
+        }
+        if let Some(ref paths) = self.paths {
+            result.push_str(&format!("{}paths:\n", indent));
+            for path in &paths.path {
+                result.push_str(&format!("{}{}:\n", next_indent, path.name));
+                if let Some(ref item) = path.value {
+                    result.push_str(&item.describe_path_item(&double_indent));
+                }
+            }
+        }
+        if let Some(ref definitions) = self.definitions {
+            result.push_str(&format!("{}definitions:\n", indent));
+            for definition in &definitions.additional_properties {
+                result.push_str(&format!("{}{}:\n", next_indent, definition.name));
+                if let Some(ref value) = definition.value {
+                    result.push_str(&value.describe_schema(&double_indent));
+                }
+            }
+        }
+        if !self.tags.is_empty() {
+            let names: Vec<&str> = self.tags.iter().map(|t| t.name.as_str()).collect();
+            result.push_str(&format!("{}tags: {:?}\n", indent, names));
+        }
+
+        result
+    }
+}
+
+impl PathItem {
+    /// Returns a string representation of the path item with the given indentation.
+    pub fn describe_path_item(&self, indent: &str) -> String {
+        let mut result = String::new();
+        let next_indent = format!("{}  ", indent);
+
+        if !self.r#ref.is_empty() {
+            result.push_str(&format!("{}$ref: {}\n", indent, self.r#ref));
+        }
+        for parameter in &self.parameters {
+            result.push_str(&format!("{}parameter: {}\n", indent, describe_parameter(parameter)));
+        }
+        let operations = [
+            ("get", &self.get),
+            ("put", &self.put),
+            ("post", &self.post),
+            ("delete", &self.delete),
+            ("options", &self.options),
+            ("head", &self.head),
+            ("patch", &self.patch),
+        ];
+        for (method, operation) in operations {
+            if let Some(operation) = operation {
+                result.push_str(&format!("{}{}:\n", indent, method));
+                result.push_str(&operation.describe_operation(&next_indent));
+            }
+        }
+
+        result
+    }
+}
+
+impl Operation {
+    /// Returns a string representation of the operation with the given indentation.
+    pub fn describe_operation(&self, indent: &str) -> String {
+        let mut result = String::new();
+        let next_indent = format!("{}  ", indent);
+
+        if !self.operation_id.is_empty() {
+            result.push_str(&format!("{}operationId: {}\n", indent, self.operation_id));
+        }
+        if !self.summary.is_empty() {
+            result.push_str(&format!("{}summary: {}\n", indent, self.summary));
+        }
+        if !self.tags.is_empty() {
+            result.push_str(&format!("{}tags: {:?}\n", indent, self.tags));
+        }
+        if self.deprecated {
+            result.push_str(&format!("{}deprecated: true\n", indent));
+        }
+        for parameter in &self.parameters {
+            result.push_str(&format!("{}parameter: {}\n", indent, describe_parameter(parameter)));
+        }
+        if let Some(ref responses) = self.responses {
+            result.push_str(&format!("{}responses:\n", indent));
+            for response in &responses.response_code {
+                let text = match response.value.as_ref().and_then(|v| v.oneof.as_ref()) {
+                    Some(response_value::Oneof::Response(r)) => r.description.clone(),
+                    Some(response_value::Oneof::JsonReference(r)) => format!("$ref {}", r.r#ref),
+                    None => String::new(),
+                };
+                result.push_str(&format!("{}{}: {}\n", next_indent, response.name, text));
+            }
+        }
+
+        result
+    }
+}
+
+impl Schema {
+    /// Returns a string representation of the schema with the given indentation.
+    pub fn describe_schema(&self, indent: &str) -> String {
+        let mut result = String::new();
+        let next_indent = format!("{}  ", indent);
+        let double_indent = format!("{}    ", indent);
+
+        if !self.r#ref.is_empty() {
+            result.push_str(&format!("{}$ref: {}\n", indent, self.r#ref));
+        }
+        if !self.title.is_empty() {
+            result.push_str(&format!("{}title: {}\n", indent, self.title));
+        }
+        if let Some(ref type_item) = self.r#type {
+            result.push_str(&format!("{}type: {}\n", indent, type_item.value.join(", ")));
+        }
+        if !self.format.is_empty() {
+            result.push_str(&format!("{}format: {}\n", indent, self.format));
+        }
+        if !self.description.is_empty() {
+            result.push_str(&format!("{}description: {}\n", indent, self.description));
+        }
+        if !self.required.is_empty() {
+            result.push_str(&format!("{}required: {:?}\n", indent, self.required));
+        }
+        if !self.r#enum.is_empty() {
+            result.push_str(&format!("{}enumeration:\n", indent));
+            for value in &self.r#enum {
+                result.push_str(&format!("{}{}\n", next_indent, value.yaml.trim_end()));
+            }
+        }
+        if let Some(ref items) = self.items {
+            result.push_str(&format!("{}items:\n", indent));
+            for item in &items.schema {
+                result.push_str(&item.describe_schema(&next_indent));
+            }
+        }
+        if let Some(ref properties) = self.properties {
+            result.push_str(&format!("{}properties:\n", indent));
+            for property in &properties.additional_properties {
+                result.push_str(&format!("{}{}:\n", next_indent, property.name));
+                if let Some(ref value) = property.value {
+                    result.push_str(&value.describe_schema(&double_indent));
+                }
+            }
+        }
+        if let Some(ref additional_properties) = self.additional_properties {
+            match &additional_properties.oneof {
+                Some(additional_properties_item::Oneof::Schema(s)) => {
+                    result.push_str(&format!("{}additionalProperties:\n", indent));
+                    result.push_str(&s.describe_schema(&next_indent));
+                }
+                Some(additional_properties_item::Oneof::Boolean(b)) => {
+                    result.push_str(&format!("{}additionalProperties: {}\n", indent, b));
+                }
+                None => {}
+            }
+        }
+        if !self.all_of.is_empty() {
+            result.push_str(&format!("{}allOf:\n", indent));
+            for s in &self.all_of {
+                result.push_str(&s.describe_schema(&next_indent));
+                result.push_str(&format!("{}-\n", indent));
+            }
+        }
+
+        result
+    }
+}
+
+/// Describes a parameter on one line as "name (in)", or its reference.
+fn describe_parameter(value: &ParametersItem) -> String {
+    let parameter = match &value.oneof {
+        Some(parameters_item::Oneof::Parameter(p)) => p,
+        Some(parameters_item::Oneof::JsonReference(r)) => return format!("$ref {}", r.r#ref),
+        None => return String::new(),
+    };
+    let (name, location, required) = match &parameter.oneof {
+        Some(parameter::Oneof::BodyParameter(p)) => (&p.name, &p.r#in, p.required),
+        Some(parameter::Oneof::NonBodyParameter(p)) => match &p.oneof {
+            Some(non_body_parameter::Oneof::HeaderParameterSubSchema(p)) => (&p.name, &p.r#in, p.required),
+            Some(non_body_parameter::Oneof::FormDataParameterSubSchema(p)) => (&p.name, &p.r#in, p.required),
+            Some(non_body_parameter::Oneof::QueryParameterSubSchema(p)) => (&p.name, &p.r#in, p.required),
+            Some(non_body_parameter::Oneof::PathParameterSubSchema(p)) => (&p.name, &p.r#in, p.required),
+            None => return String::new(),
+        },
+        None => return String::new(),
+    };
+    if required {
+        format!("{} ({}, required)", name, location)
+    } else {
+        format!("{} ({})", name, location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parse_document;
+
+    #[test]
+    fn test_document_display() {
+        let source = br#"
+swagger: "2.0"
+info:
+  title: Pets
+  version: "1.0"
+basePath: /v1
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      tags: [pets]
+definitions:
+  Pet:
+    type: object
+    required: [id]
+"#;
+        let doc = parse_document(source).unwrap();
+        let expected = "\
+swagger: 2.0
+info:
+  title: Pets
+  version: 1.0
+basePath: /v1
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      tags: [\"pets\"]
+definitions:
+  Pet:
+    type: object
+    required: [\"id\"]
+";
+        assert_eq!(doc.to_string(), expected);
+    }
+
+    #[test]
+    fn test_operation_display() {
+        let parameter = ParametersItem {
+            oneof: Some(parameters_item::Oneof::Parameter(Parameter {
+                oneof: Some(parameter::Oneof::NonBodyParameter(NonBodyParameter {
+                    oneof: Some(non_body_parameter::Oneof::PathParameterSubSchema(
+                        PathParameterSubSchema {
+                            name: "id".to_string(),
+                            r#in: "path".to_string(),
+                            required: true,
+                            ..PathParameterSubSchema::default()
+                        },
+                    )),
+                })),
+            })),
+        };
+        let response = NamedResponseValue {
+            name: "404".to_string(),
+            value: Some(ResponseValue {
+                oneof: Some(response_value::Oneof::JsonReference(JsonReference {
+                    r#ref: "#/responses/NotFound".to_string(),
+                    ..JsonReference::default()
+                })),
+            }),
+        };
+        let operation = Operation {
+            parameters: vec![parameter],
+            responses: Some(Responses {
+                response_code: vec![response],
+                ..Responses::default()
+            }),
+            ..Operation::default()
+        };
+        assert_eq!(
+            operation.to_string(),
+            "parameter: id (path, required)\nresponses:\n  404: $ref #/responses/NotFound\n"
+        );
+    }
+
+    #[test]
+    fn test_schema_display() {
+        let string = Schema {
+            r#type: Some(TypeItem {
+                value: vec!["string".to_string()],
+            }),
+            ..Schema::default()
+        };
+        let schema = Schema {
+            r#type: Some(TypeItem {
+                value: vec!["array".to_string()],
+            }),
+            items: Some(ItemsItem {
+                schema: vec![string.clone()],
+            }),
+            additional_properties: Some(Box::new(AdditionalPropertiesItem {
+                oneof: Some(additional_properties_item::Oneof::Boolean(false)),
+            })),
+            ..Schema::default()
+        };
+        assert_eq!(
+            schema.to_string(),
+            "type: array\nitems:\n  type: string\nadditionalProperties: false\n"
+        );
+    }
+}
@@ -1,16 +1,42 @@
 //! OpenAPI v2 (Swagger) document parsing.
 
-use gnostic_compiler::{Context, ErrorGroup, read_info_from_bytes, read_bytes_for_file};
+use gnostic_compiler::{
+    document_to_yaml, read_bytes_for_file, read_info_from_bytes, CompilerError, EmitOptions, ErrorGroup, ParseOptions,
+    Parsed, ToYaml,
+};
+use prost::Message;
 use std::sync::Arc;
 use serde_yaml::Value as Yaml;
 
 use crate::openapi_v2::Document;
 use crate::parser::Parser;
+use crate::resolver::parse;
+use regex::Regex;
 
 /// Parses an OpenAPI v2 (Swagger) document from YAML/JSON bytes.
 pub fn parse_document(bytes: &[u8]) -> Result<Document, ErrorGroup> {
-    let yaml = read_info_from_bytes("", bytes)
-        .map_err(|e| ErrorGroup::new(vec![e.into()]))?;
+    parse_document_with_options(bytes, &ParseOptions::default()).map(Parsed::into_value)
+}
+
+/// Parses an OpenAPI v2 (Swagger) document with the given options.
+///
+/// Warnings reported in lenient mode are returned alongside the document; if the
+/// parse fails they are included in the returned ErrorGroup. When raw info is
+/// requested, pass it to [`EmitOptions::with_raw_info`] to write fields that the
+/// model does not represent back out; likewise pass comments to
+/// [`EmitOptions::with_comments`] to write them back out.
+pub fn parse_document_with_options(
+    bytes: &[u8],
+    options: &ParseOptions,
+) -> Result<Parsed<Document>, ErrorGroup> {
+    parse_document_at("", bytes, options)
+}
+
+/// Parses a document read from `base`, which locates relative external
+/// references when the options resolve references.
+fn parse_document_at(base: &str, bytes: &[u8], options: &ParseOptions) -> Result<Parsed<Document>, ErrorGroup> {
+    let yaml = gnostic_compiler::read_info_from_bytes_with_limits("", bytes, &options.limits())
+        .map_err(|e| ErrorGroup::new(vec![e]))?;
 
     // Handle document node wrapper
     let node = if let Yaml::Sequence(ref content) = yaml {
@@ -23,13 +49,168 @@ pub fn parse_document(bytes: &[u8]) -> Result<Document, ErrorGroup> {
         &yaml
     };
 
-    let context = Arc::new(Context::root("$"));
-    Parser::parse_document(node, &context)
+    let context = Arc::new(gnostic_compiler::Context::root_with_options("$", options.clone()));
+    let resolved = gnostic_compiler::resolve_for_parse(node, base, &context);
+    let node: &Yaml = &resolved;
+    let mut parsed = context.finish(Parser::parse_document(node, &context))?;
+    if options.preserve_raw_info {
+        parsed.raw_info = Some(gnostic_compiler::RawInfo::new(node.clone(), parsed.value.to_yaml()));
+    }
+    if options.preserve_comments {
+        parsed.comments = Some(gnostic_compiler::Comments::from_source(&String::from_utf8_lossy(bytes)));
+    }
+    Ok(parsed)
 }
 
 /// Parses an OpenAPI v2 document from a file path or URL.
+///
+/// When the document cache is enabled, a file whose content has already been
+/// parsed returns the cached document.
 pub fn parse_document_from_file(path: &str) -> Result<Document, ErrorGroup> {
     let bytes = read_bytes_for_file(path)
-        .map_err(|e| ErrorGroup::new(vec![e.into()]))?;
-    parse_document(&bytes)
+        .map_err(|e| ErrorGroup::new(vec![e]))?;
+    gnostic_compiler::cached_document(&bytes, || {
+        parse_document_at(path, &bytes, &ParseOptions::default()).map(Parsed::into_value)
+    })
+}
+
+/// Parses a document from a file path or URL with the given options.
+/// Relative external references are resolved against the path.
+pub fn parse_document_from_file_with_options(
+    path: &str,
+    options: &ParseOptions,
+) -> Result<Parsed<Document>, ErrorGroup> {
+    let bytes = read_bytes_for_file(path)
+        .map_err(|e| ErrorGroup::new(vec![e]))?;
+    parse_document_at(path, &bytes, options)
+}
+
+/// Converts a Document to YAML bytes.
+pub fn yaml_value(doc: &Document) -> Vec<u8> {
+    yaml_value_with_options(doc, &EmitOptions::default())
+}
+
+/// Converts a Document to YAML bytes with the given options.
+pub fn yaml_value_with_options(doc: &Document, options: &EmitOptions) -> Vec<u8> {
+    gnostic_compiler::emit_yaml(&document_to_yaml(doc, options), options).into_bytes()
+}
+
+/// Converts a Document to pretty-printed JSON bytes.
+pub fn json_value(doc: &Document) -> Vec<u8> {
+    json_value_with_options(doc, &EmitOptions::default())
+}
+
+/// Converts a Document to pretty-printed JSON bytes with the given options.
+pub fn json_value_with_options(doc: &Document, options: &EmitOptions) -> Vec<u8> {
+    serde_json::to_vec_pretty(&gnostic_compiler::yaml_to_json(&document_to_yaml(doc, options))).unwrap_or_default()
+}
+
+impl Document {
+    /// Returns the graph of references between the document's definitions,
+    /// parameters and responses.
+    pub fn reference_graph(&self) -> gnostic_compiler::ReferenceGraph {
+        gnostic_compiler::ReferenceGraph::new(&self.to_yaml(), &[&["definitions"], &["parameters"], &["responses"]])
+    }
+
+    /// Returns the semantic differences from this version of the document to
+    /// `other`, located by JSON pointers.
+    pub fn diff(&self, other: &Document) -> gnostic_compiler::Changeset {
+        gnostic_compiler::diff_documents(&self.to_yaml(), &other.to_yaml())
+    }
+
+    /// Returns the vocabulary of the document: the names of its schemas,
+    /// properties and parameters, and its operation IDs.
+    pub fn vocabulary(&self) -> gnostic_compiler::Vocabulary {
+        gnostic_compiler::Vocabulary::new(&self.to_yaml())
+    }
+
+    /// Splits the document into a root file and files holding its
+    /// components, returning the path and contents of each file.
+    pub fn split(&self, options: &gnostic_compiler::SplitOptions) -> Vec<(String, Vec<u8>)> {
+        gnostic_compiler::split_document(&self.to_yaml(), &[&["definitions"], &["parameters"], &["responses"]], options)
+    }
+
+    /// Returns the document with only the operations tagged with one of
+    /// `tags`, without the definitions that only other operations used.
+    pub fn filter_by_tags<S: AsRef<str>>(&self, tags: &[S]) -> Result<Document, ErrorGroup> {
+        self.reduce(&gnostic_compiler::filter_by_tags(&self.to_yaml(), tags))
+    }
+
+    /// Returns the document without the paths matching `pattern` and the
+    /// definitions that only they used.
+    pub fn remove_paths_matching(&self, pattern: &Regex) -> Result<Document, ErrorGroup> {
+        self.reduce(&gnostic_compiler::remove_paths_matching(&self.to_yaml(), pattern))
+    }
+
+    /// Returns the document without the objects marked with a true extension
+    /// starting with `prefix`, such as "x-internal", the other extensions
+    /// starting with it, and the definitions that only removed objects used.
+    pub fn strip_extension_prefix(&self, prefix: &str) -> Result<Document, ErrorGroup> {
+        self.reduce(&gnostic_compiler::strip_extension_prefix(&self.to_yaml(), prefix))
+    }
+
+    /// Parses a filtered document without its unused definitions.
+    fn reduce(&self, node: &Yaml) -> Result<Document, ErrorGroup> {
+        parse(&gnostic_compiler::remove_unused_components(node, &[&["definitions"], &["parameters"], &["responses"]]))
+    }
+
+    /// Applies an RFC 6902 JSON Patch, in JSON or YAML, to the document and
+    /// parses the result, so that patches that break the document fail.
+    pub fn apply_json_patch(&self, patch: &[u8]) -> Result<Document, ErrorGroup> {
+        let patch = read_info_from_bytes("", patch).map_err(ErrorGroup::from)?;
+        parse(&gnostic_compiler::apply_json_patch(&self.to_yaml(), &patch).map_err(ErrorGroup::from)?)
+    }
+
+    /// Applies an RFC 7386 JSON Merge Patch, in JSON or YAML, to the document
+    /// and parses the result.
+    pub fn apply_merge_patch(&self, patch: &[u8]) -> Result<Document, ErrorGroup> {
+        let patch = read_info_from_bytes("", patch).map_err(ErrorGroup::from)?;
+        parse(&gnostic_compiler::apply_merge_patch(&self.to_yaml(), &patch))
+    }
+
+    /// Converts the document to a Postman Collection v2.1, with a folder for each
+    /// tag and a request for each operation.
+    pub fn to_postman_collection(&self) -> serde_json::Value {
+        gnostic_compiler::postman_collection(&self.to_yaml())
+    }
+
+    /// Encodes the document as a binary protocol buffer.
+    pub fn to_proto_bytes(&self) -> Vec<u8> {
+        self.encode_to_vec()
+    }
+
+    /// Decodes a document from a binary protocol buffer.
+    pub fn from_proto_bytes(bytes: &[u8]) -> Result<Document, ErrorGroup> {
+        Document::decode(bytes).map_err(|e| ErrorGroup::from(CompilerError::from(e)))
+    }
+
+    /// Encodes the document as CBOR.
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor_bytes(&self) -> Result<Vec<u8>, ErrorGroup> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes)
+            .map(|_| bytes)
+            .map_err(|e| ErrorGroup::from(CompilerError::Encoding(format!("CBOR: {}", e))))
+    }
+
+    /// Decodes a document from CBOR.
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor_bytes(bytes: &[u8]) -> Result<Document, ErrorGroup> {
+        ciborium::from_reader(bytes)
+            .map_err(|e| ErrorGroup::from(CompilerError::Encoding(format!("CBOR: {}", e))))
+    }
+
+    /// Encodes the document as MessagePack, with fields keyed by name.
+    #[cfg(feature = "msgpack")]
+    pub fn to_msgpack_bytes(&self) -> Result<Vec<u8>, ErrorGroup> {
+        rmp_serde::to_vec_named(self)
+            .map_err(|e| ErrorGroup::from(CompilerError::Encoding(format!("MessagePack: {}", e))))
+    }
+
+    /// Decodes a document from MessagePack.
+    #[cfg(feature = "msgpack")]
+    pub fn from_msgpack_bytes(bytes: &[u8]) -> Result<Document, ErrorGroup> {
+        rmp_serde::from_slice(bytes)
+            .map_err(|e| ErrorGroup::from(CompilerError::Encoding(format!("MessagePack: {}", e))))
+    }
 }
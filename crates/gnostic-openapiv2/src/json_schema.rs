@@ -0,0 +1,269 @@
+//! Conversion between OpenAPI v2 schemas and JSON Schemas.
+//!
+//! OpenAPI 2.0 schemas are a subset of JSON Schema Draft 4 without a "null"
+//! type; tools mark nullable schemas with the `x-nullable` extension
+//! instead. These functions convert between the protobuf [`Schema`] and
+//! [`gnostic_jsonschema::Schema`] so that the JSON Schema utilities apply to
+//! definitions. Keywords without a counterpart on the other side, such as
+//! discriminator and xml or anyOf and oneOf, are dropped. Numeric exclusive
+//! bounds become bounds with exclusive flags, and true and false schemas
+//! become `{}` and `{"not": {}}`. As when writing YAML, zero numbers are
+//! treated as unset.
+
+use crate::openapi_v2::*;
+use gnostic_compiler::marshal;
+use gnostic_jsonschema::{
+    NumberOrBoolean, Schema as JsonSchema, SchemaNumber, SchemaOrBoolean, SchemaOrSchemaArray, StringOrStringArray,
+};
+
+/// The extension that marks a schema as nullable.
+const NULLABLE: &str = "x-nullable";
+
+/// Returns the JSON Schema for a schema; `x-nullable: true` adds "null" to its type.
+pub fn to_json_schema(schema: &Schema) -> JsonSchema {
+    if !schema.r#ref.is_empty() {
+        return JsonSchema::reference(&schema.r#ref);
+    }
+    let mut types: Vec<String> = schema.r#type.as_ref().map(|types| types.value.clone()).unwrap_or_default();
+    let nullable = schema.vendor_extension.iter().any(|extension| {
+        extension.name == NULLABLE && extension.value.as_ref().and_then(any_to_json) == Some(true.into())
+    });
+    if nullable && !types.is_empty() && !types.iter().any(|name| name == "null") {
+        types.push("null".to_string());
+    }
+    let type_value = match types.len() {
+        0 => None,
+        1 => types.pop().map(StringOrStringArray::String),
+        _ => Some(StringOrStringArray::Array(types)),
+    };
+    JsonSchema {
+        title: non_empty(&schema.title),
+        description: non_empty(&schema.description),
+        default: schema.default.as_ref().and_then(any_to_json),
+        examples: schema.example.as_deref().and_then(any_to_json).map(|example| vec![example]),
+        read_only: schema.read_only.then_some(true),
+        multiple_of: number(schema.multiple_of),
+        maximum: number(schema.maximum),
+        exclusive_maximum: schema.exclusive_maximum.then_some(NumberOrBoolean::Boolean(true)),
+        minimum: number(schema.minimum),
+        exclusive_minimum: schema.exclusive_minimum.then_some(NumberOrBoolean::Boolean(true)),
+        max_length: non_zero(schema.max_length),
+        min_length: non_zero(schema.min_length),
+        pattern: non_empty(&schema.pattern),
+        items: schema.items.as_ref().and_then(|items| match items.schema.as_slice() {
+            [] => None,
+            [item] => Some(Box::new(SchemaOrSchemaArray::Schema(to_json_schema(item).into()))),
+            items => Some(Box::new(SchemaOrSchemaArray::Array(schemas(items)))),
+        }),
+        max_items: non_zero(schema.max_items),
+        min_items: non_zero(schema.min_items),
+        unique_items: schema.unique_items.then_some(true),
+        max_properties: non_zero(schema.max_properties),
+        min_properties: non_zero(schema.min_properties),
+        required: (!schema.required.is_empty()).then(|| schema.required.clone()),
+        additional_properties: schema.additional_properties.as_ref().and_then(|additional| {
+            match &additional.oneof {
+                Some(additional_properties_item::Oneof::Schema(schema)) => {
+                    Some(SchemaOrBoolean::Schema(Box::new(to_json_schema(schema))))
+                }
+                Some(additional_properties_item::Oneof::Boolean(value)) => Some(SchemaOrBoolean::Boolean(*value)),
+                None => None,
+            }
+        }),
+        properties: schema.properties.as_ref().map(|properties| {
+            properties
+                .additional_properties
+                .iter()
+                .map(|property| {
+                    let value = property.value.as_ref().map(to_json_schema).unwrap_or_default();
+                    (property.name.clone(), value.into())
+                })
+                .collect()
+        }),
+        enumeration: (!schema.r#enum.is_empty()).then(|| schema.r#enum.iter().filter_map(any_to_json).collect()),
+        type_value,
+        format: non_empty(&schema.format),
+        all_of: (!schema.all_of.is_empty()).then(|| schemas(&schema.all_of)),
+        ..JsonSchema::default()
+    }
+}
+
+/// Returns the schema for a JSON Schema.
+///
+/// A "null" type becomes `x-nullable: true` and a const becomes an enum of
+/// one value.
+pub fn from_json_schema(schema: &JsonSchema) -> Schema {
+    if let Some(reference) = &schema.reference {
+        return Schema {
+            r#ref: reference.clone(),
+            ..Schema::default()
+        };
+    }
+    let mut types: Vec<String> = match &schema.type_value {
+        Some(StringOrStringArray::String(name)) => vec![name.clone()],
+        Some(StringOrStringArray::Array(names)) => names.clone(),
+        None => Vec::new(),
+    };
+    let nullable = types.iter().any(|name| name == "null");
+    types.retain(|name| name != "null");
+    let (maximum, exclusive_maximum) = schema.upper_bound().unwrap_or_default();
+    let (minimum, exclusive_minimum) = schema.lower_bound().unwrap_or_default();
+    let values = schema.enumeration.iter().flatten().chain(&schema.const_value);
+    Schema {
+        format: schema.format.clone().unwrap_or_default(),
+        title: schema.title.clone().unwrap_or_default(),
+        description: schema.description.clone().unwrap_or_default(),
+        default: schema.default.as_ref().map(json_to_any),
+        multiple_of: schema.multiple_of.as_ref().map(SchemaNumber::as_f64).unwrap_or_default(),
+        maximum,
+        exclusive_maximum,
+        minimum,
+        exclusive_minimum,
+        max_length: schema.max_length.unwrap_or_default(),
+        min_length: schema.min_length.unwrap_or_default(),
+        pattern: schema.pattern.clone().unwrap_or_default(),
+        max_items: schema.max_items.unwrap_or_default(),
+        min_items: schema.min_items.unwrap_or_default(),
+        unique_items: schema.unique_items.unwrap_or_default(),
+        max_properties: schema.max_properties.unwrap_or_default(),
+        min_properties: schema.min_properties.unwrap_or_default(),
+        required: schema.required.clone().unwrap_or_default(),
+        r#enum: values.map(json_to_any).collect(),
+        additional_properties: schema.additional_properties.as_ref().map(|additional| {
+            Box::new(AdditionalPropertiesItem {
+                oneof: Some(match additional {
+                    SchemaOrBoolean::Schema(schema) => {
+                        additional_properties_item::Oneof::Schema(Box::new(from_json_schema(schema)))
+                    }
+                    SchemaOrBoolean::Boolean(value) => additional_properties_item::Oneof::Boolean(*value),
+                }),
+            })
+        }),
+        r#type: (!types.is_empty()).then_some(TypeItem { value: types }),
+        items: schema.items.as_deref().map(|items| ItemsItem {
+            schema: match items {
+                SchemaOrSchemaArray::Schema(item) => vec![from_subschema(item)],
+                SchemaOrSchemaArray::Array(items) => items.iter().map(from_subschema).collect(),
+            },
+        }),
+        all_of: schema.all_of.iter().flatten().map(from_subschema).collect(),
+        properties: schema.properties.as_ref().map(|properties| Properties {
+            additional_properties: properties
+                .iter()
+                .map(|(name, property)| NamedSchema {
+                    name: name.clone(),
+                    value: Some(from_subschema(property)),
+                })
+                .collect(),
+        }),
+        read_only: schema.read_only.unwrap_or_default(),
+        example: schema.examples.iter().flatten().next().map(json_to_any).map(Box::new),
+        vendor_extension: nullable
+            .then(|| NamedAny {
+                name: NULLABLE.to_string(),
+                value: Some(json_to_any(&true.into())),
+            })
+            .into_iter()
+            .collect(),
+        ..Schema::default()
+    }
+}
+
+fn schemas(schemas: &[Schema]) -> Vec<SchemaOrBoolean> {
+    schemas.iter().map(|schema| to_json_schema(schema).into()).collect()
+}
+
+fn from_subschema(schema: &SchemaOrBoolean) -> Schema {
+    from_json_schema(&schema.to_schema())
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn non_zero(value: i64) -> Option<i64> {
+    (value != 0).then_some(value)
+}
+
+/// Returns a JSON Schema number, preferring integers, or None for zero.
+fn number(value: f64) -> Option<SchemaNumber> {
+    if value == 0.0 {
+        None
+    } else if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+        Some(SchemaNumber::Integer(value as i64))
+    } else {
+        Some(SchemaNumber::Float(value))
+    }
+}
+
+fn any_to_json(any: &Any) -> Option<serde_json::Value> {
+    serde_yaml::from_str(&any.yaml).ok()
+}
+
+fn json_to_any(value: &serde_json::Value) -> Any {
+    let node = serde_yaml::to_value(value).unwrap_or_default();
+    Any {
+        value: None,
+        yaml: String::from_utf8_lossy(&marshal(&node)).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let json: JsonSchema = serde_json::from_value(serde_json::json!({
+            "type": ["integer", "null"],
+            "maximum": 10,
+            "exclusiveMaximum": true,
+            "multipleOf": 0.5,
+            "default": 2,
+            "items": [{"$ref": "#/definitions/Pet"}, {"type": "string"}],
+            "properties": {"id": {"type": "integer"}},
+            "additionalProperties": false,
+            "allOf": [{"required": ["id"]}],
+        }))
+        .unwrap();
+        let schema = from_json_schema(&json);
+        assert_eq!(schema.r#type.as_ref().map(|types| types.value.clone()), Some(vec!["integer".to_string()]));
+        assert_eq!(schema.vendor_extension[0].name, "x-nullable");
+        assert!(schema.exclusive_maximum);
+        assert_eq!(to_json_schema(&schema), json);
+    }
+
+    #[test]
+    fn test_draft6_forms() {
+        let json: JsonSchema = serde_json::from_value(serde_json::json!({
+            "minimum": 1,
+            "exclusiveMinimum": 2.5,
+            "items": true,
+            "properties": {"id": false},
+        }))
+        .unwrap();
+        let schema = from_json_schema(&json);
+        assert_eq!((schema.minimum, schema.exclusive_minimum), (2.5, true));
+        assert_eq!(
+            serde_json::to_value(to_json_schema(&schema)).unwrap(),
+            serde_json::json!({
+                "minimum": 2.5,
+                "exclusiveMinimum": true,
+                "items": {},
+                "properties": {"id": {}},
+            })
+        );
+    }
+
+    #[test]
+    fn test_const_and_references() {
+        let json: JsonSchema = serde_json::from_value(serde_json::json!({"const": "cat", "oneOf": [{}]})).unwrap();
+        assert_eq!(
+            serde_json::to_value(to_json_schema(&from_json_schema(&json))).unwrap(),
+            serde_json::json!({"enum": ["cat"]})
+        );
+        let reference = from_json_schema(&JsonSchema::reference("#/definitions/Pet"));
+        assert_eq!(reference.r#ref, "#/definitions/Pet");
+        assert_eq!(to_json_schema(&reference), JsonSchema::reference("#/definitions/Pet"));
+    }
+}
@@ -3,12 +3,116 @@
 //! This crate provides Protocol Buffer models and parsing for OpenAPI v2/Swagger specifications.
 
 pub mod parser;
+pub mod display;
 pub mod document;
+pub mod json_schema;
+pub mod lint;
+pub mod named;
+pub mod resolver;
+mod to_yaml;
+pub mod validator;
 
 /// Generated Protocol Buffer code for OpenAPI v2.
+#[allow(clippy::large_enum_variant)]
 pub mod openapi_v2 {
     include!(concat!(env!("OUT_DIR"), "/openapi.v2.rs"));
+
+    impl Eq for Any {}
+
+    impl std::hash::Hash for Any {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.value.as_ref().map(|value| (&value.type_url, &value.value)).hash(state);
+            self.yaml.hash(state);
+        }
+    }
+
+    /// Implements `Eq` and `Hash` for a message with floating-point fields,
+    /// hashing the floats by their bits. Naming every field in the pattern
+    /// keeps the hash in step with the message when fields are added.
+    macro_rules! eq_and_hash {
+        ($message:ident { floats: [$($float:ident),*], fields: [$($field:ident),*] }) => {
+            impl Eq for $message {}
+
+            impl std::hash::Hash for $message {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    let $message { $($float,)* $($field,)* } = self;
+                    $(hash_float(*$float, state);)*
+                    $($field.hash(state);)*
+                }
+            }
+        };
+    }
+
+    /// Hashes `value` by its bits, with negative zero as zero since the two
+    /// compare equal. NaN is not equal to itself, so it needs no care.
+    fn hash_float<H: std::hash::Hasher>(value: f64, state: &mut H) {
+        let value = if value == 0.0 { 0.0 } else { value };
+        std::hash::Hash::hash(&value.to_bits(), state);
+    }
+
+    eq_and_hash!(FormDataParameterSubSchema {
+        floats: [maximum, minimum, multiple_of],
+        fields: [
+            required, r#in, description, name, allow_empty_value, r#type, format, items, collection_format, default,
+            exclusive_maximum, exclusive_minimum, max_length, min_length, pattern, max_items, min_items, unique_items,
+            r#enum, vendor_extension
+        ]
+    });
+    eq_and_hash!(Header {
+        floats: [maximum, minimum, multiple_of],
+        fields: [
+            r#type, format, items, collection_format, default, exclusive_maximum, exclusive_minimum, max_length,
+            min_length, pattern, max_items, min_items, unique_items, r#enum, description, vendor_extension
+        ]
+    });
+    eq_and_hash!(HeaderParameterSubSchema {
+        floats: [maximum, minimum, multiple_of],
+        fields: [
+            required, r#in, description, name, r#type, format, items, collection_format, default, exclusive_maximum,
+            exclusive_minimum, max_length, min_length, pattern, max_items, min_items, unique_items, r#enum,
+            vendor_extension
+        ]
+    });
+    eq_and_hash!(PathParameterSubSchema {
+        floats: [maximum, minimum, multiple_of],
+        fields: [
+            required, r#in, description, name, r#type, format, items, collection_format, default, exclusive_maximum,
+            exclusive_minimum, max_length, min_length, pattern, max_items, min_items, unique_items, r#enum,
+            vendor_extension
+        ]
+    });
+    eq_and_hash!(PrimitivesItems {
+        floats: [maximum, minimum, multiple_of],
+        fields: [
+            r#type, format, items, collection_format, default, exclusive_maximum, exclusive_minimum, max_length,
+            min_length, pattern, max_items, min_items, unique_items, r#enum, vendor_extension
+        ]
+    });
+    eq_and_hash!(QueryParameterSubSchema {
+        floats: [maximum, minimum, multiple_of],
+        fields: [
+            required, r#in, description, name, allow_empty_value, r#type, format, items, collection_format, default,
+            exclusive_maximum, exclusive_minimum, max_length, min_length, pattern, max_items, min_items, unique_items,
+            r#enum, vendor_extension
+        ]
+    });
+    eq_and_hash!(Schema {
+        floats: [maximum, minimum, multiple_of],
+        fields: [
+            r#ref, format, title, description, default, exclusive_maximum, exclusive_minimum, max_length,
+            min_length, pattern, max_items, min_items, unique_items, max_properties, min_properties, required, r#enum,
+            additional_properties, r#type, items, all_of, properties, discriminator, read_only, xml, external_docs,
+            example, vendor_extension
+        ]
+    });
 }
 
 pub use document::*;
+pub use gnostic_compiler::{NamedMap, ToYaml};
+pub use json_schema::{from_json_schema, to_json_schema};
+pub use lint::lint;
 pub use openapi_v2::Document;
+pub use resolver::Resolver;
+pub use validator::{
+    validate, validate_node, validate_node_with_options, validate_with_options, validation_report,
+};
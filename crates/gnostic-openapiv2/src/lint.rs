@@ -0,0 +1,37 @@
+//! Linting of OpenAPI v2 documents.
+
+use gnostic_compiler::{CompilerError, LintReport, Linter, Rule, ToYaml};
+use serde_yaml::Value as Yaml;
+
+use crate::openapi_v2::Document;
+use crate::validator::validate_node;
+
+/// Reports the findings of the validator as a lint rule.
+pub struct ValidDocument;
+
+impl Rule<Document> for ValidDocument {
+    fn name(&self) -> &str {
+        "oas2-schema"
+    }
+
+    fn description(&self) -> &str {
+        "Documents should be valid against the OpenAPI v2 specification."
+    }
+
+    fn check(&self, _document: &Document, node: &Yaml) -> Vec<CompilerError> {
+        validate_node(node).errors
+    }
+}
+
+/// Returns a linter with the validator and the built-in rules.
+pub fn linter() -> Linter<Document> {
+    Linter::new().with_rule(ValidDocument).with_builtin_rules()
+}
+
+/// Lints a parsed document with the default [`linter`].
+///
+/// Locations are those of the document's YAML; use [`Linter::lint`] with the
+/// source YAML to check fields the model does not represent.
+pub fn lint(document: &Document) -> LintReport {
+    linter().lint(document, &document.to_yaml())
+}
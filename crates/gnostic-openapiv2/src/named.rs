@@ -0,0 +1,121 @@
+//! Map views of the named collections of documents.
+//!
+//! Mappings such as [`Paths`] and [`Definitions`] are vectors of
+//! named entries; they implement [`NamedMap`] for lookups and updates by
+//! name, and convert from [`IndexMap`]s.
+
+use gnostic_compiler::writer::Named;
+use gnostic_compiler::{NamedEntry, NamedMap};
+use indexmap::IndexMap;
+
+use crate::openapi_v2::*;
+
+/// Implements [`NamedEntry`] for entries with a `name` and an optional `value`.
+macro_rules! named_entry {
+    ($($entry:ident),* $(,)?) => {
+        $(
+            impl NamedEntry for $entry {
+                fn new(name: String, value: Self::Value) -> Self {
+                    $entry { name, value: Some(value) }
+                }
+
+                fn value_mut(&mut self) -> Option<&mut Self::Value> {
+                    self.value.as_mut()
+                }
+
+                fn into_value(self) -> Option<Self::Value> {
+                    self.value
+                }
+            }
+        )*
+    };
+}
+
+/// Implements [`NamedMap`] and the conversion from an [`IndexMap`] for
+/// collections whose entries are in the field `field`.
+macro_rules! named_map {
+    ($($map:ident.$field:ident: $entry:ident),* $(,)?) => {
+        $(
+            impl NamedMap for $map {
+                type Entry = $entry;
+
+                fn entries(&self) -> &[$entry] {
+                    &self.$field
+                }
+
+                fn entries_mut(&mut self) -> &mut Vec<$entry> {
+                    &mut self.$field
+                }
+            }
+
+            impl From<IndexMap<String, <$entry as Named>::Value>> for $map {
+                // Most collections have no fields besides their entries.
+                #[allow(clippy::needless_update)]
+                fn from(map: IndexMap<String, <$entry as Named>::Value>) -> Self {
+                    let $field = map.into_iter().map(|(name, value)| $entry::new(name, value)).collect();
+                    $map { $field, ..$map::default() }
+                }
+            }
+        )*
+    };
+}
+
+named_entry!(
+    NamedAny,
+    NamedHeader,
+    NamedParameter,
+    NamedPathItem,
+    NamedResponse,
+    NamedResponseValue,
+    NamedSchema,
+    NamedSecurityDefinitionsItem,
+    NamedStringArray,
+);
+
+impl NamedEntry for NamedString {
+    fn new(name: String, value: String) -> Self {
+        NamedString { name, value }
+    }
+
+    fn value_mut(&mut self) -> Option<&mut String> {
+        Some(&mut self.value)
+    }
+
+    fn into_value(self) -> Option<String> {
+        Some(self.value)
+    }
+}
+
+named_map!(
+    Default.additional_properties: NamedAny,
+    Definitions.additional_properties: NamedSchema,
+    Examples.additional_properties: NamedAny,
+    Headers.additional_properties: NamedHeader,
+    Oauth2Scopes.additional_properties: NamedString,
+    ParameterDefinitions.additional_properties: NamedParameter,
+    Paths.path: NamedPathItem,
+    Properties.additional_properties: NamedSchema,
+    ResponseDefinitions.additional_properties: NamedResponse,
+    Responses.response_code: NamedResponseValue,
+    SecurityDefinitions.additional_properties: NamedSecurityDefinitionsItem,
+    SecurityRequirement.additional_properties: NamedStringArray,
+    VendorExtension.additional_properties: NamedAny,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_map() {
+        let pet = Schema { r#type: Some(TypeItem { value: vec!["object".to_string()] }), ..Schema::default() };
+        let map: IndexMap<String, Schema> = [("Pet".to_string(), pet.clone())].into_iter().collect();
+        let mut definitions = Definitions::from(map);
+        assert_eq!(definitions.get("Pet"), Some(&pet));
+        assert_eq!(definitions.insert("Pet", Schema::default()), Some(pet));
+        definitions.insert("Error", Schema::default());
+        assert_eq!(definitions.names(), ["Pet", "Error"]);
+        assert_eq!(definitions.remove("Pet"), Some(Schema::default()));
+        assert_eq!(definitions.into_map().keys().collect::<Vec<_>>(), ["Error"]);
+    }
+}
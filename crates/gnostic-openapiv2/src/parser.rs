@@ -1,9 +1,9 @@
 //! OpenAPI v2 (Swagger) YAML to Protocol Buffer parser.
 
-use gnostic_compiler::{Context, CompilerError, ErrorGroup};
-use gnostic_compiler::{map_value_for_key, string_for_scalar_node, bool_for_scalar_node,
+use gnostic_compiler::{Context, CompilerError, ErrorCode, ErrorGroup, check_depth, handle_extension};
+use gnostic_compiler::{map_value_for_key, KeyIndex, string_for_scalar_node, bool_for_scalar_node,
                        string_array_for_sequence_node,
-                       is_mapping, is_sequence, iter_map, iter_sequence};
+                       is_mapping, is_sequence, iter_map, iter_sequence, marshal};
 use std::sync::Arc;
 use serde_yaml::Value as Yaml;
 
@@ -15,92 +15,184 @@ pub struct Parser;
 impl Parser {
     /// Parses a Document from a YAML node.
     pub fn parse_document(node: &Yaml, context: &Arc<Context>) -> Result<Document, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut doc = Document::default();
 
         if !is_mapping(node) {
-            errors.push(CompilerError::new(context, format!("expected mapping, got {:?}", node)));
+            errors.push(CompilerError::new_with_code(context, ErrorCode::InvalidType, format!("expected mapping, got {:?}", node)));
             return Err(ErrorGroup::new(errors));
         }
 
         // Parse swagger version
-        if let Some(v) = map_value_for_key(node, "swagger") {
+        if let Some(v) = index.get("swagger") {
             if let Some(s) = string_for_scalar_node(v) {
                 doc.swagger = s;
             }
         }
 
         // Parse info
-        if let Some(v) = map_value_for_key(node, "info") {
+        if let Some(v) = index.get("info") {
             let child_ctx = Arc::new(context.child("info"));
             match Self::parse_info(v, &child_ctx) {
                 Ok(info) => doc.info = Some(info),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
         // Parse host
-        if let Some(v) = map_value_for_key(node, "host") {
+        if let Some(v) = index.get("host") {
             if let Some(s) = string_for_scalar_node(v) {
                 doc.host = s;
             }
         }
 
         // Parse basePath
-        if let Some(v) = map_value_for_key(node, "basePath") {
+        if let Some(v) = index.get("basePath") {
             if let Some(s) = string_for_scalar_node(v) {
                 doc.base_path = s;
             }
         }
 
         // Parse schemes
-        if let Some(v) = map_value_for_key(node, "schemes") {
+        if let Some(v) = index.get("schemes") {
             doc.schemes = string_array_for_sequence_node(v);
         }
 
         // Parse consumes
-        if let Some(v) = map_value_for_key(node, "consumes") {
+        if let Some(v) = index.get("consumes") {
             doc.consumes = string_array_for_sequence_node(v);
         }
 
         // Parse produces
-        if let Some(v) = map_value_for_key(node, "produces") {
+        if let Some(v) = index.get("produces") {
             doc.produces = string_array_for_sequence_node(v);
         }
 
         // Parse paths
-        if let Some(v) = map_value_for_key(node, "paths") {
+        if let Some(v) = index.get("paths") {
             let child_ctx = Arc::new(context.child("paths"));
             match Self::parse_paths(v, &child_ctx) {
                 Ok(paths) => doc.paths = Some(paths),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
         // Parse definitions
-        if let Some(v) = map_value_for_key(node, "definitions") {
+        if let Some(v) = index.get("definitions") {
             let child_ctx = Arc::new(context.child("definitions"));
             match Self::parse_definitions(v, &child_ctx) {
                 Ok(defs) => doc.definitions = Some(defs),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse parameters
+        if let Some(v) = index.get("parameters") {
+            let child_ctx = Arc::new(context.child("parameters"));
+            match Self::parse_parameter_definitions(v, &child_ctx) {
+                Ok(parameters) => doc.parameters = Some(parameters),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse responses
+        if let Some(v) = index.get("responses") {
+            let child_ctx = Arc::new(context.child("responses"));
+            match Self::parse_response_definitions(v, &child_ctx) {
+                Ok(responses) => doc.responses = Some(responses),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse security
+        if let Some(v) = index.get("security") {
+            let child_ctx = Arc::new(context.child("security"));
+            match Self::parse_security_requirements(v, &child_ctx) {
+                Ok(security) => doc.security = security,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse securityDefinitions
+        if let Some(v) = index.get("securityDefinitions") {
+            let child_ctx = Arc::new(context.child("securityDefinitions"));
+            match Self::parse_security_definitions(v, &child_ctx) {
+                Ok(definitions) => doc.security_definitions = Some(definitions),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
         // Parse tags
-        if let Some(v) = map_value_for_key(node, "tags") {
+        if let Some(v) = index.get("tags") {
             let child_ctx = Arc::new(context.child("tags"));
             match Self::parse_tags(v, &child_ctx) {
                 Ok(tags) => doc.tags = tags,
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
         // Parse externalDocs
-        if let Some(v) = map_value_for_key(node, "externalDocs") {
+        if let Some(v) = index.get("externalDocs") {
             let child_ctx = Arc::new(context.child("externalDocs"));
             match Self::parse_external_docs(v, &child_ctx) {
-                Ok(ext) => doc.external_docs = Some(ext),
-                Err(e) => errors.extend(e.errors),
+                Ok(ext) => doc.external_docs = Some(Box::new(ext)),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        match Self::parse_vendor_extensions(node, context) {
+            Ok(extensions) => doc.vendor_extension = extensions,
+            Err(e) => {
+                errors.extend(e.errors);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
+                }
             }
         }
 
@@ -113,46 +205,67 @@ impl Parser {
 
     /// Parses Info from a YAML node.
     pub fn parse_info(node: &Yaml, context: &Arc<Context>) -> Result<Info, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut info = Info::default();
 
-        if let Some(v) = map_value_for_key(node, "title") {
+        if let Some(v) = index.get("title") {
             if let Some(s) = string_for_scalar_node(v) {
                 info.title = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "description") {
+        if let Some(v) = index.get("description") {
             if let Some(s) = string_for_scalar_node(v) {
                 info.description = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "version") {
+        if let Some(v) = index.get("version") {
             if let Some(s) = string_for_scalar_node(v) {
                 info.version = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "termsOfService") {
+        if let Some(v) = index.get("termsOfService") {
             if let Some(s) = string_for_scalar_node(v) {
                 info.terms_of_service = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "contact") {
+        if let Some(v) = index.get("contact") {
             let child_ctx = Arc::new(context.child("contact"));
             match Self::parse_contact(v, &child_ctx) {
                 Ok(contact) => info.contact = Some(contact),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "license") {
+        if let Some(v) = index.get("license") {
             let child_ctx = Arc::new(context.child("license"));
             match Self::parse_license(v, &child_ctx) {
                 Ok(license) => info.license = Some(license),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        match Self::parse_vendor_extensions(node, context) {
+            Ok(extensions) => info.vendor_extension = extensions,
+            Err(e) => {
+                errors.extend(e.errors);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
+                }
             }
         }
 
@@ -164,32 +277,34 @@ impl Parser {
     }
 
     /// Parses Contact from a YAML node.
-    pub fn parse_contact(node: &Yaml, _context: &Arc<Context>) -> Result<Contact, ErrorGroup> {
+    pub fn parse_contact(node: &Yaml, context: &Arc<Context>) -> Result<Contact, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut contact = Contact::default();
 
-        if let Some(v) = map_value_for_key(node, "name") {
+        if let Some(v) = index.get("name") {
             if let Some(s) = string_for_scalar_node(v) {
                 contact.name = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "url") {
+        if let Some(v) = index.get("url") {
             if let Some(s) = string_for_scalar_node(v) {
                 contact.url = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "email") {
+        if let Some(v) = index.get("email") {
             if let Some(s) = string_for_scalar_node(v) {
                 contact.email = s;
             }
         }
 
+        contact.vendor_extension = Self::parse_vendor_extensions(node, context)?;
         Ok(contact)
     }
 
     /// Parses License from a YAML node.
-    pub fn parse_license(node: &Yaml, _context: &Arc<Context>) -> Result<License, ErrorGroup> {
+    pub fn parse_license(node: &Yaml, context: &Arc<Context>) -> Result<License, ErrorGroup> {
         let mut license = License::default();
 
         if let Some(v) = map_value_for_key(node, "name") {
@@ -204,6 +319,7 @@ impl Parser {
             }
         }
 
+        license.vendor_extension = Self::parse_vendor_extensions(node, context)?;
         Ok(license)
     }
 
@@ -213,7 +329,13 @@ impl Parser {
         let mut paths = Paths::default();
 
         iter_map(node, |path, value| {
-            let child_ctx = Arc::new(context.child(path.to_string()));
+            if context.should_stop(&errors) {
+                return;
+            }
+            if path.starts_with("x-") {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(path));
             match Self::parse_path_item(value, &child_ctx) {
                 Ok(path_item) => {
                     paths.path.push(NamedPathItem {
@@ -225,6 +347,16 @@ impl Parser {
             }
         });
 
+        match Self::parse_vendor_extensions(node, context) {
+            Ok(extensions) => paths.vendor_extension = extensions,
+            Err(e) => {
+                errors.extend(e.errors);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
+                }
+            }
+        }
+
         if errors.is_empty() {
             Ok(paths)
         } else {
@@ -234,10 +366,11 @@ impl Parser {
 
     /// Parses PathItem from a YAML node.
     pub fn parse_path_item(node: &Yaml, context: &Arc<Context>) -> Result<PathItem, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut path_item = PathItem::default();
 
-        if let Some(v) = map_value_for_key(node, "$ref") {
+        if let Some(v) = index.get("$ref") {
             if let Some(s) = string_for_scalar_node(v) {
                 path_item.r#ref = s;
             }
@@ -245,22 +378,50 @@ impl Parser {
 
         // Parse HTTP methods
         for method in &["get", "put", "post", "delete", "options", "head", "patch"] {
-            if let Some(v) = map_value_for_key(node, method) {
+            if let Some(v) = index.get(method) {
                 let child_ctx = Arc::new(context.child(*method));
                 match Self::parse_operation(v, &child_ctx) {
                     Ok(op) => {
                         match *method {
-                            "get" => path_item.get = Some(op),
-                            "put" => path_item.put = Some(op),
-                            "post" => path_item.post = Some(op),
-                            "delete" => path_item.delete = Some(op),
-                            "options" => path_item.options = Some(op),
-                            "head" => path_item.head = Some(op),
-                            "patch" => path_item.patch = Some(op),
+                            "get" => path_item.get = Some(Box::new(op)),
+                            "put" => path_item.put = Some(Box::new(op)),
+                            "post" => path_item.post = Some(Box::new(op)),
+                            "delete" => path_item.delete = Some(Box::new(op)),
+                            "options" => path_item.options = Some(Box::new(op)),
+                            "head" => path_item.head = Some(Box::new(op)),
+                            "patch" => path_item.patch = Some(Box::new(op)),
                             _ => {}
                         }
                     }
-                    Err(e) => errors.extend(e.errors),
+                    Err(e) => {
+                        errors.extend(e.errors);
+                        if context.should_stop(&errors) {
+                            return Err(ErrorGroup::new(errors));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("parameters") {
+            let child_ctx = Arc::new(context.child("parameters"));
+            match Self::parse_parameters(v, &child_ctx) {
+                Ok(parameters) => path_item.parameters = parameters,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        match Self::parse_vendor_extensions(node, context) {
+            Ok(extensions) => path_item.vendor_extension = extensions,
+            Err(e) => {
+                errors.extend(e.errors);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
                 }
             }
         }
@@ -273,46 +434,574 @@ impl Parser {
     }
 
     /// Parses Operation from a YAML node.
-    pub fn parse_operation(node: &Yaml, _context: &Arc<Context>) -> Result<Operation, ErrorGroup> {
+    pub fn parse_operation(node: &Yaml, context: &Arc<Context>) -> Result<Operation, ErrorGroup> {
+        let index = KeyIndex::new(node);
+        let mut errors = Vec::new();
         let mut operation = Operation::default();
 
-        if let Some(v) = map_value_for_key(node, "tags") {
+        if let Some(v) = index.get("tags") {
             operation.tags = string_array_for_sequence_node(v);
         }
 
-        if let Some(v) = map_value_for_key(node, "summary") {
+        if let Some(v) = index.get("summary") {
             if let Some(s) = string_for_scalar_node(v) {
                 operation.summary = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "description") {
+        if let Some(v) = index.get("description") {
             if let Some(s) = string_for_scalar_node(v) {
                 operation.description = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "operationId") {
+        if let Some(v) = index.get("operationId") {
             if let Some(s) = string_for_scalar_node(v) {
                 operation.operation_id = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "consumes") {
+        if let Some(v) = index.get("consumes") {
             operation.consumes = string_array_for_sequence_node(v);
         }
 
-        if let Some(v) = map_value_for_key(node, "produces") {
+        if let Some(v) = index.get("produces") {
             operation.produces = string_array_for_sequence_node(v);
         }
 
-        if let Some(v) = map_value_for_key(node, "deprecated") {
+        if let Some(v) = index.get("deprecated") {
             if let Some(b) = bool_for_scalar_node(v) {
                 operation.deprecated = b;
             }
         }
 
-        Ok(operation)
+        // Parse parameters
+        if let Some(v) = index.get("parameters") {
+            let child_ctx = Arc::new(context.child("parameters"));
+            match Self::parse_parameters(v, &child_ctx) {
+                Ok(parameters) => operation.parameters = parameters,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse responses
+        if let Some(v) = index.get("responses") {
+            let child_ctx = Arc::new(context.child("responses"));
+            match Self::parse_responses(v, &child_ctx) {
+                Ok(responses) => operation.responses = Some(responses),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse security
+        if let Some(v) = index.get("security") {
+            let child_ctx = Arc::new(context.child("security"));
+            match Self::parse_security_requirements(v, &child_ctx) {
+                Ok(security) => operation.security = security,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        match Self::parse_vendor_extensions(node, context) {
+            Ok(extensions) => operation.vendor_extension = extensions,
+            Err(e) => {
+                errors.extend(e.errors);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(operation)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses the parameters of an operation or path item from a YAML node.
+    pub fn parse_parameters(node: &Yaml, context: &Arc<Context>) -> Result<Vec<ParametersItem>, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut parameters = Vec::new();
+
+        let Yaml::Sequence(items) = node else {
+            context
+                .report_recoverable(ErrorCode::InvalidType, "parameters must be an array")
+                .map_err(|e| ErrorGroup::new(vec![e]))?;
+            return Ok(parameters);
+        };
+        for (i, item) in items.iter().enumerate() {
+            let child_ctx = Arc::new(context.child(i.to_string()));
+            match Self::parse_parameters_item(item, &child_ctx) {
+                Ok(parameter) => parameters.push(parameter),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(parameters)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses ParametersItem from a YAML node.
+    pub fn parse_parameters_item(node: &Yaml, context: &Arc<Context>) -> Result<ParametersItem, ErrorGroup> {
+        // Check if it's a reference
+        if let Some(reference) = Self::parse_json_reference(node) {
+            return Ok(ParametersItem {
+                oneof: Some(parameters_item::Oneof::JsonReference(reference)),
+            });
+        }
+
+        // Parse as parameter
+        Self::parse_parameter(node, context).map(|p| ParametersItem {
+            oneof: Some(parameters_item::Oneof::Parameter(p)),
+        })
+    }
+
+    /// Parses JsonReference if the node is one.
+    pub fn parse_json_reference(node: &Yaml) -> Option<JsonReference> {
+        let r#ref = map_value_for_key(node, "$ref").and_then(string_for_scalar_node)?;
+        let description = map_value_for_key(node, "description")
+            .and_then(string_for_scalar_node)
+            .unwrap_or_default();
+        Some(JsonReference { r#ref, description })
+    }
+
+    /// Parses Parameter from a YAML node.
+    ///
+    /// Body parameters carry a schema; the others are described by a type
+    /// and format and are parsed by their location ("in").
+    pub fn parse_parameter(node: &Yaml, context: &Arc<Context>) -> Result<Parameter, ErrorGroup> {
+        let index = KeyIndex::new(node);
+        let string = |key: &str| {
+            index.get(key)
+                .and_then(string_for_scalar_node)
+                .unwrap_or_default()
+        };
+        let required = index.get("required")
+            .and_then(bool_for_scalar_node)
+            .unwrap_or_default();
+        let (name, r#in, description) = (string("name"), string("in"), string("description"));
+        let items = index.get("items").map(Self::parse_primitives_items);
+        let values = parse_enum(node);
+        let vendor_extension = Self::parse_vendor_extensions(node, context)?;
+
+        let non_body = match r#in.as_str() {
+            "body" => {
+                let schema = match index.get("schema") {
+                    Some(v) => Some(Self::parse_schema(v, &Arc::new(context.child("schema")))?),
+                    None => None,
+                };
+                let body = BodyParameter {
+                    description,
+                    name,
+                    r#in,
+                    required,
+                    schema,
+                    vendor_extension,
+                };
+                return Ok(Parameter {
+                    oneof: Some(parameter::Oneof::BodyParameter(body)),
+                });
+            }
+            "header" => non_body_parameter::Oneof::HeaderParameterSubSchema(HeaderParameterSubSchema {
+                required,
+                r#in,
+                description,
+                name,
+                r#type: string("type"),
+                format: string("format"),
+                items,
+                r#enum: values,
+                vendor_extension,
+                ..HeaderParameterSubSchema::default()
+            }),
+            "formData" => non_body_parameter::Oneof::FormDataParameterSubSchema(FormDataParameterSubSchema {
+                required,
+                r#in,
+                description,
+                name,
+                r#type: string("type"),
+                format: string("format"),
+                items,
+                r#enum: values,
+                vendor_extension,
+                ..FormDataParameterSubSchema::default()
+            }),
+            "path" => non_body_parameter::Oneof::PathParameterSubSchema(PathParameterSubSchema {
+                required,
+                r#in,
+                description,
+                name,
+                r#type: string("type"),
+                format: string("format"),
+                items,
+                r#enum: values,
+                vendor_extension,
+                ..PathParameterSubSchema::default()
+            }),
+            _ => {
+                if r#in != "query" {
+                    context
+                        .report_recoverable(
+                            ErrorCode::InvalidValue,
+                            format!("parameter location must be body, header, formData, path or query, got {:?}", r#in),
+                        )
+                        .map_err(|e| ErrorGroup::new(vec![e]))?;
+                }
+                non_body_parameter::Oneof::QueryParameterSubSchema(QueryParameterSubSchema {
+                    required,
+                    r#in,
+                    description,
+                    name,
+                    r#type: string("type"),
+                    format: string("format"),
+                    items,
+                    r#enum: values,
+                    vendor_extension,
+                    ..QueryParameterSubSchema::default()
+                })
+            }
+        };
+        Ok(Parameter {
+            oneof: Some(parameter::Oneof::NonBodyParameter(NonBodyParameter {
+                oneof: Some(non_body),
+            })),
+        })
+    }
+
+    /// Parses a list of SecurityRequirement from a YAML node.
+    pub fn parse_security_requirements(
+        node: &Yaml,
+        context: &Arc<Context>,
+    ) -> Result<Vec<SecurityRequirement>, ErrorGroup> {
+        let Yaml::Sequence(items) = node else {
+            context
+                .report_recoverable(ErrorCode::InvalidType, "security must be an array")
+                .map_err(|e| ErrorGroup::new(vec![e]))?;
+            return Ok(Vec::new());
+        };
+        Ok(items.iter().map(Self::parse_security_requirement).collect())
+    }
+
+    /// Parses SecurityRequirement from a YAML node, the scopes required of
+    /// each named security scheme.
+    pub fn parse_security_requirement(node: &Yaml) -> SecurityRequirement {
+        let mut requirement = SecurityRequirement::default();
+        iter_map(node, |name, value| {
+            requirement.additional_properties.push(NamedStringArray {
+                name: name.to_string(),
+                value: Some(StringArray { value: string_array_for_sequence_node(value) }),
+            });
+        });
+        requirement
+    }
+
+    /// Parses SecurityDefinitions from a YAML node.
+    pub fn parse_security_definitions(node: &Yaml, context: &Arc<Context>) -> Result<SecurityDefinitions, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut definitions = SecurityDefinitions::default();
+
+        iter_map(node, |name, value| {
+            if context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(name));
+            match Self::parse_security_definitions_item(value, &child_ctx) {
+                Ok(item) => {
+                    definitions.additional_properties.push(NamedSecurityDefinitionsItem {
+                        name: name.to_string(),
+                        value: Some(item),
+                    });
+                }
+                Err(e) => errors.extend(e.errors),
+            }
+        });
+
+        if errors.is_empty() {
+            Ok(definitions)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses SecurityDefinitionsItem from a YAML node.
+    ///
+    /// The kind of scheme is chosen by its type and, for OAuth2, its flow.
+    pub fn parse_security_definitions_item(
+        node: &Yaml,
+        context: &Arc<Context>,
+    ) -> Result<SecurityDefinitionsItem, ErrorGroup> {
+        use security_definitions_item::Oneof;
+
+        let index = KeyIndex::new(node);
+        let string = |key: &str| {
+            index.get(key)
+                .and_then(string_for_scalar_node)
+                .unwrap_or_default()
+        };
+        let (r#type, flow, description) = (string("type"), string("flow"), string("description"));
+        let scopes = index.get("scopes").map(Self::parse_oauth2_scopes);
+        let vendor_extension = Self::parse_vendor_extensions(node, context)?;
+
+        let oneof = match (r#type.as_str(), flow.as_str()) {
+            ("basic", _) => Oneof::BasicAuthenticationSecurity(BasicAuthenticationSecurity {
+                r#type,
+                description,
+                vendor_extension,
+            }),
+            ("apiKey", _) => Oneof::ApiKeySecurity(ApiKeySecurity {
+                r#type,
+                name: string("name"),
+                r#in: string("in"),
+                description,
+                vendor_extension,
+            }),
+            ("oauth2", "implicit") => Oneof::Oauth2ImplicitSecurity(Oauth2ImplicitSecurity {
+                r#type,
+                flow,
+                scopes,
+                authorization_url: string("authorizationUrl"),
+                description,
+                vendor_extension,
+            }),
+            ("oauth2", "password") => Oneof::Oauth2PasswordSecurity(Oauth2PasswordSecurity {
+                r#type,
+                flow,
+                scopes,
+                token_url: string("tokenUrl"),
+                description,
+                vendor_extension,
+            }),
+            ("oauth2", "application") => Oneof::Oauth2ApplicationSecurity(Oauth2ApplicationSecurity {
+                r#type,
+                flow,
+                scopes,
+                token_url: string("tokenUrl"),
+                description,
+                vendor_extension,
+            }),
+            ("oauth2", "accessCode") => Oneof::Oauth2AccessCodeSecurity(Oauth2AccessCodeSecurity {
+                r#type,
+                flow,
+                scopes,
+                authorization_url: string("authorizationUrl"),
+                token_url: string("tokenUrl"),
+                description,
+                vendor_extension,
+            }),
+            _ => {
+                context
+                    .report_recoverable(
+                        ErrorCode::InvalidValue,
+                        format!("unknown security scheme of type {:?} and flow {:?}", r#type, flow),
+                    )
+                    .map_err(|e| ErrorGroup::new(vec![e]))?;
+                return Ok(SecurityDefinitionsItem::default());
+            }
+        };
+        Ok(SecurityDefinitionsItem { oneof: Some(oneof) })
+    }
+
+    /// Parses the scopes of an OAuth2 security scheme from a YAML node.
+    pub fn parse_oauth2_scopes(node: &Yaml) -> Oauth2Scopes {
+        let mut scopes = Oauth2Scopes::default();
+        iter_map(node, |name, value| {
+            scopes.additional_properties.push(NamedString {
+                name: name.to_string(),
+                value: string_for_scalar_node(value).unwrap_or_default(),
+            });
+        });
+        scopes
+    }
+
+    /// Parses the vendor extensions ("x-" keys) of a YAML node.
+    ///
+    /// An extension that an extension handler claims has the handler's
+    /// response as its value; the others keep only the YAML of their values.
+    pub fn parse_vendor_extensions(node: &Yaml, context: &Arc<Context>) -> Result<Vec<NamedAny>, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut extensions = Vec::new();
+
+        iter_map(node, |name, value| {
+            if !name.starts_with("x-") || context.should_stop(&errors) {
+                return;
+            }
+            match handle_extension(&context.child(name), value, name) {
+                Ok(handled) => extensions.push(NamedAny {
+                    name: name.to_string(),
+                    value: Some(Any {
+                        value: handled,
+                        yaml: String::from_utf8_lossy(&marshal(value)).into_owned(),
+                    }),
+                }),
+                Err(e) => errors.push(e),
+            }
+        });
+
+        if errors.is_empty() {
+            Ok(extensions)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses the PrimitivesItems of a non-body parameter from a YAML node.
+    pub fn parse_primitives_items(node: &Yaml) -> PrimitivesItems {
+        let string = |key: &str| {
+            map_value_for_key(node, key)
+                .and_then(string_for_scalar_node)
+                .unwrap_or_default()
+        };
+        PrimitivesItems {
+            r#type: string("type"),
+            format: string("format"),
+            items: map_value_for_key(node, "items").map(|v| Box::new(Self::parse_primitives_items(v))),
+            collection_format: string("collectionFormat"),
+            r#enum: parse_enum(node),
+            ..PrimitivesItems::default()
+        }
+    }
+
+    /// Parses ParameterDefinitions from a YAML node.
+    pub fn parse_parameter_definitions(node: &Yaml, context: &Arc<Context>) -> Result<ParameterDefinitions, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut definitions = ParameterDefinitions::default();
+
+        iter_map(node, |name, value| {
+            if context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(name));
+            match Self::parse_parameter(value, &child_ctx) {
+                Ok(parameter) => {
+                    definitions.additional_properties.push(NamedParameter {
+                        name: name.to_string(),
+                        value: Some(parameter),
+                    });
+                }
+                Err(e) => errors.extend(e.errors),
+            }
+        });
+
+        if errors.is_empty() {
+            Ok(definitions)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses Responses from a YAML node.
+    pub fn parse_responses(node: &Yaml, context: &Arc<Context>) -> Result<Responses, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut responses = Responses::default();
+
+        iter_map(node, |code, value| {
+            if code.starts_with("x-") || context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(code));
+            let value = match Self::parse_json_reference(value) {
+                Some(reference) => Ok(response_value::Oneof::JsonReference(reference)),
+                None => Self::parse_response(value, &child_ctx).map(response_value::Oneof::Response),
+            };
+            match value {
+                Ok(value) => {
+                    responses.response_code.push(NamedResponseValue {
+                        name: code.to_string(),
+                        value: Some(ResponseValue { oneof: Some(value) }),
+                    });
+                }
+                Err(e) => errors.extend(e.errors),
+            }
+        });
+
+        match Self::parse_vendor_extensions(node, context) {
+            Ok(extensions) => responses.vendor_extension = extensions,
+            Err(e) => {
+                errors.extend(e.errors);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(responses)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses Response from a YAML node.
+    pub fn parse_response(node: &Yaml, context: &Arc<Context>) -> Result<Response, ErrorGroup> {
+        let mut response = Response::default();
+
+        if let Some(v) = map_value_for_key(node, "description") {
+            if let Some(s) = string_for_scalar_node(v) {
+                response.description = s;
+            }
+        }
+
+        if let Some(v) = map_value_for_key(node, "schema") {
+            let schema = Self::parse_schema(v, &Arc::new(context.child("schema")))?;
+            response.schema = Some(SchemaItem {
+                oneof: Some(schema_item::Oneof::Schema(schema)),
+            });
+        }
+
+        response.vendor_extension = Self::parse_vendor_extensions(node, context)?;
+        Ok(response)
+    }
+
+    /// Parses ResponseDefinitions from a YAML node.
+    pub fn parse_response_definitions(node: &Yaml, context: &Arc<Context>) -> Result<ResponseDefinitions, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut definitions = ResponseDefinitions::default();
+
+        iter_map(node, |name, value| {
+            if context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(name));
+            match Self::parse_response(value, &child_ctx) {
+                Ok(response) => {
+                    definitions.additional_properties.push(NamedResponse {
+                        name: name.to_string(),
+                        value: Some(response),
+                    });
+                }
+                Err(e) => errors.extend(e.errors),
+            }
+        });
+
+        if errors.is_empty() {
+            Ok(definitions)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
     }
 
     /// Parses Definitions from a YAML node.
@@ -321,7 +1010,10 @@ impl Parser {
         let mut definitions = Definitions::default();
 
         iter_map(node, |name, value| {
-            let child_ctx = Arc::new(context.child(name.to_string()));
+            if context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(name));
             match Self::parse_schema(value, &child_ctx) {
                 Ok(schema) => {
                     definitions.additional_properties.push(NamedSchema {
@@ -341,44 +1033,148 @@ impl Parser {
     }
 
     /// Parses Schema from a YAML node.
-    pub fn parse_schema(node: &Yaml, _context: &Arc<Context>) -> Result<Schema, ErrorGroup> {
+    pub fn parse_schema(node: &Yaml, context: &Arc<Context>) -> Result<Schema, ErrorGroup> {
+        check_depth(context)?;
+        let index = KeyIndex::new(node);
+
+        let mut errors = Vec::new();
         let mut schema = Schema::default();
 
-        if let Some(v) = map_value_for_key(node, "$ref") {
+        if let Some(v) = index.get("$ref") {
             if let Some(s) = string_for_scalar_node(v) {
                 schema.r#ref = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "type") {
+        if let Some(v) = index.get("type") {
             if let Some(s) = string_for_scalar_node(v) {
                 schema.r#type = Some(TypeItem { value: vec![s] });
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "format") {
+        if let Some(v) = index.get("format") {
             if let Some(s) = string_for_scalar_node(v) {
                 schema.format = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "title") {
+        if let Some(v) = index.get("title") {
             if let Some(s) = string_for_scalar_node(v) {
                 schema.title = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "description") {
+        if let Some(v) = index.get("description") {
             if let Some(s) = string_for_scalar_node(v) {
                 schema.description = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "required") {
+        if let Some(v) = index.get("required") {
             schema.required = string_array_for_sequence_node(v);
         }
 
-        Ok(schema)
+        // Parse properties
+        if let Some(v) = index.get("properties") {
+            let child_ctx = Arc::new(context.child("properties"));
+            let mut properties = Properties::default();
+            iter_map(v, |name, value| {
+                if context.should_stop(&errors) {
+                    return;
+                }
+                match Self::parse_schema(value, &Arc::new(child_ctx.child(name))) {
+                    Ok(property) => properties.additional_properties.push(NamedSchema {
+                        name: name.to_string(),
+                        value: Some(property),
+                    }),
+                    Err(e) => errors.extend(e.errors),
+                }
+            });
+            schema.properties = Some(properties);
+        }
+
+        schema.r#enum = parse_enum(node);
+
+        // Parse items, a single schema or one per position
+        if let Some(v) = index.get("items") {
+            let child_ctx = Arc::new(context.child("items"));
+            let mut items = ItemsItem::default();
+            match v {
+                Yaml::Sequence(nodes) => {
+                    for (i, item) in nodes.iter().enumerate() {
+                        match Self::parse_schema(item, &Arc::new(child_ctx.child(i.to_string()))) {
+                            Ok(item) => items.schema.push(item),
+                            Err(e) => {
+                                errors.extend(e.errors);
+                                if context.should_stop(&errors) {
+                                    return Err(ErrorGroup::new(errors));
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => match Self::parse_schema(v, &child_ctx) {
+                    Ok(item) => items.schema.push(item),
+                    Err(e) => {
+                        errors.extend(e.errors);
+                        if context.should_stop(&errors) {
+                            return Err(ErrorGroup::new(errors));
+                        }
+                    }
+                },
+            }
+            schema.items = Some(items);
+        }
+
+        // Parse allOf
+        if let Some(Yaml::Sequence(nodes)) = index.get("allOf") {
+            let child_ctx = Arc::new(context.child("allOf"));
+            for (i, item) in nodes.iter().enumerate() {
+                match Self::parse_schema(item, &Arc::new(child_ctx.child(i.to_string()))) {
+                    Ok(item) => schema.all_of.push(item),
+                    Err(e) => {
+                        errors.extend(e.errors);
+                        if context.should_stop(&errors) {
+                            return Err(ErrorGroup::new(errors));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Parse additionalProperties
+        if let Some(v) = index.get("additionalProperties") {
+            let oneof = match bool_for_scalar_node(v) {
+                Some(b) => Some(additional_properties_item::Oneof::Boolean(b)),
+                None => match Self::parse_schema(v, &Arc::new(context.child("additionalProperties"))) {
+                    Ok(s) => Some(additional_properties_item::Oneof::Schema(Box::new(s))),
+                    Err(e) => {
+                        errors.extend(e.errors);
+                        None
+                    }
+                },
+            };
+            schema.additional_properties = Some(Box::new(AdditionalPropertiesItem { oneof }));
+            if context.should_stop(&errors) {
+                return Err(ErrorGroup::new(errors));
+            }
+        }
+
+        match Self::parse_vendor_extensions(node, context) {
+            Ok(extensions) => schema.vendor_extension = extensions,
+            Err(e) => {
+                errors.extend(e.errors);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(schema)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
     }
 
     /// Parses tags array from a YAML node.
@@ -387,12 +1183,22 @@ impl Parser {
         let mut tags = Vec::new();
 
         if !is_sequence(node) {
-            errors.push(CompilerError::new(context, "tags must be an array".to_string()));
-            return Err(ErrorGroup::new(errors));
+            context.report_recoverable(ErrorCode::InvalidType, "tags must be an array")?;
+            return Ok(tags);
         }
 
         iter_sequence(node, |i, item| {
+            if context.should_stop(&errors) {
+                return;
+            }
             let child_ctx = Arc::new(context.child(i.to_string()));
+            if !is_mapping(item) {
+                // Malformed tags are skipped in lenient mode
+                if let Err(e) = child_ctx.report_recoverable(ErrorCode::InvalidType, "tag must be an object") {
+                    errors.push(e);
+                }
+                return;
+            }
             match Self::parse_tag(item, &child_ctx) {
                 Ok(tag) => tags.push(tag),
                 Err(e) => errors.extend(e.errors),
@@ -408,31 +1214,47 @@ impl Parser {
 
     /// Parses a single Tag from a YAML node.
     pub fn parse_tag(node: &Yaml, context: &Arc<Context>) -> Result<Tag, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut tag = Tag::default();
 
         if !is_mapping(node) {
-            errors.push(CompilerError::new(context, "tag must be an object".to_string()));
+            errors.push(CompilerError::new_with_code(context, ErrorCode::InvalidType, "tag must be an object"));
             return Err(ErrorGroup::new(errors));
         }
 
-        if let Some(v) = map_value_for_key(node, "name") {
+        if let Some(v) = index.get("name") {
             if let Some(s) = string_for_scalar_node(v) {
                 tag.name = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "description") {
+        if let Some(v) = index.get("description") {
             if let Some(s) = string_for_scalar_node(v) {
                 tag.description = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "externalDocs") {
+        if let Some(v) = index.get("externalDocs") {
             let child_ctx = Arc::new(context.child("externalDocs"));
             match Self::parse_external_docs(v, &child_ctx) {
-                Ok(ext) => tag.external_docs = Some(ext),
-                Err(e) => errors.extend(e.errors),
+                Ok(ext) => tag.external_docs = Some(Box::new(ext)),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        match Self::parse_vendor_extensions(node, context) {
+            Ok(extensions) => tag.vendor_extension = extensions,
+            Err(e) => {
+                errors.extend(e.errors);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
+                }
             }
         }
 
@@ -445,12 +1267,11 @@ impl Parser {
 
     /// Parses ExternalDocs from a YAML node.
     pub fn parse_external_docs(node: &Yaml, context: &Arc<Context>) -> Result<ExternalDocs, ErrorGroup> {
-        let mut errors = Vec::new();
         let mut external_docs = ExternalDocs::default();
 
         if !is_mapping(node) {
-            errors.push(CompilerError::new(context, "externalDocs must be an object".to_string()));
-            return Err(ErrorGroup::new(errors));
+            context.report_recoverable(ErrorCode::InvalidType, "externalDocs must be an object")?;
+            return Ok(external_docs);
         }
 
         if let Some(v) = map_value_for_key(node, "description") {
@@ -465,10 +1286,18 @@ impl Parser {
             }
         }
 
-        if errors.is_empty() {
-            Ok(external_docs)
-        } else {
-            Err(ErrorGroup::new(errors))
-        }
+        external_docs.vendor_extension = Self::parse_vendor_extensions(node, context)?;
+        Ok(external_docs)
+    }
+}
+
+/// Parses the values of the "enum" of a node, each stored as YAML text.
+fn parse_enum(node: &Yaml) -> Vec<Any> {
+    match map_value_for_key(node, "enum") {
+        Some(Yaml::Sequence(values)) => values
+            .iter()
+            .map(|value| Any { value: None, yaml: String::from_utf8_lossy(&marshal(value)).into_owned() })
+            .collect(),
+        _ => Vec::new(),
     }
 }
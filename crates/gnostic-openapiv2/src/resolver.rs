@@ -0,0 +1,258 @@
+//! Reference resolution for OpenAPI v2 documents.
+
+use gnostic_compiler::{
+    bundle_references, flatten_references, resolve_references, Context, CycleStrategy, ErrorGroup,
+    FetchPolicy, ResolutionReport, ResolveOptions, ToYaml,
+};
+use std::sync::Arc;
+
+use crate::openapi_v2::Document;
+use crate::parser::Parser;
+
+/// Resolves the $ref values of a document, producing a dereferenced copy.
+///
+/// Internal references ("#/definitions/...", "#/parameters/...",
+/// "#/responses/...") are resolved within the document and external ones
+/// ("pet.yaml#/Pet", URLs) are read relative to the base. Circular
+/// references are left in place and reported as warnings. As Swagger 2.0
+/// requires, keywords next to a $ref are ignored. Remote documents are only
+/// fetched when the [`FetchPolicy`] allows them, which by default it does not.
+#[derive(Debug, Clone, Default)]
+pub struct Resolver {
+    base: String,
+    cycle_strategy: CycleStrategy,
+    fetch_policy: FetchPolicy,
+}
+
+impl Resolver {
+    /// Creates a resolver for documents read from memory, for which relative
+    /// external references are resolved against the current directory.
+    pub fn new() -> Self {
+        Resolver::default()
+    }
+
+    /// Returns the resolver with the file or URL the document was read from,
+    /// used to locate relative external references.
+    pub fn with_base(mut self, base: impl Into<String>) -> Self {
+        self.base = base.into();
+        self
+    }
+
+    /// Returns the resolver with the strategy [`flatten`](Self::flatten) uses
+    /// to break circular references.
+    pub fn with_cycle_strategy(mut self, cycle_strategy: CycleStrategy) -> Self {
+        self.cycle_strategy = cycle_strategy;
+        self
+    }
+
+    /// Returns the resolver with the policy for fetching remote documents.
+    pub fn with_fetch_policy(mut self, fetch_policy: FetchPolicy) -> Self {
+        self.fetch_policy = fetch_policy;
+        self
+    }
+
+    /// Returns the document with every reference replaced by its target.
+    ///
+    /// Fails with the unresolved references if any reference cannot be resolved.
+    pub fn resolve(&self, document: &Document) -> Result<Document, ErrorGroup> {
+        let (resolved, report) = self.resolve_with_report(document)?;
+        if report.is_complete() {
+            Ok(resolved)
+        } else {
+            Err(ErrorGroup::new(report.errors))
+        }
+    }
+
+    /// Returns the document with the references that could be resolved
+    /// replaced by their targets, and a report of the resolution.
+    pub fn resolve_with_report(
+        &self,
+        document: &Document,
+    ) -> Result<(Document, ResolutionReport), ErrorGroup> {
+        let (node, report) = resolve_references(&document.to_yaml(), &self.options());
+        Ok((parse(&node)?, report))
+    }
+
+    /// Returns the document with every reference inlined, breaking circular
+    /// ones with the configured [`CycleStrategy`].
+    pub fn flatten(&self, document: &Document) -> Result<Document, ErrorGroup> {
+        let (flattened, report) = self.flatten_with_report(document)?;
+        if report.is_complete() {
+            Ok(flattened)
+        } else {
+            Err(ErrorGroup::new(report.errors))
+        }
+    }
+
+    /// Returns the flattened document and a report of the flattening.
+    pub fn flatten_with_report(
+        &self,
+        document: &Document,
+    ) -> Result<(Document, ResolutionReport), ErrorGroup> {
+        let (node, report) = flatten_references(&document.to_yaml(), &self.options(), self.cycle_strategy);
+        Ok((parse(&node)?, report))
+    }
+
+    /// Returns the document with the targets of external references copied
+    /// into it, producing a single self-contained document.
+    ///
+    /// Fails with the unresolved references if any reference cannot be resolved.
+    pub fn bundle(&self, document: &Document) -> Result<Document, ErrorGroup> {
+        let (bundled, report) = self.bundle_with_report(document)?;
+        if report.is_complete() {
+            Ok(bundled)
+        } else {
+            Err(ErrorGroup::new(report.errors))
+        }
+    }
+
+    /// Returns the bundled document and a report of the bundling.
+    ///
+    /// Schemas are added to "definitions", and parameters and responses to
+    /// the top-level sections of the same name. Path items are inlined.
+    pub fn bundle_with_report(
+        &self,
+        document: &Document,
+    ) -> Result<(Document, ResolutionReport), ErrorGroup> {
+        let (node, report) = bundle_references(&document.to_yaml(), &self.options(), |location| {
+            definitions_section(location).map(|section| vec![section.to_string()])
+        });
+        Ok((parse(&node)?, report))
+    }
+
+    fn options(&self) -> ResolveOptions {
+        ResolveOptions::new()
+            .with_base(self.base.clone())
+            .with_fetch_policy(self.fetch_policy.clone())
+    }
+}
+
+pub(crate) fn parse(node: &serde_yaml::Value) -> Result<Document, ErrorGroup> {
+    let context = Arc::new(Context::root("$"));
+    Parser::parse_document(node, &context)
+}
+
+/// Returns the top-level section for an object referenced at `location`, or
+/// None for path items.
+fn definitions_section(location: &[String]) -> Option<&'static str> {
+    let keys: Vec<&str> = location.iter().map(String::as_str).collect();
+    // Everything below a schema is a schema, whatever its property names are.
+    let in_schema = keys.iter().enumerate().any(|(i, key)| {
+        *key == "schema" || (*key == "definitions" && i == 0)
+    });
+    if in_schema {
+        return Some("definitions");
+    }
+    match keys.as_slice() {
+        ["paths", _] => None,
+        ["parameters", _] | [.., "parameters", _] => Some("parameters"),
+        ["responses", _] | [.., "responses", _] => Some("responses"),
+        _ => Some("definitions"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parse_document;
+    use crate::openapi_v2::*;
+
+    #[test]
+    fn test_resolve_internal_references() {
+        let source = br#"
+swagger: "2.0"
+info:
+  title: Pets
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      parameters:
+      - $ref: '#/parameters/limit'
+      responses:
+        '200':
+          $ref: '#/responses/Pets'
+parameters:
+  limit:
+    name: limit
+    in: query
+    type: integer
+responses:
+  Pets:
+    description: Some pets
+    schema:
+      $ref: '#/definitions/Pet'
+definitions:
+  Pet:
+    type: object
+    properties:
+      owner:
+        $ref: '#/definitions/Owner'
+  Owner:
+    type: string
+"#;
+        let doc = parse_document(source).unwrap();
+        let resolved = Resolver::new().resolve(&doc).unwrap();
+
+        let get = resolved.paths.as_ref().unwrap().path[0].value.as_ref().unwrap().get.as_ref().unwrap();
+        match get.parameters[0].oneof.as_ref().unwrap() {
+            parameters_item::Oneof::Parameter(p) => assert!(matches!(
+                p.oneof.as_ref().unwrap(),
+                parameter::Oneof::NonBodyParameter(NonBodyParameter {
+                    oneof: Some(non_body_parameter::Oneof::QueryParameterSubSchema(q)),
+                }) if q.name == "limit"
+            )),
+            other => panic!("unexpected {:?}", other),
+        }
+        let response = match get.responses.as_ref().unwrap().response_code[0].value.as_ref().unwrap().oneof.as_ref() {
+            Some(response_value::Oneof::Response(r)) => r,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(response.description, "Some pets");
+        let pet = match response.schema.as_ref().unwrap().oneof.as_ref().unwrap() {
+            schema_item::Oneof::Schema(s) => s,
+            other => panic!("unexpected {:?}", other),
+        };
+        let owner = &pet.properties.as_ref().unwrap().additional_properties[0];
+        assert_eq!(owner.value.as_ref().unwrap().r#ref, "");
+        assert_eq!(owner.value.as_ref().unwrap().r#type.as_ref().unwrap().value, ["string"]);
+    }
+
+    #[test]
+    fn test_resolve_circular_references() {
+        let source = br#"
+swagger: "2.0"
+info:
+  title: Trees
+  version: "1.0"
+definitions:
+  Node:
+    type: object
+    properties:
+      children:
+        type: array
+        items:
+          $ref: '#/definitions/Node'
+"#;
+        let doc = parse_document(source).unwrap();
+        let (resolved, report) = Resolver::new().resolve_with_report(&doc).unwrap();
+        assert_eq!(resolved, doc);
+        assert_eq!(report.cycles.len(), 1);
+
+        let flattened = Resolver::new().flatten(&doc).unwrap();
+        let text = String::from_utf8(crate::document::yaml_value(&flattened)).unwrap();
+        assert!(!text.contains("$ref"), "{}", text);
+    }
+
+    #[test]
+    fn test_definitions_section() {
+        let section = |keys: &[&str]| definitions_section(&keys.iter().map(|k| k.to_string()).collect::<Vec<_>>());
+        assert_eq!(section(&["paths", "/pets"]), None);
+        assert_eq!(section(&["paths", "/pets", "get", "parameters", "0"]), Some("parameters"));
+        assert_eq!(section(&["paths", "/pets", "get", "responses", "200"]), Some("responses"));
+        assert_eq!(section(&["paths", "/pets", "get", "parameters", "0", "schema"]), Some("definitions"));
+        assert_eq!(section(&["definitions", "Pet", "properties", "responses", "x"]), Some("definitions"));
+        assert_eq!(section(&["responses", "Error"]), Some("responses"));
+        assert_eq!(section(&["parameters", "limit"]), Some("parameters"));
+    }
+}
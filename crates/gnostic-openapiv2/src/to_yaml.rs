@@ -0,0 +1,807 @@
+//! YAML emission for OpenAPI v2 models.
+//!
+//! Keys are written in the order fields are declared in openapiv2.proto, and
+//! named collections (paths, definitions, extensions, ...) are written as
+//! mapping entries in their stored order, or sorted by name when
+//! [`KeyOrder::Canonical`] is requested.
+
+use gnostic_compiler::writer::*;
+use serde_yaml::{Mapping, Value as Yaml};
+
+use crate::openapi_v2::*;
+
+impl ToYaml for AdditionalPropertiesItem {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(additional_properties_item::Oneof::Schema(v)) => v.to_yaml_with(options),
+            Some(additional_properties_item::Oneof::Boolean(v)) => Yaml::Bool(*v),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for Any {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        yaml_for_any_source(&self.yaml)
+    }
+}
+
+impl ToYaml for ApiKeySecurity {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "in", &self.r#in);
+        insert_string(&mut map, "description", &self.description);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for BasicAuthenticationSecurity {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "description", &self.description);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for BodyParameter {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "in", &self.r#in);
+        insert_bool(&mut map, "required", self.required);
+        insert_message(&mut map, "schema", self.schema.as_ref(), options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Contact {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "url", &self.url);
+        insert_string(&mut map, "email", &self.email);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Default {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Definitions {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Document {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "swagger", &self.swagger);
+        insert_message(&mut map, "info", self.info.as_ref(), options);
+        insert_string(&mut map, "host", &self.host);
+        insert_string(&mut map, "basePath", &self.base_path);
+        insert_strings(&mut map, "schemes", &self.schemes);
+        insert_strings(&mut map, "consumes", &self.consumes);
+        insert_strings(&mut map, "produces", &self.produces);
+        insert_message(&mut map, "paths", self.paths.as_ref(), options);
+        insert_message(&mut map, "definitions", self.definitions.as_ref(), options);
+        insert_message(&mut map, "parameters", self.parameters.as_ref(), options);
+        insert_message(&mut map, "responses", self.responses.as_ref(), options);
+        insert_messages(&mut map, "security", &self.security, options);
+        insert_message(&mut map, "securityDefinitions", self.security_definitions.as_ref(), options);
+        insert_messages(&mut map, "tags", &self.tags, options);
+        insert_message(&mut map, "externalDocs", self.external_docs.as_ref(), options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Examples {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ExternalDocs {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "url", &self.url);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for FileSchema {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "format", &self.format);
+        insert_string(&mut map, "title", &self.title);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_strings(&mut map, "required", &self.required);
+        insert_string(&mut map, "type", &self.r#type);
+        insert_bool(&mut map, "readOnly", self.read_only);
+        insert_message(&mut map, "externalDocs", self.external_docs.as_ref(), options);
+        insert_message(&mut map, "example", self.example.as_ref(), options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for FormDataParameterSubSchema {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_bool(&mut map, "required", self.required);
+        insert_string(&mut map, "in", &self.r#in);
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "name", &self.name);
+        insert_bool(&mut map, "allowEmptyValue", self.allow_empty_value);
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "format", &self.format);
+        insert_message(&mut map, "items", self.items.as_ref(), options);
+        insert_string(&mut map, "collectionFormat", &self.collection_format);
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_float(&mut map, "maximum", self.maximum);
+        insert_bool(&mut map, "exclusiveMaximum", self.exclusive_maximum);
+        insert_float(&mut map, "minimum", self.minimum);
+        insert_bool(&mut map, "exclusiveMinimum", self.exclusive_minimum);
+        insert_int(&mut map, "maxLength", self.max_length);
+        insert_int(&mut map, "minLength", self.min_length);
+        insert_string(&mut map, "pattern", &self.pattern);
+        insert_int(&mut map, "maxItems", self.max_items);
+        insert_int(&mut map, "minItems", self.min_items);
+        insert_bool(&mut map, "uniqueItems", self.unique_items);
+        insert_messages(&mut map, "enum", &self.r#enum, options);
+        insert_float(&mut map, "multipleOf", self.multiple_of);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Header {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "format", &self.format);
+        insert_message(&mut map, "items", self.items.as_ref(), options);
+        insert_string(&mut map, "collectionFormat", &self.collection_format);
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_float(&mut map, "maximum", self.maximum);
+        insert_bool(&mut map, "exclusiveMaximum", self.exclusive_maximum);
+        insert_float(&mut map, "minimum", self.minimum);
+        insert_bool(&mut map, "exclusiveMinimum", self.exclusive_minimum);
+        insert_int(&mut map, "maxLength", self.max_length);
+        insert_int(&mut map, "minLength", self.min_length);
+        insert_string(&mut map, "pattern", &self.pattern);
+        insert_int(&mut map, "maxItems", self.max_items);
+        insert_int(&mut map, "minItems", self.min_items);
+        insert_bool(&mut map, "uniqueItems", self.unique_items);
+        insert_messages(&mut map, "enum", &self.r#enum, options);
+        insert_float(&mut map, "multipleOf", self.multiple_of);
+        insert_string(&mut map, "description", &self.description);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for HeaderParameterSubSchema {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_bool(&mut map, "required", self.required);
+        insert_string(&mut map, "in", &self.r#in);
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "format", &self.format);
+        insert_message(&mut map, "items", self.items.as_ref(), options);
+        insert_string(&mut map, "collectionFormat", &self.collection_format);
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_float(&mut map, "maximum", self.maximum);
+        insert_bool(&mut map, "exclusiveMaximum", self.exclusive_maximum);
+        insert_float(&mut map, "minimum", self.minimum);
+        insert_bool(&mut map, "exclusiveMinimum", self.exclusive_minimum);
+        insert_int(&mut map, "maxLength", self.max_length);
+        insert_int(&mut map, "minLength", self.min_length);
+        insert_string(&mut map, "pattern", &self.pattern);
+        insert_int(&mut map, "maxItems", self.max_items);
+        insert_int(&mut map, "minItems", self.min_items);
+        insert_bool(&mut map, "uniqueItems", self.unique_items);
+        insert_messages(&mut map, "enum", &self.r#enum, options);
+        insert_float(&mut map, "multipleOf", self.multiple_of);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Headers {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Info {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "title", &self.title);
+        insert_string(&mut map, "version", &self.version);
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "termsOfService", &self.terms_of_service);
+        insert_message(&mut map, "contact", self.contact.as_ref(), options);
+        insert_message(&mut map, "license", self.license.as_ref(), options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ItemsItem {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        // A single items schema is written inline rather than as a one-element list
+        match self.schema.as_slice() {
+            [item] => item.to_yaml_with(options),
+            items => sequence_node(items, options),
+        }
+    }
+}
+
+impl ToYaml for JsonReference {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "$ref", &self.r#ref);
+        insert_string(&mut map, "description", &self.description);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for License {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "url", &self.url);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for NonBodyParameter {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(non_body_parameter::Oneof::HeaderParameterSubSchema(v)) => v.to_yaml_with(options),
+            Some(non_body_parameter::Oneof::FormDataParameterSubSchema(v)) => v.to_yaml_with(options),
+            Some(non_body_parameter::Oneof::QueryParameterSubSchema(v)) => v.to_yaml_with(options),
+            Some(non_body_parameter::Oneof::PathParameterSubSchema(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for Oauth2AccessCodeSecurity {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "flow", &self.flow);
+        insert_message(&mut map, "scopes", self.scopes.as_ref(), options);
+        insert_string(&mut map, "authorizationUrl", &self.authorization_url);
+        insert_string(&mut map, "tokenUrl", &self.token_url);
+        insert_string(&mut map, "description", &self.description);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Oauth2ApplicationSecurity {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "flow", &self.flow);
+        insert_message(&mut map, "scopes", self.scopes.as_ref(), options);
+        insert_string(&mut map, "tokenUrl", &self.token_url);
+        insert_string(&mut map, "description", &self.description);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Oauth2ImplicitSecurity {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "flow", &self.flow);
+        insert_message(&mut map, "scopes", self.scopes.as_ref(), options);
+        insert_string(&mut map, "authorizationUrl", &self.authorization_url);
+        insert_string(&mut map, "description", &self.description);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Oauth2PasswordSecurity {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "flow", &self.flow);
+        insert_message(&mut map, "scopes", self.scopes.as_ref(), options);
+        insert_string(&mut map, "tokenUrl", &self.token_url);
+        insert_string(&mut map, "description", &self.description);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Oauth2Scopes {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Operation {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_strings(&mut map, "tags", &self.tags);
+        insert_string(&mut map, "summary", &self.summary);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "externalDocs", self.external_docs.as_ref(), options);
+        insert_string(&mut map, "operationId", &self.operation_id);
+        insert_strings(&mut map, "produces", &self.produces);
+        insert_strings(&mut map, "consumes", &self.consumes);
+        insert_messages(&mut map, "parameters", &self.parameters, options);
+        insert_message(&mut map, "responses", self.responses.as_ref(), options);
+        insert_strings(&mut map, "schemes", &self.schemes);
+        insert_bool(&mut map, "deprecated", self.deprecated);
+        insert_messages(&mut map, "security", &self.security, options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Parameter {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(parameter::Oneof::BodyParameter(v)) => v.to_yaml_with(options),
+            Some(parameter::Oneof::NonBodyParameter(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for ParameterDefinitions {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ParametersItem {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(parameters_item::Oneof::Parameter(v)) => v.to_yaml_with(options),
+            Some(parameters_item::Oneof::JsonReference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for PathItem {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "$ref", &self.r#ref);
+        insert_message(&mut map, "get", self.get.as_ref(), options);
+        insert_message(&mut map, "put", self.put.as_ref(), options);
+        insert_message(&mut map, "post", self.post.as_ref(), options);
+        insert_message(&mut map, "delete", self.delete.as_ref(), options);
+        insert_message(&mut map, "options", self.options.as_ref(), options);
+        insert_message(&mut map, "head", self.head.as_ref(), options);
+        insert_message(&mut map, "patch", self.patch.as_ref(), options);
+        insert_messages(&mut map, "parameters", &self.parameters, options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for PathParameterSubSchema {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_bool(&mut map, "required", self.required);
+        insert_string(&mut map, "in", &self.r#in);
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "format", &self.format);
+        insert_message(&mut map, "items", self.items.as_ref(), options);
+        insert_string(&mut map, "collectionFormat", &self.collection_format);
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_float(&mut map, "maximum", self.maximum);
+        insert_bool(&mut map, "exclusiveMaximum", self.exclusive_maximum);
+        insert_float(&mut map, "minimum", self.minimum);
+        insert_bool(&mut map, "exclusiveMinimum", self.exclusive_minimum);
+        insert_int(&mut map, "maxLength", self.max_length);
+        insert_int(&mut map, "minLength", self.min_length);
+        insert_string(&mut map, "pattern", &self.pattern);
+        insert_int(&mut map, "maxItems", self.max_items);
+        insert_int(&mut map, "minItems", self.min_items);
+        insert_bool(&mut map, "uniqueItems", self.unique_items);
+        insert_messages(&mut map, "enum", &self.r#enum, options);
+        insert_float(&mut map, "multipleOf", self.multiple_of);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Paths {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.vendor_extension, options);
+        insert_named(&mut map, &self.path, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for PrimitivesItems {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "format", &self.format);
+        insert_message(&mut map, "items", self.items.as_deref(), options);
+        insert_string(&mut map, "collectionFormat", &self.collection_format);
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_float(&mut map, "maximum", self.maximum);
+        insert_bool(&mut map, "exclusiveMaximum", self.exclusive_maximum);
+        insert_float(&mut map, "minimum", self.minimum);
+        insert_bool(&mut map, "exclusiveMinimum", self.exclusive_minimum);
+        insert_int(&mut map, "maxLength", self.max_length);
+        insert_int(&mut map, "minLength", self.min_length);
+        insert_string(&mut map, "pattern", &self.pattern);
+        insert_int(&mut map, "maxItems", self.max_items);
+        insert_int(&mut map, "minItems", self.min_items);
+        insert_bool(&mut map, "uniqueItems", self.unique_items);
+        insert_messages(&mut map, "enum", &self.r#enum, options);
+        insert_float(&mut map, "multipleOf", self.multiple_of);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Properties {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for QueryParameterSubSchema {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_bool(&mut map, "required", self.required);
+        insert_string(&mut map, "in", &self.r#in);
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "name", &self.name);
+        insert_bool(&mut map, "allowEmptyValue", self.allow_empty_value);
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "format", &self.format);
+        insert_message(&mut map, "items", self.items.as_ref(), options);
+        insert_string(&mut map, "collectionFormat", &self.collection_format);
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_float(&mut map, "maximum", self.maximum);
+        insert_bool(&mut map, "exclusiveMaximum", self.exclusive_maximum);
+        insert_float(&mut map, "minimum", self.minimum);
+        insert_bool(&mut map, "exclusiveMinimum", self.exclusive_minimum);
+        insert_int(&mut map, "maxLength", self.max_length);
+        insert_int(&mut map, "minLength", self.min_length);
+        insert_string(&mut map, "pattern", &self.pattern);
+        insert_int(&mut map, "maxItems", self.max_items);
+        insert_int(&mut map, "minItems", self.min_items);
+        insert_bool(&mut map, "uniqueItems", self.unique_items);
+        insert_messages(&mut map, "enum", &self.r#enum, options);
+        insert_float(&mut map, "multipleOf", self.multiple_of);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Response {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "schema", self.schema.as_ref(), options);
+        insert_message(&mut map, "headers", self.headers.as_ref(), options);
+        insert_message(&mut map, "examples", self.examples.as_ref(), options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ResponseDefinitions {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ResponseValue {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(response_value::Oneof::Response(v)) => v.to_yaml_with(options),
+            Some(response_value::Oneof::JsonReference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for Responses {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.response_code, options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Schema {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "$ref", &self.r#ref);
+        insert_string(&mut map, "format", &self.format);
+        insert_string(&mut map, "title", &self.title);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_float(&mut map, "multipleOf", self.multiple_of);
+        insert_float(&mut map, "maximum", self.maximum);
+        insert_bool(&mut map, "exclusiveMaximum", self.exclusive_maximum);
+        insert_float(&mut map, "minimum", self.minimum);
+        insert_bool(&mut map, "exclusiveMinimum", self.exclusive_minimum);
+        insert_int(&mut map, "maxLength", self.max_length);
+        insert_int(&mut map, "minLength", self.min_length);
+        insert_string(&mut map, "pattern", &self.pattern);
+        insert_int(&mut map, "maxItems", self.max_items);
+        insert_int(&mut map, "minItems", self.min_items);
+        insert_bool(&mut map, "uniqueItems", self.unique_items);
+        insert_int(&mut map, "maxProperties", self.max_properties);
+        insert_int(&mut map, "minProperties", self.min_properties);
+        insert_strings(&mut map, "required", &self.required);
+        insert_messages(&mut map, "enum", &self.r#enum, options);
+        insert_message(&mut map, "additionalProperties", self.additional_properties.as_deref(), options);
+        insert_message(&mut map, "type", self.r#type.as_ref(), options);
+        insert_message(&mut map, "items", self.items.as_ref(), options);
+        insert_messages(&mut map, "allOf", &self.all_of, options);
+        insert_message(&mut map, "properties", self.properties.as_ref(), options);
+        insert_string(&mut map, "discriminator", &self.discriminator);
+        insert_bool(&mut map, "readOnly", self.read_only);
+        insert_message(&mut map, "xml", self.xml.as_ref(), options);
+        insert_message(&mut map, "externalDocs", self.external_docs.as_ref(), options);
+        insert_message(&mut map, "example", self.example.as_ref(), options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for SchemaItem {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(schema_item::Oneof::Schema(v)) => v.to_yaml_with(options),
+            Some(schema_item::Oneof::FileSchema(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for SecurityDefinitions {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for SecurityDefinitionsItem {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(security_definitions_item::Oneof::BasicAuthenticationSecurity(v)) => v.to_yaml_with(options),
+            Some(security_definitions_item::Oneof::ApiKeySecurity(v)) => v.to_yaml_with(options),
+            Some(security_definitions_item::Oneof::Oauth2ImplicitSecurity(v)) => v.to_yaml_with(options),
+            Some(security_definitions_item::Oneof::Oauth2PasswordSecurity(v)) => v.to_yaml_with(options),
+            Some(security_definitions_item::Oneof::Oauth2ApplicationSecurity(v)) => v.to_yaml_with(options),
+            Some(security_definitions_item::Oneof::Oauth2AccessCodeSecurity(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for SecurityRequirement {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for StringArray {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        Yaml::Sequence(self.value.iter().map(|s| s.as_str().into()).collect())
+    }
+}
+
+impl ToYaml for Tag {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "externalDocs", self.external_docs.as_ref(), options);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for TypeItem {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        // A single type is written as a scalar rather than as a one-element list
+        match self.value.as_slice() {
+            [value] => value.as_str().into(),
+            values => Yaml::Sequence(values.iter().map(|s| s.as_str().into()).collect()),
+        }
+    }
+}
+
+impl ToYaml for VendorExtension {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Xml {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "namespace", &self.namespace);
+        insert_string(&mut map, "prefix", &self.prefix);
+        insert_bool(&mut map, "attribute", self.attribute);
+        insert_bool(&mut map, "wrapped", self.wrapped);
+        insert_named(&mut map, &self.vendor_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl Named for NamedAny {
+    type Value = Any;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&Any> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedHeader {
+    type Value = Header;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&Header> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedParameter {
+    type Value = Parameter;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&Parameter> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedPathItem {
+    type Value = PathItem;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&PathItem> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedResponse {
+    type Value = Response;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&Response> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedResponseValue {
+    type Value = ResponseValue;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&ResponseValue> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedSchema {
+    type Value = Schema;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&Schema> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedSecurityDefinitionsItem {
+    type Value = SecurityDefinitionsItem;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&SecurityDefinitionsItem> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedString {
+    type Value = String;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&String> {
+        Some(&self.value)
+    }
+}
+
+impl Named for NamedStringArray {
+    type Value = StringArray;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&StringArray> {
+        self.value.as_ref()
+    }
+}
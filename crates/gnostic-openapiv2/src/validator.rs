@@ -0,0 +1,608 @@
+//! Structural validation of OpenAPI v2 (Swagger 2.0) documents.
+//!
+//! The allowed and required keys of each object follow the Swagger 2.0 JSON
+//! schema: keys that are neither fixed fields, pattern fields nor "x-"
+//! extensions are reported, as are missing required fields. Parameters and
+//! security schemes are checked against the variant selected by their "in"
+//! or "type" field.
+
+use gnostic_compiler::{
+    check_operation_ids, check_path_parameters, check_responses, check_security, invalid_keys_in_map,
+    missing_keys_in_map, CompilerError, Context, ErrorCode, ErrorGroup, ToYaml, ValidateOptions, ValidationReport,
+};
+use regex::Regex;
+use serde_yaml::Value as Yaml;
+use std::sync::Arc;
+
+use crate::openapi_v2::Document;
+
+/// Validates a parsed document.
+///
+/// Fields the model does not represent have already been dropped, so unknown
+/// keys are only found by [`validate_node`] on the source.
+pub fn validate(document: &Document) -> ErrorGroup {
+    validate_with_options(document, &ValidateOptions::default())
+}
+
+/// Validates a parsed document with the given options.
+pub fn validate_with_options(document: &Document, options: &ValidateOptions) -> ErrorGroup {
+    validate_node_with_options(&document.to_yaml(), options)
+}
+
+/// Validates the YAML of a document, returning the findings located by
+/// path. The group is empty if the document is valid.
+pub fn validate_node(node: &Yaml) -> ErrorGroup {
+    validate_node_with_options(node, &ValidateOptions::default())
+}
+
+/// Validates the YAML of a document with the given options.
+///
+/// After the structure of each object, the relations between objects are
+/// checked, such as operationIds being unique, path templates having
+/// parameters and security requirements naming defined schemes.
+pub fn validate_node_with_options(node: &Yaml, options: &ValidateOptions) -> ErrorGroup {
+    let mut validator = Validator {
+        extension: Regex::new("^x-").expect("valid pattern"),
+        path: Regex::new("^/").expect("valid pattern"),
+        any: Regex::new("^").expect("valid pattern"),
+        errors: Vec::new(),
+    };
+    let root = Arc::new(Context::root("$"));
+    check_version(node, &root, &mut validator.errors);
+    validator.validate(node, Kind::Document, &root);
+    validator.errors.extend(check_operation_ids(node, OPERATIONS, options));
+    validator.errors.extend(check_path_parameters(node, OPERATIONS));
+    validator.errors.extend(check_responses(node, OPERATIONS, false));
+    validator.errors.extend(check_security(node, OPERATIONS, &["securityDefinitions"]));
+    ErrorGroup::new(validator.errors)
+}
+
+/// Validates the YAML of a document, returning the findings located by
+/// JSON pointers.
+pub fn validation_report(node: &Yaml, options: &ValidateOptions) -> ValidationReport {
+    ValidationReport::from_errors(&validate_node_with_options(node, options), node)
+}
+
+/// Reports a swagger version other than "2.0".
+fn check_version(node: &Yaml, root: &Arc<Context>, errors: &mut Vec<CompilerError>) {
+    let context = root.child("swagger");
+    match &node["swagger"] {
+        Yaml::Null => {}
+        Yaml::String(version) if version == "2.0" => {}
+        Yaml::String(version) => errors.push(CompilerError::new_with_code(
+            &context,
+            ErrorCode::UnsupportedVersion,
+            format!("has unsupported version {}; expected 2.0", version),
+        )),
+        other => errors.push(CompilerError::new_with_code(
+            &context,
+            ErrorCode::InvalidType,
+            format!("expected a string, got {}", type_name(other)),
+        )),
+    }
+}
+
+/// The Swagger objects that are validated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Document,
+    Info,
+    Contact,
+    License,
+    Paths,
+    PathItem,
+    Operation,
+    ExternalDocs,
+    Parameter,
+    BodyParameter,
+    NonBodyParameter,
+    Items,
+    Responses,
+    Response,
+    Header,
+    Examples,
+    Tag,
+    JsonReference,
+    Schema,
+    Xml,
+    SecurityScheme,
+    BasicAuthentication,
+    ApiKey,
+    OAuth2,
+    Scopes,
+}
+
+/// The shape of a field value.
+enum Shape {
+    Object(Kind),
+    Map(Kind),
+    List(Kind),
+}
+
+/// Validation keywords shared by non-body parameters, headers and items.
+const PRIMITIVE_FIELDS: &[&str] = &[
+    "type",
+    "format",
+    "items",
+    "collectionFormat",
+    "default",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "enum",
+    "multipleOf",
+];
+
+const OPERATIONS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch"];
+
+impl Kind {
+    /// Returns the fixed fields of the object.
+    fn fixed(self) -> Vec<&'static str> {
+        let fields: &[&str] = match self {
+            Kind::Document => &[
+                "swagger",
+                "info",
+                "host",
+                "basePath",
+                "schemes",
+                "consumes",
+                "produces",
+                "paths",
+                "definitions",
+                "parameters",
+                "responses",
+                "securityDefinitions",
+                "security",
+                "tags",
+                "externalDocs",
+            ],
+            Kind::Info => &["title", "version", "description", "termsOfService", "contact", "license"],
+            Kind::Contact => &["name", "url", "email"],
+            Kind::License => &["name", "url"],
+            Kind::Paths | Kind::Examples | Kind::Scopes => &[],
+            Kind::PathItem => &["$ref", "get", "put", "post", "delete", "options", "head", "patch", "parameters"],
+            Kind::Operation => &[
+                "tags",
+                "summary",
+                "description",
+                "externalDocs",
+                "operationId",
+                "produces",
+                "consumes",
+                "parameters",
+                "responses",
+                "schemes",
+                "deprecated",
+                "security",
+            ],
+            Kind::ExternalDocs => &["description", "url"],
+            Kind::Parameter => {
+                return [&["name", "in", "description", "required", "schema", "allowEmptyValue"], PRIMITIVE_FIELDS]
+                    .concat()
+            }
+            Kind::BodyParameter => &["name", "in", "description", "required", "schema"],
+            Kind::NonBodyParameter => {
+                return [&["name", "in", "description", "required", "allowEmptyValue"], PRIMITIVE_FIELDS].concat()
+            }
+            Kind::Items => PRIMITIVE_FIELDS,
+            Kind::Responses => &["default"],
+            Kind::Response => &["description", "schema", "headers", "examples"],
+            Kind::Header => return [&["description"], PRIMITIVE_FIELDS].concat(),
+            Kind::Tag => &["name", "description", "externalDocs"],
+            Kind::JsonReference => &["$ref"],
+            Kind::Schema => &[
+                "$ref",
+                "format",
+                "title",
+                "description",
+                "default",
+                "multipleOf",
+                "maximum",
+                "exclusiveMaximum",
+                "minimum",
+                "exclusiveMinimum",
+                "maxLength",
+                "minLength",
+                "pattern",
+                "maxItems",
+                "minItems",
+                "uniqueItems",
+                "maxProperties",
+                "minProperties",
+                "required",
+                "enum",
+                "additionalProperties",
+                "type",
+                "items",
+                "allOf",
+                "properties",
+                "discriminator",
+                "readOnly",
+                "xml",
+                "externalDocs",
+                "example",
+            ],
+            Kind::Xml => &["name", "namespace", "prefix", "attribute", "wrapped"],
+            Kind::SecurityScheme => &[
+                "type",
+                "description",
+                "name",
+                "in",
+                "flow",
+                "authorizationUrl",
+                "tokenUrl",
+                "scopes",
+            ],
+            Kind::BasicAuthentication => &["type", "description"],
+            Kind::ApiKey => &["type", "name", "in", "description"],
+            Kind::OAuth2 => &["type", "flow", "scopes", "authorizationUrl", "tokenUrl", "description"],
+        };
+        fields.to_vec()
+    }
+
+    /// Returns the fields that must be present.
+    fn required(self) -> &'static [&'static str] {
+        match self {
+            Kind::Document => &["swagger", "info", "paths"],
+            Kind::Info => &["title", "version"],
+            Kind::License => &["name"],
+            Kind::Operation => &["responses"],
+            Kind::ExternalDocs => &["url"],
+            Kind::Parameter => &["name", "in"],
+            Kind::BodyParameter => &["name", "in", "schema"],
+            Kind::NonBodyParameter => &["name", "in", "type"],
+            Kind::Items | Kind::Header => &["type"],
+            Kind::Response => &["description"],
+            Kind::Tag => &["name"],
+            Kind::JsonReference => &["$ref"],
+            Kind::SecurityScheme | Kind::BasicAuthentication => &["type"],
+            Kind::ApiKey => &["type", "name", "in"],
+            Kind::OAuth2 => &["type", "flow", "scopes"],
+            _ => &[],
+        }
+    }
+
+    /// Returns the kind that applies to `map`, which may be a reference or
+    /// one of the variants of the kind.
+    fn refine(self, map: &serde_yaml::Mapping) -> Kind {
+        let field = |key: &str| map.get(key).and_then(Yaml::as_str);
+        match self {
+            Kind::Parameter | Kind::Response if map.contains_key("$ref") => Kind::JsonReference,
+            Kind::Parameter => match field("in") {
+                Some("body") => Kind::BodyParameter,
+                Some("header" | "formData" | "query" | "path") => Kind::NonBodyParameter,
+                _ => Kind::Parameter,
+            },
+            Kind::SecurityScheme => match field("type") {
+                Some("basic") => Kind::BasicAuthentication,
+                Some("apiKey") => Kind::ApiKey,
+                Some("oauth2") => Kind::OAuth2,
+                _ => Kind::SecurityScheme,
+            },
+            kind => kind,
+        }
+    }
+
+    /// Returns the shape of the value of `key`, for values that are objects.
+    fn field(self, key: &str) -> Option<Shape> {
+        use Shape::*;
+        let shape = match (self, key) {
+            (_, key) if key.starts_with("x-") => return None,
+            (Kind::Document, "info") => Object(Kind::Info),
+            (Kind::Document, "paths") => Object(Kind::Paths),
+            (Kind::Document, "definitions") => Map(Kind::Schema),
+            (Kind::Document, "parameters") => Map(Kind::Parameter),
+            (Kind::Document, "responses") => Map(Kind::Response),
+            (Kind::Document, "securityDefinitions") => Map(Kind::SecurityScheme),
+            (Kind::Document, "tags") => List(Kind::Tag),
+            (Kind::Document | Kind::Operation | Kind::Tag | Kind::Schema, "externalDocs") => {
+                Object(Kind::ExternalDocs)
+            }
+            (Kind::Info, "contact") => Object(Kind::Contact),
+            (Kind::Info, "license") => Object(Kind::License),
+            (Kind::Paths, _) => Object(Kind::PathItem),
+            (Kind::PathItem, method) if OPERATIONS.contains(&method) => Object(Kind::Operation),
+            (Kind::PathItem | Kind::Operation, "parameters") => List(Kind::Parameter),
+            (Kind::Operation, "responses") => Object(Kind::Responses),
+            (Kind::Parameter | Kind::BodyParameter | Kind::Response, "schema") => Object(Kind::Schema),
+            (Kind::Parameter | Kind::NonBodyParameter | Kind::Items | Kind::Header, "items") => Object(Kind::Items),
+            (Kind::Responses, _) => Object(Kind::Response),
+            (Kind::Response, "headers") => Map(Kind::Header),
+            (Kind::Response, "examples") => Object(Kind::Examples),
+            (Kind::Schema, "xml") => Object(Kind::Xml),
+            (Kind::Schema, "allOf") => List(Kind::Schema),
+            (Kind::Schema, "properties") => Map(Kind::Schema),
+            (Kind::SecurityScheme | Kind::OAuth2, "scopes") => Object(Kind::Scopes),
+            _ => return None,
+        };
+        Some(shape)
+    }
+}
+
+struct Validator {
+    extension: Regex,
+    path: Regex,
+    any: Regex,
+    errors: Vec<CompilerError>,
+}
+
+impl Validator {
+    fn validate(&mut self, node: &Yaml, kind: Kind, context: &Arc<Context>) {
+        let Yaml::Mapping(map) = node else {
+            self.errors.push(CompilerError::new_with_code(
+                context,
+                ErrorCode::InvalidType,
+                format!("expected an object, got {}", type_name(node)),
+            ));
+            return;
+        };
+        let kind = kind.refine(map);
+
+        let patterns: Vec<&Regex> = match kind {
+            Kind::JsonReference => Vec::new(),
+            Kind::Paths => vec![&self.path, &self.extension],
+            // Status codes are checked by check_responses
+            Kind::Responses | Kind::Examples | Kind::Scopes => vec![&self.any],
+            _ => vec![&self.extension],
+        };
+        let fixed = kind.fixed();
+        let invalid = invalid_keys_in_map(node, &fixed, &patterns);
+        for key in &invalid {
+            self.errors.push(CompilerError::unknown_key(context, key, &fixed));
+        }
+        for key in missing_keys_in_map(node, kind.required()) {
+            self.errors.push(CompilerError::new_with_code(
+                context,
+                ErrorCode::MissingRequiredField,
+                format!("is missing required property: {}", key),
+            ));
+        }
+
+        for (key, value) in map {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            if invalid.iter().any(|invalid| invalid == key) {
+                continue;
+            }
+            let Some(shape) = kind.field(key) else {
+                continue;
+            };
+            let child = Arc::new(context.child(key));
+            match shape {
+                Shape::Object(kind) => self.validate(value, kind, &child),
+                Shape::Map(kind) => self.validate_entries(value, kind, &child),
+                Shape::List(kind) => self.validate_items(value, kind, &child),
+            }
+        }
+        if kind == Kind::Schema {
+            // items may be one schema or a list of them
+            match map.get("items") {
+                Some(value @ Yaml::Sequence(_)) => {
+                    self.validate_items(value, Kind::Schema, &Arc::new(context.child("items")))
+                }
+                Some(value) => self.validate(value, Kind::Schema, &Arc::new(context.child("items"))),
+                None => {}
+            }
+            // additionalProperties may also be a boolean
+            if let Some(value @ Yaml::Mapping(_)) = map.get("additionalProperties") {
+                self.validate(value, Kind::Schema, &Arc::new(context.child("additionalProperties")));
+            }
+        }
+    }
+
+    fn validate_entries(&mut self, node: &Yaml, kind: Kind, context: &Arc<Context>) {
+        let Yaml::Mapping(map) = node else {
+            self.errors.push(CompilerError::new_with_code(
+                context,
+                ErrorCode::InvalidType,
+                format!("expected an object, got {}", type_name(node)),
+            ));
+            return;
+        };
+        for (key, value) in map {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            self.validate(value, kind, &Arc::new(context.child(key)));
+        }
+    }
+
+    fn validate_items(&mut self, node: &Yaml, kind: Kind, context: &Arc<Context>) {
+        let Yaml::Sequence(items) = node else {
+            self.errors.push(CompilerError::new_with_code(
+                context,
+                ErrorCode::InvalidType,
+                format!("expected an array, got {}", type_name(node)),
+            ));
+            return;
+        };
+        for (i, item) in items.iter().enumerate() {
+            self.validate(item, kind, &Arc::new(context.child(i.to_string())));
+        }
+    }
+}
+
+fn type_name(node: &Yaml) -> &'static str {
+    match node {
+        Yaml::Null => "null",
+        Yaml::Bool(_) => "a boolean",
+        Yaml::Number(_) => "a number",
+        Yaml::String(_) => "a string",
+        Yaml::Sequence(_) => "an array",
+        Yaml::Mapping(_) => "an object",
+        Yaml::Tagged(_) => "a tagged value",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn findings(source: &str) -> Vec<String> {
+        let node: Yaml = serde_yaml::from_str(source).unwrap();
+        validate_node(&node).errors.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_valid_document() {
+        let source = r#"
+swagger: "2.0"
+info:
+  title: Pets
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      parameters:
+      - $ref: '#/parameters/limit'
+      - name: tags
+        in: query
+        type: array
+        items:
+          type: string
+      responses:
+        '200':
+          description: ok
+          schema:
+            type: array
+            items:
+              $ref: '#/definitions/Pet'
+          examples:
+            application/json: []
+        default:
+          $ref: '#/responses/Error'
+parameters:
+  limit:
+    name: limit
+    in: query
+    type: integer
+responses:
+  Error:
+    description: error
+definitions:
+  Pet:
+    type: object
+    additionalProperties:
+      type: string
+securityDefinitions:
+  key:
+    type: apiKey
+    name: api_key
+    in: header
+  oauth:
+    type: oauth2
+    flow: implicit
+    authorizationUrl: https://example.com/auth
+    scopes:
+      read:pets: read your pets
+"#;
+        assert!(findings(source).is_empty(), "{:?}", findings(source));
+    }
+
+    #[test]
+    fn test_invalid_document() {
+        let source = r#"
+swagger: "2.0"
+info:
+  title: Pets
+  version: "1.0"
+host: example.com
+paths:
+  /pets:
+    get:
+      parameters:
+      - name: body
+        in: body
+        type: string
+      - name: limit
+        in: query
+        schema:
+          type: integer
+      responses:
+        '2XX':
+          description: ok
+    post:
+      responses:
+        '201':
+          descripton: created
+securityDefinitions:
+  basic:
+    type: basic
+    name: user
+"#;
+        assert_eq!(
+            findings(source),
+            [
+                "$.paths./pets.get.parameters.0 has invalid property: type",
+                "$.paths./pets.get.parameters.0 is missing required property: schema",
+                "$.paths./pets.get.parameters.1 has invalid property: schema",
+                "$.paths./pets.get.parameters.1 is missing required property: type",
+                "$.paths./pets.post.responses.201 has invalid property: descripton (did you mean description?)",
+                "$.paths./pets.post.responses.201 is missing required property: description",
+                "$.securityDefinitions.basic has invalid property: name",
+                "$.paths./pets.get.responses has invalid status code: 2XX",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_versions() {
+        let source = "info: {title: Pets, version: '1.0'}\npaths: {}\n";
+        assert_eq!(findings(&format!("swagger: 2.0\n{}", source)), ["$.swagger expected a string, got a number"]);
+        assert_eq!(
+            findings(&format!("swagger: '1.2'\n{}", source)),
+            ["$.swagger has unsupported version 1.2; expected 2.0"]
+        );
+    }
+
+    #[test]
+    fn test_validate_parsed_document() {
+        let source = r#"
+swagger: "2.0"
+info:
+  title: Pets
+  version: "1.0"
+security:
+- oauth: [read]
+- basic: []
+paths:
+  /pets:
+    get:
+      security:
+      - oauth: [write]
+      - key: []
+      responses:
+        '200':
+          description: pets
+securityDefinitions:
+  oauth:
+    type: oauth2
+    flow: implicit
+    authorizationUrl: https://example.com/authorize
+    scopes:
+      read: read pets
+  key:
+    type: apiKey
+    name: key
+    in: header
+"#;
+        let document = crate::parse_document(source.as_bytes()).unwrap();
+        let findings: Vec<String> = validate(&document).errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            findings,
+            [
+                "$.security.1 uses undefined security scheme: basic",
+                "$.paths./pets.get.security.0.oauth uses undefined scope write of security scheme oauth",
+            ]
+        );
+        assert_eq!(findings, self::findings(source));
+    }
+}
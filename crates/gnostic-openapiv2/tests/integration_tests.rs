@@ -1,6 +1,10 @@
 //! Integration tests comparing Rust parsing with Go reference output.
 
-use gnostic_openapiv2::document::parse_document;
+use gnostic_compiler::{EmitOptions, ParseOptions};
+use gnostic_openapiv2::Resolver;
+use gnostic_openapiv2::document::{
+    json_value, json_value_with_options, parse_document, parse_document_with_options, yaml_value,
+};
 use serde_json::Value;
 use std::fs;
 
@@ -226,3 +230,345 @@ fn test_openapiv2_external_docs() {
         }
     }
 }
+
+#[test]
+fn test_openapiv2_preserves_source_order() {
+    let bytes = load_openapi_file("petstore-v2.json");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v2.json");
+    let reference = load_reference("petstore-v2-reference.json");
+
+    // Named* entries must appear in the same order as in the source document
+    let path_names: Vec<&str> = doc.paths.as_ref().expect("paths should exist")
+        .path.iter().map(|p| p.name.as_str()).collect();
+    let ref_path_names: Vec<&str> = reference["paths"]["path"].as_array().unwrap()
+        .iter().filter_map(|p| p["name"].as_str()).collect();
+    assert_eq!(path_names, ref_path_names, "paths order mismatch");
+
+    let def_names: Vec<&str> = doc.definitions.as_ref().expect("definitions should exist")
+        .additional_properties.iter().map(|d| d.name.as_str()).collect();
+    let ref_def_names: Vec<&str> = reference["definitions"]["additionalProperties"]
+        .as_array().unwrap()
+        .iter().filter_map(|d| d["name"].as_str()).collect();
+    assert_eq!(def_names, ref_def_names, "definitions order mismatch");
+}
+
+#[test]
+fn test_openapiv2_lenient_mode() {
+    let source = br#"
+swagger: "2.0"
+info:
+  title: Lenient
+  version: "1.0"
+paths: {}
+tags: not-a-list
+externalDocs: [oops]
+"#;
+
+    // Strict mode rejects the malformed sections
+    let err = parse_document(source).expect_err("strict parse should fail");
+    assert_eq!(err.errors.len(), 2);
+
+    // Lenient mode downgrades them to warnings and keeps the rest
+    let parsed = parse_document_with_options(source, &ParseOptions::lenient())
+        .expect("lenient parse should succeed");
+    assert_eq!(parsed.value.info.as_ref().unwrap().title, "Lenient");
+    assert!(parsed.value.tags.is_empty());
+    assert_eq!(parsed.warnings.warnings().count(), 2);
+    assert!(!parsed.warnings.has_errors());
+}
+
+#[test]
+fn test_openapiv2_fail_fast() {
+    let source = br#"
+swagger: "2.0"
+info:
+  title: Broken
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: a
+          in: nowhere
+    put:
+      parameters:
+        - name: b
+          in: nowhere
+"#;
+
+    let err = parse_document(source).expect_err("parse should fail");
+    assert_eq!(err.errors.len(), 2);
+
+    // Fail-fast stops before parsing the second operation
+    let err = parse_document_with_options(source, &ParseOptions::fail_fast())
+        .expect_err("fail-fast parse should fail");
+    assert_eq!(err.errors.len(), 1);
+    assert!(err.errors[0].to_string().contains("get.parameters.0"), "{}", err);
+}
+
+#[test]
+fn test_openapiv2_yaml_round_trip() {
+    let bytes = load_openapi_file("petstore-v2.json");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v2.json");
+
+    let yaml = yaml_value(&doc);
+    let reparsed = parse_document(&yaml).expect("Failed to parse emitted YAML");
+    assert_eq!(doc, reparsed);
+    assert_eq!(yaml, yaml_value(&reparsed));
+}
+
+#[test]
+fn test_openapiv2_json_round_trip() {
+    let bytes = load_openapi_file("petstore-v2.json");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v2.json");
+
+    let json = json_value(&doc);
+    let value: Value = serde_json::from_slice(&json).expect("emitted JSON should be valid");
+    assert_eq!(value["swagger"], "2.0");
+    assert_eq!(value["info"]["title"], doc.info.as_ref().unwrap().title.as_str());
+
+    let reparsed = parse_document(&json).expect("Failed to parse emitted JSON");
+    assert_eq!(doc, reparsed);
+}
+
+#[test]
+fn test_openapiv2_canonical_json_output() {
+    let bytes = load_openapi_file("petstore-v2.json");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v2.json");
+
+    let json = json_value_with_options(&doc, &EmitOptions::canonical());
+    let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    let definitions: Vec<&String> = value["definitions"].as_object().unwrap().keys().collect();
+    let mut sorted = definitions.clone();
+    sorted.sort();
+    assert_eq!(definitions, sorted);
+    let paths: Vec<&String> = value["paths"].as_object().unwrap().keys().collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted);
+
+    // The canonical form describes the same document
+    assert_eq!(value, serde_json::from_slice::<serde_json::Value>(&json_value(&doc)).unwrap());
+}
+
+#[test]
+fn test_openapiv2_proto_bytes_round_trip() {
+    let bytes = load_openapi_file("petstore-v2.json");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v2.json");
+
+    let encoded = doc.to_proto_bytes();
+    let decoded = gnostic_openapiv2::Document::from_proto_bytes(&encoded)
+        .expect("Failed to decode document");
+    assert_eq!(doc, decoded);
+
+    let err = gnostic_openapiv2::Document::from_proto_bytes(&[0xff, 0xff, 0xff])
+        .expect_err("garbage should not decode");
+    assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::Protobuf);
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_openapiv2_cbor_round_trip() {
+    let bytes = load_openapi_file("petstore-v2.json");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v2.json");
+
+    let encoded = doc.to_cbor_bytes().expect("Failed to encode document");
+    let decoded = gnostic_openapiv2::Document::from_cbor_bytes(&encoded)
+        .expect("Failed to decode document");
+    assert_eq!(doc, decoded);
+
+    let err = gnostic_openapiv2::Document::from_cbor_bytes(&[0xff, 0xff, 0xff])
+        .expect_err("garbage should not decode");
+    assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::Encoding);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_openapiv2_msgpack_round_trip() {
+    let bytes = load_openapi_file("petstore-v2.json");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v2.json");
+
+    let encoded = doc.to_msgpack_bytes().expect("Failed to encode document");
+    let decoded = gnostic_openapiv2::Document::from_msgpack_bytes(&encoded)
+        .expect("Failed to decode document");
+    assert_eq!(doc, decoded);
+
+    let err = gnostic_openapiv2::Document::from_msgpack_bytes(&[0xc1])
+        .expect_err("garbage should not decode");
+    assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::Encoding);
+}
+
+#[test]
+fn test_openapiv2_resolve_external_references() {
+    let path = format!("{}/refs/swagger.yaml", TESTDATA_DIR);
+    let doc = parse_document(&load_openapi_file("refs/swagger.yaml")).expect("Failed to parse refs/swagger.yaml");
+    let resolved = Resolver::new().with_base(path.clone()).resolve(&doc).expect("Failed to resolve references");
+    let yaml: serde_yaml::Value = serde_yaml::from_slice(&yaml_value(&resolved)).unwrap();
+    let get = &yaml["paths"]["/pets"]["get"];
+    assert_eq!(get["parameters"][0]["name"], "limit");
+    assert_eq!(get["responses"]["200"]["description"], "A list of pets");
+    let pet = &get["responses"]["200"]["schema"]["items"];
+    assert_eq!(pet["properties"]["owner"]["properties"]["name"]["type"], "string");
+    let text = String::from_utf8(yaml_value(&resolved)).unwrap();
+    assert!(!text.contains("$ref"), "{}", text);
+
+    // Without a base the external file cannot be found, wherever Pet is used
+    let err = Resolver::new().resolve(&doc).expect_err("external reference should not resolve");
+    assert_eq!(err.errors.len(), 3);
+}
+
+#[test]
+fn test_openapiv2_bundle_external_references() {
+    let path = format!("{}/refs/swagger.yaml", TESTDATA_DIR);
+    let doc = parse_document(&load_openapi_file("refs/swagger.yaml")).expect("Failed to parse refs/swagger.yaml");
+    let bundled = Resolver::new().with_base(path).bundle(&doc).expect("Failed to bundle references");
+    let yaml: serde_yaml::Value = serde_yaml::from_slice(&yaml_value(&bundled)).unwrap();
+
+    // Internal references are kept and external targets join the definitions
+    let get = &yaml["paths"]["/pets"]["get"];
+    assert_eq!(get["parameters"][0]["$ref"], "#/parameters/limit");
+    assert_eq!(get["responses"]["200"]["$ref"], "#/responses/PetList");
+    let definitions = &yaml["definitions"];
+    assert_eq!(definitions["Pet"]["properties"]["owner"]["$ref"], "#/definitions/Owner");
+    assert_eq!(definitions["Owner"]["properties"]["name"]["type"], "string");
+
+    let text = String::from_utf8(yaml_value(&bundled)).unwrap();
+    assert!(!text.contains(".yaml#"), "{}", text);
+    assert!(Resolver::new().resolve(&bundled).is_ok());
+}
+
+#[test]
+fn test_openapiv2_reference_graph() {
+    let doc = parse_document(&load_openapi_file("refs/swagger.yaml")).expect("Failed to parse refs/swagger.yaml");
+    let graph = doc.reference_graph();
+    assert_eq!(graph.components(), ["#/definitions/Pet", "#/parameters/limit", "#/responses/PetList"]);
+    assert_eq!(graph.dependencies("#/responses/PetList"), ["#/definitions/Pet"]);
+    assert!(graph.unused().is_empty());
+    // The external reference leaves the document
+    let pet = graph.edges().iter().find(|e| e.from.as_deref() == Some("#/definitions/Pet")).unwrap();
+    assert_eq!(pet.to, None);
+    assert_eq!(graph.dependency_order(), ["#/definitions/Pet", "#/parameters/limit", "#/responses/PetList"]);
+}
+
+#[test]
+fn test_openapiv2_validate_petstore() {
+    let content = fs::read(format!("{}/petstore-v2.json", TESTDATA_DIR)).expect("Failed to read file");
+    let node: serde_yaml::Value = serde_yaml::from_slice(&content).expect("Failed to read YAML");
+    let errors = gnostic_openapiv2::validate_node(&node);
+    assert!(errors.is_empty(), "{}", errors);
+}
+
+#[test]
+fn test_openapiv2_postman_collection() {
+    let content = fs::read(format!("{}/petstore-v2.json", TESTDATA_DIR)).expect("Failed to read file");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let collection = doc.to_postman_collection();
+    assert_eq!(collection["info"]["name"], "Swagger Petstore");
+    assert_eq!(collection["variable"][0]["value"], "https://petstore.swagger.io/v2");
+    let upload = &collection["item"][0]["item"][0];
+    assert_eq!(upload["name"], "uploads an image");
+    assert_eq!(upload["request"]["url"]["path"], serde_json::json!(["pet", ":petId", "uploadImage"]));
+    assert_eq!(upload["request"]["body"]["mode"], "formdata");
+    assert_eq!(upload["request"]["body"]["formdata"][1]["type"], "file");
+}
+
+#[test]
+fn test_openapiv2_diff() {
+    let content = load_openapi_file("petstore-v2.json");
+    let old = parse_document(&content).expect("Failed to parse document");
+    assert!(old.diff(&old).is_empty());
+
+    let mut new = old.clone();
+    new.definitions.as_mut().unwrap().additional_properties.retain(|named| named.name != "Order");
+    let changes = old.diff(&new);
+    assert_eq!(changes.to_string(), "component-removed #/definitions/Order");
+}
+
+#[test]
+fn test_openapiv2_filter() {
+    let content = load_openapi_file("petstore-v2.json");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let stores = doc.filter_by_tags(&["store"]).expect("Failed to filter by tags");
+    let paths = stores.paths.as_ref().unwrap();
+    assert!(paths.path.iter().all(|named| named.name.starts_with("/store")));
+    let definitions = stores.definitions.as_ref().unwrap();
+    let names: Vec<&str> = definitions.additional_properties.iter().map(|named| named.name.as_str()).collect();
+    assert_eq!(names, ["Order"]);
+
+    let none = doc.remove_paths_matching(&regex::Regex::new("^/").unwrap()).unwrap();
+    assert!(none.paths.as_ref().is_none_or(|paths| paths.path.is_empty()));
+    assert!(none.definitions.as_ref().is_none_or(|definitions| definitions.additional_properties.is_empty()));
+}
+
+#[test]
+fn test_openapiv2_vendor_extensions() {
+    use gnostic_openapiv2::openapi_v2::{non_body_parameter, parameter, parameters_item};
+
+    let source = br#"
+swagger: "2.0"
+info: {title: Pets, version: "1.0", x-audience: public}
+x-google-backend: {address: https://pets.example.com}
+paths:
+  x-paths-note: reviewed
+  /pets:
+    get:
+      x-google-quota: {metricCosts: {read: 1}}
+      parameters:
+      - {name: limit, in: query, type: integer, x-example: 10}
+      responses:
+        x-cached: true
+        '200': {description: ok}
+definitions:
+  Pet: {type: object, x-nullable: true}
+"#;
+    let doc = parse_document(source).expect("Failed to parse vendor extensions");
+    assert_eq!(doc.vendor_extension[0].name, "x-google-backend");
+    assert_eq!(doc.info.as_ref().unwrap().vendor_extension[0].name, "x-audience");
+    let paths = doc.paths.as_ref().unwrap();
+    assert_eq!(paths.vendor_extension[0].name, "x-paths-note");
+    assert_eq!(paths.path.len(), 1);
+    let get = paths.path[0].value.as_ref().unwrap().get.as_ref().unwrap();
+    assert_eq!(get.vendor_extension[0].name, "x-google-quota");
+    let Some(parameters_item::Oneof::Parameter(parameter)) = &get.parameters[0].oneof else {
+        panic!("expected a parameter");
+    };
+    let Some(parameter::Oneof::NonBodyParameter(non_body)) = &parameter.oneof else {
+        panic!("expected a non-body parameter");
+    };
+    let Some(non_body_parameter::Oneof::QueryParameterSubSchema(query)) = &non_body.oneof else {
+        panic!("expected a query parameter");
+    };
+    assert_eq!(query.vendor_extension[0].value.as_ref().unwrap().yaml, "10\n");
+    let responses = get.responses.as_ref().unwrap();
+    assert_eq!(responses.vendor_extension[0].name, "x-cached");
+    assert_eq!(responses.response_code.len(), 1);
+    let pet = doc.definitions.as_ref().unwrap().additional_properties[0].value.as_ref().unwrap();
+    assert_eq!(pet.vendor_extension[0].name, "x-nullable");
+
+    assert_eq!(parse_document(&yaml_value(&doc)).unwrap(), doc);
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn test_openapiv2_type_sizes() {
+    use gnostic_openapiv2::openapi_v2::{PathItem, Schema};
+    use std::mem::size_of;
+
+    assert!(size_of::<PathItem>() <= 256, "PathItem is {} bytes", size_of::<PathItem>());
+    assert!(size_of::<Schema>() <= 512, "Schema is {} bytes", size_of::<Schema>());
+}
+
+#[test]
+fn test_openapiv2_hash() {
+    use gnostic_openapiv2::openapi_v2::{Document, Schema};
+    use std::collections::HashSet;
+
+    let doc = parse_document(&load_openapi_file("petstore-v2.json")).unwrap();
+    let documents: HashSet<Document> = [doc.clone(), doc].into_iter().collect();
+    assert_eq!(documents.len(), 1);
+    let schemas: HashSet<Schema> = [0.0, -0.0, 1.5]
+        .into_iter()
+        .map(|maximum| Schema { maximum, ..Schema::default() })
+        .collect();
+    assert_eq!(schemas.len(), 2);
+}
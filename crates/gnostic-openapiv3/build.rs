@@ -1,6 +1,35 @@
 use std::io::Result;
 use std::path::PathBuf;
 
+/// Fields of rarely used or sparse messages, boxed to keep parents small.
+const BOXED_FIELDS: &[&str] = &[
+    ".openapi.v3.PathItem.get",
+    ".openapi.v3.PathItem.put",
+    ".openapi.v3.PathItem.post",
+    ".openapi.v3.PathItem.delete",
+    ".openapi.v3.PathItem.options",
+    ".openapi.v3.PathItem.head",
+    ".openapi.v3.PathItem.patch",
+    ".openapi.v3.PathItem.trace",
+    ".openapi.v3.Schema.discriminator",
+    ".openapi.v3.Schema.xml",
+    ".openapi.v3.Schema.external_docs",
+    ".openapi.v3.Schema.example",
+    ".openapi.v3.Operation.external_docs",
+    ".openapi.v3.Parameter.example",
+    ".openapi.v3.Header.example",
+    ".openapi.v3.MediaType.example",
+    ".openapi.v3.MediaType.encoding",
+    ".openapi.v3.SecurityScheme.flows",
+    ".openapi.v3.OauthFlows.implicit",
+    ".openapi.v3.OauthFlows.password",
+    ".openapi.v3.OauthFlows.client_credentials",
+    ".openapi.v3.OauthFlows.authorization_code",
+    ".openapi.v3.Link.server",
+    ".openapi.v3.Tag.external_docs",
+    ".openapi.v3.Document.external_docs",
+];
+
 fn main() -> Result<()> {
     let proto_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .parent()
@@ -14,10 +43,34 @@ fn main() -> Result<()> {
         proto_root.join("annotations.proto"),
     ];
 
-    let include_dirs = &[proto_root.clone()];
+    let include_dirs = &[&proto_root];
+
+    // Serde support is opt-in through the `serde` feature. Embedded
+    // google.protobuf.Any values have no serde impls; their YAML text is kept.
+    let mut config = prost_build::Config::new();
+    config
+        .type_attribute(
+            ".",
+            "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]",
+        )
+        .field_attribute(".openapi.v3.Any.value", "#[cfg_attr(feature = \"serde\", serde(skip))]");
 
-    prost_build::Config::new()
-        .compile_protos(proto_files, include_dirs)?;
+    // Optional messages that most documents leave out are boxed, so that they
+    // cost a pointer rather than their full size in every parent. The eight
+    // operations of a path item alone would otherwise take over 4KB.
+    for path in BOXED_FIELDS {
+        config.boxed(path);
+    }
+
+    // The messages listed implement Eq and Hash by hand in the crate root.
+    let implemented = &[
+        ".openapi.v3.Any",
+        ".openapi.v3.DefaultType",
+        ".openapi.v3.Schema",
+        ".openapi.v3.SpecificationExtension",
+    ];
+    gnostic_build::derive_eq_and_hash(&mut config, proto_files, include_dirs, implemented)?;
+    config.compile_protos(proto_files, include_dirs)?;
 
     for proto in proto_files {
         println!("cargo:rerun-if-changed={}", proto.display());
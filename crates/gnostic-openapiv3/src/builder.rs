@@ -0,0 +1,624 @@
+//! Builders of OpenAPI v3 documents.
+//!
+//! The models mirror the protocol buffer messages, so maps are lists of named
+//! values and alternatives such as a schema or a reference are oneofs. The
+//! builders construct them fluently, converting their arguments with `From`:
+//!
+//! ```
+//! use gnostic_openapiv3::openapi_v3::*;
+//!
+//! let pets = Schema::reference("#/components/schemas/Pets");
+//! let pet = Schema::reference("#/components/schemas/Pet");
+//! let document = Document::builder()
+//!     .title("Pets")
+//!     .version("1.0")
+//!     .path(
+//!         "/pets",
+//!         PathItem::builder().get(
+//!             Operation::builder()
+//!                 .operation_id("listPets")
+//!                 .parameter(Parameter::builder("limit", "query").schema(Schema::builder().schema_type("integer")))
+//!                 .response("200", Response::builder("The pets.").content("application/json", pets)),
+//!         ),
+//!     )
+//!     .schema("Pets", Schema::builder().schema_type("array").items(pet))
+//!     .schema("Pet", Schema::builder().schema_type("object").property("id", Schema::builder().schema_type("integer")))
+//!     .build();
+//! assert_eq!(document.info.unwrap().title, "Pets");
+//! ```
+
+use gnostic_compiler::{marshal, NamedMap};
+use serde_yaml::Value as Yaml;
+
+use crate::method::HttpMethod;
+use crate::openapi_v3::*;
+
+/// The version of OpenAPI that built documents declare unless told otherwise.
+const DEFAULT_OPENAPI_VERSION: &str = "3.0.3";
+
+/// Implements the conversions of a builder into its object and into the
+/// oneof of the object or a reference.
+macro_rules! or_reference {
+    ($object:ident, $builder:ident, $or_reference:ident) => {
+        impl From<$builder> for $object {
+            fn from(builder: $builder) -> Self {
+                builder.build()
+            }
+        }
+
+        impl From<$builder> for $or_reference {
+            fn from(builder: $builder) -> Self {
+                builder.build().into()
+            }
+        }
+    };
+}
+
+or_reference!(Parameter, ParameterBuilder, ParameterOrReference);
+or_reference!(RequestBody, RequestBodyBuilder, RequestBodyOrReference);
+or_reference!(Response, ResponseBuilder, ResponseOrReference);
+or_reference!(Schema, SchemaBuilder, SchemaOrReference);
+
+impl From<OperationBuilder> for Operation {
+    fn from(builder: OperationBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<PathItemBuilder> for PathItem {
+    fn from(builder: PathItemBuilder) -> Self {
+        builder.build()
+    }
+}
+
+fn any(value: impl Into<Yaml>) -> Any {
+    Any { value: None, yaml: String::from_utf8_lossy(&marshal(&value.into())).into_owned() }
+}
+
+fn media_types(schema: impl Into<SchemaOrReference>, media_type: impl Into<String>, content: &mut Option<MediaTypes>) {
+    let value = MediaType { schema: Some(schema.into()), ..MediaType::default() };
+    content.get_or_insert_with(MediaTypes::default).insert(media_type, value);
+}
+
+impl Document {
+    /// Returns a builder of a document.
+    pub fn builder() -> DocumentBuilder {
+        DocumentBuilder::default()
+    }
+}
+
+/// A builder of [`Document`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentBuilder {
+    document: Document,
+}
+
+impl Default for DocumentBuilder {
+    fn default() -> Self {
+        let document = Document { openapi: DEFAULT_OPENAPI_VERSION.to_string(), ..Document::default() };
+        DocumentBuilder { document }
+    }
+}
+
+impl DocumentBuilder {
+    /// Sets the version of OpenAPI the document uses, "3.0.3" by default.
+    pub fn openapi(mut self, version: impl Into<String>) -> Self {
+        self.document.openapi = version.into();
+        self
+    }
+
+    /// Sets the title of the API.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.info().title = title.into();
+        self
+    }
+
+    /// Sets the version of the API.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.info().version = version.into();
+        self
+    }
+
+    /// Sets the description of the API.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.info().description = description.into();
+        self
+    }
+
+    /// Adds a server.
+    pub fn server(mut self, url: impl Into<String>) -> Self {
+        self.document.servers.push(Server { url: url.into(), ..Server::default() });
+        self
+    }
+
+    /// Adds a tag, replacing a tag of the same name.
+    pub fn tag(mut self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        let tag = Tag { name: name.into(), description: description.into(), ..Tag::default() };
+        self.document.add_tag(tag);
+        self
+    }
+
+    /// Sets the path item of a path.
+    pub fn path(mut self, path: impl Into<String>, item: impl Into<PathItem>) -> Self {
+        self.document.add_path(path, item);
+        self
+    }
+
+    /// Sets a schema of the components.
+    pub fn schema(mut self, name: impl Into<String>, schema: impl Into<SchemaOrReference>) -> Self {
+        self.document.add_schema(name, schema);
+        self
+    }
+
+    /// Sets a parameter of the components.
+    pub fn parameter(mut self, name: impl Into<String>, parameter: impl Into<ParameterOrReference>) -> Self {
+        self.document.components_mut().add_parameter(name, parameter);
+        self
+    }
+
+    /// Sets a request body of the components.
+    pub fn request_body(mut self, name: impl Into<String>, request_body: impl Into<RequestBodyOrReference>) -> Self {
+        self.document.components_mut().add_request_body(name, request_body);
+        self
+    }
+
+    /// Sets a response of the components.
+    pub fn response(mut self, name: impl Into<String>, response: impl Into<ResponseOrReference>) -> Self {
+        self.document.components_mut().add_response(name, response);
+        self
+    }
+
+    /// Returns the document.
+    pub fn build(self) -> Document {
+        self.document
+    }
+
+    fn info(&mut self) -> &mut Info {
+        self.document.info.get_or_insert_with(Info::default)
+    }
+}
+
+impl PathItem {
+    /// Returns a builder of a path item.
+    pub fn builder() -> PathItemBuilder {
+        PathItemBuilder::default()
+    }
+}
+
+/// A builder of [`PathItem`]s.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathItemBuilder {
+    item: PathItem,
+}
+
+impl PathItemBuilder {
+    /// Sets the summary of the path item.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.item.summary = summary.into();
+        self
+    }
+
+    /// Sets the description of the path item.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.item.description = description.into();
+        self
+    }
+
+    /// Adds a parameter shared by the operations of the path item.
+    pub fn parameter(mut self, parameter: impl Into<ParameterOrReference>) -> Self {
+        self.item.parameters.push(parameter.into());
+        self
+    }
+
+    /// Sets the operation for a method.
+    pub fn operation(mut self, method: HttpMethod, operation: impl Into<Operation>) -> Self {
+        self.item.set_operation(method, operation.into());
+        self
+    }
+
+    /// Sets the GET operation.
+    pub fn get(self, operation: impl Into<Operation>) -> Self {
+        self.operation(HttpMethod::Get, operation)
+    }
+
+    /// Sets the PUT operation.
+    pub fn put(self, operation: impl Into<Operation>) -> Self {
+        self.operation(HttpMethod::Put, operation)
+    }
+
+    /// Sets the POST operation.
+    pub fn post(self, operation: impl Into<Operation>) -> Self {
+        self.operation(HttpMethod::Post, operation)
+    }
+
+    /// Sets the DELETE operation.
+    pub fn delete(self, operation: impl Into<Operation>) -> Self {
+        self.operation(HttpMethod::Delete, operation)
+    }
+
+    /// Sets the OPTIONS operation.
+    pub fn options(self, operation: impl Into<Operation>) -> Self {
+        self.operation(HttpMethod::Options, operation)
+    }
+
+    /// Sets the HEAD operation.
+    pub fn head(self, operation: impl Into<Operation>) -> Self {
+        self.operation(HttpMethod::Head, operation)
+    }
+
+    /// Sets the PATCH operation.
+    pub fn patch(self, operation: impl Into<Operation>) -> Self {
+        self.operation(HttpMethod::Patch, operation)
+    }
+
+    /// Sets the TRACE operation.
+    pub fn trace(self, operation: impl Into<Operation>) -> Self {
+        self.operation(HttpMethod::Trace, operation)
+    }
+
+    /// Returns the path item.
+    pub fn build(self) -> PathItem {
+        self.item
+    }
+}
+
+impl Operation {
+    /// Returns a builder of an operation.
+    pub fn builder() -> OperationBuilder {
+        OperationBuilder::default()
+    }
+}
+
+/// A builder of [`Operation`]s.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationBuilder {
+    operation: Operation,
+}
+
+impl OperationBuilder {
+    /// Sets the ID of the operation.
+    pub fn operation_id(mut self, operation_id: impl Into<String>) -> Self {
+        self.operation.operation_id = operation_id.into();
+        self
+    }
+
+    /// Sets the summary of the operation.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.operation.summary = summary.into();
+        self
+    }
+
+    /// Sets the description of the operation.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.operation.description = description.into();
+        self
+    }
+
+    /// Adds a tag, replacing a tag of the same name.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.operation.tags.push(tag.into());
+        self
+    }
+
+    /// Marks the operation as deprecated.
+    pub fn deprecated(mut self) -> Self {
+        self.operation.deprecated = true;
+        self
+    }
+
+    /// Adds a parameter.
+    pub fn parameter(mut self, parameter: impl Into<ParameterOrReference>) -> Self {
+        self.operation.parameters.push(parameter.into());
+        self
+    }
+
+    /// Sets the request body.
+    pub fn request_body(mut self, request_body: impl Into<RequestBodyOrReference>) -> Self {
+        self.operation.request_body = Some(request_body.into());
+        self
+    }
+
+    /// Sets the response for a status code, such as "200", or the default
+    /// response for "default".
+    pub fn response(mut self, code: impl Into<String>, response: impl Into<ResponseOrReference>) -> Self {
+        let (code, response) = (code.into(), response.into());
+        let responses = self.operation.responses.get_or_insert_with(Responses::default);
+        if code == "default" {
+            responses.default = Some(response);
+        } else {
+            responses.insert(code, response);
+        }
+        self
+    }
+
+    /// Returns the operation.
+    pub fn build(self) -> Operation {
+        self.operation
+    }
+}
+
+impl Parameter {
+    /// Returns a builder of a parameter in a location such as "query" or
+    /// "path". Path parameters are required.
+    pub fn builder(name: impl Into<String>, location: impl Into<String>) -> ParameterBuilder {
+        let location = location.into();
+        let required = location == "path";
+        let parameter = Parameter { name: name.into(), r#in: location, required, ..Parameter::default() };
+        ParameterBuilder { parameter }
+    }
+}
+
+/// A builder of [`Parameter`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterBuilder {
+    parameter: Parameter,
+}
+
+impl ParameterBuilder {
+    /// Sets the description of the parameter.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.parameter.description = description.into();
+        self
+    }
+
+    /// Marks the parameter as required.
+    pub fn required(mut self) -> Self {
+        self.parameter.required = true;
+        self
+    }
+
+    /// Marks the parameter as deprecated.
+    pub fn deprecated(mut self) -> Self {
+        self.parameter.deprecated = true;
+        self
+    }
+
+    /// Sets the schema of the parameter's values.
+    pub fn schema(mut self, schema: impl Into<SchemaOrReference>) -> Self {
+        self.parameter.schema = Some(schema.into());
+        self
+    }
+
+    /// Returns the parameter.
+    pub fn build(self) -> Parameter {
+        self.parameter
+    }
+}
+
+impl RequestBody {
+    /// Returns a builder of a request body.
+    pub fn builder() -> RequestBodyBuilder {
+        RequestBodyBuilder::default()
+    }
+}
+
+/// A builder of [`RequestBody`]s.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestBodyBuilder {
+    request_body: RequestBody,
+}
+
+impl RequestBodyBuilder {
+    /// Sets the description of the request body.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.request_body.description = description.into();
+        self
+    }
+
+    /// Marks the request body as required.
+    pub fn required(mut self) -> Self {
+        self.request_body.required = true;
+        self
+    }
+
+    /// Sets the schema of the request body for a media type, such as
+    /// "application/json".
+    pub fn content(mut self, media_type: impl Into<String>, schema: impl Into<SchemaOrReference>) -> Self {
+        media_types(schema, media_type, &mut self.request_body.content);
+        self
+    }
+
+    /// Returns the request body.
+    pub fn build(self) -> RequestBody {
+        self.request_body
+    }
+}
+
+impl Response {
+    /// Returns a builder of a response, which must have a description.
+    pub fn builder(description: impl Into<String>) -> ResponseBuilder {
+        ResponseBuilder { response: Response { description: description.into(), ..Response::default() } }
+    }
+}
+
+/// A builder of [`Response`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseBuilder {
+    response: Response,
+}
+
+impl ResponseBuilder {
+    /// Sets the schema of the response for a media type, such as
+    /// "application/json".
+    pub fn content(mut self, media_type: impl Into<String>, schema: impl Into<SchemaOrReference>) -> Self {
+        media_types(schema, media_type, &mut self.response.content);
+        self
+    }
+
+    /// Returns the response.
+    pub fn build(self) -> Response {
+        self.response
+    }
+}
+
+impl Schema {
+    /// Returns a builder of a schema.
+    pub fn builder() -> SchemaBuilder {
+        SchemaBuilder::default()
+    }
+}
+
+/// A builder of [`Schema`]s.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaBuilder {
+    schema: Schema,
+}
+
+impl SchemaBuilder {
+    /// Sets the type of the schema, such as "object" or "string".
+    pub fn schema_type(mut self, schema_type: impl Into<String>) -> Self {
+        self.schema.r#type = schema_type.into();
+        self
+    }
+
+    /// Sets the format of the schema, such as "int64".
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.schema.format = format.into();
+        self
+    }
+
+    /// Sets the title of the schema.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.schema.title = title.into();
+        self
+    }
+
+    /// Sets the description of the schema.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.schema.description = description.into();
+        self
+    }
+
+    /// Marks the schema as nullable.
+    pub fn nullable(mut self) -> Self {
+        self.schema.nullable = true;
+        self
+    }
+
+    /// Sets the schema of a property.
+    pub fn property(mut self, name: impl Into<String>, schema: impl Into<SchemaOrReference>) -> Self {
+        self.schema.properties.get_or_insert_with(Properties::default).insert(name, schema.into());
+        self
+    }
+
+    /// Sets the schema of a property and marks the property as required.
+    pub fn required_property(self, name: impl Into<String>, schema: impl Into<SchemaOrReference>) -> Self {
+        let name = name.into();
+        let mut builder = self.property(name.clone(), schema);
+        if !builder.schema.required.contains(&name) {
+            builder.schema.required.push(name);
+        }
+        builder
+    }
+
+    /// Sets the schema of the items of an array schema.
+    pub fn items(mut self, schema: impl Into<SchemaOrReference>) -> Self {
+        self.schema.items = Some(ItemsItem { schema_or_reference: vec![schema.into()] });
+        self
+    }
+
+    /// Sets the values the schema allows.
+    pub fn enum_values<V: Into<Yaml>>(mut self, values: impl IntoIterator<Item = V>) -> Self {
+        self.schema.r#enum = values.into_iter().map(any).collect();
+        self
+    }
+
+    /// Sets an example of the schema's values.
+    pub fn example(mut self, example: impl Into<Yaml>) -> Self {
+        self.schema.example = Some(Box::new(any(example)));
+        self
+    }
+
+    /// Returns the schema.
+    pub fn build(self) -> Schema {
+        self.schema
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parse_document;
+
+    #[test]
+    fn test_builder() {
+        let pet = Schema::reference("#/components/schemas/Pet");
+        let built = Document::builder()
+            .title("Pets")
+            .version("1.0.0")
+            .server("https://pets.example.com")
+            .path(
+                "/pets/{petId}",
+                PathItem::builder()
+                    .parameter(Parameter::builder("petId", "path").schema(Schema::builder().schema_type("string")))
+                    .get(
+                        Operation::builder()
+                            .operation_id("getPet")
+                            .tag("pets")
+                            .response("200", Response::builder("A pet.").content("application/json", pet))
+                            .response("404", Response::reference("#/components/responses/Error")),
+                    )
+                    .delete(
+                        Operation::builder()
+                            .operation_id("deletePet")
+                            .request_body(RequestBody::builder().required().content("text/plain", Schema::builder()))
+                            .response("204", Response::builder("Deleted.")),
+                    ),
+            )
+            .schema(
+                "Pet",
+                Schema::builder()
+                    .schema_type("object")
+                    .required_property("id", Schema::builder().schema_type("integer").format("int64"))
+                    .property("status", Schema::builder().schema_type("string").enum_values(["available", "sold"])),
+            )
+            .response("Error", Response::builder("An error."))
+            .build();
+
+        let parsed = parse_document(
+            br##"
+openapi: 3.0.3
+info: {title: Pets, version: 1.0.0}
+servers: [{url: "https://pets.example.com"}]
+paths:
+  /pets/{petId}:
+    get:
+      tags: [pets]
+      operationId: getPet
+      responses:
+        "200":
+          description: A pet.
+          content: {application/json: {schema: {$ref: "#/components/schemas/Pet"}}}
+        "404": {$ref: "#/components/responses/Error"}
+    delete:
+      operationId: deletePet
+      requestBody: {content: {text/plain: {schema: {}}}, required: true}
+      responses: {"204": {description: Deleted.}}
+    parameters:
+      - {name: petId, in: path, required: true, schema: {type: string}}
+components:
+  schemas:
+    Pet:
+      required: [id]
+      type: object
+      properties:
+        id: {type: integer, format: int64}
+        status: {type: string, enum: [available, sold]}
+  responses:
+    Error: {description: An error.}
+"##,
+        )
+        .unwrap();
+        assert_eq!(built, parsed);
+
+        let operation = Operation::builder()
+            .response("default", Response::builder("An error."))
+            .response("200", Response::builder("OK."))
+            .response("200", Response::builder("Replaced."))
+            .build();
+        let responses = operation.responses.unwrap();
+        assert_eq!(responses.default, Some(Response::builder("An error.").into()));
+        assert_eq!(responses.response_or_reference.len(), 1);
+        assert_eq!(responses.response_or_reference[0].value, Some(Response::builder("Replaced.").into()));
+    }
+}
@@ -0,0 +1,173 @@
+//! Lookup of components by name and by reference.
+//!
+//! Components are stored as vectors of named entries, which preserve the
+//! order of the source document; the accessors here find entries by name so
+//! that callers need not scan the vectors themselves.
+
+use gnostic_compiler::unescape_json_pointer_segment;
+use gnostic_compiler::NamedMap;
+
+use crate::openapi_v3::*;
+
+impl Components {
+    /// Returns the schema component named `name`.
+    pub fn schema(&self, name: &str) -> Option<&SchemaOrReference> {
+        self.schemas.as_ref()?.get(name)
+    }
+
+    /// Returns the response component named `name`.
+    pub fn response(&self, name: &str) -> Option<&ResponseOrReference> {
+        self.responses.as_ref()?.get(name)
+    }
+
+    /// Returns the parameter component named `name`.
+    pub fn parameter(&self, name: &str) -> Option<&ParameterOrReference> {
+        self.parameters.as_ref()?.get(name)
+    }
+
+    /// Returns the example component named `name`.
+    pub fn example(&self, name: &str) -> Option<&ExampleOrReference> {
+        self.examples.as_ref()?.get(name)
+    }
+
+    /// Returns the request body component named `name`.
+    pub fn request_body(&self, name: &str) -> Option<&RequestBodyOrReference> {
+        self.request_bodies.as_ref()?.get(name)
+    }
+
+    /// Returns the header component named `name`.
+    pub fn header(&self, name: &str) -> Option<&HeaderOrReference> {
+        self.headers.as_ref()?.get(name)
+    }
+
+    /// Returns the security scheme component named `name`.
+    pub fn security_scheme(&self, name: &str) -> Option<&SecuritySchemeOrReference> {
+        self.security_schemes.as_ref()?.get(name)
+    }
+
+    /// Returns the link component named `name`.
+    pub fn link(&self, name: &str) -> Option<&LinkOrReference> {
+        self.links.as_ref()?.get(name)
+    }
+
+    /// Returns the callback component named `name`.
+    pub fn callback(&self, name: &str) -> Option<&CallbackOrReference> {
+        self.callbacks.as_ref()?.get(name)
+    }
+}
+
+impl Paths {
+    /// Returns the path item of a path template, such as "/pets/{id}".
+    pub fn path_item(&self, path: &str) -> Option<&PathItem> {
+        self.get(path)
+    }
+}
+
+/// Follows local references to components of one section until a component
+/// that is not a reference, giving up after as many steps as there are
+/// components, which only a cycle of references takes.
+macro_rules! follow_references {
+    ($doc:expr, $reference:expr, $section:literal, $field:ident, $lookup:ident, $oneof:ident, $variant:ident) => {{
+        let components = $doc.components.as_ref()?;
+        let count = components.$field.as_ref().map_or(0, |entries| entries.additional_properties.len());
+        let mut reference = $reference;
+        for _ in 0..count {
+            let name = component_name(reference, $section)?;
+            match components.$lookup(&name)?.oneof.as_ref()? {
+                $oneof::Oneof::$variant(value) => return Some(value),
+                $oneof::Oneof::Reference(next) => reference = &next.r#ref,
+            }
+        }
+        None
+    }};
+}
+
+/// Returns the name of the component a local reference such as
+/// "#/components/schemas/Pet" refers to, if it refers to one of `section`.
+fn component_name(reference: &str, section: &str) -> Option<String> {
+    let name = reference.strip_prefix("#/components/")?.strip_prefix(section)?.strip_prefix('/')?;
+    (!name.contains('/')).then(|| unescape_json_pointer_segment(name))
+}
+
+impl Document {
+    /// Returns the schema a local reference such as "#/components/schemas/Pet"
+    /// refers to, following references between schemas.
+    pub fn schema_for_ref(&self, reference: &str) -> Option<&Schema> {
+        follow_references!(self, reference, "schemas", schemas, schema, schema_or_reference, Schema)
+    }
+
+    /// Returns the response a local reference such as
+    /// "#/components/responses/NotFound" refers to.
+    pub fn response_for_ref(&self, reference: &str) -> Option<&Response> {
+        follow_references!(self, reference, "responses", responses, response, response_or_reference, Response)
+    }
+
+    /// Returns the parameter a local reference such as
+    /// "#/components/parameters/limit" refers to.
+    pub fn parameter_for_ref(&self, reference: &str) -> Option<&Parameter> {
+        follow_references!(self, reference, "parameters", parameters, parameter, parameter_or_reference, Parameter)
+    }
+
+    /// Returns the request body a local reference such as
+    /// "#/components/requestBodies/Pet" refers to.
+    pub fn request_body_for_ref(&self, reference: &str) -> Option<&RequestBody> {
+        follow_references!(
+            self,
+            reference,
+            "requestBodies",
+            request_bodies,
+            request_body,
+            request_body_or_reference,
+            RequestBody
+        )
+    }
+
+    /// Returns the header a local reference such as
+    /// "#/components/headers/X-Rate-Limit" refers to.
+    pub fn header_for_ref(&self, reference: &str) -> Option<&Header> {
+        follow_references!(self, reference, "headers", headers, header, header_or_reference, Header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::document::parse_document;
+
+    #[test]
+    fn test_lookup() {
+        let doc = parse_document(
+            br##"
+openapi: 3.0.3
+info: {title: Pets, version: 1.0.0}
+paths:
+  /pets: {}
+components:
+  schemas:
+    Pet: {type: object, description: A pet.}
+    Animal: {$ref: "#/components/schemas/Pet"}
+    A/B: {type: string}
+    Loop: {$ref: "#/components/schemas/Loop"}
+  responses:
+    NotFound: {description: Not found.}
+  parameters:
+    limit: {name: limit, in: query}
+"##,
+        )
+        .unwrap();
+        let components = doc.components.as_ref().unwrap();
+        assert!(components.schema("Pet").is_some());
+        assert!(components.schema("Cat").is_none());
+        assert!(components.response("NotFound").is_some());
+        assert!(components.header("NotFound").is_none());
+        assert!(doc.paths.as_ref().unwrap().path_item("/pets").is_some());
+
+        assert_eq!(doc.schema_for_ref("#/components/schemas/Pet").unwrap().description, "A pet.");
+        assert_eq!(doc.schema_for_ref("#/components/schemas/Animal").unwrap().description, "A pet.");
+        assert_eq!(doc.schema_for_ref("#/components/schemas/A~1B").unwrap().r#type, "string");
+        assert!(doc.schema_for_ref("#/components/schemas/Loop").is_none());
+        assert!(doc.schema_for_ref("#/components/responses/NotFound").is_none());
+        assert!(doc.schema_for_ref("pets.yaml#/components/schemas/Pet").is_none());
+        assert_eq!(doc.response_for_ref("#/components/responses/NotFound").unwrap().description, "Not found.");
+        assert_eq!(doc.parameter_for_ref("#/components/parameters/limit").unwrap().name, "limit");
+    }
+}
@@ -0,0 +1,158 @@
+//! Constructors of models that are awkward to build directly.
+//!
+//! Objects that may be given by reference are oneofs, such as
+//! [`SchemaOrReference`]; the constructors and `From` conversions here wrap
+//! objects and references in them, and the [`Schema`] constructors cover the
+//! common schemas of a single type.
+
+use crate::openapi_v3::*;
+
+/// Implements the constructors of a oneof of an object or a reference, and
+/// the conversions of objects and references into it.
+macro_rules! or_reference {
+    ($object:ident, $or_reference:ident, $module:ident, $variant:ident, $constructor:ident) => {
+        impl $or_reference {
+            #[doc = concat!("Returns the oneof of a ", stringify!($object), ".")]
+            pub fn $constructor(object: $object) -> Self {
+                $or_reference { oneof: Some($module::Oneof::$variant(object.into())) }
+            }
+
+            /// Returns the oneof of a reference, such as
+            /// "#/components/schemas/Pet".
+            pub fn reference(reference: impl Into<String>) -> Self {
+                Reference::new(reference).into()
+            }
+        }
+
+        impl From<$object> for $or_reference {
+            fn from(object: $object) -> Self {
+                $or_reference::$constructor(object)
+            }
+        }
+
+        impl From<Reference> for $or_reference {
+            fn from(reference: Reference) -> Self {
+                $or_reference { oneof: Some($module::Oneof::Reference(reference)) }
+            }
+        }
+
+        impl $object {
+            /// Returns a reference to an object, such as
+            /// "#/components/schemas/Pet".
+            pub fn reference(reference: impl Into<String>) -> $or_reference {
+                $or_reference::reference(reference)
+            }
+        }
+    };
+}
+
+or_reference!(Callback, CallbackOrReference, callback_or_reference, Callback, callback);
+or_reference!(Example, ExampleOrReference, example_or_reference, Example, example);
+or_reference!(Header, HeaderOrReference, header_or_reference, Header, header);
+or_reference!(Link, LinkOrReference, link_or_reference, Link, link);
+or_reference!(Parameter, ParameterOrReference, parameter_or_reference, Parameter, parameter);
+or_reference!(RequestBody, RequestBodyOrReference, request_body_or_reference, RequestBody, request_body);
+or_reference!(Response, ResponseOrReference, response_or_reference, Response, response);
+or_reference!(Schema, SchemaOrReference, schema_or_reference, Schema, schema);
+or_reference!(SecurityScheme, SecuritySchemeOrReference, security_scheme_or_reference, SecurityScheme, security_scheme);
+
+impl Reference {
+    /// Returns a reference to a location, such as "#/components/schemas/Pet".
+    pub fn new(reference: impl Into<String>) -> Self {
+        Reference { r#ref: reference.into(), ..Reference::default() }
+    }
+}
+
+impl From<bool> for AdditionalPropertiesItem {
+    fn from(allowed: bool) -> Self {
+        AdditionalPropertiesItem { oneof: Some(additional_properties_item::Oneof::Boolean(allowed)) }
+    }
+}
+
+impl From<SchemaOrReference> for AdditionalPropertiesItem {
+    fn from(schema: SchemaOrReference) -> Self {
+        let schema = Box::new(schema);
+        AdditionalPropertiesItem { oneof: Some(additional_properties_item::Oneof::SchemaOrReference(schema)) }
+    }
+}
+
+impl Schema {
+    /// Returns a schema of a type, such as "string".
+    pub fn of_type(schema_type: impl Into<String>) -> Self {
+        Schema { r#type: schema_type.into(), ..Schema::default() }
+    }
+
+    /// Returns a schema of strings.
+    pub fn string() -> Self {
+        Schema::of_type("string")
+    }
+
+    /// Returns a schema of integers.
+    pub fn integer() -> Self {
+        Schema::of_type("integer")
+    }
+
+    /// Returns a schema of numbers.
+    pub fn number() -> Self {
+        Schema::of_type("number")
+    }
+
+    /// Returns a schema of booleans.
+    pub fn boolean() -> Self {
+        Schema::of_type("boolean")
+    }
+
+    /// Returns a schema of objects.
+    pub fn object() -> Self {
+        Schema::of_type("object")
+    }
+
+    /// Returns a schema of arrays of items.
+    pub fn array_of(items: impl Into<SchemaOrReference>) -> Self {
+        let items = ItemsItem { schema_or_reference: vec![items.into()] };
+        Schema { items: Some(items), ..Schema::of_type("array") }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_or_reference() {
+        let reference = SchemaOrReference::reference("#/components/schemas/Pet");
+        assert_eq!(reference, Schema::reference("#/components/schemas/Pet"));
+        assert_eq!(reference, Reference::new("#/components/schemas/Pet").into());
+        let Some(schema_or_reference::Oneof::Reference(reference)) = reference.oneof else {
+            panic!("not a reference");
+        };
+        assert_eq!(reference.r#ref, "#/components/schemas/Pet");
+
+        let schema = SchemaOrReference::schema(Schema::string());
+        assert_eq!(schema, Schema::string().into());
+        assert!(matches!(schema.oneof, Some(schema_or_reference::Oneof::Schema(schema)) if schema.r#type == "string"));
+
+        let response = Response { description: "OK".to_string(), ..Response::default() };
+        assert!(matches!(
+            ResponseOrReference::response(response).oneof,
+            Some(response_or_reference::Oneof::Response(response)) if response.description == "OK"
+        ));
+    }
+
+    #[test]
+    fn test_schema_constructors() {
+        let pets = Schema::array_of(Schema::reference("#/components/schemas/Pet"));
+        assert_eq!(pets.r#type, "array");
+        assert_eq!(pets.items.unwrap().schema_or_reference, [Schema::reference("#/components/schemas/Pet")]);
+        assert_eq!(Schema::integer(), Schema { r#type: "integer".to_string(), ..Schema::default() });
+
+        let values = SchemaOrReference::schema(Schema::number());
+        let map = Schema { additional_properties: Some(Box::new(values.into())), ..Schema::object() };
+        assert!(matches!(
+            map.additional_properties.unwrap().oneof,
+            Some(additional_properties_item::Oneof::SchemaOrReference(_))
+        ));
+        let allowed = AdditionalPropertiesItem::from(false);
+        assert_eq!(allowed.oneof, Some(additional_properties_item::Oneof::Boolean(false)));
+    }
+}
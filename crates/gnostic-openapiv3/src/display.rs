@@ -0,0 +1,360 @@
+//! Display functionality for OpenAPI v3 structures.
+
+use std::fmt;
+use crate::openapi_v3::*;
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe_document(""))
+    }
+}
+
+impl fmt::Display for PathItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe_path_item(""))
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe_operation(""))
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe_schema(""))
+    }
+}
+
+impl Document {
+    /// Returns a string representation of the document with the given indentation.
+    pub fn describe_document(&self, indent: &str) -> String {
+        let mut result = String::new();
+        let next_indent = format!("{}  ", indent);
+        let double_indent = format!("{}    ", indent);
+
+        result.push_str(&format!("{}openapi: {}\n", indent, self.openapi));
+        if let Some(ref info) = self.info {
+            result.push_str(&format!("{}info:\n", indent));
+            result.push_str(&format!("{}title: {}\n", next_indent, info.title));
+            result.push_str(&format!("{}version: {}\n", next_indent, info.version));
+        }
+        if !self.servers.is_empty() {
+            result.push_str(&format!("{}servers:\n", indent));
+            for server in &self.servers {
+                result.push_str(&format!("{}{}\n", next_indent, server.url));
+            }
+        }
+        if let Some(ref paths) = self.paths {
+            result.push_str(&format!("{}paths:\n", indent));
+            for path in &paths.path {
+                result.push_str(&format!("{}{}:\n", next_indent, path.name));
+                if let Some(ref item) = path.value {
+                    result.push_str(&item.describe_path_item(&double_indent));
+                }
+            }
+        }
+        if let Some(schemas) = self.components.as_ref().and_then(|c| c.schemas.as_ref()) {
+            result.push_str(&format!("{}schemas:\n", indent));
+            for schema in &schemas.additional_properties {
+                result.push_str(&format!("{}{}:\n", next_indent, schema.name));
+                if let Some(ref value) = schema.value {
+                    result.push_str(&describe_schema_or_reference(value, &double_indent));
+                }
+            }
+        }
+        if !self.tags.is_empty() {
+            let names: Vec<&str> = self.tags.iter().map(|t| t.name.as_str()).collect();
+            result.push_str(&format!("{}tags: {:?}\n", indent, names));
+        }
+
+        result
+    }
+}
+
+impl PathItem {
+    /// Returns a string representation of the path item with the given indentation.
+    pub fn describe_path_item(&self, indent: &str) -> String {
+        let mut result = String::new();
+        let next_indent = format!("{}  ", indent);
+
+        if !self.r#ref.is_empty() {
+            result.push_str(&format!("{}$ref: {}\n", indent, self.r#ref));
+        }
+        if !self.summary.is_empty() {
+            result.push_str(&format!("{}summary: {}\n", indent, self.summary));
+        }
+        for parameter in &self.parameters {
+            result.push_str(&format!("{}parameter: {}\n", indent, describe_parameter(parameter)));
+        }
+        for (method, operation) in self.iter_operations() {
+            result.push_str(&format!("{}{}:\n", indent, method));
+            result.push_str(&operation.describe_operation(&next_indent));
+        }
+
+        result
+    }
+}
+
+impl Operation {
+    /// Returns a string representation of the operation with the given indentation.
+    pub fn describe_operation(&self, indent: &str) -> String {
+        let mut result = String::new();
+        let next_indent = format!("{}  ", indent);
+
+        if !self.operation_id.is_empty() {
+            result.push_str(&format!("{}operationId: {}\n", indent, self.operation_id));
+        }
+        if !self.summary.is_empty() {
+            result.push_str(&format!("{}summary: {}\n", indent, self.summary));
+        }
+        if !self.tags.is_empty() {
+            result.push_str(&format!("{}tags: {:?}\n", indent, self.tags));
+        }
+        if self.deprecated {
+            result.push_str(&format!("{}deprecated: true\n", indent));
+        }
+        for parameter in &self.parameters {
+            result.push_str(&format!("{}parameter: {}\n", indent, describe_parameter(parameter)));
+        }
+        if let Some(ref request_body) = self.request_body {
+            match &request_body.oneof {
+                Some(request_body_or_reference::Oneof::RequestBody(body)) => {
+                    let types: Vec<&str> = body
+                        .content
+                        .iter()
+                        .flat_map(|c| c.additional_properties.iter())
+                        .map(|m| m.name.as_str())
+                        .collect();
+                    result.push_str(&format!("{}requestBody: {:?}\n", indent, types));
+                }
+                Some(request_body_or_reference::Oneof::Reference(r)) => {
+                    result.push_str(&format!("{}requestBody: $ref {}\n", indent, r.r#ref));
+                }
+                None => {}
+            }
+        }
+        if let Some(ref responses) = self.responses {
+            result.push_str(&format!("{}responses:\n", indent));
+            let default = responses.default.as_ref().map(|r| ("default", r));
+            let named = responses
+                .response_or_reference
+                .iter()
+                .filter_map(|r| r.value.as_ref().map(|v| (r.name.as_str(), v)));
+            for (code, response) in named.chain(default) {
+                let text = match &response.oneof {
+                    Some(response_or_reference::Oneof::Response(r)) => r.description.clone(),
+                    Some(response_or_reference::Oneof::Reference(r)) => format!("$ref {}", r.r#ref),
+                    None => String::new(),
+                };
+                result.push_str(&format!("{}{}: {}\n", next_indent, code, text));
+            }
+        }
+
+        result
+    }
+}
+
+impl Schema {
+    /// Returns a string representation of the schema with the given indentation.
+    pub fn describe_schema(&self, indent: &str) -> String {
+        let mut result = String::new();
+        let next_indent = format!("{}  ", indent);
+        let double_indent = format!("{}    ", indent);
+
+        if !self.title.is_empty() {
+            result.push_str(&format!("{}title: {}\n", indent, self.title));
+        }
+        if !self.r#type.is_empty() {
+            result.push_str(&format!("{}type: {}\n", indent, self.r#type));
+        }
+        if !self.format.is_empty() {
+            result.push_str(&format!("{}format: {}\n", indent, self.format));
+        }
+        if self.nullable {
+            result.push_str(&format!("{}nullable: true\n", indent));
+        }
+        if !self.description.is_empty() {
+            result.push_str(&format!("{}description: {}\n", indent, self.description));
+        }
+        if !self.required.is_empty() {
+            result.push_str(&format!("{}required: {:?}\n", indent, self.required));
+        }
+        if !self.r#enum.is_empty() {
+            result.push_str(&format!("{}enumeration:\n", indent));
+            for value in &self.r#enum {
+                result.push_str(&format!("{}{}\n", next_indent, value.yaml.trim_end()));
+            }
+        }
+        if let Some(ref items) = self.items {
+            result.push_str(&format!("{}items:\n", indent));
+            for item in &items.schema_or_reference {
+                result.push_str(&describe_schema_or_reference(item, &next_indent));
+            }
+        }
+        if let Some(ref properties) = self.properties {
+            result.push_str(&format!("{}properties:\n", indent));
+            for property in &properties.additional_properties {
+                result.push_str(&format!("{}{}:\n", next_indent, property.name));
+                if let Some(ref value) = property.value {
+                    result.push_str(&describe_schema_or_reference(value, &double_indent));
+                }
+            }
+        }
+        if let Some(ref additional_properties) = self.additional_properties {
+            match &additional_properties.oneof {
+                Some(additional_properties_item::Oneof::SchemaOrReference(s)) => {
+                    result.push_str(&format!("{}additionalProperties:\n", indent));
+                    result.push_str(&describe_schema_or_reference(s, &next_indent));
+                }
+                Some(additional_properties_item::Oneof::Boolean(b)) => {
+                    result.push_str(&format!("{}additionalProperties: {}\n", indent, b));
+                }
+                None => {}
+            }
+        }
+        for (name, schemas) in [
+            ("allOf", &self.all_of),
+            ("anyOf", &self.any_of),
+            ("oneOf", &self.one_of),
+        ] {
+            if !schemas.is_empty() {
+                result.push_str(&format!("{}{}:\n", indent, name));
+                for s in schemas {
+                    result.push_str(&describe_schema_or_reference(s, &next_indent));
+                    result.push_str(&format!("{}-\n", indent));
+                }
+            }
+        }
+        if let Some(ref not) = self.not {
+            result.push_str(&format!("{}not:\n", indent));
+            result.push_str(&not.describe_schema(&next_indent));
+        }
+
+        result
+    }
+}
+
+/// Describes a schema, or the target of a reference to one.
+fn describe_schema_or_reference(value: &SchemaOrReference, indent: &str) -> String {
+    match &value.oneof {
+        Some(schema_or_reference::Oneof::Schema(s)) => s.describe_schema(indent),
+        Some(schema_or_reference::Oneof::Reference(r)) => format!("{}$ref: {}\n", indent, r.r#ref),
+        None => String::new(),
+    }
+}
+
+/// Describes a parameter on one line as "name (in)", or its reference.
+fn describe_parameter(value: &ParameterOrReference) -> String {
+    match &value.oneof {
+        Some(parameter_or_reference::Oneof::Parameter(p)) if p.required => {
+            format!("{} ({}, required)", p.name, p.r#in)
+        }
+        Some(parameter_or_reference::Oneof::Parameter(p)) => format!("{} ({})", p.name, p.r#in),
+        Some(parameter_or_reference::Oneof::Reference(r)) => format!("$ref {}", r.r#ref),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parse_document;
+
+    #[test]
+    fn test_document_display() {
+        let source = br#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: "1.0"
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      tags: [pets]
+      responses:
+        '200':
+          description: A pet
+        default:
+          $ref: '#/components/responses/Error'
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id]
+      properties:
+        id:
+          type: integer
+          format: int64
+        owner:
+          $ref: '#/components/schemas/Owner'
+"#;
+        let doc = parse_document(source).unwrap();
+        let expected = "\
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      tags: [\"pets\"]
+      responses:
+        200: A pet
+        default: $ref #/components/responses/Error
+schemas:
+  Pet:
+    type: object
+    required: [\"id\"]
+    properties:
+      id:
+        type: integer
+        format: int64
+      owner:
+        $ref: #/components/schemas/Owner
+";
+        assert_eq!(doc.to_string(), expected);
+    }
+
+    #[test]
+    fn test_operation_display() {
+        let parameter = ParameterOrReference {
+            oneof: Some(parameter_or_reference::Oneof::Parameter(Parameter {
+                name: "limit".to_string(),
+                r#in: "query".to_string(),
+                required: true,
+                ..Default::default()
+            })),
+        };
+        let request_body = RequestBodyOrReference {
+            oneof: Some(request_body_or_reference::Oneof::RequestBody(RequestBody {
+                content: Some(MediaTypes {
+                    additional_properties: vec![NamedMediaType {
+                        name: "application/json".to_string(),
+                        value: Some(MediaType::default()),
+                    }],
+                }),
+                ..Default::default()
+            })),
+        };
+        let operation = Operation {
+            deprecated: true,
+            parameters: vec![parameter],
+            request_body: Some(request_body),
+            ..Default::default()
+        };
+        assert_eq!(
+            operation.to_string(),
+            "deprecated: true\nparameter: limit (query, required)\nrequestBody: [\"application/json\"]\n"
+        );
+
+        let item = PathItem {
+            post: Some(Box::new(operation.clone())),
+            ..Default::default()
+        };
+        assert_eq!(item.to_string(), format!("post:\n{}", operation.describe_operation("  ")));
+    }
+}
@@ -0,0 +1,121 @@
+//! Incremental changes to documents.
+//!
+//! Tools that assemble documents piece by piece add paths, components and
+//! tags by name; the methods here replace an entry of the same name in place
+//! instead of adding a duplicate, so that names stay unique and the order of
+//! the entries is stable.
+
+use gnostic_compiler::NamedMap;
+
+use crate::openapi_v3::*;
+
+/// Implements a method of [`Components`] that sets the component of a name
+/// in one section.
+macro_rules! add_component {
+    ($($method:ident($field:ident: $map:ident, $value:ident, $what:literal)),* $(,)?) => {
+        impl Components {
+            $(
+                #[doc = concat!("Sets the ", $what, " component named `name`, returning the one it replaces.")]
+                pub fn $method(&mut self, name: impl Into<String>, value: impl Into<$value>) -> Option<$value> {
+                    self.$field.get_or_insert_with($map::default).insert(name, value.into())
+                }
+            )*
+        }
+    };
+}
+
+add_component!(
+    add_schema(schemas: SchemasOrReferences, SchemaOrReference, "schema"),
+    add_response(responses: ResponsesOrReferences, ResponseOrReference, "response"),
+    add_parameter(parameters: ParametersOrReferences, ParameterOrReference, "parameter"),
+    add_example(examples: ExamplesOrReferences, ExampleOrReference, "example"),
+    add_request_body(request_bodies: RequestBodiesOrReferences, RequestBodyOrReference, "request body"),
+    add_header(headers: HeadersOrReferences, HeaderOrReference, "header"),
+    add_security_scheme(security_schemes: SecuritySchemesOrReferences, SecuritySchemeOrReference, "security scheme"),
+    add_link(links: LinksOrReferences, LinkOrReference, "link"),
+    add_callback(callbacks: CallbacksOrReferences, CallbackOrReference, "callback"),
+);
+
+impl Document {
+    /// Returns the components for modification, adding them if missing.
+    pub fn components_mut(&mut self) -> &mut Components {
+        self.components.get_or_insert_with(Components::default)
+    }
+
+    /// Sets the path item of a path, returning the one it replaces.
+    pub fn add_path(&mut self, path: impl Into<String>, item: impl Into<PathItem>) -> Option<PathItem> {
+        self.paths.get_or_insert_with(Paths::default).insert(path, item.into())
+    }
+
+    /// Removes the path item of a path, keeping the order of the others.
+    pub fn remove_path(&mut self, path: &str) -> Option<PathItem> {
+        self.paths.as_mut()?.remove(path)
+    }
+
+    /// Sets the schema component named `name`, returning the one it replaces.
+    pub fn add_schema(
+        &mut self,
+        name: impl Into<String>,
+        schema: impl Into<SchemaOrReference>,
+    ) -> Option<SchemaOrReference> {
+        self.components_mut().add_schema(name, schema)
+    }
+
+    /// Removes the schema component named `name`.
+    pub fn remove_schema(&mut self, name: &str) -> Option<SchemaOrReference> {
+        self.components.as_mut()?.schemas.as_mut()?.remove(name)
+    }
+
+    /// Adds a tag, replacing the tag of the same name in place, and returns
+    /// the tag it replaces.
+    pub fn add_tag(&mut self, tag: Tag) -> Option<Tag> {
+        match self.tags.iter_mut().find(|existing| existing.name == tag.name) {
+            Some(existing) => Some(std::mem::replace(existing, tag)),
+            None => {
+                self.tags.push(tag);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit() {
+        let mut doc = Document::default();
+        assert!(doc.add_path("/pets", PathItem::default()).is_none());
+        assert!(doc.add_path("/owners", PathItem::default()).is_none());
+        let pets = PathItem { summary: "Pets".to_string(), ..PathItem::default() };
+        assert_eq!(doc.add_path("/pets", pets), Some(PathItem::default()));
+        assert_eq!(doc.paths.as_ref().unwrap().names(), ["/pets", "/owners"]);
+        assert_eq!(doc.paths.as_ref().unwrap().path_item("/pets").unwrap().summary, "Pets");
+        assert!(doc.remove_path("/pets").is_some());
+        assert!(doc.remove_path("/pets").is_none());
+
+        assert!(doc.add_schema("Pet", Schema::object()).is_none());
+        assert!(doc.add_schema("Error", Schema::object()).is_none());
+        assert_eq!(doc.add_schema("Pet", Schema::string()), Some(Schema::object().into()));
+        let components = doc.components.as_ref().unwrap();
+        assert_eq!(components.schemas.as_ref().unwrap().names(), ["Pet", "Error"]);
+        assert_eq!(components.schema("Pet"), Some(&Schema::string().into()));
+        assert_eq!(doc.remove_schema("Error"), Some(Schema::object().into()));
+
+        let response = Response { description: "Not found.".to_string(), ..Response::default() };
+        doc.components_mut().add_response("NotFound", response);
+        assert!(doc.components_mut().add_parameter("limit", Parameter::reference("#/x")).is_none());
+        assert!(doc.components.as_ref().unwrap().response("NotFound").is_some());
+
+        let tag = |name: &str, description: &str| Tag {
+            name: name.to_string(),
+            description: description.to_string(),
+            ..Tag::default()
+        };
+        assert!(doc.add_tag(tag("pets", "Pets")).is_none());
+        assert!(doc.add_tag(tag("owners", "Owners")).is_none());
+        assert_eq!(doc.add_tag(tag("pets", "All pets")), Some(tag("pets", "Pets")));
+        assert_eq!(doc.tags, [tag("pets", "All pets"), tag("owners", "Owners")]);
+    }
+}
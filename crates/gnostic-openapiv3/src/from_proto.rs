@@ -0,0 +1,778 @@
+//! Generation of OpenAPI v3 documents from protocol buffer descriptors.
+//!
+//! [`generate_openapi`] does what protoc-gen-openapi does for gRPC services
+//! with `google.api.http` rules, reading a serialized FileDescriptorSet such
+//! as `protoc --include_imports --include_source_info --descriptor_set_out`
+//! writes. Each binding of a rule becomes an operation: the variables of its
+//! path template become path parameters, its `body` becomes the request body
+//! and the other scalar fields of the request become query parameters. The
+//! messages and enums the operations reach become component schemas, and
+//! leading comments become descriptions. It is the inverse of
+//! [`generate_proto`](crate::generate_proto).
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use gnostic_compiler::CompilerError;
+use indexmap::{IndexMap, IndexSet};
+use prost::Message;
+
+use crate::builder::OperationBuilder;
+use crate::method::HttpMethod;
+use crate::openapi_v3::*;
+use descriptor::*;
+
+const TYPE_GROUP: i32 = 10;
+const TYPE_MESSAGE: i32 = 11;
+const TYPE_ENUM: i32 = 14;
+const LABEL_REPEATED: i32 = 3;
+const EMPTY: &str = ".google.protobuf.Empty";
+
+/// The parts of descriptor.proto and google/api/http.proto that are read.
+mod descriptor {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FileDescriptorSet {
+        #[prost(message, repeated, tag = "1")]
+        pub file: Vec<FileDescriptorProto>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FileDescriptorProto {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub package: String,
+        #[prost(message, repeated, tag = "4")]
+        pub message_type: Vec<DescriptorProto>,
+        #[prost(message, repeated, tag = "5")]
+        pub enum_type: Vec<EnumDescriptorProto>,
+        #[prost(message, repeated, tag = "6")]
+        pub service: Vec<ServiceDescriptorProto>,
+        #[prost(message, optional, tag = "9")]
+        pub source_code_info: Option<SourceCodeInfo>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct DescriptorProto {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(message, repeated, tag = "2")]
+        pub field: Vec<FieldDescriptorProto>,
+        #[prost(message, repeated, tag = "3")]
+        pub nested_type: Vec<DescriptorProto>,
+        #[prost(message, repeated, tag = "4")]
+        pub enum_type: Vec<EnumDescriptorProto>,
+        #[prost(message, optional, tag = "7")]
+        pub options: Option<MessageOptions>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MessageOptions {
+        #[prost(bool, tag = "7")]
+        pub map_entry: bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FieldDescriptorProto {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(int32, tag = "3")]
+        pub number: i32,
+        #[prost(int32, tag = "4")]
+        pub label: i32,
+        #[prost(int32, tag = "5")]
+        pub r#type: i32,
+        #[prost(string, tag = "6")]
+        pub type_name: String,
+        #[prost(string, tag = "10")]
+        pub json_name: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct EnumDescriptorProto {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(message, repeated, tag = "2")]
+        pub value: Vec<EnumValueDescriptorProto>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct EnumValueDescriptorProto {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(int32, tag = "2")]
+        pub number: i32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ServiceDescriptorProto {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(message, repeated, tag = "2")]
+        pub method: Vec<MethodDescriptorProto>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MethodDescriptorProto {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub input_type: String,
+        #[prost(string, tag = "3")]
+        pub output_type: String,
+        #[prost(message, optional, tag = "4")]
+        pub options: Option<MethodOptions>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MethodOptions {
+        #[prost(bool, tag = "33")]
+        pub deprecated: bool,
+        /// The `google.api.http` extension.
+        #[prost(message, optional, tag = "72295728")]
+        pub http: Option<HttpRule>,
+    }
+
+    /// A rule of google/api/http.proto; at most one of its patterns is set.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct HttpRule {
+        #[prost(string, tag = "2")]
+        pub get: String,
+        #[prost(string, tag = "3")]
+        pub put: String,
+        #[prost(string, tag = "4")]
+        pub post: String,
+        #[prost(string, tag = "5")]
+        pub delete: String,
+        #[prost(string, tag = "6")]
+        pub patch: String,
+        #[prost(string, tag = "7")]
+        pub body: String,
+        #[prost(message, optional, tag = "8")]
+        pub custom: Option<CustomHttpPattern>,
+        #[prost(message, repeated, tag = "11")]
+        pub additional_bindings: Vec<HttpRule>,
+        #[prost(string, tag = "12")]
+        pub response_body: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CustomHttpPattern {
+        #[prost(string, tag = "1")]
+        pub kind: String,
+        #[prost(string, tag = "2")]
+        pub path: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SourceCodeInfo {
+        #[prost(message, repeated, tag = "1")]
+        pub location: Vec<Location>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Location {
+        #[prost(int32, repeated, tag = "1")]
+        pub path: Vec<i32>,
+        #[prost(string, tag = "3")]
+        pub leading_comments: String,
+    }
+}
+
+/// Generates an OpenAPI v3 document for the RPCs with HTTP rules in a
+/// serialized FileDescriptorSet.
+///
+/// Operations are named `Service_Method` and tagged with their service; the
+/// operations of additional bindings have the number of the binding appended.
+/// The descriptor set must include the imports of its files, and include
+/// source info for comments to become descriptions.
+pub fn generate_openapi(descriptor_set: &[u8]) -> Result<Document, CompilerError> {
+    let set = FileDescriptorSet::decode(descriptor_set)?;
+    let mut generator = Generator::new(&set);
+    let mut services = Vec::new();
+    let mut paths: IndexMap<String, PathItem> = IndexMap::new();
+    for file in &set.file {
+        let comments = comments(file);
+        for (s, service) in file.service.iter().enumerate() {
+            let mut bound = false;
+            for (m, method) in service.method.iter().enumerate() {
+                let Some(rule) = method.options.as_ref().and_then(|options| options.http.as_ref()) else {
+                    continue;
+                };
+                let description = comment(&comments, &[6, s as i32, 2, m as i32]);
+                let rules = std::iter::once(rule).chain(&rule.additional_bindings);
+                for (i, rule) in rules.enumerate() {
+                    let Some((http_method, template)) = pattern(rule) else {
+                        continue;
+                    };
+                    let mut operation_id = format!("{}_{}", service.name, method.name);
+                    if i > 0 {
+                        operation_id.push_str(&(i + 1).to_string());
+                    }
+                    let (path, variables) = path_template(template);
+                    let operation = generator
+                        .operation(method, rule, &variables)?
+                        .operation_id(operation_id)
+                        .tag(&service.name)
+                        .description(&description);
+                    let mut operation = operation.build();
+                    operation.deprecated = method.options.as_ref().is_some_and(|options| options.deprecated);
+                    paths.entry(path).or_default().set_operation(http_method, operation);
+                    bound = true;
+                }
+            }
+            if bound {
+                services.push((service.name.as_str(), comment(&comments, &[6, s as i32])));
+            }
+        }
+    }
+
+    let mut builder = Document::builder().version("0.0.1");
+    builder = match services.as_slice() {
+        [(name, description)] => builder.title(*name).description(description),
+        _ => builder.title("API"),
+    };
+    for (name, description) in &services {
+        builder = builder.tag(*name, description);
+    }
+    for (path, item) in paths {
+        builder = builder.path(path, item);
+    }
+    let mut schemas = generator.component_schemas()?;
+    schemas.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, schema) in schemas {
+        builder = builder.schema(name, schema);
+    }
+    Ok(builder.build())
+}
+
+/// A message of the descriptor set.
+struct MessageType<'a> {
+    descriptor: &'a DescriptorProto,
+    /// The name of the message's component schema.
+    name: String,
+    description: String,
+    field_descriptions: Vec<String>,
+}
+
+/// An enum of the descriptor set.
+struct EnumType<'a> {
+    descriptor: &'a EnumDescriptorProto,
+    name: String,
+    description: String,
+}
+
+struct Generator<'a> {
+    /// The messages and enums by full name, such as ".library.v1.Book".
+    messages: HashMap<String, MessageType<'a>>,
+    enums: HashMap<String, EnumType<'a>>,
+    /// The full names of the types referenced by the schemas so far.
+    referenced: IndexSet<String>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(set: &'a FileDescriptorSet) -> Self {
+        let mut generator = Generator { messages: HashMap::new(), enums: HashMap::new(), referenced: IndexSet::new() };
+        for file in &set.file {
+            let comments = comments(file);
+            let scope = if file.package.is_empty() { String::new() } else { format!(".{}", file.package) };
+            generator.add_messages(&comments, &scope, &file.message_type, &[4]);
+            generator.add_enums(&comments, &scope, &file.enum_type, &[5]);
+        }
+
+        // Types are named by their own name, unless another type has the
+        // same name, when they are named by their full name.
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for name in generator.messages.keys().chain(generator.enums.keys()) {
+            *counts.entry(short_name(name).to_string()).or_default() += 1;
+        }
+        let messages = generator.messages.iter_mut().map(|(full_name, t)| (full_name, &mut t.name));
+        let enums = generator.enums.iter_mut().map(|(full_name, t)| (full_name, &mut t.name));
+        for (full_name, name) in messages.chain(enums) {
+            *name = match counts[short_name(full_name)] {
+                1 => short_name(full_name).to_string(),
+                _ => full_name.trim_start_matches('.').to_string(),
+            };
+        }
+        generator
+    }
+
+    fn add_messages(&mut self, comments: &Comments, scope: &str, messages: &'a [DescriptorProto], path: &[i32]) {
+        for (i, message) in messages.iter().enumerate() {
+            let path = [path, &[i as i32]].concat();
+            let full_name = format!("{}.{}", scope, message.name);
+            let field_descriptions = (0..message.field.len())
+                .map(|k| comment(comments, &[&path[..], &[2, k as i32]].concat()))
+                .collect();
+            self.add_messages(comments, &full_name, &message.nested_type, &[&path[..], &[3]].concat());
+            self.add_enums(comments, &full_name, &message.enum_type, &[&path[..], &[4]].concat());
+            let message_type = MessageType {
+                descriptor: message,
+                name: String::new(),
+                description: comment(comments, &path),
+                field_descriptions,
+            };
+            self.messages.insert(full_name, message_type);
+        }
+    }
+
+    fn add_enums(&mut self, comments: &Comments, scope: &str, enums: &'a [EnumDescriptorProto], path: &[i32]) {
+        for (i, descriptor) in enums.iter().enumerate() {
+            let path = [path, &[i as i32]].concat();
+            let full_name = format!("{}.{}", scope, descriptor.name);
+            let enum_type = EnumType { descriptor, name: String::new(), description: comment(comments, &path) };
+            self.enums.insert(full_name, enum_type);
+        }
+    }
+
+    fn message(&self, full_name: &str) -> Result<&MessageType<'a>, CompilerError> {
+        self.messages.get(full_name).ok_or_else(|| unknown_type(full_name))
+    }
+
+    /// Returns the operation of an RPC for a binding with the given path
+    /// variables.
+    fn operation(
+        &mut self,
+        method: &MethodDescriptorProto,
+        rule: &HttpRule,
+        variables: &[String],
+    ) -> Result<OperationBuilder, CompilerError> {
+        let input = &method.input_type;
+        let mut builder = Operation::builder();
+        for variable in variables {
+            let (field, description) = self.field_at(input, variable)?;
+            let parameter = Parameter::builder(variable, "path")
+                .required()
+                .description(description)
+                .schema(self.single_schema(field)?);
+            builder = builder.parameter(parameter);
+        }
+
+        let body = match rule.body.as_str() {
+            "*" => Some(self.reference(input)?),
+            "" => None,
+            name => Some(self.field_schema(self.field_at(input, name)?.0)?),
+        };
+        if rule.body != "*" {
+            let message = self.message(input)?;
+            let (fields, descriptions) = (&message.descriptor.field, message.field_descriptions.clone());
+            for (field, description) in fields.iter().zip(descriptions) {
+                let bound = variables.iter().any(|variable| variable.split('.').next() == Some(field.name.as_str()));
+                if bound || field.name == rule.body {
+                    continue;
+                }
+                let schema = self.field_schema(field)?;
+                if is_query_schema(&schema) {
+                    let parameter = Parameter::builder(json_name(field), "query").description(description);
+                    builder = builder.parameter(parameter.schema(schema));
+                }
+            }
+        }
+        if let Some(body) = body {
+            builder = builder.request_body(RequestBody::builder().required().content("application/json", body));
+        }
+
+        let response = Response::builder("OK");
+        let response = match rule.response_body.as_str() {
+            _ if method.output_type == EMPTY => response,
+            "" => response.content("application/json", self.reference(&method.output_type)?),
+            name => {
+                let field = self.field_at(&method.output_type, name)?.0;
+                response.content("application/json", self.field_schema(field)?)
+            }
+        };
+        Ok(builder.response("200", response))
+    }
+
+    /// Returns the field at a path of field names, such as "book.name", and
+    /// its description.
+    fn field_at(&self, message: &str, path: &str) -> Result<(&'a FieldDescriptorProto, String), CompilerError> {
+        let mut message = self.message(message)?;
+        let mut segments = path.split('.').peekable();
+        while let Some(segment) = segments.next() {
+            let Some(k) = message.descriptor.field.iter().position(|field| field.name == segment) else {
+                let error = format!("no field {} in message {} for {}", segment, message.name, path);
+                return Err(CompilerError::Protobuf(error));
+            };
+            let field = &message.descriptor.field[k];
+            if segments.peek().is_none() {
+                return Ok((field, message.field_descriptions[k].clone()));
+            }
+            message = self.message(&field.type_name)?;
+        }
+        Err(CompilerError::Protobuf(format!("empty field path in message {}", message.name)))
+    }
+
+    /// Returns the schema of a field, which is an array or a map if the field
+    /// is repeated.
+    fn field_schema(&mut self, field: &FieldDescriptorProto) -> Result<SchemaOrReference, CompilerError> {
+        if field.label != LABEL_REPEATED {
+            return self.single_schema(field);
+        }
+        let entry = match field.r#type {
+            TYPE_MESSAGE => self.message(&field.type_name)?.descriptor,
+            _ => return Ok(Schema::array_of(self.single_schema(field)?).into()),
+        };
+        if !entry.options.as_ref().is_some_and(|options| options.map_entry) {
+            return Ok(Schema::array_of(self.single_schema(field)?).into());
+        }
+        let value = entry.field.iter().find(|field| field.number == 2).ok_or_else(|| unknown_type(&field.type_name))?;
+        let values = self.single_schema(value)?;
+        Ok(Schema { additional_properties: Some(Box::new(values.into())), ..Schema::object() }.into())
+    }
+
+    /// Returns the schema of a single value of a field.
+    fn single_schema(&mut self, field: &FieldDescriptorProto) -> Result<SchemaOrReference, CompilerError> {
+        match field.r#type {
+            TYPE_MESSAGE | TYPE_GROUP => match well_known_schema(&field.type_name) {
+                Some(schema) => Ok(schema.into()),
+                None => self.reference(&field.type_name),
+            },
+            TYPE_ENUM => self.reference(&field.type_name),
+            scalar => Ok(scalar_schema(scalar).into()),
+        }
+    }
+
+    /// Returns a reference to the component schema of a message or enum.
+    fn reference(&mut self, full_name: &str) -> Result<SchemaOrReference, CompilerError> {
+        let name = match (self.messages.get(full_name), self.enums.get(full_name)) {
+            (Some(message), _) => &message.name,
+            (_, Some(enum_type)) => &enum_type.name,
+            _ => return Err(unknown_type(full_name)),
+        };
+        let reference = Schema::reference(format!("#/components/schemas/{}", name));
+        self.referenced.insert(full_name.to_string());
+        Ok(reference)
+    }
+
+    /// Returns the schemas of the referenced types, including the types that
+    /// their schemas reference.
+    fn component_schemas(&mut self) -> Result<Vec<(String, Schema)>, CompilerError> {
+        let mut schemas = Vec::new();
+        let mut i = 0;
+        while let Some(full_name) = self.referenced.get_index(i).cloned() {
+            i += 1;
+            if let Some(enum_type) = self.enums.get(&full_name) {
+                let values = enum_type.descriptor.value.iter().map(|value| value.name.as_str());
+                let schema = Schema::builder().schema_type("string").format("enum").enum_values(values);
+                schemas.push((enum_type.name.clone(), schema.description(&enum_type.description).build()));
+                continue;
+            }
+            let message = self.message(&full_name)?;
+            let (descriptor, name) = (message.descriptor, message.name.clone());
+            let mut builder = Schema::builder().schema_type("object").description(&message.description);
+            let descriptions = message.field_descriptions.clone();
+            for (field, description) in descriptor.field.iter().zip(descriptions) {
+                let mut schema = self.field_schema(field)?;
+                if let Some(schema_or_reference::Oneof::Schema(schema)) = &mut schema.oneof {
+                    schema.description = description;
+                }
+                builder = builder.property(json_name(field), schema);
+            }
+            schemas.push((name, builder.build()));
+        }
+        Ok(schemas)
+    }
+}
+
+/// The leading comments of a file by the path of the element they are
+/// attached to.
+type Comments = HashMap<Vec<i32>, String>;
+
+/// Returns the last part of a full name, such as "Book" for
+/// ".library.v1.Book".
+fn short_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Returns the leading comments of a file.
+fn comments(file: &FileDescriptorProto) -> Comments {
+    let locations = file.source_code_info.iter().flat_map(|info| &info.location);
+    locations
+        .filter(|location| !location.leading_comments.is_empty())
+        .map(|location| {
+            let lines: Vec<&str> = location.leading_comments.lines().map(str::trim).collect();
+            (location.path.clone(), lines.join("\n").trim().to_string())
+        })
+        .collect()
+}
+
+fn comment(comments: &Comments, path: &[i32]) -> String {
+    comments.get(path).cloned().unwrap_or_default()
+}
+
+fn unknown_type(full_name: &str) -> CompilerError {
+    CompilerError::Protobuf(format!("unknown type {}; the descriptor set must include imports", full_name))
+}
+
+/// Returns the method and path template of a rule, or None if it has no
+/// pattern or a custom method that OpenAPI lacks.
+fn pattern(rule: &HttpRule) -> Option<(HttpMethod, &str)> {
+    let patterns = [
+        (HttpMethod::Get, &rule.get),
+        (HttpMethod::Put, &rule.put),
+        (HttpMethod::Post, &rule.post),
+        (HttpMethod::Delete, &rule.delete),
+        (HttpMethod::Patch, &rule.patch),
+    ];
+    if let Some((method, path)) = patterns.into_iter().find(|(_, path)| !path.is_empty()) {
+        return Some((method, path.as_str()));
+    }
+    let custom = rule.custom.as_ref()?;
+    Some((HttpMethod::from_str(&custom.kind).ok()?, custom.path.as_str()))
+}
+
+/// Converts a path template such as "/v1/{name=shelves/*}" to an OpenAPI
+/// path, returning the path and the field paths of its variables.
+fn path_template(template: &str) -> (String, Vec<String>) {
+    let mut path = String::new();
+    let mut variables = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        let variable = rest[start + 1..end].split('=').next().unwrap_or_default().trim();
+        path.push_str(&rest[..start]);
+        path.push_str(&format!("{{{}}}", variable));
+        variables.push(variable.to_string());
+        rest = &rest[end + 1..];
+    }
+    path.push_str(rest);
+    (path, variables)
+}
+
+/// Returns the JSON name of a field, which protoc sets to the lowerCamelCase
+/// form of its name.
+fn json_name(field: &FieldDescriptorProto) -> String {
+    if !field.json_name.is_empty() {
+        return field.json_name.clone();
+    }
+    let mut words = field.name.split('_');
+    let first = words.next().unwrap_or_default().to_string();
+    words.fold(first, |name, word| {
+        let mut chars = word.chars();
+        let capital = chars.next().map(|c| c.to_ascii_uppercase().to_string()).unwrap_or_default();
+        name + &capital + chars.as_str()
+    })
+}
+
+/// Returns true if a schema can be the schema of a query parameter, which
+/// excludes messages and maps.
+fn is_query_schema(schema: &SchemaOrReference) -> bool {
+    match &schema.oneof {
+        Some(schema_or_reference::Oneof::Schema(schema)) => {
+            let mut items = schema.items.iter().flat_map(|items| &items.schema_or_reference);
+            schema.r#type != "object" && items.all(is_query_schema)
+        }
+        _ => false,
+    }
+}
+
+fn formatted(schema: Schema, format: &str) -> Schema {
+    Schema { format: format.to_string(), ..schema }
+}
+
+/// Returns the schema of a scalar type, in the JSON mapping of proto3, which
+/// writes 64-bit integers as strings.
+fn scalar_schema(scalar: i32) -> Schema {
+    match scalar {
+        1 => formatted(Schema::number(), "double"),
+        2 => formatted(Schema::number(), "float"),
+        3 | 16 | 18 => formatted(Schema::string(), "int64"),
+        4 | 6 => formatted(Schema::string(), "uint64"),
+        5 | 15 | 17 => formatted(Schema::integer(), "int32"),
+        7 | 13 => formatted(Schema::integer(), "uint32"),
+        8 => Schema::boolean(),
+        12 => formatted(Schema::string(), "bytes"),
+        _ => Schema::string(),
+    }
+}
+
+/// Returns the schema of a well-known type in its JSON mapping.
+fn well_known_schema(full_name: &str) -> Option<Schema> {
+    let schema = match full_name.strip_prefix(".google.protobuf.")? {
+        "Timestamp" => formatted(Schema::string(), "date-time"),
+        "Duration" => Schema::string(),
+        "FieldMask" => formatted(Schema::string(), "field-mask"),
+        "Empty" | "Struct" | "Any" => Schema::object(),
+        "Value" => Schema::default(),
+        "ListValue" => Schema::array_of(Schema::default()),
+        "DoubleValue" => scalar_schema(1),
+        "FloatValue" => scalar_schema(2),
+        "Int64Value" => scalar_schema(3),
+        "UInt64Value" => scalar_schema(4),
+        "Int32Value" => scalar_schema(5),
+        "UInt32Value" => scalar_schema(13),
+        "BoolValue" => scalar_schema(8),
+        "StringValue" => scalar_schema(9),
+        "BytesValue" => scalar_schema(12),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::validate;
+    use gnostic_compiler::NamedMap;
+
+    fn field(name: &str, number: i32, r#type: i32, type_name: &str) -> FieldDescriptorProto {
+        let json_name = json_name(&FieldDescriptorProto { name: name.to_string(), ..Default::default() });
+        let type_name = type_name.to_string();
+        FieldDescriptorProto { name: name.to_string(), number, label: 1, r#type, type_name, json_name }
+    }
+
+    fn message(name: &str, field: Vec<FieldDescriptorProto>) -> DescriptorProto {
+        DescriptorProto { name: name.to_string(), field, ..Default::default() }
+    }
+
+    fn method(name: &str, input: &str, output: &str, http: HttpRule) -> MethodDescriptorProto {
+        MethodDescriptorProto {
+            name: name.to_string(),
+            input_type: format!(".library.v1.{}", input),
+            output_type: output.to_string(),
+            options: Some(MethodOptions { deprecated: false, http: Some(http) }),
+        }
+    }
+
+    fn location(path: Vec<i32>, comment: &str) -> Location {
+        Location { path, leading_comments: comment.to_string() }
+    }
+
+    fn library() -> FileDescriptorSet {
+        let entry = DescriptorProto {
+            options: Some(MessageOptions { map_entry: true }),
+            ..message("LabelsEntry", vec![field("key", 1, 9, ""), field("value", 2, 5, "")])
+        };
+        let kind = EnumDescriptorProto {
+            name: "Kind".to_string(),
+            value: vec![EnumValueDescriptorProto { name: "KIND_UNSPECIFIED".to_string(), number: 0 }],
+        };
+        let book = DescriptorProto {
+            nested_type: vec![entry],
+            enum_type: vec![kind],
+            ..message(
+                "Book",
+                vec![
+                    field("name", 1, 9, ""),
+                    field("page_count", 2, 3, ""),
+                    FieldDescriptorProto { label: LABEL_REPEATED, ..field("tags", 3, 9, "") },
+                    field("kind", 4, TYPE_ENUM, ".library.v1.Book.Kind"),
+                    field("create_time", 5, TYPE_MESSAGE, ".google.protobuf.Timestamp"),
+                    FieldDescriptorProto {
+                        label: LABEL_REPEATED,
+                        ..field("labels", 6, TYPE_MESSAGE, ".library.v1.Book.LabelsEntry")
+                    },
+                ],
+            )
+        };
+        let messages = vec![
+            book,
+            message("GetBookRequest", vec![field("name", 1, 9, "")]),
+            message(
+                "CreateBookRequest",
+                vec![field("parent", 1, 9, ""), field("book", 2, TYPE_MESSAGE, ".library.v1.Book")],
+            ),
+            message("ListBooksRequest", vec![field("parent", 1, 9, ""), field("page_size", 2, 5, "")]),
+            message("ListBooksResponse", vec![FieldDescriptorProto {
+                label: LABEL_REPEATED,
+                ..field("books", 1, TYPE_MESSAGE, ".library.v1.Book")
+            }]),
+        ];
+        let get = HttpRule {
+            get: "/v1/{name=shelves/*/books/*}".to_string(),
+            additional_bindings: vec![HttpRule { get: "/v1/books/{name}".to_string(), ..Default::default() }],
+            ..Default::default()
+        };
+        let create = HttpRule {
+            post: "/v1/{parent=shelves/*}/books".to_string(),
+            body: "book".to_string(),
+            ..Default::default()
+        };
+        let list = HttpRule { get: "/v1/{parent=shelves/*}/books".to_string(), ..Default::default() };
+        let delete = HttpRule { delete: "/v1/{name=shelves/*/books/*}".to_string(), ..Default::default() };
+        let service = ServiceDescriptorProto {
+            name: "LibraryService".to_string(),
+            method: vec![
+                method("GetBook", "GetBookRequest", ".library.v1.Book", get),
+                method("CreateBook", "CreateBookRequest", ".library.v1.Book", create),
+                method("ListBooks", "ListBooksRequest", ".library.v1.ListBooksResponse", list),
+                method("DeleteBook", "GetBookRequest", EMPTY, delete),
+            ],
+        };
+        let source_code_info = SourceCodeInfo {
+            location: vec![
+                location(vec![6, 0], " Manages books.\n"),
+                location(vec![6, 0, 2, 0], " Gets a book.\n"),
+                location(vec![4, 0], " A book.\n"),
+                location(vec![4, 0, 2, 1], " The number of pages.\n"),
+                location(vec![4, 3, 2, 1], " The most books to return.\n"),
+            ],
+        };
+        let file = FileDescriptorProto {
+            name: "library.proto".to_string(),
+            package: "library.v1".to_string(),
+            message_type: messages,
+            service: vec![service],
+            source_code_info: Some(source_code_info),
+            ..Default::default()
+        };
+        FileDescriptorSet { file: vec![file] }
+    }
+
+    #[test]
+    fn test_generate_openapi() {
+        let doc = generate_openapi(&library().encode_to_vec()).unwrap();
+        assert!(validate(&doc).errors.is_empty(), "{}", validate(&doc));
+        let info = doc.info.as_ref().unwrap();
+        assert_eq!((info.title.as_str(), info.description.as_str()), ("LibraryService", "Manages books."));
+        assert_eq!(doc.tags[0].name, "LibraryService");
+
+        let paths = doc.paths.as_ref().unwrap();
+        let names: Vec<&str> = paths.path.iter().map(|path| path.name.as_str()).collect();
+        assert_eq!(names, ["/v1/{name}", "/v1/books/{name}", "/v1/{parent}/books"]);
+        let item = paths.get("/v1/{name}").unwrap();
+        let get = item.operation(HttpMethod::Get).unwrap();
+        assert_eq!((get.operation_id.as_str(), get.description.as_str()), ("LibraryService_GetBook", "Gets a book."));
+        assert_eq!(get.tags, ["LibraryService"]);
+        let responses = item.operation(HttpMethod::Delete).unwrap().responses.as_ref().unwrap();
+        let Some(response_or_reference::Oneof::Response(deleted)) = &responses.get("200").unwrap().oneof else {
+            panic!("{:?}", responses);
+        };
+        assert!(deleted.content.is_none());
+        let binding = paths.get("/v1/books/{name}").unwrap().operation(HttpMethod::Get).unwrap();
+        assert_eq!(binding.operation_id, "LibraryService_GetBook2");
+
+        let yaml = String::from_utf8(crate::yaml_value(&doc)).unwrap();
+        for expected in [
+            "operationId: LibraryService_CreateBook",
+            "$ref: '#/components/schemas/Book'",
+            "name: pageSize\n        in: query\n        description: The most books to return.",
+            "pageCount:\n          type: string\n          description: The number of pages.\n          format: int64",
+            "createTime:\n          type: string\n          format: date-time",
+            "labels:\n          type: object\n          additionalProperties:\n            type: integer",
+            "Kind:\n      enum:\n      - KIND_UNSPECIFIED\n      type: string",
+        ] {
+            assert!(yaml.contains(expected), "{}\n{}", expected, yaml);
+        }
+        assert!(!yaml.contains("name: parent\n        in: query"), "{}", yaml);
+    }
+
+    #[test]
+    fn test_generate_openapi_errors() {
+        assert!(matches!(generate_openapi(b"\xff"), Err(CompilerError::Protobuf(_))));
+        let mut set = library();
+        set.file[0].message_type.remove(1);
+        let error = generate_openapi(&set.encode_to_vec()).unwrap_err();
+        assert!(error.to_string().contains("unknown type .library.v1.GetBookRequest"), "{}", error);
+    }
+
+    #[test]
+    fn test_path_template() {
+        let (path, variables) = path_template("/v1/{book.name=shelves/*/books/*}:publish");
+        assert_eq!(path, "/v1/{book.name}:publish");
+        assert_eq!(variables, ["book.name"]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Conversions between gnostic models and the serde types of other crates.
+//!
+//! The `openapiv3` feature converts to and from [`openapiv3::OpenAPI`], and
+//! the `utoipa` feature to and from `utoipa::openapi::OpenApi`, so that specs
+//! derived at compile time can be fed to gnostic. Documents are converted
+//! through their JSON form, so a converted document is checked by the same
+//! parser as documents read from files, and fields that the target model
+//! does not represent are dropped.
+
+use gnostic_compiler::{CompilerError, ErrorGroup};
+
+use crate::document::{json_value, parse_document};
+use crate::openapi_v3::Document;
+
+/// Implements TryFrom in both directions between Document and a serde type.
+macro_rules! serde_conversions {
+    ($feature:literal, $type:ty) => {
+        #[cfg(feature = $feature)]
+        impl TryFrom<&Document> for $type {
+            type Error = ErrorGroup;
+
+            fn try_from(doc: &Document) -> Result<Self, Self::Error> {
+                serde_json::from_slice(&json_value(doc)).map_err(|e| encoding_error($feature, e))
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl TryFrom<Document> for $type {
+            type Error = ErrorGroup;
+
+            fn try_from(doc: Document) -> Result<Self, Self::Error> {
+                <$type>::try_from(&doc)
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl TryFrom<&$type> for Document {
+            type Error = ErrorGroup;
+
+            fn try_from(value: &$type) -> Result<Self, Self::Error> {
+                parse_document(&serde_json::to_vec(value).map_err(|e| encoding_error($feature, e))?)
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl TryFrom<$type> for Document {
+            type Error = ErrorGroup;
+
+            fn try_from(value: $type) -> Result<Self, Self::Error> {
+                Document::try_from(&value)
+            }
+        }
+    };
+}
+
+serde_conversions!("openapiv3", openapiv3::OpenAPI);
+serde_conversions!("utoipa", utoipa::openapi::OpenApi);
+
+fn encoding_error(name: &str, error: serde_json::Error) -> ErrorGroup {
+    ErrorGroup::from(CompilerError::Encoding(format!("{}: {}", name, error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETSTORE: &str = r##"
+openapi: 3.0.3
+info:
+  title: Petstore
+  version: 1.0.0
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: integer
+            format: int64
+      responses:
+        "200":
+          description: A pet
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Pet"
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        name:
+          type: string
+"##;
+
+    #[test]
+    #[cfg(feature = "openapiv3")]
+    fn test_document_to_openapiv3() {
+        let doc = parse_document(PETSTORE.as_bytes()).unwrap();
+        let openapi = openapiv3::OpenAPI::try_from(&doc).unwrap();
+        assert_eq!(openapi.info.title, "Petstore");
+        let path = openapi.paths.paths["/pets/{id}"].as_item().unwrap();
+        assert_eq!(path.get.as_ref().unwrap().operation_id.as_deref(), Some("getPet"));
+        assert!(openapi.components.unwrap().schemas.contains_key("Pet"));
+    }
+
+    #[test]
+    #[cfg(feature = "openapiv3")]
+    fn test_openapiv3_round_trip() {
+        let doc = parse_document(PETSTORE.as_bytes()).unwrap();
+        let openapi = openapiv3::OpenAPI::try_from(doc).unwrap();
+        let converted = Document::try_from(&openapi).unwrap();
+        assert_eq!(converted.paths.as_ref().unwrap().path[0].name, "/pets/{id}");
+        // The openapiv3 crate writes default parameter styles explicitly.
+        assert_eq!(openapiv3::OpenAPI::try_from(converted).unwrap(), openapi);
+    }
+
+    #[test]
+    #[cfg(feature = "openapiv3")]
+    fn test_document_missing_required_fields() {
+        let err = openapiv3::OpenAPI::try_from(&Document::default()).unwrap_err();
+        assert!(err.to_string().contains("openapiv3"));
+    }
+
+    #[test]
+    #[cfg(feature = "utoipa")]
+    fn test_utoipa_to_document() {
+        use utoipa::openapi::path::{HttpMethod, OperationBuilder};
+        use utoipa::openapi::{Info, OpenApiBuilder, PathItem, PathsBuilder, Response};
+
+        let operation = OperationBuilder::new()
+            .operation_id(Some("listPets"))
+            .response("200", Response::new("A list of pets"));
+        let openapi = OpenApiBuilder::new()
+            .info(Info::new("Petstore", "1.0.0"))
+            .paths(PathsBuilder::new().path("/pets", PathItem::new(HttpMethod::Get, operation)))
+            .build();
+        let doc = Document::try_from(&openapi).unwrap();
+        assert_eq!(doc.openapi, "3.1.0");
+        assert_eq!(doc.info.as_ref().unwrap().title, "Petstore");
+        let path = &doc.paths.as_ref().unwrap().path[0];
+        assert_eq!(path.name, "/pets");
+        assert_eq!(path.value.as_ref().unwrap().get.as_ref().unwrap().operation_id, "listPets");
+        let back = utoipa::openapi::OpenApi::try_from(doc).unwrap();
+        assert_eq!(back.to_pretty_json().unwrap(), openapi.to_pretty_json().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "utoipa")]
+    fn test_document_to_utoipa() {
+        // utoipa models OpenAPI 3.1 only.
+        let doc = parse_document(PETSTORE.replace("3.0.3", "3.1.0").as_bytes()).unwrap();
+        let openapi = utoipa::openapi::OpenApi::try_from(&doc).unwrap();
+        assert_eq!(openapi.info.title, "Petstore");
+        assert!(openapi.paths.get_path_item("/pets/{id}").unwrap().get.is_some());
+        assert!(openapi.components.unwrap().schemas.contains_key("Pet"));
+    }
+}
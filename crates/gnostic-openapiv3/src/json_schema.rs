@@ -0,0 +1,332 @@
+//! Conversion between OpenAPI v3 schemas and JSON Schemas.
+//!
+//! OpenAPI 3.0 schemas are a variant of JSON Schema Draft 4: `nullable: true`
+//! stands for a "null" type, and a single `example` for `examples`. These
+//! functions convert between the protobuf [`Schema`] and
+//! [`gnostic_jsonschema::Schema`] so that the JSON Schema utilities apply to
+//! component schemas. Keywords without a counterpart on the other side, such
+//! as discriminator and xml or patternProperties and dependencies, are
+//! dropped. Numeric exclusive bounds become bounds with exclusive flags, and
+//! true and false schemas become `{}` and `{"not": {}}`. As when writing
+//! YAML, zero numbers are treated as unset.
+
+use crate::openapi_v3::*;
+use gnostic_compiler::marshal;
+use gnostic_jsonschema::{
+    NumberOrBoolean, Schema as JsonSchema, SchemaNumber, SchemaOrBoolean, SchemaOrSchemaArray, StringOrStringArray,
+};
+
+/// Returns the JSON Schema for a schema or reference.
+pub fn to_json_schema(schema: &SchemaOrReference) -> JsonSchema {
+    match &schema.oneof {
+        Some(schema_or_reference::Oneof::Schema(schema)) => schema_to_json_schema(schema),
+        Some(schema_or_reference::Oneof::Reference(reference)) => JsonSchema::reference(&reference.r#ref),
+        None => JsonSchema::default(),
+    }
+}
+
+/// Returns the JSON Schema for a schema; `nullable: true` adds "null" to its type.
+pub fn schema_to_json_schema(schema: &Schema) -> JsonSchema {
+    let type_value = match (schema.r#type.as_str(), schema.nullable) {
+        ("", _) => None,
+        (name, true) => Some(StringOrStringArray::Array(vec![name.to_string(), "null".to_string()])),
+        (name, false) => Some(StringOrStringArray::String(name.to_string())),
+    };
+    JsonSchema {
+        title: non_empty(&schema.title),
+        description: non_empty(&schema.description),
+        default: schema.default.as_ref().and_then(|default| match &default.oneof {
+            Some(default_type::Oneof::Number(number)) => serde_json::Number::from_f64(*number).map(Into::into),
+            Some(default_type::Oneof::Boolean(value)) => Some((*value).into()),
+            Some(default_type::Oneof::String(value)) => Some(value.clone().into()),
+            None => None,
+        }),
+        examples: schema.example.as_deref().and_then(any_to_json).map(|example| vec![example]),
+        read_only: schema.read_only.then_some(true),
+        write_only: schema.write_only.then_some(true),
+        multiple_of: number(schema.multiple_of),
+        maximum: number(schema.maximum),
+        exclusive_maximum: schema.exclusive_maximum.then_some(NumberOrBoolean::Boolean(true)),
+        minimum: number(schema.minimum),
+        exclusive_minimum: schema.exclusive_minimum.then_some(NumberOrBoolean::Boolean(true)),
+        max_length: non_zero(schema.max_length),
+        min_length: non_zero(schema.min_length),
+        pattern: non_empty(&schema.pattern),
+        items: schema.items.as_ref().and_then(|items| match items.schema_or_reference.as_slice() {
+            [] => None,
+            [item] => Some(Box::new(SchemaOrSchemaArray::Schema(to_json_schema(item).into()))),
+            items => Some(Box::new(SchemaOrSchemaArray::Array(schemas(items).unwrap_or_default()))),
+        }),
+        max_items: non_zero(schema.max_items),
+        min_items: non_zero(schema.min_items),
+        unique_items: schema.unique_items.then_some(true),
+        max_properties: non_zero(schema.max_properties),
+        min_properties: non_zero(schema.min_properties),
+        required: (!schema.required.is_empty()).then(|| schema.required.clone()),
+        additional_properties: schema.additional_properties.as_ref().and_then(|additional| {
+            match &additional.oneof {
+                Some(additional_properties_item::Oneof::SchemaOrReference(schema)) => {
+                    Some(SchemaOrBoolean::Schema(Box::new(to_json_schema(schema))))
+                }
+                Some(additional_properties_item::Oneof::Boolean(value)) => Some(SchemaOrBoolean::Boolean(*value)),
+                None => None,
+            }
+        }),
+        properties: schema.properties.as_ref().map(|properties| {
+            properties
+                .additional_properties
+                .iter()
+                .map(|property| {
+                    let value = property.value.as_ref().map(to_json_schema).unwrap_or_default();
+                    (property.name.clone(), value.into())
+                })
+                .collect()
+        }),
+        enumeration: (!schema.r#enum.is_empty()).then(|| schema.r#enum.iter().filter_map(any_to_json).collect()),
+        type_value,
+        format: non_empty(&schema.format),
+        all_of: schemas(&schema.all_of),
+        any_of: schemas(&schema.any_of),
+        one_of: schemas(&schema.one_of),
+        not: schema.not.as_deref().map(|not| schema_to_json_schema(not).into()),
+        ..Default::default()
+    }
+}
+
+/// Returns the schema or reference for a JSON Schema.
+pub fn from_json_schema(schema: &JsonSchema) -> SchemaOrReference {
+    let oneof = match &schema.reference {
+        Some(reference) => schema_or_reference::Oneof::Reference(Reference {
+            r#ref: reference.clone(),
+            ..Default::default()
+        }),
+        None => schema_or_reference::Oneof::Schema(Box::new(schema_from_json_schema(schema))),
+    };
+    SchemaOrReference { oneof: Some(oneof) }
+}
+
+/// Returns the schema for a JSON Schema, ignoring its $ref.
+///
+/// A "null" type becomes `nullable: true`. Since OpenAPI 3.0 schemas have a
+/// single type, several other types become an anyOf of one schema per type;
+/// the other keywords only apply to some types, so they stay in the schema.
+/// A const becomes an enum of one value.
+pub fn schema_from_json_schema(schema: &JsonSchema) -> Schema {
+    let mut types: Vec<String> = match &schema.type_value {
+        Some(StringOrStringArray::String(name)) => vec![name.clone()],
+        Some(StringOrStringArray::Array(names)) => names.clone(),
+        None => Vec::new(),
+    };
+    let nullable = types.iter().any(|name| name == "null");
+    types.retain(|name| name != "null");
+    let (maximum, exclusive_maximum) = schema.upper_bound().unwrap_or_default();
+    let (minimum, exclusive_minimum) = schema.lower_bound().unwrap_or_default();
+    let values = schema.enumeration.iter().flatten().chain(&schema.const_value);
+    let mut result = Schema {
+        nullable,
+        read_only: schema.read_only.unwrap_or_default(),
+        write_only: schema.write_only.unwrap_or_default(),
+        example: schema.examples.iter().flatten().next().map(json_to_any).map(Box::new),
+        title: schema.title.clone().unwrap_or_default(),
+        multiple_of: schema.multiple_of.as_ref().map(SchemaNumber::as_f64).unwrap_or_default(),
+        maximum,
+        exclusive_maximum,
+        minimum,
+        exclusive_minimum,
+        max_length: schema.max_length.unwrap_or_default(),
+        min_length: schema.min_length.unwrap_or_default(),
+        pattern: schema.pattern.clone().unwrap_or_default(),
+        max_items: schema.max_items.unwrap_or_default(),
+        min_items: schema.min_items.unwrap_or_default(),
+        unique_items: schema.unique_items.unwrap_or_default(),
+        max_properties: schema.max_properties.unwrap_or_default(),
+        min_properties: schema.min_properties.unwrap_or_default(),
+        required: schema.required.clone().unwrap_or_default(),
+        r#enum: values.map(json_to_any).collect(),
+        r#type: if types.len() == 1 { types.remove(0) } else { String::new() },
+        all_of: schema.all_of.iter().flatten().map(from_subschema).collect(),
+        one_of: schema.one_of.iter().flatten().map(from_subschema).collect(),
+        any_of: schema.any_of.iter().flatten().map(from_subschema).collect(),
+        not: schema.not.as_ref().map(|not| Box::new(schema_from_json_schema(&not.to_schema()))),
+        items: schema.items.as_deref().map(|items| ItemsItem {
+            schema_or_reference: match items {
+                SchemaOrSchemaArray::Schema(item) => vec![from_subschema(item)],
+                SchemaOrSchemaArray::Array(items) => items.iter().map(from_subschema).collect(),
+            },
+        }),
+        properties: schema.properties.as_ref().map(|properties| Properties {
+            additional_properties: properties
+                .iter()
+                .map(|(name, property)| NamedSchemaOrReference {
+                    name: name.clone(),
+                    value: Some(from_subschema(property)),
+                })
+                .collect(),
+        }),
+        additional_properties: schema.additional_properties.as_ref().map(|additional| {
+            Box::new(AdditionalPropertiesItem {
+                oneof: Some(match additional {
+                    SchemaOrBoolean::Schema(schema) => {
+                        additional_properties_item::Oneof::SchemaOrReference(Box::new(from_json_schema(schema)))
+                    }
+                    SchemaOrBoolean::Boolean(value) => additional_properties_item::Oneof::Boolean(*value),
+                }),
+            })
+        }),
+        default: schema.default.as_ref().and_then(|default| {
+            let oneof = match default {
+                serde_json::Value::Number(number) => default_type::Oneof::Number(number.as_f64()?),
+                serde_json::Value::Bool(value) => default_type::Oneof::Boolean(*value),
+                serde_json::Value::String(value) => default_type::Oneof::String(value.clone()),
+                _ => return None,
+            };
+            Some(DefaultType { oneof: Some(oneof) })
+        }),
+        description: schema.description.clone().unwrap_or_default(),
+        format: schema.format.clone().unwrap_or_default(),
+        ..Default::default()
+    };
+    if types.len() > 1 {
+        let alternatives: Vec<SchemaOrReference> = types
+            .into_iter()
+            .map(|name| SchemaOrReference::schema(Schema::of_type(name)))
+            .collect();
+        if result.any_of.is_empty() {
+            result.any_of = alternatives;
+        } else {
+            result.all_of.push(SchemaOrReference::schema(Schema { any_of: alternatives, ..Default::default() }));
+        }
+    }
+    result
+}
+
+fn schemas(schemas: &[SchemaOrReference]) -> Option<Vec<SchemaOrBoolean>> {
+    (!schemas.is_empty()).then(|| schemas.iter().map(|schema| to_json_schema(schema).into()).collect())
+}
+
+fn from_subschema(schema: &SchemaOrBoolean) -> SchemaOrReference {
+    from_json_schema(&schema.to_schema())
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn non_zero(value: i64) -> Option<i64> {
+    (value != 0).then_some(value)
+}
+
+/// Returns a JSON Schema number, preferring integers, or None for zero.
+fn number(value: f64) -> Option<SchemaNumber> {
+    if value == 0.0 {
+        None
+    } else if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+        Some(SchemaNumber::Integer(value as i64))
+    } else {
+        Some(SchemaNumber::Float(value))
+    }
+}
+
+fn any_to_json(any: &Any) -> Option<serde_json::Value> {
+    serde_yaml::from_str(&any.yaml).ok()
+}
+
+fn json_to_any(value: &serde_json::Value) -> Any {
+    let node = serde_yaml::to_value(value).unwrap_or_default();
+    Any {
+        value: None,
+        yaml: String::from_utf8_lossy(&marshal(&node)).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gnostic_compiler::Context;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_round_trip() {
+        let json: JsonSchema = serde_json::from_value(serde_json::json!({
+            "type": ["string", "null"],
+            "maxLength": 8,
+            "enum": ["cat", "dog", null],
+            "default": "cat",
+            "examples": ["dog"],
+            "readOnly": true,
+        }))
+        .unwrap();
+        let schema = schema_from_json_schema(&json);
+        assert!(schema.nullable);
+        assert_eq!(schema.r#type, "string");
+        assert_eq!(schema.r#enum.len(), 3);
+        assert_eq!(schema.example.as_ref().map(|example| example.yaml.trim()), Some("dog"));
+        assert_eq!(schema_to_json_schema(&schema), json);
+    }
+
+    #[test]
+    fn test_from_openapi() {
+        let source = "type: object\nrequired: [id]\nproperties:\n  id:\n    type: integer\n  owner:\n    \
+                      $ref: '#/components/schemas/Owner'\n";
+        let node: serde_yaml::Value = serde_yaml::from_str(source).unwrap();
+        let schema = crate::parser::Parser::parse_schema(&node, &Arc::new(Context::root("$"))).unwrap();
+        let json = schema_to_json_schema(&schema);
+        assert_eq!(
+            serde_json::to_value(&json).unwrap(),
+            serde_json::json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": {"id": {"type": "integer"}, "owner": {"$ref": "#/components/schemas/Owner"}},
+            })
+        );
+        assert!(gnostic_jsonschema::validate_instance(&json, &serde_json::json!({"id": 1})).is_empty());
+    }
+
+    #[test]
+    fn test_draft6_forms() {
+        let json: JsonSchema = serde_json::from_value(serde_json::json!({
+            "type": "array",
+            "items": true,
+            "maximum": 20,
+            "exclusiveMaximum": 10,
+            "not": false,
+        }))
+        .unwrap();
+        let schema = schema_from_json_schema(&json);
+        assert_eq!((schema.maximum, schema.exclusive_maximum), (10.0, true));
+        assert_eq!(
+            serde_json::to_value(schema_to_json_schema(&schema)).unwrap(),
+            serde_json::json!({
+                "type": "array",
+                "items": {},
+                "maximum": 10,
+                "exclusiveMaximum": true,
+                "not": {"not": {}},
+            })
+        );
+    }
+
+    #[test]
+    fn test_several_types() {
+        let json: JsonSchema = serde_json::from_value(serde_json::json!({
+            "type": ["integer", "string"],
+            "minimum": 1,
+            "exclusiveMinimum": true,
+            "anyOf": [{"$ref": "#/components/schemas/Id"}],
+        }))
+        .unwrap();
+        let schema = schema_from_json_schema(&json);
+        assert_eq!(schema.r#type, "");
+        assert!(schema.exclusive_minimum);
+        assert_eq!(
+            schema_to_json_schema(&schema),
+            serde_json::from_value::<JsonSchema>(serde_json::json!({
+                "minimum": 1,
+                "exclusiveMinimum": true,
+                "anyOf": [{"$ref": "#/components/schemas/Id"}],
+                "allOf": [{"anyOf": [{"type": "integer"}, {"type": "string"}]}],
+            }))
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! On-demand parsing of OpenAPI v3 documents.
+//!
+//! Most of the work of parsing a document goes into its paths and component
+//! schemas. A [`LazyDocument`] parses everything else up front and keeps the
+//! nodes of each path item and schema, parsing one only when it is first
+//! accessed, so that tools that need the info block or a single path do not
+//! pay for the rest.
+
+use std::borrow::Cow;
+use std::sync::{Arc, OnceLock};
+
+use gnostic_compiler::{
+    read_info_from_bytes_with_limits, resolve_for_parse, CompilerError, Context, ErrorGroup, ParseOptions, Parsed,
+};
+use indexmap::IndexMap;
+use serde_yaml::{Mapping, Value as Yaml};
+
+use crate::openapi_v3::*;
+use crate::parser::Parser;
+
+/// Parses an OpenAPI v3 document, deferring the parsing of its paths and
+/// component schemas until they are accessed.
+pub fn parse_document_lazy(bytes: &[u8]) -> Result<LazyDocument, ErrorGroup> {
+    parse_document_lazy_with_options(bytes, &ParseOptions::default()).map(Parsed::into_value)
+}
+
+/// Parses an OpenAPI v3 document lazily with the given options.
+///
+/// The warnings returned are those of the eagerly parsed parts; the warnings
+/// of each path item and schema are reported when it is parsed. Raw info and
+/// comments are not preserved.
+pub fn parse_document_lazy_with_options(
+    bytes: &[u8],
+    options: &ParseOptions,
+) -> Result<Parsed<LazyDocument>, ErrorGroup> {
+    let mut root = match read_info_from_bytes_with_limits("", bytes, &options.limits()).map_err(|e| ErrorGroup::new(vec![e]))? {
+        Yaml::Sequence(mut content) if content.len() == 1 => content.remove(0),
+        yaml => yaml,
+    };
+
+    let context = Arc::new(Context::root_with_options("$", options.clone()));
+    let resolved = match resolve_for_parse(&root, "", &context) {
+        Cow::Owned(resolved) => Some(resolved),
+        Cow::Borrowed(_) => None,
+    };
+    if let Some(resolved) = resolved {
+        root = resolved;
+    }
+
+    let paths = take_path_items(&mut root);
+    let schemas = take_entries(
+        root.get_mut("components")
+            .and_then(Yaml::as_mapping_mut)
+            .and_then(|components| components.remove("schemas")),
+    );
+    let result = Parser::parse_document(&root, &context).map(|mut document| {
+        if schemas.is_some() {
+            document.components.get_or_insert_default().schemas = Some(SchemasOrReferences::default());
+        }
+        LazyDocument {
+            document,
+            paths: paths.unwrap_or_default(),
+            schemas: schemas.unwrap_or_default(),
+            options: options.clone(),
+        }
+    });
+    context.finish(result)
+}
+
+/// Takes the path items of a document to parse later, leaving the extensions
+/// of its paths to be parsed with it.
+fn take_path_items(root: &mut Yaml) -> Option<IndexMap<String, Lazy<PathItem>>> {
+    let paths = root.get_mut("paths")?.as_mapping_mut()?;
+    let (extensions, items): (Mapping, Mapping) = std::mem::take(paths)
+        .into_iter()
+        .partition(|(key, _)| key.as_str().is_some_and(|key| key.starts_with("x-")));
+    *paths = extensions;
+    take_entries(Some(Yaml::Mapping(items)))
+}
+
+/// Splits the node of a map into entries to parse later.
+fn take_entries<T>(node: Option<Yaml>) -> Option<IndexMap<String, Lazy<T>>> {
+    match node? {
+        Yaml::Mapping(map) => Some(
+            map.into_iter()
+                .filter_map(|(key, node)| {
+                    let name = key.as_str()?.to_string();
+                    Some((name, Lazy { node, value: OnceLock::new() }))
+                })
+                .collect(),
+        ),
+        _ => Some(IndexMap::new()),
+    }
+}
+
+/// The node of a value and the result of parsing it, once it is parsed.
+#[derive(Debug)]
+struct Lazy<T> {
+    node: Yaml,
+    value: OnceLock<Result<Parsed<T>, ErrorGroup>>,
+}
+
+/// An OpenAPI v3 document whose path items and component schemas are parsed
+/// on first access.
+#[derive(Debug)]
+pub struct LazyDocument {
+    document: Document,
+    paths: IndexMap<String, Lazy<PathItem>>,
+    schemas: IndexMap<String, Lazy<SchemaOrReference>>,
+    options: ParseOptions,
+}
+
+impl LazyDocument {
+    /// Returns the document without its path items and component schemas.
+    ///
+    /// The paths and schemas sections are present but have no entries if the
+    /// source has them; the extensions of the paths are parsed.
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    /// Returns the info block of the document.
+    pub fn info(&self) -> Option<&Info> {
+        self.document.info.as_ref()
+    }
+
+    /// Returns the paths of the document, in source order.
+    pub fn path_names(&self) -> impl Iterator<Item = &str> {
+        self.paths.keys().map(String::as_str)
+    }
+
+    /// Returns the names of the component schemas, in source order.
+    pub fn schema_names(&self) -> impl Iterator<Item = &str> {
+        self.schemas.keys().map(String::as_str)
+    }
+
+    /// Returns the item of a path, parsing it if this is the first access.
+    pub fn path(&self, path: &str) -> Option<Result<&Parsed<PathItem>, &ErrorGroup>> {
+        let lazy = self.paths.get(path)?;
+        Some(self.force(lazy, &["paths", path], Parser::parse_path_item))
+    }
+
+    /// Returns a component schema, parsing it if this is the first access.
+    pub fn schema(&self, name: &str) -> Option<Result<&Parsed<SchemaOrReference>, &ErrorGroup>> {
+        let lazy = self.schemas.get(name)?;
+        Some(self.force(lazy, &["components", "schemas", name], Parser::parse_schema_or_reference))
+    }
+
+    /// Returns true if the item of a path has been parsed.
+    pub fn is_path_parsed(&self, path: &str) -> bool {
+        self.paths.get(path).is_some_and(|lazy| lazy.value.get().is_some())
+    }
+
+    /// Parses every remaining path item and schema, returning the complete
+    /// document as [`parse_document_with_options`](crate::parse_document_with_options)
+    /// would, with the warnings of every part.
+    pub fn into_document(self) -> Result<Parsed<Document>, ErrorGroup> {
+        let options = &self.options;
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        let mut path = Vec::new();
+        for (name, lazy) in self.paths {
+            let result = lazy.into_result(options, &["paths", &name], Parser::parse_path_item);
+            if let Some(value) = collect(result, &mut errors, &mut warnings) {
+                path.push(NamedPathItem { name, value: Some(value) });
+            }
+        }
+        let mut schemas = Vec::new();
+        for (name, lazy) in self.schemas {
+            let location = ["components", "schemas", &name];
+            let result = lazy.into_result(options, &location, Parser::parse_schema_or_reference);
+            if let Some(value) = collect(result, &mut errors, &mut warnings) {
+                schemas.push(NamedSchemaOrReference { name, value: Some(value) });
+            }
+        }
+        if !errors.is_empty() {
+            return Err(ErrorGroup::new(errors));
+        }
+
+        let mut document = self.document;
+        if let Some(paths) = document.paths.as_mut() {
+            paths.path = path;
+        }
+        if let Some(section) = document.components.as_mut().and_then(|c| c.schemas.as_mut()) {
+            section.additional_properties = schemas;
+        }
+        Ok(Parsed { value: document, warnings: ErrorGroup::new(warnings), raw_info: None, comments: None })
+    }
+
+    fn force<'a, T>(
+        &self,
+        lazy: &'a Lazy<T>,
+        location: &[&str],
+        parse: ParseFn<T>,
+    ) -> Result<&'a Parsed<T>, &'a ErrorGroup> {
+        lazy.value.get_or_init(|| lazy.parse(&self.options, location, parse)).as_ref()
+    }
+}
+
+/// A parser of the values of a lazy section.
+type ParseFn<T> = fn(&Yaml, &Arc<Context>) -> Result<T, ErrorGroup>;
+
+impl<T> Lazy<T> {
+    /// Parses the node in its own context, so that its diagnostics are kept
+    /// apart from those of values parsed concurrently.
+    fn parse(&self, options: &ParseOptions, location: &[&str], parse: ParseFn<T>) -> Result<Parsed<T>, ErrorGroup> {
+        let root = Arc::new(Context::root_with_options("$", options.clone()));
+        let context = location.iter().fold(root, |parent, name| Arc::new(parent.child(*name)));
+        context.finish(parse(&self.node, &context))
+    }
+
+    /// Returns the result of parsing the node, parsing it if it has not been.
+    fn into_result(
+        mut self,
+        options: &ParseOptions,
+        location: &[&str],
+        parse: ParseFn<T>,
+    ) -> Result<Parsed<T>, ErrorGroup> {
+        match self.value.take() {
+            Some(result) => result,
+            None => self.parse(options, location, parse),
+        }
+    }
+}
+
+/// Returns the value of a parse, collecting its warnings or errors.
+fn collect<T>(
+    result: Result<Parsed<T>, ErrorGroup>,
+    errors: &mut Vec<CompilerError>,
+    warnings: &mut Vec<CompilerError>,
+) -> Option<T> {
+    match result {
+        Ok(parsed) => {
+            warnings.extend(parsed.warnings.errors);
+            Some(parsed.value)
+        }
+        Err(e) => {
+            errors.extend(e.errors);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_errors() {
+        let source = b"openapi: 3.0.0\ninfo: {title: Pets}\npaths:\n  /pets: {get: {parameters: 1}}\n  /ok: {}\n";
+        let lazy = parse_document_lazy(source).unwrap();
+        assert_eq!(lazy.info().unwrap().title, "Pets");
+        assert!(lazy.path("/ok").unwrap().is_ok());
+        let error = lazy.path("/pets").unwrap().unwrap_err();
+        assert!(error.to_string().contains("$.paths./pets"), "{}", error);
+        assert!(lazy.into_document().is_err());
+        assert!(parse_document_lazy(b"openapi").is_err());
+    }
+}
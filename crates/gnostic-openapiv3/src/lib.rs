@@ -3,12 +3,116 @@
 //! This crate provides Protocol Buffer models and parsing for OpenAPI v3 specifications.
 
 pub mod parser;
+pub mod builder;
+pub mod components;
+pub mod constructors;
+pub mod display;
 pub mod document;
+pub mod edit;
+pub mod from_proto;
+#[cfg(any(feature = "openapiv3", feature = "utoipa"))]
+pub mod interop;
+pub mod json_schema;
+pub mod lazy;
+pub mod lint;
+pub mod matcher;
+pub mod merge;
+pub mod method;
+pub mod named;
+pub mod protogen;
+pub mod resolver;
+mod to_yaml;
+pub mod transformer;
+pub mod validator;
+pub mod visitor;
+pub mod watch;
 
 /// Generated Protocol Buffer code for OpenAPI v3.
+#[allow(clippy::large_enum_variant)]
 pub mod openapi_v3 {
     include!(concat!(env!("OUT_DIR"), "/openapi.v3.rs"));
+
+    impl Eq for Any {}
+
+    impl std::hash::Hash for Any {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.value.as_ref().map(|value| (&value.type_url, &value.value)).hash(state);
+            self.yaml.hash(state);
+        }
+    }
+
+    /// Implements `Eq` and `Hash` for a message with floating-point fields,
+    /// hashing the floats by their bits. Naming every field in the pattern
+    /// keeps the hash in step with the message when fields are added.
+    macro_rules! eq_and_hash {
+        ($message:ident { floats: [$($float:ident),*], fields: [$($field:ident),*] }) => {
+            impl Eq for $message {}
+
+            impl std::hash::Hash for $message {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    let $message { $($float,)* $($field,)* } = self;
+                    $(hash_float(*$float, state);)*
+                    $($field.hash(state);)*
+                }
+            }
+        };
+        ($message:ident { oneof: $module:ident }) => {
+            impl Eq for $message {}
+
+            impl std::hash::Hash for $message {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    self.oneof.as_ref().map(std::mem::discriminant).hash(state);
+                    match &self.oneof {
+                        Some($module::Oneof::Number(number)) => hash_float(*number, state),
+                        Some($module::Oneof::Boolean(boolean)) => boolean.hash(state),
+                        Some($module::Oneof::String(string)) => string.hash(state),
+                        None => {}
+                    }
+                }
+            }
+        };
+    }
+
+    /// Hashes `value` by its bits, with negative zero as zero since the two
+    /// compare equal. NaN is not equal to itself, so it needs no care.
+    fn hash_float<H: std::hash::Hasher>(value: f64, state: &mut H) {
+        let value = if value == 0.0 { 0.0 } else { value };
+        std::hash::Hash::hash(&value.to_bits(), state);
+    }
+
+    eq_and_hash!(DefaultType { oneof: default_type });
+    eq_and_hash!(SpecificationExtension { oneof: specification_extension });
+    eq_and_hash!(Schema {
+        floats: [multiple_of, maximum, minimum],
+        fields: [
+            nullable, discriminator, read_only, write_only, xml, external_docs, example, deprecated, title,
+            exclusive_maximum, exclusive_minimum, max_length, min_length, pattern, max_items, min_items, unique_items,
+            max_properties, min_properties, required, r#enum, r#type, all_of, one_of, any_of, not, items, properties,
+            additional_properties, default, description, format, specification_extension
+        ]
+    });
 }
 
+pub use builder::{
+    DocumentBuilder, OperationBuilder, ParameterBuilder, PathItemBuilder, RequestBodyBuilder, ResponseBuilder,
+    SchemaBuilder,
+};
 pub use document::*;
+pub use from_proto::generate_openapi;
+pub use gnostic_compiler::{NamedMap, ToYaml};
+pub use json_schema::{from_json_schema, to_json_schema};
+pub use lazy::{parse_document_lazy, parse_document_lazy_with_options, LazyDocument};
+pub use lint::lint;
+pub use matcher::{PathMatch, PathMatcher};
+pub use merge::{merge, merge_with_options, MergeConflict, MergeConflicts, MergeOptions};
+pub use method::HttpMethod;
 pub use openapi_v3::Document;
+pub use protogen::{generate_messages, generate_proto, ProtoMessages, ProtoRename, ProtoRenameKind};
+pub use resolver::Resolver;
+pub use transformer::DocumentTransformer;
+pub use validator::{
+    validate, validate_node, validate_node_with_options, validate_with_options, validation_report,
+    IncrementalValidator,
+};
+pub use visitor::DocumentVisitor;
+pub use watch::{watch_document, watch_document_with_options, DocumentUpdate};
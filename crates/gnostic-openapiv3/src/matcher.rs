@@ -0,0 +1,246 @@
+//! Matching of concrete request paths against the path templates of a document.
+//!
+//! A [`PathMatcher`] finds the path item whose template, such as
+//! "/pets/{petId}", matches a request path such as "/pets/42", and extracts
+//! the values of its path parameters. As the OpenAPI specification requires,
+//! templates with literal segments take precedence over templated ones, so
+//! "/pets/mine" matches "/pets/mine" rather than "/pets/{petId}"; templates
+//! that are equally specific are tried in document order.
+
+use crate::method::HttpMethod;
+use crate::openapi_v3::{Document, Operation, PathItem};
+
+/// A request path matched to a path item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathMatch<'a> {
+    /// The path template, such as "/pets/{petId}".
+    pub path: &'a str,
+    pub path_item: &'a PathItem,
+    /// The operation for the request method, or None if the path item has
+    /// no operation for it.
+    pub operation: Option<&'a Operation>,
+    /// The values of the path parameters, percent-decoded, in template order.
+    pub parameters: Vec<(&'a str, String)>,
+}
+
+/// Matches request paths against the paths of a document.
+#[derive(Debug, Clone)]
+pub struct PathMatcher<'a> {
+    base_path: String,
+    routes: Vec<Route<'a>>,
+}
+
+#[derive(Debug, Clone)]
+struct Route<'a> {
+    path: &'a str,
+    path_item: &'a PathItem,
+    segments: Vec<Vec<Part<'a>>>,
+}
+
+/// A part of a path segment: literal text or a templated parameter.
+#[derive(Debug, Clone, PartialEq)]
+enum Part<'a> {
+    Literal(&'a str),
+    Parameter(&'a str),
+}
+
+impl<'a> PathMatcher<'a> {
+    /// Creates a matcher for the paths of a document.
+    pub fn new(doc: &'a Document) -> Self {
+        let mut routes: Vec<Route> = doc
+            .paths
+            .iter()
+            .flat_map(|paths| &paths.path)
+            .filter_map(|named| {
+                Some(Route {
+                    path: &named.name,
+                    path_item: named.value.as_ref()?,
+                    segments: split_path(&named.name).map(parse_segment).collect(),
+                })
+            })
+            .collect();
+        // Stable, so that equally specific templates keep their document order.
+        routes.sort_by_key(|route| route.segments.iter().map(|parts| specificity(parts)).collect::<Vec<_>>());
+        PathMatcher { base_path: String::new(), routes }
+    }
+
+    /// Returns the matcher with a base path, such as the path of a server URL,
+    /// which request paths must start with and which is removed before matching.
+    pub fn with_base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.base_path = base_path.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Finds the path item matching a request path, and its operation for a
+    /// method such as "GET". A query string or fragment in the path is ignored.
+    pub fn find(&self, method: &str, path: &str) -> Option<PathMatch<'a>> {
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let path = path.strip_prefix(self.base_path.as_str())?;
+        if !path.starts_with('/') {
+            return None;
+        }
+        let segments: Vec<&str> = split_path(path).collect();
+        self.routes.iter().find_map(|route| {
+            if route.segments.len() != segments.len() {
+                return None;
+            }
+            let mut parameters = Vec::new();
+            for (parts, segment) in route.segments.iter().zip(&segments) {
+                if !match_segment(parts, segment, &mut parameters) {
+                    return None;
+                }
+            }
+            Some(PathMatch {
+                path: route.path,
+                path_item: route.path_item,
+                operation: method.parse().ok().and_then(|method: HttpMethod| route.path_item.operation(method)),
+                parameters: parameters.into_iter().map(|(name, value)| (name, percent_decode(value))).collect(),
+            })
+        })
+    }
+}
+
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.strip_prefix('/').unwrap_or(path).split('/')
+}
+
+fn parse_segment(segment: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = segment;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        if start > 0 {
+            parts.push(Part::Literal(&rest[..start]));
+        }
+        parts.push(Part::Parameter(&rest[start + 1..start + end]));
+        rest = &rest[start + end + 1..];
+    }
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(Part::Literal(rest));
+    }
+    parts
+}
+
+/// Ranks a segment for precedence: literal segments first, then segments
+/// mixing literals and parameters, then segments that are a single parameter.
+fn specificity(parts: &[Part]) -> u8 {
+    match parts {
+        [Part::Literal(_)] => 0,
+        [Part::Parameter(_)] => 2,
+        _ => 1,
+    }
+}
+
+/// Matches a segment against its parts, appending parameter values; each
+/// parameter matches at least one character, and as few as possible.
+fn match_segment<'a, 'b>(parts: &[Part<'a>], segment: &'b str, parameters: &mut Vec<(&'a str, &'b str)>) -> bool {
+    match parts {
+        [] => segment.is_empty(),
+        [Part::Literal(literal), rest @ ..] => {
+            segment.strip_prefix(literal).is_some_and(|segment| match_segment(rest, segment, parameters))
+        }
+        [Part::Parameter(name), rest @ ..] => {
+            let count = parameters.len();
+            for (end, _) in segment.char_indices().skip(1).chain([(segment.len(), ' ')]) {
+                parameters.push((name, &segment[..end]));
+                if match_segment(rest, &segment[end..], parameters) {
+                    return true;
+                }
+                parameters.truncate(count);
+            }
+            false
+        }
+    }
+}
+
+/// Decodes %XX escapes, leaving invalid escapes and invalid UTF-8 as they are.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parse_document;
+
+    fn document() -> Document {
+        parse_document(
+            br#"
+openapi: 3.0.3
+info: {title: Pets, version: 1.0.0}
+paths:
+  /pets/{petId}:
+    get: {operationId: getPet, responses: {}}
+    delete: {operationId: deletePet, responses: {}}
+  /pets/mine:
+    get: {operationId: getMyPets, responses: {}}
+  /pets/{petId}/photos/{name}.{ext}:
+    get: {operationId: getPhoto, responses: {}}
+  /:
+    get: {operationId: root, responses: {}}
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_find() {
+        let doc = document();
+        let matcher = PathMatcher::new(&doc);
+
+        let found = matcher.find("GET", "/pets/42?verbose=true").unwrap();
+        assert_eq!(found.path, "/pets/{petId}");
+        assert_eq!(found.operation.unwrap().operation_id, "getPet");
+        assert_eq!(found.parameters, [("petId", "42".to_string())]);
+
+        assert_eq!(matcher.find("delete", "/pets/42").unwrap().operation.unwrap().operation_id, "deletePet");
+        assert_eq!(matcher.find("GET", "/pets/mine").unwrap().operation.unwrap().operation_id, "getMyPets");
+        assert_eq!(matcher.find("GET", "/").unwrap().operation.unwrap().operation_id, "root");
+        assert!(matcher.find("PUT", "/pets/42").unwrap().operation.is_none());
+        assert!(matcher.find("GET", "/pets").is_none());
+        assert!(matcher.find("GET", "/pets/42/photos").is_none());
+
+        let found = matcher.find("GET", "/pets/a%20b/photos/cat.v2.png").unwrap();
+        assert_eq!(
+            found.parameters,
+            [("petId", "a b".to_string()), ("name", "cat".to_string()), ("ext", "v2.png".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_find_with_base_path() {
+        let doc = document();
+        let matcher = PathMatcher::new(&doc).with_base_path("/api/v3/");
+        assert_eq!(matcher.find("GET", "/api/v3/pets/mine").unwrap().path, "/pets/mine");
+        assert!(matcher.find("GET", "/pets/mine").is_none());
+        assert!(matcher.find("GET", "/api/v3pets/mine").is_none());
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%2Fb%zz%"), "a/b%zz%");
+        assert_eq!(percent_decode("%E2%9C%93"), "\u{2713}");
+        assert_eq!(percent_decode("%FF"), "%FF");
+    }
+}
@@ -0,0 +1,381 @@
+//! Merging of partial documents into one.
+//!
+//! APIs are often described by several documents, such as one per team,
+//! that [`merge`] combines into one: the paths, components, tags, servers
+//! and security requirements of every document, with the info of the first.
+//! Definitions that several documents share are kept once if they are equal
+//! and reported as conflicts otherwise. Prefixing the component names of
+//! each document with [`MergeOptions::with_component_prefixes`] avoids
+//! conflicts between components that only share a name.
+
+use std::fmt;
+
+use gnostic_compiler::escape_json_pointer_segment;
+use gnostic_compiler::writer::Named;
+
+use crate::openapi_v3::*;
+use crate::transformer::RENAMEABLE_SECTIONS;
+
+/// Options for merging documents.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// Prefixes for the names of the components of each document, by
+    /// position; documents without one keep their names.
+    pub component_prefixes: Vec<String>,
+}
+
+impl MergeOptions {
+    /// Creates options that merge documents as they are.
+    pub fn new() -> Self {
+        MergeOptions::default()
+    }
+
+    /// Returns the options with prefixes for the component names of each
+    /// document, such as `["Billing", "Users"]`. References to the renamed
+    /// components are updated.
+    pub fn with_component_prefixes<S: Into<String>>(mut self, prefixes: impl IntoIterator<Item = S>) -> Self {
+        self.component_prefixes = prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// A definition that two documents being merged define differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// JSON pointer to the definition, such as "/components/schemas/Pet".
+    pub pointer: String,
+    /// The position of the document whose definition collides with that of
+    /// an earlier one.
+    pub document: usize,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "document {} redefines #{}", self.document, self.pointer)
+    }
+}
+
+/// The conflicts that prevented documents from being merged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeConflicts {
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl fmt::Display for MergeConflicts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, conflict) in self.conflicts.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", conflict)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MergeConflicts {}
+
+/// Merges documents into one, reporting the definitions they disagree on.
+pub fn merge(docs: &[Document]) -> Result<Document, MergeConflicts> {
+    merge_with_options(docs, &MergeOptions::default())
+}
+
+/// Merges documents into one with the given options.
+pub fn merge_with_options(docs: &[Document], options: &MergeOptions) -> Result<Document, MergeConflicts> {
+    let mut merger = Merger { merged: Document::default(), document: 0, conflicts: Vec::new() };
+    for (index, doc) in docs.iter().enumerate() {
+        merger.document = index;
+        match options.component_prefixes.get(index).filter(|prefix| !prefix.is_empty()) {
+            Some(prefix) => {
+                let mut doc = doc.clone();
+                merger.prefix_components(&mut doc, prefix);
+                merger.add(&doc);
+            }
+            None => merger.add(doc),
+        }
+    }
+    if merger.conflicts.is_empty() {
+        Ok(merger.merged)
+    } else {
+        Err(MergeConflicts { conflicts: merger.conflicts })
+    }
+}
+
+struct Merger {
+    merged: Document,
+    /// The position of the document being added.
+    document: usize,
+    conflicts: Vec<MergeConflict>,
+}
+
+impl Merger {
+    fn conflict(&mut self, pointer: String) {
+        self.conflicts.push(MergeConflict { pointer, document: self.document });
+    }
+
+    fn prefix_components(&mut self, doc: &mut Document, prefix: &str) {
+        for section in RENAMEABLE_SECTIONS {
+            for name in component_names(doc, section) {
+                let prefixed = format!("{}{}", prefix, name);
+                if !doc.rename_component(section, &name, &prefixed) {
+                    self.conflict(format!("/components/{}/{}", section, escape_json_pointer_segment(&prefixed)));
+                }
+            }
+        }
+    }
+
+    fn add(&mut self, doc: &Document) {
+        let merged = &mut self.merged;
+        if merged.openapi.is_empty() {
+            merged.openapi = doc.openapi.clone();
+        }
+        if merged.info.is_none() {
+            merged.info = doc.info.clone();
+        }
+        if merged.external_docs.is_none() {
+            merged.external_docs = doc.external_docs.clone();
+        }
+        extend_unique(&mut merged.servers, &doc.servers);
+        extend_unique(&mut merged.security, &doc.security);
+        self.add_tags(&doc.tags);
+        let mut extensions = std::mem::take(&mut self.merged.specification_extension);
+        self.add_named(&mut extensions, &doc.specification_extension, "");
+        self.merged.specification_extension = extensions;
+
+        if let Some(paths) = &doc.paths {
+            let mut merged_paths = self.merged.paths.take().unwrap_or_default();
+            for named in &paths.path {
+                let pointer = format!("/paths/{}", escape_json_pointer_segment(&named.name));
+                match merged_paths.path.iter_mut().find(|existing| existing.name == named.name) {
+                    Some(existing) => match (&mut existing.value, &named.value) {
+                        (Some(existing), Some(item)) => self.add_path_item(existing, item, &pointer),
+                        (existing, item) => {
+                            if existing.is_none() {
+                                *existing = item.clone();
+                            }
+                        }
+                    },
+                    None => merged_paths.path.push(named.clone()),
+                }
+            }
+            let mut extensions = std::mem::take(&mut merged_paths.specification_extension);
+            self.add_named(&mut extensions, &paths.specification_extension, "/paths");
+            merged_paths.specification_extension = extensions;
+            self.merged.paths = Some(merged_paths);
+        }
+
+        if let Some(components) = &doc.components {
+            let mut merged_components = self.merged.components.take().unwrap_or_default();
+            self.add_components(&mut merged_components, components);
+            self.merged.components = Some(merged_components);
+        }
+    }
+
+    /// Adds tags that are not yet defined; a tag defined again must have the
+    /// same description, unless one of them has none.
+    fn add_tags(&mut self, tags: &[Tag]) {
+        for tag in tags {
+            match self.merged.tags.iter().position(|existing| existing.name == tag.name) {
+                Some(i) if self.merged.tags[i] == *tag || tag.description.is_empty() => {}
+                Some(i) if self.merged.tags[i].description.is_empty() => self.merged.tags[i] = tag.clone(),
+                Some(i) => self.conflict(format!("/tags/{}", i)),
+                None => self.merged.tags.push(tag.clone()),
+            }
+        }
+    }
+
+    /// Adds the operations of a path item to those of the same path; an
+    /// operation defined again must be equal.
+    fn add_path_item(&mut self, existing: &mut PathItem, item: &PathItem, pointer: &str) {
+        if existing.summary.is_empty() {
+            existing.summary = item.summary.clone();
+        }
+        if existing.description.is_empty() {
+            existing.description = item.description.clone();
+        }
+        extend_unique(&mut existing.servers, &item.servers);
+        if existing.parameters.is_empty() {
+            existing.parameters = item.parameters.clone();
+        } else if !item.parameters.is_empty() && existing.parameters != item.parameters {
+            self.conflict(format!("{}/parameters", pointer));
+        }
+        for (method, operation) in item.iter_operations() {
+            match existing.operation(method) {
+                None => {
+                    existing.set_operation(method, operation.clone());
+                }
+                Some(existing) if existing != operation => self.conflict(format!("{}/{}", pointer, method)),
+                Some(_) => {}
+            }
+        }
+        self.add_named(&mut existing.specification_extension, &item.specification_extension, pointer);
+    }
+
+    fn add_components(&mut self, merged: &mut Components, components: &Components) {
+        macro_rules! add_section {
+            ($field:ident, $section:literal) => {
+                if let Some(section) = &components.$field {
+                    let target = merged.$field.get_or_insert_with(Default::default);
+                    let pointer = concat!("/components/", $section);
+                    self.add_named(&mut target.additional_properties, &section.additional_properties, pointer);
+                }
+            };
+        }
+        add_section!(schemas, "schemas");
+        add_section!(responses, "responses");
+        add_section!(parameters, "parameters");
+        add_section!(examples, "examples");
+        add_section!(request_bodies, "requestBodies");
+        add_section!(headers, "headers");
+        add_section!(security_schemes, "securitySchemes");
+        add_section!(links, "links");
+        add_section!(callbacks, "callbacks");
+        self.add_named(&mut merged.specification_extension, &components.specification_extension, "/components");
+    }
+
+    /// Adds named entries that are not yet defined; an entry defined again
+    /// must be equal.
+    fn add_named<N: Named + Clone + PartialEq>(&mut self, merged: &mut Vec<N>, entries: &[N], pointer: &str) {
+        for entry in entries {
+            match merged.iter().find(|existing| existing.name() == entry.name()) {
+                Some(existing) if existing != entry => {
+                    self.conflict(format!("{}/{}", pointer, escape_json_pointer_segment(entry.name())));
+                }
+                Some(_) => {}
+                None => merged.push(entry.clone()),
+            }
+        }
+    }
+}
+
+/// Returns the names of the components in a section, such as "schemas".
+fn component_names(doc: &Document, section: &str) -> Vec<String> {
+    fn names<N: Named>(entries: Option<&Vec<N>>) -> Vec<String> {
+        entries.into_iter().flatten().map(|entry| entry.name().to_string()).collect()
+    }
+    let Some(components) = &doc.components else {
+        return Vec::new();
+    };
+    match section {
+        "schemas" => names(components.schemas.as_ref().map(|s| &s.additional_properties)),
+        "responses" => names(components.responses.as_ref().map(|s| &s.additional_properties)),
+        "parameters" => names(components.parameters.as_ref().map(|s| &s.additional_properties)),
+        "requestBodies" => names(components.request_bodies.as_ref().map(|s| &s.additional_properties)),
+        "headers" => names(components.headers.as_ref().map(|s| &s.additional_properties)),
+        "callbacks" => names(components.callbacks.as_ref().map(|s| &s.additional_properties)),
+        _ => Vec::new(),
+    }
+}
+
+fn extend_unique<T: Clone + PartialEq>(merged: &mut Vec<T>, values: &[T]) {
+    for value in values {
+        if !merged.contains(value) {
+            merged.push(value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_document;
+
+    fn doc(yaml: &str) -> Document {
+        parse_document(yaml.as_bytes()).unwrap()
+    }
+
+    const PETS: &str = r##"
+openapi: 3.0.3
+info: {title: Pets, version: 1.0.0}
+tags:
+  - {name: pets, description: Pets.}
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: Pets.
+          content:
+            application/json: {schema: {$ref: "#/components/schemas/Pet"}}
+components:
+  schemas:
+    Pet: {type: object, properties: {name: {type: string}}}
+    Error: {type: object}
+"##;
+
+    const OWNERS: &str = r##"
+openapi: 3.0.3
+info: {title: Owners, version: 2.0.0}
+tags:
+  - {name: pets}
+  - {name: owners}
+paths:
+  /pets:
+    post:
+      operationId: addPet
+      responses: {}
+  /owners:
+    get:
+      operationId: listOwners
+      responses:
+        "200":
+          description: Owners.
+          content:
+            application/json: {schema: {$ref: "#/components/schemas/Owner"}}
+components:
+  schemas:
+    Owner: {type: object}
+    Error: {type: object}
+"##;
+
+    #[test]
+    fn test_merge() {
+        let merged = merge(&[doc(PETS), doc(OWNERS)]).unwrap();
+        assert_eq!(merged.info.as_ref().unwrap().title, "Pets");
+        let paths = merged.paths.as_ref().unwrap();
+        assert_eq!(paths.path.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["/pets", "/owners"]);
+        let pets = paths.path_item("/pets").unwrap();
+        assert!(pets.get.is_some() && pets.post.is_some());
+        let components = merged.components.as_ref().unwrap();
+        assert!(components.schema("Pet").is_some() && components.schema("Owner").is_some());
+        assert_eq!(components.schemas.as_ref().unwrap().additional_properties.len(), 3);
+        let tags: Vec<(&str, &str)> = merged.tags.iter().map(|t| (t.name.as_str(), t.description.as_str())).collect();
+        assert_eq!(tags, [("pets", "Pets."), ("owners", "")]);
+        assert_eq!(merge(&[]).unwrap(), Document::default());
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let other = PETS.replace("{name: {type: string}}", "{name: {type: integer}}").replace("Pets.}", "All pets.}");
+        let conflicts = merge(&[doc(PETS), doc(OWNERS), doc(&other)]).unwrap_err();
+        assert_eq!(
+            conflicts.to_string(),
+            ["document 2 redefines #/tags/0", "document 2 redefines #/components/schemas/Pet"].join("\n")
+        );
+
+        let conflicts = merge(&[doc(PETS), doc(&PETS.replace("listPets", "getPets"))]).unwrap_err();
+        assert_eq!(conflicts.conflicts, [MergeConflict { pointer: "/paths/~1pets/get".to_string(), document: 1 }]);
+    }
+
+    #[test]
+    fn test_merge_prefixes() {
+        let other = PETS.replace("{name: {type: string}}", "{name: {type: integer}}").replace("/pets", "/animals");
+        let options = MergeOptions::new().with_component_prefixes(["", "Zoo"]);
+        let merged = merge_with_options(&[doc(PETS), doc(&other)], &options).unwrap();
+        let components = merged.components.as_ref().unwrap();
+        assert!(components.schema("Pet").is_some() && components.schema("ZooPet").is_some());
+        assert!(components.schema("ZooError").is_some());
+
+        let animals = merged.paths.as_ref().unwrap().path_item("/animals").unwrap();
+        let response = &animals.get.as_ref().unwrap().responses.as_ref().unwrap().response_or_reference[0];
+        let Some(response_or_reference::Oneof::Response(response)) = &response.value.as_ref().unwrap().oneof else {
+            panic!("expected a response");
+        };
+        let schema = response.content.as_ref().unwrap().additional_properties[0].value.as_ref().unwrap();
+        let Some(schema_or_reference::Oneof::Reference(reference)) = &schema.schema.as_ref().unwrap().oneof else {
+            panic!("expected a reference");
+        };
+        assert_eq!(reference.r#ref, "#/components/schemas/ZooPet");
+    }
+}
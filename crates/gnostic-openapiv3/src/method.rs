@@ -0,0 +1,176 @@
+//! HTTP methods and the operations of path items.
+//!
+//! A path item stores its operations in one field per method; [`HttpMethod`]
+//! names those fields so that callers can get, set and iterate operations
+//! without matching on every method themselves.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::openapi_v3::*;
+
+/// An HTTP method that a path item can have an operation for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HttpMethod {
+    Get,
+    Put,
+    Post,
+    Delete,
+    Options,
+    Head,
+    Patch,
+    Trace,
+}
+
+impl HttpMethod {
+    /// All methods, in the order their fields appear in path items.
+    pub const ALL: [HttpMethod; 8] = [
+        HttpMethod::Get,
+        HttpMethod::Put,
+        HttpMethod::Post,
+        HttpMethod::Delete,
+        HttpMethod::Options,
+        HttpMethod::Head,
+        HttpMethod::Patch,
+        HttpMethod::Trace,
+    ];
+
+    /// Returns the name of the method's field in path items, such as "get".
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HttpMethod::Get => "get",
+            HttpMethod::Put => "put",
+            HttpMethod::Post => "post",
+            HttpMethod::Delete => "delete",
+            HttpMethod::Options => "options",
+            HttpMethod::Head => "head",
+            HttpMethod::Patch => "patch",
+            HttpMethod::Trace => "trace",
+        }
+    }
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = String;
+
+    /// Parses a method in any case, such as "get" or "GET".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HttpMethod::ALL
+            .into_iter()
+            .find(|method| method.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown HTTP method: {}", s))
+    }
+}
+
+impl PathItem {
+    /// Returns the operation for a method.
+    pub fn operation(&self, method: HttpMethod) -> Option<&Operation> {
+        self.operation_field(method).as_deref()
+    }
+
+    /// Returns the operation for a method for modification.
+    pub fn operation_mut(&mut self, method: HttpMethod) -> Option<&mut Operation> {
+        self.operation_field_mut(method).as_deref_mut()
+    }
+
+    /// Sets the operation for a method, returning the one it replaces.
+    pub fn set_operation(&mut self, method: HttpMethod, operation: Operation) -> Option<Operation> {
+        self.operation_field_mut(method).replace(Box::new(operation)).map(|operation| *operation)
+    }
+
+    /// Removes the operation for a method, returning it.
+    pub fn remove_operation(&mut self, method: HttpMethod) -> Option<Operation> {
+        self.operation_field_mut(method).take().map(|operation| *operation)
+    }
+
+    /// Returns the methods and operations of the path item, in the order of
+    /// [`HttpMethod::ALL`].
+    pub fn iter_operations(&self) -> impl Iterator<Item = (HttpMethod, &Operation)> {
+        HttpMethod::ALL.into_iter().filter_map(|method| Some((method, self.operation(method)?)))
+    }
+
+    /// Returns the methods and operations of the path item for modification.
+    pub fn iter_operations_mut(&mut self) -> impl Iterator<Item = (HttpMethod, &mut Operation)> {
+        let operations = [
+            &mut self.get,
+            &mut self.put,
+            &mut self.post,
+            &mut self.delete,
+            &mut self.options,
+            &mut self.head,
+            &mut self.patch,
+            &mut self.trace,
+        ];
+        HttpMethod::ALL
+            .into_iter()
+            .zip(operations)
+            .filter_map(|(method, operation)| Some((method, operation.as_deref_mut()?)))
+    }
+
+    fn operation_field(&self, method: HttpMethod) -> &Option<Box<Operation>> {
+        match method {
+            HttpMethod::Get => &self.get,
+            HttpMethod::Put => &self.put,
+            HttpMethod::Post => &self.post,
+            HttpMethod::Delete => &self.delete,
+            HttpMethod::Options => &self.options,
+            HttpMethod::Head => &self.head,
+            HttpMethod::Patch => &self.patch,
+            HttpMethod::Trace => &self.trace,
+        }
+    }
+
+    fn operation_field_mut(&mut self, method: HttpMethod) -> &mut Option<Box<Operation>> {
+        match method {
+            HttpMethod::Get => &mut self.get,
+            HttpMethod::Put => &mut self.put,
+            HttpMethod::Post => &mut self.post,
+            HttpMethod::Delete => &mut self.delete,
+            HttpMethod::Options => &mut self.options,
+            HttpMethod::Head => &mut self.head,
+            HttpMethod::Patch => &mut self.patch,
+            HttpMethod::Trace => &mut self.trace,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_method() {
+        assert_eq!("GET".parse::<HttpMethod>(), Ok(HttpMethod::Get));
+        assert_eq!("trace".parse::<HttpMethod>(), Ok(HttpMethod::Trace));
+        assert_eq!("query".parse::<HttpMethod>(), Err("unknown HTTP method: query".to_string()));
+        assert_eq!(HttpMethod::Options.to_string(), "options");
+    }
+
+    #[test]
+    fn test_path_item_operations() {
+        let operation = |id: &str| Operation { operation_id: id.to_string(), ..Operation::default() };
+        let mut path_item = PathItem::default();
+        assert_eq!(path_item.set_operation(HttpMethod::Post, operation("create")), None);
+        assert_eq!(path_item.set_operation(HttpMethod::Get, operation("list")), None);
+        assert_eq!(path_item.set_operation(HttpMethod::Get, operation("read")), Some(operation("list")));
+        assert_eq!(path_item.get.as_deref(), Some(&operation("read")));
+        assert_eq!(path_item.operation(HttpMethod::Post), Some(&operation("create")));
+        assert_eq!(path_item.operation(HttpMethod::Put), None);
+
+        for (method, operation) in path_item.iter_operations_mut() {
+            operation.summary = method.to_string();
+        }
+        let summaries: Vec<(HttpMethod, &str)> =
+            path_item.iter_operations().map(|(method, operation)| (method, operation.summary.as_str())).collect();
+        assert_eq!(summaries, [(HttpMethod::Get, "get"), (HttpMethod::Post, "post")]);
+
+        assert_eq!(path_item.remove_operation(HttpMethod::Get).map(|op| op.operation_id), Some("read".to_string()));
+        assert_eq!(path_item.iter_operations().count(), 1);
+    }
+}
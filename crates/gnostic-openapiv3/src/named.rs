@@ -0,0 +1,144 @@
+//! Map views of the named collections of documents.
+//!
+//! Mappings such as [`Paths`] and [`SchemasOrReferences`] are vectors of
+//! named entries; they implement [`NamedMap`] for lookups and updates by
+//! name, and convert from [`IndexMap`]s.
+
+use gnostic_compiler::writer::Named;
+use gnostic_compiler::{NamedEntry, NamedMap};
+use indexmap::IndexMap;
+
+use crate::openapi_v3::*;
+
+/// Implements [`NamedEntry`] for entries with a `name` and an optional `value`.
+macro_rules! named_entry {
+    ($($entry:ident),* $(,)?) => {
+        $(
+            impl NamedEntry for $entry {
+                fn new(name: String, value: Self::Value) -> Self {
+                    $entry { name, value: Some(value) }
+                }
+
+                fn value_mut(&mut self) -> Option<&mut Self::Value> {
+                    self.value.as_mut()
+                }
+
+                fn into_value(self) -> Option<Self::Value> {
+                    self.value
+                }
+            }
+        )*
+    };
+}
+
+/// Implements [`NamedMap`] and the conversion from an [`IndexMap`] for
+/// collections whose entries are in the field `field`.
+macro_rules! named_map {
+    ($($map:ident.$field:ident: $entry:ident),* $(,)?) => {
+        $(
+            impl NamedMap for $map {
+                type Entry = $entry;
+
+                fn entries(&self) -> &[$entry] {
+                    &self.$field
+                }
+
+                fn entries_mut(&mut self) -> &mut Vec<$entry> {
+                    &mut self.$field
+                }
+            }
+
+            impl From<IndexMap<String, <$entry as Named>::Value>> for $map {
+                // Most collections have no fields besides their entries.
+                #[allow(clippy::needless_update)]
+                fn from(map: IndexMap<String, <$entry as Named>::Value>) -> Self {
+                    let $field = map.into_iter().map(|(name, value)| $entry::new(name, value)).collect();
+                    $map { $field, ..$map::default() }
+                }
+            }
+        )*
+    };
+}
+
+named_entry!(
+    NamedAny,
+    NamedCallbackOrReference,
+    NamedEncoding,
+    NamedExampleOrReference,
+    NamedHeaderOrReference,
+    NamedLinkOrReference,
+    NamedMediaType,
+    NamedParameterOrReference,
+    NamedPathItem,
+    NamedRequestBodyOrReference,
+    NamedResponseOrReference,
+    NamedSchemaOrReference,
+    NamedSecuritySchemeOrReference,
+    NamedServerVariable,
+    NamedStringArray,
+);
+
+impl NamedEntry for NamedString {
+    fn new(name: String, value: String) -> Self {
+        NamedString { name, value }
+    }
+
+    fn value_mut(&mut self) -> Option<&mut String> {
+        Some(&mut self.value)
+    }
+
+    fn into_value(self) -> Option<String> {
+        Some(self.value)
+    }
+}
+
+named_map!(
+    Callback.path: NamedPathItem,
+    CallbacksOrReferences.additional_properties: NamedCallbackOrReference,
+    Encodings.additional_properties: NamedEncoding,
+    ExamplesOrReferences.additional_properties: NamedExampleOrReference,
+    Expression.additional_properties: NamedAny,
+    HeadersOrReferences.additional_properties: NamedHeaderOrReference,
+    LinksOrReferences.additional_properties: NamedLinkOrReference,
+    MediaTypes.additional_properties: NamedMediaType,
+    Object.additional_properties: NamedAny,
+    ParametersOrReferences.additional_properties: NamedParameterOrReference,
+    Paths.path: NamedPathItem,
+    Properties.additional_properties: NamedSchemaOrReference,
+    RequestBodiesOrReferences.additional_properties: NamedRequestBodyOrReference,
+    Responses.response_or_reference: NamedResponseOrReference,
+    ResponsesOrReferences.additional_properties: NamedResponseOrReference,
+    SchemasOrReferences.additional_properties: NamedSchemaOrReference,
+    SecurityRequirement.additional_properties: NamedStringArray,
+    SecuritySchemesOrReferences.additional_properties: NamedSecuritySchemeOrReference,
+    ServerVariables.additional_properties: NamedServerVariable,
+    Strings.additional_properties: NamedString,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_map() {
+        let map: IndexMap<String, SchemaOrReference> = [
+            ("Pet".to_string(), SchemaOrReference::schema(Schema::object())),
+            ("Pets".to_string(), Schema::reference("#/components/schemas/Pet")),
+        ]
+        .into_iter()
+        .collect();
+        let mut schemas = SchemasOrReferences::from(map.clone());
+        assert_eq!(schemas.names(), ["Pet", "Pets"]);
+        assert_eq!(schemas.get("Pet"), map.get("Pet"));
+
+        schemas.insert("Error", SchemaOrReference::schema(Schema::string()));
+        assert!(schemas.remove("Pets").is_some());
+        assert_eq!(schemas.as_map().keys().copied().collect::<Vec<_>>(), ["Pet", "Error"]);
+        assert_eq!(schemas.into_map()["Error"], SchemaOrReference::schema(Schema::string()));
+
+        let mut paths = Paths::default();
+        paths.insert("/pets", PathItem::default());
+        paths.get_mut("/pets").unwrap().summary = "Pets".to_string();
+        assert_eq!(paths.path_item("/pets").unwrap().summary, "Pets");
+    }
+}
@@ -1,11 +1,12 @@
 //! OpenAPI v3 YAML to Protocol Buffer parser.
 
-use gnostic_compiler::{Context, CompilerError, ErrorGroup};
-use gnostic_compiler::{map_value_for_key, string_for_scalar_node, bool_for_scalar_node,
-                       string_array_for_sequence_node, is_mapping, iter_map};
+use gnostic_compiler::{Context, CompilerError, ErrorCode, ErrorGroup, RefSiblings, check_depth, handle_extension};
+use gnostic_compiler::{map_value_for_key, KeyIndex, string_for_scalar_node, bool_for_scalar_node,
+                       string_array_for_sequence_node, is_mapping, iter_map, marshal};
 use serde_yaml::Value as Yaml;
 use std::sync::Arc;
 
+use crate::method::HttpMethod;
 use crate::openapi_v3::*;
 
 /// Parser for converting YAML nodes to OpenAPI v3 Protocol Buffer types.
@@ -14,83 +15,148 @@ pub struct Parser;
 impl Parser {
     /// Parses a Document from a YAML node.
     pub fn parse_document(node: &Yaml, context: &Arc<Context>) -> Result<Document, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut doc = Document::default();
 
         if !is_mapping(node) {
-            errors.push(CompilerError::new(context, format!("expected mapping, got {:?}", node)));
+            errors.push(CompilerError::new_with_code(context, ErrorCode::InvalidType, format!("expected mapping, got {:?}", node)));
             return Err(ErrorGroup::new(errors));
         }
 
         // Parse openapi version
-        if let Some(v) = map_value_for_key(node, "openapi") {
+        if let Some(v) = index.get("openapi") {
             if let Some(s) = string_for_scalar_node(v) {
                 doc.openapi = s;
             }
         }
+        let context = &with_ref_siblings(context, &doc.openapi);
 
         // Parse info
-        if let Some(v) = map_value_for_key(node, "info") {
+        if let Some(v) = index.get("info") {
             let child_ctx = Arc::new(context.child("info"));
             match Self::parse_info(v, &child_ctx) {
                 Ok(info) => doc.info = Some(info),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
         // Parse servers
-        if let Some(v) = map_value_for_key(node, "servers") {
+        if let Some(v) = index.get("servers") {
             if let Yaml::Sequence(arr) = v {
                 for (i, item) in arr.iter().enumerate() {
                     let child_ctx = Arc::new(context.child(format!("servers[{}]", i)));
                     match Self::parse_server(item, &child_ctx) {
                         Ok(server) => doc.servers.push(server),
-                        Err(e) => errors.extend(e.errors),
+                        Err(e) => {
+                            errors.extend(e.errors);
+                            if context.should_stop(&errors) {
+                                return Err(ErrorGroup::new(errors));
+                            }
+                        }
                     }
                 }
+            } else if let Err(e) = Arc::new(context.child("servers"))
+                .report_recoverable(ErrorCode::InvalidType, "servers must be an array")
+            {
+                errors.push(e);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
+                }
             }
         }
 
         // Parse paths
-        if let Some(v) = map_value_for_key(node, "paths") {
+        if let Some(v) = index.get("paths") {
             let child_ctx = Arc::new(context.child("paths"));
             match Self::parse_paths(v, &child_ctx) {
                 Ok(paths) => doc.paths = Some(paths),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
         // Parse components
-        if let Some(v) = map_value_for_key(node, "components") {
+        if let Some(v) = index.get("components") {
             let child_ctx = Arc::new(context.child("components"));
             match Self::parse_components(v, &child_ctx) {
                 Ok(components) => doc.components = Some(components),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse security
+        if let Some(v) = index.get("security") {
+            let child_ctx = Arc::new(context.child("security"));
+            match Self::parse_security_requirements(v, &child_ctx) {
+                Ok(security) => doc.security = security,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
         // Parse tags
-        if let Some(v) = map_value_for_key(node, "tags") {
+        if let Some(v) = index.get("tags") {
             if let Yaml::Sequence(arr) = v {
                 for (i, item) in arr.iter().enumerate() {
                     let child_ctx = Arc::new(context.child(format!("tags[{}]", i)));
                     match Self::parse_tag(item, &child_ctx) {
                         Ok(tag) => doc.tags.push(tag),
-                        Err(e) => errors.extend(e.errors),
+                        Err(e) => {
+                            errors.extend(e.errors);
+                            if context.should_stop(&errors) {
+                                return Err(ErrorGroup::new(errors));
+                            }
+                        }
                     }
                 }
+            } else if let Err(e) = Arc::new(context.child("tags"))
+                .report_recoverable(ErrorCode::InvalidType, "tags must be an array")
+            {
+                errors.push(e);
+                if context.should_stop(&errors) {
+                    return Err(ErrorGroup::new(errors));
+                }
             }
         }
 
         // Parse externalDocs
-        if let Some(v) = map_value_for_key(node, "externalDocs") {
+        if let Some(v) = index.get("externalDocs") {
             let child_ctx = Arc::new(context.child("externalDocs"));
             match Self::parse_external_docs(v, &child_ctx) {
-                Ok(external_docs) => doc.external_docs = Some(external_docs),
-                Err(e) => errors.extend(e.errors),
+                Ok(external_docs) => doc.external_docs = Some(Box::new(external_docs)),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
+        match Self::parse_specification_extensions(node, context) {
+            Ok(extensions) => doc.specification_extension = extensions,
+            Err(e) => errors.extend(e.errors),
+        }
+
         if errors.is_empty() {
             Ok(doc)
         } else {
@@ -100,49 +166,65 @@ impl Parser {
 
     /// Parses Info from a YAML node.
     pub fn parse_info(node: &Yaml, context: &Arc<Context>) -> Result<Info, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut info = Info::default();
 
-        if let Some(v) = map_value_for_key(node, "title") {
+        if let Some(v) = index.get("title") {
             if let Some(s) = string_for_scalar_node(v) {
                 info.title = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "description") {
+        if let Some(v) = index.get("description") {
             if let Some(s) = string_for_scalar_node(v) {
                 info.description = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "termsOfService") {
+        if let Some(v) = index.get("termsOfService") {
             if let Some(s) = string_for_scalar_node(v) {
                 info.terms_of_service = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "contact") {
+        if let Some(v) = index.get("contact") {
             let child_ctx = Arc::new(context.child("contact"));
             match Self::parse_contact(v, &child_ctx) {
                 Ok(contact) => info.contact = Some(contact),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "license") {
+        if let Some(v) = index.get("license") {
             let child_ctx = Arc::new(context.child("license"));
             match Self::parse_license(v, &child_ctx) {
                 Ok(license) => info.license = Some(license),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "version") {
+        if let Some(v) = index.get("version") {
             if let Some(s) = string_for_scalar_node(v) {
                 info.version = s;
             }
         }
 
+        match Self::parse_specification_extensions(node, context) {
+            Ok(extensions) => info.specification_extension = extensions,
+            Err(e) => errors.extend(e.errors),
+        }
+
         if errors.is_empty() {
             Ok(info)
         } else {
@@ -151,32 +233,34 @@ impl Parser {
     }
 
     /// Parses Contact from a YAML node.
-    pub fn parse_contact(node: &Yaml, _context: &Arc<Context>) -> Result<Contact, ErrorGroup> {
+    pub fn parse_contact(node: &Yaml, context: &Arc<Context>) -> Result<Contact, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut contact = Contact::default();
 
-        if let Some(v) = map_value_for_key(node, "name") {
+        if let Some(v) = index.get("name") {
             if let Some(s) = string_for_scalar_node(v) {
                 contact.name = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "url") {
+        if let Some(v) = index.get("url") {
             if let Some(s) = string_for_scalar_node(v) {
                 contact.url = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "email") {
+        if let Some(v) = index.get("email") {
             if let Some(s) = string_for_scalar_node(v) {
                 contact.email = s;
             }
         }
 
+        contact.specification_extension = Self::parse_specification_extensions(node, context)?;
         Ok(contact)
     }
 
     /// Parses License from a YAML node.
-    pub fn parse_license(node: &Yaml, _context: &Arc<Context>) -> Result<License, ErrorGroup> {
+    pub fn parse_license(node: &Yaml, context: &Arc<Context>) -> Result<License, ErrorGroup> {
         let mut license = License::default();
 
         if let Some(v) = map_value_for_key(node, "name") {
@@ -191,11 +275,12 @@ impl Parser {
             }
         }
 
+        license.specification_extension = Self::parse_specification_extensions(node, context)?;
         Ok(license)
     }
 
     /// Parses Server from a YAML node.
-    pub fn parse_server(node: &Yaml, _context: &Arc<Context>) -> Result<Server, ErrorGroup> {
+    pub fn parse_server(node: &Yaml, context: &Arc<Context>) -> Result<Server, ErrorGroup> {
         let mut server = Server::default();
 
         if let Some(v) = map_value_for_key(node, "url") {
@@ -210,16 +295,106 @@ impl Parser {
             }
         }
 
+        if let Some(v) = map_value_for_key(node, "variables") {
+            let child_ctx = Arc::new(context.child("variables"));
+            server.variables = Some(Self::parse_server_variables(v, &child_ctx)?);
+        }
+
+        server.specification_extension = Self::parse_specification_extensions(node, context)?;
         Ok(server)
     }
 
+    /// Parses the servers of a path item or operation from a YAML node.
+    pub fn parse_servers(node: &Yaml, context: &Arc<Context>) -> Result<Vec<Server>, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut servers = Vec::new();
+
+        let Yaml::Sequence(items) = node else {
+            context
+                .report_recoverable(ErrorCode::InvalidType, "servers must be an array")
+                .map_err(|e| ErrorGroup::new(vec![e]))?;
+            return Ok(servers);
+        };
+        for (i, item) in items.iter().enumerate() {
+            let child_ctx = Arc::new(context.child(i.to_string()));
+            match Self::parse_server(item, &child_ctx) {
+                Ok(server) => servers.push(server),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(servers)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses ServerVariables from a YAML node.
+    pub fn parse_server_variables(node: &Yaml, context: &Arc<Context>) -> Result<ServerVariables, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut variables = ServerVariables::default();
+
+        iter_map(node, |name, value| {
+            if context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(name));
+            match Self::parse_server_variable(value, &child_ctx) {
+                Ok(variable) => {
+                    variables.additional_properties.push(NamedServerVariable {
+                        name: name.to_string(),
+                        value: Some(variable),
+                    });
+                }
+                Err(e) => errors.extend(e.errors),
+            }
+        });
+
+        if errors.is_empty() {
+            Ok(variables)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses ServerVariable from a YAML node.
+    pub fn parse_server_variable(node: &Yaml, context: &Arc<Context>) -> Result<ServerVariable, ErrorGroup> {
+        let index = KeyIndex::new(node);
+        let mut variable = ServerVariable::default();
+
+        if let Some(v) = index.get("enum") {
+            variable.r#enum = string_array_for_sequence_node(v);
+        }
+
+        for (key, field) in [("default", &mut variable.default), ("description", &mut variable.description)] {
+            if let Some(s) = index.get(key).and_then(string_for_scalar_node) {
+                *field = s;
+            }
+        }
+
+        variable.specification_extension = Self::parse_specification_extensions(node, context)?;
+        Ok(variable)
+    }
+
     /// Parses Paths from a YAML node.
     pub fn parse_paths(node: &Yaml, context: &Arc<Context>) -> Result<Paths, ErrorGroup> {
         let mut errors = Vec::new();
         let mut paths = Paths::default();
 
         iter_map(node, |path, value| {
-            let child_ctx = Arc::new(context.child(path.to_string()));
+            if context.should_stop(&errors) {
+                return;
+            }
+            if path.starts_with("x-") {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(path));
             match Self::parse_path_item(value, &child_ctx) {
                 Ok(path_item) => {
                     paths.path.push(NamedPathItem {
@@ -231,6 +406,11 @@ impl Parser {
             }
         });
 
+        match Self::parse_specification_extensions(node, context) {
+            Ok(extensions) => paths.specification_extension = extensions,
+            Err(e) => errors.extend(e.errors),
+        }
+
         if errors.is_empty() {
             Ok(paths)
         } else {
@@ -240,50 +420,77 @@ impl Parser {
 
     /// Parses PathItem from a YAML node.
     pub fn parse_path_item(node: &Yaml, context: &Arc<Context>) -> Result<PathItem, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut path_item = PathItem::default();
 
-        if let Some(v) = map_value_for_key(node, "$ref") {
+        if let Some(v) = index.get("$ref") {
             if let Some(s) = string_for_scalar_node(v) {
                 path_item.r#ref = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "summary") {
+        if let Some(v) = index.get("summary") {
             if let Some(s) = string_for_scalar_node(v) {
                 path_item.summary = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "description") {
+        if let Some(v) = index.get("description") {
             if let Some(s) = string_for_scalar_node(v) {
                 path_item.description = s;
             }
         }
 
+        if let Some(v) = index.get("parameters") {
+            let child_ctx = Arc::new(context.child("parameters"));
+            match Self::parse_parameters(v, &child_ctx) {
+                Ok(parameters) => path_item.parameters = parameters,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("servers") {
+            let child_ctx = Arc::new(context.child("servers"));
+            match Self::parse_servers(v, &child_ctx) {
+                Ok(servers) => path_item.servers = servers,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
         // Parse HTTP methods
-        for method in &["get", "put", "post", "delete", "options", "head", "patch", "trace"] {
-            if let Some(v) = map_value_for_key(node, method) {
-                let child_ctx = Arc::new(context.child(*method));
+        for method in HttpMethod::ALL {
+            if let Some(v) = index.get(method.as_str()) {
+                let child_ctx = Arc::new(context.child(method.as_str()));
                 match Self::parse_operation(v, &child_ctx) {
                     Ok(op) => {
-                        match *method {
-                            "get" => path_item.get = Some(op),
-                            "put" => path_item.put = Some(op),
-                            "post" => path_item.post = Some(op),
-                            "delete" => path_item.delete = Some(op),
-                            "options" => path_item.options = Some(op),
-                            "head" => path_item.head = Some(op),
-                            "patch" => path_item.patch = Some(op),
-                            "trace" => path_item.trace = Some(op),
-                            _ => {}
+                        path_item.set_operation(method, op);
+                    }
+                    Err(e) => {
+                        errors.extend(e.errors);
+                        if context.should_stop(&errors) {
+                            return Err(ErrorGroup::new(errors));
                         }
                     }
-                    Err(e) => errors.extend(e.errors),
                 }
             }
         }
 
+        match Self::parse_specification_extensions(node, context) {
+            Ok(extensions) => path_item.specification_extension = extensions,
+            Err(e) => errors.extend(e.errors),
+        }
+
         if errors.is_empty() {
             Ok(path_item)
         } else {
@@ -293,46 +500,112 @@ impl Parser {
 
     /// Parses Operation from a YAML node.
     pub fn parse_operation(node: &Yaml, context: &Arc<Context>) -> Result<Operation, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut operation = Operation::default();
 
-        if let Some(v) = map_value_for_key(node, "tags") {
+        if let Some(v) = index.get("tags") {
             operation.tags = string_array_for_sequence_node(v);
         }
 
-        if let Some(v) = map_value_for_key(node, "summary") {
+        if let Some(v) = index.get("summary") {
             if let Some(s) = string_for_scalar_node(v) {
                 operation.summary = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "description") {
+        if let Some(v) = index.get("description") {
             if let Some(s) = string_for_scalar_node(v) {
                 operation.description = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "operationId") {
+        if let Some(v) = index.get("operationId") {
             if let Some(s) = string_for_scalar_node(v) {
                 operation.operation_id = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "deprecated") {
+        if let Some(v) = index.get("deprecated") {
             if let Some(b) = bool_for_scalar_node(v) {
                 operation.deprecated = b;
             }
         }
 
+        // Parse parameters
+        if let Some(v) = index.get("parameters") {
+            let child_ctx = Arc::new(context.child("parameters"));
+            match Self::parse_parameters(v, &child_ctx) {
+                Ok(parameters) => operation.parameters = parameters,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse requestBody
+        if let Some(v) = index.get("requestBody") {
+            let child_ctx = Arc::new(context.child("requestBody"));
+            match Self::parse_request_body_or_reference(v, &child_ctx) {
+                Ok(request_body) => operation.request_body = Some(request_body),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
         // Parse responses
-        if let Some(v) = map_value_for_key(node, "responses") {
+        if let Some(v) = index.get("responses") {
             let child_ctx = Arc::new(context.child("responses"));
             match Self::parse_responses(v, &child_ctx) {
                 Ok(responses) => operation.responses = Some(responses),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse security
+        if let Some(v) = index.get("security") {
+            let child_ctx = Arc::new(context.child("security"));
+            match Self::parse_security_requirements(v, &child_ctx) {
+                Ok(security) => operation.security = security,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("servers") {
+            let child_ctx = Arc::new(context.child("servers"));
+            match Self::parse_servers(v, &child_ctx) {
+                Ok(servers) => operation.servers = servers,
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
+        match Self::parse_specification_extensions(node, context) {
+            Ok(extensions) => operation.specification_extension = extensions,
+            Err(e) => errors.extend(e.errors),
+        }
+
         if errors.is_empty() {
             Ok(operation)
         } else {
@@ -340,13 +613,95 @@ impl Parser {
         }
     }
 
+    /// Parses a list of ParameterOrReference from a YAML node.
+    pub fn parse_parameters(node: &Yaml, context: &Arc<Context>) -> Result<Vec<ParameterOrReference>, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut parameters = Vec::new();
+
+        let Yaml::Sequence(items) = node else {
+            context
+                .report_recoverable(ErrorCode::InvalidType, "parameters must be an array")
+                .map_err(|e| ErrorGroup::new(vec![e]))?;
+            return Ok(parameters);
+        };
+        for (i, item) in items.iter().enumerate() {
+            let child_ctx = Arc::new(context.child(i.to_string()));
+            match Self::parse_parameter_or_reference(item, &child_ctx) {
+                Ok(parameter) => parameters.push(parameter),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(parameters)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses ParameterOrReference from a YAML node.
+    pub fn parse_parameter_or_reference(node: &Yaml, context: &Arc<Context>) -> Result<ParameterOrReference, ErrorGroup> {
+        // Check if it's a reference
+        if let Some(reference) = Self::parse_reference(node, context) {
+            return Ok(reference.into());
+        }
+
+        // Parse as parameter
+        Self::parse_parameter(node, context).map(ParameterOrReference::parameter)
+    }
+
+    /// Parses Parameter from a YAML node.
+    pub fn parse_parameter(node: &Yaml, context: &Arc<Context>) -> Result<Parameter, ErrorGroup> {
+        let index = KeyIndex::new(node);
+        let mut parameter = Parameter::default();
+
+        for (key, field) in [
+            ("name", &mut parameter.name),
+            ("in", &mut parameter.r#in),
+            ("description", &mut parameter.description),
+            ("style", &mut parameter.style),
+        ] {
+            if let Some(s) = index.get(key).and_then(string_for_scalar_node) {
+                *field = s;
+            }
+        }
+
+        for (key, field) in [
+            ("required", &mut parameter.required),
+            ("deprecated", &mut parameter.deprecated),
+            ("allowEmptyValue", &mut parameter.allow_empty_value),
+            ("explode", &mut parameter.explode),
+            ("allowReserved", &mut parameter.allow_reserved),
+        ] {
+            if let Some(b) = index.get(key).and_then(bool_for_scalar_node) {
+                *field = b;
+            }
+        }
+
+        if let Some(v) = index.get("schema") {
+            let child_ctx = Arc::new(context.child("schema"));
+            parameter.schema = Some(Self::parse_schema_or_reference(v, &child_ctx)?);
+        }
+
+        parameter.specification_extension = Self::parse_specification_extensions(node, context)?;
+        Ok(parameter)
+    }
+
     /// Parses Responses from a YAML node.
     pub fn parse_responses(node: &Yaml, context: &Arc<Context>) -> Result<Responses, ErrorGroup> {
         let mut errors = Vec::new();
         let mut responses = Responses::default();
 
         iter_map(node, |code, value| {
-            let child_ctx = Arc::new(context.child(code.to_string()));
+            if code.starts_with("x-") || context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(code));
             match Self::parse_response_or_reference(value, &child_ctx) {
                 Ok(response) => {
                     responses.response_or_reference.push(NamedResponseOrReference {
@@ -358,6 +713,11 @@ impl Parser {
             }
         });
 
+        match Self::parse_specification_extensions(node, context) {
+            Ok(extensions) => responses.specification_extension = extensions,
+            Err(e) => errors.extend(e.errors),
+        }
+
         if errors.is_empty() {
             Ok(responses)
         } else {
@@ -368,25 +728,16 @@ impl Parser {
     /// Parses ResponseOrReference from a YAML node.
     pub fn parse_response_or_reference(node: &Yaml, context: &Arc<Context>) -> Result<ResponseOrReference, ErrorGroup> {
         // Check if it's a reference
-        if let Some(v) = map_value_for_key(node, "$ref") {
-            if let Some(s) = string_for_scalar_node(v) {
-                return Ok(ResponseOrReference {
-                    oneof: Some(response_or_reference::Oneof::Reference(Reference {
-                        r#ref: s,
-                        ..Default::default()
-                    })),
-                });
-            }
+        if let Some(reference) = Self::parse_reference(node, context) {
+            return Ok(reference.into());
         }
 
         // Parse as response
-        Self::parse_response(node, context).map(|r| ResponseOrReference {
-            oneof: Some(response_or_reference::Oneof::Response(r)),
-        })
+        Self::parse_response(node, context).map(ResponseOrReference::response)
     }
 
     /// Parses Response from a YAML node.
-    pub fn parse_response(node: &Yaml, _context: &Arc<Context>) -> Result<Response, ErrorGroup> {
+    pub fn parse_response(node: &Yaml, context: &Arc<Context>) -> Result<Response, ErrorGroup> {
         let mut response = Response::default();
 
         if let Some(v) = map_value_for_key(node, "description") {
@@ -395,23 +746,203 @@ impl Parser {
             }
         }
 
+        if let Some(v) = map_value_for_key(node, "content") {
+            let child_ctx = Arc::new(context.child("content"));
+            response.content = Some(Self::parse_media_types(v, &child_ctx)?);
+        }
+
+        response.specification_extension = Self::parse_specification_extensions(node, context)?;
         Ok(response)
     }
 
+    /// Parses RequestBodyOrReference from a YAML node.
+    pub fn parse_request_body_or_reference(
+        node: &Yaml,
+        context: &Arc<Context>,
+    ) -> Result<RequestBodyOrReference, ErrorGroup> {
+        // Check if it's a reference
+        if let Some(reference) = Self::parse_reference(node, context) {
+            return Ok(reference.into());
+        }
+
+        // Parse as request body
+        Self::parse_request_body(node, context).map(RequestBodyOrReference::request_body)
+    }
+
+    /// Parses RequestBody from a YAML node.
+    pub fn parse_request_body(node: &Yaml, context: &Arc<Context>) -> Result<RequestBody, ErrorGroup> {
+        let index = KeyIndex::new(node);
+        let mut request_body = RequestBody::default();
+
+        if let Some(v) = index.get("description") {
+            if let Some(s) = string_for_scalar_node(v) {
+                request_body.description = s;
+            }
+        }
+
+        if let Some(v) = index.get("required") {
+            if let Some(b) = bool_for_scalar_node(v) {
+                request_body.required = b;
+            }
+        }
+
+        if let Some(v) = index.get("content") {
+            let child_ctx = Arc::new(context.child("content"));
+            request_body.content = Some(Self::parse_media_types(v, &child_ctx)?);
+        }
+
+        request_body.specification_extension = Self::parse_specification_extensions(node, context)?;
+        Ok(request_body)
+    }
+
+    /// Parses MediaTypes from a YAML node.
+    pub fn parse_media_types(node: &Yaml, context: &Arc<Context>) -> Result<MediaTypes, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut media_types = MediaTypes::default();
+
+        iter_map(node, |name, value| {
+            if context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(name));
+            match Self::parse_media_type(value, &child_ctx) {
+                Ok(media_type) => {
+                    media_types.additional_properties.push(NamedMediaType {
+                        name: name.to_string(),
+                        value: Some(media_type),
+                    });
+                }
+                Err(e) => errors.extend(e.errors),
+            }
+        });
+
+        if errors.is_empty() {
+            Ok(media_types)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
+    /// Parses MediaType from a YAML node.
+    pub fn parse_media_type(node: &Yaml, context: &Arc<Context>) -> Result<MediaType, ErrorGroup> {
+        let mut media_type = MediaType::default();
+
+        if let Some(v) = map_value_for_key(node, "schema") {
+            let child_ctx = Arc::new(context.child("schema"));
+            media_type.schema = Some(Self::parse_schema_or_reference(v, &child_ctx)?);
+        }
+
+        if let Some(v) = map_value_for_key(node, "example") {
+            media_type.example = Some(Box::new(Self::parse_any(v, context)?));
+        }
+
+        media_type.specification_extension = Self::parse_specification_extensions(node, context)?;
+        Ok(media_type)
+    }
+
     /// Parses Components from a YAML node.
     pub fn parse_components(node: &Yaml, context: &Arc<Context>) -> Result<Components, ErrorGroup> {
+        let index = KeyIndex::new(node);
         let mut errors = Vec::new();
         let mut components = Components::default();
 
         // Parse schemas
-        if let Some(v) = map_value_for_key(node, "schemas") {
+        if let Some(v) = index.get("schemas") {
             let child_ctx = Arc::new(context.child("schemas"));
             match Self::parse_schemas_or_references(v, &child_ctx) {
                 Ok(schemas) => components.schemas = Some(schemas),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
+        // Parse responses
+        if let Some(v) = index.get("responses") {
+            let child_ctx = Arc::new(context.child("responses"));
+            match Self::parse_responses(v, &child_ctx) {
+                Ok(responses) => {
+                    components.responses = Some(ResponsesOrReferences {
+                        additional_properties: responses.response_or_reference,
+                    })
+                }
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse parameters
+        if let Some(v) = index.get("parameters") {
+            let child_ctx = Arc::new(context.child("parameters"));
+            let mut parameters = ParametersOrReferences::default();
+            iter_map(v, |name, value| {
+                if context.should_stop(&errors) {
+                    return;
+                }
+                let named_ctx = Arc::new(child_ctx.child(name));
+                match Self::parse_parameter_or_reference(value, &named_ctx) {
+                    Ok(parameter) => parameters.additional_properties.push(NamedParameterOrReference {
+                        name: name.to_string(),
+                        value: Some(parameter),
+                    }),
+                    Err(e) => errors.extend(e.errors),
+                }
+            });
+            components.parameters = Some(parameters);
+        }
+
+        // Parse requestBodies
+        if let Some(v) = index.get("requestBodies") {
+            let child_ctx = Arc::new(context.child("requestBodies"));
+            let mut request_bodies = RequestBodiesOrReferences::default();
+            iter_map(v, |name, value| {
+                if context.should_stop(&errors) {
+                    return;
+                }
+                let named_ctx = Arc::new(child_ctx.child(name));
+                match Self::parse_request_body_or_reference(value, &named_ctx) {
+                    Ok(request_body) => request_bodies.additional_properties.push(NamedRequestBodyOrReference {
+                        name: name.to_string(),
+                        value: Some(request_body),
+                    }),
+                    Err(e) => errors.extend(e.errors),
+                }
+            });
+            components.request_bodies = Some(request_bodies);
+        }
+
+        // Parse securitySchemes
+        if let Some(v) = index.get("securitySchemes") {
+            let child_ctx = Arc::new(context.child("securitySchemes"));
+            let mut security_schemes = SecuritySchemesOrReferences::default();
+            iter_map(v, |name, value| {
+                if context.should_stop(&errors) {
+                    return;
+                }
+                let named_ctx = Arc::new(child_ctx.child(name));
+                match Self::parse_security_scheme_or_reference(value, &named_ctx) {
+                    Ok(security_scheme) => security_schemes.additional_properties.push(NamedSecuritySchemeOrReference {
+                        name: name.to_string(),
+                        value: Some(security_scheme),
+                    }),
+                    Err(e) => errors.extend(e.errors),
+                }
+            });
+            components.security_schemes = Some(security_schemes);
+        }
+
+        match Self::parse_specification_extensions(node, context) {
+            Ok(extensions) => components.specification_extension = extensions,
+            Err(e) => errors.extend(e.errors),
+        }
+
         if errors.is_empty() {
             Ok(components)
         } else {
@@ -419,13 +950,142 @@ impl Parser {
         }
     }
 
+    /// Parses a list of SecurityRequirement from a YAML node.
+    pub fn parse_security_requirements(
+        node: &Yaml,
+        context: &Arc<Context>,
+    ) -> Result<Vec<SecurityRequirement>, ErrorGroup> {
+        let Yaml::Sequence(items) = node else {
+            context
+                .report_recoverable(ErrorCode::InvalidType, "security must be an array")
+                .map_err(|e| ErrorGroup::new(vec![e]))?;
+            return Ok(Vec::new());
+        };
+        Ok(items.iter().map(Self::parse_security_requirement).collect())
+    }
+
+    /// Parses SecurityRequirement from a YAML node, the scopes required of
+    /// each named security scheme.
+    pub fn parse_security_requirement(node: &Yaml) -> SecurityRequirement {
+        let mut requirement = SecurityRequirement::default();
+        iter_map(node, |name, value| {
+            requirement.additional_properties.push(NamedStringArray {
+                name: name.to_string(),
+                value: Some(StringArray { value: string_array_for_sequence_node(value) }),
+            });
+        });
+        requirement
+    }
+
+    /// Parses SecuritySchemeOrReference from a YAML node.
+    pub fn parse_security_scheme_or_reference(
+        node: &Yaml,
+        context: &Arc<Context>,
+    ) -> Result<SecuritySchemeOrReference, ErrorGroup> {
+        // Check if it's a reference
+        if let Some(reference) = Self::parse_reference(node, context) {
+            return Ok(reference.into());
+        }
+
+        // Parse as security scheme
+        Self::parse_security_scheme(node, context).map(SecuritySchemeOrReference::security_scheme)
+    }
+
+    /// Parses SecurityScheme from a YAML node.
+    pub fn parse_security_scheme(node: &Yaml, context: &Arc<Context>) -> Result<SecurityScheme, ErrorGroup> {
+        let index = KeyIndex::new(node);
+        let mut security_scheme = SecurityScheme::default();
+
+        for (key, field) in [
+            ("type", &mut security_scheme.r#type),
+            ("description", &mut security_scheme.description),
+            ("name", &mut security_scheme.name),
+            ("in", &mut security_scheme.r#in),
+            ("scheme", &mut security_scheme.scheme),
+            ("bearerFormat", &mut security_scheme.bearer_format),
+            ("openIdConnectUrl", &mut security_scheme.open_id_connect_url),
+        ] {
+            if let Some(s) = index.get(key).and_then(string_for_scalar_node) {
+                *field = s;
+            }
+        }
+
+        if let Some(v) = index.get("flows") {
+            let child_ctx = Arc::new(context.child("flows"));
+            security_scheme.flows = Some(Box::new(Self::parse_oauth_flows(v, &child_ctx)?));
+        }
+
+        security_scheme.specification_extension = Self::parse_specification_extensions(node, context)?;
+        Ok(security_scheme)
+    }
+
+    /// Parses OauthFlows from a YAML node.
+    pub fn parse_oauth_flows(node: &Yaml, context: &Arc<Context>) -> Result<OauthFlows, ErrorGroup> {
+        let index = KeyIndex::new(node);
+        let mut flows = OauthFlows::default();
+
+        for (key, field) in [
+            ("implicit", &mut flows.implicit),
+            ("password", &mut flows.password),
+            ("clientCredentials", &mut flows.client_credentials),
+            ("authorizationCode", &mut flows.authorization_code),
+        ] {
+            if let Some(v) = index.get(key) {
+                let child_ctx = Arc::new(context.child(key));
+                *field = Some(Box::new(Self::parse_oauth_flow(v, &child_ctx)?));
+            }
+        }
+
+        flows.specification_extension = Self::parse_specification_extensions(node, context)?;
+        Ok(flows)
+    }
+
+    /// Parses OauthFlow from a YAML node.
+    pub fn parse_oauth_flow(node: &Yaml, context: &Arc<Context>) -> Result<OauthFlow, ErrorGroup> {
+        let index = KeyIndex::new(node);
+        let mut flow = OauthFlow::default();
+
+        for (key, field) in [
+            ("authorizationUrl", &mut flow.authorization_url),
+            ("tokenUrl", &mut flow.token_url),
+            ("refreshUrl", &mut flow.refresh_url),
+        ] {
+            if let Some(s) = index.get(key).and_then(string_for_scalar_node) {
+                *field = s;
+            }
+        }
+
+        if let Some(v) = index.get("scopes") {
+            flow.scopes = Some(Self::parse_strings(v));
+        }
+
+        flow.specification_extension = Self::parse_specification_extensions(node, context)?;
+        Ok(flow)
+    }
+
+    /// Parses Strings, a map of strings such as the scopes of an OAuth
+    /// flow, from a YAML node.
+    pub fn parse_strings(node: &Yaml) -> Strings {
+        let mut strings = Strings::default();
+        iter_map(node, |name, value| {
+            strings.additional_properties.push(NamedString {
+                name: name.to_string(),
+                value: string_for_scalar_node(value).unwrap_or_default(),
+            });
+        });
+        strings
+    }
+
     /// Parses SchemasOrReferences from a YAML node.
     pub fn parse_schemas_or_references(node: &Yaml, context: &Arc<Context>) -> Result<SchemasOrReferences, ErrorGroup> {
         let mut errors = Vec::new();
         let mut schemas = SchemasOrReferences::default();
 
         iter_map(node, |name, value| {
-            let child_ctx = Arc::new(context.child(name.to_string()));
+            if context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(name));
             match Self::parse_schema_or_reference(value, &child_ctx) {
                 Ok(schema) => {
                     schemas.additional_properties.push(NamedSchemaOrReference {
@@ -444,95 +1104,136 @@ impl Parser {
         }
     }
 
+    /// Parses a Reference if the node is one.
+    ///
+    /// The summary and description next to the $ref are only kept when the
+    /// options merge $ref siblings, as OpenAPI 3.1 does; 3.0 ignores them.
+    pub fn parse_reference(node: &Yaml, context: &Arc<Context>) -> Option<Reference> {
+        let r#ref = map_value_for_key(node, "$ref").and_then(string_for_scalar_node)?;
+        let mut reference = Reference {
+            r#ref,
+            ..Default::default()
+        };
+        if context.options.ref_siblings == Some(RefSiblings::Merge) {
+            for (key, field) in [
+                ("summary", &mut reference.summary),
+                ("description", &mut reference.description),
+            ] {
+                if let Some(s) = map_value_for_key(node, key).and_then(string_for_scalar_node) {
+                    *field = s;
+                }
+            }
+        }
+        Some(reference)
+    }
+
     /// Parses SchemaOrReference from a YAML node.
     pub fn parse_schema_or_reference(node: &Yaml, context: &Arc<Context>) -> Result<SchemaOrReference, ErrorGroup> {
         // Check if it's a reference
-        if let Some(v) = map_value_for_key(node, "$ref") {
-            if let Some(s) = string_for_scalar_node(v) {
-                return Ok(SchemaOrReference {
-                    oneof: Some(schema_or_reference::Oneof::Reference(Reference {
-                        r#ref: s,
-                        ..Default::default()
-                    })),
-                });
-            }
+        if let Some(reference) = Self::parse_reference(node, context) {
+            return Ok(reference.into());
         }
 
         // Parse as schema
-        Self::parse_schema(node, context).map(|s| SchemaOrReference {
-            oneof: Some(schema_or_reference::Oneof::Schema(Box::new(s))),
-        })
+        Self::parse_schema(node, context).map(SchemaOrReference::schema)
     }
 
     /// Parses Schema from a YAML node.
     pub fn parse_schema(node: &Yaml, context: &Arc<Context>) -> Result<Schema, ErrorGroup> {
+        check_depth(context)?;
+        let index = KeyIndex::new(node);
+
         let mut errors = Vec::new();
         let mut schema = Schema::default();
 
-        if let Some(v) = map_value_for_key(node, "type") {
+        if let Some(v) = index.get("type") {
             if let Some(s) = string_for_scalar_node(v) {
                 schema.r#type = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "format") {
+        if let Some(v) = index.get("format") {
             if let Some(s) = string_for_scalar_node(v) {
                 schema.format = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "title") {
+        if let Some(v) = index.get("title") {
             if let Some(s) = string_for_scalar_node(v) {
                 schema.title = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "description") {
+        if let Some(v) = index.get("description") {
             if let Some(s) = string_for_scalar_node(v) {
                 schema.description = s;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "nullable") {
+        if let Some(v) = index.get("nullable") {
             if let Some(b) = bool_for_scalar_node(v) {
                 schema.nullable = b;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "readOnly") {
+        if let Some(v) = index.get("readOnly") {
             if let Some(b) = bool_for_scalar_node(v) {
                 schema.read_only = b;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "writeOnly") {
+        if let Some(v) = index.get("writeOnly") {
             if let Some(b) = bool_for_scalar_node(v) {
                 schema.write_only = b;
             }
         }
 
-        if let Some(v) = map_value_for_key(node, "deprecated") {
+        if let Some(v) = index.get("deprecated") {
             if let Some(b) = bool_for_scalar_node(v) {
                 schema.deprecated = b;
             }
         }
 
+        if let Some(v) = index.get("example") {
+            let child_ctx = Arc::new(context.child("example"));
+            match Self::parse_any(v, &child_ctx) {
+                Ok(example) => schema.example = Some(Box::new(example)),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
         // Parse properties
-        if let Some(v) = map_value_for_key(node, "properties") {
+        if let Some(v) = index.get("properties") {
             let child_ctx = Arc::new(context.child("properties"));
             match Self::parse_properties(v, &child_ctx) {
                 Ok(props) => schema.properties = Some(props),
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
             }
         }
 
         // Parse required
-        if let Some(v) = map_value_for_key(node, "required") {
+        if let Some(v) = index.get("required") {
             schema.required = string_array_for_sequence_node(v);
         }
 
+        if let Some(Yaml::Sequence(values)) = index.get("enum") {
+            for value in values {
+                schema.r#enum.push(Self::parse_any(value, context)?);
+            }
+        }
+
         // Parse items (for arrays)
-        if let Some(v) = map_value_for_key(node, "items") {
+        if let Some(v) = index.get("items") {
             let child_ctx = Arc::new(context.child("items"));
             match Self::parse_schema_or_reference(v, &child_ctx) {
                 Ok(items) => {
@@ -540,10 +1241,76 @@ impl Parser {
                         schema_or_reference: vec![items],
                     });
                 }
-                Err(e) => errors.extend(e.errors),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse allOf, oneOf and anyOf
+        for (key, field) in [
+            ("allOf", &mut schema.all_of),
+            ("oneOf", &mut schema.one_of),
+            ("anyOf", &mut schema.any_of),
+        ] {
+            let Some(Yaml::Sequence(nodes)) = index.get(key) else {
+                continue;
+            };
+            let child_ctx = Arc::new(context.child(key));
+            for (i, item) in nodes.iter().enumerate() {
+                match Self::parse_schema_or_reference(item, &Arc::new(child_ctx.child(i.to_string()))) {
+                    Ok(item) => field.push(item),
+                    Err(e) => {
+                        errors.extend(e.errors);
+                        if context.should_stop(&errors) {
+                            return Err(ErrorGroup::new(errors));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(v) = index.get("discriminator") {
+            let child_ctx = Arc::new(context.child("discriminator"));
+            match Self::parse_discriminator(v, &child_ctx) {
+                Ok(discriminator) => schema.discriminator = Some(Box::new(discriminator)),
+                Err(e) => {
+                    errors.extend(e.errors);
+                    if context.should_stop(&errors) {
+                        return Err(ErrorGroup::new(errors));
+                    }
+                }
+            }
+        }
+
+        // Parse additionalProperties, a boolean or the schema of property values
+        if let Some(v) = index.get("additionalProperties") {
+            if let Some(b) = bool_for_scalar_node(v) {
+                schema.additional_properties = Some(Box::new(b.into()));
+            } else {
+                let child_ctx = Arc::new(context.child("additionalProperties"));
+                match Self::parse_schema_or_reference(v, &child_ctx) {
+                    Ok(values) => {
+                        schema.additional_properties = Some(Box::new(values.into()));
+                    }
+                    Err(e) => {
+                        errors.extend(e.errors);
+                        if context.should_stop(&errors) {
+                            return Err(ErrorGroup::new(errors));
+                        }
+                    }
+                }
             }
         }
 
+        match Self::parse_specification_extensions(node, context) {
+            Ok(extensions) => schema.specification_extension = extensions,
+            Err(e) => errors.extend(e.errors),
+        }
+
         if errors.is_empty() {
             Ok(schema)
         } else {
@@ -551,13 +1318,73 @@ impl Parser {
         }
     }
 
+    /// Parses Discriminator from a YAML node.
+    pub fn parse_discriminator(node: &Yaml, context: &Arc<Context>) -> Result<Discriminator, ErrorGroup> {
+        let mut discriminator = Discriminator::default();
+
+        if let Some(v) = map_value_for_key(node, "propertyName") {
+            if let Some(s) = string_for_scalar_node(v) {
+                discriminator.property_name = s;
+            }
+        }
+
+        if let Some(v) = map_value_for_key(node, "mapping") {
+            discriminator.mapping = Some(Self::parse_strings(v));
+        }
+
+        discriminator.specification_extension = Self::parse_specification_extensions(node, context)?;
+        Ok(discriminator)
+    }
+
+    /// Parses Any from a YAML node, keeping the YAML serialization of the value.
+    pub fn parse_any(node: &Yaml, _context: &Arc<Context>) -> Result<Any, ErrorGroup> {
+        Ok(Any {
+            value: None,
+            yaml: String::from_utf8_lossy(&marshal(node)).into_owned(),
+        })
+    }
+
+    /// Parses the specification extensions ("x-" keys) of a YAML node.
+    ///
+    /// An extension that an extension handler claims has the handler's
+    /// response as its value; the others keep only the YAML of their values.
+    pub fn parse_specification_extensions(node: &Yaml, context: &Arc<Context>) -> Result<Vec<NamedAny>, ErrorGroup> {
+        let mut errors = Vec::new();
+        let mut extensions = Vec::new();
+
+        iter_map(node, |name, value| {
+            if !name.starts_with("x-") || context.should_stop(&errors) {
+                return;
+            }
+            match handle_extension(&context.child(name), value, name) {
+                Ok(handled) => extensions.push(NamedAny {
+                    name: name.to_string(),
+                    value: Some(Any {
+                        value: handled,
+                        yaml: String::from_utf8_lossy(&marshal(value)).into_owned(),
+                    }),
+                }),
+                Err(e) => errors.push(e),
+            }
+        });
+
+        if errors.is_empty() {
+            Ok(extensions)
+        } else {
+            Err(ErrorGroup::new(errors))
+        }
+    }
+
     /// Parses Properties from a YAML node.
     pub fn parse_properties(node: &Yaml, context: &Arc<Context>) -> Result<Properties, ErrorGroup> {
         let mut errors = Vec::new();
         let mut properties = Properties::default();
 
         iter_map(node, |name, value| {
-            let child_ctx = Arc::new(context.child(name.to_string()));
+            if context.should_stop(&errors) {
+                return;
+            }
+            let child_ctx = Arc::new(context.child(name));
             match Self::parse_schema_or_reference(value, &child_ctx) {
                 Ok(schema) => {
                     properties.additional_properties.push(NamedSchemaOrReference {
@@ -577,7 +1404,7 @@ impl Parser {
     }
 
     /// Parses Tag from a YAML node.
-    pub fn parse_tag(node: &Yaml, _context: &Arc<Context>) -> Result<Tag, ErrorGroup> {
+    pub fn parse_tag(node: &Yaml, context: &Arc<Context>) -> Result<Tag, ErrorGroup> {
         let mut tag = Tag::default();
 
         if let Some(v) = map_value_for_key(node, "name") {
@@ -592,11 +1419,12 @@ impl Parser {
             }
         }
 
+        tag.specification_extension = Self::parse_specification_extensions(node, context)?;
         Ok(tag)
     }
 
     /// Parses ExternalDocs from a YAML node.
-    pub fn parse_external_docs(node: &Yaml, _context: &Arc<Context>) -> Result<ExternalDocs, ErrorGroup> {
+    pub fn parse_external_docs(node: &Yaml, context: &Arc<Context>) -> Result<ExternalDocs, ErrorGroup> {
         let mut external_docs = ExternalDocs::default();
 
         if let Some(v) = map_value_for_key(node, "description") {
@@ -611,6 +1439,20 @@ impl Parser {
             }
         }
 
+        external_docs.specification_extension = Self::parse_specification_extensions(node, context)?;
         Ok(external_docs)
     }
 }
+
+/// Returns the context with the treatment of $ref siblings set from the
+/// document version, unless the options already fix it.
+fn with_ref_siblings(context: &Arc<Context>, version: &str) -> Arc<Context> {
+    if context.options.ref_siblings.is_some() {
+        return Arc::clone(context);
+    }
+    let mut options = (*context.options).clone();
+    options.ref_siblings = Some(RefSiblings::for_version(version));
+    let mut with_siblings = (**context).clone();
+    with_siblings.options = Arc::new(options);
+    Arc::new(with_siblings)
+}
@@ -0,0 +1,791 @@
+//! Generation of protocol buffer service definitions from OpenAPI v3 documents.
+//!
+//! [`generate_proto`] is the inverse of protoc-gen-openapi: the operations of
+//! a document become the RPCs of a service with `google.api.http` rules, and
+//! the schemas of its components become messages. The request message of an
+//! RPC holds the path and query parameters of the operation and its request
+//! body; an RPC returns the message of the first successful response, or
+//! google.protobuf.Empty if the response has no content.
+//!
+//! [`generate_messages`] generates only the messages, reporting the names of
+//! schemas, properties and enum values that had to change to be valid and
+//! unique protobuf identifiers.
+
+use crate::openapi_v3::*;
+use serde_yaml::Value as Yaml;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::{self, Write};
+
+const EMPTY: &str = "google.protobuf.Empty";
+
+/// Generates a .proto file with a service for the operations of `doc` and
+/// messages for its component schemas, in the protobuf package `package`.
+pub fn generate_proto(doc: &Document, package: &str) -> String {
+    let mut generator = Generator::new(doc);
+    generator.imports.insert("google/api/annotations.proto");
+    let service = generator.service();
+    generator.components();
+    generator.file(package, Some(&service))
+}
+
+/// Generates a .proto file with a message or enum for each component schema
+/// of `doc`, in the protobuf package `package`, and reports the names that
+/// were changed.
+///
+/// Object schemas become messages, with repeated fields for arrays and map
+/// fields for `additionalProperties`; string enums become enums. Component
+/// names that convert to the same message name are told apart by a number,
+/// as are properties that convert to the same field name.
+pub fn generate_messages(doc: &Document, package: &str) -> ProtoMessages {
+    let mut generator = Generator::new(doc);
+    generator.components();
+    ProtoMessages { proto: generator.file(package, None), renames: generator.renames }
+}
+
+/// The messages generated for the component schemas of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtoMessages {
+    /// The text of the .proto file.
+    pub proto: String,
+    /// The names that were changed, in the order they were generated.
+    pub renames: Vec<ProtoRename>,
+}
+
+/// A name in a document that was changed to name a protobuf definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtoRename {
+    /// What the name names.
+    pub kind: ProtoRenameKind,
+    /// The name of the enclosing message or enum, such as "Pet.Status", or
+    /// empty for a top-level definition.
+    pub scope: String,
+    /// The name in the document.
+    pub original: String,
+    /// The name in the .proto file.
+    pub name: String,
+}
+
+/// The kinds of names that are changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProtoRenameKind {
+    /// A component schema, named by a message or enum.
+    Definition,
+    /// A property, named by a field.
+    Field,
+    /// A value of a string enum.
+    EnumValue,
+}
+
+impl fmt::Display for ProtoRename {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            ProtoRenameKind::Definition => "definition",
+            ProtoRenameKind::Field => "field",
+            ProtoRenameKind::EnumValue => "enum value",
+        };
+        let scope = if self.scope.is_empty() { String::new() } else { format!("{}.", self.scope) };
+        write!(f, "{} {:?} is {}{}", kind, self.original, scope, self.name)
+    }
+}
+
+struct Generator<'a> {
+    doc: &'a Document,
+    imports: BTreeSet<&'static str>,
+    /// Top-level messages and enums, in the order they are written.
+    definitions: Vec<String>,
+    /// The names of the definitions of component schemas.
+    names: HashMap<&'a str, String>,
+    /// The names of the enclosing definitions of the one being generated.
+    scope: Vec<String>,
+    renames: Vec<ProtoRename>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(doc: &'a Document) -> Self {
+        let mut names = HashMap::new();
+        let mut used = HashSet::new();
+        for (name, _) in component_schemas(doc) {
+            let unique = unique_name(upper_camel_case(name), "", &used);
+            used.insert(unique.clone());
+            names.entry(name).or_insert(unique);
+        }
+        Generator {
+            doc,
+            imports: BTreeSet::new(),
+            definitions: Vec::new(),
+            names,
+            scope: Vec::new(),
+            renames: Vec::new(),
+        }
+    }
+
+    /// Adds the definitions of the component schemas.
+    fn components(&mut self) {
+        for (name, schema) in component_schemas(self.doc) {
+            if let Some(schema_or_reference::Oneof::Schema(schema)) = &schema.oneof {
+                let message = self.message_name(name);
+                self.rename(ProtoRenameKind::Definition, name, &message);
+                let definition = self.definition(&message, schema, "");
+                self.definitions.push(definition);
+            }
+        }
+    }
+
+    /// Returns the text of the file, with a service if there is one.
+    fn file(&self, package: &str, service: Option<&str>) -> String {
+        let mut out = format!("syntax = \"proto3\";\n\npackage {};\n\n", package);
+        for import in &self.imports {
+            let _ = writeln!(out, "import \"{}\";", import);
+        }
+        if !self.imports.is_empty() {
+            out.push('\n');
+        }
+        let mut first = true;
+        for definition in service.into_iter().chain(self.definitions.iter().map(String::as_str)) {
+            if !first {
+                out.push('\n');
+            }
+            first = false;
+            out.push_str(definition);
+        }
+        out
+    }
+
+    /// Returns the name of the definition of a component schema.
+    fn message_name(&self, name: &str) -> String {
+        self.names.get(name).cloned().unwrap_or_else(|| upper_camel_case(name))
+    }
+
+    /// Records a change of name, if the name changed.
+    fn rename(&mut self, kind: ProtoRenameKind, original: &str, name: &str) {
+        if original != name {
+            let (original, name) = (original.to_string(), name.to_string());
+            self.renames.push(ProtoRename { kind, scope: self.scope.join("."), original, name });
+        }
+    }
+
+    fn service(&mut self) -> String {
+        let doc = self.doc;
+        let info = doc.info.as_ref();
+        let title = info.map_or("", |info| info.title.as_str());
+        let mut out = String::new();
+        write_comment(&mut out, "", info.map_or("", |info| info.description.as_str()));
+        let _ = writeln!(out, "service {}Service {{", upper_camel_case(if title.is_empty() { "Api" } else { title }));
+        let mut first = true;
+        for named in doc.paths.iter().flat_map(|paths| &paths.path) {
+            let Some(item) = &named.value else {
+                continue;
+            };
+            let operations = [
+                ("get", &item.get),
+                ("put", &item.put),
+                ("post", &item.post),
+                ("delete", &item.delete),
+                ("patch", &item.patch),
+            ];
+            for (method, operation) in operations {
+                if let Some(operation) = operation {
+                    if !first {
+                        out.push('\n');
+                    }
+                    first = false;
+                    self.rpc(&mut out, &named.name, method, &item.parameters, operation);
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn rpc(
+        &mut self,
+        out: &mut String,
+        path: &str,
+        method: &str,
+        path_parameters: &'a [ParameterOrReference],
+        operation: &'a Operation,
+    ) {
+        let name = if operation.operation_id.is_empty() {
+            upper_camel_case(&format!("{} {}", method, path))
+        } else {
+            upper_camel_case(&operation.operation_id)
+        };
+
+        // Path and query parameters become fields of the request message.
+        let mut fields = Vec::new();
+        let mut nested = Vec::new();
+        let mut template = path.to_string();
+        let parameters: Vec<_> =
+            path_parameters.iter().chain(&operation.parameters).filter_map(|p| self.parameter(p)).collect();
+        for parameter in parameters {
+            if parameter.r#in != "path" && parameter.r#in != "query" {
+                continue;
+            }
+            let field = snake_case(&parameter.name);
+            if parameter.r#in == "path" {
+                template = template.replace(&format!("{{{}}}", parameter.name), &format!("{{{}}}", field));
+            }
+            let field_type = match &parameter.schema {
+                Some(schema) => self.field_type(schema, &parameter.name, &mut nested, "  "),
+                None => "string".to_string(),
+            };
+            fields.push((field, field_type, parameter.description.clone()));
+        }
+
+        // The request body is a field, or its properties are fields if it is an inline object.
+        let mut body = None;
+        if let Some(schema) = self.request_body(operation).and_then(first_schema) {
+            match &schema.oneof {
+                Some(schema_or_reference::Oneof::Schema(schema)) if schema.properties.is_some() => {
+                    self.scope.push(format!("{}Request", name));
+                    self.add_fields(schema, &mut nested, "  ", &mut fields);
+                    self.scope.pop();
+                    body = Some("*".to_string());
+                }
+                Some(schema_or_reference::Oneof::Reference(reference)) => {
+                    let field = snake_case(reference_name(&reference.r#ref));
+                    let field_type = self.field_type(schema, &field, &mut nested, "  ");
+                    body = Some(field.clone());
+                    fields.push((field, field_type, String::new()));
+                }
+                _ => {
+                    let field_type = self.field_type(schema, "body", &mut nested, "  ");
+                    body = Some("body".to_string());
+                    fields.push(("body".to_string(), field_type, String::new()));
+                }
+            }
+        }
+        let request = if fields.is_empty() {
+            self.imports.insert("google/protobuf/empty.proto");
+            EMPTY.to_string()
+        } else {
+            let request = format!("{}Request", name);
+            self.definitions.push(message_text(&request, &nested, &fields, ""));
+            request
+        };
+
+        let mut response_body = None;
+        let response = match self.response_schema(operation) {
+            None => {
+                self.imports.insert("google/protobuf/empty.proto");
+                EMPTY.to_string()
+            }
+            Some(schema) => match &schema.oneof {
+                Some(schema_or_reference::Oneof::Reference(reference)) => {
+                    self.message_name(reference_name(&reference.r#ref))
+                }
+                Some(schema_or_reference::Oneof::Schema(inline)) if inline.properties.is_some() => {
+                    let response = format!("{}Response", name);
+                    let definition = self.definition(&response, inline, "");
+                    self.definitions.push(definition);
+                    response
+                }
+                _ => {
+                    // Other responses are wrapped in a message, whose field is the response body.
+                    let response = format!("{}Response", name);
+                    let mut nested = Vec::new();
+                    let field_type = self.field_type(schema, "items", &mut nested, "  ");
+                    let fields = [("items".to_string(), field_type, String::new())];
+                    self.definitions.push(message_text(&response, &nested, &fields, ""));
+                    response_body = Some("items");
+                    response
+                }
+            },
+        };
+
+        let description = if operation.summary.is_empty() { &operation.description } else { &operation.summary };
+        write_comment(out, "  ", description);
+        let _ = writeln!(out, "  rpc {}({}) returns ({}) {{", name, request, response);
+        let _ = writeln!(out, "    option (google.api.http) = {{");
+        let _ = writeln!(out, "      {}: \"{}\"", method, template);
+        if let Some(body) = body {
+            let _ = writeln!(out, "      body: \"{}\"", body);
+        }
+        if let Some(response_body) = response_body {
+            let _ = writeln!(out, "      response_body: \"{}\"", response_body);
+        }
+        let _ = writeln!(out, "    }};");
+        if operation.deprecated {
+            let _ = writeln!(out, "    option deprecated = true;");
+        }
+        let _ = writeln!(out, "  }}");
+    }
+
+    /// Returns a message or, for a string enum, an enum definition.
+    fn definition(&mut self, name: &str, schema: &'a Schema, indent: &str) -> String {
+        self.scope.push(name.to_string());
+        let out = if !schema.r#enum.is_empty() {
+            self.enum_text(name, schema, indent)
+        } else {
+            let inner = format!("{}  ", indent);
+            let mut nested = Vec::new();
+            let mut fields = Vec::new();
+            self.add_fields(schema, &mut nested, &inner, &mut fields);
+            let mut out = String::new();
+            write_comment(&mut out, indent, &schema.description);
+            out.push_str(&message_text(name, &nested, &fields, indent));
+            out
+        };
+        self.scope.pop();
+        out
+    }
+
+    /// Adds the fields for the properties of a schema and the schemas it is
+    /// all of, numbering the names of fields that are already present.
+    fn add_fields(
+        &mut self,
+        schema: &'a Schema,
+        nested: &mut Vec<String>,
+        indent: &str,
+        fields: &mut Vec<(String, String, String)>,
+    ) {
+        for part in &schema.all_of {
+            let part = match &part.oneof {
+                Some(schema_or_reference::Oneof::Reference(reference)) => self.doc.schema_for_ref(&reference.r#ref),
+                Some(schema_or_reference::Oneof::Schema(schema)) => Some(schema.as_ref()),
+                None => None,
+            };
+            if let Some(part) = part {
+                self.add_fields(part, nested, indent, fields);
+            }
+        }
+        for property in schema.properties.iter().flat_map(|properties| &properties.additional_properties) {
+            let Some(value) = &property.value else {
+                continue;
+            };
+            let field_type = self.field_type(value, &property.name, nested, indent);
+            let description = match &value.oneof {
+                Some(schema_or_reference::Oneof::Schema(schema)) => schema.description.clone(),
+                _ => String::new(),
+            };
+            let used: HashSet<String> = fields.iter().map(|(field, _, _)| field.clone()).collect();
+            let field = unique_name(snake_case(&property.name), "_", &used);
+            self.rename(ProtoRenameKind::Field, &property.name, &field);
+            fields.push((field, field_type, description));
+        }
+    }
+
+    /// Returns an enum with a value for each string of a schema's enum, after
+    /// an unspecified zero value; values are prefixed with the enum name, as
+    /// their names are scoped to the enclosing package or message.
+    fn enum_text(&mut self, name: &str, schema: &Schema, indent: &str) -> String {
+        let prefix = constant_case(name);
+        let mut out = String::new();
+        write_comment(&mut out, indent, &schema.description);
+        let _ = writeln!(out, "{}enum {} {{", indent, name);
+        let _ = writeln!(out, "{}  {}_UNSPECIFIED = 0;", indent, prefix);
+        let values = schema.r#enum.iter().filter_map(|value| match serde_yaml::from_str(&value.yaml) {
+            Ok(Yaml::String(value)) => Some(value),
+            _ => None,
+        });
+        let mut used = HashSet::from([format!("{}_UNSPECIFIED", prefix)]);
+        for (number, value) in values.enumerate() {
+            let constant = unique_name(format!("{}_{}", prefix, constant_case(&value)), "_", &used);
+            if constant[prefix.len() + 1..] != value {
+                self.rename(ProtoRenameKind::EnumValue, &value, &constant);
+            }
+            let _ = writeln!(out, "{}  {} = {};", indent, constant, number + 1);
+            used.insert(constant);
+        }
+        let _ = writeln!(out, "{}}}", indent);
+        out
+    }
+
+    /// Returns the type of a field holding values of a schema, adding nested
+    /// definitions for inline objects and enums.
+    fn field_type(
+        &mut self,
+        schema: &'a SchemaOrReference,
+        name: &str,
+        nested: &mut Vec<String>,
+        indent: &str,
+    ) -> String {
+        let schema = match &schema.oneof {
+            Some(schema_or_reference::Oneof::Schema(schema)) => schema,
+            Some(schema_or_reference::Oneof::Reference(reference)) => {
+                return self.message_name(reference_name(&reference.r#ref));
+            }
+            None => return self.value_type("google.protobuf.Value"),
+        };
+        let is_enum = !schema.r#enum.is_empty() && schema.r#type == "string";
+        if schema.properties.is_some() || !schema.all_of.is_empty() || is_enum {
+            let type_name = upper_camel_case(name);
+            nested.push(self.definition(&type_name, schema, indent));
+            return type_name;
+        }
+        match schema.r#type.as_str() {
+            "string" if schema.format == "byte" || schema.format == "binary" => "bytes".to_string(),
+            "string" => "string".to_string(),
+            "integer" if schema.format == "int64" => "int64".to_string(),
+            "integer" => "int32".to_string(),
+            "number" if schema.format == "float" => "float".to_string(),
+            "number" => "double".to_string(),
+            "boolean" => "bool".to_string(),
+            "array" => {
+                let items = schema.items.as_ref().and_then(|items| items.schema_or_reference.first());
+                match items.map(|items| self.field_type(items, name, nested, indent)) {
+                    Some(item) if !item.starts_with("repeated ") && !item.starts_with("map<") => {
+                        format!("repeated {}", item)
+                    }
+                    Some(_) => format!("repeated {}", self.value_type("google.protobuf.ListValue")),
+                    None => format!("repeated {}", self.value_type("google.protobuf.Value")),
+                }
+            }
+            "object" => {
+                let values = match schema.additional_properties.as_deref() {
+                    Some(AdditionalPropertiesItem {
+                        oneof: Some(additional_properties_item::Oneof::SchemaOrReference(values)),
+                    }) => Some(self.field_type(values, name, nested, indent)),
+                    _ => None,
+                };
+                match values {
+                    Some(values) if !values.starts_with("repeated ") && !values.starts_with("map<") => {
+                        format!("map<string, {}>", values)
+                    }
+                    _ => self.value_type("google.protobuf.Struct"),
+                }
+            }
+            _ => self.value_type("google.protobuf.Value"),
+        }
+    }
+
+    /// Returns a well-known type for dynamic values, importing its definition.
+    fn value_type(&mut self, name: &str) -> String {
+        self.imports.insert("google/protobuf/struct.proto");
+        name.to_string()
+    }
+
+    fn parameter(&self, parameter: &'a ParameterOrReference) -> Option<&'a Parameter> {
+        match parameter.oneof.as_ref()? {
+            parameter_or_reference::Oneof::Parameter(parameter) => Some(parameter),
+            parameter_or_reference::Oneof::Reference(reference) => self.doc.parameter_for_ref(&reference.r#ref),
+        }
+    }
+
+    fn request_body(&self, operation: &'a Operation) -> Option<&'a RequestBody> {
+        match operation.request_body.as_ref()?.oneof.as_ref()? {
+            request_body_or_reference::Oneof::RequestBody(request_body) => Some(request_body),
+            request_body_or_reference::Oneof::Reference(reference) => self.doc.request_body_for_ref(&reference.r#ref),
+        }
+    }
+
+    /// Returns the schema of the first successful response with content.
+    fn response_schema(&self, operation: &'a Operation) -> Option<&'a SchemaOrReference> {
+        let responses = &operation.responses.as_ref()?.response_or_reference;
+        responses.iter().filter(|named| named.name.starts_with('2')).find_map(|named| {
+            let response = match named.value.as_ref()?.oneof.as_ref()? {
+                response_or_reference::Oneof::Response(response) => response,
+                response_or_reference::Oneof::Reference(reference) => self.doc.response_for_ref(&reference.r#ref)?,
+            };
+            response.content.as_ref().and_then(|content| first_media_schema(content))
+        })
+    }
+}
+
+fn component_schemas(doc: &Document) -> impl Iterator<Item = (&str, &SchemaOrReference)> {
+    let schemas = doc.components.as_ref().and_then(|components| components.schemas.as_ref());
+    schemas
+        .into_iter()
+        .flat_map(|schemas| &schemas.additional_properties)
+        .filter_map(|named| Some((named.name.as_str(), named.value.as_ref()?)))
+}
+
+fn first_schema(request_body: &RequestBody) -> Option<&SchemaOrReference> {
+    first_media_schema(request_body.content.as_ref()?)
+}
+
+fn first_media_schema(content: &MediaTypes) -> Option<&SchemaOrReference> {
+    content.additional_properties.first()?.value.as_ref()?.schema.as_ref()
+}
+
+/// Returns the last segment of a reference, which names the referenced component.
+fn reference_name(reference: &str) -> &str {
+    reference.rsplit('/').next().unwrap_or(reference)
+}
+
+fn message_text(name: &str, nested: &[String], fields: &[(String, String, String)], indent: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}message {} {{", indent, name);
+    for definition in nested {
+        out.push_str(definition);
+    }
+    for (number, (field, field_type, description)) in fields.iter().enumerate() {
+        write_comment(&mut out, &format!("{}  ", indent), description);
+        let _ = writeln!(out, "{}  {} {} = {};", indent, field_type, field, number + 1);
+    }
+    let _ = writeln!(out, "{}}}", indent);
+    out
+}
+
+fn write_comment(out: &mut String, indent: &str, text: &str) {
+    for line in text.trim().lines().map(str::trim_end) {
+        if line.is_empty() {
+            let _ = writeln!(out, "{}//", indent);
+        } else {
+            let _ = writeln!(out, "{}// {}", indent, line);
+        }
+    }
+}
+
+/// Splits a name into words at non-alphanumeric characters and case changes.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            words.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+        } else {
+            if c.is_ascii_uppercase() && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit()) {
+                words.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+            }
+            current.push(c);
+        }
+        previous = Some(c);
+    }
+    words.extend((!current.is_empty()).then_some(current));
+    words
+}
+
+/// Converts a name to an UpperCamelCase message, enum or service name.
+fn upper_camel_case(name: &str) -> String {
+    let mut result: String = words(name)
+        .iter()
+        .map(|word| word[..1].to_ascii_uppercase() + &word[1..].to_ascii_lowercase())
+        .collect();
+    if !result.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        result.insert(0, 'X');
+    }
+    result
+}
+
+/// Converts a name to a snake_case field name.
+fn snake_case(name: &str) -> String {
+    let mut result = words(name).join("_").to_ascii_lowercase();
+    if !result.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        result.insert(0, 'x');
+    }
+    result
+}
+
+/// Converts a name to a CONSTANT_CASE enum value name.
+fn constant_case(name: &str) -> String {
+    snake_case(name).to_ascii_uppercase()
+}
+
+/// Returns a name that is not used, appending a number after `separator` if
+/// the name is.
+fn unique_name(name: String, separator: &str, used: &HashSet<String>) -> String {
+    if !used.contains(&name) {
+        return name;
+    }
+    (2..).map(|n| format!("{}{}{}", name, separator, n)).find(|candidate| !used.contains(candidate)).unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parse_document;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(upper_camel_case("listPets"), "ListPets");
+        assert_eq!(upper_camel_case("get /pets/{petId}"), "GetPetsPetId");
+        assert_eq!(upper_camel_case("2fa"), "X2fa");
+        assert_eq!(snake_case("petId"), "pet_id");
+        assert_eq!(snake_case("X-Request-ID"), "x_request_id");
+        assert_eq!(constant_case("in-stock"), "IN_STOCK");
+    }
+
+    #[test]
+    fn test_generate_messages() {
+        let doc = parse_document(
+            br##"
+openapi: 3.0.3
+info: {title: Pets, version: 1.0.0}
+paths: {}
+components:
+  schemas:
+    pet-status: {type: string, enum: [in-stock, in_stock, SOLD]}
+    PetStatus: {type: string, enum: [a]}
+    Pet:
+      type: object
+      properties:
+        petId: {type: string}
+        pet_id: {type: integer}
+        status: {$ref: "#/components/schemas/pet-status"}
+        labels: {type: object, additionalProperties: {type: string}}
+"##,
+        )
+        .unwrap();
+        let messages = generate_messages(&doc, "pets.v1");
+        assert_eq!(
+            messages.proto,
+            r#"syntax = "proto3";
+
+package pets.v1;
+
+enum PetStatus {
+  PET_STATUS_UNSPECIFIED = 0;
+  PET_STATUS_IN_STOCK = 1;
+  PET_STATUS_IN_STOCK_2 = 2;
+  PET_STATUS_SOLD = 3;
+}
+
+enum PetStatus2 {
+  PET_STATUS2_UNSPECIFIED = 0;
+  PET_STATUS2_A = 1;
+}
+
+message Pet {
+  string pet_id = 1;
+  int32 pet_id_2 = 2;
+  PetStatus status = 3;
+  map<string, string> labels = 4;
+}
+"#
+        );
+        let renames: Vec<String> = messages.renames.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            renames,
+            [
+                r#"definition "pet-status" is PetStatus"#,
+                r#"enum value "in-stock" is PetStatus.PET_STATUS_IN_STOCK"#,
+                r#"enum value "in_stock" is PetStatus.PET_STATUS_IN_STOCK_2"#,
+                r#"definition "PetStatus" is PetStatus2"#,
+                r#"enum value "a" is PetStatus2.PET_STATUS2_A"#,
+                r#"field "petId" is Pet.pet_id"#,
+                r#"field "pet_id" is Pet.pet_id_2"#,
+            ]
+        );
+        assert_eq!(messages.renames[0].kind, ProtoRenameKind::Definition);
+    }
+
+    #[test]
+    fn test_generate_proto() {
+        let doc = parse_document(
+            br##"
+openapi: 3.0.3
+info:
+  title: Pet Store
+  version: 1.0.0
+  description: Pets for sale.
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      summary: List pets.
+      parameters:
+        - {name: limit, in: query, description: Page size., schema: {type: integer}}
+      responses:
+        "200":
+          description: Pets
+          content:
+            application/json:
+              schema: {type: array, items: {$ref: "#/components/schemas/Pet"}}
+    post:
+      operationId: createPet
+      requestBody:
+        content:
+          application/json:
+            schema: {$ref: "#/components/schemas/Pet"}
+      responses:
+        "201":
+          description: Created
+          content:
+            application/json:
+              schema: {$ref: "#/components/schemas/Pet"}
+  /pets/{petId}:
+    parameters:
+      - $ref: "#/components/parameters/petId"
+    delete:
+      operationId: deletePet
+      responses:
+        "204": {description: Deleted}
+components:
+  parameters:
+    petId: {name: petId, in: path, required: true, schema: {type: string}}
+  schemas:
+    Pet:
+      type: object
+      description: A pet.
+      properties:
+        id: {type: integer, format: int64}
+        tags: {type: array, items: {type: string}}
+        status: {type: string, enum: [available, sold]}
+        owner:
+          type: object
+          properties:
+            name: {type: string}
+"##,
+        )
+        .unwrap();
+        assert_eq!(
+            generate_proto(&doc, "pets.v1"),
+            r#"syntax = "proto3";
+
+package pets.v1;
+
+import "google/api/annotations.proto";
+import "google/protobuf/empty.proto";
+
+// Pets for sale.
+service PetStoreService {
+  // List pets.
+  rpc ListPets(ListPetsRequest) returns (ListPetsResponse) {
+    option (google.api.http) = {
+      get: "/pets"
+      response_body: "items"
+    };
+  }
+
+  rpc CreatePet(CreatePetRequest) returns (Pet) {
+    option (google.api.http) = {
+      post: "/pets"
+      body: "pet"
+    };
+  }
+
+  rpc DeletePet(DeletePetRequest) returns (google.protobuf.Empty) {
+    option (google.api.http) = {
+      delete: "/pets/{pet_id}"
+    };
+  }
+}
+
+message ListPetsRequest {
+  // Page size.
+  int32 limit = 1;
+}
+
+message ListPetsResponse {
+  repeated Pet items = 1;
+}
+
+message CreatePetRequest {
+  Pet pet = 1;
+}
+
+message DeletePetRequest {
+  string pet_id = 1;
+}
+
+// A pet.
+message Pet {
+  enum Status {
+    STATUS_UNSPECIFIED = 0;
+    STATUS_AVAILABLE = 1;
+    STATUS_SOLD = 2;
+  }
+  message Owner {
+    string name = 1;
+  }
+  int64 id = 1;
+  repeated string tags = 2;
+  Status status = 3;
+  Owner owner = 4;
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,437 @@
+//! Reference resolution for OpenAPI v3 documents.
+
+use gnostic_compiler::{
+    bundle_references, flatten_references, resolve_references, Context, CycleStrategy, ErrorGroup,
+    FetchPolicy, ParseOptions, RefSiblings, ResolutionReport, ResolveOptions, ToYaml,
+};
+use std::sync::Arc;
+
+use crate::openapi_v3::Document;
+use crate::parser::Parser;
+
+/// Resolves the $ref values of a document, producing a dereferenced copy.
+///
+/// Internal references ("#/components/...") are resolved within the document
+/// and external ones ("pet.yaml#/Pet", URLs) are read relative to the base.
+/// Circular references, such as a schema that refers to itself, are left in
+/// place and reported as warnings. For OpenAPI 3.1 documents the summary and
+/// description next to a $ref replace those of its target.
+///
+/// Remote documents are only fetched when the [`FetchPolicy`] allows them,
+/// which by default it does not.
+#[derive(Debug, Clone, Default)]
+pub struct Resolver {
+    base: String,
+    cycle_strategy: CycleStrategy,
+    ref_siblings: Option<RefSiblings>,
+    fetch_policy: FetchPolicy,
+}
+
+impl Resolver {
+    /// Creates a resolver for documents read from memory, for which relative
+    /// external references are resolved against the current directory.
+    pub fn new() -> Self {
+        Resolver::default()
+    }
+
+    /// Returns the resolver with the file or URL the document was read from,
+    /// used to locate relative external references. For documents fetched
+    /// over HTTP this is the document URL, not the current directory.
+    pub fn with_base(mut self, base: impl Into<String>) -> Self {
+        self.base = base.into();
+        self
+    }
+
+    /// Returns the resolver with the strategy [`flatten`](Self::flatten) uses
+    /// to break circular references.
+    pub fn with_cycle_strategy(mut self, cycle_strategy: CycleStrategy) -> Self {
+        self.cycle_strategy = cycle_strategy;
+        self
+    }
+
+    /// Returns the resolver with the treatment of $ref siblings fixed, rather
+    /// than following the version the document declares.
+    pub fn with_ref_siblings(mut self, ref_siblings: RefSiblings) -> Self {
+        self.ref_siblings = Some(ref_siblings);
+        self
+    }
+
+    /// Returns the resolver with the policy for fetching remote documents.
+    pub fn with_fetch_policy(mut self, fetch_policy: FetchPolicy) -> Self {
+        self.fetch_policy = fetch_policy;
+        self
+    }
+
+    /// Returns the document with every reference replaced by its target.
+    ///
+    /// Fails with the unresolved references if any reference cannot be resolved.
+    pub fn resolve(&self, document: &Document) -> Result<Document, ErrorGroup> {
+        let (resolved, report) = self.resolve_with_report(document)?;
+        if report.is_complete() {
+            Ok(resolved)
+        } else {
+            Err(ErrorGroup::new(report.errors))
+        }
+    }
+
+    /// Returns the document with the references that could be resolved
+    /// replaced by their targets, and a report of the resolution.
+    pub fn resolve_with_report(
+        &self,
+        document: &Document,
+    ) -> Result<(Document, ResolutionReport), ErrorGroup> {
+        let options = self.options(document);
+        let (node, report) = resolve_references(&document.to_yaml(), &options);
+        let resolved = parse(&node, options.ref_siblings)?;
+        Ok((resolved, report))
+    }
+
+    /// Returns the document with every reference inlined, for consumers that
+    /// cannot handle references at all.
+    ///
+    /// Unlike [`resolve`](Self::resolve), no references are left: circular
+    /// ones are broken with the configured [`CycleStrategy`] and reported as
+    /// warnings by [`flatten_with_report`](Self::flatten_with_report).
+    pub fn flatten(&self, document: &Document) -> Result<Document, ErrorGroup> {
+        let (flattened, report) = self.flatten_with_report(document)?;
+        if report.is_complete() {
+            Ok(flattened)
+        } else {
+            Err(ErrorGroup::new(report.errors))
+        }
+    }
+
+    /// Returns the flattened document and a report of the flattening.
+    pub fn flatten_with_report(
+        &self,
+        document: &Document,
+    ) -> Result<(Document, ResolutionReport), ErrorGroup> {
+        let options = self.options(document);
+        let (node, report) = flatten_references(&document.to_yaml(), &options, self.cycle_strategy);
+        let flattened = parse(&node, options.ref_siblings)?;
+        Ok((flattened, report))
+    }
+
+    /// Returns the document with the targets of external references copied
+    /// into its components, producing a single self-contained document.
+    ///
+    /// Fails with the unresolved references if any reference cannot be resolved.
+    pub fn bundle(&self, document: &Document) -> Result<Document, ErrorGroup> {
+        let (bundled, report) = self.bundle_with_report(document)?;
+        if report.is_complete() {
+            Ok(bundled)
+        } else {
+            Err(ErrorGroup::new(report.errors))
+        }
+    }
+
+    /// Returns the bundled document and a report of the bundling.
+    ///
+    /// Each target is added to the components section for the kind of
+    /// object referenced (schemas, responses, parameters, ...) and its
+    /// references are rewritten to point there. Path items have no components
+    /// section in OpenAPI 3.0, so they are inlined.
+    pub fn bundle_with_report(
+        &self,
+        document: &Document,
+    ) -> Result<(Document, ResolutionReport), ErrorGroup> {
+        let options = self.options(document);
+        let (node, report) = bundle_references(&document.to_yaml(), &options, |location| {
+            components_section(location).map(|section| vec!["components".to_string(), section.to_string()])
+        });
+        let bundled = parse(&node, options.ref_siblings)?;
+        Ok((bundled, report))
+    }
+
+    fn options(&self, document: &Document) -> ResolveOptions {
+        let ref_siblings = self
+            .ref_siblings
+            .unwrap_or_else(|| RefSiblings::for_version(&document.openapi));
+        ResolveOptions::new()
+            .with_base(self.base.clone())
+            .with_ref_siblings(ref_siblings)
+            .with_fetch_policy(self.fetch_policy.clone())
+    }
+}
+
+/// Parses a resolved document, keeping $ref siblings as they were resolved.
+pub(crate) fn parse(node: &serde_yaml::Value, siblings: RefSiblings) -> Result<Document, ErrorGroup> {
+    let options = ParseOptions::new().with_ref_siblings(siblings);
+    let context = Arc::new(Context::root_with_options("$", options));
+    Parser::parse_document(node, &context)
+}
+
+/// The sections of the components object.
+pub(crate) const COMPONENT_SECTIONS: &[&str] = &[
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "callbacks",
+];
+
+/// Returns the components section for an object referenced at `location`, or
+/// None for path items.
+fn components_section(location: &[String]) -> Option<&'static str> {
+    let keys: Vec<&str> = location.iter().map(String::as_str).collect();
+    // Everything below a schema is a schema, whatever its property names are.
+    let in_schema = keys.iter().enumerate().any(|(i, key)| {
+        *key == "schema" || (*key == "schemas" && i > 0 && keys[i - 1] == "components")
+    });
+    if in_schema {
+        return Some("schemas");
+    }
+    let section = match keys.as_slice() {
+        ["paths", _] => return None,
+        [.., "components", section, _] => section,
+        [.., "parameters", _] => "parameters",
+        [.., "requestBody"] => "requestBodies",
+        [.., "responses", _] => "responses",
+        [.., "headers", _] => "headers",
+        [.., "examples", _] => "examples",
+        [.., "links", _] => "links",
+        [.., "callbacks", _] => "callbacks",
+        _ => "schemas",
+    };
+    COMPONENT_SECTIONS.iter().find(|s| **s == section).copied().or(Some("schemas"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parse_document;
+    use crate::openapi_v3::*;
+
+    #[test]
+    fn test_resolve_internal_references() {
+        let source = br#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: A pet
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        owner:
+          $ref: '#/components/schemas/Owner'
+    Owner:
+      type: string
+"#;
+        let doc = parse_document(source).unwrap();
+        let resolved = Resolver::new().resolve(&doc).unwrap();
+
+        let schemas = &resolved.components.as_ref().unwrap().schemas.as_ref().unwrap().additional_properties;
+        let pet = match schemas[0].value.as_ref().unwrap().oneof.as_ref().unwrap() {
+            schema_or_reference::Oneof::Schema(s) => s,
+            other => panic!("unexpected {:?}", other),
+        };
+        let owner = &pet.properties.as_ref().unwrap().additional_properties[0];
+        match owner.value.as_ref().unwrap().oneof.as_ref().unwrap() {
+            schema_or_reference::Oneof::Schema(s) => assert_eq!(s.r#type, "string"),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_resolve_report() {
+        let source = br#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: "1.0"
+components:
+  schemas:
+    Pet:
+      $ref: '#/components/schemas/Missing'
+"#;
+        let doc = parse_document(source).unwrap();
+        let (resolved, report) = Resolver::new().resolve_with_report(&doc).unwrap();
+        assert_eq!(resolved, doc);
+        assert_eq!(report.errors.len(), 1);
+        assert!(Resolver::new().resolve(&doc).is_err());
+    }
+
+    #[test]
+    fn test_components_section() {
+        let section = |keys: &[&str]| components_section(&keys.iter().map(|k| k.to_string()).collect::<Vec<_>>());
+        assert_eq!(section(&["paths", "/pets"]), None);
+        assert_eq!(section(&["paths", "/pets", "get", "parameters", "0"]), Some("parameters"));
+        assert_eq!(section(&["paths", "/pets", "get", "responses", "200"]), Some("responses"));
+        assert_eq!(section(&["paths", "/pets", "get", "requestBody"]), Some("requestBodies"));
+        assert_eq!(section(&["paths", "/pets", "get", "parameters", "0", "schema"]), Some("schemas"));
+        assert_eq!(section(&["components", "schemas", "Pet", "properties", "responses", "x"]), Some("schemas"));
+        assert_eq!(section(&["components", "responses", "Error"]), Some("responses"));
+        assert_eq!(section(&["components", "responses", "Error", "headers", "X-Rate"]), Some("headers"));
+    }
+
+    #[test]
+    fn test_resolve_circular_references() {
+        let source = br#"
+openapi: 3.0.0
+info:
+  title: Trees
+  version: "1.0"
+components:
+  schemas:
+    Node:
+      type: object
+      properties:
+        children:
+          type: array
+          items:
+            $ref: '#/components/schemas/Node'
+"#;
+        let doc = parse_document(source).unwrap();
+        let (resolved, report) = Resolver::new().resolve_with_report(&doc).unwrap();
+        assert_eq!(resolved, doc);
+        assert_eq!(report.cycles.len(), 1);
+        assert_eq!(
+            report.cycles[0].to_string(),
+            "warning: $.components.schemas.Node.properties.children.items circular reference #/components/schemas/Node"
+        );
+        assert!(Resolver::new().resolve(&doc).is_ok());
+    }
+
+    #[test]
+    fn test_flatten() {
+        let source = br#"
+openapi: 3.0.0
+info:
+  title: Trees
+  version: "1.0"
+components:
+  schemas:
+    Node:
+      type: object
+      properties:
+        name:
+          $ref: '#/components/schemas/Name'
+        parent:
+          $ref: '#/components/schemas/Node'
+    Name:
+      type: string
+"#;
+        let doc = parse_document(source).unwrap();
+        let flattened = Resolver::new().flatten(&doc).unwrap();
+        let text = String::from_utf8(crate::document::yaml_value(&flattened)).unwrap();
+        assert!(!text.contains("$ref"), "{}", text);
+
+        let schemas = &flattened.components.as_ref().unwrap().schemas.as_ref().unwrap().additional_properties;
+        let node = match schemas[0].value.as_ref().unwrap().oneof.as_ref().unwrap() {
+            schema_or_reference::Oneof::Schema(s) => s,
+            other => panic!("unexpected {:?}", other),
+        };
+        let properties = &node.properties.as_ref().unwrap().additional_properties;
+        let parent = match properties[1].value.as_ref().unwrap().oneof.as_ref().unwrap() {
+            schema_or_reference::Oneof::Schema(s) => s,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(**parent, Schema::default());
+
+        let unrolled = Resolver::new()
+            .with_cycle_strategy(CycleStrategy::Unroll(2))
+            .flatten(&doc)
+            .unwrap();
+        let text = String::from_utf8(crate::document::yaml_value(&unrolled)).unwrap();
+        assert_eq!(text.matches("parent:").count(), 3, "{}", text);
+    }
+
+    #[test]
+    fn test_ref_siblings() {
+        let source = |version: &str| {
+            format!(
+                "openapi: {}\ninfo:\n  title: Pets\n  version: \"1.0\"\ncomponents:\n  schemas:\n    Pet:\n      $ref: '#/components/schemas/Animal'\n      description: A pet\n    Animal:\n      type: object\n      description: An animal\n",
+                version
+            )
+        };
+        let pet = |doc: &Document| {
+            let schemas = &doc.components.as_ref().unwrap().schemas.as_ref().unwrap().additional_properties;
+            schemas[0].value.as_ref().unwrap().oneof.clone().unwrap()
+        };
+
+        let v30 = parse_document(source("3.0.3").as_bytes()).unwrap();
+        match pet(&v30) {
+            schema_or_reference::Oneof::Reference(r) => assert_eq!(r.description, ""),
+            other => panic!("unexpected {:?}", other),
+        }
+        match pet(&Resolver::new().resolve(&v30).unwrap()) {
+            schema_or_reference::Oneof::Schema(s) => assert_eq!(s.description, "An animal"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let v31 = parse_document(source("3.1.0").as_bytes()).unwrap();
+        match pet(&v31) {
+            schema_or_reference::Oneof::Reference(r) => assert_eq!(r.description, "A pet"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match pet(&Resolver::new().resolve(&v31).unwrap()) {
+            schema_or_reference::Oneof::Schema(s) => assert_eq!(s.description, "A pet"),
+            other => panic!("unexpected {:?}", other),
+        }
+        let ignored = Resolver::new().with_ref_siblings(RefSiblings::Ignore).resolve(&v31).unwrap();
+        match pet(&ignored) {
+            schema_or_reference::Oneof::Schema(s) => assert_eq!(s.description, "An animal"),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fetch_policy() {
+        let source = br#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: "1.0"
+components:
+  schemas:
+    Pet:
+      $ref: 'http://localhost:1/pet.yaml#/Pet'
+"#;
+        let doc = parse_document(source).unwrap();
+        let err = Resolver::new().resolve(&doc).unwrap_err();
+        assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::FetchDenied);
+
+        // An allowed URL is fetched, and here fails to connect instead
+        let policy = FetchPolicy::new().allow_scheme("http").allow_host("localhost");
+        let err = Resolver::new().with_fetch_policy(policy).resolve(&doc).unwrap_err();
+        assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::UnresolvedReference);
+    }
+
+    #[test]
+    fn test_resolve_escaped_pointer() {
+        let source = br#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: "1.0"
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      responses:
+        '200':
+          description: A pet
+  /animals/{id}:
+    $ref: '#/paths/~1pets~1%7Bid%7D'
+"#;
+        let doc = parse_document(source).unwrap();
+        let resolved = Resolver::new().resolve(&doc).unwrap();
+        let paths = &resolved.paths.as_ref().unwrap().path;
+        assert_eq!(paths[1].name, "/animals/{id}");
+        let animal = paths[1].value.as_ref().unwrap();
+        assert_eq!(animal.r#ref, "");
+        assert_eq!(animal.get.as_ref().unwrap().operation_id, "getPet");
+    }
+}
@@ -0,0 +1,904 @@
+//! YAML emission for OpenAPI v3 models.
+//!
+//! Keys are written in the order fields are declared in openapiv3.proto, and
+//! named collections (paths, properties, extensions, ...) are written as
+//! mapping entries in their stored order, or sorted by name when
+//! [`KeyOrder::Canonical`] is requested.
+
+use gnostic_compiler::writer::*;
+use serde_yaml::{Mapping, Value as Yaml};
+
+use crate::openapi_v3::*;
+
+impl ToYaml for AdditionalPropertiesItem {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(additional_properties_item::Oneof::SchemaOrReference(v)) => v.to_yaml_with(options),
+            Some(additional_properties_item::Oneof::Boolean(v)) => Yaml::Bool(*v),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for Any {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        yaml_for_any_source(&self.yaml)
+    }
+}
+
+impl ToYaml for AnyOrExpression {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(any_or_expression::Oneof::Any(v)) => v.to_yaml_with(options),
+            Some(any_or_expression::Oneof::Expression(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for Callback {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.path, options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for CallbackOrReference {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(callback_or_reference::Oneof::Callback(v)) => v.to_yaml_with(options),
+            Some(callback_or_reference::Oneof::Reference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for CallbacksOrReferences {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Components {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_message(&mut map, "schemas", self.schemas.as_ref(), options);
+        insert_message(&mut map, "responses", self.responses.as_ref(), options);
+        insert_message(&mut map, "parameters", self.parameters.as_ref(), options);
+        insert_message(&mut map, "examples", self.examples.as_ref(), options);
+        insert_message(&mut map, "requestBodies", self.request_bodies.as_ref(), options);
+        insert_message(&mut map, "headers", self.headers.as_ref(), options);
+        insert_message(&mut map, "securitySchemes", self.security_schemes.as_ref(), options);
+        insert_message(&mut map, "links", self.links.as_ref(), options);
+        insert_message(&mut map, "callbacks", self.callbacks.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Contact {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "url", &self.url);
+        insert_string(&mut map, "email", &self.email);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for DefaultType {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(default_type::Oneof::Number(v)) => new_scalar_node_for_number(*v),
+            Some(default_type::Oneof::Boolean(v)) => Yaml::Bool(*v),
+            Some(default_type::Oneof::String(v)) => Yaml::String(v.clone()),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for Discriminator {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "propertyName", &self.property_name);
+        insert_message(&mut map, "mapping", self.mapping.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Document {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "openapi", &self.openapi);
+        insert_message(&mut map, "info", self.info.as_ref(), options);
+        insert_messages(&mut map, "servers", &self.servers, options);
+        insert_message(&mut map, "paths", self.paths.as_ref(), options);
+        insert_message(&mut map, "components", self.components.as_ref(), options);
+        insert_messages(&mut map, "security", &self.security, options);
+        insert_messages(&mut map, "tags", &self.tags, options);
+        insert_message(&mut map, "externalDocs", self.external_docs.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Encoding {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "contentType", &self.content_type);
+        insert_message(&mut map, "headers", self.headers.as_ref(), options);
+        insert_string(&mut map, "style", &self.style);
+        insert_bool(&mut map, "explode", self.explode);
+        insert_bool(&mut map, "allowReserved", self.allow_reserved);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Encodings {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Example {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "summary", &self.summary);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "value", self.value.as_ref(), options);
+        insert_string(&mut map, "externalValue", &self.external_value);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ExampleOrReference {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(example_or_reference::Oneof::Example(v)) => v.to_yaml_with(options),
+            Some(example_or_reference::Oneof::Reference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for ExamplesOrReferences {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Expression {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ExternalDocs {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "url", &self.url);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Header {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "description", &self.description);
+        insert_bool(&mut map, "required", self.required);
+        insert_bool(&mut map, "deprecated", self.deprecated);
+        insert_bool(&mut map, "allowEmptyValue", self.allow_empty_value);
+        insert_string(&mut map, "style", &self.style);
+        insert_bool(&mut map, "explode", self.explode);
+        insert_bool(&mut map, "allowReserved", self.allow_reserved);
+        insert_message(&mut map, "schema", self.schema.as_ref(), options);
+        insert_message(&mut map, "example", self.example.as_ref(), options);
+        insert_message(&mut map, "examples", self.examples.as_ref(), options);
+        insert_message(&mut map, "content", self.content.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for HeaderOrReference {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(header_or_reference::Oneof::Header(v)) => v.to_yaml_with(options),
+            Some(header_or_reference::Oneof::Reference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for HeadersOrReferences {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Info {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "title", &self.title);
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "termsOfService", &self.terms_of_service);
+        insert_message(&mut map, "contact", self.contact.as_ref(), options);
+        insert_message(&mut map, "license", self.license.as_ref(), options);
+        insert_string(&mut map, "version", &self.version);
+        insert_named(&mut map, &self.specification_extension, options);
+        insert_string(&mut map, "summary", &self.summary);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ItemsItem {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        // A single items schema is written inline rather than as a one-element list
+        match self.schema_or_reference.as_slice() {
+            [item] => item.to_yaml_with(options),
+            items => sequence_node(items, options),
+        }
+    }
+}
+
+impl ToYaml for License {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "url", &self.url);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Link {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "operationRef", &self.operation_ref);
+        insert_string(&mut map, "operationId", &self.operation_id);
+        insert_message(&mut map, "parameters", self.parameters.as_ref(), options);
+        insert_message(&mut map, "requestBody", self.request_body.as_ref(), options);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "server", self.server.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for LinkOrReference {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(link_or_reference::Oneof::Link(v)) => v.to_yaml_with(options),
+            Some(link_or_reference::Oneof::Reference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for LinksOrReferences {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for MediaType {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_message(&mut map, "schema", self.schema.as_ref(), options);
+        insert_message(&mut map, "example", self.example.as_ref(), options);
+        insert_message(&mut map, "examples", self.examples.as_ref(), options);
+        insert_message(&mut map, "encoding", self.encoding.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for MediaTypes {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for OauthFlow {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "authorizationUrl", &self.authorization_url);
+        insert_string(&mut map, "tokenUrl", &self.token_url);
+        insert_string(&mut map, "refreshUrl", &self.refresh_url);
+        insert_message(&mut map, "scopes", self.scopes.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for OauthFlows {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_message(&mut map, "implicit", self.implicit.as_ref(), options);
+        insert_message(&mut map, "password", self.password.as_ref(), options);
+        insert_message(&mut map, "clientCredentials", self.client_credentials.as_ref(), options);
+        insert_message(&mut map, "authorizationCode", self.authorization_code.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Object {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Operation {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_strings(&mut map, "tags", &self.tags);
+        insert_string(&mut map, "summary", &self.summary);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "externalDocs", self.external_docs.as_ref(), options);
+        insert_string(&mut map, "operationId", &self.operation_id);
+        insert_messages(&mut map, "parameters", &self.parameters, options);
+        insert_message(&mut map, "requestBody", self.request_body.as_ref(), options);
+        insert_message(&mut map, "responses", self.responses.as_ref(), options);
+        insert_message(&mut map, "callbacks", self.callbacks.as_ref(), options);
+        insert_bool(&mut map, "deprecated", self.deprecated);
+        insert_messages(&mut map, "security", &self.security, options);
+        insert_messages(&mut map, "servers", &self.servers, options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Parameter {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "in", &self.r#in);
+        insert_string(&mut map, "description", &self.description);
+        insert_bool(&mut map, "required", self.required);
+        insert_bool(&mut map, "deprecated", self.deprecated);
+        insert_bool(&mut map, "allowEmptyValue", self.allow_empty_value);
+        insert_string(&mut map, "style", &self.style);
+        insert_bool(&mut map, "explode", self.explode);
+        insert_bool(&mut map, "allowReserved", self.allow_reserved);
+        insert_message(&mut map, "schema", self.schema.as_ref(), options);
+        insert_message(&mut map, "example", self.example.as_ref(), options);
+        insert_message(&mut map, "examples", self.examples.as_ref(), options);
+        insert_message(&mut map, "content", self.content.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ParameterOrReference {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(parameter_or_reference::Oneof::Parameter(v)) => v.to_yaml_with(options),
+            Some(parameter_or_reference::Oneof::Reference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for ParametersOrReferences {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for PathItem {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "$ref", &self.r#ref);
+        insert_string(&mut map, "summary", &self.summary);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "get", self.get.as_ref(), options);
+        insert_message(&mut map, "put", self.put.as_ref(), options);
+        insert_message(&mut map, "post", self.post.as_ref(), options);
+        insert_message(&mut map, "delete", self.delete.as_ref(), options);
+        insert_message(&mut map, "options", self.options.as_ref(), options);
+        insert_message(&mut map, "head", self.head.as_ref(), options);
+        insert_message(&mut map, "patch", self.patch.as_ref(), options);
+        insert_message(&mut map, "trace", self.trace.as_ref(), options);
+        insert_messages(&mut map, "servers", &self.servers, options);
+        insert_messages(&mut map, "parameters", &self.parameters, options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Paths {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.path, options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Properties {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Reference {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "$ref", &self.r#ref);
+        insert_string(&mut map, "summary", &self.summary);
+        insert_string(&mut map, "description", &self.description);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for RequestBodiesOrReferences {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for RequestBody {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "content", self.content.as_ref(), options);
+        insert_bool(&mut map, "required", self.required);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for RequestBodyOrReference {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(request_body_or_reference::Oneof::RequestBody(v)) => v.to_yaml_with(options),
+            Some(request_body_or_reference::Oneof::Reference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for Response {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "headers", self.headers.as_ref(), options);
+        insert_message(&mut map, "content", self.content.as_ref(), options);
+        insert_message(&mut map, "links", self.links.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ResponseOrReference {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(response_or_reference::Oneof::Response(v)) => v.to_yaml_with(options),
+            Some(response_or_reference::Oneof::Reference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for Responses {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_named(&mut map, &self.response_or_reference, options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ResponsesOrReferences {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Schema {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_bool(&mut map, "nullable", self.nullable);
+        insert_message(&mut map, "discriminator", self.discriminator.as_ref(), options);
+        insert_bool(&mut map, "readOnly", self.read_only);
+        insert_bool(&mut map, "writeOnly", self.write_only);
+        insert_message(&mut map, "xml", self.xml.as_ref(), options);
+        insert_message(&mut map, "externalDocs", self.external_docs.as_ref(), options);
+        insert_message(&mut map, "example", self.example.as_ref(), options);
+        insert_bool(&mut map, "deprecated", self.deprecated);
+        insert_string(&mut map, "title", &self.title);
+        insert_float(&mut map, "multipleOf", self.multiple_of);
+        insert_float(&mut map, "maximum", self.maximum);
+        insert_bool(&mut map, "exclusiveMaximum", self.exclusive_maximum);
+        insert_float(&mut map, "minimum", self.minimum);
+        insert_bool(&mut map, "exclusiveMinimum", self.exclusive_minimum);
+        insert_int(&mut map, "maxLength", self.max_length);
+        insert_int(&mut map, "minLength", self.min_length);
+        insert_string(&mut map, "pattern", &self.pattern);
+        insert_int(&mut map, "maxItems", self.max_items);
+        insert_int(&mut map, "minItems", self.min_items);
+        insert_bool(&mut map, "uniqueItems", self.unique_items);
+        insert_int(&mut map, "maxProperties", self.max_properties);
+        insert_int(&mut map, "minProperties", self.min_properties);
+        insert_strings(&mut map, "required", &self.required);
+        insert_messages(&mut map, "enum", &self.r#enum, options);
+        insert_string(&mut map, "type", &self.r#type);
+        insert_messages(&mut map, "allOf", &self.all_of, options);
+        insert_messages(&mut map, "oneOf", &self.one_of, options);
+        insert_messages(&mut map, "anyOf", &self.any_of, options);
+        insert_message(&mut map, "not", self.not.as_deref(), options);
+        insert_message(&mut map, "items", self.items.as_ref(), options);
+        insert_message(&mut map, "properties", self.properties.as_ref(), options);
+        insert_message(&mut map, "additionalProperties", self.additional_properties.as_deref(), options);
+        insert_message(&mut map, "default", self.default.as_ref(), options);
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "format", &self.format);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for SchemaOrReference {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(schema_or_reference::Oneof::Schema(v)) => v.to_yaml_with(options),
+            Some(schema_or_reference::Oneof::Reference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for SchemasOrReferences {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for SecurityRequirement {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for SecurityScheme {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "type", &self.r#type);
+        insert_string(&mut map, "description", &self.description);
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "in", &self.r#in);
+        insert_string(&mut map, "scheme", &self.scheme);
+        insert_string(&mut map, "bearerFormat", &self.bearer_format);
+        insert_message(&mut map, "flows", self.flows.as_ref(), options);
+        insert_string(&mut map, "openIdConnectUrl", &self.open_id_connect_url);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for SecuritySchemeOrReference {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(security_scheme_or_reference::Oneof::SecurityScheme(v)) => v.to_yaml_with(options),
+            Some(security_scheme_or_reference::Oneof::Reference(v)) => v.to_yaml_with(options),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for SecuritySchemesOrReferences {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Server {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "url", &self.url);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "variables", self.variables.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ServerVariable {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_strings(&mut map, "enum", &self.r#enum);
+        insert_string(&mut map, "default", &self.default);
+        insert_string(&mut map, "description", &self.description);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for ServerVariables {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for SpecificationExtension {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        match &self.oneof {
+            Some(specification_extension::Oneof::Number(v)) => new_scalar_node_for_number(*v),
+            Some(specification_extension::Oneof::Boolean(v)) => Yaml::Bool(*v),
+            Some(specification_extension::Oneof::String(v)) => Yaml::String(v.clone()),
+            None => Yaml::Null,
+        }
+    }
+}
+
+impl ToYaml for StringArray {
+    fn to_yaml_with(&self, _options: &EmitOptions) -> Yaml {
+        Yaml::Sequence(self.value.iter().map(|s| s.as_str().into()).collect())
+    }
+}
+
+impl ToYaml for Strings {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_named(&mut map, &self.additional_properties, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Tag {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "description", &self.description);
+        insert_message(&mut map, "externalDocs", self.external_docs.as_ref(), options);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl ToYaml for Xml {
+    fn to_yaml_with(&self, options: &EmitOptions) -> Yaml {
+        let mut map = Mapping::new();
+        insert_string(&mut map, "name", &self.name);
+        insert_string(&mut map, "namespace", &self.namespace);
+        insert_string(&mut map, "prefix", &self.prefix);
+        insert_bool(&mut map, "attribute", self.attribute);
+        insert_bool(&mut map, "wrapped", self.wrapped);
+        insert_named(&mut map, &self.specification_extension, options);
+        Yaml::Mapping(map)
+    }
+}
+
+impl Named for NamedAny {
+    type Value = Any;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&Any> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedCallbackOrReference {
+    type Value = CallbackOrReference;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&CallbackOrReference> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedEncoding {
+    type Value = Encoding;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&Encoding> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedExampleOrReference {
+    type Value = ExampleOrReference;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&ExampleOrReference> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedHeaderOrReference {
+    type Value = HeaderOrReference;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&HeaderOrReference> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedLinkOrReference {
+    type Value = LinkOrReference;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&LinkOrReference> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedMediaType {
+    type Value = MediaType;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&MediaType> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedParameterOrReference {
+    type Value = ParameterOrReference;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&ParameterOrReference> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedPathItem {
+    type Value = PathItem;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&PathItem> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedRequestBodyOrReference {
+    type Value = RequestBodyOrReference;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&RequestBodyOrReference> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedResponseOrReference {
+    type Value = ResponseOrReference;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&ResponseOrReference> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedSchemaOrReference {
+    type Value = SchemaOrReference;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&SchemaOrReference> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedSecuritySchemeOrReference {
+    type Value = SecuritySchemeOrReference;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&SecuritySchemeOrReference> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedServerVariable {
+    type Value = ServerVariable;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&ServerVariable> {
+        self.value.as_ref()
+    }
+}
+
+impl Named for NamedString {
+    type Value = String;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&String> {
+        Some(&self.value)
+    }
+}
+
+impl Named for NamedStringArray {
+    type Value = StringArray;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Option<&StringArray> {
+        self.value.as_ref()
+    }
+}
@@ -0,0 +1,503 @@
+//! In-place rewriting of OpenAPI v3 documents.
+//!
+//! [`Document::transform`] walks a document like [`Document::accept`] but
+//! passes each object to a [`DocumentTransformer`] mutably, before the objects
+//! nested in it are walked, so that a transform can replace an object and
+//! have its replacement walked. [`Document::rename_component`] renames a
+//! component and updates the references to it.
+//!
+//! [`Document::accept`]: crate::openapi_v3::Document::accept
+
+use gnostic_compiler::escape_json_pointer_segment;
+
+use crate::openapi_v3::*;
+
+/// Receives the objects of a document walked by [`Document::transform`],
+/// which it may modify. Every callback does nothing by default.
+pub trait DocumentTransformer {
+    /// Transforms the document itself, before anything in it, for example to
+    /// add servers or components.
+    fn transform_document(&mut self, _document: &mut Document) {}
+
+    fn transform_info(&mut self, _pointer: &str, _info: &mut Info) {}
+
+    fn transform_server(&mut self, _pointer: &str, _server: &mut Server) {}
+
+    fn transform_tag(&mut self, _pointer: &str, _tag: &mut Tag) {}
+
+    /// Transforms the path item of a path template, such as "/pets/{id}".
+    fn transform_path_item(&mut self, _pointer: &str, _path: &str, _path_item: &mut PathItem) {}
+
+    /// Transforms an operation with its lowercase HTTP method.
+    fn transform_operation(&mut self, _pointer: &str, _method: &str, _operation: &mut Operation) {}
+
+    fn transform_parameter(&mut self, _pointer: &str, _parameter: &mut Parameter) {}
+
+    fn transform_request_body(&mut self, _pointer: &str, _request_body: &mut RequestBody) {}
+
+    /// Transforms a media type with its name, such as "application/json".
+    fn transform_media_type(&mut self, _pointer: &str, _name: &str, _media_type: &mut MediaType) {}
+
+    fn transform_response(&mut self, _pointer: &str, _response: &mut Response) {}
+
+    fn transform_header(&mut self, _pointer: &str, _header: &mut Header) {}
+
+    /// Transforms a schema; returning false skips the schemas nested in it.
+    fn transform_schema(&mut self, _pointer: &str, _schema: &mut Schema) -> bool {
+        true
+    }
+
+    /// Transforms a reference in place of the object it refers to.
+    fn transform_reference(&mut self, _pointer: &str, _reference: &mut Reference) {}
+
+    /// Transforms the specification extensions of the object at `pointer`,
+    /// after the callback for the object itself.
+    fn transform_extensions(&mut self, _pointer: &str, _extensions: &mut Vec<NamedAny>) {}
+}
+
+/// The component sections whose entries [`Document::rename_component`] renames.
+pub(crate) const RENAMEABLE_SECTIONS: [&str; 6] = ["schemas", "responses", "parameters", "requestBodies", "headers", "callbacks"];
+
+impl Document {
+    /// Walks the document, passing each object in it to `transformer`.
+    pub fn transform(&mut self, transformer: &mut impl DocumentTransformer) {
+        transformer.transform_document(self);
+        transformer.transform_extensions("", &mut self.specification_extension);
+        if let Some(info) = &mut self.info {
+            transformer.transform_info("/info", info);
+            transformer.transform_extensions("/info", &mut info.specification_extension);
+        }
+        transform_servers("", &mut self.servers, transformer);
+        for (i, tag) in self.tags.iter_mut().enumerate() {
+            let pointer = format!("/tags/{}", i);
+            transformer.transform_tag(&pointer, tag);
+            transformer.transform_extensions(&pointer, &mut tag.specification_extension);
+        }
+        if let Some(paths) = &mut self.paths {
+            transformer.transform_extensions("/paths", &mut paths.specification_extension);
+            for named in &mut paths.path {
+                if let Some(path_item) = &mut named.value {
+                    transform_path_item(&child("/paths", &named.name), &named.name, path_item, transformer);
+                }
+            }
+        }
+        if let Some(components) = &mut self.components {
+            transform_components(components, transformer);
+        }
+    }
+
+    /// Renames the component `from` in a section of the components, such as
+    /// "schemas", to `to`, and updates the references to it and to the
+    /// objects nested in it. Returns false, leaving the document unchanged, if
+    /// the section has no component `from` or already has a component `to`.
+    pub fn rename_component(&mut self, section: &str, from: &str, to: &str) -> bool {
+        if !RENAMEABLE_SECTIONS.contains(&section) {
+            return false;
+        }
+        let Some(components) = &mut self.components else {
+            return false;
+        };
+        let names: Vec<&mut String> = match section {
+            "schemas" => names(&mut components.schemas, |s| &mut s.additional_properties, |n| &mut n.name),
+            "responses" => names(&mut components.responses, |s| &mut s.additional_properties, |n| &mut n.name),
+            "parameters" => names(&mut components.parameters, |s| &mut s.additional_properties, |n| &mut n.name),
+            "requestBodies" => names(&mut components.request_bodies, |s| &mut s.additional_properties, |n| &mut n.name),
+            "headers" => names(&mut components.headers, |s| &mut s.additional_properties, |n| &mut n.name),
+            _ => names(&mut components.callbacks, |s| &mut s.additional_properties, |n| &mut n.name),
+        };
+        if names.iter().any(|name| *name == to) {
+            return false;
+        }
+        let Some(name) = names.into_iter().find(|name| *name == from) else {
+            return false;
+        };
+        *name = to.to_string();
+
+        let prefix = format!("#/components/{}/", section);
+        let from = prefix.clone() + &escape_json_pointer_segment(from);
+        let to = prefix + &escape_json_pointer_segment(to);
+        self.transform(&mut ReferenceRenamer { from: &from, to: &to });
+        true
+    }
+}
+
+/// Returns mutable references to the names of the entries of a components section.
+fn names<'a, S, N: 'a>(
+    section: &'a mut Option<S>,
+    entries: impl Fn(&mut S) -> &mut Vec<N>,
+    name: impl Fn(&mut N) -> &mut String,
+) -> Vec<&'a mut String> {
+    section.iter_mut().flat_map(|section| entries(section).iter_mut().map(&name)).collect()
+}
+
+/// Replaces references to one component, or to the objects nested in it, with
+/// references to another.
+struct ReferenceRenamer<'a> {
+    from: &'a str,
+    to: &'a str,
+}
+
+impl DocumentTransformer for ReferenceRenamer<'_> {
+    fn transform_reference(&mut self, _: &str, reference: &mut Reference) {
+        if let Some(rest) = reference.r#ref.strip_prefix(self.from) {
+            if rest.is_empty() || rest.starts_with('/') {
+                reference.r#ref = format!("{}{}", self.to, rest);
+            }
+        }
+    }
+}
+
+/// Returns the pointer to the member `name` of the object at `pointer`.
+fn child(pointer: &str, name: &str) -> String {
+    format!("{}/{}", pointer, escape_json_pointer_segment(name))
+}
+
+fn transform_servers(pointer: &str, servers: &mut [Server], transformer: &mut impl DocumentTransformer) {
+    for (i, server) in servers.iter_mut().enumerate() {
+        let pointer = format!("{}/servers/{}", pointer, i);
+        transformer.transform_server(&pointer, server);
+        transformer.transform_extensions(&pointer, &mut server.specification_extension);
+    }
+}
+
+fn transform_path_item(
+    pointer: &str,
+    path: &str,
+    path_item: &mut PathItem,
+    transformer: &mut impl DocumentTransformer,
+) {
+    transformer.transform_path_item(pointer, path, path_item);
+    transformer.transform_extensions(pointer, &mut path_item.specification_extension);
+    transform_servers(pointer, &mut path_item.servers, transformer);
+    transform_parameters(pointer, &mut path_item.parameters, transformer);
+    for (method, operation) in path_item.iter_operations_mut() {
+        transform_operation(&child(pointer, method.as_str()), method.as_str(), operation, transformer);
+    }
+}
+
+fn transform_operation(
+    pointer: &str,
+    method: &str,
+    operation: &mut Operation,
+    transformer: &mut impl DocumentTransformer,
+) {
+    transformer.transform_operation(pointer, method, operation);
+    transformer.transform_extensions(pointer, &mut operation.specification_extension);
+    transform_parameters(pointer, &mut operation.parameters, transformer);
+    if let Some(request_body) = &mut operation.request_body {
+        transform_request_body_or_reference(&child(pointer, "requestBody"), request_body, transformer);
+    }
+    if let Some(responses) = &mut operation.responses {
+        let pointer = child(pointer, "responses");
+        transformer.transform_extensions(&pointer, &mut responses.specification_extension);
+        if let Some(response) = &mut responses.default {
+            transform_response_or_reference(&child(&pointer, "default"), response, transformer);
+        }
+        for named in &mut responses.response_or_reference {
+            if let Some(response) = &mut named.value {
+                transform_response_or_reference(&child(&pointer, &named.name), response, transformer);
+            }
+        }
+    }
+    if let Some(callbacks) = &mut operation.callbacks {
+        transform_callbacks(&child(pointer, "callbacks"), callbacks, transformer);
+    }
+    transform_servers(pointer, &mut operation.servers, transformer);
+}
+
+fn transform_parameters(
+    pointer: &str,
+    parameters: &mut [ParameterOrReference],
+    transformer: &mut impl DocumentTransformer,
+) {
+    for (i, parameter) in parameters.iter_mut().enumerate() {
+        transform_parameter_or_reference(&format!("{}/parameters/{}", pointer, i), parameter, transformer);
+    }
+}
+
+fn transform_parameter_or_reference(
+    pointer: &str,
+    parameter: &mut ParameterOrReference,
+    transformer: &mut impl DocumentTransformer,
+) {
+    match &mut parameter.oneof {
+        Some(parameter_or_reference::Oneof::Parameter(parameter)) => {
+            transformer.transform_parameter(pointer, parameter);
+            transformer.transform_extensions(pointer, &mut parameter.specification_extension);
+            if let Some(schema) = &mut parameter.schema {
+                transform_schema_or_reference(&child(pointer, "schema"), schema, transformer);
+            }
+            transform_media_types(pointer, &mut parameter.content, transformer);
+        }
+        Some(parameter_or_reference::Oneof::Reference(reference)) => {
+            transformer.transform_reference(pointer, reference)
+        }
+        None => {}
+    }
+}
+
+fn transform_request_body_or_reference(
+    pointer: &str,
+    request_body: &mut RequestBodyOrReference,
+    transformer: &mut impl DocumentTransformer,
+) {
+    match &mut request_body.oneof {
+        Some(request_body_or_reference::Oneof::RequestBody(request_body)) => {
+            transformer.transform_request_body(pointer, request_body);
+            transformer.transform_extensions(pointer, &mut request_body.specification_extension);
+            transform_media_types(pointer, &mut request_body.content, transformer);
+        }
+        Some(request_body_or_reference::Oneof::Reference(reference)) => {
+            transformer.transform_reference(pointer, reference)
+        }
+        None => {}
+    }
+}
+
+fn transform_response_or_reference(
+    pointer: &str,
+    response: &mut ResponseOrReference,
+    transformer: &mut impl DocumentTransformer,
+) {
+    match &mut response.oneof {
+        Some(response_or_reference::Oneof::Response(response)) => {
+            transformer.transform_response(pointer, response);
+            transformer.transform_extensions(pointer, &mut response.specification_extension);
+            transform_headers(pointer, &mut response.headers, transformer);
+            transform_media_types(pointer, &mut response.content, transformer);
+        }
+        Some(response_or_reference::Oneof::Reference(reference)) => transformer.transform_reference(pointer, reference),
+        None => {}
+    }
+}
+
+fn transform_headers(
+    pointer: &str,
+    headers: &mut Option<HeadersOrReferences>,
+    transformer: &mut impl DocumentTransformer,
+) {
+    for named in headers.iter_mut().flat_map(|headers| &mut headers.additional_properties) {
+        if let Some(header) = &mut named.value {
+            transform_header_or_reference(&child(&child(pointer, "headers"), &named.name), header, transformer);
+        }
+    }
+}
+
+fn transform_header_or_reference(
+    pointer: &str,
+    header: &mut HeaderOrReference,
+    transformer: &mut impl DocumentTransformer,
+) {
+    match &mut header.oneof {
+        Some(header_or_reference::Oneof::Header(header)) => {
+            transformer.transform_header(pointer, header);
+            transformer.transform_extensions(pointer, &mut header.specification_extension);
+            if let Some(schema) = &mut header.schema {
+                transform_schema_or_reference(&child(pointer, "schema"), schema, transformer);
+            }
+            transform_media_types(pointer, &mut header.content, transformer);
+        }
+        Some(header_or_reference::Oneof::Reference(reference)) => transformer.transform_reference(pointer, reference),
+        None => {}
+    }
+}
+
+fn transform_media_types(
+    pointer: &str,
+    content: &mut Option<MediaTypes>,
+    transformer: &mut impl DocumentTransformer,
+) {
+    for named in content.iter_mut().flat_map(|content| &mut content.additional_properties) {
+        let Some(media_type) = &mut named.value else {
+            continue;
+        };
+        let pointer = child(&child(pointer, "content"), &named.name);
+        transformer.transform_media_type(&pointer, &named.name, media_type);
+        transformer.transform_extensions(&pointer, &mut media_type.specification_extension);
+        if let Some(schema) = &mut media_type.schema {
+            transform_schema_or_reference(&child(&pointer, "schema"), schema, transformer);
+        }
+        for named in media_type.encoding.iter_mut().flat_map(|encodings| &mut encodings.additional_properties) {
+            if let Some(encoding) = &mut named.value {
+                let pointer = child(&child(&pointer, "encoding"), &named.name);
+                transformer.transform_extensions(&pointer, &mut encoding.specification_extension);
+                transform_headers(&pointer, &mut encoding.headers, transformer);
+            }
+        }
+    }
+}
+
+fn transform_callbacks(
+    pointer: &str,
+    callbacks: &mut CallbacksOrReferences,
+    transformer: &mut impl DocumentTransformer,
+) {
+    for named in &mut callbacks.additional_properties {
+        let pointer = child(pointer, &named.name);
+        match named.value.as_mut().and_then(|callback| callback.oneof.as_mut()) {
+            Some(callback_or_reference::Oneof::Callback(callback)) => {
+                transformer.transform_extensions(&pointer, &mut callback.specification_extension);
+                for named in &mut callback.path {
+                    if let Some(path_item) = &mut named.value {
+                        transform_path_item(&child(&pointer, &named.name), &named.name, path_item, transformer);
+                    }
+                }
+            }
+            Some(callback_or_reference::Oneof::Reference(reference)) => {
+                transformer.transform_reference(&pointer, reference)
+            }
+            None => {}
+        }
+    }
+}
+
+fn transform_schema_or_reference(
+    pointer: &str,
+    schema: &mut SchemaOrReference,
+    transformer: &mut impl DocumentTransformer,
+) {
+    match &mut schema.oneof {
+        Some(schema_or_reference::Oneof::Schema(schema)) => transform_schema(pointer, schema, transformer),
+        Some(schema_or_reference::Oneof::Reference(reference)) => transformer.transform_reference(pointer, reference),
+        None => {}
+    }
+}
+
+fn transform_schema(pointer: &str, schema: &mut Schema, transformer: &mut impl DocumentTransformer) {
+    let walk_nested = transformer.transform_schema(pointer, schema);
+    transformer.transform_extensions(pointer, &mut schema.specification_extension);
+    if !walk_nested {
+        return;
+    }
+    let combinators = [("allOf", &mut schema.all_of), ("oneOf", &mut schema.one_of), ("anyOf", &mut schema.any_of)];
+    for (keyword, schemas) in combinators {
+        for (i, nested) in schemas.iter_mut().enumerate() {
+            transform_schema_or_reference(&format!("{}/{}/{}", pointer, keyword, i), nested, transformer);
+        }
+    }
+    if let Some(not) = &mut schema.not {
+        transform_schema(&child(pointer, "not"), not, transformer);
+    }
+    if let Some(items) = &mut schema.items {
+        // A single items schema is written as an object rather than a sequence.
+        let single = items.schema_or_reference.len() == 1;
+        for (i, nested) in items.schema_or_reference.iter_mut().enumerate() {
+            let pointer = if single { child(pointer, "items") } else { format!("{}/items/{}", pointer, i) };
+            transform_schema_or_reference(&pointer, nested, transformer);
+        }
+    }
+    for named in schema.properties.iter_mut().flat_map(|properties| &mut properties.additional_properties) {
+        if let Some(nested) = &mut named.value {
+            transform_schema_or_reference(&child(&child(pointer, "properties"), &named.name), nested, transformer);
+        }
+    }
+    if let Some(AdditionalPropertiesItem {
+        oneof: Some(additional_properties_item::Oneof::SchemaOrReference(nested)),
+    }) = schema.additional_properties.as_deref_mut()
+    {
+        transform_schema_or_reference(&child(pointer, "additionalProperties"), nested, transformer);
+    }
+}
+
+fn transform_components(components: &mut Components, transformer: &mut impl DocumentTransformer) {
+    let pointer = "/components";
+    transformer.transform_extensions(pointer, &mut components.specification_extension);
+    for named in components.schemas.iter_mut().flat_map(|schemas| &mut schemas.additional_properties) {
+        if let Some(schema) = &mut named.value {
+            transform_schema_or_reference(&child(&child(pointer, "schemas"), &named.name), schema, transformer);
+        }
+    }
+    for named in components.responses.iter_mut().flat_map(|responses| &mut responses.additional_properties) {
+        if let Some(response) = &mut named.value {
+            let pointer = child(&child(pointer, "responses"), &named.name);
+            transform_response_or_reference(&pointer, response, transformer);
+        }
+    }
+    for named in components.parameters.iter_mut().flat_map(|parameters| &mut parameters.additional_properties) {
+        if let Some(parameter) = &mut named.value {
+            let pointer = child(&child(pointer, "parameters"), &named.name);
+            transform_parameter_or_reference(&pointer, parameter, transformer);
+        }
+    }
+    let request_bodies = components.request_bodies.iter_mut().flat_map(|bodies| &mut bodies.additional_properties);
+    for named in request_bodies {
+        if let Some(request_body) = &mut named.value {
+            let pointer = child(&child(pointer, "requestBodies"), &named.name);
+            transform_request_body_or_reference(&pointer, request_body, transformer);
+        }
+    }
+    transform_headers(pointer, &mut components.headers, transformer);
+    if let Some(callbacks) = &mut components.callbacks {
+        transform_callbacks(&child(pointer, "callbacks"), callbacks, transformer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{parse_document, yaml_value};
+
+    const PETS: &str = r##"
+openapi: 3.0.3
+info: {title: Pets, version: 1.0.0, x-internal: true}
+paths:
+  /pets:
+    get:
+      x-codegen: skip
+      responses:
+        "200":
+          description: Pets.
+          content:
+            application/json:
+              schema: {type: array, items: {$ref: "#/components/schemas/Pet"}}
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        owner: {$ref: "#/components/schemas/Owner"}
+        name: {$ref: "#/components/schemas/Pet/properties/owner"}
+    Owner: {type: string}
+"##;
+
+    #[test]
+    fn test_transform() {
+        struct Transformer;
+        impl DocumentTransformer for Transformer {
+            fn transform_document(&mut self, document: &mut Document) {
+                document.servers.push(Server { url: "https://pets.example.com".to_string(), ..Default::default() });
+            }
+
+            fn transform_operation(&mut self, _: &str, method: &str, operation: &mut Operation) {
+                operation.operation_id = format!("{}Pets", method);
+            }
+
+            fn transform_extensions(&mut self, _: &str, extensions: &mut Vec<NamedAny>) {
+                extensions.clear();
+            }
+        }
+
+        let mut doc = parse_document(PETS.as_bytes()).unwrap();
+        doc.transform(&mut Transformer);
+        assert_eq!(doc.servers[0].url, "https://pets.example.com");
+        assert!(doc.info.as_ref().unwrap().specification_extension.is_empty());
+        let operation = doc.paths.as_ref().unwrap().path[0].value.as_ref().unwrap().get.as_ref().unwrap();
+        assert_eq!(operation.operation_id, "getPets");
+        assert!(operation.specification_extension.is_empty());
+    }
+
+    #[test]
+    fn test_rename_component() {
+        let mut doc = parse_document(PETS.as_bytes()).unwrap();
+        assert!(!doc.rename_component("schemas", "Cat", "Dog"));
+        assert!(!doc.rename_component("schemas", "Pet", "Owner"));
+        assert!(!doc.rename_component("examples", "Pet", "Animal"));
+        assert!(doc.rename_component("schemas", "Pet", "Animal"));
+        let yaml = String::from_utf8(yaml_value(&doc)).unwrap();
+        assert!(yaml.contains("    Animal:\n"));
+        assert!(yaml.contains("$ref: '#/components/schemas/Animal'"));
+        assert!(yaml.contains("$ref: '#/components/schemas/Animal/properties/owner'"));
+        assert!(yaml.contains("$ref: '#/components/schemas/Owner'"));
+        assert!(!yaml.contains("Pet'"));
+    }
+}
@@ -0,0 +1,1237 @@
+//! Structural validation of OpenAPI v3 documents.
+//!
+//! Every object is checked against the fixed fields of the OpenAPI 3.0
+//! specification: keys that are neither fixed fields, pattern fields nor
+//! "x-" extensions are reported, as are missing required fields. Fields
+//! that only OpenAPI 3.1 has, or that it removed, are accepted or reported
+//! according to the version the document declares. The parser is forgiving
+//! and drops what it does not model, so validation works on the YAML of a
+//! document.
+
+use gnostic_compiler::{
+    check_operation_ids, check_path_parameters, check_responses, check_security, invalid_keys_in_map, local_target,
+    missing_keys_in_map, node_for_pointer, template_names, CompilerError, Context, ErrorCode, ErrorGroup, Severity, ToYaml, ValidateOptions,
+    ValidationReport,
+};
+use regex::Regex;
+use serde_yaml::{Mapping, Value as Yaml};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::openapi_v3::Document;
+
+/// Validates a parsed document.
+///
+/// Fields the model does not represent have already been dropped, so unknown
+/// keys are only found by [`validate_node`] on the source.
+pub fn validate(document: &Document) -> ErrorGroup {
+    validate_with_options(document, &ValidateOptions::default())
+}
+
+/// Validates a parsed document with the given options.
+pub fn validate_with_options(document: &Document, options: &ValidateOptions) -> ErrorGroup {
+    validate_node_with_options(&document.to_yaml(), options)
+}
+
+/// Validates the YAML of a document, returning the findings located by
+/// path. The group is empty if the document is valid.
+pub fn validate_node(node: &Yaml) -> ErrorGroup {
+    validate_node_with_options(node, &ValidateOptions::default())
+}
+
+/// Validates the YAML of a document with the given options.
+///
+/// After the structure of each object, the relations between objects are
+/// checked, such as operationIds being unique, path templates having
+/// parameters and security requirements naming defined schemes.
+pub fn validate_node_with_options(node: &Yaml, options: &ValidateOptions) -> ErrorGroup {
+    let root = Arc::new(Context::root("$"));
+    let mut validator = Validator::new(node, &root, options);
+    validator.validate(node, Kind::Document, &root);
+    check_relations(node, options, &mut validator.errors);
+    ErrorGroup::new(validator.errors)
+}
+
+/// Checks the relations between the objects of a document.
+fn check_relations(node: &Yaml, options: &ValidateOptions, errors: &mut Vec<CompilerError>) {
+    errors.extend(check_operation_ids(node, OPERATIONS, options));
+    errors.extend(check_path_parameters(node, OPERATIONS));
+    errors.extend(check_responses(node, OPERATIONS, true));
+    errors.extend(check_security(node, OPERATIONS, &["components", "securitySchemes"]));
+}
+
+/// Validates successive versions of a document, such as those of a file
+/// being edited, validating again only the path items that changed.
+///
+/// The findings are those of [`validate_node_with_options`], though not
+/// necessarily in the same order. Every path item is validated again when
+/// the version or components of the document change, since its schemas may
+/// refer to them.
+#[derive(Debug, Default)]
+pub struct IncrementalValidator {
+    options: ValidateOptions,
+    /// The version and components that the path items were validated with.
+    version: Yaml,
+    components: Yaml,
+    /// The node and findings of each path item, by path.
+    paths: HashMap<String, (Yaml, Vec<CompilerError>)>,
+    revalidated: usize,
+}
+
+impl IncrementalValidator {
+    /// Creates a validator with the given options.
+    pub fn new(options: ValidateOptions) -> Self {
+        IncrementalValidator { options, ..Default::default() }
+    }
+
+    /// Validates the YAML of a document, reusing the findings of the path
+    /// items that are unchanged since the last call.
+    pub fn validate(&mut self, node: &Yaml) -> ErrorGroup {
+        if node["openapi"] != self.version || node["components"] != self.components {
+            self.version = node["openapi"].clone();
+            self.components = node["components"].clone();
+            self.paths.clear();
+        }
+
+        let root = Arc::new(Context::root("$"));
+        let mut validator = Validator::new(node, &root, &self.options);
+        validator.validate(&without_path_items(node), Kind::Document, &root);
+
+        let context = Arc::new(root.child("paths"));
+        let mut paths = HashMap::new();
+        self.revalidated = 0;
+        for (name, item) in path_items(node) {
+            let findings = match self.paths.remove(name) {
+                Some((previous, findings)) if previous == *item => findings,
+                _ => {
+                    let start = validator.errors.len();
+                    validator.validate(item, Kind::PathItem, &Arc::new(context.child(name)));
+                    self.revalidated += 1;
+                    validator.errors.drain(start..).collect()
+                }
+            };
+            validator.errors.extend(findings.iter().cloned());
+            paths.insert(name.to_string(), (item.clone(), findings));
+        }
+        self.paths = paths;
+
+        check_relations(node, &self.options, &mut validator.errors);
+        ErrorGroup::new(validator.errors)
+    }
+
+    /// Returns the number of path items that the last call validated.
+    pub fn revalidated(&self) -> usize {
+        self.revalidated
+    }
+}
+
+/// Returns the path items of a document, which are validated one by one.
+fn path_items(node: &Yaml) -> impl Iterator<Item = (&str, &Yaml)> {
+    node["paths"]
+        .as_mapping()
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, item)| Some((key.as_str().filter(|key| key.starts_with('/'))?, item)))
+}
+
+/// Returns a copy of a document whose path items are empty.
+fn without_path_items(node: &Yaml) -> Cow<'_, Yaml> {
+    if !node["paths"].is_mapping() {
+        return Cow::Borrowed(node);
+    }
+    let mut document = Mapping::new();
+    for (key, value) in node.as_mapping().into_iter().flatten() {
+        let value = match (key.as_str(), value) {
+            (Some("paths"), Yaml::Mapping(paths)) => Yaml::Mapping(
+                paths
+                    .iter()
+                    .map(|(key, item)| match key.as_str() {
+                        Some(path) if path.starts_with('/') => (key.clone(), Yaml::Mapping(Mapping::new())),
+                        _ => (key.clone(), item.clone()),
+                    })
+                    .collect(),
+            ),
+            _ => value.clone(),
+        };
+        document.insert(key.clone(), value);
+    }
+    Cow::Owned(Yaml::Mapping(document))
+}
+
+/// Validates the YAML of a document, returning the findings located by
+/// JSON pointers.
+pub fn validation_report(node: &Yaml, options: &ValidateOptions) -> ValidationReport {
+    ValidationReport::from_errors(&validate_node_with_options(node, options), node)
+}
+
+/// The OpenAPI objects that are validated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Document,
+    Info,
+    Contact,
+    License,
+    Server,
+    ServerVariable,
+    Components,
+    Paths,
+    PathItem,
+    Operation,
+    ExternalDocs,
+    Parameter,
+    RequestBody,
+    MediaType,
+    Encoding,
+    Responses,
+    Response,
+    Callback,
+    Example,
+    Link,
+    Header,
+    Tag,
+    Reference,
+    Schema,
+    Discriminator,
+    Xml,
+    SecurityScheme,
+    OAuthFlows,
+    OAuthFlow,
+}
+
+/// The shape of a field value.
+enum Shape {
+    Object(Kind),
+    Map(Kind),
+    List(Kind),
+}
+
+/// Keys allowed in a Parameter and, except name and in, a Header.
+const PARAMETER_FIELDS: &[&str] = &[
+    "name",
+    "in",
+    "description",
+    "required",
+    "deprecated",
+    "allowEmptyValue",
+    "style",
+    "explode",
+    "allowReserved",
+    "schema",
+    "example",
+    "examples",
+    "content",
+];
+
+const OPERATIONS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// The last minor version of OpenAPI 3 that has every field.
+const LATEST: u32 = u32::MAX;
+
+/// Returns the version string of a document and its minor version, after
+/// reporting versions that are malformed or not supported.
+fn check_version(node: &Yaml, root: &Arc<Context>, errors: &mut Vec<CompilerError>) -> (String, u32) {
+    let context = root.child("openapi");
+    let version = match &node["openapi"] {
+        Yaml::Null => return ("3.0".to_string(), 0),
+        Yaml::String(version) => version.clone(),
+        other => {
+            errors.push(CompilerError::new_with_code(
+                &context,
+                ErrorCode::InvalidType,
+                format!("expected a string, got {}", type_name(other)),
+            ));
+            return (display_scalar(other), 0);
+        }
+    };
+    let pattern = Regex::new(r"^3\.(\d+)\.\d+(-[0-9A-Za-z.-]+)?$").expect("valid pattern");
+    let Some(minor) = pattern.captures(&version).and_then(|captures| captures[1].parse::<u32>().ok()) else {
+        errors.push(CompilerError::new_with_code(
+            &context,
+            ErrorCode::InvalidFormat,
+            format!("has invalid version: {}", version),
+        ));
+        return (version, 0);
+    };
+    if minor > 1 {
+        errors.push(
+            CompilerError::warning(&context, format!("has unsupported version {}; validating as 3.1", version))
+                .with_code(ErrorCode::UnsupportedVersion),
+        );
+    }
+    (version, minor.min(1))
+}
+
+fn display_scalar(node: &Yaml) -> String {
+    serde_yaml::to_string(node).unwrap_or_default().trim().to_string()
+}
+
+impl Kind {
+    /// Returns the fixed fields of the object.
+    fn fixed(self) -> &'static [&'static str] {
+        match self {
+            Kind::Document => &[
+                "openapi",
+                "info",
+                "servers",
+                "paths",
+                "components",
+                "security",
+                "tags",
+                "externalDocs",
+            ],
+            Kind::Info => &[
+                "title",
+                "description",
+                "termsOfService",
+                "contact",
+                "license",
+                "version",
+            ],
+            Kind::Contact => &["name", "url", "email"],
+            Kind::License => &["name", "url"],
+            Kind::Server => &["url", "description", "variables"],
+            Kind::ServerVariable => &["enum", "default", "description"],
+            Kind::Components => &[
+                "schemas",
+                "responses",
+                "parameters",
+                "examples",
+                "requestBodies",
+                "headers",
+                "securitySchemes",
+                "links",
+                "callbacks",
+            ],
+            Kind::Paths | Kind::Callback => &[],
+            Kind::PathItem => &[
+                "$ref",
+                "summary",
+                "description",
+                "get",
+                "put",
+                "post",
+                "delete",
+                "options",
+                "head",
+                "patch",
+                "trace",
+                "servers",
+                "parameters",
+            ],
+            Kind::Operation => &[
+                "tags",
+                "summary",
+                "description",
+                "externalDocs",
+                "operationId",
+                "parameters",
+                "requestBody",
+                "responses",
+                "callbacks",
+                "deprecated",
+                "security",
+                "servers",
+            ],
+            Kind::ExternalDocs => &["description", "url"],
+            Kind::Parameter => PARAMETER_FIELDS,
+            Kind::Header => &PARAMETER_FIELDS[2..],
+            Kind::RequestBody => &["description", "content", "required"],
+            Kind::MediaType => &["schema", "example", "examples", "encoding"],
+            Kind::Encoding => &[
+                "contentType",
+                "headers",
+                "style",
+                "explode",
+                "allowReserved",
+            ],
+            Kind::Responses => &["default"],
+            Kind::Response => &["description", "headers", "content", "links"],
+            Kind::Example => &["summary", "description", "value", "externalValue"],
+            Kind::Link => &[
+                "operationRef",
+                "operationId",
+                "parameters",
+                "requestBody",
+                "description",
+                "server",
+            ],
+            Kind::Tag => &["name", "description", "externalDocs"],
+            Kind::Reference => &["$ref"],
+            Kind::Schema => &[
+                "discriminator",
+                "readOnly",
+                "writeOnly",
+                "xml",
+                "externalDocs",
+                "example",
+                "deprecated",
+                "title",
+                "multipleOf",
+                "maximum",
+                "exclusiveMaximum",
+                "minimum",
+                "exclusiveMinimum",
+                "maxLength",
+                "minLength",
+                "pattern",
+                "maxItems",
+                "minItems",
+                "uniqueItems",
+                "maxProperties",
+                "minProperties",
+                "required",
+                "enum",
+                "type",
+                "allOf",
+                "oneOf",
+                "anyOf",
+                "not",
+                "items",
+                "properties",
+                "additionalProperties",
+                "default",
+                "description",
+                "format",
+            ],
+            Kind::Discriminator => &["propertyName", "mapping"],
+            Kind::Xml => &["name", "namespace", "prefix", "attribute", "wrapped"],
+            Kind::SecurityScheme => &[
+                "type",
+                "description",
+                "name",
+                "in",
+                "scheme",
+                "bearerFormat",
+                "flows",
+                "openIdConnectUrl",
+            ],
+            Kind::OAuthFlows => &[
+                "implicit",
+                "password",
+                "clientCredentials",
+                "authorizationCode",
+            ],
+            Kind::OAuthFlow => &["authorizationUrl", "tokenUrl", "refreshUrl", "scopes"],
+        }
+    }
+
+    /// Returns the fields that only some minor versions of OpenAPI 3 have,
+    /// with the first and last of them.
+    fn gated(self) -> &'static [(&'static str, u32, u32)] {
+        match self {
+            Kind::Document => &[("webhooks", 1, LATEST), ("jsonSchemaDialect", 1, LATEST)],
+            Kind::Info => &[("summary", 1, LATEST)],
+            Kind::License => &[("identifier", 1, LATEST)],
+            Kind::Components => &[("pathItems", 1, LATEST)],
+            Kind::Reference => &[("summary", 1, LATEST), ("description", 1, LATEST)],
+            Kind::Schema => &[
+                ("nullable", 0, 0),
+                ("const", 1, LATEST),
+                ("examples", 1, LATEST),
+                ("$id", 1, LATEST),
+                ("$schema", 1, LATEST),
+                ("$anchor", 1, LATEST),
+                ("$comment", 1, LATEST),
+                ("$defs", 1, LATEST),
+                ("$dynamicRef", 1, LATEST),
+                ("$dynamicAnchor", 1, LATEST),
+                ("prefixItems", 1, LATEST),
+                ("contains", 1, LATEST),
+                ("minContains", 1, LATEST),
+                ("maxContains", 1, LATEST),
+                ("if", 1, LATEST),
+                ("then", 1, LATEST),
+                ("else", 1, LATEST),
+                ("dependentRequired", 1, LATEST),
+                ("dependentSchemas", 1, LATEST),
+                ("patternProperties", 1, LATEST),
+                ("propertyNames", 1, LATEST),
+                ("unevaluatedItems", 1, LATEST),
+                ("unevaluatedProperties", 1, LATEST),
+                ("contentEncoding", 1, LATEST),
+                ("contentMediaType", 1, LATEST),
+                ("contentSchema", 1, LATEST),
+            ],
+            _ => &[],
+        }
+    }
+
+    /// Returns the fields that must be present in a document of the given
+    /// minor version.
+    fn required(self, minor: u32) -> &'static [&'static str] {
+        match self {
+            // OpenAPI 3.1 documents may have only components or webhooks
+            Kind::Document if minor >= 1 => &["openapi", "info"],
+            Kind::Document => &["openapi", "info", "paths"],
+            Kind::Info => &["title", "version"],
+            Kind::License => &["name"],
+            Kind::Server => &["url"],
+            Kind::ServerVariable => &["default"],
+            Kind::Operation => &["responses"],
+            Kind::ExternalDocs => &["url"],
+            Kind::Parameter => &["name", "in"],
+            Kind::RequestBody => &["content"],
+            Kind::Response => &["description"],
+            Kind::Tag => &["name"],
+            Kind::Reference => &["$ref"],
+            Kind::Discriminator => &["propertyName"],
+            Kind::SecurityScheme => &["type"],
+            _ => &[],
+        }
+    }
+
+    /// Returns true if the object may be replaced by a Reference.
+    fn referenceable(self) -> bool {
+        matches!(
+            self,
+            Kind::Schema
+                | Kind::Response
+                | Kind::Parameter
+                | Kind::Example
+                | Kind::RequestBody
+                | Kind::Header
+                | Kind::SecurityScheme
+                | Kind::Link
+                | Kind::Callback
+        )
+    }
+
+    /// Returns the shape of the value of `key`, for values that are objects.
+    fn field(self, key: &str) -> Option<Shape> {
+        use Shape::*;
+        let shape = match (self, key) {
+            (_, key) if key.starts_with("x-") => return None,
+            (Kind::Document, "info") => Object(Kind::Info),
+            (Kind::Document | Kind::PathItem | Kind::Operation, "servers") => List(Kind::Server),
+            (Kind::Document, "paths") => Object(Kind::Paths),
+            (Kind::Document, "components") => Object(Kind::Components),
+            (Kind::Document, "tags") => List(Kind::Tag),
+            (Kind::Document | Kind::Operation | Kind::Tag | Kind::Schema, "externalDocs") => {
+                Object(Kind::ExternalDocs)
+            }
+            (Kind::Info, "contact") => Object(Kind::Contact),
+            (Kind::Info, "license") => Object(Kind::License),
+            (Kind::Server, "variables") => Map(Kind::ServerVariable),
+            (Kind::Components, "schemas") => Map(Kind::Schema),
+            (Kind::Components, "responses") => Map(Kind::Response),
+            (Kind::Components, "parameters") => Map(Kind::Parameter),
+            (Kind::Components | Kind::Parameter | Kind::Header | Kind::MediaType, "examples") => {
+                Map(Kind::Example)
+            }
+            (Kind::Components, "requestBodies") => Map(Kind::RequestBody),
+            (Kind::Components | Kind::Encoding | Kind::Response, "headers") => Map(Kind::Header),
+            (Kind::Components, "securitySchemes") => Map(Kind::SecurityScheme),
+            (Kind::Document, "webhooks") | (Kind::Components, "pathItems") => Map(Kind::PathItem),
+            (Kind::Components | Kind::Response, "links") => Map(Kind::Link),
+            (Kind::Components | Kind::Operation, "callbacks") => Map(Kind::Callback),
+            (Kind::Paths | Kind::Callback, _) => Object(Kind::PathItem),
+            (Kind::PathItem, method) if OPERATIONS.contains(&method) => Object(Kind::Operation),
+            (Kind::PathItem | Kind::Operation, "parameters") => List(Kind::Parameter),
+            (Kind::Operation, "requestBody") => Object(Kind::RequestBody),
+            (Kind::Operation, "responses") => Object(Kind::Responses),
+            (Kind::Parameter | Kind::Header | Kind::MediaType, "schema") => Object(Kind::Schema),
+            (Kind::Parameter | Kind::Header | Kind::RequestBody | Kind::Response, "content") => {
+                Map(Kind::MediaType)
+            }
+            (Kind::MediaType, "encoding") => Map(Kind::Encoding),
+            (Kind::Responses, _) => Object(Kind::Response),
+            (Kind::Link, "server") => Object(Kind::Server),
+            (Kind::Schema, "discriminator") => Object(Kind::Discriminator),
+            (Kind::Schema, "xml") => Object(Kind::Xml),
+            (Kind::Schema, "allOf" | "oneOf" | "anyOf") => List(Kind::Schema),
+            (Kind::Schema, "not" | "items") => Object(Kind::Schema),
+            (Kind::Schema, "properties" | "$defs" | "dependentSchemas" | "patternProperties") => Map(Kind::Schema),
+            (Kind::Schema, "prefixItems") => List(Kind::Schema),
+            (Kind::Schema, "contains" | "if" | "then" | "else" | "propertyNames" | "contentSchema") => {
+                Object(Kind::Schema)
+            }
+            (Kind::SecurityScheme, "flows") => Object(Kind::OAuthFlows),
+            (Kind::OAuthFlows, _) => Object(Kind::OAuthFlow),
+            _ => return None,
+        };
+        Some(shape)
+    }
+}
+
+struct Validator<'a> {
+    /// The document, for following references.
+    root: &'a Yaml,
+    extension: Regex,
+    path: Regex,
+    any: Regex,
+    /// The version the document declares.
+    version: String,
+    /// The minor version of OpenAPI 3 that the document is validated as.
+    minor: u32,
+    feature_severity: Severity,
+    errors: Vec<CompilerError>,
+}
+
+impl<'a> Validator<'a> {
+    /// Creates a validator of a document, after checking its version.
+    fn new(root: &'a Yaml, context: &Arc<Context>, options: &ValidateOptions) -> Self {
+        let mut errors = Vec::new();
+        let (version, minor) = check_version(root, context, &mut errors);
+        Validator {
+            root,
+            extension: Regex::new("^x-").expect("valid pattern"),
+            path: Regex::new("^/").expect("valid pattern"),
+            any: Regex::new("^").expect("valid pattern"),
+            version,
+            minor,
+            feature_severity: options.version_features,
+            errors,
+        }
+    }
+
+    fn validate(&mut self, node: &Yaml, kind: Kind, context: &Arc<Context>) {
+        // OpenAPI 3.1 schemas may be true or false
+        if kind == Kind::Schema && self.minor >= 1 && node.is_bool() {
+            return;
+        }
+        let Yaml::Mapping(map) = node else {
+            self.errors.push(CompilerError::new_with_code(
+                context,
+                ErrorCode::InvalidType,
+                format!("expected an object, got {}", type_name(node)),
+            ));
+            return;
+        };
+        // OpenAPI 3.1 schemas are JSON schemas, in which $ref is a keyword
+        let schema_ref = kind == Kind::Schema && self.minor >= 1;
+        let kind = if kind.referenceable() && !schema_ref && map.contains_key("$ref") {
+            Kind::Reference
+        } else {
+            kind
+        };
+
+        let patterns: Vec<&Regex> = match kind {
+            Kind::Reference | Kind::Discriminator => Vec::new(),
+            Kind::Paths => vec![&self.path, &self.extension],
+            // Status codes are checked by check_responses
+            Kind::Responses | Kind::Callback => vec![&self.any],
+            _ => vec![&self.extension],
+        };
+        let mut allowed = kind.fixed().to_vec();
+        for (key, first, last) in kind.gated() {
+            allowed.push(key);
+            if map.contains_key(*key) && !(*first..=*last).contains(&self.minor) {
+                self.errors.push(
+                    CompilerError::new_with_code(
+                        context,
+                        ErrorCode::UnsupportedFeature,
+                        format!("uses {}, which OpenAPI {} does not have", key, self.version),
+                    )
+                    .with_severity(self.feature_severity),
+                );
+            }
+        }
+        let invalid = invalid_keys_in_map(node, &allowed, &patterns);
+        for key in &invalid {
+            self.errors.push(CompilerError::unknown_key(context, key, &allowed));
+        }
+        for key in missing_keys_in_map(node, kind.required(self.minor)) {
+            self.errors.push(CompilerError::new_with_code(
+                context,
+                ErrorCode::MissingRequiredField,
+                format!("is missing required property: {}", key),
+            ));
+        }
+
+        for (key, value) in map {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            if invalid.iter().any(|invalid| invalid == key) {
+                continue;
+            }
+            let Some(shape) = kind.field(key) else {
+                continue;
+            };
+            let child = Arc::new(context.child(key));
+            match shape {
+                Shape::Object(kind) => self.validate(value, kind, &child),
+                Shape::Map(kind) => self.validate_entries(value, kind, &child),
+                Shape::List(kind) => match value {
+                    Yaml::Sequence(items) => {
+                        for (i, item) in items.iter().enumerate() {
+                            self.validate(item, kind, &Arc::new(child.child(i.to_string())));
+                        }
+                    }
+                    _ => self.errors.push(CompilerError::new_with_code(
+                        &child,
+                        ErrorCode::InvalidType,
+                        format!("expected an array, got {}", type_name(value)),
+                    )),
+                },
+            }
+        }
+        // additionalProperties may also be a boolean
+        if kind == Kind::Schema {
+            if let Some(value @ Yaml::Mapping(_)) = map.get("additionalProperties") {
+                self.validate(
+                    value,
+                    Kind::Schema,
+                    &Arc::new(context.child("additionalProperties")),
+                );
+            }
+            if map.get("discriminator").is_some_and(Yaml::is_mapping) {
+                self.check_discriminator(node, context);
+            }
+        }
+        if kind == Kind::Server {
+            self.check_server(node, context);
+        }
+    }
+
+    /// Checks that each variable in the url of a server is defined, and that
+    /// the default of each variable is one of its enum values.
+    fn check_server(&mut self, server: &Yaml, context: &Arc<Context>) {
+        let variables = &server["variables"];
+        if let Some(url) = server["url"].as_str() {
+            for name in template_names(url) {
+                if variables.get(name).is_none() {
+                    self.errors.push(CompilerError::new_with_code(
+                        &context.child("url"),
+                        ErrorCode::UnresolvedReference,
+                        format!("uses undefined variable {}", name),
+                    ));
+                }
+            }
+        }
+        let Yaml::Mapping(variables) = variables else {
+            return;
+        };
+        let variables_context = Arc::new(context.child("variables"));
+        for (name, variable) in variables {
+            let (Some(name), Some(default)) = (name.as_str(), variable["default"].as_str()) else {
+                continue;
+            };
+            if let Some(values) = variable["enum"].as_sequence() {
+                if !values.iter().any(|value| value.as_str() == Some(default)) {
+                    self.errors.push(CompilerError::new_with_code(
+                        &variables_context.child(name),
+                        ErrorCode::InvalidValue,
+                        format!("has default {} that is not one of its enum values", default),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Checks that each oneOf or anyOf branch of a schema with a
+    /// discriminator has and requires its property, or the schema itself if
+    /// it has no branches, and that the mapping names schemas that exist.
+    fn check_discriminator(&mut self, schema: &Yaml, context: &Arc<Context>) {
+        let discriminator = &schema["discriminator"];
+        let Some(name) = discriminator["propertyName"].as_str() else {
+            return;
+        };
+        let mut branches = Vec::new();
+        for key in ["oneOf", "anyOf"] {
+            let Yaml::Sequence(items) = &schema[key] else {
+                continue;
+            };
+            let list_context = Arc::new(context.child(key));
+            for (i, item) in items.iter().enumerate() {
+                branches.push((Arc::new(list_context.child(i.to_string())), item));
+            }
+        }
+        if branches.is_empty() {
+            branches.push((context.clone(), schema));
+        }
+        for (branch_context, branch) in branches {
+            let mut properties = Vec::new();
+            let mut required = Vec::new();
+            let Some(branch) = local_target(self.root, branch) else {
+                continue;
+            };
+            self.collect_properties(branch, &mut properties, &mut required, 0);
+            let message = if !properties.contains(&name) {
+                format!("does not have discriminator property {}", name)
+            } else if !required.contains(&name) {
+                format!("does not require discriminator property {}", name)
+            } else {
+                continue;
+            };
+            self.errors
+                .push(CompilerError::new_with_code(&branch_context, ErrorCode::InvalidValue, message));
+        }
+
+        let Yaml::Mapping(mapping) = &discriminator["mapping"] else {
+            return;
+        };
+        let mapping_context = Arc::new(Arc::new(context.child("discriminator")).child("mapping"));
+        for (key, target) in mapping {
+            let (Some(key), Some(target)) = (key.as_str(), target.as_str()) else {
+                continue;
+            };
+            let found = match target.strip_prefix('#') {
+                Some(pointer) => node_for_pointer(self.root, pointer).is_some(),
+                None => {
+                    self.root["components"]["schemas"].get(target).is_some()
+                        || target.contains(['/', '#'])
+                        || [".yaml", ".yml", ".json"].iter().any(|suffix| target.ends_with(suffix))
+                }
+            };
+            if !found {
+                self.errors.push(CompilerError::new_with_code(
+                    &mapping_context.child(key),
+                    ErrorCode::UnresolvedReference,
+                    format!("maps to undefined schema {}", target),
+                ));
+            }
+        }
+    }
+
+    /// Collects the properties and required properties of a schema and of
+    /// the schemas it combines with allOf.
+    fn collect_properties<'b>(
+        &self,
+        schema: &'b Yaml,
+        properties: &mut Vec<&'b str>,
+        required: &mut Vec<&'b str>,
+        depth: usize,
+    ) where
+        'a: 'b,
+    {
+        if let Yaml::Mapping(map) = &schema["properties"] {
+            properties.extend(map.keys().filter_map(Yaml::as_str));
+        }
+        if let Yaml::Sequence(names) = &schema["required"] {
+            required.extend(names.iter().filter_map(Yaml::as_str));
+        }
+        if depth >= 16 {
+            return;
+        }
+        for item in schema["allOf"].as_sequence().into_iter().flatten() {
+            if let Some(item) = local_target(self.root, item) {
+                self.collect_properties(item, properties, required, depth + 1);
+            }
+        }
+    }
+
+    fn validate_entries(&mut self, node: &Yaml, kind: Kind, context: &Arc<Context>) {
+        let Yaml::Mapping(map) = node else {
+            self.errors.push(CompilerError::new_with_code(
+                context,
+                ErrorCode::InvalidType,
+                format!("expected an object, got {}", type_name(node)),
+            ));
+            return;
+        };
+        for (key, value) in map {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            self.validate(value, kind, &Arc::new(context.child(key)));
+        }
+    }
+}
+
+fn type_name(node: &Yaml) -> &'static str {
+    match node {
+        Yaml::Null => "null",
+        Yaml::Bool(_) => "a boolean",
+        Yaml::Number(_) => "a number",
+        Yaml::String(_) => "a string",
+        Yaml::Sequence(_) => "an array",
+        Yaml::Mapping(_) => "an object",
+        Yaml::Tagged(_) => "a tagged value",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn findings(source: &str) -> Vec<String> {
+        let node: Yaml = serde_yaml::from_str(source).unwrap();
+        validate_node(&node)
+            .errors
+            .iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_document() {
+        let source = r#"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: "1.0"
+  x-audience: public
+paths:
+  /pets:
+    get:
+      parameters:
+      - $ref: '#/components/parameters/limit'
+      responses:
+        '200':
+          description: ok
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: '#/components/schemas/Pet'
+        default:
+          description: error
+components:
+  parameters:
+    limit:
+      name: limit
+      in: query
+      schema:
+        type: integer
+  schemas:
+    Pet:
+      type: object
+      additionalProperties: false
+"#;
+        assert!(findings(source).is_empty(), "{:?}", findings(source));
+    }
+
+    #[test]
+    fn test_invalid_document() {
+        let source = r#"
+openapi: 3.0.3
+info:
+  titel: Pets
+  version: "1.0"
+paths:
+  pets:
+    get:
+      description: no responses
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                name:
+                  type: string
+                  nullabel: true
+      responses:
+        '20':
+          description: bad code
+tags:
+- description: no name
+"#;
+        assert_eq!(
+            findings(source),
+            [
+                "$.info has invalid property: titel (did you mean title?)",
+                "$.info is missing required property: title",
+                "$.paths has invalid property: pets",
+                "$.paths./pets.post.requestBody.content.application/json.schema.properties.name has invalid property: nullabel (did you mean nullable?)",
+                "$.tags.0 is missing required property: name",
+                "$.paths./pets.post.responses has invalid status code: 20",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_references_and_types() {
+        let source = r#"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: "1.0"
+paths: {}
+servers: https://example.com
+components:
+  schemas:
+    Pet:
+      $ref: '#/components/schemas/Animal'
+      type: object
+"#;
+        assert_eq!(
+            findings(source),
+            [
+                "$.servers expected an array, got a string",
+                "$.components.schemas.Pet has invalid property: type",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operation_ids() {
+        let source = r#"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        default:
+          description: ok
+    trace:
+      operationId: listPets
+      responses:
+        default:
+          description: ok
+    post:
+      responses:
+        default:
+          description: ok
+"#;
+        let node: Yaml = serde_yaml::from_str(source).unwrap();
+        assert_eq!(
+            findings(source),
+            ["$.paths./pets.trace.operationId duplicates operationId listPets of $.paths./pets.get"]
+        );
+        let options = ValidateOptions::new().with_required_operation_ids();
+        let errors = validate_node_with_options(&node, &options);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.errors[1].to_string(), "$.paths./pets.post is missing required property: operationId");
+    }
+
+    #[test]
+    fn test_versions() {
+        let source = r#"
+openapi: 3.0.3
+info:
+  title: Pets
+  summary: All about pets
+  version: "1.0"
+paths: {}
+webhooks: {}
+components:
+  schemas:
+    Pet:
+      type: [object, "null"]
+      nullable: true
+      $defs:
+        Name:
+          const: rex
+"#;
+        assert_eq!(
+            findings(source),
+            [
+                "$ uses webhooks, which OpenAPI 3.0.3 does not have",
+                "$.info uses summary, which OpenAPI 3.0.3 does not have",
+                "$.components.schemas.Pet uses $defs, which OpenAPI 3.0.3 does not have",
+                "$.components.schemas.Pet.$defs.Name uses const, which OpenAPI 3.0.3 does not have",
+            ]
+        );
+
+        let source = source.replace("3.0.3", "3.1.0");
+        let node: Yaml = serde_yaml::from_str(&source).unwrap();
+        let options = ValidateOptions::new().with_version_feature_severity(Severity::Warning);
+        let messages: Vec<String> =
+            validate_node_with_options(&node, &options).errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages, ["warning: $.components.schemas.Pet uses nullable, which OpenAPI 3.1.0 does not have"]);
+
+        assert_eq!(
+            findings("openapi: '3.1'\ninfo: {title: Pets, version: '1.0'}\npaths: {}\n"),
+            ["$.openapi has invalid version: 3.1"]
+        );
+        assert_eq!(
+            findings("openapi: 3.2.0\ninfo: {title: Pets, version: '1.0'}\n"),
+            ["warning: $.openapi has unsupported version 3.2.0; validating as 3.1"]
+        );
+    }
+
+    #[test]
+    fn test_discriminators() {
+        let source = r#"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: "1.0"
+paths: {}
+components:
+  schemas:
+    Pet:
+      oneOf:
+      - $ref: '#/components/schemas/Cat'
+      - $ref: '#/components/schemas/Dog'
+      - $ref: '#/components/schemas/Fish'
+      discriminator:
+        propertyName: kind
+        mapping:
+          cat: Cat
+          dog: '#/components/schemas/Dog'
+          bird: Bird
+          fish: '#/components/schemas/Fishes'
+          lizard: reptiles.yaml#/Lizard
+    Base:
+      type: object
+      required: [kind]
+      properties:
+        kind:
+          type: string
+    Cat:
+      allOf:
+      - $ref: '#/components/schemas/Base'
+    Dog:
+      type: object
+      properties:
+        kind:
+          type: string
+    Fish:
+      type: object
+"#;
+        assert_eq!(
+            findings(source),
+            [
+                "$.components.schemas.Pet.oneOf.1 does not require discriminator property kind",
+                "$.components.schemas.Pet.oneOf.2 does not have discriminator property kind",
+                "$.components.schemas.Pet.discriminator.mapping.bird maps to undefined schema Bird",
+                "$.components.schemas.Pet.discriminator.mapping.fish maps to undefined schema #/components/schemas/Fishes",
+            ]
+        );
+
+        // The parsed document keeps the combined schemas and discriminator
+        let document = crate::parse_document(source.as_bytes()).unwrap();
+        let mut parsed: Vec<String> = validate(&document).errors.iter().map(|e| e.to_string()).collect();
+        let mut expected = findings(source);
+        parsed.sort();
+        expected.sort();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_servers() {
+        let source = r#"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: "1.0"
+servers:
+- url: https://{region}.example.com:{port}/{basePath}
+  variables:
+    region:
+      default: eu
+      enum: [us, eu]
+    port:
+      default: "8443"
+      enum: ["443", "8080"]
+paths:
+  /pets:
+    servers:
+    - url: /{version}
+      variables:
+        version: {}
+"#;
+        assert_eq!(
+            findings(source),
+            [
+                "$.servers.0.url uses undefined variable basePath",
+                "$.servers.0.variables.port has default 8443 that is not one of its enum values",
+                "$.paths./pets.servers.0.variables.version is missing required property: default",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_parsed_document() {
+        let source = r#"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: "1.0"
+servers:
+- url: https://{env}.example.com
+  variables:
+    env:
+      default: prod
+security:
+- oauth: [read]
+- basic: []
+paths:
+  /pets:
+    servers:
+    - url: /{version}/{basePath}
+      variables:
+        version:
+          default: v2
+          enum: [v1]
+    get:
+      servers:
+      - url: https://{region}.example.com
+      security:
+      - oauth: [write]
+      responses:
+        '200':
+          description: pets
+components:
+  securitySchemes:
+    oauth:
+      type: oauth2
+      flows:
+        implicit:
+          authorizationUrl: https://example.com/authorize
+          scopes:
+            read: read pets
+"#;
+        let document = crate::parse_document(source.as_bytes()).unwrap();
+        // The model emits operations before the servers of a path item
+        let mut findings: Vec<String> = validate(&document).errors.iter().map(|e| e.to_string()).collect();
+        findings.sort();
+        assert_eq!(
+            findings,
+            [
+                "$.paths./pets.get.security.0.oauth uses undefined scope write of security scheme oauth",
+                "$.paths./pets.get.servers.0.url uses undefined variable region",
+                "$.paths./pets.servers.0.url uses undefined variable basePath",
+                "$.paths./pets.servers.0.variables.version has default v2 that is not one of its enum values",
+                "$.security.1 uses undefined security scheme: basic",
+            ]
+        );
+        let mut expected = self::findings(source);
+        expected.sort();
+        assert_eq!(findings, expected);
+    }
+
+    #[test]
+    fn test_incremental_validator() {
+        let source = r#"
+openapi: 3.0.3
+info: {title: Pets}
+paths:
+  /pets:
+    get: {operationId: list, responses: {'200': {}}}
+  /pets/{id}:
+    get: {operationId: list, summary: 1, responses: {}}
+  x-paths: {}
+components:
+  schemas:
+    Pet: {type: object, discriminator: {propertyName: kind}}
+"#;
+        let sorted = |group: ErrorGroup| {
+            let mut findings: Vec<String> = group.errors.iter().map(|e| e.to_string()).collect();
+            findings.sort();
+            findings
+        };
+        let mut node: Yaml = serde_yaml::from_str(source).unwrap();
+        let options = ValidateOptions::default();
+        let mut validator = IncrementalValidator::new(options.clone());
+        assert_eq!(sorted(validator.validate(&node)), sorted(validate_node_with_options(&node, &options)));
+        assert_eq!(validator.revalidated(), 2);
+
+        node["paths"]["/pets"]["get"]["operationId"] = Yaml::from("create");
+        node["info"]["version"] = Yaml::from("1.0");
+        let findings = sorted(validator.validate(&node));
+        assert_eq!(findings, sorted(validate_node_with_options(&node, &options)));
+        assert!(!findings.is_empty());
+        assert_eq!(validator.revalidated(), 1);
+
+        let properties = serde_yaml::from_str("{kind: {type: string}}").unwrap();
+        node["components"]["schemas"]["Pet"]["properties"] = properties;
+        assert_eq!(sorted(validator.validate(&node)), sorted(validate_node_with_options(&node, &options)));
+        assert_eq!(validator.revalidated(), 2);
+        assert_eq!(validator.validate(&node).errors.len(), validate_node(&node).errors.len());
+        assert_eq!(validator.revalidated(), 0);
+    }
+}
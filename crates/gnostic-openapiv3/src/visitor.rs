@@ -0,0 +1,381 @@
+//! Traversal of OpenAPI v3 documents.
+//!
+//! [`Document::accept`] walks a document and calls a [`DocumentVisitor`] for
+//! its info, servers, tags, path items, operations, parameters, request
+//! bodies, media types, responses, headers, schemas and references, including
+//! those of components and callbacks, so that analysis tools need not recurse
+//! through the models by hand. Each object is visited with its JSON pointer
+//! relative to the document root, before the objects nested in it.
+
+use gnostic_compiler::escape_json_pointer_segment;
+
+use crate::openapi_v3::*;
+
+/// Receives the objects of a document visited by [`Document::accept`]. Every
+/// callback does nothing by default.
+pub trait DocumentVisitor {
+    fn visit_info(&mut self, _pointer: &str, _info: &Info) {}
+
+    fn visit_server(&mut self, _pointer: &str, _server: &Server) {}
+
+    fn visit_tag(&mut self, _pointer: &str, _tag: &Tag) {}
+
+    /// Visits the path item of a path template, such as "/pets/{id}".
+    fn visit_path_item(&mut self, _pointer: &str, _path: &str, _path_item: &PathItem) {}
+
+    /// Visits an operation with its lowercase HTTP method.
+    fn visit_operation(&mut self, _pointer: &str, _method: &str, _operation: &Operation) {}
+
+    fn visit_parameter(&mut self, _pointer: &str, _parameter: &Parameter) {}
+
+    fn visit_request_body(&mut self, _pointer: &str, _request_body: &RequestBody) {}
+
+    /// Visits a media type with its name, such as "application/json".
+    fn visit_media_type(&mut self, _pointer: &str, _name: &str, _media_type: &MediaType) {}
+
+    fn visit_response(&mut self, _pointer: &str, _response: &Response) {}
+
+    fn visit_header(&mut self, _pointer: &str, _header: &Header) {}
+
+    /// Visits a schema; returning false skips the schemas nested in it.
+    fn visit_schema(&mut self, _pointer: &str, _schema: &Schema) -> bool {
+        true
+    }
+
+    /// Visits a reference in place of the object it refers to.
+    fn visit_reference(&mut self, _pointer: &str, _reference: &Reference) {}
+}
+
+impl Document {
+    /// Walks the document, calling `visitor` for each object in it.
+    pub fn accept(&self, visitor: &mut impl DocumentVisitor) {
+        if let Some(info) = &self.info {
+            visitor.visit_info("/info", info);
+        }
+        walk_servers("", &self.servers, visitor);
+        for (i, tag) in self.tags.iter().enumerate() {
+            visitor.visit_tag(&format!("/tags/{}", i), tag);
+        }
+        for named in self.paths.iter().flat_map(|paths| &paths.path) {
+            if let Some(path_item) = &named.value {
+                walk_path_item(&child("/paths", &named.name), &named.name, path_item, visitor);
+            }
+        }
+        if let Some(components) = &self.components {
+            walk_components(components, visitor);
+        }
+    }
+}
+
+/// Returns the pointer to the member `name` of the object at `pointer`.
+fn child(pointer: &str, name: &str) -> String {
+    format!("{}/{}", pointer, escape_json_pointer_segment(name))
+}
+
+fn walk_servers(pointer: &str, servers: &[Server], visitor: &mut impl DocumentVisitor) {
+    for (i, server) in servers.iter().enumerate() {
+        visitor.visit_server(&format!("{}/servers/{}", pointer, i), server);
+    }
+}
+
+fn walk_path_item(pointer: &str, path: &str, path_item: &PathItem, visitor: &mut impl DocumentVisitor) {
+    visitor.visit_path_item(pointer, path, path_item);
+    walk_servers(pointer, &path_item.servers, visitor);
+    walk_parameters(pointer, &path_item.parameters, visitor);
+    for (method, operation) in path_item.iter_operations() {
+        walk_operation(&child(pointer, method.as_str()), method.as_str(), operation, visitor);
+    }
+}
+
+fn walk_operation(pointer: &str, method: &str, operation: &Operation, visitor: &mut impl DocumentVisitor) {
+    visitor.visit_operation(pointer, method, operation);
+    walk_parameters(pointer, &operation.parameters, visitor);
+    if let Some(request_body) = &operation.request_body {
+        walk_request_body_or_reference(&child(pointer, "requestBody"), request_body, visitor);
+    }
+    if let Some(responses) = &operation.responses {
+        let pointer = child(pointer, "responses");
+        if let Some(response) = &responses.default {
+            walk_response_or_reference(&child(&pointer, "default"), response, visitor);
+        }
+        for named in &responses.response_or_reference {
+            if let Some(response) = &named.value {
+                walk_response_or_reference(&child(&pointer, &named.name), response, visitor);
+            }
+        }
+    }
+    if let Some(callbacks) = &operation.callbacks {
+        walk_callbacks(&child(pointer, "callbacks"), callbacks, visitor);
+    }
+    walk_servers(pointer, &operation.servers, visitor);
+}
+
+fn walk_parameters(pointer: &str, parameters: &[ParameterOrReference], visitor: &mut impl DocumentVisitor) {
+    for (i, parameter) in parameters.iter().enumerate() {
+        walk_parameter_or_reference(&format!("{}/parameters/{}", pointer, i), parameter, visitor);
+    }
+}
+
+fn walk_parameter_or_reference(pointer: &str, parameter: &ParameterOrReference, visitor: &mut impl DocumentVisitor) {
+    match &parameter.oneof {
+        Some(parameter_or_reference::Oneof::Parameter(parameter)) => {
+            visitor.visit_parameter(pointer, parameter);
+            if let Some(schema) = &parameter.schema {
+                walk_schema_or_reference(&child(pointer, "schema"), schema, visitor);
+            }
+            walk_media_types(pointer, &parameter.content, visitor);
+        }
+        Some(parameter_or_reference::Oneof::Reference(reference)) => visitor.visit_reference(pointer, reference),
+        None => {}
+    }
+}
+
+fn walk_request_body_or_reference(
+    pointer: &str,
+    request_body: &RequestBodyOrReference,
+    visitor: &mut impl DocumentVisitor,
+) {
+    match &request_body.oneof {
+        Some(request_body_or_reference::Oneof::RequestBody(request_body)) => {
+            visitor.visit_request_body(pointer, request_body);
+            walk_media_types(pointer, &request_body.content, visitor);
+        }
+        Some(request_body_or_reference::Oneof::Reference(reference)) => visitor.visit_reference(pointer, reference),
+        None => {}
+    }
+}
+
+fn walk_response_or_reference(pointer: &str, response: &ResponseOrReference, visitor: &mut impl DocumentVisitor) {
+    match &response.oneof {
+        Some(response_or_reference::Oneof::Response(response)) => {
+            visitor.visit_response(pointer, response);
+            walk_headers(pointer, &response.headers, visitor);
+            walk_media_types(pointer, &response.content, visitor);
+        }
+        Some(response_or_reference::Oneof::Reference(reference)) => visitor.visit_reference(pointer, reference),
+        None => {}
+    }
+}
+
+fn walk_headers(pointer: &str, headers: &Option<HeadersOrReferences>, visitor: &mut impl DocumentVisitor) {
+    for named in headers.iter().flat_map(|headers| &headers.additional_properties) {
+        if let Some(header) = &named.value {
+            walk_header_or_reference(&child(&child(pointer, "headers"), &named.name), header, visitor);
+        }
+    }
+}
+
+fn walk_header_or_reference(pointer: &str, header: &HeaderOrReference, visitor: &mut impl DocumentVisitor) {
+    match &header.oneof {
+        Some(header_or_reference::Oneof::Header(header)) => {
+            visitor.visit_header(pointer, header);
+            if let Some(schema) = &header.schema {
+                walk_schema_or_reference(&child(pointer, "schema"), schema, visitor);
+            }
+            walk_media_types(pointer, &header.content, visitor);
+        }
+        Some(header_or_reference::Oneof::Reference(reference)) => visitor.visit_reference(pointer, reference),
+        None => {}
+    }
+}
+
+fn walk_media_types(pointer: &str, content: &Option<MediaTypes>, visitor: &mut impl DocumentVisitor) {
+    for named in content.iter().flat_map(|content| &content.additional_properties) {
+        let Some(media_type) = &named.value else {
+            continue;
+        };
+        let pointer = child(&child(pointer, "content"), &named.name);
+        visitor.visit_media_type(&pointer, &named.name, media_type);
+        if let Some(schema) = &media_type.schema {
+            walk_schema_or_reference(&child(&pointer, "schema"), schema, visitor);
+        }
+        for named in media_type.encoding.iter().flat_map(|encodings| &encodings.additional_properties) {
+            if let Some(encoding) = &named.value {
+                walk_headers(&child(&child(&pointer, "encoding"), &named.name), &encoding.headers, visitor);
+            }
+        }
+    }
+}
+
+fn walk_callbacks(pointer: &str, callbacks: &CallbacksOrReferences, visitor: &mut impl DocumentVisitor) {
+    for named in &callbacks.additional_properties {
+        let pointer = child(pointer, &named.name);
+        match named.value.as_ref().and_then(|callback| callback.oneof.as_ref()) {
+            Some(callback_or_reference::Oneof::Callback(callback)) => {
+                for named in &callback.path {
+                    if let Some(path_item) = &named.value {
+                        walk_path_item(&child(&pointer, &named.name), &named.name, path_item, visitor);
+                    }
+                }
+            }
+            Some(callback_or_reference::Oneof::Reference(reference)) => visitor.visit_reference(&pointer, reference),
+            None => {}
+        }
+    }
+}
+
+fn walk_schema_or_reference(pointer: &str, schema: &SchemaOrReference, visitor: &mut impl DocumentVisitor) {
+    match &schema.oneof {
+        Some(schema_or_reference::Oneof::Schema(schema)) => walk_schema(pointer, schema, visitor),
+        Some(schema_or_reference::Oneof::Reference(reference)) => visitor.visit_reference(pointer, reference),
+        None => {}
+    }
+}
+
+fn walk_schema(pointer: &str, schema: &Schema, visitor: &mut impl DocumentVisitor) {
+    if !visitor.visit_schema(pointer, schema) {
+        return;
+    }
+    for (keyword, schemas) in [("allOf", &schema.all_of), ("oneOf", &schema.one_of), ("anyOf", &schema.any_of)] {
+        for (i, nested) in schemas.iter().enumerate() {
+            walk_schema_or_reference(&format!("{}/{}/{}", pointer, keyword, i), nested, visitor);
+        }
+    }
+    if let Some(not) = &schema.not {
+        walk_schema(&child(pointer, "not"), not, visitor);
+    }
+    if let Some(items) = &schema.items {
+        // A single items schema is written as an object rather than a sequence.
+        let items = &items.schema_or_reference;
+        for (i, nested) in items.iter().enumerate() {
+            let pointer = if items.len() == 1 { child(pointer, "items") } else { format!("{}/items/{}", pointer, i) };
+            walk_schema_or_reference(&pointer, nested, visitor);
+        }
+    }
+    for named in schema.properties.iter().flat_map(|properties| &properties.additional_properties) {
+        if let Some(nested) = &named.value {
+            walk_schema_or_reference(&child(&child(pointer, "properties"), &named.name), nested, visitor);
+        }
+    }
+    if let Some(AdditionalPropertiesItem {
+        oneof: Some(additional_properties_item::Oneof::SchemaOrReference(nested)),
+    }) = schema.additional_properties.as_deref()
+    {
+        walk_schema_or_reference(&child(pointer, "additionalProperties"), nested, visitor);
+    }
+}
+
+fn walk_components(components: &Components, visitor: &mut impl DocumentVisitor) {
+    let pointer = "/components";
+    for named in components.schemas.iter().flat_map(|schemas| &schemas.additional_properties) {
+        if let Some(schema) = &named.value {
+            walk_schema_or_reference(&child(&child(pointer, "schemas"), &named.name), schema, visitor);
+        }
+    }
+    for named in components.responses.iter().flat_map(|responses| &responses.additional_properties) {
+        if let Some(response) = &named.value {
+            walk_response_or_reference(&child(&child(pointer, "responses"), &named.name), response, visitor);
+        }
+    }
+    for named in components.parameters.iter().flat_map(|parameters| &parameters.additional_properties) {
+        if let Some(parameter) = &named.value {
+            walk_parameter_or_reference(&child(&child(pointer, "parameters"), &named.name), parameter, visitor);
+        }
+    }
+    for named in components.request_bodies.iter().flat_map(|request_bodies| &request_bodies.additional_properties) {
+        if let Some(request_body) = &named.value {
+            let pointer = child(&child(pointer, "requestBodies"), &named.name);
+            walk_request_body_or_reference(&pointer, request_body, visitor);
+        }
+    }
+    walk_headers(pointer, &components.headers, visitor);
+    if let Some(callbacks) = &components.callbacks {
+        walk_callbacks(&child(pointer, "callbacks"), callbacks, visitor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parse_document;
+
+    #[derive(Default)]
+    struct Recorder {
+        visits: Vec<String>,
+    }
+
+    impl DocumentVisitor for Recorder {
+        fn visit_info(&mut self, pointer: &str, info: &Info) {
+            self.visits.push(format!("info {} {}", pointer, info.title));
+        }
+
+        fn visit_path_item(&mut self, pointer: &str, path: &str, _: &PathItem) {
+            self.visits.push(format!("path {} {}", pointer, path));
+        }
+
+        fn visit_operation(&mut self, pointer: &str, method: &str, operation: &Operation) {
+            self.visits.push(format!("operation {} {} {}", pointer, method, operation.operation_id));
+        }
+
+        fn visit_parameter(&mut self, pointer: &str, parameter: &Parameter) {
+            self.visits.push(format!("parameter {} {}", pointer, parameter.name));
+        }
+
+        fn visit_media_type(&mut self, pointer: &str, name: &str, _: &MediaType) {
+            self.visits.push(format!("media type {} {}", pointer, name));
+        }
+
+        fn visit_response(&mut self, pointer: &str, _: &Response) {
+            self.visits.push(format!("response {}", pointer));
+        }
+
+        fn visit_schema(&mut self, pointer: &str, _: &Schema) -> bool {
+            self.visits.push(format!("schema {}", pointer));
+            !pointer.ends_with("/owner")
+        }
+
+        fn visit_reference(&mut self, pointer: &str, reference: &Reference) {
+            self.visits.push(format!("reference {} {}", pointer, reference.r#ref));
+        }
+    }
+
+    #[test]
+    fn test_accept() {
+        let doc = parse_document(
+            br##"
+openapi: 3.0.3
+info: {title: Pets, version: 1.0.0}
+paths:
+  /pets/{id}:
+    parameters:
+      - {name: id, in: path, required: true, schema: {type: string}}
+    get:
+      operationId: getPet
+      responses:
+        "200":
+          description: A pet.
+          content:
+            application/json:
+              schema: {$ref: "#/components/schemas/Pet"}
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        tags: {type: array, items: {type: string}}
+        owner: {type: object, properties: {name: {type: string}}}
+"##,
+        )
+        .unwrap();
+        let mut recorder = Recorder::default();
+        doc.accept(&mut recorder);
+        assert_eq!(
+            recorder.visits,
+            [
+                "info /info Pets",
+                "path /paths/~1pets~1{id} /pets/{id}",
+                "parameter /paths/~1pets~1{id}/parameters/0 id",
+                "schema /paths/~1pets~1{id}/parameters/0/schema",
+                "operation /paths/~1pets~1{id}/get get getPet",
+                "response /paths/~1pets~1{id}/get/responses/200",
+                "media type /paths/~1pets~1{id}/get/responses/200/content/application~1json application/json",
+                "reference /paths/~1pets~1{id}/get/responses/200/content/application~1json/schema \
+                 #/components/schemas/Pet",
+                "schema /components/schemas/Pet",
+                "schema /components/schemas/Pet/properties/tags",
+                "schema /components/schemas/Pet/properties/tags/items",
+                "schema /components/schemas/Pet/properties/owner",
+            ]
+        );
+    }
+}
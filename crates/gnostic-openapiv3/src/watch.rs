@@ -0,0 +1,112 @@
+//! Watching OpenAPI v3 documents for changes.
+//!
+//! Editors and daemons that show the problems of a document as it is edited
+//! can watch its file, which is parsed and validated again on each change.
+//! Validation reuses the findings of the path items that did not change.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use gnostic_compiler::{
+    read_info_from_bytes_with_limits, ErrorGroup, FileWatcher, ParseOptions, Parsed, ValidateOptions, DEFAULT_WATCH_INTERVAL,
+};
+
+use crate::document::{document_node, parse_node_at};
+use crate::openapi_v3::Document;
+use crate::validator::IncrementalValidator;
+
+/// A version of a watched document.
+#[derive(Debug)]
+pub struct DocumentUpdate {
+    /// The result of reading and parsing the document.
+    pub document: Result<Parsed<Document>, ErrorGroup>,
+    /// The validation findings of the document, which are empty if it could
+    /// not be read.
+    pub findings: ErrorGroup,
+}
+
+/// Watches the file of a document, calling back with the document each time
+/// the file changes.
+///
+/// The callback runs on the watcher's thread, first with the document as it
+/// is when watching starts. Watching stops when the watcher is dropped.
+pub fn watch_document<F>(path: impl Into<PathBuf>, callback: F) -> FileWatcher
+where
+    F: FnMut(DocumentUpdate) + Send + 'static,
+{
+    watch_document_with_options(
+        path,
+        &ParseOptions::default(),
+        &ValidateOptions::default(),
+        DEFAULT_WATCH_INTERVAL,
+        callback,
+    )
+}
+
+/// Watches the file of a document with the given options, polling it at the
+/// given interval.
+///
+/// Relative external references are resolved against the path, and cached
+/// external documents are kept between changes.
+pub fn watch_document_with_options<F>(
+    path: impl Into<PathBuf>,
+    options: &ParseOptions,
+    validate: &ValidateOptions,
+    interval: Duration,
+    mut callback: F,
+) -> FileWatcher
+where
+    F: FnMut(DocumentUpdate) + Send + 'static,
+{
+    let path = path.into();
+    let base = path.to_string_lossy().into_owned();
+    let options = options.clone();
+    let mut validator = IncrementalValidator::new(validate.clone());
+    FileWatcher::new(path, interval, move |result| {
+        let yaml = result.and_then(|bytes| read_info_from_bytes_with_limits("", &bytes, &options.limits()).map(|yaml| (bytes, yaml)));
+        let update = match yaml {
+            Ok((bytes, yaml)) => {
+                let node = document_node(&yaml);
+                DocumentUpdate {
+                    document: parse_node_at(&base, node, &bytes, &options),
+                    findings: validator.validate(node),
+                }
+            }
+            Err(e) => DocumentUpdate { document: Err(ErrorGroup::new(vec![e])), findings: ErrorGroup::new(vec![]) },
+        };
+        callback(update);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_watch_document() {
+        let path = std::env::temp_dir().join(format!("gnostic-watch-v3-{}.yaml", std::process::id()));
+        std::fs::write(&path, "openapi: 3.0.0\ninfo: {title: Pets, version: '1'}\npaths: {}\n").unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let (options, validate) = (ParseOptions::default(), ValidateOptions::default());
+        let watcher = watch_document_with_options(&path, &options, &validate, Duration::from_millis(5), move |update| {
+            let _ = sender.send(update);
+        });
+        let wait = || receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        let update = wait();
+        assert_eq!(update.document.unwrap().value.info.unwrap().title, "Pets");
+        assert!(update.findings.errors.is_empty());
+
+        std::fs::write(&path, "openapi: 3.0.0\ninfo: {title: Pets}\npaths: {/pets: {get: {}}}\n").unwrap();
+        let update = wait();
+        assert!(update.document.is_ok());
+        let findings = update.findings.to_string();
+        assert!(findings.contains("$.info is missing required property: version"), "{}", findings);
+        assert!(findings.contains("$.paths./pets.get is missing required property: responses"), "{}", findings);
+
+        std::fs::write(&path, "openapi: [").unwrap();
+        assert!(wait().document.is_err());
+        watcher.stop();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,6 +1,11 @@
 //! Integration tests comparing Rust parsing with Go reference output.
 
-use gnostic_openapiv3::document::parse_document;
+use gnostic_compiler::{EmitOptions, ParseOptions, QuoteStyle, SequenceStyle, Severity, SplitOptions};
+use gnostic_openapiv3::Resolver;
+use gnostic_openapiv3::document::{
+    json_value_with_options, parse_document, parse_document_from_file_with_options, parse_document_with_options,
+    yaml_value, yaml_value_with_options,
+};
 use serde_json::Value;
 use std::fs;
 
@@ -160,6 +165,7 @@ fn test_openapiv3_paths_detail() {
 }
 
 #[test]
+#[allow(unused_variables, clippy::assertions_on_constants)]
 fn test_openapiv3_components() {
     let bytes = load_openapi_file("petstore-v3.yaml");
     let doc = parse_document(&bytes).expect("Failed to parse petstore-v3.yaml");
@@ -199,3 +205,1092 @@ fn test_openapiv3_components() {
         }
     }
 }
+
+#[test]
+fn test_openapiv3_parameters_detail() {
+    use gnostic_openapiv3::openapi_v3::parameter_or_reference::Oneof;
+
+    let bytes = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v3.yaml");
+    let reference = load_reference("petstore-v3-reference.json");
+
+    let ref_paths = reference["paths"]["path"].as_array().expect("reference paths should be array");
+    let mut checked = 0;
+    for (path_item, ref_path) in doc.paths.as_ref().unwrap().path.iter().zip(ref_paths) {
+        assert_eq!(path_item.name, ref_path["name"].as_str().unwrap());
+        for (method, operation) in path_item.value.as_ref().unwrap().iter_operations() {
+            let ref_parameters = ref_path["value"][method.as_str()]["parameters"].as_array();
+            let ref_parameters = ref_parameters.map(Vec::as_slice).unwrap_or_default();
+            assert_eq!(operation.parameters.len(), ref_parameters.len(),
+                "parameters count mismatch for {} {}", method.as_str(), path_item.name);
+            for (parameter, ref_parameter) in operation.parameters.iter().zip(ref_parameters) {
+                let Some(Oneof::Parameter(parameter)) = &parameter.oneof else {
+                    panic!("parameter of {} {} should not be a reference", method.as_str(), path_item.name);
+                };
+                let ref_parameter = &ref_parameter["parameter"];
+                assert_eq!(parameter.name, ref_parameter["name"].as_str().unwrap_or(""));
+                assert_eq!(parameter.r#in, ref_parameter["in"].as_str().unwrap_or(""));
+                assert_eq!(parameter.description, ref_parameter["description"].as_str().unwrap_or(""));
+                assert_eq!(parameter.required, ref_parameter["required"].as_bool().unwrap_or(false));
+                assert_eq!(parameter.explode, ref_parameter["explode"].as_bool().unwrap_or(false));
+                assert!(parameter.schema.is_some(), "{} has no schema", parameter.name);
+                checked += 1;
+            }
+        }
+    }
+    assert!(checked > 0);
+
+    // Parameters of path items and components, and references to them
+    let source = br#"
+openapi: 3.0.3
+info: {title: Pets, version: "1.0"}
+paths:
+  /pets/{id}:
+    parameters:
+    - {name: id, in: path, required: true, schema: {type: string}}
+    get:
+      parameters:
+      - $ref: '#/components/parameters/limit'
+      responses: {}
+components:
+  parameters:
+    limit: {name: limit, in: query, allowEmptyValue: true}
+"#;
+    let doc = parse_document(source).unwrap();
+    let item = doc.paths.unwrap().path.remove(0).value.unwrap();
+    assert!(matches!(&item.parameters[0].oneof, Some(Oneof::Parameter(parameter)) if parameter.name == "id"));
+    assert!(matches!(
+        &item.get.unwrap().parameters[0].oneof,
+        Some(Oneof::Reference(reference)) if reference.r#ref == "#/components/parameters/limit"
+    ));
+    let parameters = doc.components.unwrap().parameters.unwrap().additional_properties;
+    assert_eq!(parameters[0].name, "limit");
+    assert!(matches!(
+        &parameters[0].value.as_ref().unwrap().oneof,
+        Some(Oneof::Parameter(parameter)) if parameter.allow_empty_value && parameter.r#in == "query"
+    ));
+}
+
+#[test]
+fn test_openapiv3_request_bodies_detail() {
+    use gnostic_openapiv3::openapi_v3::{request_body_or_reference, response_or_reference, MediaTypes};
+
+    let bytes = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v3.yaml");
+    let reference = load_reference("petstore-v3-reference.json");
+
+    let media_type_names = |content: &Option<MediaTypes>| -> Vec<String> {
+        content.iter().flat_map(|content| &content.additional_properties).map(|m| m.name.clone()).collect()
+    };
+    let ref_media_type_names = |content: &Value| -> Vec<String> {
+        content["additionalProperties"].as_array().into_iter().flatten()
+            .filter_map(|m| m["name"].as_str().map(String::from))
+            .collect()
+    };
+
+    let ref_paths = reference["paths"]["path"].as_array().expect("reference paths should be array");
+    let mut checked = 0;
+    for (path_item, ref_path) in doc.paths.as_ref().unwrap().path.iter().zip(ref_paths) {
+        for (method, operation) in path_item.value.as_ref().unwrap().iter_operations() {
+            let ref_operation = &ref_path["value"][method.as_str()];
+            let ref_body = &ref_operation["requestBody"]["requestBody"];
+            match &operation.request_body.as_ref().and_then(|body| body.oneof.as_ref()) {
+                Some(request_body_or_reference::Oneof::RequestBody(body)) => {
+                    assert_eq!(body.description, ref_body["description"].as_str().unwrap_or(""));
+                    assert_eq!(media_type_names(&body.content), ref_media_type_names(&ref_body["content"]),
+                        "request body content mismatch for {} {}", method.as_str(), path_item.name);
+                    assert!(body.content.iter().flat_map(|c| &c.additional_properties)
+                        .all(|m| m.value.as_ref().is_some_and(|m| m.schema.is_some())));
+                    checked += 1;
+                }
+                Some(request_body_or_reference::Oneof::Reference(reference)) => {
+                    assert_eq!(reference.r#ref, ref_operation["requestBody"]["reference"]["Ref"].as_str().unwrap());
+                }
+                None => assert!(ref_operation["requestBody"].is_null()),
+            }
+
+            let ref_responses = ref_operation["responses"]["responseOrReference"].as_array().unwrap();
+            let responses = &operation.responses.as_ref().unwrap().response_or_reference;
+            for (response, ref_response) in responses.iter().zip(ref_responses) {
+                if let Some(response_or_reference::Oneof::Response(value)) = &response.value.as_ref().unwrap().oneof {
+                    let ref_content = &ref_response["value"]["response"]["content"];
+                    assert_eq!(media_type_names(&value.content), ref_media_type_names(ref_content),
+                        "response {} content mismatch for {} {}", response.name, method.as_str(), path_item.name);
+                }
+            }
+        }
+    }
+    assert!(checked > 0);
+
+    let request_bodies = doc.components.as_ref().unwrap().request_bodies.as_ref().unwrap();
+    let ref_request_bodies = reference["components"]["requestBodies"]["additionalProperties"].as_array().unwrap();
+    assert_eq!(request_bodies.additional_properties.len(), ref_request_bodies.len());
+    for (body, ref_body) in request_bodies.additional_properties.iter().zip(ref_request_bodies) {
+        assert_eq!(body.name, ref_body["name"].as_str().unwrap());
+        let Some(request_body_or_reference::Oneof::RequestBody(value)) = &body.value.as_ref().unwrap().oneof else {
+            panic!("request body {} should not be a reference", body.name);
+        };
+        let ref_value = &ref_body["value"]["requestBody"];
+        assert_eq!(value.description, ref_value["description"].as_str().unwrap_or(""));
+        assert_eq!(value.required, ref_value["required"].as_bool().unwrap_or(false));
+        assert_eq!(media_type_names(&value.content), ref_media_type_names(&ref_value["content"]));
+    }
+}
+
+#[test]
+fn test_openapiv3_preserves_source_order() {
+    let bytes = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v3.yaml");
+    let reference = load_reference("petstore-v3-reference.json");
+
+    // Named* entries must appear in the same order as in the source document
+    let path_names: Vec<&str> = doc.paths.as_ref().expect("paths should exist")
+        .path.iter().map(|p| p.name.as_str()).collect();
+    let ref_path_names: Vec<&str> = reference["paths"]["path"].as_array().unwrap()
+        .iter().filter_map(|p| p["name"].as_str()).collect();
+    assert_eq!(path_names, ref_path_names, "paths order mismatch");
+
+    let schemas = doc.components.as_ref().and_then(|c| c.schemas.as_ref())
+        .expect("schemas should exist");
+    let schema_names: Vec<&str> = schemas.additional_properties.iter()
+        .map(|s| s.name.as_str()).collect();
+    let ref_schema_names: Vec<&str> = reference["components"]["schemas"]["additionalProperties"]
+        .as_array().unwrap()
+        .iter().filter_map(|s| s["name"].as_str()).collect();
+    assert_eq!(schema_names, ref_schema_names, "schemas order mismatch");
+
+    let response_codes: Vec<&str> = doc.paths.as_ref().unwrap().path[0].value.as_ref().unwrap()
+        .put.as_ref().unwrap().responses.as_ref().unwrap()
+        .response_or_reference.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(response_codes, vec!["200", "400", "404", "422", "default"]);
+}
+
+#[test]
+fn test_openapiv3_schema_examples_with_dates() {
+    let yaml = br#"
+openapi: 3.0.3
+info:
+  title: Dates
+  version: 1.0.0
+paths: {}
+components:
+  schemas:
+    Event:
+      type: object
+      properties:
+        day:
+          type: string
+          format: date
+          example: 2024-01-01
+        at:
+          type: string
+          format: date-time
+          example: !!timestamp 2024-01-01T10:00:00Z
+        code:
+          type: string
+          example: !custom ABC
+"#;
+    let doc = parse_document(yaml).expect("Failed to parse document with dates");
+    let event = &doc.components.as_ref().unwrap().schemas.as_ref().unwrap()
+        .additional_properties[0];
+    let properties = match event.value.as_ref().unwrap().oneof.as_ref().unwrap() {
+        gnostic_openapiv3::openapi_v3::schema_or_reference::Oneof::Schema(s) => {
+            &s.properties.as_ref().unwrap().additional_properties
+        }
+        _ => panic!("expected schema"),
+    };
+
+    let example = |i: usize| -> serde_yaml::Value {
+        let schema = match properties[i].value.as_ref().unwrap().oneof.as_ref().unwrap() {
+            gnostic_openapiv3::openapi_v3::schema_or_reference::Oneof::Schema(s) => s,
+            _ => panic!("expected schema"),
+        };
+        serde_yaml::from_str(&schema.example.as_ref().expect("example should exist").yaml).unwrap()
+    };
+    assert_eq!(example(0), serde_yaml::Value::String("2024-01-01".to_string()));
+    assert_eq!(example(1), serde_yaml::Value::String("2024-01-01T10:00:00Z".to_string()));
+    assert_eq!(gnostic_compiler::string_for_scalar_node(&example(2)), Some("ABC".to_string()));
+}
+
+#[test]
+fn test_openapiv3_fail_fast() {
+    let source = br#"
+openapi: 3.0.0
+info:
+  title: Broken
+  version: "1.0"
+servers: not-a-list
+paths: {}
+tags: not-a-list
+"#;
+
+    let err = parse_document(source).expect_err("parse should fail");
+    assert_eq!(err.errors.len(), 2);
+
+    let err = parse_document_with_options(source, &ParseOptions::fail_fast())
+        .expect_err("fail-fast parse should fail");
+    assert_eq!(err.errors.len(), 1);
+    assert!(err.errors[0].to_string().contains("servers must be an array"));
+}
+
+#[test]
+fn test_openapiv3_sort_errors_by_path() {
+    let source = br#"
+openapi: 3.0.0
+info:
+  title: Broken
+  version: "1.0"
+tags: not-a-list
+paths:
+  /b:
+    get:
+      servers: not-a-list
+      parameters: not-a-list
+      responses: {}
+  /a:
+    servers: not-a-list
+    get:
+      security: not-a-list
+      responses: {}
+servers: not-a-list
+"#;
+
+    // Parse errors carry a path but no position, so they are ordered by path
+    let mut err = parse_document(source).expect_err("parse should fail");
+    assert!(err.errors.iter().all(|e| e.location().is_none() && e.path().is_some()));
+    err.errors.reverse();
+    err.sort_by_location();
+    let paths: Vec<_> = err.errors.iter().map(|e| e.path().unwrap()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "$.paths./a.get.security",
+            "$.paths./a.servers",
+            "$.paths./b.get.parameters",
+            "$.paths./b.get.servers",
+            "$.servers",
+            "$.tags",
+        ]
+    );
+}
+
+#[test]
+fn test_openapiv3_max_errors() {
+    let source = br#"
+openapi: 3.0.0
+info:
+  title: Broken
+  version: "1.0"
+servers: not-a-list
+paths: {}
+tags: not-a-list
+"#;
+
+    let err = parse_document_with_options(source, &ParseOptions::default().with_max_errors(1))
+        .expect_err("parse should fail");
+    assert_eq!(err.errors.len(), 2);
+    assert!(err.errors[0].to_string().contains("servers must be an array"));
+    assert_eq!(err.errors[1].to_string(), "$ and 1 more error");
+    assert_eq!(err.errors[1].severity(), Severity::Error);
+
+    // Capped warnings are summarized as warnings
+    let parsed = parse_document_with_options(source, &ParseOptions::lenient().with_max_errors(1))
+        .expect("lenient parse should succeed");
+    assert_eq!(parsed.warnings.len(), 2);
+    assert_eq!(parsed.warnings.errors[1].to_string(), "warning: $ and 1 more warning");
+    assert!(!parsed.warnings.has_errors());
+}
+
+#[test]
+fn test_openapiv3_yaml_round_trip() {
+    let bytes = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v3.yaml");
+
+    let yaml = yaml_value(&doc);
+    assert!(!yaml.is_empty());
+    let reparsed = parse_document(&yaml).expect("Failed to parse emitted YAML");
+    assert_eq!(doc, reparsed);
+
+    // Emitting again is stable
+    assert_eq!(yaml, yaml_value(&reparsed));
+}
+
+#[test]
+fn test_openapiv3_yaml_emitter_shapes() {
+    let source = br#"
+openapi: 3.0.0
+info:
+  title: Shapes
+  version: "1.0"
+paths: {}
+components:
+  schemas:
+    Pets:
+      type: array
+      items:
+        $ref: '#/components/schemas/Pet'
+    Pet:
+      type: object
+      required: [id]
+      properties:
+        id:
+          type: integer
+          format: int64
+"#;
+    let doc = parse_document(source).expect("Failed to parse document");
+    let yaml: serde_yaml::Value = serde_yaml::from_slice(&yaml_value(&doc)).unwrap();
+
+    let schemas = &yaml["components"]["schemas"];
+    assert_eq!(schemas["Pets"]["items"]["$ref"], "#/components/schemas/Pet");
+    assert_eq!(schemas["Pet"]["required"][0], "id");
+    assert_eq!(schemas["Pet"]["properties"]["id"]["format"], "int64");
+    // Default values are omitted
+    assert!(schemas["Pet"].get("nullable").is_none());
+    assert!(yaml.get("servers").is_none());
+}
+
+#[test]
+fn test_openapiv3_canonical_output() {
+    let source = br#"
+openapi: 3.0.0
+info:
+  title: Ordering
+  version: "1.0"
+paths:
+  /pets: {}
+  /owners: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name:
+          type: string
+        id:
+          type: integer
+    Owner:
+      type: object
+"#;
+    let doc = parse_document(source).expect("Failed to parse document");
+    let keys = |node: &serde_yaml::Value| -> Vec<String> {
+        node.as_mapping().unwrap().keys().map(|k| k.as_str().unwrap().to_string()).collect()
+    };
+
+    // As-authored output keeps the source order
+    let authored: serde_yaml::Value = serde_yaml::from_slice(&yaml_value(&doc)).unwrap();
+    assert_eq!(keys(&authored["paths"]), vec!["/pets", "/owners"]);
+    assert_eq!(keys(&authored["components"]["schemas"]), vec!["Pet", "Owner"]);
+
+    let canonical = yaml_value_with_options(&doc, &EmitOptions::canonical());
+    let yaml: serde_yaml::Value = serde_yaml::from_slice(&canonical).unwrap();
+    assert_eq!(keys(&yaml["paths"]), vec!["/owners", "/pets"]);
+    assert_eq!(keys(&yaml["components"]["schemas"]), vec!["Owner", "Pet"]);
+    assert_eq!(keys(&yaml["components"]["schemas"]["Pet"]["properties"]), vec!["id", "name"]);
+    // Fixed fields keep specification order
+    assert_eq!(keys(&yaml), vec!["openapi", "info", "paths", "components"]);
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&json_value_with_options(&doc, &EmitOptions::canonical())).unwrap();
+    assert_eq!(json["components"]["schemas"]["Pet"]["properties"]["id"]["type"], "integer");
+
+    // Canonical output is the same regardless of source order
+    let reordered = parse_document(&canonical).expect("Failed to parse canonical YAML");
+    assert_eq!(canonical, yaml_value_with_options(&reordered, &EmitOptions::canonical()));
+}
+
+#[test]
+fn test_openapiv3_emitter_styles() {
+    let bytes = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v3.yaml");
+
+    let options = EmitOptions::default()
+        .with_indent(4)
+        .with_quote_style(QuoteStyle::Double)
+        .with_sequence_style(SequenceStyle::Flow)
+        .with_indented_sequences(true)
+        .with_line_width(80);
+    let yaml = yaml_value_with_options(&doc, &options);
+    let text = String::from_utf8(yaml.clone()).unwrap();
+    assert!(text.contains("\n    /pet:\n        put:\n"));
+    assert!(text.contains("\n                \"200\":\n"));
+    assert!(text.contains("tags: [pet]"));
+    assert!(!text.contains(": '"), "no single-quoted scalars expected");
+    assert!(text.contains("description: \"Multiple status values can be provided with comma \\\n"));
+
+    let reparsed = parse_document(&yaml).expect("Failed to parse styled YAML");
+    assert_eq!(doc, reparsed);
+}
+
+#[test]
+fn test_openapiv3_raw_info_round_trip() {
+    let source = br#"openapi: 3.0.0
+info:
+  version: '1.0'
+  title: Raw
+  x-logo: logo.png
+  summary: not in 3.0
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: ok
+      x-internal: true
+webhooks: {}
+"#;
+    let options = ParseOptions::default().with_raw_info();
+    let parsed = parse_document_with_options(source, &options).expect("Failed to parse document");
+    let raw_info = parsed.raw_info.expect("raw info should be preserved");
+    let emit = EmitOptions::default().with_raw_info(raw_info);
+
+    // Unchanged documents are written back exactly as parsed
+    let expected: serde_yaml::Value = serde_yaml::from_slice(source).unwrap();
+    let output = yaml_value_with_options(&parsed.value, &emit);
+    assert_eq!(output, serde_yaml::to_string(&expected).unwrap().into_bytes());
+
+    // Edits are kept while unknown fields survive
+    let mut doc = parsed.value;
+    doc.info.as_mut().unwrap().title = "Edited".to_string();
+    let yaml: serde_yaml::Value =
+        serde_yaml::from_slice(&yaml_value_with_options(&doc, &emit)).unwrap();
+    assert_eq!(yaml["info"]["title"], "Edited");
+    assert_eq!(yaml["info"]["summary"], "not in 3.0");
+    assert_eq!(yaml["paths"]["/pets"]["get"]["x-internal"], true);
+    assert!(yaml["webhooks"].is_mapping());
+
+    // Without raw info the unknown fields are dropped
+    assert!(parse_document_with_options(source, &ParseOptions::default()).unwrap().raw_info.is_none());
+    let yaml: serde_yaml::Value = serde_yaml::from_slice(&yaml_value(&doc)).unwrap();
+    assert!(yaml.get("webhooks").is_none());
+}
+
+#[test]
+fn test_openapiv3_comments_round_trip() {
+    let source = "\
+# Pet store API
+openapi: 3.0.0 # spec version
+info:
+  title: Pets
+  # bumped on release
+  version: '1.0'
+paths:
+  /pets:
+    get:
+      tags:
+      # primary tag
+      - pets
+      - animals # legacy
+      responses:
+        '200': # success
+          description: ok
+# end of document
+";
+    let options = ParseOptions::default().with_raw_info().with_comments();
+    let parsed = parse_document_with_options(source.as_bytes(), &options).expect("Failed to parse document");
+    let emit = EmitOptions::default()
+        .with_raw_info(parsed.raw_info.expect("raw info should be preserved"))
+        .with_comments(parsed.comments.expect("comments should be preserved"));
+
+    let output = yaml_value_with_options(&parsed.value, &emit);
+    assert_eq!(String::from_utf8(output).unwrap(), source);
+
+    // Comments stay with their nodes when the document is edited
+    let mut doc = parsed.value;
+    doc.info.as_mut().unwrap().version = "2.0".to_string();
+    let text = String::from_utf8(yaml_value_with_options(&doc, &emit)).unwrap();
+    assert!(text.contains("  # bumped on release\n  version: '2.0'\n"), "{}", text);
+    assert_eq!(parse_document(text.as_bytes()).expect("Failed to parse output"), doc);
+
+    assert!(parse_document_with_options(source.as_bytes(), &ParseOptions::default()).unwrap().comments.is_none());
+}
+
+#[test]
+fn test_openapiv3_resolve_external_references() {
+    let path = format!("{}/refs/openapi.yaml", TESTDATA_DIR);
+    let doc = parse_document(&load_openapi_file("refs/openapi.yaml")).expect("Failed to parse refs/openapi.yaml");
+    let resolved = Resolver::new().with_base(path).resolve(&doc).expect("Failed to resolve references");
+
+    let yaml: serde_yaml::Value = serde_yaml::from_slice(&yaml_value(&resolved)).unwrap();
+    let response = &yaml["paths"]["/pets"]["get"]["responses"]["200"];
+    assert_eq!(response["description"], "A list of pets");
+    let pet = &yaml["components"]["schemas"]["Pet"];
+    assert_eq!(pet["properties"]["id"]["format"], "int64");
+    // References inside an external file are resolved against that file
+    assert_eq!(pet["properties"]["owner"]["properties"]["name"]["type"], "string");
+    assert!(!String::from_utf8(yaml_value(&resolved)).unwrap().contains("$ref"));
+
+    // Relative references resolve against a URL base the same way
+    let dir = std::fs::canonicalize(format!("{}/refs", TESTDATA_DIR)).unwrap();
+    let url = format!("file://{}/openapi.yaml", dir.display());
+    let from_url = Resolver::new().with_base(url).resolve(&doc).expect("Failed to resolve against URL");
+    assert_eq!(from_url, resolved);
+
+    // Without a base the relative files cannot be found
+    let (unresolved, report) = Resolver::new().resolve_with_report(&doc).unwrap();
+    assert_eq!(report.errors.len(), 3);
+    assert_eq!(unresolved, doc);
+}
+
+#[test]
+fn test_openapiv3_parse_with_resolved_references() {
+    let path = format!("{}/refs/openapi.yaml", TESTDATA_DIR);
+    let options = ParseOptions::default().with_resolved_references();
+    let parsed = parse_document_from_file_with_options(&path, &options).expect("Failed to parse refs/openapi.yaml");
+    assert!(parsed.warnings.is_empty(), "{}", parsed.warnings);
+    let bytes = load_openapi_file("refs/openapi.yaml");
+    let resolved = Resolver::new().with_base(path.as_str()).resolve(&parse_document(&bytes).unwrap()).unwrap();
+    assert_eq!(parsed.value, resolved);
+
+    // From memory the relative files cannot be found; their references stay
+    let parsed = parse_document_with_options(&bytes, &options).expect("Failed to parse refs/openapi.yaml");
+    assert_eq!(parsed.warnings.len(), 3);
+    assert_eq!(parsed.value, parse_document(&bytes).unwrap());
+}
+
+#[test]
+fn test_openapiv3_bundle_external_references() {
+    let path = format!("{}/refs/openapi.yaml", TESTDATA_DIR);
+    let doc = parse_document(&load_openapi_file("refs/openapi.yaml")).expect("Failed to parse refs/openapi.yaml");
+    let (bundled, report) = Resolver::new()
+        .with_base(path)
+        .bundle_with_report(&doc)
+        .expect("Failed to bundle references");
+    assert!(report.is_complete());
+
+    let yaml: serde_yaml::Value = serde_yaml::from_slice(&yaml_value(&bundled)).unwrap();
+    let get = &yaml["paths"]["/pets"]["get"];
+    assert_eq!(get["parameters"][0]["$ref"], "#/components/parameters/limit");
+    assert_eq!(get["responses"]["200"]["$ref"], "#/components/responses/PetList");
+
+    let components = &yaml["components"];
+    assert_eq!(components["parameters"]["limit"]["schema"]["$ref"], "#/components/schemas/Limit");
+    assert_eq!(components["schemas"]["Limit"]["format"], "int32");
+    assert_eq!(components["responses"]["PetList"]["description"], "A list of pets");
+    // The Pet component takes the place of its reference
+    assert_eq!(components["schemas"]["Pet"]["properties"]["owner"]["$ref"], "#/components/schemas/Owner");
+    assert_eq!(components["schemas"]["Owner"]["properties"]["name"]["type"], "string");
+
+    // The bundle is self-contained
+    let text = String::from_utf8(yaml_value(&bundled)).unwrap();
+    assert!(!text.contains(".yaml#"), "{}", text);
+    let (_, report) = Resolver::new().resolve_with_report(&bundled).unwrap();
+    assert!(report.is_complete());
+}
+
+#[test]
+fn test_openapiv3_proto_bytes_round_trip() {
+    let bytes = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v3.yaml");
+
+    let encoded = doc.to_proto_bytes();
+    let decoded = gnostic_openapiv3::Document::from_proto_bytes(&encoded)
+        .expect("Failed to decode document");
+    assert_eq!(doc, decoded);
+
+    let err = gnostic_openapiv3::Document::from_proto_bytes(&[0xff, 0xff, 0xff])
+        .expect_err("garbage should not decode");
+    assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::Protobuf);
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_openapiv3_cbor_round_trip() {
+    let bytes = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v3.yaml");
+
+    let encoded = doc.to_cbor_bytes().expect("Failed to encode document");
+    let decoded = gnostic_openapiv3::Document::from_cbor_bytes(&encoded)
+        .expect("Failed to decode document");
+    assert_eq!(doc, decoded);
+
+    let err = gnostic_openapiv3::Document::from_cbor_bytes(&[0xff, 0xff, 0xff])
+        .expect_err("garbage should not decode");
+    assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::Encoding);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_openapiv3_msgpack_round_trip() {
+    let bytes = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&bytes).expect("Failed to parse petstore-v3.yaml");
+
+    let encoded = doc.to_msgpack_bytes().expect("Failed to encode document");
+    let decoded = gnostic_openapiv3::Document::from_msgpack_bytes(&encoded)
+        .expect("Failed to decode document");
+    assert_eq!(doc, decoded);
+
+    let err = gnostic_openapiv3::Document::from_msgpack_bytes(&[0xc1])
+        .expect_err("garbage should not decode");
+    assert_eq!(err.errors[0].code(), gnostic_compiler::ErrorCode::Encoding);
+}
+
+#[test]
+fn test_openapiv3_reference_graph() {
+    let doc = parse_document(br#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: "1.0"
+paths:
+  /pets:
+    get:
+      parameters:
+      - $ref: '#/components/parameters/filter'
+      responses:
+        '200':
+          description: A list of pets
+components:
+  parameters:
+    filter:
+      name: filter
+      in: query
+      schema:
+        $ref: '#/components/schemas/Pet'
+  schemas:
+    Pet:
+      type: object
+      properties:
+        owner:
+          $ref: '#/components/schemas/Owner'
+    Owner:
+      type: string
+    Legacy:
+      type: string
+"#).expect("Failed to parse document");
+    let graph = doc.reference_graph();
+
+    let referrers: Vec<_> = graph.referrers("#/components/schemas/Pet").iter().map(|e| e.from.clone()).collect();
+    assert_eq!(referrers, [Some("#/components/parameters/filter".to_string())]);
+    assert_eq!(graph.unused(), ["#/components/schemas/Legacy"]);
+    assert_eq!(
+        graph.dependency_order(),
+        [
+            "#/components/schemas/Owner",
+            "#/components/schemas/Pet",
+            "#/components/schemas/Legacy",
+            "#/components/parameters/filter",
+        ]
+    );
+}
+
+#[test]
+fn test_openapiv3_validate_petstore() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let node: serde_yaml::Value = serde_yaml::from_slice(&content).expect("Failed to read YAML");
+    let errors = gnostic_openapiv3::validate_node(&node);
+    assert!(errors.is_empty(), "{}", errors);
+
+    let doc = parse_document(&content).expect("Failed to parse document");
+    assert!(gnostic_openapiv3::validate(&doc).is_empty());
+}
+
+#[test]
+fn test_openapiv3_lint_petstore() {
+    use gnostic_openapiv3::ToYaml;
+
+    let doc = parse_document(&load_openapi_file("petstore-v3.yaml")).expect("Failed to parse document");
+    let report = gnostic_openapiv3::lint(&doc);
+    assert!(!report.has_errors(), "{}", report);
+    assert_eq!(report.for_rule("oas3-schema").count(), 0);
+    assert!(report.for_rule("info-description").count() <= 1);
+
+    let linter = gnostic_openapiv3::lint::linter().without_rule("operation-tags");
+    assert_eq!(linter.lint(&doc, &doc.to_yaml()).for_rule("operation-tags").count(), 0);
+}
+
+#[test]
+fn test_openapiv3_postman_collection() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let collection = doc.to_postman_collection();
+    assert_eq!(collection["info"]["schema"], gnostic_compiler::POSTMAN_SCHEMA_URL);
+    assert_eq!(collection["variable"][0], serde_json::json!({"key": "baseUrl", "value": "/api/v3"}));
+    let folders: Vec<&str> =
+        collection["item"].as_array().unwrap().iter().map(|f| f["name"].as_str().unwrap()).collect();
+    assert_eq!(folders, ["pet", "store", "user"]);
+    let update = &collection["item"][0]["item"][0];
+    assert_eq!(update["name"], "Update an existing pet.");
+    assert_eq!(update["request"]["method"], "PUT");
+    assert_eq!(update["request"]["url"]["raw"], "{{baseUrl}}/pet");
+
+    // Request bodies are exported from the YAML of documents.
+    let node: serde_yaml::Value = serde_yaml::from_slice(&content).expect("Failed to read YAML");
+    let collection = gnostic_compiler::postman_collection(&node);
+    let update = &collection["item"][0]["item"][0]["request"];
+    assert_eq!(update["header"][0], serde_json::json!({"key": "Content-Type", "value": "application/json"}));
+    assert_eq!(update["body"]["mode"], "raw");
+}
+
+#[test]
+fn test_openapiv3_generate_proto() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let proto = gnostic_openapiv3::generate_proto(&doc, "petstore.v1");
+    assert!(proto.contains("service SwaggerPetstoreOpenApi30Service {"));
+    assert!(proto.contains("  rpc UpdatePet(UpdatePetRequest) returns (Pet) {"));
+    assert!(proto.contains("      put: \"/pet\"\n      body: \"pet\"\n"));
+    assert!(proto.contains("      delete: \"/pet/{pet_id}\"\n"));
+    assert!(proto.contains("message FindPetsByStatusResponse {\n  repeated Pet items = 1;\n}"));
+    assert!(proto.contains("message Pet {"));
+    assert!(proto.contains("  rpc LogoutUser(google.protobuf.Empty) returns (google.protobuf.Empty) {"));
+}
+
+#[test]
+fn test_openapiv3_generate_messages() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let messages = gnostic_openapiv3::generate_messages(&doc, "petstore.v1");
+    assert!(messages.proto.starts_with("syntax = \"proto3\";\n\npackage petstore.v1;\n\n"));
+    assert!(!messages.proto.contains("service "));
+    assert!(messages.proto.contains("message Pet {"));
+    assert!(messages.proto.contains("  repeated string photo_urls = "));
+    let rename = messages.renames.iter().find(|rename| rename.original == "photoUrls").expect("No rename of photoUrls");
+    assert_eq!((rename.kind, rename.scope.as_str()), (gnostic_openapiv3::ProtoRenameKind::Field, "Pet"));
+}
+
+#[test]
+fn test_openapiv3_visitor() {
+    #[derive(Default)]
+    struct Counter {
+        operations: Vec<String>,
+        references: usize,
+    }
+    impl gnostic_openapiv3::DocumentVisitor for Counter {
+        fn visit_operation(&mut self, _: &str, method: &str, operation: &gnostic_openapiv3::openapi_v3::Operation) {
+            self.operations.push(format!("{} {}", method, operation.operation_id));
+        }
+
+        fn visit_reference(&mut self, _: &str, _: &gnostic_openapiv3::openapi_v3::Reference) {
+            self.references += 1;
+        }
+    }
+
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let mut counter = Counter::default();
+    doc.accept(&mut counter);
+    assert_eq!(counter.operations.len(), 19);
+    assert_eq!(counter.operations[..2], ["put updatePet", "post addPet"]);
+    assert!(counter.references > 0);
+}
+
+#[test]
+fn test_openapiv3_path_matcher() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let matcher = gnostic_openapiv3::PathMatcher::new(&doc).with_base_path("/api/v3");
+    let found = matcher.find("POST", "/api/v3/pet/10/uploadImage").expect("No match");
+    assert_eq!(found.path, "/pet/{petId}/uploadImage");
+    assert_eq!(found.operation.map(|operation| operation.operation_id.as_str()), Some("uploadFile"));
+    assert_eq!(found.parameters, [("petId", "10".to_string())]);
+    let found = matcher.find("GET", "/api/v3/pet/findByStatus").expect("No match");
+    assert_eq!(found.path, "/pet/findByStatus");
+    assert!(matcher.find("GET", "/api/v3/unknown").is_none());
+}
+
+#[test]
+fn test_openapiv3_vocabulary() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let vocabulary = doc.vocabulary();
+    assert_eq!(vocabulary.schemas.get("Pet"), Some(&1));
+    assert_eq!(vocabulary.operations.get("getPetById"), Some(&1));
+    assert_eq!(vocabulary.parameters.get("petId").copied(), Some(4));
+    assert!(vocabulary.properties.contains_key("photoUrls"));
+
+    let twice = vocabulary.union(&vocabulary);
+    assert_eq!(twice.operations.get("getPetById"), Some(&2));
+    assert_eq!(twice.intersection(&vocabulary), vocabulary);
+    assert!(twice.difference(&vocabulary).is_empty());
+}
+
+#[test]
+fn test_openapiv3_split() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let files = doc.split(&SplitOptions::new());
+    assert_eq!(files[0].0, "openapi.yaml");
+    assert!(files.iter().any(|(path, _)| path == "components/schemas/Pet.yaml"));
+
+    let dir = std::env::temp_dir().join(format!("gnostic-split-{}", std::process::id()));
+    for (path, bytes) in &files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, bytes).unwrap();
+    }
+    let root = parse_document(&files[0].1).expect("Failed to parse root file");
+    let base = dir.join("openapi.yaml").to_string_lossy().into_owned();
+    let resolved = Resolver::new().with_base(base).resolve(&root).expect("Failed to resolve split document");
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(resolved, Resolver::new().resolve(&doc).unwrap());
+}
+
+#[test]
+fn test_openapiv3_filter() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let schemas = |doc: &gnostic_openapiv3::Document| -> Vec<String> {
+        let components = doc.components.as_ref().unwrap();
+        let schemas = components.schemas.as_ref().unwrap();
+        schemas.additional_properties.iter().map(|named| named.name.clone()).collect()
+    };
+
+    let users = doc.filter_by_tags(&["user"]).expect("Failed to filter by tags");
+    let paths = users.paths.as_ref().unwrap();
+    assert!(paths.path.iter().all(|named| named.name.starts_with("/user")));
+    assert_eq!(users.tags.len(), 1);
+    assert_eq!(schemas(&users), ["User"]);
+
+    let pets = doc.remove_paths_matching(&regex::Regex::new("^/(user|store)").unwrap()).unwrap();
+    assert_eq!(schemas(&pets), ["Category", "Tag", "Pet", "ApiResponse"]);
+
+    let mut marked = doc.clone();
+    let paths = marked.paths.as_mut().unwrap();
+    let logout = paths.path.iter_mut().find(|named| named.name == "/user/logout").unwrap();
+    let get = logout.value.as_mut().unwrap().get.as_mut().unwrap();
+    get.specification_extension.push(gnostic_openapiv3::openapi_v3::NamedAny {
+        name: "x-internal".to_string(),
+        value: Some(gnostic_openapiv3::openapi_v3::Any { value: None, yaml: "true".to_string() }),
+    });
+    let public = marked.strip_extension_prefix("x-internal").unwrap();
+    let paths = public.paths.as_ref().unwrap();
+    assert!(paths.path.iter().all(|named| named.name != "/user/logout"));
+    assert_eq!(paths.path.len(), doc.paths.as_ref().unwrap().path.len() - 1);
+}
+
+#[test]
+fn test_openapiv3_patch() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+
+    let patch = br#"[
+        {"op": "replace", "path": "/info/title", "value": "Pets"},
+        {"op": "remove", "path": "/paths/~1user~1logout"}
+    ]"#;
+    let patched = doc.apply_json_patch(patch).expect("Failed to apply JSON patch");
+    assert_eq!(patched.info.as_ref().unwrap().title, "Pets");
+    let changes = doc.diff(&patched);
+    assert_eq!(changes.to_string(), "path-removed #/paths/~1user~1logout");
+
+    let merged = doc.apply_merge_patch(b"info: {title: Pets, termsOfService: null}").unwrap();
+    assert_eq!(merged.info.as_ref().unwrap().title, "Pets");
+    assert_eq!(merged.info.as_ref().unwrap().terms_of_service, "");
+    assert_eq!(merged.paths, doc.paths);
+
+    assert!(doc.apply_json_patch(br#"[{"op": "replace", "path": "", "value": "Pets"}]"#).is_err());
+    assert!(doc.apply_json_patch(br#"[{"op": "test", "path": "/openapi", "value": "2.0"}]"#).is_err());
+}
+
+#[test]
+fn test_openapiv3_diff() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let old = parse_document(&content).expect("Failed to parse document");
+    assert!(old.diff(&old).is_empty());
+
+    let mut new = old.clone();
+    new.paths.as_mut().unwrap().path.retain(|named| named.name != "/user/logout");
+    let changes = old.diff(&new);
+    assert_eq!(changes.to_string(), "path-removed #/paths/~1user~1logout");
+    assert!(changes.has_breaking_changes());
+    assert!(changes.to_report().has_errors());
+    let changelog = changes.changelog();
+    assert_eq!(changelog.sections[0].name, "/user/logout");
+    assert!(changelog.to_markdown().contains("- **Breaking:** path removed\n"));
+    let changes = new.diff(&old);
+    assert_eq!(changes.changes[0].kind, gnostic_compiler::ChangeKind::PathAdded);
+    assert!(!changes.has_breaking_changes());
+}
+
+#[test]
+fn test_openapiv3_merge() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let petstore = parse_document(&content).expect("Failed to parse document");
+    let zoo = parse_document(br##"
+openapi: 3.0.3
+info: {title: Zoo, version: 1.0.0}
+paths:
+  /zoos:
+    get:
+      operationId: listZoos
+      responses:
+        "200":
+          description: Zoos.
+          content:
+            application/json: {schema: {$ref: "#/components/schemas/Pet"}}
+components:
+  schemas:
+    Pet: {type: string}
+"##).expect("Failed to parse document");
+
+    let conflicts = gnostic_openapiv3::merge(&[petstore.clone(), zoo.clone()]).unwrap_err();
+    assert_eq!(conflicts.conflicts[0].pointer, "/components/schemas/Pet");
+
+    let options = gnostic_openapiv3::MergeOptions::new().with_component_prefixes(["", "Zoo"]);
+    let merged = gnostic_openapiv3::merge_with_options(&[petstore.clone(), zoo], &options).expect("Failed to merge");
+    assert_eq!(merged.info, petstore.info);
+    assert!(merged.paths.as_ref().unwrap().path_item("/zoos").is_some());
+    assert!(merged.components.as_ref().unwrap().schema("ZooPet").is_some());
+    assert!(gnostic_openapiv3::validate(&merged).is_empty());
+}
+
+#[test]
+fn test_openapiv3_builder() {
+    use gnostic_openapiv3::openapi_v3::{Document, Operation, Parameter, PathItem, Response, Schema};
+
+    let pet = Schema::reference("#/components/schemas/Pet");
+
+    let document = Document::builder()
+        .title("Pets")
+        .version("1.0.0")
+        .path(
+            "/pets/{petId}",
+            PathItem::builder().get(
+                Operation::builder()
+                    .operation_id("getPet")
+                    .parameter(Parameter::builder("petId", "path").schema(Schema::builder().schema_type("string")))
+                    .response("200", Response::builder("A pet.").content("application/json", pet)),
+            ),
+        )
+        .schema("Pet", Schema::builder().required_property("name", Schema::builder().schema_type("string")))
+        .build();
+    assert!(gnostic_openapiv3::validate(&document).is_empty());
+
+    let yaml = String::from_utf8(yaml_value(&document)).unwrap();
+    assert!(yaml.starts_with("openapi: 3.0.3\ninfo:\n  title: Pets\n"));
+    assert_eq!(parse_document(yaml.as_bytes()).expect("Failed to parse document"), document);
+}
+
+#[test]
+fn test_openapiv3_hash() {
+    use gnostic_openapiv3::openapi_v3::{Document, Info, Schema, Server};
+    use std::collections::HashSet;
+
+    let content = load_openapi_file("petstore-v3.yaml");
+    let doc = parse_document(&content).expect("Failed to parse document");
+    let servers: HashSet<&Server> = doc.servers.iter().chain(&doc.servers).collect();
+    assert_eq!(servers.len(), doc.servers.len());
+    let infos: HashSet<Info> = [doc.info.clone().unwrap(), doc.info.clone().unwrap()].into_iter().collect();
+    assert_eq!(infos.len(), 1);
+
+    let documents: HashSet<Document> = [doc.clone(), doc].into_iter().collect();
+    assert_eq!(documents.len(), 1);
+    let schemas: HashSet<Schema> = [0.0, -0.0, 1.5]
+        .into_iter()
+        .map(|minimum| Schema { minimum, ..Schema::default() })
+        .collect();
+    assert_eq!(schemas.len(), 2);
+}
+
+#[test]
+fn test_openapiv3_parse_lazy() {
+    let bytes = load_openapi_file("petstore-v3.yaml");
+    let eager = parse_document(&bytes).unwrap();
+    let lazy = gnostic_openapiv3::parse_document_lazy(&bytes).expect("Failed to parse petstore-v3.yaml lazily");
+    assert_eq!(lazy.info(), eager.info.as_ref());
+    assert!(lazy.document().paths.as_ref().is_some_and(|paths| paths.path.is_empty()));
+    assert_eq!(lazy.path_names().count(), eager.paths.as_ref().unwrap().path.len());
+
+    assert!(!lazy.is_path_parsed("/pet/{petId}"));
+    let item = lazy.path("/pet/{petId}").unwrap().unwrap();
+    assert!(item.value.get.is_some());
+    assert!(lazy.is_path_parsed("/pet/{petId}"));
+    assert!(!lazy.is_path_parsed("/pet"));
+    assert!(lazy.path("/missing").is_none());
+    assert!(lazy.schema("Pet").unwrap().is_ok());
+
+    assert_eq!(lazy.into_document().unwrap().value, eager);
+}
+
+#[test]
+fn test_openapiv3_specification_extensions() {
+    let source = br#"
+openapi: 3.0.3
+x-owner: pets-team
+info: {title: Pets, version: "1.0", x-audience: public}
+paths:
+  x-paths-note: {reviewed: true}
+  /pets:
+    x-rate-limit: 100
+    get:
+      x-amazon-apigateway-integration: {type: mock}
+      responses:
+        x-cached: true
+        '200': {description: ok, x-example-count: 2}
+components:
+  schemas:
+    Pet: {type: object, x-go-type: Pet}
+"#;
+    let doc = parse_document(source).expect("Failed to parse extensions");
+    let names = |extensions: &[gnostic_openapiv3::openapi_v3::NamedAny]| -> Vec<String> {
+        extensions.iter().map(|extension| extension.name.clone()).collect()
+    };
+    assert_eq!(names(&doc.specification_extension), ["x-owner"]);
+    assert_eq!(doc.specification_extension[0].value.as_ref().unwrap().yaml, "pets-team\n");
+    assert_eq!(names(&doc.info.as_ref().unwrap().specification_extension), ["x-audience"]);
+    let paths = doc.paths.as_ref().unwrap();
+    assert_eq!(names(&paths.specification_extension), ["x-paths-note"]);
+    assert_eq!(paths.path.len(), 1);
+    let item = paths.path[0].value.as_ref().unwrap();
+    assert_eq!(names(&item.specification_extension), ["x-rate-limit"]);
+    let get = item.get.as_ref().unwrap();
+    assert_eq!(names(&get.specification_extension), ["x-amazon-apigateway-integration"]);
+    let responses = get.responses.as_ref().unwrap();
+    assert_eq!(names(&responses.specification_extension), ["x-cached"]);
+    assert_eq!(responses.response_or_reference.len(), 1);
+
+    // Extensions survive round trips
+    let reparsed = parse_document(&yaml_value(&doc)).unwrap();
+    assert_eq!(reparsed, doc);
+    let lazy = gnostic_openapiv3::parse_document_lazy(source).unwrap();
+    assert_eq!(lazy.into_document().unwrap().value, doc);
+}
+
+#[test]
+fn test_openapiv3_document_cache() {
+    use gnostic_openapiv3::document::parse_document_from_file;
+
+    gnostic_compiler::enable_document_cache();
+    let path = format!("{}/petstore-v3.yaml", TESTDATA_DIR);
+    let first = parse_document_from_file(&path).expect("Failed to parse petstore-v3.yaml");
+    assert_eq!(parse_document_from_file(&path).unwrap(), first);
+    assert_eq!(first, parse_document(&load_openapi_file("petstore-v3.yaml")).unwrap());
+
+    // A changed file is parsed again
+    let path = std::env::temp_dir().join(format!("gnostic-cache-{}.yaml", std::process::id()));
+    let path = path.to_string_lossy();
+    fs::write(&*path, "openapi: 3.0.0\ninfo: {title: Pets, version: '1'}\n").unwrap();
+    assert_eq!(parse_document_from_file(&path).unwrap().info.unwrap().title, "Pets");
+    fs::write(&*path, "openapi: 3.0.0\ninfo: {title: Stores, version: '1'}\n").unwrap();
+    gnostic_compiler::remove_from_file_cache(&path);
+    assert_eq!(parse_document_from_file(&path).unwrap().info.unwrap().title, "Stores");
+    fs::remove_file(&*path).unwrap();
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn test_openapiv3_type_sizes() {
+    use gnostic_openapiv3::openapi_v3::{Operation, PathItem, Schema, SecurityScheme};
+    use std::mem::size_of;
+
+    // Sparse and rarely used messages are boxed; unboxed, a path item holds
+    // eight operations inline and takes over 4KB.
+    assert!(size_of::<PathItem>() <= 256, "PathItem is {} bytes", size_of::<PathItem>());
+    assert!(size_of::<Operation>() <= 512, "Operation is {} bytes", size_of::<Operation>());
+    assert!(size_of::<Schema>() <= 512, "Schema is {} bytes", size_of::<Schema>());
+    assert!(size_of::<SecurityScheme>() <= 256, "SecurityScheme is {} bytes", size_of::<SecurityScheme>());
+}
+
+#[test]
+fn test_openapiv3_parse_limits_from_options() {
+    let content = load_openapi_file("petstore-v3.yaml");
+    let small = gnostic_compiler::Limits { max_input_size: 100, ..Default::default() };
+    let error = parse_document_with_options(&content, &ParseOptions::default().with_limits(small)).unwrap_err();
+    assert!(error.to_string().contains("exceeds maximum of 100 bytes"), "{}", error);
+    let shallow = gnostic_compiler::Limits { max_depth: 3, ..Default::default() };
+    let error = parse_document_with_options(&content, &ParseOptions::default().with_limits(shallow)).unwrap_err();
+    assert!(error.to_string().contains("maximum nesting depth of 3"), "{}", error);
+
+    // Other parses keep the default limits
+    assert_eq!(gnostic_compiler::limits(), gnostic_compiler::Limits::default());
+    assert!(parse_document_with_options(&content, &ParseOptions::default()).is_ok());
+}
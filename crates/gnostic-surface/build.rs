@@ -0,0 +1,32 @@
+use std::io::Result;
+use std::path::PathBuf;
+
+fn main() -> Result<()> {
+    let proto_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("proto");
+
+    let proto_files = &[proto_root.join("surface.proto")];
+
+    let include_dirs = &[&proto_root];
+
+    // Serde support is opt-in through the `serde` feature.
+    let mut config = prost_build::Config::new();
+    config
+        .type_attribute(
+            ".",
+            "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]",
+        );
+
+    gnostic_build::derive_eq_and_hash(&mut config, proto_files, include_dirs, &[])?;
+    config.compile_protos(proto_files, include_dirs)?;
+
+    for proto in proto_files {
+        println!("cargo:rerun-if-changed={}", proto.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Surface models of Google API Discovery documents.
+
+use gnostic_discovery::discovery::*;
+
+use crate::model::{method, parameter_field, Builder, SchemaSource};
+use crate::surface_v1::{Field, FieldKind, Model};
+
+impl SchemaSource for Schema {
+    fn reference(&self) -> Option<&str> {
+        (!self.r#ref.is_empty()).then_some(self.r#ref.as_str())
+    }
+
+    fn schema_type(&self) -> &str {
+        &self.r#type
+    }
+
+    fn format(&self) -> &str {
+        &self.format
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn enum_values(&self) -> Vec<String> {
+        self.r#enum.clone()
+    }
+
+    fn properties(&self) -> Vec<(&str, &Self)> {
+        let properties = self.properties.iter().flat_map(|properties| &properties.additional_properties);
+        properties.filter_map(|named| Some((named.name.as_str(), named.value.as_ref()?))).collect()
+    }
+
+    fn items(&self) -> Option<&Self> {
+        self.items.as_deref()
+    }
+
+    fn additional_properties(&self) -> Option<&Self> {
+        self.additional_properties.as_deref()
+    }
+}
+
+impl Model {
+    /// Builds the surface model of a Discovery document, with a method for
+    /// each method of the API and of its resources.
+    pub fn from_discovery(doc: &Document, name: &str) -> Model {
+        let schemas = doc.schemas.iter().flat_map(|schemas| &schemas.additional_properties);
+        let schemas = schemas.filter_map(|named| Some((named.name.as_str(), named.value.as_ref()?))).collect();
+        let mut builder = Builder::new(name, schemas);
+        builder.build_schema_types();
+        build_methods(&mut builder, doc.methods.as_ref(), doc.resources.as_ref());
+        builder.finish()
+    }
+}
+
+fn build_methods(builder: &mut Builder<'_, Schema>, methods: Option<&Methods>, resources: Option<&Resources>) {
+    for named in methods.iter().flat_map(|methods| &methods.additional_properties) {
+        if let Some(m) = &named.value {
+            build_method(builder, m);
+        }
+    }
+    for named in resources.iter().flat_map(|resources| &resources.additional_properties) {
+        if let Some(resource) = &named.value {
+            build_methods(builder, resource.methods.as_ref(), resource.resources.as_ref());
+        }
+    }
+}
+
+fn build_method(builder: &mut Builder<'_, Schema>, m: &Method) {
+    let method = method(&m.id, &m.http_method, &m.path, &m.description);
+
+    let mut fields = Vec::new();
+    for named in m.parameters.iter().flat_map(|parameters| &parameters.additional_properties) {
+        let Some(p) = &named.value else {
+            continue;
+        };
+        let mut field = Field { name: named.name.clone(), r#type: p.r#type.clone(), ..Default::default() };
+        field.format = p.format.clone();
+        field.enum_values = p.r#enum.clone();
+        if p.repeated {
+            field.set_kind(FieldKind::Array);
+        }
+        fields.push(parameter_field(field, &p.location));
+    }
+    if let Some(request) = m.request.as_ref().filter(|request| !request.r#ref.is_empty()) {
+        let name = if request.parameter_name.is_empty() { "body" } else { &request.parameter_name };
+        fields.push(parameter_field(builder.reference_field(name, &request.r#ref), "body"));
+    }
+
+    // Discovery methods have a single response, which is successful.
+    let mut responses = Vec::new();
+    if let Some(response) = m.response.as_ref().filter(|response| !response.r#ref.is_empty()) {
+        responses.push(builder.reference_field("200", &response.r#ref));
+    }
+    builder.add_method(method, fields, responses);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::surface_v1::Position;
+    use gnostic_discovery::parse_document;
+
+    #[test]
+    fn test_from_discovery() {
+        let doc = parse_document(
+            br#"{
+  "kind": "discovery#restDescription",
+  "discoveryVersion": "v1",
+  "name": "books",
+  "schemas": {
+    "Shelf": {"id": "Shelf", "type": "object", "properties": {
+      "name": {"type": "string"},
+      "volumeCount": {"type": "integer", "format": "int32"}
+    }}
+  },
+  "resources": {
+    "shelves": {
+      "methods": {
+        "get": {
+          "id": "books.shelves.get",
+          "path": "shelves/{shelf}",
+          "httpMethod": "GET",
+          "parameters": {"shelf": {"type": "string", "location": "path", "required": true}},
+          "response": {"$ref": "Shelf"}
+        },
+        "insert": {
+          "id": "books.shelves.insert",
+          "path": "shelves",
+          "httpMethod": "POST",
+          "request": {"$ref": "Shelf"},
+          "response": {"$ref": "Shelf"}
+        }
+      }
+    }
+  }
+}"#,
+        )
+        .unwrap();
+        let model = Model::from_discovery(&doc, "books");
+        let names: Vec<&str> = model.types.iter().map(|ty| ty.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Shelf",
+                "BooksShelvesGetParameters",
+                "BooksShelvesGetResponses",
+                "BooksShelvesInsertParameters",
+                "BooksShelvesInsertResponses",
+            ]
+        );
+        assert_eq!(model.types[0].fields[1].format, "int32");
+        assert_eq!(model.types[1].fields[0].position(), Position::Path);
+        let body = &model.types[3].fields[0];
+        assert_eq!((body.name.as_str(), body.kind(), body.r#type.as_str()), ("body", FieldKind::Reference, "Shelf"));
+        assert_eq!(model.methods[0].path, "shelves/{shelf}");
+        assert_eq!(model.methods[1].operation, "books.shelves.insert");
+        assert_eq!(model.methods[1].method, "POST");
+    }
+}
@@ -0,0 +1,22 @@
+//! API surface models for gnostic-models.
+//!
+//! The surface model is a simplified, language-neutral representation of an
+//! API for code generators: the types it uses, with their fields, and its
+//! methods, with the types of their parameters and responses. A [`Model`] is
+//! computed from an OpenAPI v2, OpenAPI v3 or Discovery document with
+//! [`Model::from_openapiv2`], [`Model::from_openapiv3`] or
+//! [`Model::from_discovery`], so that generators can target one normalized
+//! structure. The language-specific fields of the model, such as native
+//! types, are left for generators to fill in.
+
+pub mod discovery;
+mod model;
+pub mod openapiv2;
+pub mod openapiv3;
+
+/// Generated Protocol Buffer code for the surface model.
+pub mod surface_v1 {
+    include!(concat!(env!("OUT_DIR"), "/surface.v1.rs"));
+}
+
+pub use surface_v1::Model;
@@ -0,0 +1,266 @@
+//! Construction of surface models, shared by the document formats.
+//!
+//! Each format implements [`SchemaSource`] for its schemas, so that the
+//! [`Builder`] derives types and fields from them in the same way: objects
+//! with properties become struct types, maps become object types, and the
+//! schemas of fields are described by a kind and a type name.
+
+use crate::surface_v1::{Field, FieldKind, Method, Model, Position, Type, TypeKind};
+
+/// Read access to the parts of a schema that the surface model represents.
+pub(crate) trait SchemaSource: Sized {
+    /// Returns the reference the schema consists of, if it is one.
+    fn reference(&self) -> Option<&str>;
+    fn schema_type(&self) -> &str;
+    fn format(&self) -> &str;
+    fn description(&self) -> &str;
+    fn enum_values(&self) -> Vec<String>;
+    fn properties(&self) -> Vec<(&str, &Self)>;
+    fn items(&self) -> Option<&Self>;
+    fn additional_properties(&self) -> Option<&Self>;
+}
+
+/// The number of references between schemas followed to find the schema of a
+/// field, which only a cycle of references reaches.
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// Builds a model from the named schemas of a document, and the types and
+/// methods its format-specific code adds.
+pub(crate) struct Builder<'a, S> {
+    model: Model,
+    schemas: Vec<(&'a str, &'a S)>,
+}
+
+impl<'a, S: SchemaSource> Builder<'a, S> {
+    pub fn new(name: &str, schemas: Vec<(&'a str, &'a S)>) -> Self {
+        Builder { model: Model { name: name.to_string(), ..Default::default() }, schemas }
+    }
+
+    /// Adds a type for each named schema that defines one.
+    pub fn build_schema_types(&mut self) {
+        for (name, schema) in self.schemas.clone() {
+            if defines_type(schema) {
+                self.build_type(name, schema);
+            }
+        }
+    }
+
+    fn build_type(&mut self, name: &str, schema: &'a S) {
+        let description = schema.description().to_string();
+        let mut ty = Type { name: name.to_string(), description, ..Default::default() };
+        match schema.additional_properties() {
+            Some(values) if schema.properties().is_empty() => {
+                ty.set_kind(TypeKind::Object);
+                ty.content_type = self.field("", values, name).r#type;
+            }
+            _ => {
+                for (property, value) in schema.properties() {
+                    let field = self.field(property, value, name);
+                    ty.fields.push(field);
+                }
+            }
+        }
+        self.model.types.push(ty);
+    }
+
+    /// Returns a field holding values of a schema. Inline objects are added as
+    /// types named after the field and the type `parent` that holds it.
+    pub fn field(&mut self, name: &str, schema: &'a S, parent: &str) -> Field {
+        let mut field = Field { name: name.to_string(), serialize: true, ..Default::default() };
+        self.describe(&mut field, schema, &format!("{}{}", parent, upper_camel_case(name)), 0);
+        field
+    }
+
+    /// Returns a field holding values of the named schema that a reference
+    /// refers to.
+    pub fn reference_field(&mut self, name: &str, reference: &str) -> Field {
+        let mut field = Field { name: name.to_string(), serialize: true, ..Default::default() };
+        self.describe_reference(&mut field, reference, 0);
+        field
+    }
+
+    fn describe(&mut self, field: &mut Field, schema: &'a S, inline_name: &str, depth: usize) {
+        if let Some(reference) = schema.reference() {
+            return self.describe_reference(field, reference, depth);
+        }
+        field.format = schema.format().to_string();
+        if !schema.properties().is_empty() {
+            self.build_type(inline_name, schema);
+            field.set_kind(FieldKind::Reference);
+            field.r#type = inline_name.to_string();
+            return;
+        }
+        match schema.schema_type() {
+            "array" => {
+                let mut item = Field::default();
+                if let Some(items) = schema.items() {
+                    self.describe(&mut item, items, &format!("{}Item", inline_name), depth);
+                }
+                field.set_kind(FieldKind::Array);
+                if matches!(item.kind(), FieldKind::Scalar | FieldKind::Reference) {
+                    field.r#type = item.r#type;
+                    field.format = item.format;
+                    field.enum_values = item.enum_values;
+                }
+            }
+            "object" | "" if schema.additional_properties().is_some() => {
+                let mut value = Field::default();
+                if let Some(values) = schema.additional_properties() {
+                    self.describe(&mut value, values, &format!("{}Value", inline_name), depth);
+                }
+                field.set_kind(FieldKind::Map);
+                field.r#type = value.r#type;
+            }
+            "object" => {
+                field.set_kind(FieldKind::Any);
+                field.r#type = "object".to_string();
+            }
+            "" | "any" => field.set_kind(FieldKind::Any),
+            scalar => {
+                field.set_kind(FieldKind::Scalar);
+                field.r#type = scalar.to_string();
+                field.enum_values = schema.enum_values();
+            }
+        }
+    }
+
+    fn describe_reference(&mut self, field: &mut Field, reference: &str, depth: usize) {
+        let name = reference_name(reference);
+        if let Some(file) = external_reference(reference) {
+            if !self.model.symbolic_references.iter().any(|known| known == file) {
+                self.model.symbolic_references.push(file.to_string());
+            }
+        } else if let Some(&(_, target)) = self.schemas.iter().find(|(known, _)| *known == name) {
+            // Named schemas that define no type, such as enums, are described in place.
+            if !defines_type(target) && depth < MAX_REFERENCE_DEPTH {
+                return self.describe(field, target, name, depth + 1);
+            }
+        }
+        field.set_kind(FieldKind::Reference);
+        field.r#type = name.to_string();
+    }
+
+    /// Adds a method, with its parameters and responses types, which are
+    /// named after it and omitted if they have no fields.
+    pub fn add_method(&mut self, mut method: Method, parameters: Vec<Field>, responses: Vec<Field>) {
+        if !parameters.is_empty() {
+            method.parameters_type_name = format!("{}Parameters", method.name);
+            let name = method.parameters_type_name.clone();
+            self.model.types.push(Type { name, fields: parameters, ..Default::default() });
+        }
+        if !responses.is_empty() {
+            method.responses_type_name = format!("{}Responses", method.name);
+            let name = method.responses_type_name.clone();
+            self.model.types.push(Type { name, fields: responses, ..Default::default() });
+        }
+        self.model.methods.push(method);
+    }
+
+    pub fn finish(self) -> Model {
+        self.model
+    }
+}
+
+/// Returns whether a named schema is represented by a type of its own.
+fn defines_type<S: SchemaSource>(schema: &S) -> bool {
+    schema.reference().is_none()
+        && (!schema.properties().is_empty()
+            || schema.schema_type() == "object"
+            || schema.schema_type().is_empty() && schema.additional_properties().is_some())
+}
+
+/// Returns a method for an operation, named after its ID or, if it has none,
+/// after its HTTP method and path.
+pub(crate) fn method(operation_id: &str, http_method: &str, path: &str, description: &str) -> Method {
+    let name = if operation_id.is_empty() {
+        upper_camel_case(&format!("{} {}", http_method, path))
+    } else {
+        upper_camel_case(operation_id)
+    };
+    Method {
+        operation: operation_id.to_string(),
+        path: path.to_string(),
+        method: http_method.to_ascii_uppercase(),
+        description: description.to_string(),
+        handler_name: format!("Handle{}", name),
+        processor_name: name.clone(),
+        client_name: name.clone(),
+        name,
+        ..Default::default()
+    }
+}
+
+/// Returns a parameter field, which is serialized only in a request body.
+pub(crate) fn parameter_field(mut field: Field, location: &str) -> Field {
+    let position = match location {
+        "header" => Position::Header,
+        "formData" => Position::Formdata,
+        "query" => Position::Query,
+        "path" => Position::Path,
+        "cookie" => Position::Cookie,
+        _ => Position::Body,
+    };
+    field.set_position(position);
+    field.serialize = position == Position::Body;
+    field
+}
+
+/// Returns the string form of an enum value stored as YAML text.
+pub(crate) fn enum_value(yaml: &str) -> Option<String> {
+    match serde_yaml::from_str(yaml).ok()? {
+        serde_yaml::Value::String(value) => Some(value),
+        serde_yaml::Value::Number(value) => Some(value.to_string()),
+        serde_yaml::Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the name of the schema a reference refers to, the last segment of
+/// its path.
+fn reference_name(reference: &str) -> &str {
+    reference.rsplit(['/', '#']).find(|segment| !segment.is_empty()).unwrap_or(reference)
+}
+
+/// Returns the file or URL of a reference to another document.
+fn external_reference(reference: &str) -> Option<&str> {
+    let file = reference.split('#').next().unwrap_or_default();
+    (!file.is_empty() && (reference.contains('#') || file.contains(['/', '.']))).then_some(file)
+}
+
+/// Converts a name to UpperCamelCase, splitting it into words at
+/// non-alphanumeric characters.
+pub(crate) fn upper_camel_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word[..1].to_ascii_uppercase() + &word[1..])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(upper_camel_case("listPets"), "ListPets");
+        assert_eq!(upper_camel_case("get /pets/{petId}"), "GetPetsPetId");
+        assert_eq!(upper_camel_case("books.bookshelves.list"), "BooksBookshelvesList");
+        assert_eq!(reference_name("#/components/schemas/Pet"), "Pet");
+        assert_eq!(reference_name("Volume"), "Volume");
+        assert_eq!(external_reference("#/definitions/Pet"), None);
+        assert_eq!(external_reference("Volume"), None);
+        assert_eq!(external_reference("pet.yaml"), Some("pet.yaml"));
+        assert_eq!(external_reference("common.yaml#/Error"), Some("common.yaml"));
+    }
+
+    #[test]
+    fn test_method() {
+        let method = method("", "get", "/pets/{petId}", "Gets a pet.");
+        assert_eq!(method.name, "GetPetsPetId");
+        assert_eq!(method.method, "GET");
+        assert_eq!(method.handler_name, "HandleGetPetsPetId");
+        assert_eq!(enum_value("available"), Some("available".to_string()));
+        assert_eq!(enum_value("3"), Some("3".to_string()));
+        assert_eq!(enum_value("{a: 1}"), None);
+    }
+}
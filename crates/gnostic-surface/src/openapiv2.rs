@@ -0,0 +1,235 @@
+//! Surface models of OpenAPI v2 documents.
+
+use gnostic_openapiv2::openapi_v2::*;
+
+use crate::model::{enum_value, method, parameter_field, Builder, SchemaSource};
+use crate::surface_v1::{Field, FieldKind, Model};
+
+impl SchemaSource for Schema {
+    fn reference(&self) -> Option<&str> {
+        (!self.r#ref.is_empty()).then_some(self.r#ref.as_str())
+    }
+
+    fn schema_type(&self) -> &str {
+        self.r#type.as_ref().and_then(|ty| ty.value.first()).map_or("", String::as_str)
+    }
+
+    fn format(&self) -> &str {
+        &self.format
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn enum_values(&self) -> Vec<String> {
+        self.r#enum.iter().filter_map(|value| enum_value(&value.yaml)).collect()
+    }
+
+    fn properties(&self) -> Vec<(&str, &Self)> {
+        let properties = self.properties.iter().flat_map(|properties| &properties.additional_properties);
+        properties.filter_map(|named| Some((named.name.as_str(), named.value.as_ref()?))).collect()
+    }
+
+    fn items(&self) -> Option<&Self> {
+        self.items.as_ref()?.schema.first()
+    }
+
+    fn additional_properties(&self) -> Option<&Self> {
+        match self.additional_properties.as_deref()?.oneof.as_ref()? {
+            additional_properties_item::Oneof::Schema(values) => Some(values),
+            additional_properties_item::Oneof::Boolean(_) => None,
+        }
+    }
+}
+
+impl Model {
+    /// Builds the surface model of an OpenAPI v2 document.
+    pub fn from_openapiv2(doc: &Document, name: &str) -> Model {
+        let schemas = doc.definitions.iter().flat_map(|definitions| &definitions.additional_properties);
+        let schemas = schemas.filter_map(|named| Some((named.name.as_str(), named.value.as_ref()?))).collect();
+        let mut builder = Builder::new(name, schemas);
+        builder.build_schema_types();
+
+        for named in doc.paths.iter().flat_map(|paths| &paths.path) {
+            let Some(item) = &named.value else {
+                continue;
+            };
+            let operations = [
+                ("get", &item.get),
+                ("put", &item.put),
+                ("post", &item.post),
+                ("delete", &item.delete),
+                ("options", &item.options),
+                ("head", &item.head),
+                ("patch", &item.patch),
+            ];
+            for (http_method, operation) in operations {
+                if let Some(operation) = operation {
+                    let parameters = item.parameters.iter().chain(&operation.parameters);
+                    build_method(&mut builder, doc, &named.name, http_method, parameters, operation);
+                }
+            }
+        }
+        builder.finish()
+    }
+}
+
+fn build_method<'a>(
+    builder: &mut Builder<'a, Schema>,
+    doc: &'a Document,
+    path: &str,
+    http_method: &str,
+    parameters: impl Iterator<Item = &'a ParametersItem>,
+    operation: &'a Operation,
+) {
+    let description = if operation.description.is_empty() { &operation.summary } else { &operation.description };
+    let method = method(&operation.operation_id, http_method, path, description);
+
+    let parameters_name = format!("{}Parameters", method.name);
+    let mut fields = Vec::new();
+    for item in parameters {
+        let p = match item.oneof.as_ref() {
+            Some(parameters_item::Oneof::Parameter(p)) => Some(p),
+            Some(parameters_item::Oneof::JsonReference(reference)) => {
+                let name = reference.r#ref.strip_prefix("#/parameters/");
+                let definitions = doc.parameters.iter().flat_map(|definitions| &definitions.additional_properties);
+                definitions.filter(|named| Some(named.name.as_str()) == name).find_map(|named| named.value.as_ref())
+            }
+            None => None,
+        };
+        match p.and_then(|p| p.oneof.as_ref()) {
+            Some(parameter::Oneof::BodyParameter(body)) => {
+                let field = match &body.schema {
+                    Some(schema) => builder.field(&body.name, schema, &parameters_name),
+                    None => Field { name: body.name.clone(), ..Field::default() },
+                };
+                fields.push(parameter_field(field, "body"));
+            }
+            Some(parameter::Oneof::NonBodyParameter(non_body)) => {
+                if let Some(field) = non_body_field(non_body) {
+                    fields.push(field);
+                }
+            }
+            None => {}
+        }
+    }
+
+    let responses_name = format!("{}Responses", method.name);
+    let mut responses = Vec::new();
+    for named in operation.responses.iter().flat_map(|responses| &responses.response_code) {
+        let response = match named.value.as_ref().and_then(|value| value.oneof.as_ref()) {
+            Some(response_value::Oneof::Response(response)) => Some(response),
+            Some(response_value::Oneof::JsonReference(reference)) => {
+                let name = reference.r#ref.strip_prefix("#/responses/");
+                let definitions = doc.responses.iter().flat_map(|definitions| &definitions.additional_properties);
+                definitions.filter(|named| Some(named.name.as_str()) == name).find_map(|named| named.value.as_ref())
+            }
+            None => None,
+        };
+        match response.and_then(|response| response.schema.as_ref()).and_then(|schema| schema.oneof.as_ref()) {
+            Some(schema_item::Oneof::Schema(schema)) => {
+                responses.push(builder.field(&named.name, schema, &responses_name));
+            }
+            Some(schema_item::Oneof::FileSchema(_)) => {
+                let mut field = Field { name: named.name.clone(), serialize: true, ..Field::default() };
+                field.r#type = "file".to_string();
+                responses.push(field);
+            }
+            None => {}
+        }
+    }
+    builder.add_method(method, fields, responses);
+}
+
+/// Returns the field of a header, form data, query or path parameter.
+fn non_body_field(non_body: &NonBodyParameter) -> Option<Field> {
+    let (name, location, ty, format, items, values) = match non_body.oneof.as_ref()? {
+        non_body_parameter::Oneof::HeaderParameterSubSchema(p) => {
+            (&p.name, "header", &p.r#type, &p.format, &p.items, &p.r#enum)
+        }
+        non_body_parameter::Oneof::FormDataParameterSubSchema(p) => {
+            (&p.name, "formData", &p.r#type, &p.format, &p.items, &p.r#enum)
+        }
+        non_body_parameter::Oneof::QueryParameterSubSchema(p) => {
+            (&p.name, "query", &p.r#type, &p.format, &p.items, &p.r#enum)
+        }
+        non_body_parameter::Oneof::PathParameterSubSchema(p) => {
+            (&p.name, "path", &p.r#type, &p.format, &p.items, &p.r#enum)
+        }
+    };
+    let mut field = Field { name: name.clone(), ..Field::default() };
+    match (ty.as_str(), items) {
+        ("array", Some(items)) => {
+            field.set_kind(FieldKind::Array);
+            field.r#type = items.r#type.clone();
+            field.format = items.format.clone();
+            field.enum_values = items.r#enum.iter().filter_map(|value| enum_value(&value.yaml)).collect();
+        }
+        _ => {
+            field.r#type = ty.clone();
+            field.format = format.clone();
+            field.enum_values = values.iter().filter_map(|value| enum_value(&value.yaml)).collect();
+        }
+    }
+    Some(parameter_field(field, location))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::surface_v1::Position;
+    use gnostic_openapiv2::parse_document;
+
+    #[test]
+    fn test_from_openapiv2() {
+        let doc = parse_document(
+            br##"
+swagger: "2.0"
+info: {title: Pets, version: 1.0.0}
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - {name: tags, in: query, type: array, items: {type: string}}
+        - $ref: "#/parameters/limit"
+      responses:
+        "200":
+          description: Pets.
+          schema: {type: array, items: {$ref: "#/definitions/Pet"}}
+    post:
+      parameters:
+        - {name: pet, in: body, schema: {$ref: "#/definitions/Pet"}}
+      responses:
+        "201": {description: Created.}
+parameters:
+  limit: {name: limit, in: query, type: integer, format: int32}
+definitions:
+  Pet:
+    type: object
+    properties:
+      id: {type: integer, format: int64}
+      status: {type: string, enum: [available, sold]}
+"##,
+        )
+        .unwrap();
+        let model = Model::from_openapiv2(&doc, "pets");
+        let names: Vec<&str> = model.types.iter().map(|ty| ty.name.as_str()).collect();
+        assert_eq!(names, ["Pet", "ListPetsParameters", "ListPetsResponses", "PostPetsParameters"]);
+        assert_eq!(model.types[0].fields[1].enum_values, ["available", "sold"]);
+
+        let parameters = &model.types[1].fields;
+        assert_eq!((parameters[0].kind(), parameters[0].r#type.as_str()), (FieldKind::Array, "string"));
+        assert_eq!(parameters[0].position(), Position::Query);
+        assert_eq!((parameters[1].name.as_str(), parameters[1].format.as_str()), ("limit", "int32"));
+        let responses = &model.types[2].fields;
+        assert_eq!((responses[0].kind(), responses[0].r#type.as_str()), (FieldKind::Array, "Pet"));
+        let body = &model.types[3].fields[0];
+        assert_eq!((body.kind(), body.r#type.as_str(), body.position()), (FieldKind::Reference, "Pet", Position::Body));
+
+        assert_eq!(model.methods[1].name, "PostPets");
+        assert_eq!(model.methods[1].method, "POST");
+        assert_eq!(model.methods[1].responses_type_name, "");
+    }
+}
@@ -0,0 +1,238 @@
+//! Surface models of OpenAPI v3 documents.
+
+use gnostic_openapiv3::openapi_v3::*;
+
+use crate::model::{enum_value, method, parameter_field, Builder, SchemaSource};
+use crate::surface_v1::{Field, Model};
+
+impl SchemaSource for SchemaOrReference {
+    fn reference(&self) -> Option<&str> {
+        match &self.oneof {
+            Some(schema_or_reference::Oneof::Reference(reference)) => Some(&reference.r#ref),
+            _ => None,
+        }
+    }
+
+    fn schema_type(&self) -> &str {
+        schema(self).map_or("", |schema| &schema.r#type)
+    }
+
+    fn format(&self) -> &str {
+        schema(self).map_or("", |schema| &schema.format)
+    }
+
+    fn description(&self) -> &str {
+        schema(self).map_or("", |schema| &schema.description)
+    }
+
+    fn enum_values(&self) -> Vec<String> {
+        schema(self).map_or_else(Vec::new, |schema| schema.r#enum.iter().filter_map(|v| enum_value(&v.yaml)).collect())
+    }
+
+    fn properties(&self) -> Vec<(&str, &Self)> {
+        let properties = schema(self).and_then(|schema| schema.properties.as_ref());
+        properties
+            .into_iter()
+            .flat_map(|properties| &properties.additional_properties)
+            .filter_map(|named| Some((named.name.as_str(), named.value.as_ref()?)))
+            .collect()
+    }
+
+    fn items(&self) -> Option<&Self> {
+        schema(self)?.items.as_ref()?.schema_or_reference.first()
+    }
+
+    fn additional_properties(&self) -> Option<&Self> {
+        match schema(self)?.additional_properties.as_deref()?.oneof.as_ref()? {
+            additional_properties_item::Oneof::SchemaOrReference(values) => Some(values),
+            additional_properties_item::Oneof::Boolean(_) => None,
+        }
+    }
+}
+
+fn schema(schema: &SchemaOrReference) -> Option<&Schema> {
+    match &schema.oneof {
+        Some(schema_or_reference::Oneof::Schema(schema)) => Some(schema),
+        _ => None,
+    }
+}
+
+impl Model {
+    /// Builds the surface model of an OpenAPI v3 document.
+    pub fn from_openapiv3(doc: &Document, name: &str) -> Model {
+        let schemas = doc.components.iter().flat_map(|components| &components.schemas);
+        let schemas = schemas
+            .flat_map(|schemas| &schemas.additional_properties)
+            .filter_map(|named| Some((named.name.as_str(), named.value.as_ref()?)))
+            .collect();
+        let mut builder = Builder::new(name, schemas);
+        builder.build_schema_types();
+
+        for named in doc.paths.iter().flat_map(|paths| &paths.path) {
+            let Some(item) = &named.value else {
+                continue;
+            };
+            for (http_method, operation) in item.iter_operations() {
+                let parameters = item.parameters.iter().chain(&operation.parameters);
+                build_method(&mut builder, doc, &named.name, http_method.as_str(), parameters, operation);
+            }
+        }
+        builder.finish()
+    }
+}
+
+fn build_method<'a>(
+    builder: &mut Builder<'a, SchemaOrReference>,
+    doc: &'a Document,
+    path: &str,
+    http_method: &str,
+    parameters: impl Iterator<Item = &'a ParameterOrReference>,
+    operation: &'a Operation,
+) {
+    let description = if operation.description.is_empty() { &operation.summary } else { &operation.description };
+    let method = method(&operation.operation_id, http_method, path, description);
+
+    let parameters_name = format!("{}Parameters", method.name);
+    let mut fields = Vec::new();
+    for parameter_or_reference in parameters {
+        let Some(p) = (match parameter_or_reference.oneof.as_ref() {
+            Some(parameter_or_reference::Oneof::Parameter(p)) => Some(p),
+            Some(parameter_or_reference::Oneof::Reference(reference)) => doc.parameter_for_ref(&reference.r#ref),
+            None => None,
+        }) else {
+            continue;
+        };
+        let field = match &p.schema {
+            Some(schema) => builder.field(&p.name, schema, &parameters_name),
+            None => Field { name: p.name.clone(), r#type: "string".to_string(), ..Default::default() },
+        };
+        fields.push(parameter_field(field, &p.r#in));
+    }
+    let request_body = match operation.request_body.as_ref().and_then(|body| body.oneof.as_ref()) {
+        Some(request_body_or_reference::Oneof::RequestBody(request_body)) => Some(request_body),
+        Some(request_body_or_reference::Oneof::Reference(reference)) => doc.request_body_for_ref(&reference.r#ref),
+        None => None,
+    };
+    if let Some(schema) = request_body.and_then(|body| content_schema(&body.content)) {
+        fields.push(parameter_field(builder.field("body", schema, &parameters_name), "body"));
+    }
+
+    let responses_name = format!("{}Responses", method.name);
+    let mut responses = Vec::new();
+    if let Some(operation_responses) = &operation.responses {
+        let default = operation_responses.default.as_ref().map(|response| ("default", response));
+        let named = operation_responses.response_or_reference.iter();
+        let named = named.filter_map(|named| Some((named.name.as_str(), named.value.as_ref()?)));
+        for (code, response) in named.chain(default) {
+            let response = match response.oneof.as_ref() {
+                Some(response_or_reference::Oneof::Response(response)) => Some(response),
+                Some(response_or_reference::Oneof::Reference(reference)) => doc.response_for_ref(&reference.r#ref),
+                None => None,
+            };
+            if let Some(schema) = response.and_then(|response| content_schema(&response.content)) {
+                responses.push(builder.field(code, schema, &responses_name));
+            }
+        }
+    }
+    builder.add_method(method, fields, responses);
+}
+
+/// Returns the schema of JSON content, or of the first media type if there is none.
+fn content_schema(content: &Option<MediaTypes>) -> Option<&SchemaOrReference> {
+    let media_types = &content.as_ref()?.additional_properties;
+    let media_type = media_types.iter().find(|named| named.name == "application/json").or(media_types.first())?;
+    media_type.value.as_ref()?.schema.as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::surface_v1::{FieldKind, Position, TypeKind};
+    use gnostic_openapiv3::parse_document;
+
+    #[test]
+    fn test_from_openapiv3() {
+        let doc = parse_document(
+            br##"
+openapi: 3.0.3
+info: {title: Pets, version: 1.0.0}
+paths:
+  /pets/{petId}:
+    parameters:
+      - {name: petId, in: path, required: true, schema: {type: integer, format: int64}}
+    put:
+      operationId: updatePet
+      requestBody:
+        content:
+          application/json:
+            schema: {$ref: "#/components/schemas/Pet"}
+      responses:
+        "200":
+          description: The pet.
+          content:
+            application/json:
+              schema: {$ref: "#/components/schemas/Pet"}
+        default:
+          description: An error.
+          content:
+            application/json:
+              schema: {$ref: "common.yaml#/Error"}
+components:
+  schemas:
+    Status: {type: string, enum: [available, sold]}
+    Pet:
+      type: object
+      description: A pet.
+      properties:
+        status: {$ref: "#/components/schemas/Status"}
+        tags: {type: array, items: {type: string}}
+        owner: {type: object, properties: {name: {type: string}}}
+        labels: {type: object, additionalProperties: {type: string}}
+    Inventory: {type: object, additionalProperties: {type: integer}}
+"##,
+        )
+        .unwrap();
+        let model = Model::from_openapiv3(&doc, "pets");
+        assert_eq!(model.name, "pets");
+        let names: Vec<&str> = model.types.iter().map(|ty| ty.name.as_str()).collect();
+        assert_eq!(names, ["PetOwner", "Pet", "Inventory", "UpdatePetParameters", "UpdatePetResponses"]);
+
+        let pet = &model.types[1];
+        assert_eq!(pet.description, "A pet.");
+        let fields: Vec<(&str, FieldKind, &str)> =
+            pet.fields.iter().map(|f| (f.name.as_str(), f.kind(), f.r#type.as_str())).collect();
+        assert_eq!(
+            fields,
+            [
+                ("status", FieldKind::Scalar, "string"),
+                ("tags", FieldKind::Array, "string"),
+                ("owner", FieldKind::Reference, "PetOwner"),
+                ("labels", FieldKind::Map, "string"),
+            ]
+        );
+        assert_eq!(pet.fields[0].enum_values, ["available", "sold"]);
+        assert_eq!(model.types[2].kind(), TypeKind::Object);
+        assert_eq!(model.types[2].content_type, "integer");
+
+        let parameters = &model.types[3];
+        assert_eq!(parameters.fields[0].position(), Position::Path);
+        assert_eq!(parameters.fields[0].format, "int64");
+        assert!(!parameters.fields[0].serialize);
+        assert_eq!(parameters.fields[1].name, "body");
+        assert_eq!(parameters.fields[1].position(), Position::Body);
+        assert!(parameters.fields[1].serialize);
+        let responses = &model.types[4];
+        assert_eq!(responses.fields[0].name, "200");
+        assert_eq!(responses.fields[1].name, "default");
+        assert_eq!(responses.fields[1].r#type, "Error");
+        assert_eq!(model.symbolic_references, ["common.yaml"]);
+
+        let method = &model.methods[0];
+        assert_eq!(
+            (method.operation.as_str(), method.path.as_str(), method.method.as_str(), method.name.as_str()),
+            ("updatePet", "/pets/{petId}", "PUT", "UpdatePet")
+        );
+        assert_eq!(method.parameters_type_name, "UpdatePetParameters");
+        assert_eq!(method.responses_type_name, "UpdatePetResponses");
+    }
+}
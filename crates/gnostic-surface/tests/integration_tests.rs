@@ -0,0 +1,53 @@
+//! Integration tests building surface models of the test documents.
+
+use gnostic_surface::surface_v1::{FieldKind, Position};
+use gnostic_surface::Model;
+use std::fs;
+
+const TESTDATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../testdata");
+
+fn load_file(filename: &str) -> Vec<u8> {
+    let path = format!("{}/{}", TESTDATA_DIR, filename);
+    fs::read(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e))
+}
+
+#[test]
+fn test_surface_openapiv3_petstore() {
+    let doc = gnostic_openapiv3::parse_document(&load_file("petstore-v3.yaml")).unwrap();
+    let model = Model::from_openapiv3(&doc, "petstore");
+
+    let method = model.methods.iter().find(|m| m.operation == "getPetById").unwrap();
+    assert_eq!((method.method.as_str(), method.name.as_str()), ("GET", "GetPetById"));
+    let parameters = model.types.iter().find(|ty| ty.name == method.parameters_type_name).unwrap();
+    assert_eq!(parameters.fields[0].name, "petId");
+    assert_eq!(parameters.fields[0].position(), Position::Path);
+
+    let pet = model.types.iter().find(|ty| ty.name == "Pet").unwrap();
+    let tags = pet.fields.iter().find(|field| field.name == "tags").unwrap();
+    assert_eq!((tags.kind(), tags.r#type.as_str()), (FieldKind::Array, "Tag"));
+}
+
+#[test]
+fn test_surface_openapiv2_petstore() {
+    let doc = gnostic_openapiv2::parse_document(&load_file("petstore-v2.json")).unwrap();
+    let model = Model::from_openapiv2(&doc, "petstore");
+
+    let method = model.methods.iter().find(|m| m.operation == "addPet").unwrap();
+    assert_eq!(method.method, "POST");
+    let parameters = model.types.iter().find(|ty| ty.name == method.parameters_type_name).unwrap();
+    let body = &parameters.fields[0];
+    assert_eq!((body.kind(), body.r#type.as_str(), body.position()), (FieldKind::Reference, "Pet", Position::Body));
+    assert!(model.types.iter().any(|ty| ty.name == "Order"));
+}
+
+#[test]
+fn test_surface_discovery_books() {
+    let doc = gnostic_discovery::parse_document(&load_file("books-discovery.json")).unwrap();
+    let model = Model::from_discovery(&doc, "books");
+
+    let method = model.methods.iter().find(|m| m.operation == "books.bookshelves.list").unwrap();
+    assert_eq!(method.method, "GET");
+    assert_eq!(method.name, "BooksBookshelvesList");
+    assert!(model.types.iter().any(|ty| ty.name == "Volume"));
+    assert!(model.symbolic_references.is_empty());
+}